@@ -6,6 +6,7 @@ use std::fmt::{self, Display, Formatter};
 use std::fs::File;
 use std::io::{self, ErrorKind, Read, Write};
 use std::num::NonZeroUsize;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
 use std::sync::mpsc::{self, Receiver, Sender, TryRecvError};
 use std::thread::JoinHandle;
@@ -39,6 +40,11 @@ pub enum Msg {
     Resize(WindowSize),
 }
 
+/// Parses a chunk of PTY output into the terminal in place of
+/// [`ansi::Processor::advance`], so the embedder can act on the terminal
+/// between bytes (see [`EventLoop::set_output_parser`]).
+pub type OutputParser<U> = Box<dyn FnMut(&mut ansi::Processor, &mut Term<U>, &[u8]) + Send>;
+
 /// The main event loop.
 ///
 /// Handles all the PTY I/O and runs the PTY parser which updates terminal
@@ -48,8 +54,10 @@ pub struct EventLoop<T: tty::EventedPty, U: EventListener> {
     pty: T,
     rx: PeekableReceiver<Msg>,
     tx: Sender<Msg>,
+    pending_input: Arc<AtomicUsize>,
     terminal: Arc<FairMutex<Term<U>>>,
     event_proxy: U,
+    output_parser: Option<OutputParser<U>>,
     drain_on_exit: bool,
     ref_test: bool,
 }
@@ -73,16 +81,29 @@ where
             poll,
             pty,
             tx,
+            pending_input: Arc::new(AtomicUsize::new(0)),
             rx: PeekableReceiver::new(rx),
             terminal,
             event_proxy,
+            output_parser: None,
             drain_on_exit,
             ref_test,
         })
     }
 
+    /// Parse PTY output with `parser` instead of handing it straight to the
+    /// processor. It runs under the terminal lock and must pass every byte
+    /// to the processor, in order.
+    pub fn set_output_parser(&mut self, parser: OutputParser<U>) {
+        self.output_parser = Some(parser);
+    }
+
     pub fn channel(&self) -> EventLoopSender {
-        EventLoopSender { sender: self.tx.clone(), poller: self.poll.clone() }
+        EventLoopSender {
+            sender: self.tx.clone(),
+            poller: self.poll.clone(),
+            pending_input: self.pending_input.clone(),
+        }
     }
 
     /// Drain the channel.
@@ -151,7 +172,10 @@ where
             }
 
             // Parse the incoming bytes.
-            state.parser.advance(&mut **terminal, &buf[..unprocessed]);
+            match &mut self.output_parser {
+                Some(parse) => parse(&mut state.parser, &mut **terminal, &buf[..unprocessed]),
+                None => state.parser.advance(&mut **terminal, &buf[..unprocessed]),
+            }
 
             processed += unprocessed;
             unprocessed = 0;
@@ -184,6 +208,7 @@ where
                     Ok(n) => {
                         current.advance(n);
                         if current.finished() {
+                            self.pending_input.fetch_sub(current.source.len(), Ordering::Relaxed);
                             state.goto_next();
                             break 'write_one;
                         }
@@ -383,13 +408,28 @@ impl std::error::Error for EventLoopSendError {
 pub struct EventLoopSender {
     sender: Sender<Msg>,
     poller: Arc<polling::Poller>,
+    pending_input: Arc<AtomicUsize>,
 }
 
 impl EventLoopSender {
     pub fn send(&self, msg: Msg) -> Result<(), EventLoopSendError> {
-        self.sender.send(msg).map_err(EventLoopSendError::Send)?;
+        let input_len = match &msg {
+            Msg::Input(input) => input.len(),
+            _ => 0,
+        };
+        self.pending_input.fetch_add(input_len, Ordering::Relaxed);
+        if let Err(err) = self.sender.send(msg) {
+            self.pending_input.fetch_sub(input_len, Ordering::Relaxed);
+            return Err(EventLoopSendError::Send(err));
+        }
         self.poller.notify().map_err(EventLoopSendError::Io)
     }
+
+    /// Bytes sent as `Msg::Input` that the event loop has not yet written to
+    /// the PTY. Grows while the child isn't reading its input.
+    pub fn pending_input(&self) -> usize {
+        self.pending_input.load(Ordering::Relaxed)
+    }
 }
 
 /// All of the mutable state needed to run the event loop.
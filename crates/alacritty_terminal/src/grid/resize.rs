@@ -182,6 +182,7 @@ impl<T: GridCell + Default + PartialEq> Grid<T> {
                 let line_delta = self.cursor.point.line - target.line;
 
                 if line_delta != 0 && row.is_clear() {
+                    last_row.take_marks_from(&mut row);
                     continue;
                 }
 
@@ -197,7 +198,8 @@ impl<T: GridCell + Default + PartialEq> Grid<T> {
                     self.cursor.point.line += 1;
                 }
 
-                // Don't push line into the new buffer.
+                // Don't push line into the new buffer, but keep its marks on the line it joined.
+                last_row.take_marks_from(&mut row);
                 continue;
             }
 
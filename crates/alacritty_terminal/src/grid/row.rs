@@ -22,6 +22,13 @@ pub struct Row<T> {
     /// This is the upper bound on the number of elements in the row, which have been modified
     /// since the last reset. All cells after this point are guaranteed to be equal.
     pub(crate) occ: usize,
+
+    /// Opaque tags attached to the line by the embedder.
+    ///
+    /// They move with the row when it scrolls or is reflown, and are dropped when the row is
+    /// reset or recycled.
+    #[cfg_attr(feature = "serde", serde(default))]
+    marks: Vec<u64>,
 }
 
 impl<T: PartialEq> PartialEq for Row<T> {
@@ -52,7 +59,7 @@ impl<T: Default> Row<T> {
             inner.set_len(columns);
         }
 
-        Row { inner, occ: 0 }
+        Row { inner, occ: 0, marks: Vec::new() }
     }
 
     /// Increase the number of columns in the row.
@@ -107,6 +114,7 @@ impl<T: Default> Row<T> {
         }
 
         self.occ = 0;
+        self.marks.clear();
     }
 }
 
@@ -114,7 +122,7 @@ impl<T: Default> Row<T> {
 impl<T> Row<T> {
     #[inline]
     pub fn from_vec(vec: Vec<T>, occ: usize) -> Row<T> {
-        Row { inner: vec, occ }
+        Row { inner: vec, occ, marks: Vec::new() }
     }
 
     #[inline]
@@ -159,6 +167,24 @@ impl<T> Row<T> {
         self.inner.iter().all(GridCell::is_empty)
     }
 
+    /// Tags attached to this line, oldest first.
+    #[inline]
+    pub fn marks(&self) -> &[u64] {
+        &self.marks
+    }
+
+    /// Attach a tag to this line.
+    #[inline]
+    pub fn push_mark(&mut self, mark: u64) {
+        self.marks.push(mark);
+    }
+
+    /// Move all of `other`'s tags onto this line.
+    #[inline]
+    pub(crate) fn take_marks_from(&mut self, other: &mut Row<T>) {
+        self.marks.append(&mut other.marks);
+    }
+
     #[inline]
     pub fn front_split_off(&mut self, at: usize) -> Vec<T> {
         self.occ = self.occ.saturating_sub(at);
@@ -351,6 +351,9 @@ pub struct Config {
 
     /// OSC52 support mode.
     pub osc52: Osc52,
+
+    /// Rewrap lines when the number of columns changes, instead of truncating them.
+    pub reflow: bool,
 }
 
 impl Default for Config {
@@ -362,6 +365,7 @@ impl Default for Config {
             vi_mode_cursor_style: Default::default(),
             kitty_keyboard: Default::default(),
             osc52: Default::default(),
+            reflow: true,
         }
     }
 }
@@ -674,8 +678,9 @@ impl<T> Term<T> {
         self.vi_mode_cursor.point.line += delta;
 
         let is_alt = self.mode.contains(TermMode::ALT_SCREEN);
-        self.grid.resize(!is_alt, num_lines, num_cols);
-        self.inactive_grid.resize(is_alt, num_lines, num_cols);
+        let reflow = self.config.reflow;
+        self.grid.resize(reflow && !is_alt, num_lines, num_cols);
+        self.inactive_grid.resize(reflow && is_alt, num_lines, num_cols);
 
         // Invalidate selection and tabs only when necessary.
         if old_cols != num_cols {
@@ -930,6 +935,18 @@ impl<T> Term<T> {
         &self.config.semantic_escape_chars
     }
 
+    /// Whether column changes rewrap lines (see [`Config::reflow`]).
+    #[inline]
+    pub fn reflow(&self) -> bool {
+        self.config.reflow
+    }
+
+    /// Toggle rewrapping on resize without replacing the whole [`Config`].
+    #[inline]
+    pub fn set_reflow(&mut self, reflow: bool) {
+        self.config.reflow = reflow;
+    }
+
     #[cfg(test)]
     pub(crate) fn set_semantic_escape_chars(&mut self, semantic_escape_chars: &str) {
         self.config.semantic_escape_chars = semantic_escape_chars.into();
@@ -132,6 +132,7 @@ impl App {
         self.focused = Some(new_id);
         self.router.set_focused(new_id);
         self.cache.invalidate_chrome();
+        self.schedule_session_save();
         self.compute_layout();
     }
 }
@@ -3,9 +3,9 @@ mod focus_nav;
 mod text_extract;
 mod file_ops;
 
-pub(crate) use pane_lifecycle::LauncherChoice;
-
-use std::time::Instant;
+pub(crate) use pane_lifecycle::{last_pane_exit_outcome, LauncherChoice, PaneExit};
+#[cfg(test)]
+pub(crate) use pane_lifecycle::PaneExitOutcome;
 
 use tide_core::{InputEvent, LayoutEngine, Size, SplitDirection, TerminalBackend, Vec2};
 use tide_editor::input::EditorAction;
@@ -86,6 +86,7 @@ impl App {
                     self.ft.visible = false;
                     self.focus_area = FocusArea::PaneArea;
                     self.cache.invalidate_chrome();
+                    self.schedule_session_save();
                     self.compute_layout();
                 } else if self.ft.visible {
                     // Visible but not focused → focus
@@ -95,6 +96,7 @@ impl App {
                     self.ft.visible = true;
                     self.focus_area = FocusArea::FileTree;
                     self.update_file_tree_cwd();
+                    self.schedule_session_save();
                     self.compute_layout();
                 }
             }
@@ -152,20 +154,6 @@ impl App {
                 if let Some(InputEvent::MouseClick { position, .. }) = event {
                     self.focus_terminal(id);
 
-                    // Ctrl+Click / Cmd+Click on terminal -> try to open URL or file at click position
-                    let mods = self.modifiers;
-                    if mods.ctrl || mods.meta {
-                        // Try URL first — open in embedded browser panel
-                        if let Some(url) = self.extract_url_at(id, position) {
-                            self.open_browser_pane(Some(url));
-                            return;
-                        }
-                        if let Some((path, line)) = self.extract_file_path_at(id, position) {
-                            self.open_editor_pane_at_line(path, line);
-                            return;
-                        }
-                    }
-
                     // Click on editor pane -> move cursor (skip in preview mode)
                     if let Some(PaneKind::Editor(pane)) = self.panes.get_mut(&id) {
                         if pane.preview_mode { return; }
@@ -196,6 +184,7 @@ impl App {
 
                 // Forward keyboard input to the pane
                 let cs_for_keys = self.cell_size();
+                let key_chars = self.key_chars.take();
                 if let Some(InputEvent::KeyPress { key, modifiers }) = event {
                     match self.panes.get_mut(&id) {
                         Some(PaneKind::Terminal(pane)) => {
@@ -204,9 +193,8 @@ impl App {
                                 self.respawn_terminal(id);
                             } else {
                                 pane.selection = None; // Clear selection on key input
-                                pane.handle_key(&key, &modifiers);
-                                self.input_just_sent = true;
-                                self.input_sent_at = Some(Instant::now());
+                                pane.handle_key(&key, &modifiers, key_chars.as_deref());
+                                self.redraw.on_input();
                             }
                         }
                         Some(PaneKind::Editor(pane)) => {
@@ -239,18 +227,24 @@ impl App {
                                     pane.select_all();
                                     return;
                                 }
-                                // Delete selection on editing actions (insert, backspace, delete, enter)
-                                match &action {
-                                    tide_editor::EditorActionKind::InsertChar(_)
-                                    | tide_editor::EditorActionKind::Backspace
-                                    | tide_editor::EditorActionKind::Delete
-                                    | tide_editor::EditorActionKind::Enter => {
-                                        pane.delete_selection();
+                                let was_modified = pane.editor.is_modified();
+                                // Typing across a block selection edits every row and keeps the block caret;
+                                // an opening bracket/quote wraps a selection and keeps it
+                                let block_edited = pane.edit_block(&action) || pane.surround_selection(&action);
+                                if !block_edited {
+                                    // Delete selection on editing actions (insert, backspace, delete, enter)
+                                    match &action {
+                                        tide_editor::EditorActionKind::InsertChar(_)
+                                        | tide_editor::EditorActionKind::Backspace
+                                        | tide_editor::EditorActionKind::Delete
+                                        | tide_editor::EditorActionKind::Enter => {
+                                            pane.delete_selection();
+                                        }
+                                        _ => {}
                                     }
-                                    _ => {}
+                                    // Clear selection on movement and editing keys
+                                    pane.selection = None;
                                 }
-                                // Clear selection on movement and editing keys
-                                pane.selection = None;
                                 let is_save = matches!(action, tide_editor::EditorActionKind::Save);
                                 // Intercept Save on untitled files -> open save-as input
                                 if is_save && pane.editor.file_path().is_none() {
@@ -262,7 +256,6 @@ impl App {
                                     self.modal.save_as_input = Some(crate::SaveAsInput::new(id, base_dir, anchor));
                                     return;
                                 }
-                                let was_modified = pane.editor.is_modified();
                                 let cell_size = Some(cs_for_keys);
                                 let content_top = TAB_BAR_HEIGHT;
                                 let (visible_rows, visible_cols) = if let Some(cs) = cell_size {
@@ -280,7 +273,12 @@ impl App {
                                 } else {
                                     (30, 80)
                                 };
-                                pane.handle_action_with_size(action, visible_rows, visible_cols);
+                                if block_edited {
+                                    pane.editor.ensure_cursor_visible(visible_rows);
+                                    pane.editor.ensure_cursor_visible_h(visible_cols);
+                                } else {
+                                    pane.handle_action_with_size(action, visible_rows, visible_cols);
+                                }
                                 // Clear disk_changed on save (user's version wins)
                                 if is_save {
                                     pane.disk_changed = false;
@@ -409,6 +407,28 @@ impl App {
             Action::GlobalAction(global) => {
                 self.handle_global_action(global);
             }
+            Action::OpenLinkAt(id, position) => {
+                // Cmd+Click / Ctrl+Click -> try to open URL or file at click position
+                self.focus_terminal(id);
+                // Try URL first — open in embedded browser panel
+                if let Some(url) = self.extract_url_at(id, position) {
+                    self.open_browser_pane(Some(url));
+                    return;
+                }
+                if let Some((path, line)) = self.extract_file_path_at(id, position) {
+                    self.open_editor_pane_at_line(path, line);
+                    return;
+                }
+                // Nothing to open: behave like a plain click
+                self.handle_action(Action::RouteToPane(id), event);
+                // In an editor, the click placed the cursor; go to the file
+                // named there (the pane queues `PaneEvent::OpenFile`)
+                if let Some(PaneKind::Editor(pane)) = self.panes.get_mut(&id) {
+                    if !pane.preview_mode {
+                        pane.open_path_under_cursor();
+                    }
+                }
+            }
             Action::DragBorder(pos) => {
                 let logical = self.logical_size();
                 let mut left = 0.0_f32;
@@ -457,6 +477,7 @@ impl App {
         self.focused = Some(new_id);
         self.router.set_focused(new_id);
         self.cache.invalidate_chrome();
+        self.schedule_session_save();
         self.compute_layout();
         Some(new_id)
     }
@@ -469,6 +490,9 @@ impl App {
             GlobalAction::SplitHorizontal | GlobalAction::SplitHorizontalHere => {
                 self.split_with_launcher(SplitDirection::Horizontal);
             }
+            GlobalAction::DuplicatePane => {
+                self.duplicate_pane();
+            }
             GlobalAction::ClosePane => {
                 if let Some(focused) = self.focused {
                     self.close_specific_pane(focused);
@@ -534,61 +558,34 @@ impl App {
                 if let Some(target_id) = self.action_target_id() {
                     match self.panes.get_mut(&target_id) {
                         Some(PaneKind::Terminal(pane)) => {
-                            if let Ok(mut clipboard) = arboard::Clipboard::new() {
-                                if let Ok(text) = clipboard.get_text() {
-                                    if !text.is_empty() {
-                                        // Scroll to bottom so pasted text is visible
-                                        if pane.backend.display_offset() > 0 {
-                                            pane.backend.request_scroll_to_bottom();
-                                        }
-                                        let bracketed = pane.backend.is_bracketed_paste_mode();
-                                        let mut data = Vec::new();
-                                        if bracketed {
-                                            data.extend_from_slice(b"\x1b[200~");
-                                            // Sanitize: strip the bracket-close sequence from
-                                            // clipboard text to prevent pastejacking attacks
-                                            // that escape bracketed paste mode.
-                                            let safe = text.replace("\x1b[201~", "");
-                                            data.extend_from_slice(safe.as_bytes());
-                                        } else {
-                                            data.extend_from_slice(text.as_bytes());
-                                        }
-                                        if bracketed {
-                                            data.extend_from_slice(b"\x1b[201~");
-                                            // Nudge shell to redraw and clear paste standout
-                                            // (left + right arrow = net-zero cursor move that
-                                            // triggers zsh/bash/fish to re-render without
-                                            // the INVERSE highlight on pasted text).
-                                            data.extend_from_slice(b"\x1b[D\x1b[C");
-                                        }
-                                        pane.backend.write(&data);
-                                        self.input_just_sent = true;
-                                        self.input_sent_at = Some(Instant::now());
+                            if let Some(text) = crate::clipboard::system().read() {
+                                if !text.is_empty() {
+                                    // Scroll to bottom so pasted text is visible
+                                    if pane.backend.display_offset() > 0 {
+                                        pane.backend.request_scroll_to_bottom();
                                     }
+                                    pane.backend.paste(&text);
+                                    self.redraw.on_input();
                                 }
                             }
                         }
                         Some(PaneKind::Editor(pane)) => {
-                            if let Ok(mut clipboard) = arboard::Clipboard::new() {
-                                if let Ok(text) = clipboard.get_text() {
-                                    if !text.is_empty() {
-                                        pane.delete_selection();
-                                        pane.editor.insert_text(&text);
-                                    }
-                                }
+                            // Read once: the selection is only replaced when there is text
+                            let text = pane.editor.clipboard().and_then(|c| c.read());
+                            if let Some(text) = text.filter(|text| !text.is_empty()) {
+                                pane.delete_selection();
+                                pane.editor.insert_text(&text);
                             }
                         }
                         Some(PaneKind::Browser(bp)) if bp.url_input_focused => {
-                            if let Ok(mut clipboard) = arboard::Clipboard::new() {
-                                if let Ok(text) = clipboard.get_text() {
-                                    if !text.is_empty() {
-                                        for ch in text.chars() {
-                                            let byte_off = bp.cursor_byte_offset();
-                                            bp.url_input.insert(byte_off, ch);
-                                            bp.url_input_cursor += 1;
-                                        }
-                                        self.cache.invalidate_chrome();
+                            if let Some(text) = crate::clipboard::system().read() {
+                                if !text.is_empty() {
+                                    for ch in text.chars() {
+                                        let byte_off = bp.cursor_byte_offset();
+                                        bp.url_input.insert(byte_off, ch);
+                                        bp.url_input_cursor += 1;
                                     }
+                                    self.cache.invalidate_chrome();
                                 }
                             }
                         }
@@ -603,18 +600,17 @@ impl App {
                             if let Some(ref sel) = pane.selection {
                                 let text = pane.selected_text(sel);
                                 if !text.is_empty() {
-                                    if let Ok(mut clipboard) = arboard::Clipboard::new() {
-                                        let _ = clipboard.set_text(&text);
-                                    }
+                                    crate::clipboard::system().write(&text);
                                 }
                             }
                         }
                         Some(PaneKind::Editor(pane)) => {
                             if let Some(ref sel) = pane.selection {
+                                // Block and preview selections aren't plain buffer ranges
                                 let text = pane.selected_text(sel);
                                 if !text.is_empty() {
-                                    if let Ok(mut clipboard) = arboard::Clipboard::new() {
-                                        let _ = clipboard.set_text(&text);
+                                    if let Some(clipboard) = pane.editor.clipboard() {
+                                        clipboard.write(&text);
                                     }
                                 }
                             }
@@ -685,27 +681,8 @@ impl App {
             }
             GlobalAction::ToggleTheme => {
                 self.dark_mode = !self.dark_mode;
-                let border_color = self.palette().border_color;
-                if let Some(renderer) = &mut self.renderer {
-                    renderer.clear_color = border_color;
-                }
-                let dark = self.dark_mode;
-                for pane in self.panes.values_mut() {
-                    match pane {
-                        crate::pane::PaneKind::Terminal(tp) => {
-                            tp.backend.set_dark_mode(dark);
-                        }
-                        crate::pane::PaneKind::Editor(ep) => {
-                            ep.editor.set_dark_mode(dark);
-                        }
-                        crate::pane::PaneKind::Diff(_) => {}
-                        crate::pane::PaneKind::Browser(_) => {}
-                        crate::pane::PaneKind::Launcher(_) => {}
-                    }
-                }
-                self.cache.invalidate_chrome();
-                self.cache.layout_generation = self.cache.layout_generation.wrapping_add(1);
-                self.cache.pane_generations.clear();
+                self.apply_theme();
+                self.schedule_session_save();
             }
             GlobalAction::ScrollHalfPageUp => {
                 self.scroll_half_page(tide_input::Direction::Up);
@@ -713,7 +690,67 @@ impl App {
             GlobalAction::ScrollHalfPageDown => {
                 self.scroll_half_page(tide_input::Direction::Down);
             }
+            GlobalAction::ClearScrollback => {
+                self.with_focused_terminal(|pane| pane.clear_scrollback());
+            }
+            GlobalAction::ResetTerminal => {
+                self.with_focused_terminal(|pane| pane.reset());
+            }
+            GlobalAction::PrevPrompt => {
+                self.with_focused_terminal(|pane| {
+                    pane.jump_to_prompt(false);
+                });
+            }
+            GlobalAction::NextPrompt => {
+                self.with_focused_terminal(|pane| {
+                    pane.jump_to_prompt(true);
+                });
+            }
+            GlobalAction::SelectCommandOutput => {
+                self.with_focused_terminal(|pane| {
+                    pane.select_command_output();
+                });
+            }
+        }
+    }
+
+    /// Apply `f` to the focused pane if it's a terminal, then repaint it.
+    fn with_focused_terminal(&mut self, f: impl FnOnce(&mut crate::pane::TerminalPane)) {
+        let Some(focused) = self.focused else { return };
+        if let Some(PaneKind::Terminal(pane)) = self.panes.get_mut(&focused) {
+            f(pane);
+            self.cache.invalidate_pane(focused);
+        }
+    }
+
+    /// Push the current theme to the renderer and every pane, then redraw.
+    pub(crate) fn apply_theme(&mut self) {
+        let theme = self.theme();
+        let text_gamma = self.text_gamma();
+        if let Some(renderer) = &mut self.renderer {
+            renderer.set_theme(&theme);
+            renderer.set_text_gamma(text_gamma);
+        }
+        for pane in self.panes.values_mut() {
+            match pane {
+                crate::pane::PaneKind::Terminal(tp) => {
+                    tp.backend.set_theme(&theme);
+                    tp.backend.set_cursor_style(tide_terminal::CursorStyleConfig {
+                        fallback_color: theme.cursor,
+                        ..tp.backend.cursor_style()
+                    });
+                }
+                crate::pane::PaneKind::Editor(ep) => {
+                    ep.editor.set_theme(&theme);
+                }
+                crate::pane::PaneKind::Diff(_) => {}
+                crate::pane::PaneKind::Browser(_) => {}
+                crate::pane::PaneKind::Launcher(_) => {}
+            }
         }
+        self.cache.invalidate_chrome();
+        self.cache.layout_generation = self.cache.layout_generation.wrapping_add(1);
+        self.cache.pane_generations.clear();
     }
 
     pub(crate) fn toggle_config_page(&mut self) {
@@ -758,6 +795,8 @@ impl App {
             .unwrap_or_default();
 
         self.modal.config_page = Some(crate::ConfigPageState::new(bindings, worktree_pattern, copy_files));
+        // Give the user an editable theme.toml next to settings.json
+        crate::settings::save_theme_template(&self.theme());
         self.cache.invalidate_chrome();
     }
 
@@ -824,6 +863,25 @@ impl App {
                 self.router.keybinding_map = Some(map);
             }
         }
+        self.router.set_clipboard_key_policy(crate::settings::clipboard_key_policy(&self.settings));
+        self.router.set_modifier_normalization(crate::settings::modifier_normalization(&self.settings));
+        let auto_close = self.settings.editor_auto_close;
+        let write_filter = crate::settings::write_filter(&self.settings);
+        let background = self.ws.workspaces.iter_mut().flat_map(|ws| ws.panes.values_mut());
+        for pane in self.panes.values_mut().chain(background) {
+            match pane {
+                crate::pane::PaneKind::Editor(ep) => ep.editor.set_auto_close(auto_close),
+                crate::pane::PaneKind::Terminal(tp) => tp.backend.set_write_filter(write_filter),
+                _ => {}
+            }
+        }
+
+        // theme.toml is edited outside the config page; pick up changes on close
+        let user_theme = crate::settings::load_theme();
+        if user_theme != self.user_theme {
+            self.set_user_theme(user_theme);
+            self.apply_theme();
+        }
 
         self.cache.invalidate_chrome();
     }
@@ -869,6 +927,7 @@ impl App {
             self.zoomed_pane = Some(new_tab);
         }
         self.cache.invalidate_chrome();
+        self.schedule_session_save();
         self.compute_layout();
     }
 }
@@ -1,11 +1,12 @@
 use std::path::PathBuf;
 
-use tide_core::LayoutEngine;
+use tide_core::{LayoutEngine, TerminalBackend};
 
 use crate::browser_pane::BrowserPane;
 use crate::drag_drop::PaneDragState;
 use crate::editor_pane::EditorPane;
 use crate::pane::{PaneKind, TerminalPane};
+use crate::settings::OnLastPaneExit;
 use crate::App;
 
 impl App {
@@ -19,8 +20,9 @@ impl App {
         let cols = ((logical.width / 2.0 / cell_size.width).max(1.0).min(1000.0)) as u16;
         let rows = ((logical.height / cell_size.height).max(1.0).min(500.0)) as u16;
 
-        match TerminalPane::with_cwd(id, cols, rows, cwd, self.dark_mode) {
-            Ok(pane) => {
+        match TerminalPane::with_options(id, cols, rows, self.terminal_options(cwd), &self.theme()) {
+            Ok(mut pane) => {
+                self.apply_terminal_settings(&mut pane);
                 self.install_pty_waker(&pane);
                 self.panes.insert(id, PaneKind::Terminal(pane));
                 self.ime.pending_creates.push(id);
@@ -49,6 +51,7 @@ impl App {
         }
         self.cache.invalidate_pane(id);
         self.cache.invalidate_chrome();
+        self.schedule_session_save();
         self.compute_layout();
     }
 
@@ -58,13 +61,13 @@ impl App {
         // If focused pane is a terminal, use its CWD
         if let Some(focused) = self.focused {
             if let Some(PaneKind::Terminal(p)) = self.panes.get(&focused) {
-                return p.backend.detect_cwd_fallback();
+                return p.backend.cwd();
             }
         }
         // Otherwise, find any terminal pane and use its CWD
         for &id in &self.layout.pane_ids() {
             if let Some(PaneKind::Terminal(p)) = self.panes.get(&id) {
-                if let Some(cwd) = p.backend.detect_cwd_fallback() {
+                if let Some(cwd) = p.backend.cwd() {
                     return Some(cwd);
                 }
             }
@@ -81,7 +84,7 @@ impl App {
         };
         let new_id = self.layout.alloc_id();
         let mut pane = EditorPane::new_empty(new_id);
-        pane.editor.set_dark_mode(self.dark_mode);
+        self.apply_editor_settings(&mut pane);
         self.panes.insert(new_id, PaneKind::Editor(pane));
         self.ime.pending_creates.push(new_id);
         self.layout.add_tab(focused, new_id);
@@ -93,6 +96,7 @@ impl App {
         }
         self.focus_area = crate::ui_state::FocusArea::PaneArea;
         self.cache.invalidate_chrome();
+        self.schedule_session_save();
         self.compute_layout();
     }
 
@@ -115,6 +119,7 @@ impl App {
         }
         self.focus_area = crate::ui_state::FocusArea::PaneArea;
         self.cache.invalidate_chrome();
+        self.schedule_session_save();
         self.compute_layout();
     }
 
@@ -135,7 +140,7 @@ impl App {
             LauncherChoice::NewFile => {
                 self.ime.pending_removes.push(launcher_id);
                 let mut pane = crate::editor_pane::EditorPane::new_empty(launcher_id);
-                pane.editor.set_dark_mode(self.dark_mode);
+                self.apply_editor_settings(&mut pane);
                 self.panes.insert(launcher_id, PaneKind::Editor(pane));
                 self.ime.pending_creates.push(launcher_id);
             }
@@ -156,6 +161,7 @@ impl App {
         self.router.set_focused(launcher_id);
         self.cache.invalidate_chrome();
         self.cache.pane_generations.clear();
+        self.schedule_session_save();
         self.compute_layout();
     }
 
@@ -177,9 +183,68 @@ impl App {
         self.focused = Some(new_id);
         self.router.set_focused(new_id);
         self.cache.invalidate_chrome();
+        self.schedule_session_save();
         self.compute_layout();
     }
 
+    /// Split the focused pane into a copy of itself: a terminal opens a new
+    /// shell in the source's current directory (OSC 7 or the process CWD),
+    /// an editor opens the same file, carrying over unsaved edits and the
+    /// cursor. Other panes get a Launcher split.
+    pub(crate) fn duplicate_pane(&mut self) {
+        let Some(focused) = self.focused else { return };
+        let direction = tide_core::SplitDirection::Horizontal;
+        let (path, unsaved) = match self.panes.get(&focused) {
+            Some(PaneKind::Terminal(pane)) => {
+                let cwd = pane.backend.cwd().or_else(|| pane.cwd.clone());
+                self.split_pane_from(focused, direction, cwd);
+                return;
+            }
+            Some(PaneKind::Editor(pane)) => {
+                let unsaved = pane
+                    .editor
+                    .is_modified()
+                    .then(|| (pane.editor.buffer.lines.join("\n"), pane.editor.cursor_position()));
+                (pane.editor.file_path().map(PathBuf::from), unsaved)
+            }
+            _ => (None, None),
+        };
+        let Some(path) = path else {
+            self.split_with_launcher(direction);
+            return;
+        };
+
+        if self.zoomed_pane.is_some() {
+            self.zoomed_pane = None;
+            self.cache.pane_generations.clear();
+        }
+        let new_id = self.layout.split(focused, direction);
+        match EditorPane::open(new_id, &path) {
+            Ok(mut pane) => {
+                self.apply_editor_settings(&mut pane);
+                if let Some((text, cursor)) = unsaved {
+                    // The copy starts out dirty with the source's contents
+                    pane.select_all();
+                    pane.delete_selection();
+                    pane.editor.insert_text(&text);
+                    pane.editor.cursor.set_position(cursor);
+                }
+                self.panes.insert(new_id, PaneKind::Editor(pane));
+                self.ime.pending_creates.push(new_id);
+                self.focused = Some(new_id);
+                self.router.set_focused(new_id);
+                self.focus_area = crate::ui_state::FocusArea::PaneArea;
+                self.cache.invalidate_chrome();
+                self.schedule_session_save();
+                self.compute_layout();
+            }
+            Err(e) => {
+                log::error!("Failed to duplicate editor for {:?}: {}", path, e);
+                self.layout.remove(new_id);
+            }
+        }
+    }
+
     /// Open a browser pane to the right of the focused pane's tab group.
     /// If a tab group already exists to the right, the browser is added there.
     /// Otherwise a new horizontal split is created.
@@ -201,6 +266,7 @@ impl App {
         self.router.set_focused(new_id);
         self.focus_area = crate::ui_state::FocusArea::PaneArea;
         self.cache.invalidate_chrome();
+        self.schedule_session_save();
         self.compute_layout();
     }
 
@@ -222,6 +288,7 @@ impl App {
                     self.panes.remove(&pane_id);
                     self.cleanup_closed_pane_state(pane_id);
                     self.cache.invalidate_chrome();
+                    self.schedule_session_save();
                     self.compute_layout();
                     return;
                 }
@@ -231,7 +298,7 @@ impl App {
         // Replace the pane in-place: swap PaneKind from Launcher to Editor
         match EditorPane::open(pane_id, &path) {
             Ok(mut pane) => {
-                pane.editor.set_dark_mode(self.dark_mode);
+                self.apply_editor_settings(&mut pane);
                 self.panes.insert(pane_id, PaneKind::Editor(pane));
                 // Clear IME composition if the replaced pane was the target.
                 if self.ime.last_target == Some(pane_id) {
@@ -244,6 +311,7 @@ impl App {
                 self.cache.invalidate_chrome();
                 self.cache.pane_generations.clear();
                 self.watch_file(&path);
+                self.schedule_session_save();
                 self.compute_layout();
             }
             Err(e) => {
@@ -272,6 +340,7 @@ impl App {
                     self.router.set_focused(id);
                     self.focus_area = crate::ui_state::FocusArea::PaneArea;
                     self.cache.invalidate_chrome();
+                    self.schedule_session_save();
                     self.compute_layout();
                     return;
                 }
@@ -282,7 +351,7 @@ impl App {
         let new_id = self.layout.alloc_id();
         match EditorPane::open(new_id, &path) {
             Ok(mut pane) => {
-                pane.editor.set_dark_mode(self.dark_mode);
+                self.apply_editor_settings(&mut pane);
                 self.panes.insert(new_id, PaneKind::Editor(pane));
                 self.ime.pending_creates.push(new_id);
                 self.add_pane_to_right(focused, new_id);
@@ -293,6 +362,7 @@ impl App {
                 self.cache.invalidate_chrome();
                 // Watch the file for external changes
                 self.watch_file(&path);
+                self.schedule_session_save();
                 self.compute_layout();
             }
             Err(e) => {
@@ -308,12 +378,7 @@ impl App {
             if let Some(active_id) = self.focused {
                 let visible_rows = self.visible_editor_size(active_id).0;
                 if let Some(PaneKind::Editor(pane)) = self.panes.get_mut(&active_id) {
-                    let target_line = line.saturating_sub(1); // 1-based to 0-based
-                    pane.handle_action(
-                        tide_editor::input::EditorAction::SetCursor { line: target_line, col: 0 },
-                        visible_rows,
-                    );
-                    pane.editor.ensure_cursor_visible(visible_rows.max(30));
+                    pane.editor.go_to_line(line, visible_rows.max(30));
                 }
             }
         }
@@ -435,6 +500,7 @@ impl App {
 
         self.cache.pane_generations.clear();
         self.cache.invalidate_chrome();
+        self.schedule_session_save();
         self.compute_layout();
     }
 
@@ -568,14 +634,15 @@ impl App {
         }
         let remaining = self.layout.pane_ids();
         if remaining.len() <= 1 {
-            // If other workspaces exist, close this one instead of exiting
-            if self.ws.workspaces.len() > 1 {
-                self.close_workspace();
-                return;
-            }
-            let session = crate::session::Session::from_app(self);
-            crate::session::save_session(&session);
-            std::process::exit(0);
+            let exited = matches!(self.panes.get(&pane_id), Some(PaneKind::Terminal(p)) if p.child_dead);
+            let outcome = last_pane_exit_outcome(
+                self.settings.on_last_pane_exit,
+                PaneExit::Closed { exited },
+                remaining.len(),
+                self.ws.workspaces.len(),
+            );
+            self.apply_pane_exit_outcome(pane_id, outcome);
+            return;
         }
 
         // Determine next focus target BEFORE removal so we can find the
@@ -612,6 +679,7 @@ impl App {
         }
 
         self.cache.invalidate_chrome();
+        self.schedule_session_save();
         self.compute_layout();
         self.update_file_tree_cwd();
     }
@@ -723,6 +791,47 @@ impl App {
         }
     }
 
+    /// Act on `last_pane_exit_outcome` for `pane_id`.
+    pub(crate) fn apply_pane_exit_outcome(&mut self, pane_id: tide_core::PaneId, outcome: PaneExitOutcome) {
+        match outcome {
+            PaneExitOutcome::KeepLayout => {}
+            PaneExitOutcome::Placeholder => self.replace_with_exited_placeholder(pane_id),
+            PaneExitOutcome::CloseWorkspace => self.close_workspace(),
+            PaneExitOutcome::Quit => {
+                let session = crate::session::Session::from_app(self);
+                crate::session::save_session(&session);
+                crate::session::delete_running_marker();
+                std::process::exit(0);
+            }
+        }
+    }
+
+    /// Swap the pane for an exited terminal placeholder in the same slot,
+    /// keeping the terminal's CWD for the relaunch. A terminal whose shell
+    /// already exited is left as it is.
+    fn replace_with_exited_placeholder(&mut self, id: tide_core::PaneId) {
+        let cwd = match self.panes.get(&id) {
+            Some(PaneKind::Terminal(pane)) if pane.child_dead => return,
+            Some(PaneKind::Terminal(pane)) => pane.cwd.clone().or_else(|| pane.backend.detect_cwd_fallback()),
+            _ => None,
+        };
+        let cell_size = self.cell_size();
+        let logical = self.logical_size();
+        let cols = ((logical.width / cell_size.width.max(1.0)).clamp(1.0, 1000.0)) as u16;
+        let rows = ((logical.height / cell_size.height.max(1.0)).clamp(1.0, 500.0)) as u16;
+        // Dropping the old terminal hangs up its shell.
+        let placeholder = TerminalPane::exited_placeholder(id, cols, rows, cwd, &self.theme());
+        self.panes.insert(id, PaneKind::Terminal(placeholder));
+        if self.ime.last_target == Some(id) {
+            self.ime.clear_composition();
+            self.ime.last_target = None;
+        }
+        self.cache.invalidate_pane(id);
+        self.cache.invalidate_chrome();
+        self.schedule_session_save();
+        self.compute_layout();
+    }
+
     /// Add a pane to the right of the focused pane's tab group.
     /// If a tab group already exists to the right, add there.
     /// Otherwise split horizontally to create a new tab group on the right.
@@ -737,6 +846,49 @@ impl App {
     }
 }
 
+/// How a pane went away, for `last_pane_exit_outcome`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum PaneExit {
+    /// Its shell exited on its own.
+    ShellExited,
+    /// The user closed it; `exited` if it was already an exited terminal.
+    Closed { exited: bool },
+}
+
+/// What the app does when a pane goes away.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum PaneExitOutcome {
+    /// Other panes remain, or the last shell exited under `CloseWindow`:
+    /// the usual close, or the dead-shell overlay.
+    KeepLayout,
+    /// Keep the window on an exited terminal that relaunches on a key press.
+    Placeholder,
+    /// Close the current workspace; others remain.
+    CloseWorkspace,
+    /// Save the session and quit.
+    Quit,
+}
+
+/// Decide what happens when a pane exits, given how many panes the
+/// workspace has (the exiting one included) and how many workspaces exist.
+/// Only the last pane consults the `OnLastPaneExit` policy.
+pub(crate) fn last_pane_exit_outcome(
+    policy: OnLastPaneExit,
+    exit: PaneExit,
+    pane_count: usize,
+    workspace_count: usize,
+) -> PaneExitOutcome {
+    if pane_count > 1 {
+        return PaneExitOutcome::KeepLayout;
+    }
+    match (policy, exit) {
+        (OnLastPaneExit::KeepOpen, exit) if exit != (PaneExit::Closed { exited: true }) => PaneExitOutcome::Placeholder,
+        (OnLastPaneExit::CloseWindow, PaneExit::ShellExited) => PaneExitOutcome::KeepLayout,
+        _ if workspace_count > 1 => PaneExitOutcome::CloseWorkspace,
+        _ => PaneExitOutcome::Quit,
+    }
+}
+
 /// Launcher type selection choices.
 pub(crate) enum LauncherChoice {
     Terminal,
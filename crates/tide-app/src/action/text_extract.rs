@@ -7,14 +7,9 @@ use crate::theme::*;
 use crate::App;
 
 impl App {
-    /// Try to extract a URL from the terminal grid at the given click position.
-    /// Checks if the click is within a detected URL range and extracts the URL string.
-    pub(crate) fn extract_url_at(&self, pane_id: tide_core::PaneId, position: Vec2) -> Option<String> {
-        let pane = match self.panes.get(&pane_id) {
-            Some(PaneKind::Terminal(p)) => p,
-            _ => return None,
-        };
-
+    /// Screen cell `(row, col)` of terminal `pane_id` under `position`, or
+    /// `None` when the position is outside its grid.
+    pub(crate) fn terminal_cell_at(&self, pane_id: tide_core::PaneId, position: Vec2) -> Option<(usize, usize)> {
         let (_, visual_rect) = self
             .visual_pane_rects
             .iter()
@@ -30,8 +25,25 @@ impl App {
         let actual_width = max_cols as f32 * cell_size.width;
         let extra_x = ((visual_rect.width - 2.0 * PANE_PADDING) - actual_width) / 2.0;
 
-        let col = ((position.x - inner_x - extra_x) / cell_size.width) as usize;
-        let row = ((position.y - inner_y) / cell_size.height) as usize;
+        let x = position.x - inner_x - extra_x;
+        let y = position.y - inner_y;
+        if x < 0.0 || y < 0.0 {
+            return None;
+        }
+        let col = (x / cell_size.width) as usize;
+        let row = (y / cell_size.height) as usize;
+        (col < max_cols).then_some((row, col))
+    }
+
+    /// Try to extract a URL from the terminal grid at the given click position.
+    /// Checks if the click is within a detected URL range and extracts the URL string.
+    pub(crate) fn extract_url_at(&self, pane_id: tide_core::PaneId, position: Vec2) -> Option<String> {
+        let pane = match self.panes.get(&pane_id) {
+            Some(PaneKind::Terminal(p)) => p,
+            _ => return None,
+        };
+
+        let (row, col) = self.terminal_cell_at(pane_id, position)?;
 
         let url_ranges = pane.backend.url_ranges();
         if row >= url_ranges.len() {
@@ -70,23 +82,7 @@ impl App {
             _ => return None,
         };
 
-        let (_, visual_rect) = self
-            .visual_pane_rects
-            .iter()
-            .find(|(id, _)| *id == pane_id)?;
-        let cell_size = self.cell_size();
-
-        let content_top = TAB_BAR_HEIGHT;
-        let inner_x = visual_rect.x + PANE_PADDING;
-        let inner_y = visual_rect.y + content_top;
-
-        // Center offset matching render_grid
-        let max_cols = ((visual_rect.width - 2.0 * PANE_PADDING) / cell_size.width).floor() as usize;
-        let actual_width = max_cols as f32 * cell_size.width;
-        let extra_x = ((visual_rect.width - 2.0 * PANE_PADDING) - actual_width) / 2.0;
-
-        let col = ((position.x - inner_x - extra_x) / cell_size.width) as usize;
-        let row = ((position.y - inner_y) / cell_size.height) as usize;
+        let (row, col) = self.terminal_cell_at(pane_id, position)?;
 
         let grid = pane.backend.grid();
         if row >= grid.cells.len() {
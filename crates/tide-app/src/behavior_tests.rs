@@ -9,7 +9,7 @@ mod focus_management {
     use crate::pane::PaneKind;
     use crate::ui_state::FocusArea;
     use crate::App;
-    use tide_core::LayoutEngine;
+    use tide_core::{LayoutEngine, PaneId};
 
     fn test_app() -> App {
         let mut app = App::new();
@@ -18,7 +18,7 @@ mod focus_management {
         app
     }
 
-    fn app_with_editor() -> (App, u64) {
+    fn app_with_editor() -> (App, PaneId) {
         let mut app = test_app();
         let (layout, pane_id) = tide_layout::SplitLayout::with_initial_pane();
         app.layout = layout;
@@ -146,6 +146,81 @@ mod focus_management {
         app.handle_global_action(tide_input::GlobalAction::ToggleZoom);
         assert!(app.zoomed_pane.is_none());
     }
+
+    fn app_with_two_panes_following_mouse() -> (App, PaneId, PaneId) {
+        let mut app = test_app();
+        let (layout, id1) = tide_layout::SplitLayout::with_initial_pane();
+        app.layout = layout;
+        app.panes.insert(id1, PaneKind::Editor(crate::editor_pane::EditorPane::new_empty(id1)));
+        let id2 = app.layout.split(id1, tide_core::SplitDirection::Vertical);
+        app.panes.insert(id2, PaneKind::Editor(crate::editor_pane::EditorPane::new_empty(id2)));
+        app.focused = Some(id1);
+        app.router.set_focused(id1);
+        app.settings.focus_follows_mouse = true;
+        app.compute_layout();
+        (app, id1, id2)
+    }
+
+    /// Move the pointer to the center of `id` as the mouse-move handler does.
+    fn hover(app: &mut App, id: PaneId, now: std::time::Instant) {
+        let rect = app.pane_rects.iter().find(|(pid, _)| *pid == id).map(|(_, r)| *r).unwrap();
+        let pos = tide_core::Vec2::new(rect.x + rect.width / 2.0, rect.y + rect.height / 2.0);
+        app.router.process(tide_core::InputEvent::MouseMove { position: pos }, &app.pane_rects);
+        if app.router.hovered_changed() {
+            app.schedule_focus_follow(now);
+        }
+    }
+
+    #[test]
+    fn focus_follows_mouse_after_dwell() {
+        // UC-3 BR-42: With focus_follows_mouse, the hovered Pane takes focus after the dwell
+        let (mut app, id1, id2) = app_with_two_panes_following_mouse();
+        let t0 = std::time::Instant::now();
+        hover(&mut app, id2, t0);
+
+        app.apply_focus_follow(t0 + crate::theme::FOCUS_FOLLOWS_MOUSE_DWELL / 2);
+        assert_eq!(app.focused, Some(id1));
+        app.apply_focus_follow(t0 + crate::theme::FOCUS_FOLLOWS_MOUSE_DWELL);
+        assert_eq!(app.focused, Some(id2));
+        assert_eq!(app.router.focused(), Some(id2));
+    }
+
+    #[test]
+    fn focus_follows_mouse_ignores_brief_pass_over() {
+        // UC-3 BR-42: Leaving the Pane before the dwell elapses cancels the switch
+        let (mut app, id1, id2) = app_with_two_panes_following_mouse();
+        let t0 = std::time::Instant::now();
+        hover(&mut app, id2, t0);
+        hover(&mut app, id1, t0 + crate::theme::FOCUS_FOLLOWS_MOUSE_DWELL / 2);
+
+        app.apply_focus_follow(t0 + crate::theme::FOCUS_FOLLOWS_MOUSE_DWELL * 2);
+        assert_eq!(app.focused, Some(id1));
+        assert!(app.interaction.focus_follow_at.is_none());
+    }
+
+    #[test]
+    fn focus_follows_mouse_does_not_switch_mid_drag() {
+        // UC-3 BR-43: A drag in progress when the dwell elapses keeps focus where it is
+        let (mut app, id1, id2) = app_with_two_panes_following_mouse();
+        let t0 = std::time::Instant::now();
+        hover(&mut app, id2, t0);
+        app.interaction.mouse_left_pressed = true;
+
+        app.apply_focus_follow(t0 + crate::theme::FOCUS_FOLLOWS_MOUSE_DWELL);
+        assert_eq!(app.focused, Some(id1));
+    }
+
+    #[test]
+    fn focus_follows_mouse_is_off_by_default() {
+        // UC-3 BR-42: Without the setting, hovering never moves focus
+        let (mut app, id1, id2) = app_with_two_panes_following_mouse();
+        app.settings.focus_follows_mouse = false;
+        let t0 = std::time::Instant::now();
+        hover(&mut app, id2, t0);
+
+        app.apply_focus_follow(t0 + crate::theme::FOCUS_FOLLOWS_MOUSE_DWELL);
+        assert_eq!(app.focused, Some(id1));
+    }
 }
 
 #[cfg(test)]
@@ -155,7 +230,7 @@ mod modal_behavior {
     use crate::ui_state::*;
     use crate::App;
     use std::path::PathBuf;
-    use tide_core::Rect;
+    use tide_core::{PaneId, Rect};
 
     fn test_app() -> App {
         let mut app = App::new();
@@ -164,7 +239,7 @@ mod modal_behavior {
         app
     }
 
-    fn app_with_editor() -> (App, u64) {
+    fn app_with_editor() -> (App, PaneId) {
         let mut app = test_app();
         let (layout, id) = tide_layout::SplitLayout::with_initial_pane();
         app.layout = layout;
@@ -189,7 +264,7 @@ mod modal_behavior {
         let mut app = test_app();
         app.modal.file_finder = Some(FileFinderState::new(PathBuf::from("/"), vec![]));
         app.modal.git_switcher = Some(GitSwitcherState::new(
-            1, GitSwitcherMode::Branches, vec![], vec![],
+            PaneId::new(1), GitSwitcherMode::Branches, vec![], vec![],
             Rect::new(0.0, 0.0, 100.0, 30.0),
         ));
         assert!(app.modal.is_any_open());
@@ -369,7 +444,7 @@ mod pane_lifecycle {
     use crate::pane::PaneKind;
     use crate::ui_state::FocusArea;
     use crate::App;
-    use tide_core::LayoutEngine;
+    use tide_core::{LayoutEngine, PaneId};
 
     fn test_app() -> App {
         let mut app = App::new();
@@ -378,7 +453,7 @@ mod pane_lifecycle {
         app
     }
 
-    fn app_with_editor() -> (App, u64) {
+    fn app_with_editor() -> (App, PaneId) {
         let mut app = test_app();
         let (layout, id) = tide_layout::SplitLayout::with_initial_pane();
         app.layout = layout;
@@ -558,6 +633,43 @@ mod pane_lifecycle {
         assert_eq!(app.layout.pane_ids().len(), app.panes.len());
     }
 
+    #[test]
+    fn tab_next_and_prev_cycle_the_active_tab_with_wraparound() {
+        // UC-1 BR-3a: TabNext/TabPrev cycle tabs within the focused TabGroup
+        let (mut app, first) = app_with_editor();
+        app.new_editor_pane();
+        let second = app.focused.unwrap();
+        app.new_editor_pane();
+        let third = app.focused.unwrap();
+
+        app.handle_global_action(tide_input::GlobalAction::TabNext);
+        assert_eq!(app.focused, Some(first));
+        assert_eq!(app.layout.tab_group_containing(first).unwrap().active_pane(), first);
+        app.handle_global_action(tide_input::GlobalAction::TabNext);
+        assert_eq!(app.focused, Some(second));
+        app.handle_global_action(tide_input::GlobalAction::TabPrev);
+        app.handle_global_action(tide_input::GlobalAction::TabPrev);
+        assert_eq!(app.focused, Some(third));
+    }
+
+    #[test]
+    fn closing_active_middle_tab_selects_next_tab() {
+        // UC-5 BR-12: Closing the active tab activates its neighbor in the group
+        let (mut app, first) = app_with_editor();
+        app.new_editor_pane();
+        let second = app.focused.unwrap();
+        app.new_editor_pane();
+        let third = app.focused.unwrap();
+        app.handle_global_action(tide_input::GlobalAction::TabPrev);
+        assert_eq!(app.focused, Some(second));
+
+        app.force_close_editor_panel_tab(second);
+        assert_eq!(app.focused, Some(third));
+        let tg = app.layout.tab_group_containing(first).unwrap();
+        assert_eq!(tg.tabs, vec![first, third]);
+        assert_eq!(tg.active_pane(), third);
+    }
+
     #[test]
     fn closing_tab_in_right_group_focuses_same_group_not_left() {
         // UC-5 BR-12: Focus stays in the same TabGroup after close
@@ -604,6 +716,106 @@ mod pane_lifecycle {
         app.cancel_save_confirm();
         assert!(app.modal.save_confirm.is_none());
     }
+
+    // --- UC-7: LastPaneExit ---
+
+    use crate::action::{last_pane_exit_outcome, PaneExit, PaneExitOutcome};
+    use crate::settings::OnLastPaneExit;
+
+    #[test]
+    fn pane_exit_with_other_panes_keeps_the_layout() {
+        // UC-7 BR-16: Only the last pane consults the policy
+        for policy in [OnLastPaneExit::CloseWindow, OnLastPaneExit::CloseWindowOnExit, OnLastPaneExit::KeepOpen] {
+            for exit in [PaneExit::ShellExited, PaneExit::Closed { exited: false }, PaneExit::Closed { exited: true }] {
+                assert_eq!(last_pane_exit_outcome(policy, exit, 2, 1), PaneExitOutcome::KeepLayout);
+            }
+        }
+    }
+
+    #[test]
+    fn last_pane_exit_with_close_window_quits_or_closes_the_workspace() {
+        // UC-7 BR-17: CloseWindow quits, or closes the workspace when others exist
+        let policy = OnLastPaneExit::CloseWindow;
+        for exit in [PaneExit::Closed { exited: false }, PaneExit::Closed { exited: true }] {
+            assert_eq!(last_pane_exit_outcome(policy, exit, 1, 1), PaneExitOutcome::Quit);
+            assert_eq!(last_pane_exit_outcome(policy, exit, 1, 3), PaneExitOutcome::CloseWorkspace);
+        }
+        assert_eq!(OnLastPaneExit::default(), OnLastPaneExit::CloseWindow);
+    }
+
+    #[test]
+    fn last_shell_exiting_keeps_the_dead_shell_overlay_by_default() {
+        // UC-7 BR-17: Under the default policy a shell exit leaves the pane as is
+        for workspaces in [1, 3] {
+            assert_eq!(
+                last_pane_exit_outcome(OnLastPaneExit::default(), PaneExit::ShellExited, 1, workspaces),
+                PaneExitOutcome::KeepLayout
+            );
+        }
+    }
+
+    #[test]
+    fn last_shell_exiting_with_close_window_on_exit_closes_the_window() {
+        // UC-7 BR-17a: CloseWindowOnExit also closes on a shell exit
+        let policy = OnLastPaneExit::CloseWindowOnExit;
+        for exit in [PaneExit::ShellExited, PaneExit::Closed { exited: false }] {
+            assert_eq!(last_pane_exit_outcome(policy, exit, 1, 1), PaneExitOutcome::Quit);
+            assert_eq!(last_pane_exit_outcome(policy, exit, 1, 3), PaneExitOutcome::CloseWorkspace);
+        }
+        let settings: crate::settings::TideSettings =
+            serde_json::from_str(r#"{"on_last_pane_exit": "close_window_on_exit"}"#).unwrap();
+        assert_eq!(settings.on_last_pane_exit, policy);
+    }
+
+    #[test]
+    fn last_pane_exit_with_keep_open_leaves_a_placeholder() {
+        // UC-7 BR-18: KeepOpen keeps the window on an exited placeholder
+        let policy = OnLastPaneExit::KeepOpen;
+        for workspaces in [1, 2] {
+            assert_eq!(last_pane_exit_outcome(policy, PaneExit::ShellExited, 1, workspaces), PaneExitOutcome::Placeholder);
+            assert_eq!(
+                last_pane_exit_outcome(policy, PaneExit::Closed { exited: false }, 1, workspaces),
+                PaneExitOutcome::Placeholder
+            );
+        }
+        let settings: crate::settings::TideSettings =
+            serde_json::from_str(r#"{"on_last_pane_exit": "keep_open"}"#).unwrap();
+        assert_eq!(settings.on_last_pane_exit, policy);
+    }
+
+    #[test]
+    fn closing_the_exited_placeholder_closes_the_window() {
+        // UC-7 BR-19: Closing an exited last pane closes even with KeepOpen
+        let closed = PaneExit::Closed { exited: true };
+        assert_eq!(last_pane_exit_outcome(OnLastPaneExit::KeepOpen, closed, 1, 1), PaneExitOutcome::Quit);
+        assert_eq!(last_pane_exit_outcome(OnLastPaneExit::KeepOpen, closed, 1, 2), PaneExitOutcome::CloseWorkspace);
+    }
+
+    #[test]
+    fn closing_the_last_terminal_with_keep_open_swaps_in_an_exited_placeholder() {
+        // UC-7 BR-18: The placeholder takes the pane's slot and keeps its CWD
+        let mut app = test_app();
+        app.settings.on_last_pane_exit = OnLastPaneExit::KeepOpen;
+        let (layout, id) = tide_layout::SplitLayout::with_initial_pane();
+        app.layout = layout;
+        let backend = tide_terminal::Terminal::from_reader(std::io::empty(), 80, 24);
+        let mut pane = crate::pane::TerminalPane::with_terminal(id, backend);
+        pane.cwd = Some(std::path::PathBuf::from("/tmp"));
+        app.panes.insert(id, PaneKind::Terminal(pane));
+        app.focused = Some(id);
+
+        app.force_close_specific_pane(id);
+
+        assert_eq!(app.layout.pane_ids(), vec![id]);
+        assert_eq!(app.focused, Some(id));
+        match app.panes.get(&id) {
+            Some(PaneKind::Terminal(placeholder)) => {
+                assert!(placeholder.child_dead);
+                assert_eq!(placeholder.cwd.as_deref(), Some(std::path::Path::new("/tmp")));
+            }
+            _ => panic!("expected an exited terminal placeholder"),
+        }
+    }
 }
 
 #[cfg(test)]
@@ -613,6 +825,7 @@ mod editor_behavior {
     use crate::pane::PaneKind;
     use crate::ui_state::FocusArea;
     use crate::App;
+    use tide_core::PaneId;
 
     fn test_app() -> App {
         let mut app = App::new();
@@ -621,7 +834,7 @@ mod editor_behavior {
         app
     }
 
-    fn app_with_editor() -> (App, u64) {
+    fn app_with_editor() -> (App, PaneId) {
         let mut app = test_app();
         let (layout, id) = tide_layout::SplitLayout::with_initial_pane();
         app.layout = layout;
@@ -754,6 +967,145 @@ mod editor_behavior {
             assert!(!pane.preview_mode);
         }
     }
+
+    // --- UC-4: BlockSelection ---
+
+    fn app_with_block_selection(text: &str, anchor: (usize, usize), end: (usize, usize)) -> (App, PaneId) {
+        let (mut app, id) = app_with_editor();
+        if let Some(PaneKind::Editor(pane)) = app.panes.get_mut(&id) {
+            pane.editor.insert_text(text);
+            pane.editor.set_selection_mode(tide_editor::SelectionMode::Block);
+            pane.selection = Some(crate::pane::Selection { anchor, end });
+        }
+        (app, id)
+    }
+
+    fn editor_lines(app: &App, id: PaneId) -> Vec<String> {
+        match app.panes.get(&id) {
+            Some(PaneKind::Editor(pane)) => (0..pane.editor.buffer.line_count())
+                .filter_map(|i| pane.editor.buffer.line(i).map(str::to_string))
+                .collect(),
+            _ => panic!("expected editor pane"),
+        }
+    }
+
+    #[test]
+    fn block_selection_copies_per_row_column_slices() {
+        // UC-4 BR-16: A block selection's text is the same columns of each row, newline-joined
+        let (app, id) = app_with_block_selection("abcd\nefgh\nijkl", (0, 1), (2, 3));
+        if let Some(PaneKind::Editor(pane)) = app.panes.get(&id) {
+            let sel = pane.selection.clone().unwrap();
+            assert_eq!(pane.selected_text(&sel), "bc\nfg\njk");
+        }
+    }
+
+    #[test]
+    fn typing_into_block_selection_inserts_on_every_row() {
+        // UC-4 BR-17: Typing replaces the block on every row and leaves a caret on each row
+        let (mut app, id) = app_with_block_selection("abcd\nefgh\nijkl", (0, 1), (2, 3));
+        app.send_text_to_target("X");
+        assert_eq!(editor_lines(&app, id), ["aXd", "eXh", "iXl"]);
+        app.send_text_to_target("Y");
+        assert_eq!(editor_lines(&app, id), ["aXYd", "eXYh", "iXYl"]);
+
+        // BR-18: Each keystroke is one undo step across all rows
+        if let Some(PaneKind::Editor(pane)) = app.panes.get_mut(&id) {
+            pane.editor.handle_action(tide_editor::EditorActionKind::Undo);
+        }
+        assert_eq!(editor_lines(&app, id), ["aXd", "eXh", "iXl"]);
+    }
+
+    #[test]
+    fn backspace_at_block_caret_deletes_a_column() {
+        // UC-4 BR-18: Backspace at a caret block deletes the column to its left on every row
+        let (mut app, id) = app_with_block_selection("abcd\nefgh\nijkl", (0, 2), (2, 2));
+        let backspace = tide_core::InputEvent::KeyPress {
+            key: tide_core::Key::Backspace,
+            modifiers: tide_core::Modifiers::default(),
+        };
+        app.handle_action(tide_input::Action::RouteToPane(id), Some(backspace));
+        assert_eq!(editor_lines(&app, id), ["acd", "egh", "ikl"]);
+        if let Some(PaneKind::Editor(pane)) = app.panes.get(&id) {
+            let sel = pane.selection.as_ref().unwrap();
+            assert_eq!((sel.anchor, sel.end), ((0, 1), (2, 1)));
+        }
+    }
+
+    #[test]
+    fn typing_into_block_pads_short_rows() {
+        // UC-4 BR-19: Rows shorter than the block are padded with spaces when typing
+        let (mut app, id) = app_with_block_selection("abcd\ne\nijkl", (0, 3), (2, 3));
+        app.send_text_to_target("|");
+        assert_eq!(editor_lines(&app, id), ["abc|d", "e  |", "ijk|l"]);
+    }
+
+    // --- UC-5: SurroundSelection ---
+
+    fn app_with_selection(text: &str, anchor: (usize, usize), end: (usize, usize)) -> (App, PaneId) {
+        let (mut app, id) = app_with_editor();
+        if let Some(PaneKind::Editor(pane)) = app.panes.get_mut(&id) {
+            pane.editor.insert_text(text);
+            pane.selection = Some(crate::pane::Selection { anchor, end });
+        }
+        (app, id)
+    }
+
+    fn selected_text(app: &App, id: PaneId) -> String {
+        match app.panes.get(&id) {
+            Some(PaneKind::Editor(pane)) => pane.selected_text(pane.selection.as_ref().expect("selection kept")),
+            _ => panic!("expected editor pane"),
+        }
+    }
+
+    #[test]
+    fn quote_typed_over_selection_surrounds_it() {
+        // UC-5 BR-20: An opening quote/bracket wraps the selection and keeps it on the original text
+        let (mut app, id) = app_with_selection("a selection here", (0, 2), (0, 11));
+        app.send_text_to_target("\"");
+        assert_eq!(editor_lines(&app, id), ["a \"selection\" here"]);
+        assert_eq!(selected_text(&app, id), "selection");
+
+        // BR-21: The surround is one undo step
+        if let Some(PaneKind::Editor(pane)) = app.panes.get_mut(&id) {
+            pane.editor.handle_action(tide_editor::EditorActionKind::Undo);
+        }
+        assert_eq!(editor_lines(&app, id), ["a selection here"]);
+    }
+
+    #[test]
+    fn surround_follows_auto_close_setting() {
+        // UC-5 BR-22: With auto-close off, typing an opener replaces the selection
+        let (mut app, id) = app_with_selection("a selection here", (0, 11), (0, 2));
+        app.settings.editor_auto_close = false;
+        app.modal.config_page = Some(crate::ConfigPageState::new(vec![], String::new(), String::new()));
+        app.close_config_page();
+        app.send_text_to_target("(");
+        assert_eq!(editor_lines(&app, id), ["a ( here"]);
+    }
+
+    #[test]
+    fn typing_an_opener_inserts_its_closer() {
+        // UC-1 BR-23: Auto-close pairs brackets and quotes as they are typed
+        let (mut app, id) = app_with_editor();
+        app.send_text_to_target("f(x)");
+        assert_eq!(editor_lines(&app, id), ["f(x)"]);
+        app.send_text_to_target(" \"");
+        assert_eq!(editor_lines(&app, id), ["f(x) \"\""]);
+    }
+
+    #[test]
+    fn new_editors_follow_the_auto_close_setting() {
+        // UC-1 BR-23: `editor_auto_close` applies to editors as they are created
+        let (mut app, id) = app_with_editor();
+        app.settings.editor_auto_close = false;
+        app.router.set_focused(id);
+        app.focused = Some(id);
+        app.new_editor_pane();
+        let new_id = app.focused.expect("new editor focused");
+        assert_ne!(new_id, id);
+        app.send_text_to_target("(");
+        assert_eq!(editor_lines(&app, new_id), ["("]);
+    }
 }
 
 #[cfg(test)]
@@ -763,7 +1115,7 @@ mod keyboard_routing {
     use crate::pane::PaneKind;
     use crate::ui_state::*;
     use crate::App;
-    use tide_core::{Key, Modifiers};
+    use tide_core::{Key, LayoutEngine, Modifiers, PaneId};
     use std::path::PathBuf;
 
     fn test_app() -> App {
@@ -773,7 +1125,7 @@ mod keyboard_routing {
         app
     }
 
-    fn app_with_editor() -> (App, u64) {
+    fn app_with_editor() -> (App, PaneId) {
         let mut app = test_app();
         let (layout, id) = tide_layout::SplitLayout::with_initial_pane();
         app.layout = layout;
@@ -818,6 +1170,113 @@ mod keyboard_routing {
         assert!(app.modal.config_page.is_none());
     }
 
+    #[test]
+    fn closing_the_config_page_applies_the_clipboard_key_policy() {
+        // UC-1 BR-44: The clipboard key policy follows the settings after a settings reload
+        let (mut app, id) = app_with_editor();
+        app.router.set_focused(id);
+        let ctrl_v = tide_core::InputEvent::KeyPress {
+            key: Key::Char('v'),
+            modifiers: Modifiers { ctrl: true, ..Default::default() },
+        };
+        assert_eq!(app.router.process(ctrl_v, &app.pane_rects), tide_input::Action::RouteToPane(id));
+        app.settings.ctrl_v_pastes = true;
+        app.modal.config_page = Some(ConfigPageState::new(vec![], String::new(), String::new()));
+        app.close_config_page();
+        assert_eq!(
+            app.router.process(ctrl_v, &app.pane_rects),
+            tide_input::Action::GlobalAction(tide_input::GlobalAction::Paste)
+        );
+    }
+
+    #[test]
+    fn closing_the_config_page_applies_the_modifier_normalization() {
+        // UC-1 BR-48: Ctrl as Cmd follows the settings after a settings reload
+        let (mut app, id) = app_with_editor();
+        app.router.set_focused(id);
+        let ctrl = Modifiers { ctrl: true, ..Default::default() };
+        let ctrl_t = tide_core::InputEvent::KeyPress { key: Key::Char('t'), modifiers: ctrl };
+        let ctrl_w = tide_core::InputEvent::KeyPress { key: Key::Char('w'), modifiers: ctrl };
+        assert_eq!(app.router.process(ctrl_t, &app.pane_rects), tide_input::Action::RouteToPane(id));
+        app.settings.ctrl_as_cmd = true;
+        app.modal.config_page = Some(ConfigPageState::new(vec![], String::new(), String::new()));
+        app.close_config_page();
+        assert_eq!(
+            app.router.process(ctrl_t, &app.pane_rects),
+            tide_input::Action::GlobalAction(tide_input::GlobalAction::NewTab)
+        );
+        assert_eq!(app.router.process(ctrl_w, &app.pane_rects), tide_input::Action::RouteToPane(id));
+    }
+
+    #[test]
+    fn closing_the_config_page_applies_safe_paste_to_terminals() {
+        // UC-1 BR-49: Pastes and commands Tide types follow `safe_paste`; keys are never filtered
+        let (mut app, _) = app_with_editor();
+        let term_id = PaneId::new(99);
+        let backend = tide_terminal::Terminal::from_reader(std::io::empty(), 80, 24);
+        app.panes.insert(term_id, PaneKind::Terminal(crate::pane::TerminalPane::with_terminal(term_id, backend)));
+        let filter = |app: &App| match app.panes.get(&term_id) {
+            Some(PaneKind::Terminal(pane)) => pane.backend.write_filter(),
+            _ => panic!("terminal pane"),
+        };
+        assert_eq!(filter(&app), tide_terminal::WriteFilter::NONE);
+        app.settings.safe_paste = true;
+        app.modal.config_page = Some(ConfigPageState::new(vec![], String::new(), String::new()));
+        app.close_config_page();
+        assert_eq!(filter(&app).apply("ls\r\x1b[31m\tx\n"), "ls[31m\tx\n");
+        assert!(!filter(&app).needs_confirmation("ls\n"), "no confirmation prompt yet");
+
+        let backend = tide_terminal::Terminal::from_reader(std::io::empty(), 80, 24);
+        let mut pane = crate::pane::TerminalPane::with_terminal(PaneId::new(100), backend);
+        app.apply_terminal_settings(&mut pane);
+        assert_eq!(pane.backend.write_filter(), filter(&app));
+    }
+
+    #[test]
+    fn held_navigation_chord_repeats_with_its_modifiers() {
+        // UC-1 BR-50: A held Navigate chord repeats on Tide's cadence; OS repeats are dropped
+        let mut app = test_app();
+        let (layout, id1) = tide_layout::SplitLayout::with_initial_pane();
+        app.layout = layout;
+        app.panes.insert(id1, PaneKind::Editor(EditorPane::new_empty(id1)));
+        let id2 = app.layout.split(id1, tide_core::SplitDirection::Vertical);
+        app.panes.insert(id2, PaneKind::Editor(EditorPane::new_empty(id2)));
+        app.focused = Some(id1);
+        app.router.set_focused(id1);
+        app.focus_area = FocusArea::PaneArea;
+        app.compute_layout();
+        let refocus = |app: &mut App| {
+            app.focused = Some(id1);
+            app.router.set_focused(id1);
+        };
+
+        app.handle_platform_key_down(Key::Char('j'), cmd(), None, false);
+        assert_eq!(app.focused, Some(id2));
+        assert_eq!(app.key_repeat.held_key(), Some((Key::Char('j'), cmd())));
+
+        // The OS's own repeat of the held chord is dropped.
+        refocus(&mut app);
+        app.handle_platform_key_down(Key::Char('j'), cmd(), None, false);
+        assert_eq!(app.focused, Some(id1));
+
+        // The software repeat presses the chord again, modifiers included.
+        let later = std::time::Instant::now() + tide_input::DEFAULT_KEY_REPEAT_DELAY;
+        app.apply_key_repeat(later);
+        assert_eq!(app.focused, Some(id2));
+
+        // Releasing Cmd stops it.
+        refocus(&mut app);
+        app.key_repeat.on_modifiers_changed(Modifiers::default());
+        app.apply_key_repeat(later + tide_input::DEFAULT_KEY_REPEAT_DELAY);
+        assert_eq!(app.focused, Some(id1));
+
+        // Any other key stops it too.
+        app.handle_platform_key_down(Key::Char('j'), cmd(), None, false);
+        refocus(&mut app);
+        app.handle_platform_key_down(Key::Char('a'), Modifiers::default(), Some("a".to_string()), false);
+        assert_eq!(app.key_repeat.held_key(), None);
+    }
+
     #[test]
     fn file_finder_intercepts_keys_before_pane() {
         // UC-1 BR-4: File finder intercepts keys before Pane
@@ -834,7 +1293,7 @@ mod keyboard_routing {
         // UC-1 BR-6: Escape during pane drag cancels the drag
         let (mut app, _) = app_with_editor();
         app.interaction.pane_drag = crate::drag_drop::PaneDragState::PendingDrag {
-            source_pane: 1,
+            source_pane: PaneId::new(1),
             press_pos: tide_core::Vec2::new(0.0, 0.0),
         };
         app.handle_key_down(Key::Escape, Modifiers::default(), None);
@@ -892,6 +1351,7 @@ mod launcher_behavior {
     use crate::pane::PaneKind;
     use crate::ui_state::FocusArea;
     use crate::App;
+    use tide_core::PaneId;
 
     fn test_app() -> App {
         let mut app = App::new();
@@ -900,7 +1360,7 @@ mod launcher_behavior {
         app
     }
 
-    fn app_with_launcher() -> (App, u64) {
+    fn app_with_launcher() -> (App, PaneId) {
         let mut app = test_app();
         let (layout, id) = tide_layout::SplitLayout::with_initial_pane();
         app.layout = layout;
@@ -1012,6 +1472,18 @@ mod theme_behavior {
         assert!(app.cache.pane_generations.is_empty());
     }
 
+    #[test]
+    fn text_gamma_follows_the_theme_mode() {
+        // UC-1 BR-8: Each mode has its own text gamma setting
+        let mut app = test_app();
+        assert_eq!(app.text_gamma(), 1.0);
+        app.settings.text_gamma_dark = 1.6;
+        app.settings.text_gamma_light = 1.1;
+        assert_eq!(app.text_gamma(), 1.6);
+        app.handle_global_action(tide_input::GlobalAction::ToggleTheme);
+        assert_eq!(app.text_gamma(), 1.1);
+    }
+
     // --- UC-2: FontDefaults ---
 
     #[test]
@@ -1020,6 +1492,46 @@ mod theme_behavior {
         let app = test_app();
         assert!((app.current_font_size - 14.0).abs() < f32::EPSILON);
     }
+
+    // --- UC-3: UserTheme ---
+
+    #[test]
+    fn user_theme_replaces_builtin_theme_for_its_own_mode_only() {
+        // UC-3 BR-5: theme.toml replaces the built-in theme for the mode it declares
+        let mut app = test_app();
+        let user = tide_core::Theme::from_toml("dark = true\ncursor = \"#FF0000\"").unwrap();
+        app.set_user_theme(Some(user.clone()));
+        assert_eq!(app.theme(), user);
+
+        app.handle_global_action(tide_input::GlobalAction::ToggleTheme);
+        assert_eq!(app.theme(), tide_core::Theme::light());
+    }
+
+    #[test]
+    fn user_theme_colors_the_chrome_palette() {
+        // UC-3 BR-5: the UI chrome (pane background, tree, borders) follows theme.toml too
+        let mut app = test_app();
+        let user = tide_core::Theme::from_toml(
+            "dark = true\npane_bg = \"#102030\"\ntree_text = \"#405060\"",
+        )
+        .unwrap();
+        app.set_user_theme(Some(user.clone()));
+        let p = app.palette();
+        assert_eq!(p.pane_bg, user.pane_bg);
+        assert_eq!(p.tree_text, user.tree_text);
+        assert_eq!(p.surface_bg, crate::theme::DARK.surface_bg);
+
+        app.handle_global_action(tide_input::GlobalAction::ToggleTheme);
+        assert_eq!(app.palette().pane_bg, crate::theme::LIGHT.pane_bg);
+    }
+
+    #[test]
+    fn no_user_theme_uses_builtin_theme() {
+        // UC-3 BR-5: without theme.toml the built-in dark/light theme applies
+        let mut app = test_app();
+        app.set_user_theme(None);
+        assert_eq!(app.theme(), tide_core::Theme::dark());
+    }
 }
 
 #[cfg(test)]
@@ -1032,6 +1544,7 @@ mod workspace_behavior {
     use crate::App;
     use std::collections::HashMap;
     use tide_layout::SplitLayout;
+    use tide_core::PaneId;
 
     fn test_app() -> App {
         let mut app = App::new();
@@ -1044,8 +1557,8 @@ mod workspace_behavior {
         let mut app = test_app();
 
         // Use distinct pane IDs for each workspace
-        let id1: u64 = 100;
-        let id2: u64 = 200;
+        let id1 = PaneId::new(100);
+        let id2 = PaneId::new(200);
 
         // Push two workspace slots
         app.ws.workspaces.push(Workspace {
@@ -1089,14 +1602,14 @@ mod workspace_behavior {
         // UC-1 BR-1: Switching preserves each Workspace's focused Pane
         let mut app = app_with_two_workspaces();
         let ws1_focus = app.focused;
-        assert_eq!(ws1_focus, Some(100));
+        assert_eq!(ws1_focus, Some(PaneId::new(100)));
 
         app.switch_workspace(1);
         let ws2_focus = app.focused;
-        assert_eq!(ws2_focus, Some(200));
+        assert_eq!(ws2_focus, Some(PaneId::new(200)));
 
         app.switch_workspace(0);
-        assert_eq!(app.focused, Some(100));
+        assert_eq!(app.focused, Some(PaneId::new(100)));
     }
 
     #[test]
@@ -1266,7 +1779,7 @@ mod ime_behavior {
     use crate::workspace::Workspace;
     use crate::App;
     use std::collections::HashMap;
-    use tide_core::LayoutEngine;
+    use tide_core::{LayoutEngine, PaneId};
     use tide_layout::SplitLayout;
 
     // --- UC-1: Composition ---
@@ -1318,8 +1831,8 @@ mod ime_behavior {
 
     fn app_with_two_workspaces() -> App {
         let mut app = test_app();
-        let id1: u64 = 100;
-        let id2: u64 = 200;
+        let id1 = PaneId::new(100);
+        let id2 = PaneId::new(200);
         app.ws.workspaces.push(Workspace {
             name: "WS1".into(),
             layout: SplitLayout::new(),
@@ -1356,7 +1869,7 @@ mod ime_behavior {
         let mut app = app_with_two_workspaces();
         app.ime.composing = true;
         app.ime.preedit = "ㅎ".to_string();
-        app.ime.last_target = Some(100);
+        app.ime.last_target = Some(PaneId::new(100));
 
         app.switch_workspace(1);
 
@@ -1422,12 +1935,119 @@ mod ime_behavior {
         assert_eq!(app.ime.preedit, "한");
         assert_eq!(app.ime.last_target, Some(id1));
     }
+
+    // --- UC-3: CursorArea ---
+
+    /// A focused editor pane at (100, 50) with 50 lines of "0123456789",
+    /// the cursor at line 10, byte 3.
+    fn app_with_editor_cursor() -> (App, PaneId) {
+        let mut app = test_app();
+        let id: PaneId = PaneId::new(100);
+        let mut pane = EditorPane::new_empty(id);
+        pane.editor.insert_text(&vec!["0123456789"; 50].join("\n"));
+        pane.editor.cursor.position = tide_editor::EditorPosition { line: 10, col: 3 };
+        app.panes.insert(id, PaneKind::Editor(pane));
+        app.focused = Some(id);
+        app.visual_pane_rects = vec![(id, tide_core::Rect::new(100.0, 50.0, 600.0, 400.0))];
+        (app, id)
+    }
+
+    fn editor_mut(app: &mut App, id: PaneId) -> &mut EditorPane {
+        match app.panes.get_mut(&id) {
+            Some(PaneKind::Editor(pane)) => pane,
+            _ => panic!("not an editor"),
+        }
+    }
+
+    #[test]
+    fn editor_ime_rect_is_offset_by_gutter_and_scroll() {
+        // UC-3 BR-9: Editor IME rect is offset by the gutter, scroll and h-scroll
+        use crate::editor_pane::GUTTER_WIDTH_CELLS;
+        use crate::theme::{PANE_PADDING, TAB_BAR_HEIGHT};
+        let (mut app, id) = app_with_editor_cursor();
+        editor_mut(&mut app, id).editor.set_scroll_offset(8);
+        editor_mut(&mut app, id).editor.set_h_scroll_offset(1);
+
+        let r = app.ime_cursor_rect().expect("cursor in view");
+        // Row 10 - 8 = 2, column 3 - 1 = 2, after the gutter.
+        assert_eq!(r.x, 100.0 + PANE_PADDING + (GUTTER_WIDTH_CELLS + 2) as f32 * 8.0);
+        assert_eq!(r.y, 50.0 + TAB_BAR_HEIGHT + 2.0 * 16.0);
+        assert_eq!((r.width, r.height), (8.0, 16.0));
+    }
+
+    #[test]
+    fn editor_ime_rect_is_none_when_cursor_scrolled_out() {
+        // UC-3 BR-10: No IME rect while the editor cursor is scrolled out of view
+        let (mut app, id) = app_with_editor_cursor();
+        editor_mut(&mut app, id).editor.set_scroll_offset(20);
+        assert!(app.ime_cursor_rect().is_none());
+        editor_mut(&mut app, id).editor.set_scroll_offset(0);
+        editor_mut(&mut app, id).editor.set_h_scroll_offset(5);
+        assert!(app.ime_cursor_rect().is_none());
+    }
+
+    #[test]
+    fn ime_rect_follows_focus_and_ignores_cursorless_panes() {
+        // UC-3 BR-11: Only the focused pane's cursor counts; panes without a cursor have none
+        let (mut app, id) = app_with_editor_cursor();
+        let launcher = PaneId::new(200);
+        app.panes.insert(launcher, PaneKind::Launcher(launcher));
+        app.visual_pane_rects.push((launcher, tide_core::Rect::new(700.0, 50.0, 200.0, 400.0)));
+        assert!(app.ime_cursor_rect().is_some());
+        app.focused = Some(launcher);
+        assert!(app.ime_cursor_rect().is_none());
+        app.focused = None;
+        assert!(app.ime_cursor_rect().is_none());
+        app.focused = Some(id);
+        app.visual_pane_rects.retain(|(pid, _)| *pid != id);
+        assert!(app.ime_cursor_rect().is_none());
+    }
+
+    // --- UC-4: DeadKeys ---
+
+    fn focused_empty_editor() -> (App, PaneId) {
+        let mut app = test_app();
+        let id = PaneId::new(100);
+        app.panes.insert(id, PaneKind::Editor(EditorPane::new_empty(id)));
+        app.focused = Some(id);
+        app.focus_area = FocusArea::PaneArea;
+        (app, id)
+    }
+
+    fn first_line(app: &mut App, id: PaneId) -> String {
+        editor_mut(app, id).editor.buffer.line(0).unwrap_or_default().to_string()
+    }
+
+    #[test]
+    fn dead_key_sequence_types_the_composed_char_once() {
+        // UC-4 BR-12: A composing KeyDown is left to the IME; only ImeCommit inserts text
+        use tide_core::{Key, Modifiers};
+        let (mut app, id) = focused_empty_editor();
+        // ´ then e, as the proxy delivers it inside one batch: preedit for the
+        // pending accent, the key flagged as composing, then the commit.
+        app.handle_ime_preedit("´");
+        app.handle_platform_key_down(Key::Char('e'), Modifiers::none(), Some("e".into()), true);
+        app.handle_ime_preedit("");
+        app.handle_ime_commit("é");
+        assert_eq!(first_line(&mut app, id), "é");
+        assert!(!app.ime.composing);
+    }
+
+    #[test]
+    fn plain_key_down_still_types() {
+        // UC-4 BR-13: KeyDown outside a composition routes its chars as before
+        use tide_core::{Key, Modifiers};
+        let (mut app, id) = focused_empty_editor();
+        app.handle_platform_key_down(Key::Char('e'), Modifiers::none(), Some("e".into()), false);
+        assert_eq!(first_line(&mut app, id), "e");
+    }
 }
 
 #[cfg(test)]
 mod render_cache_behavior {
     // Spec: docs/specs/terminal-sync.md — UC-2: InvalidateCache
     use crate::ui_state::RenderCache;
+    use tide_core::PaneId;
 
     #[test]
     fn new_render_cache_starts_dirty_for_initial_render() {
@@ -1449,9 +2069,9 @@ mod render_cache_behavior {
     fn invalidating_pane_removes_pane_generation_and_marks_render_cache_dirty() {
         // UC-2 BR-3: invalidate_pane removes pane generation entry and marks dirty
         let mut cache = RenderCache::new();
-        cache.pane_generations.insert(42, 1);
-        cache.invalidate_pane(42);
-        assert!(!cache.pane_generations.contains_key(&42));
+        cache.pane_generations.insert(PaneId::new(42), 1);
+        cache.invalidate_pane(PaneId::new(42));
+        assert!(!cache.pane_generations.contains_key(&PaneId::new(42)));
         assert!(cache.needs_redraw);
     }
 
@@ -1482,6 +2102,7 @@ mod global_actions {
     use crate::ui_state::FocusArea;
     use crate::App;
     use tide_input::GlobalAction;
+    use tide_core::PaneId;
 
     fn test_app() -> App {
         let mut app = App::new();
@@ -1490,7 +2111,7 @@ mod global_actions {
         app
     }
 
-    fn app_with_editor() -> (App, u64) {
+    fn app_with_editor() -> (App, PaneId) {
         let mut app = test_app();
         let (layout, id) = tide_layout::SplitLayout::with_initial_pane();
         app.layout = layout;
@@ -1598,27 +2219,195 @@ mod global_actions {
         let (mut app, _) = app_with_editor();
         app.handle_global_action(GlobalAction::FileFinder);
     }
-}
-
-#[cfg(test)]
-mod text_input_routing {
-    // Spec: docs/specs/input-routing.md — UC-2: RouteTextInput
-    use crate::editor_pane::EditorPane;
-    use crate::pane::PaneKind;
-    use crate::ui_state::*;
-    use crate::event_handler::text_routing::TextInputTarget;
-    use crate::App;
-    use std::path::PathBuf;
-    use tide_core::Rect;
 
-    fn test_app() -> App {
-        let mut app = App::new();
-        app.cached_cell_size = tide_core::Size::new(8.0, 16.0);
-        app.window_size = (960, 640);
-        app
+    #[test]
+    fn menu_bar_selection_dispatches_the_items_global_action() {
+        // UC-4 BR-36: A menu bar item maps back to the GlobalAction it was built from
+        let (mut app, _) = app_with_editor();
+        let spec = crate::menu_bar::menu_spec(&tide_input::KeybindingMap::new());
+        let toggle_id = spec
+            .menus
+            .iter()
+            .flat_map(|m| &m.items)
+            .find_map(|item| match item {
+                tide_platform::MenuItem::Item { id, label, .. } if label == "Toggle Fullscreen" => Some(*id),
+                _ => None,
+            })
+            .expect("View menu lists Toggle Fullscreen");
+        let action = crate::menu_bar::action_for_menu_id(toggle_id).unwrap();
+        app.handle_global_action(action);
+        assert!(app.pending_fullscreen_toggle);
+        assert_eq!(
+            spec.preferences_id.and_then(crate::menu_bar::action_for_menu_id),
+            Some(GlobalAction::OpenConfig)
+        );
+        assert_eq!(crate::menu_bar::action_for_menu_id(0), None);
+        // Context menu ids sit below the menu bar range and never dispatch.
+        assert!(toggle_id >= crate::menu_bar::MENU_BAR_ID_BASE);
+        assert_eq!(crate::menu_bar::action_for_menu_id(1), None);
+        assert_eq!(crate::menu_bar::action_for_menu_id(crate::menu_bar::MENU_BAR_ID_BASE - 1), None);
+    }
+
+    #[test]
+    fn menu_bar_shortcuts_mirror_the_active_hotkey_table() {
+        // UC-4 BR-37: Menu shortcuts are display-only copies of the hotkey table
+        let copy = tide_input::Hotkey::new(tide_core::Key::Char('y'), true, false, true, false);
+        let map = tide_input::KeybindingMap::with_overrides(vec![(copy, GlobalAction::Copy)]);
+        let spec = crate::menu_bar::menu_spec(&map);
+        let shortcut = spec
+            .menus
+            .iter()
+            .flat_map(|m| &m.items)
+            .find_map(|item| match item {
+                tide_platform::MenuItem::Item { label, shortcut, .. } if label == "Copy" => *shortcut,
+                _ => None,
+            });
+        let (key, mods) = shortcut.expect("Copy shows its hotkey");
+        assert_eq!(key, tide_core::Key::Char('y'));
+        assert!(mods.meta && mods.shift && !mods.ctrl && !mods.alt);
+    }
+
+    /// `cwd()` of a terminal pane once it reports `want`, polling the child process.
+    fn wait_for_cwd(app: &mut App, id: PaneId, want: &std::path::Path) -> Option<std::path::PathBuf> {
+        let deadline = std::time::Instant::now() + std::time::Duration::from_secs(5);
+        loop {
+            let Some(PaneKind::Terminal(pane)) = app.panes.get_mut(&id) else { return None };
+            pane.backend.set_cwd_poll_interval(Some(std::time::Duration::ZERO));
+            let cwd = tide_core::TerminalBackend::cwd(&pane.backend);
+            if cwd.as_deref() == Some(want) || std::time::Instant::now() > deadline {
+                return cwd;
+            }
+            std::thread::sleep(std::time::Duration::from_millis(20));
+        }
     }
 
-    fn app_with_editor() -> (App, u64) {
+    #[test]
+    fn duplicate_pane_opens_a_terminal_in_the_same_directory() {
+        // UC-4 BR-45: DuplicatePane splits a terminal into a new shell in its CWD
+        let dir = std::env::temp_dir().join(format!("tide-duplicate-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let dir = dir.canonicalize().unwrap();
+
+        let mut app = test_app();
+        let (layout, source) = tide_layout::SplitLayout::with_initial_pane();
+        app.layout = layout;
+        let options = app.terminal_options(Some(dir.clone()));
+        let pane = crate::pane::TerminalPane::with_options(source, 80, 24, options, &app.theme()).expect("PTY available");
+        app.panes.insert(source, PaneKind::Terminal(pane));
+        app.focused = Some(source);
+        app.focus_area = FocusArea::PaneArea;
+        assert_eq!(wait_for_cwd(&mut app, source, &dir).as_deref(), Some(dir.as_path()));
+
+        app.handle_global_action(GlobalAction::DuplicatePane);
+        let copy = app.focused.unwrap();
+        assert_ne!(copy, source);
+        assert_eq!(app.layout.pane_ids().len(), 2);
+        assert_eq!(wait_for_cwd(&mut app, copy, &dir).as_deref(), Some(dir.as_path()));
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn duplicate_pane_opens_an_editor_on_the_same_file() {
+        // UC-4 BR-45: DuplicatePane splits an editor into a second view of its file
+        let path = std::env::temp_dir().join(format!("tide-duplicate-{}.txt", std::process::id()));
+        std::fs::write(&path, "hello\n").unwrap();
+        let mut app = test_app();
+        let (layout, source) = tide_layout::SplitLayout::with_initial_pane();
+        app.layout = layout;
+        app.panes.insert(source, PaneKind::Editor(EditorPane::open(source, &path).unwrap()));
+        app.focused = Some(source);
+
+        app.handle_global_action(GlobalAction::DuplicatePane);
+        let copy = app.focused.unwrap();
+        assert_ne!(copy, source);
+        assert_eq!(app.layout.pane_ids().len(), 2);
+        match app.panes.get(&copy) {
+            Some(PaneKind::Editor(pane)) => assert_eq!(pane.editor.file_path(), Some(path.as_path())),
+            _ => panic!("duplicate of an editor is an editor"),
+        }
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn duplicate_pane_carries_unsaved_edits_over() {
+        // UC-4 BR-45: A duplicated editor starts with the source's unsaved edits and cursor
+        let path = std::env::temp_dir().join(format!("tide-duplicate-dirty-{}.txt", std::process::id()));
+        std::fs::write(&path, "hello\n").unwrap();
+        let mut app = test_app();
+        let (layout, source) = tide_layout::SplitLayout::with_initial_pane();
+        app.layout = layout;
+        let mut pane = EditorPane::open(source, &path).unwrap();
+        pane.editor.insert_text("edited ");
+        app.panes.insert(source, PaneKind::Editor(pane));
+        app.focused = Some(source);
+
+        app.handle_global_action(GlobalAction::DuplicatePane);
+        let copy = app.focused.unwrap();
+        match app.panes.get(&copy) {
+            Some(PaneKind::Editor(pane)) => {
+                assert_eq!(pane.editor.buffer.lines, ["edited hello"]);
+                assert!(pane.editor.is_modified());
+                assert_eq!(pane.editor.cursor_position().col, 7);
+            }
+            _ => panic!("duplicate of an editor is an editor"),
+        }
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "hello\n");
+        let _ = std::fs::remove_file(&path);
+    }
+
+    /// Clipboard that counts reads, to check paste reads it once.
+    #[derive(Default)]
+    struct CountingClipboard {
+        reads: std::sync::atomic::AtomicUsize,
+    }
+
+    impl tide_core::Clipboard for CountingClipboard {
+        fn read(&self) -> Option<String> {
+            self.reads.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+            Some("pasted".to_string())
+        }
+
+        fn write(&self, _text: &str) {}
+    }
+
+    #[test]
+    fn paste_reads_the_clipboard_once_and_replaces_the_selection() {
+        // UC-4 BR-47: Paste reads the clipboard once and replaces the selection with that text
+        let (mut app, id) = app_with_editor();
+        let clipboard = std::sync::Arc::new(CountingClipboard::default());
+        if let Some(PaneKind::Editor(pane)) = app.panes.get_mut(&id) {
+            pane.editor.set_clipboard(clipboard.clone());
+            pane.editor.insert_text("old");
+            pane.select_all();
+        }
+        app.handle_global_action(GlobalAction::Paste);
+        assert_eq!(clipboard.reads.load(std::sync::atomic::Ordering::Relaxed), 1);
+        match app.panes.get(&id) {
+            Some(PaneKind::Editor(pane)) => assert_eq!(pane.editor.buffer.line(0), Some("pasted")),
+            _ => panic!("editor pane kept"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod text_input_routing {
+    // Spec: docs/specs/input-routing.md — UC-2: RouteTextInput
+    use crate::editor_pane::EditorPane;
+    use crate::pane::PaneKind;
+    use crate::ui_state::*;
+    use crate::event_handler::text_routing::TextInputTarget;
+    use crate::App;
+    use std::path::PathBuf;
+    use tide_core::{PaneId, Rect};
+
+    fn test_app() -> App {
+        let mut app = App::new();
+        app.cached_cell_size = tide_core::Size::new(8.0, 16.0);
+        app.window_size = (960, 640);
+        app
+    }
+
+    fn app_with_editor() -> (App, PaneId) {
         let mut app = test_app();
         let (layout, id) = tide_layout::SplitLayout::with_initial_pane();
         app.layout = layout;
@@ -1711,14 +2500,25 @@ mod text_input_routing {
 
 #[cfg(test)]
 mod session_behavior {
-    // Spec: docs/specs/session.md — UC-1: SaveLoadSession
-    use crate::session::{Session, SessionLayout};
+    // Spec: docs/specs/session.md — UC-1: SaveLoadSession, UC-2: RestorePanes
+    use crate::session::{
+        resolve_restore_cwd, session_to_snapshot, Session, SessionLayout, SessionPaneKind,
+    };
+    use std::collections::HashMap;
+    use std::path::PathBuf;
+    use tide_layout::SplitLayout;
+    use tide_core::PaneId;
 
     #[test]
     fn session_preserves_dark_mode_preference() {
         // UC-1 BR-1: Session preserves dark_mode preference
         let session = Session {
-            layout: SessionLayout::Leaf { pane_id: 1, cwd: None },
+            layout: SessionLayout::Leaf {
+                pane_id: 1,
+                cwd: None,
+                kind: SessionPaneKind::Terminal,
+                file: None,
+            },
             focused_pane_id: Some(1),
             show_file_tree: false,
             file_tree_width: 200.0,
@@ -1738,7 +2538,12 @@ mod session_behavior {
     fn session_preserves_file_tree_visibility() {
         // UC-1 BR-2: Session preserves file tree visibility and width
         let session = Session {
-            layout: SessionLayout::Leaf { pane_id: 1, cwd: None },
+            layout: SessionLayout::Leaf {
+                pane_id: 1,
+                cwd: None,
+                kind: SessionPaneKind::Terminal,
+                file: None,
+            },
             focused_pane_id: Some(1),
             show_file_tree: true,
             file_tree_width: 300.0,
@@ -1772,6 +2577,113 @@ mod session_behavior {
         assert_eq!(session.sidebar_side, "left");
         assert!(session.sidebar_outer);
     }
+
+    fn leaf(pane_id: u64, cwd: Option<PathBuf>) -> SessionLayout {
+        SessionLayout::Leaf {
+            pane_id,
+            cwd,
+            kind: SessionPaneKind::Terminal,
+            file: None,
+        }
+    }
+
+    #[test]
+    fn saved_session_round_trips_to_equivalent_layout() {
+        // UC-2 BR-4: Saved layout round-trips with the same panes and CWDs
+        let tmp = std::env::temp_dir();
+        let layout = SessionLayout::Split {
+            direction: "horizontal".to_string(),
+            ratio: 0.4,
+            left: Box::new(leaf(3, Some(tmp.clone()))),
+            right: Box::new(SessionLayout::Split {
+                direction: "vertical".to_string(),
+                ratio: 0.5,
+                left: Box::new(leaf(5, Some(PathBuf::from("/")))),
+                right: Box::new(leaf(9, None)),
+            }),
+        };
+        let json = serde_json::to_string(&layout).unwrap();
+        let parsed: SessionLayout = serde_json::from_str(&json).unwrap();
+
+        let mut restored = Vec::new();
+        let snap = session_to_snapshot(&parsed, &mut restored, &mut HashMap::new()).unwrap();
+        let split = SplitLayout::from_snapshot(snap);
+
+        assert_eq!(split.pane_ids().len(), 3);
+        let cwds: Vec<_> = restored.iter().map(|p| p.cwd.clone()).collect();
+        assert_eq!(cwds, vec![Some(tmp), Some(PathBuf::from("/")), None]);
+    }
+
+    #[test]
+    fn restored_pane_ids_are_remapped_and_focus_follows() {
+        // UC-2 BR-5: Saved PaneIds are remapped to fresh ids; focus follows the mapping
+        let layout = SessionLayout::Split {
+            direction: "horizontal".to_string(),
+            ratio: 0.5,
+            left: Box::new(leaf(7, None)),
+            right: Box::new(leaf(42, None)),
+        };
+        let mut restored = Vec::new();
+        let mut id_map = HashMap::new();
+        session_to_snapshot(&layout, &mut restored, &mut id_map).unwrap();
+
+        let ids: Vec<_> = restored.iter().map(|p| p.id).collect();
+        assert_eq!(ids, vec![PaneId::new(1), PaneId::new(2)]);
+        assert_eq!(id_map.get(&42), Some(&PaneId::new(2)));
+    }
+
+    #[test]
+    fn missing_cwd_falls_back_to_home() {
+        // UC-2 BR-6: A saved CWD that no longer exists falls back to $HOME
+        let gone = std::env::temp_dir().join("tide-session-test-does-not-exist");
+        assert_eq!(resolve_restore_cwd(Some(&gone)), dirs::home_dir());
+        let tmp = std::env::temp_dir();
+        assert_eq!(resolve_restore_cwd(Some(&tmp)), Some(tmp.clone()));
+        assert_eq!(resolve_restore_cwd(None), None);
+    }
+
+    #[test]
+    fn editor_pane_restores_with_its_file() {
+        // UC-2 BR-7: Editor panes keep their kind and open file
+        let layout = SessionLayout::Leaf {
+            pane_id: 4,
+            cwd: Some(PathBuf::from("/src")),
+            kind: SessionPaneKind::Editor,
+            file: Some(PathBuf::from("/src/main.rs")),
+        };
+        let json = serde_json::to_string(&layout).unwrap();
+        let parsed: SessionLayout = serde_json::from_str(&json).unwrap();
+        let mut restored = Vec::new();
+        session_to_snapshot(&parsed, &mut restored, &mut HashMap::new()).unwrap();
+
+        assert_eq!(restored[0].kind, SessionPaneKind::Editor);
+        assert_eq!(restored[0].file, Some(PathBuf::from("/src/main.rs")));
+    }
+
+    #[test]
+    fn leaf_without_kind_restores_as_terminal() {
+        // UC-2 BR-8: Older session files without pane kind restore as terminals
+        let parsed: SessionLayout =
+            serde_json::from_str(r#"{"Leaf": {"pane_id": 1, "cwd": null}}"#).unwrap();
+        let mut restored = Vec::new();
+        session_to_snapshot(&parsed, &mut restored, &mut HashMap::new()).unwrap();
+        assert_eq!(restored[0].kind, SessionPaneKind::Terminal);
+    }
+
+    #[test]
+    fn layout_pass_alone_does_not_schedule_autosave() {
+        // UC-2 BR-13: autosave is scheduled by real layout changes, not every layout pass
+        let mut app = crate::App::new();
+        app.window_size = (960, 640);
+        app.compute_layout();
+        app.compute_layout();
+        assert!(app.session_save_at.is_none());
+
+        // Releasing a file tree border drag is a real layout change
+        app.ft.border_dragging = true;
+        app.handle_mouse_up(tide_core::MouseButton::Left);
+        assert!(app.session_save_at.is_some());
+    }
 }
 
 #[cfg(test)]
@@ -1826,6 +2738,84 @@ mod file_tree_scroll {
     }
 }
 
+#[cfg(test)]
+mod file_tree_resize {
+    // Spec: docs/specs/file-tree.md — UC-3: ResizeFileTree
+    use crate::editor_pane::EditorPane;
+    use crate::layout_compute::clamp_file_tree_width;
+    use crate::pane::PaneKind;
+    use crate::theme::{FILE_TREE_MIN_WIDTH, PANE_GAP};
+    use crate::App;
+    use tide_core::{MouseButton, PaneId, Vec2};
+
+    fn app_with_file_tree() -> (App, PaneId) {
+        let mut app = App::new();
+        app.cached_cell_size = tide_core::Size::new(8.0, 16.0);
+        app.window_size = (960, 640);
+        let (layout, id) = tide_layout::SplitLayout::with_initial_pane();
+        app.layout = layout;
+        app.panes.insert(id, PaneKind::Editor(EditorPane::new_empty(id)));
+        app.focused = Some(id);
+        app.ft.visible = true;
+        app.sidebar_side = crate::LayoutSide::Left;
+        app.compute_layout();
+        (app, id)
+    }
+
+    fn window_proxy() -> tide_platform::WindowProxy {
+        tide_platform::WindowProxy::new(std::sync::mpsc::channel().0, std::sync::Arc::new(|| {}))
+    }
+
+    fn pane_x(app: &App, id: PaneId) -> f32 {
+        app.pane_rects.iter().find(|(pid, _)| *pid == id).map(|(_, r)| r.x).unwrap()
+    }
+
+    #[test]
+    fn file_tree_width_clamped_to_min_and_pane_area() {
+        // UC-3 BR-1: Width stays between the minimum and the window minus the pane area
+        assert_eq!(clamp_file_tree_width(300.0, 960.0, 180.0), 300.0);
+        assert_eq!(clamp_file_tree_width(40.0, 960.0, 180.0), FILE_TREE_MIN_WIDTH);
+        assert_eq!(clamp_file_tree_width(900.0, 960.0, 180.0), 680.0);
+        // Too narrow for both: the minimum wins
+        assert_eq!(clamp_file_tree_width(300.0, 200.0, 180.0), FILE_TREE_MIN_WIDTH);
+    }
+
+    #[test]
+    fn restored_width_clamped_on_layout() {
+        // UC-3 BR-2: An out-of-range width (e.g. from a session) is clamped by compute_layout
+        let (mut app, _) = app_with_file_tree();
+        app.ft.width = 10.0;
+        app.compute_layout();
+        assert_eq!(app.ft.width, FILE_TREE_MIN_WIDTH);
+        app.ft.width = 5000.0;
+        app.compute_layout();
+        assert!(app.ft.width < app.logical_size().width);
+    }
+
+    #[test]
+    fn dragging_border_resizes_file_tree_and_reflows_panes() {
+        // UC-3 BR-3: Dragging the border updates the width and shifts the pane area
+        let (mut app, id) = app_with_file_tree();
+        let window = window_proxy();
+        let width_before = app.ft.width;
+        let pane_x_before = pane_x(&app, id);
+        let ft_rect = app.ft.rect.expect("file tree laid out");
+        let border = Vec2::new(ft_rect.x + ft_rect.width + PANE_GAP, ft_rect.y + 100.0);
+
+        app.last_cursor_pos = border;
+        app.handle_mouse_down(MouseButton::Left, &window);
+        assert!(app.ft.border_dragging);
+
+        app.handle_cursor_moved_logical(Vec2::new(border.x + 60.0, border.y), &window);
+        assert_eq!(app.ft.width, width_before + 60.0);
+        assert_eq!(pane_x(&app, id), pane_x_before + 60.0);
+
+        app.handle_mouse_up(MouseButton::Left);
+        assert!(!app.ft.border_dragging);
+        assert_eq!(app.ft.width, width_before + 60.0);
+    }
+}
+
 mod preview_scroll {
     // Spec: docs/specs/editor.md — UC-3: PreviewScroll
     use crate::editor_pane;
@@ -1905,3 +2895,709 @@ mod preview_scroll {
         assert_eq!(v2, 100);
     }
 }
+
+#[cfg(test)]
+mod terminal_selection_behavior {
+    // Spec: docs/specs/terminal-selection.md — UC-1: DragSelect
+    use crate::pane::{is_double_click, pixel_to_grid_cell, selection_autoscroll_delta};
+    use std::time::{Duration, Instant};
+    use tide_core::{PaneId, Rect, Size, Vec2};
+
+    fn inner() -> Rect {
+        // 10 columns of 8px plus 4px of slack → 2px centering offset.
+        Rect::new(100.0, 50.0, 84.0, 160.0)
+    }
+
+    fn cell() -> Size {
+        Size::new(8.0, 16.0)
+    }
+
+    #[test]
+    fn pixel_to_cell_accounts_for_centering_offset() {
+        // UC-1 BR-1: Pixel → cell accounts for grid centering offset
+        assert_eq!(pixel_to_grid_cell(Vec2::new(109.0, 50.0), inner(), cell()), (0, 0));
+        assert_eq!(pixel_to_grid_cell(Vec2::new(110.0, 66.0), inner(), cell()), (1, 1));
+    }
+
+    #[test]
+    fn pixel_above_grid_yields_negative_row() {
+        // UC-1 BR-2: Pixel above the grid yields a negative row
+        let (row, _) = pixel_to_grid_cell(Vec2::new(120.0, 20.0), inner(), cell());
+        assert_eq!(row, -2);
+    }
+
+    #[test]
+    fn pixel_past_right_edge_clamps_column() {
+        // UC-1 BR-3: Column is clamped to the grid width
+        let (_, col) = pixel_to_grid_cell(Vec2::new(500.0, 60.0), inner(), cell());
+        assert_eq!(col, 10);
+        let (_, col) = pixel_to_grid_cell(Vec2::new(0.0, 60.0), inner(), cell());
+        assert_eq!(col, 0);
+    }
+
+    #[test]
+    fn dragging_above_viewport_scrolls_into_history() {
+        // UC-1 BR-4: Dragging above the viewport scrolls into history
+        assert_eq!(selection_autoscroll_delta(-1, 10), 1);
+        assert_eq!(selection_autoscroll_delta(-3, 10), 3);
+    }
+
+    #[test]
+    fn dragging_below_viewport_scrolls_toward_bottom() {
+        // UC-1 BR-5: Dragging below the viewport scrolls toward the bottom
+        assert_eq!(selection_autoscroll_delta(10, 10), -1);
+        assert_eq!(selection_autoscroll_delta(12, 10), -3);
+    }
+
+    #[test]
+    fn autoscroll_speed_is_capped() {
+        // UC-1 BR-6: Auto-scroll speed is capped
+        assert_eq!(selection_autoscroll_delta(-100, 10), 5);
+        assert_eq!(selection_autoscroll_delta(100, 10), -5);
+    }
+
+    #[test]
+    fn no_autoscroll_inside_viewport() {
+        // UC-1 BR-7: No auto-scroll while the pointer is inside the viewport
+        assert_eq!(selection_autoscroll_delta(0, 10), 0);
+        assert_eq!(selection_autoscroll_delta(9, 10), 0);
+    }
+
+    #[test]
+    fn second_click_on_same_cell_is_a_double_click() {
+        // UC-2 BR-8: Two clicks on the same cell within the interval select a word
+        let t0 = Instant::now();
+        let first = Some((t0, PaneId::new(1), (2, 5)));
+        assert!(is_double_click(first, t0 + Duration::from_millis(200), PaneId::new(1), (2, 5)));
+    }
+
+    #[test]
+    fn slow_or_moved_second_click_starts_a_new_selection() {
+        // UC-2 BR-9: A late click, another cell, or another pane is a single click
+        let t0 = Instant::now();
+        let first = Some((t0, PaneId::new(1), (2, 5)));
+        assert!(!is_double_click(first, t0 + Duration::from_millis(800), PaneId::new(1), (2, 5)));
+        assert!(!is_double_click(first, t0 + Duration::from_millis(100), PaneId::new(1), (2, 6)));
+        assert!(!is_double_click(first, t0 + Duration::from_millis(100), PaneId::new(2), (2, 5)));
+        assert!(!is_double_click(None, t0, PaneId::new(1), (2, 5)));
+    }
+}
+
+#[cfg(test)]
+mod frame_pacing_behavior {
+    // Spec: docs/specs/terminal-sync.md — UC-3: PaceFrames
+    use std::time::{Duration, Instant};
+    use tide_core::{frame_budget, RedrawScheduler};
+
+    #[test]
+    fn clean_frame_schedules_nothing() {
+        // UC-3 BR-1: Nothing dirty means no frame is scheduled
+        let now = Instant::now();
+        let mut redraw = RedrawScheduler::new(frame_budget(60));
+        redraw.on_frame_rendered(now);
+        assert_eq!(redraw.frame_wait(now, false), None);
+    }
+
+    #[test]
+    fn sustained_output_renders_at_most_once_per_budget() {
+        // UC-3 BR-2: Sustained dirty wakes render at most once per budget window
+        let budget = frame_budget(60);
+        let start = Instant::now();
+        let mut redraw = RedrawScheduler::new(budget);
+        redraw.on_frame_rendered(start);
+        let mut frames = Vec::new();
+        // A sync wake every millisecond for one second.
+        for ms in 1..=1000 {
+            let now = start + Duration::from_millis(ms);
+            redraw.on_output();
+            if redraw.should_render(now, true) {
+                frames.push(now);
+                redraw.on_frame_rendered(now);
+            }
+        }
+        assert!(frames.len() <= 60, "rendered {} frames", frames.len());
+        assert!(frames.len() >= 55, "rendered {} frames", frames.len());
+        for pair in frames.windows(2) {
+            assert!(pair[1].duration_since(pair[0]) >= budget);
+        }
+    }
+
+    #[test]
+    fn input_bypasses_frame_budget() {
+        // UC-3 BR-3: Recent input or scroll bypasses the budget
+        let now = Instant::now();
+        let mut redraw = RedrawScheduler::new(frame_budget(60));
+        redraw.on_frame_rendered(now);
+        redraw.on_input();
+        assert!(redraw.should_render(now, true));
+        redraw.on_output();
+        assert!(!redraw.should_render(now, true));
+        redraw.on_scroll(now);
+        assert!(redraw.should_render(now, true));
+    }
+
+    #[test]
+    fn uncapped_fps_uses_coalescing_window() {
+        // UC-3 BR-4: max_fps = 0 falls back to the 2ms coalescing window
+        assert_eq!(frame_budget(0), Duration::from_millis(2));
+        assert!(frame_budget(60) > Duration::from_millis(16));
+        // The default does not cap 120Hz displays at 60fps
+        assert_eq!(crate::settings::TideSettings::default().max_fps, 0);
+    }
+
+    #[test]
+    fn smooth_scroll_keeps_frames_coming_until_it_settles() {
+        // UC-3 BR-5: An unsettled file tree scroll schedules frames with nothing dirty
+        let mut app = crate::App::new();
+        app.ft.scroll_target = 200.0;
+        app.update();
+        let now = Instant::now();
+        assert!(app.redraw.frame_wait(now, false).is_some());
+        for _ in 0..50 {
+            app.update();
+        }
+        assert_eq!(app.ft.scroll, app.ft.scroll_target);
+        assert_eq!(app.redraw.frame_wait(now, false), None);
+    }
+}
+
+#[cfg(test)]
+mod scroll_input_behavior {
+    // Spec: docs/specs/input-routing.md — UC-5: NormalizeScroll, UC-6: PinchZoom
+    use crate::App;
+
+    fn test_app() -> App {
+        let mut app = App::new();
+        app.cached_cell_size = tide_core::Size::new(8.0, 16.0);
+        app
+    }
+
+    #[test]
+    fn wheel_notch_and_trackpad_pixels_scroll_the_same_lines() {
+        // UC-5 BR-38: Wheel notches and trackpad pixels both become lines
+        let mut app = test_app();
+        let cell_height = app.cell_size().height;
+        let (_, wheel) = app.scroll_lines(0.0, 1.0, false);
+        let (_, trackpad) = app.scroll_lines(0.0, tide_input::LINES_PER_NOTCH * cell_height, true);
+        assert_eq!(wheel, tide_input::LINES_PER_NOTCH);
+        assert!((wheel - trackpad).abs() < 1e-4);
+    }
+
+    #[test]
+    fn wheel_acceleration_is_off_by_default() {
+        // UC-5 BR-39: Acceleration only applies with scroll_acceleration enabled
+        let mut app = test_app();
+        let first = app.scroll_lines(0.0, 1.0, false);
+        let second = app.scroll_lines(0.0, 1.0, false);
+        assert_eq!(first, second);
+
+        app.settings.scroll_acceleration = true;
+        app.scroll_lines(0.0, 1.0, false);
+        let (_, fast) = app.scroll_lines(0.0, 1.0, false);
+        assert!(fast > tide_input::LINES_PER_NOTCH);
+    }
+
+    #[test]
+    fn pinch_past_threshold_steps_font_size() {
+        // UC-6 BR-40: Pinch magnification past PINCH_FONT_STEP changes font size by one step
+        let mut app = test_app();
+        let start = app.current_font_size;
+        app.handle_pinch(tide_input::PINCH_FONT_STEP * 0.5, false);
+        assert_eq!(app.current_font_size, start);
+        app.handle_pinch(tide_input::PINCH_FONT_STEP * 0.6, false);
+        assert_eq!(app.current_font_size, start + 1.0);
+        app.handle_pinch(-tide_input::PINCH_FONT_STEP * 1.2, false);
+        assert_eq!(app.current_font_size, start);
+    }
+
+    #[test]
+    fn pinch_gesture_end_discards_the_remainder() {
+        // UC-6 BR-46: Ending a pinch drops magnification that didn't reach a step
+        let mut app = test_app();
+        let start = app.current_font_size;
+        app.handle_pinch(tide_input::PINCH_FONT_STEP * 0.6, true);
+        app.handle_pinch(tide_input::PINCH_FONT_STEP * 0.6, true);
+        assert_eq!(app.current_font_size, start);
+    }
+}
+
+#[cfg(test)]
+mod cell_snap_behavior {
+    // Spec: docs/specs/terminal-sync.md — UC-4: SnapToCells
+    use crate::pane::{grid_dimensions, snap_window_to_cells, PaneKind, TerminalPane};
+    use crate::App;
+    use tide_core::Size;
+
+    const CELL: Size = Size { width: 8.0, height: 16.0 };
+
+    fn app_with_terminal() -> App {
+        let mut app = App::new();
+        app.cached_cell_size = CELL;
+        app.window_size = (963, 641);
+        let (layout, pane_id) = tide_layout::SplitLayout::with_initial_pane();
+        app.layout = layout;
+        let pane = TerminalPane::with_options(pane_id, 80, 24, app.terminal_options(None), &app.theme()).expect("PTY available");
+        app.panes.insert(pane_id, PaneKind::Terminal(pane));
+        app.focused = Some(pane_id);
+        app.compute_layout();
+        app
+    }
+
+    #[test]
+    fn grid_dimensions_floor_to_whole_cells() {
+        // UC-4 BR-1: Columns/rows are whole cells that fit; the remainder is letterboxed
+        assert_eq!(grid_dimensions(Size::new(640.0, 384.0), CELL), (80, 24));
+        assert_eq!(grid_dimensions(Size::new(647.9, 399.9), CELL), (80, 24));
+        assert_eq!(grid_dimensions(Size::new(648.0, 400.0), CELL), (81, 25));
+        assert_eq!(grid_dimensions(Size::new(3.0, 5.0), CELL), (1, 1));
+        assert_eq!(grid_dimensions(Size::new(100_000.0, 100_000.0), CELL), (1000, 500));
+    }
+
+    #[test]
+    fn snapping_trims_or_fills_the_leftover_strip() {
+        // UC-4 BR-2: A leftover up to half a cell is trimmed, more is filled to the next cell
+        let window = Size::new(1000.0, 700.0);
+        let snapped = snap_window_to_cells(window, Size::new(643.0, 390.0), CELL);
+        assert_eq!(snapped, Size::new(997.0, 694.0));
+        let grown = snap_window_to_cells(window, Size::new(646.0, 390.0), CELL);
+        assert_eq!(grown, Size::new(1002.0, 694.0));
+        let exact = snap_window_to_cells(window, Size::new(640.0, 384.0), CELL);
+        assert_eq!(exact, window);
+    }
+
+    #[test]
+    fn snap_to_cells_is_off_by_default() {
+        // UC-4 BR-3: Without snap_to_cells the window is never resized
+        let mut app = app_with_terminal();
+        assert!(!app.settings.snap_to_cells);
+        assert_eq!(app.cell_snapped_window_size(), None);
+
+        app.settings.snap_to_cells = true;
+        let snapped = app.cell_snapped_window_size().expect("odd window size leaves a strip");
+        // Applying the snapped size leaves nothing to snap
+        app.window_size = (snapped.width.round() as u32, snapped.height.round() as u32);
+        app.compute_layout();
+        assert_eq!(app.cell_snapped_window_size(), None);
+    }
+
+    #[test]
+    fn the_snap_resize_is_not_snapped_again() {
+        // UC-4 BR-5: The resize a snap causes settles without another snap, so the window can't oscillate
+        let mut app = app_with_terminal();
+        app.settings.snap_to_cells = true;
+        let (width, height) = app.settle_cell_snap().expect("odd window size leaves a strip");
+        // The window lands a pixel off the request, leaving a sliver to snap
+        app.window_size = (width + 1, height);
+        app.compute_layout();
+        assert!(app.cell_snapped_window_size().is_some());
+        assert_eq!(app.settle_cell_snap(), None);
+        // A later resize by the user snaps again
+        app.window_size = (width + 3, height + 5);
+        app.compute_layout();
+        assert!(app.settle_cell_snap().is_some());
+    }
+
+    #[test]
+    fn snap_to_cells_skips_fullscreen() {
+        // UC-4 BR-4: Fullscreen windows are never resized
+        let mut app = app_with_terminal();
+        app.settings.snap_to_cells = true;
+        app.is_fullscreen = true;
+        assert_eq!(app.cell_snapped_window_size(), None);
+    }
+}
+
+#[cfg(test)]
+mod launch_behavior {
+    // Spec: docs/specs/session.md — UC-3: LaunchOptions
+    use crate::launch::LaunchOptions;
+    use crate::App;
+    use std::path::{Path, PathBuf};
+
+    #[test]
+    fn cwd_and_exec_flags_are_parsed() {
+        // UC-3 BR-9: --cwd and --exec accept a separate or `=` value
+        let opts = LaunchOptions::parse(["--cwd", "/tmp/project", "--exec=cargo watch"]).unwrap();
+        assert_eq!(opts.cwd, Some(PathBuf::from("/tmp/project")));
+        assert_eq!(opts.exec.as_deref(), Some("cargo watch"));
+        assert!(LaunchOptions::parse(Vec::<String>::new()).unwrap().is_empty());
+    }
+
+    #[test]
+    fn unknown_or_incomplete_arguments_are_rejected() {
+        // UC-3 BR-10: Unknown flags and flags without a value are errors; Finder's -psn_ is ignored
+        assert!(LaunchOptions::parse(["--verbose"]).is_err());
+        assert!(LaunchOptions::parse(["--cwd"]).is_err());
+        assert!(LaunchOptions::parse(["--exec="]).is_err());
+        assert!(LaunchOptions::parse(["-psn_0_12345"]).unwrap().is_empty());
+    }
+
+    #[test]
+    fn relative_cwd_resolves_against_the_start_directory() {
+        // UC-3 BR-11: A relative --cwd is taken relative to where tide was started
+        let opts = LaunchOptions::parse(["--cwd", "src"]).unwrap();
+        assert_eq!(opts.resolved_cwd(Path::new("/work")), Some(PathBuf::from("/work/src")));
+        let abs = LaunchOptions::parse(["--cwd", "/etc"]).unwrap();
+        assert_eq!(abs.resolved_cwd(Path::new("/work")), Some(PathBuf::from("/etc")));
+    }
+
+    #[test]
+    fn initial_pane_roots_the_file_tree_at_cwd_and_consumes_exec() {
+        // UC-3 BR-12: The first pane uses --cwd as file tree root and writes --exec once
+        use tide_core::FileTreeSource;
+        let dir = std::env::temp_dir();
+        let mut app = App::new();
+        app.launch = LaunchOptions { cwd: Some(dir.clone()), exec: Some("true".to_string()) };
+        app.create_initial_pane(None);
+        assert_eq!(app.ft.tree.as_ref().map(|t| t.root().to_path_buf()), Some(dir.clone()));
+        assert_eq!(app.last_cwd, Some(dir));
+        assert_eq!(app.launch.exec, None);
+    }
+}
+
+#[cfg(test)]
+mod prompt_navigation_behavior {
+    // Spec: docs/specs/prompt-navigation.md
+    use crate::pane::{command_output_lines, prompt_jump_delta, TerminalPane};
+    use tide_core::PaneId;
+    use tide_terminal::{PromptMark, PromptMarkKind, Terminal};
+
+    fn mark(kind: PromptMarkKind, line: usize) -> PromptMark {
+        PromptMark { kind, line }
+    }
+
+    /// Prompts on lines 0, 5, 10 and 15 (the live one); each finished
+    /// command printed output from the line after its prompt up to the next.
+    fn marks() -> Vec<PromptMark> {
+        let mut marks = Vec::new();
+        for prompt in [0, 5, 10] {
+            marks.push(mark(PromptMarkKind::PromptStart, prompt));
+            marks.push(mark(PromptMarkKind::CommandStart, prompt));
+            marks.push(mark(PromptMarkKind::OutputStart, prompt + 1));
+            marks.push(mark(PromptMarkKind::CommandEnd { exit_code: Some(0) }, prompt + 5));
+        }
+        marks.push(mark(PromptMarkKind::PromptStart, 15));
+        marks
+    }
+
+    /// A read-only terminal (5 rows) fed the same three commands as `marks()`.
+    fn pane_with_commands() -> TerminalPane {
+        let (a, b, c) = ("\x1b]133;A\x07", "\x1b]133;B\x07", "\x1b]133;C\x07");
+        let mut data = String::new();
+        for cmd in 0..3 {
+            data += &format!("{a}$ {b}cmd{cmd}\r\n{c}");
+            for line in 0..4 {
+                data += &format!("out{cmd}-{line}\r\n");
+            }
+            data += "\x1b]133;D;0\x07";
+        }
+        data += &format!("{a}$ ");
+        let mut backend = Terminal::from_reader(std::io::Cursor::new(data.into_bytes()), 20, 5);
+        let deadline = std::time::Instant::now() + std::time::Duration::from_secs(5);
+        while backend.prompt_marks().len() < 13 {
+            assert!(std::time::Instant::now() < deadline, "prompt marks never arrived");
+            std::thread::sleep(std::time::Duration::from_millis(10));
+            tide_core::TerminalBackend::process(&mut backend);
+        }
+        TerminalPane::with_terminal(PaneId::new(1), backend)
+    }
+
+    #[test]
+    fn prev_prompt_scrolls_the_prompt_above_the_view_to_the_top() {
+        // UC-1 BR-1: PrevPrompt puts the nearest prompt above the top line at the top
+        // 16 lines on a 5-row screen: 11 history lines, live view starts at line 11
+        assert_eq!(prompt_jump_delta(&marks(), 11, 0, false), Some(1));
+        // Scrolled back 1 (prompt 10 at the top) → prompt 5 needs offset 6
+        assert_eq!(prompt_jump_delta(&marks(), 11, 1, false), Some(5));
+        assert_eq!(prompt_jump_delta(&marks(), 11, 6, false), Some(5));
+        assert_eq!(prompt_jump_delta(&marks(), 11, 11, false), None);
+    }
+
+    #[test]
+    fn next_prompt_scrolls_down_and_stops_at_the_live_view() {
+        // UC-1 BR-2: NextPrompt puts the next prompt at the top, clamped to offset 0
+        assert_eq!(prompt_jump_delta(&marks(), 11, 11, true), Some(-5));
+        assert_eq!(prompt_jump_delta(&marks(), 11, 6, true), Some(-5));
+        // Prompt 15 is on the bottom screen: only the live view (offset 0) shows it
+        assert_eq!(prompt_jump_delta(&marks(), 11, 1, true), Some(-1));
+        assert_eq!(prompt_jump_delta(&marks(), 11, 0, true), None);
+    }
+
+    #[test]
+    fn jumps_without_prompt_marks_do_nothing() {
+        // UC-1 BR-3: A shell that emits no OSC 133 gives no jump targets
+        assert_eq!(prompt_jump_delta(&[], 100, 20, false), None);
+        assert_eq!(prompt_jump_delta(&[], 100, 20, true), None);
+    }
+
+    #[test]
+    fn command_output_spans_from_output_start_to_command_end() {
+        // UC-2 BR-4: The output region runs from C to D (or the next prompt)
+        assert_eq!(command_output_lines(&marks(), usize::MAX), Some((11, 15)));
+        assert_eq!(command_output_lines(&marks(), 5), Some((6, 10)));
+        assert_eq!(command_output_lines(&marks(), 7), Some((6, 10)));
+        // No D: the next prompt ends the output
+        let no_end = [
+            mark(PromptMarkKind::PromptStart, 0),
+            mark(PromptMarkKind::OutputStart, 1),
+            mark(PromptMarkKind::PromptStart, 3),
+        ];
+        assert_eq!(command_output_lines(&no_end, usize::MAX), Some((1, 3)));
+        // Running or output-less commands have nothing to select
+        let running = [mark(PromptMarkKind::PromptStart, 0), mark(PromptMarkKind::OutputStart, 1)];
+        assert_eq!(command_output_lines(&running, usize::MAX), None);
+    }
+
+    #[test]
+    fn jumping_between_prompts_moves_the_terminal_view() {
+        // UC-1 BR-1, BR-2: The pane scrolls the terminal by the computed delta
+        let mut pane = pane_with_commands();
+        assert_eq!(pane.backend.history_size(), 11);
+        assert!(pane.jump_to_prompt(false));
+        assert_eq!(pane.backend.first_visible_line(), 10);
+        assert!(pane.jump_to_prompt(false));
+        assert_eq!(pane.backend.first_visible_line(), 5);
+        assert!(pane.jump_to_prompt(true));
+        assert_eq!(pane.backend.first_visible_line(), 10);
+        assert!(pane.jump_to_prompt(true));
+        assert_eq!(pane.backend.display_offset(), 0);
+        assert!(!pane.jump_to_prompt(true));
+    }
+
+    #[test]
+    fn select_command_output_selects_the_last_or_the_scrolled_to_command() {
+        // UC-2 BR-5: Live view selects the last command's output; scrolled back, the top prompt's
+        let mut pane = pane_with_commands();
+        assert!(pane.select_command_output());
+        let sel = pane.selection.clone().unwrap();
+        assert_eq!(pane.selected_text(&sel), "out2-0\nout2-1\nout2-2\nout2-3\n");
+
+        pane.jump_to_prompt(false);
+        pane.jump_to_prompt(false);
+        assert!(pane.select_command_output());
+        let sel = pane.selection.clone().unwrap();
+        assert_eq!(pane.selected_text(&sel), "out1-0\nout1-1\nout1-2\nout1-3\n");
+    }
+}
+
+#[cfg(test)]
+mod pane_events {
+    // Spec: docs/specs/pane-lifecycle.md — UC-8: PaneEvents
+    use crate::editor_pane::EditorPane;
+    use crate::pane::{PaneKind, TerminalPane};
+    use crate::App;
+    use tide_core::{LayoutEngine, PaneEvent, PaneId, SplitDirection, TerminalBackend};
+    use tide_terminal::Terminal;
+
+    fn test_app() -> App {
+        let mut app = App::new();
+        app.cached_cell_size = tide_core::Size::new(8.0, 16.0);
+        app.window_size = (960, 640);
+        app
+    }
+
+    /// A terminal with no shell behind it.
+    fn idle_terminal(id: PaneId) -> PaneKind {
+        PaneKind::Terminal(TerminalPane::with_terminal(id, Terminal::from_reader(std::io::empty(), 80, 24)))
+    }
+
+    /// An app whose layout holds `first` and a split-off editor; returns the
+    /// editor's id.
+    fn app_with(first: impl FnOnce(PaneId) -> PaneKind) -> (App, PaneId, PaneId) {
+        let mut app = test_app();
+        let (layout, id) = tide_layout::SplitLayout::with_initial_pane();
+        app.layout = layout;
+        app.panes.insert(id, first(id));
+        let other = app.layout.split(id, SplitDirection::Horizontal);
+        app.panes.insert(other, PaneKind::Editor(EditorPane::new_empty(other)));
+        app.focused = Some(id);
+        app.router.set_focused(id);
+        (app, id, other)
+    }
+
+    #[test]
+    fn terminal_whose_cwd_changed_emits_cwd_changed() {
+        // UC-8 BR-20: A CWD change seen by the badge poll becomes a CwdChanged event
+        let start = std::env::temp_dir().join(format!("tide-pane-events-start-{}", std::process::id()));
+        let target = std::env::temp_dir().join(format!("tide-pane-events-target-{}", std::process::id()));
+        std::fs::create_dir_all(&start).unwrap();
+        std::fs::create_dir_all(&target).unwrap();
+        let start = start.canonicalize().unwrap();
+        let target = target.canonicalize().unwrap();
+
+        let mut app = test_app();
+        let options = app.terminal_options(Some(start.clone()));
+        let mut pane = TerminalPane::with_options(PaneId::new(1), 80, 24, options, &app.theme()).expect("PTY available");
+        pane.backend.set_cwd_poll_interval(Some(std::time::Duration::ZERO));
+        let (layout, id) = tide_layout::SplitLayout::with_initial_pane();
+        app.layout = layout;
+        pane.id = id;
+        app.panes.insert(id, PaneKind::Terminal(pane));
+
+        // Polls until the pane queues an event (or gives up after 5s).
+        let next_events = |app: &mut App| {
+            let deadline = std::time::Instant::now() + std::time::Duration::from_secs(5);
+            loop {
+                app.update_terminal_badges();
+                let events = app.panes.get_mut(&id).unwrap().take_events();
+                if !events.is_empty() || std::time::Instant::now() > deadline {
+                    return events;
+                }
+                std::thread::sleep(std::time::Duration::from_millis(20));
+            }
+        };
+
+        assert_eq!(next_events(&mut app), vec![PaneEvent::CwdChanged(start.clone())]);
+        if let Some(PaneKind::Terminal(pane)) = app.panes.get_mut(&id) {
+            pane.backend.write(format!("cd '{}'\n", target.display()).as_bytes());
+        }
+        assert_eq!(next_events(&mut app), vec![PaneEvent::CwdChanged(target.clone())]);
+
+        app.panes.clear();
+        let _ = std::fs::remove_dir(&start);
+        let _ = std::fs::remove_dir(&target);
+    }
+
+    #[test]
+    fn editor_requesting_a_jump_emits_open_file() {
+        // UC-8 BR-21: request_open queues OpenFile, relative to the editor's file
+        let dir = std::env::temp_dir().join(format!("tide-pane-events-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("main.rs");
+        std::fs::write(&path, "fn main() {}\n").unwrap();
+        let mut pane = EditorPane::open(PaneId::new(1), &path).unwrap();
+
+        pane.request_open("lib.rs".into(), Some((4, 2)));
+        pane.request_open("/etc/hosts".into(), None);
+        assert_eq!(
+            pane.take_events(),
+            vec![
+                PaneEvent::OpenFile(dir.join("lib.rs"), Some((4, 2))),
+                PaneEvent::OpenFile("/etc/hosts".into(), None),
+            ]
+        );
+        assert!(pane.take_events().is_empty());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn open_file_event_opens_an_editor_at_the_position() {
+        // UC-8 BR-21: Draining OpenFile opens the file and moves the cursor
+        let path = std::env::temp_dir().join(format!("tide-pane-events-{}.txt", std::process::id()));
+        std::fs::write(&path, "one\ntwo\nthree\n").unwrap();
+        let (mut app, _, editor) = app_with(|id| PaneKind::Editor(EditorPane::new_empty(id)));
+        match app.panes.get_mut(&editor) {
+            Some(PaneKind::Editor(pane)) => pane.request_open(path.clone(), Some((2, 3))),
+            _ => unreachable!(),
+        }
+
+        app.drain_pane_events();
+        let focused = app.focused.unwrap();
+        match app.panes.get(&focused) {
+            Some(PaneKind::Editor(pane)) => {
+                assert_eq!(pane.editor.file_path(), Some(path.as_path()));
+                assert_eq!(pane.editor.cursor_position(), tide_editor::EditorPosition { line: 2, col: 3 });
+            }
+            _ => panic!("OpenFile focuses an editor"),
+        }
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn cmd_click_on_a_path_in_an_editor_opens_that_file() {
+        // UC-8 BR-21: Cmd+click on a file path in an editor queues OpenFile for it
+        let dir = std::env::temp_dir().join(format!("tide-pane-events-click-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let notes = dir.join("notes.txt");
+        std::fs::write(&notes, "see lib.rs:2 and missing.rs\n").unwrap();
+        std::fs::write(dir.join("lib.rs"), "one\ntwo\n").unwrap();
+        let (mut app, id, _) = app_with(|id| PaneKind::Editor(EditorPane::open(id, &notes).unwrap()));
+        app.visual_pane_rects = vec![(id, tide_core::Rect::new(0.0, 0.0, 480.0, 320.0))];
+        let cell = app.cell_size();
+        let gutter = crate::editor_pane::GUTTER_WIDTH_CELLS as f32 * cell.width;
+        let click_at = |col: usize| {
+            let position = tide_core::Vec2::new(
+                crate::theme::PANE_PADDING + gutter + (col as f32 + 0.5) * cell.width,
+                crate::theme::TAB_BAR_HEIGHT + 0.5 * cell.height,
+            );
+            let modifiers = tide_core::Modifiers { meta: true, ..Default::default() };
+            let event = tide_core::InputEvent::MouseClick { position, button: tide_core::MouseButton::Left, modifiers };
+            (tide_input::Action::OpenLinkAt(id, position), Some(event))
+        };
+
+        // A name that isn't a file only moves the cursor
+        let (action, event) = click_at(20);
+        app.handle_action(action, event);
+        assert!(app.panes.get_mut(&id).unwrap().take_events().is_empty());
+
+        let (action, event) = click_at(6);
+        app.handle_action(action, event);
+        assert_eq!(
+            app.panes.get_mut(&id).unwrap().take_events(),
+            vec![PaneEvent::OpenFile(dir.join("lib.rs"), Some((1, 0)))]
+        );
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn set_title_event_renames_the_terminal_tab() {
+        // UC-8 BR-22: SetTitle replaces a terminal's CWD-derived title
+        let (mut app, id, _) = app_with(idle_terminal);
+        if let Some(PaneKind::Terminal(pane)) = app.panes.get_mut(&id) {
+            pane.push_event(PaneEvent::SetTitle("build".to_string()));
+        }
+
+        app.drain_pane_events();
+        assert_eq!(crate::ui::pane_title(&app.panes, id), "build");
+    }
+
+    #[test]
+    fn osc_title_from_the_shell_renames_the_terminal_tab() {
+        // UC-8 BR-22: A program's OSC 0/2 title becomes SetTitle; an empty one restores the default
+        let (mut app, id, _) = app_with(idle_terminal);
+        let default_title = crate::ui::pane_title(&app.panes, id);
+        if let Some(PaneKind::Terminal(pane)) = app.panes.get_mut(&id) {
+            pane.backend.feed(b"\x1b]2;htop\x07");
+        }
+        app.update_terminal_badges();
+        app.drain_pane_events();
+        assert_eq!(crate::ui::pane_title(&app.panes, id), "htop");
+
+        if let Some(PaneKind::Terminal(pane)) = app.panes.get_mut(&id) {
+            pane.push_event(PaneEvent::SetTitle(String::new()));
+        }
+        app.drain_pane_events();
+        assert_eq!(crate::ui::pane_title(&app.panes, id), default_title);
+    }
+
+    #[test]
+    fn terminal_whose_shell_exited_requests_close_with_close_on_shell_exit() {
+        // UC-8 BR-23: With close_on_shell_exit, a Terminal whose shell exited emits RequestClose and is closed
+        let (mut app, id, _) = app_with(idle_terminal);
+        app.check_child_exits();
+        app.drain_pane_events();
+        assert!(app.panes.contains_key(&id), "off by default: the dead-shell overlay stays");
+
+        let (mut app, id, other) = app_with(idle_terminal);
+        app.settings.close_on_shell_exit = true;
+        app.check_child_exits();
+        app.drain_pane_events();
+        assert!(!app.panes.contains_key(&id));
+        assert_eq!(app.layout.pane_ids(), vec![other]);
+    }
+
+    #[test]
+    fn last_terminal_whose_shell_exited_follows_on_last_pane_exit() {
+        // UC-8 BR-23: The last Pane's RequestClose is left to on_last_pane_exit (CloseWindow keeps the overlay)
+        let mut app = test_app();
+        let (layout, id) = tide_layout::SplitLayout::with_initial_pane();
+        app.layout = layout;
+        app.panes.insert(id, idle_terminal(id));
+        app.focused = Some(id);
+        app.settings.close_on_shell_exit = true;
+        app.check_child_exits();
+        app.drain_pane_events();
+        assert!(matches!(app.panes.get(&id), Some(PaneKind::Terminal(t)) if t.child_dead));
+        assert_eq!(app.layout.pane_ids(), vec![id]);
+    }
+}
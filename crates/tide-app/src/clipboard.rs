@@ -0,0 +1,27 @@
+// System clipboard behind `tide_core::Clipboard`, shared by every pane.
+
+use std::sync::{Arc, OnceLock};
+
+use tide_core::Clipboard;
+
+/// The OS clipboard (via arboard). Each call opens a fresh handle; failures
+/// (no clipboard, non-text contents) read as `None` and drop writes.
+struct SystemClipboard;
+
+impl Clipboard for SystemClipboard {
+    fn read(&self) -> Option<String> {
+        arboard::Clipboard::new().ok()?.get_text().ok()
+    }
+
+    fn write(&self, text: &str) {
+        if let Ok(mut clipboard) = arboard::Clipboard::new() {
+            let _ = clipboard.set_text(text);
+        }
+    }
+}
+
+/// The process-wide system clipboard.
+pub(crate) fn system() -> Arc<dyn Clipboard> {
+    static SYSTEM: OnceLock<Arc<dyn Clipboard>> = OnceLock::new();
+    SYSTEM.get_or_init(|| Arc::new(SystemClipboard)).clone()
+}
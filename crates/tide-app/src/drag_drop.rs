@@ -51,6 +51,8 @@ pub(crate) enum HoverTarget {
     BrowserRefresh,
     BrowserUrlBar,
     EditorScrollbar(PaneId),
+    /// A detected URL in a terminal pane (underlined in the grid).
+    TerminalLink(PaneId),
     WorkspaceSidebarItem(usize),
     WorkspaceSidebarNewBtn,
     WsSidebarBorder,
@@ -3,11 +3,11 @@
 mod rendering;
 
 use std::io;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
-use tide_core::PaneId;
+use tide_core::{PaneEvent, PaneId};
 use tide_editor::input::EditorAction;
-use tide_editor::EditorState;
+use tide_editor::{BlockRange, EditorState, SelectionMode};
 
 use tide_editor::markdown::{PreviewLine, render_markdown_preview, MarkdownTheme};
 
@@ -110,21 +110,73 @@ pub struct EditorPane {
     /// Generation counter at the time of the last `is_modified()` check.
     /// Avoids expensive Vec<String> comparison every frame.
     pub last_checked_gen: u64,
+    /// App-level requests queued for the next update tick (`take_events`).
+    events: Vec<PaneEvent>,
 }
 
 impl EditorPane {
     pub fn new_empty(id: PaneId) -> Self {
-        let editor = EditorState::new_empty();
-        Self { id, editor, search: None, selection: None, disk_changed: false, file_deleted: false, diff_mode: false, disk_content: None, preview_mode: false, preview_cache: None, preview_scroll: 0, preview_h_scroll: 0, preview_last_width: None, preview_scroll_pending_ratio: None, last_is_modified: false, last_checked_gen: 0 }
+        let mut editor = EditorState::new_empty();
+        editor.set_clipboard(crate::clipboard::system());
+        Self { id, editor, search: None, selection: None, disk_changed: false, file_deleted: false, diff_mode: false, disk_content: None, preview_mode: false, preview_cache: None, preview_scroll: 0, preview_h_scroll: 0, preview_last_width: None, preview_scroll_pending_ratio: None, last_is_modified: false, last_checked_gen: 0, events: Vec::new() }
     }
 
     pub fn open(id: PaneId, path: &Path) -> io::Result<Self> {
-        let editor = EditorState::open(path)?;
+        let mut editor = EditorState::open(path)?;
+        editor.set_clipboard(crate::clipboard::system());
         let is_markdown = path.extension()
             .and_then(|ext| ext.to_str())
             .map(|ext| matches!(ext, "md" | "markdown" | "mdown" | "mkd"))
             .unwrap_or(false);
-        Ok(Self { id, editor, search: None, selection: None, disk_changed: false, file_deleted: false, diff_mode: false, disk_content: None, preview_mode: is_markdown, preview_cache: None, preview_scroll: 0, preview_h_scroll: 0, preview_last_width: None, preview_scroll_pending_ratio: None, last_is_modified: false, last_checked_gen: 0 })
+        Ok(Self { id, editor, search: None, selection: None, disk_changed: false, file_deleted: false, diff_mode: false, disk_content: None, preview_mode: is_markdown, preview_cache: None, preview_scroll: 0, preview_h_scroll: 0, preview_last_width: None, preview_scroll_pending_ratio: None, last_is_modified: false, last_checked_gen: 0, events: Vec::new() })
+    }
+
+    /// Ask the app to open `path` at an optional 0-based (line, column).
+    /// A relative path resolves against this file's directory.
+    pub fn request_open(&mut self, path: PathBuf, at: Option<(usize, usize)>) {
+        let path = self.resolve_path(path);
+        self.events.push(PaneEvent::OpenFile(path, at));
+    }
+
+    fn resolve_path(&self, path: PathBuf) -> PathBuf {
+        match self.editor.file_path().and_then(Path::parent) {
+            Some(dir) if path.is_relative() => dir.join(path),
+            _ => path,
+        }
+    }
+
+    /// Go to the file named under the cursor (Cmd+click): a path such as
+    /// `src/lib.rs` or `../README.md:12`, relative to this file's directory.
+    /// Returns false, queueing nothing, if no existing file is named there.
+    pub fn open_path_under_cursor(&mut self) -> bool {
+        let cursor = self.editor.cursor_position();
+        let Some(line) = self.editor.buffer.line(cursor.line) else { return false };
+        let is_path_char = |c: char| c.is_alphanumeric() || matches!(c, '/' | '.' | '-' | '_' | '~' | ':');
+        let mut col = cursor.col.min(line.len());
+        while !line.is_char_boundary(col) {
+            col -= 1;
+        }
+        let start = line[..col].char_indices().rev().find(|&(_, c)| !is_path_char(c)).map_or(0, |(i, c)| i + c.len_utf8());
+        let end = line[col..].find(|c| !is_path_char(c)).map_or(line.len(), |i| col + i);
+        let token = line[start..end].trim_end_matches([':', '.']);
+
+        let mut parts = token.splitn(3, ':');
+        let path_str = parts.next().unwrap_or("");
+        let line_number: Option<usize> = parts.next().and_then(|s| s.parse().ok());
+        if path_str.is_empty() || !path_str.contains('.') && !path_str.contains('/') {
+            return false;
+        }
+        let path = self.resolve_path(PathBuf::from(path_str));
+        if !path.is_file() {
+            return false;
+        }
+        self.request_open(path, line_number.map(|n| (n.saturating_sub(1), 0)));
+        true
+    }
+
+    /// Drain the requests queued since the last call.
+    pub fn take_events(&mut self) -> Vec<PaneEvent> {
+        std::mem::take(&mut self.events)
     }
 
     /// Whether this pane needs a notification bar (disk changed, diff mode, or file deleted).
@@ -204,6 +256,13 @@ impl EditorPane {
         if self.preview_mode {
             return self.preview_selected_text(sel);
         }
+        if self.editor.selection_mode() == SelectionMode::Block {
+            let block = BlockRange::from_corners(sel.anchor, sel.end);
+            if block.width() == 0 {
+                return String::new();
+            }
+            return self.editor.block_text(&block);
+        }
 
         let (start, end) = if sel.anchor < sel.end {
             (sel.anchor, sel.end)
@@ -293,6 +352,7 @@ impl EditorPane {
 
     /// Select all text in the buffer (or preview lines in preview mode).
     pub fn select_all(&mut self) {
+        self.editor.set_selection_mode(SelectionMode::Linear);
         if self.preview_mode {
             if let Some((_, _, _, ref lines)) = self.preview_cache {
                 use unicode_width::UnicodeWidthChar;
@@ -351,6 +411,10 @@ impl EditorPane {
     /// Returns true if a selection was deleted.
     pub fn delete_selection(&mut self) -> bool {
         if let Some(sel) = self.selection.take() {
+            if self.editor.selection_mode() == SelectionMode::Block {
+                self.editor.replace_block(&BlockRange::from_corners(sel.anchor, sel.end), "");
+                return true;
+            }
             let (start, end) = self.selection_byte_range(&sel);
             let new_pos = self.editor.buffer.delete_range(start, end);
             self.editor.cursor.set_position(new_pos);
@@ -360,6 +424,65 @@ impl EditorPane {
         }
     }
 
+    /// Apply typing or Backspace/Delete to every row of a block selection as
+    /// one undo step, leaving a caret on each row so typing continues down the
+    /// column. Returns false (nothing done) for other actions or without a
+    /// block selection.
+    pub fn edit_block(&mut self, action: &EditorAction) -> bool {
+        if self.preview_mode || self.editor.selection_mode() != SelectionMode::Block {
+            return false;
+        }
+        let Some(ref sel) = self.selection else { return false };
+        let block = BlockRange::from_corners(sel.anchor, sel.end);
+        let (target, text) = match action {
+            EditorAction::InsertChar(ch) if !ch.is_control() => (block, ch.to_string()),
+            // A caret block deletes the column on its left/right
+            EditorAction::Backspace if block.width() == 0 => {
+                if block.left == 0 {
+                    return true;
+                }
+                (BlockRange { left: block.left - 1, ..block }, String::new())
+            }
+            EditorAction::Delete if block.width() == 0 => {
+                (BlockRange { right: block.right + 1, ..block }, String::new())
+            }
+            EditorAction::Backspace | EditorAction::Delete => (block, String::new()),
+            _ => return false,
+        };
+        self.editor.replace_block(&target, &text);
+        let col = target.left + text.chars().count();
+        self.selection = Some(Selection {
+            anchor: (block.first_line, col),
+            end: (block.last_line, col),
+        });
+        true
+    }
+
+    /// Typing an opening bracket or quote over a linear selection wraps it in
+    /// the pair (one undo step) and keeps the selection on the original text.
+    /// Returns false (nothing done) for other actions, without a selection, or
+    /// when the editor's auto-close is off.
+    pub fn surround_selection(&mut self, action: &EditorAction) -> bool {
+        if self.preview_mode || self.editor.selection_mode() == SelectionMode::Block {
+            return false;
+        }
+        let (EditorAction::InsertChar(open), Some(sel)) = (action, self.selection.as_ref()) else {
+            return false;
+        };
+        let (start, end) = self.selection_byte_range(sel);
+        if !self.editor.surround_range(start, end, *open) {
+            return false;
+        }
+        // The opener shifts the selected text on its first line right by one char
+        let first_line = start.line;
+        let shift = |(line, col): (usize, usize)| (line, if line == first_line { col + 1 } else { col });
+        if let Some(sel) = self.selection.as_mut() {
+            sel.anchor = shift(sel.anchor);
+            sel.end = shift(sel.end);
+        }
+        true
+    }
+
     /// Get the generation counter for dirty checking.
     pub fn generation(&self) -> u64 {
         if self.preview_mode {
@@ -2,7 +2,7 @@
 
 use unicode_width::UnicodeWidthChar;
 
-use tide_core::{Color, Rect, Renderer, TextStyle, Vec2};
+use tide_core::{Color, Rect, Renderer, Size, TextStyle, Vec2};
 use tide_renderer::WgpuRenderer;
 
 use crate::search::SearchState;
@@ -55,11 +55,11 @@ impl EditorPane {
         let content_width = (rect.width - gutter_width - scrollbar_reserved).max(0.0);
 
         let visible_rows = (rect.height / cell_size.height).floor() as usize;
-        let scroll = self.editor.scroll_offset();
         let h_scroll = self.editor.h_scroll_offset();
 
-        // Get highlighted lines
-        let highlighted = self.editor.visible_highlighted_lines(visible_rows);
+        // Get highlighted display rows (one per row, soft-wrap aware)
+        let highlighted = self.editor.visible_display_rows(visible_rows);
+        let gutter = self.editor.gutter_info(visible_rows);
         let cursor_pos = self.editor.cursor_position();
         let cursor_line = cursor_pos.line;
 
@@ -83,8 +83,8 @@ impl EditorPane {
             0
         };
 
-        for (vi, spans) in highlighted.iter().enumerate() {
-            let abs_line = scroll + vi;
+        for (vi, (spans, gutter_line)) in highlighted.iter().zip(&gutter).enumerate() {
+            let abs_line = gutter_line.buffer_line;
             let y = rect.y + vi as f32 * cell_size.height;
 
             if y + cell_size.height > rect.y + rect.height {
@@ -98,7 +98,7 @@ impl EditorPane {
             }
 
             // Draw line number in gutter
-            let line_num = format!("{:>4}  ", abs_line + 1);
+            let line_num = format!("{:>4}  ", gutter_line.display);
             let gutter_color = if abs_line == cursor_line {
                 gutter_active_text
             } else {
@@ -212,6 +212,19 @@ impl EditorPane {
                 }
             }
         }
+
+        // Rulers: full-height lines at the language's ruler columns
+        let rows_drawn = highlighted.len().min(visible_rows) as f32;
+        for &ruler in self.editor.rulers() {
+            let Some(col) = ruler.checked_sub(h_scroll) else { continue };
+            let ruler_x = content_x + col as f32 * cell_size.width;
+            if ruler_x < content_x + content_width {
+                renderer.draw_grid_rect(
+                    Rect::new(ruler_x, rect.y, 1.0, rows_drawn * cell_size.height),
+                    indent_guide,
+                );
+            }
+        }
     }
 
     /// Render the diff view grid.
@@ -418,41 +431,28 @@ impl EditorPane {
         self.preview_line_count() > visible_rows
     }
 
-    /// Render the editor cursor into the overlay layer (always redrawn).
-    /// `preedit_width_cells` shifts the cursor rightward during IME composition.
-    pub fn render_cursor(&self, rect: Rect, renderer: &mut WgpuRenderer, cursor_color: Color, preedit_width_cells: usize) {
-        let cell_size = renderer.cell_size();
+    /// The cursor's visual row and display column (cells from the start of
+    /// the text, after the gutter), or `None` when it is scrolled out of view.
+    fn cursor_cell(&self) -> Option<(usize, usize)> {
         let pos = self.editor.cursor_position();
         let scroll = self.editor.scroll_offset();
         let h_scroll = self.editor.h_scroll_offset();
 
         // In diff mode, map buffer cursor line to virtual diff line
         let visual_row = if self.diff_mode {
-            if let Some(ref disk_content) = self.disk_content {
-                use crate::diff::{compute_diff, DiffOp};
-                let diff_ops = compute_diff(disk_content, &self.editor.buffer.lines);
-                let mut vline = None;
-                for (vi, op) in diff_ops.iter().enumerate() {
-                    match op {
-                        DiffOp::Equal(buf_idx) | DiffOp::Insert(buf_idx) => {
-                            if *buf_idx == pos.line {
-                                vline = Some(vi);
-                                break;
-                            }
-                        }
-                        DiffOp::Delete(_) => {}
-                    }
-                }
-                match vline {
-                    Some(vl) if vl >= scroll => vl - scroll,
-                    _ => return,
-                }
-            } else {
-                return;
+            let disk_content = self.disk_content.as_ref()?;
+            use crate::diff::{compute_diff, DiffOp};
+            let diff_ops = compute_diff(disk_content, &self.editor.buffer.lines);
+            let vline = diff_ops.iter().position(|op| {
+                matches!(op, DiffOp::Equal(buf_idx) | DiffOp::Insert(buf_idx) if *buf_idx == pos.line)
+            });
+            match vline {
+                Some(vl) if vl >= scroll => vl - scroll,
+                _ => return None,
             }
         } else {
             if pos.line < scroll {
-                return;
+                return None;
             }
             pos.line - scroll
         };
@@ -465,10 +465,10 @@ impl EditorPane {
             0
         };
         if cursor_char_col < h_scroll {
-            return;
+            return None;
         }
         // Compute visual column accounting for wide characters
-        let visual_col_offset = if let Some(line_text) = self.editor.buffer.line(pos.line) {
+        let visual_col = if let Some(line_text) = self.editor.buffer.line(pos.line) {
             line_text.chars()
                 .skip(h_scroll)
                 .take(cursor_char_col - h_scroll)
@@ -477,6 +477,32 @@ impl EditorPane {
         } else {
             cursor_char_col - h_scroll
         };
+        Some((visual_row, visual_col))
+    }
+
+    /// Cell-sized rect of the cursor within `rect` (the pane's content area),
+    /// where the IME candidate window should appear. `None` in preview mode or
+    /// when the cursor is out of view.
+    pub fn ime_cursor_rect(&self, rect: Rect, cell_size: Size) -> Option<Rect> {
+        if self.preview_mode {
+            return None;
+        }
+        let (row, col) = self.cursor_cell()?;
+        let cx = rect.x + (GUTTER_WIDTH_CELLS + col) as f32 * cell_size.width;
+        let cy = rect.y + row as f32 * cell_size.height;
+        if cy + cell_size.height > rect.y + rect.height || cx > rect.x + rect.width {
+            return None;
+        }
+        Some(Rect::new(cx, cy, cell_size.width, cell_size.height))
+    }
+
+    /// Render the editor cursor into the overlay layer (always redrawn).
+    /// `preedit_width_cells` shifts the cursor rightward during IME composition.
+    pub fn render_cursor(&self, rect: Rect, renderer: &mut WgpuRenderer, cursor_color: Color, preedit_width_cells: usize) {
+        let cell_size = renderer.cell_size();
+        let Some((visual_row, visual_col_offset)) = self.cursor_cell() else {
+            return;
+        };
         let visual_col = GUTTER_WIDTH_CELLS + visual_col_offset + preedit_width_cells;
 
         let cx = rect.x + visual_col as f32 * cell_size.width;
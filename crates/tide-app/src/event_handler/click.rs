@@ -9,22 +9,30 @@ use crate::{App, GitSwitcherMode, GitSwitcherState, shell_escape};
 
 impl App {
     /// Convert a pixel position to a terminal cell (row, col) within a pane's content area.
-    /// Returns None if the position is outside any terminal pane's content area.
+    /// Returns None if the position is above or left of the pane's content area.
     pub(crate) fn pixel_to_cell(&self, pos: Vec2, pane_id: tide_core::PaneId) -> Option<(usize, usize)> {
-        let (_, visual_rect) = self.visual_pane_rects.iter().find(|(id, _)| *id == pane_id)?;
-        let cell_size = self.cell_size();
-        let content_top = TAB_BAR_HEIGHT;
-        let inner_x = visual_rect.x + PANE_PADDING;
-        let inner_y = visual_rect.y + content_top;
-        let col = ((pos.x - inner_x) / cell_size.width).floor() as isize;
-        let row = ((pos.y - inner_y) / cell_size.height).floor() as isize;
-        if row >= 0 && col >= 0 {
-            Some((row as usize, col as usize))
+        let (row, col) = self.pixel_to_cell_unclamped(pos, pane_id)?;
+        if row >= 0 {
+            Some((row as usize, col))
         } else {
             None
         }
     }
 
+    /// Like `pixel_to_cell`, but rows above/below the content area are kept
+    /// (negative or past the last row) so drag-selection can auto-scroll.
+    pub(crate) fn pixel_to_cell_unclamped(&self, pos: Vec2, pane_id: tide_core::PaneId) -> Option<(isize, usize)> {
+        let (_, visual_rect) = self.visual_pane_rects.iter().find(|(id, _)| *id == pane_id)?;
+        let content_top = TAB_BAR_HEIGHT;
+        let inner = Rect::new(
+            visual_rect.x + PANE_PADDING,
+            visual_rect.y + content_top,
+            visual_rect.width - 2.0 * PANE_PADDING,
+            visual_rect.height - content_top - PANE_PADDING,
+        );
+        Some(crate::pane::pixel_to_grid_cell(pos, inner, self.cell_size()))
+    }
+
     /// Compute the hover target for a given cursor position.
     /// Priority: TopHandles → SplitBorder → PaneTabBar → FileTreeBorder → FileTreeEntry → None
     pub(crate) fn compute_hover_target(&self, pos: Vec2) -> Option<HoverTarget> {
@@ -1099,6 +1107,7 @@ impl App {
                 self.layout.insert_at_root(source, zone);
                 self.focused = Some(source);
                 self.cache.invalidate_chrome();
+                self.schedule_session_save();
                 self.compute_layout();
             }
             DropDestination::TreePane(target_id, DropZone::Center) => {
@@ -1113,6 +1122,7 @@ impl App {
                 self.layout.set_active_tab(source);
                 self.focused = Some(source);
                 self.cache.invalidate_chrome();
+                self.schedule_session_save();
                 self.compute_layout();
             }
             DropDestination::TreePane(target_id, zone) => {
@@ -1131,6 +1141,7 @@ impl App {
                 self.layout.insert_pane(target_id, source, direction, insert_first);
                 self.focused = Some(source);
                 self.cache.invalidate_chrome();
+                self.schedule_session_save();
                 self.compute_layout();
             }
             DropDestination::Workspace(target_idx) => {
@@ -11,6 +11,32 @@ use crate::ui_state::FocusArea;
 use crate::App;
 
 impl App {
+    /// Entry point for a platform `KeyDown`. A key that belongs to an active
+    /// composition (dead key, IME preedit) is left to the IME, whose
+    /// `ImeCommit` carries the text; only Cmd shortcuts still run.
+    pub(crate) fn handle_platform_key_down(
+        &mut self,
+        key: Key,
+        modifiers: Modifiers,
+        chars: Option<String>,
+        composing: bool,
+    ) {
+        // A held navigation chord repeats on `key_repeat`'s cadence; the OS's
+        // own repeats of it are dropped. Any other key stops it, as with OS
+        // repeat.
+        if self.key_repeat.held_key() == Some((key, modifiers)) {
+            return;
+        }
+        self.key_repeat.cancel();
+        if composing {
+            if modifiers.meta {
+                self.handle_key_down(key, modifiers, None);
+            }
+            return;
+        }
+        self.handle_key_down(key, modifiers, chars);
+    }
+
     pub(crate) fn handle_key_down(
         &mut self,
         key: Key,
@@ -132,9 +158,10 @@ impl App {
                     self.handle_file_tree_nav_key(key, &modifiers);
                     return;
                 }
-                if modifiers.meta || (modifiers.ctrl && modifiers.shift) {
+                if tide_input::is_hotkey_candidate(&key, &modifiers) {
                     let input = InputEvent::KeyPress { key, modifiers };
                     let action = self.router.process(input, &self.pane_rects);
+                    self.hold_for_repeat(&action, key, modifiers);
                     if !matches!(action, tide_input::Action::RouteToPane(_)) {
                         self.handle_action(action, Some(input));
                     }
@@ -150,7 +177,7 @@ impl App {
                     if let Some(PaneKind::Browser(bp)) = self.panes.get(&focused_id) {
                         if bp.url_input_focused {
                             // Global hotkeys take priority over URL bar input
-                            if modifiers.meta || (modifiers.ctrl && modifiers.shift) {
+                            if tide_input::is_hotkey_candidate(&key, &modifiers) {
                                 let input = InputEvent::KeyPress { key, modifiers };
                                 let action = self.router.process(input, &self.pane_rects);
                                 if !matches!(action, tide_input::Action::RouteToPane(_)) {
@@ -186,11 +213,39 @@ impl App {
         }
 
         let input = InputEvent::KeyPress { key, modifiers };
+        self.router.set_has_selection(self.focused_has_selection());
         let action = self.router.process(input, &self.pane_rects);
+        self.hold_for_repeat(&action, key, modifiers);
+        self.key_chars = chars;
         self.handle_action(action, Some(input));
+        self.key_chars = None;
         self.cache.needs_redraw = true;
     }
 
+    /// Start the software repeat for a chord that moves focus between panes.
+    fn hold_for_repeat(&mut self, action: &tide_input::Action, key: Key, modifiers: Modifiers) {
+        if matches!(action, tide_input::Action::GlobalAction(tide_input::GlobalAction::Navigate(_))) {
+            self.key_repeat.on_key_down(key, modifiers);
+        }
+    }
+
+    /// Press the held chord again if its software repeat is due at `now`.
+    pub(crate) fn apply_key_repeat(&mut self, now: std::time::Instant) {
+        if let Some((key, modifiers)) = self.key_repeat.tick(now) {
+            self.handle_key_down(key, modifiers, None);
+        }
+    }
+
+    /// The focused pane has a non-empty text selection (for Ctrl+C copy).
+    fn focused_has_selection(&self) -> bool {
+        let selection = match self.focused.and_then(|id| self.panes.get(&id)) {
+            Some(PaneKind::Terminal(pane)) => pane.selection.as_ref(),
+            Some(PaneKind::Editor(pane)) => pane.selection.as_ref(),
+            _ => None,
+        };
+        selection.is_some_and(|sel| sel.anchor != sel.end)
+    }
+
     fn handle_git_switcher_key(&mut self, key: Key, modifiers: &Modifiers) {
         // Cmd+Backspace → delete selected item
         if matches!(key, Key::Backspace) && modifiers.meta && !modifiers.ctrl && !modifiers.alt {
@@ -545,7 +600,7 @@ impl App {
             Key::Enter => {
                 if let Some(tree) = &self.ft.tree {
                     let entries = tree.visible_entries();
-                    if let Some(entry) = entries.get(self.ft.cursor) {
+                    if let Some(entry) = entries.get(self.ft.cursor).filter(|e| !e.is_placeholder) {
                         if entry.entry.is_dir {
                             let path = entry.entry.path.clone();
                             if let Some(tree) = &mut self.ft.tree {
@@ -4,7 +4,7 @@ use tide_core::{FileTreeSource, InputEvent, LayoutEngine, MouseButton, Rect, Vec
 use tide_platform::WindowProxy;
 
 use crate::drag_drop::PaneDragState;
-use crate::pane::{PaneKind, Selection};
+use crate::pane::{is_double_click, PaneKind, Selection};
 use crate::theme::*;
 use crate::ui_state::FocusArea;
 use crate::App;
@@ -64,11 +64,17 @@ impl App {
                     let cell_size_cached = self.cell_size();
                     match self.panes.get_mut(&pid) {
                         Some(PaneKind::Terminal(pane)) => {
-                            if let Some(cell) = term_cell {
-                                pane.selection = Some(Selection {
-                                    anchor: cell,
-                                    end: cell,
-                                });
+                            if let Some((row, col)) = term_cell {
+                                let now = std::time::Instant::now();
+                                if is_double_click(self.interaction.last_click, now, pid, (row, col)) {
+                                    pane.select_word_at(row, col);
+                                    self.interaction.last_click = None;
+                                    self.copy_terminal_selection(pid);
+                                } else {
+                                    pane.begin_selection(row, col);
+                                    self.interaction.selection_drag = Some(pid);
+                                    self.interaction.last_click = Some((now, pid, (row, col)));
+                                }
                             }
                         }
                         Some(PaneKind::Browser(_)) => {}
@@ -93,6 +99,12 @@ impl App {
                             } else if let Some((rr, rc)) = editor_cell {
                                 let line = pane.editor.scroll_offset() + rr;
                                 let col = pane.editor.h_scroll_offset() + rc;
+                                // Alt+drag selects a column block
+                                pane.editor.set_selection_mode(if mods.alt {
+                                    tide_editor::SelectionMode::Block
+                                } else {
+                                    tide_editor::SelectionMode::Linear
+                                });
                                 pane.selection = Some(Selection {
                                     anchor: (line, col),
                                     end: (line, col),
@@ -233,7 +245,7 @@ impl App {
 
                             if let Some(tree) = self.ft.tree.as_ref() {
                                 let entries = tree.visible_entries();
-                                if index < entries.len() {
+                                if index < entries.len() && !entries[index].is_placeholder {
                                     let entry = &entries[index];
                                     self.modal.context_menu = None;
                                     self.modal.file_tree_rename = None;
@@ -320,6 +332,7 @@ impl App {
                             crate::LayoutSide::Left => crate::LayoutSide::Right,
                             crate::LayoutSide::Right => crate::LayoutSide::Left,
                         };
+                        self.schedule_session_save();
                         self.compute_layout();
                         self.cache.invalidate_chrome();
                         return;
@@ -422,6 +435,7 @@ impl App {
         let input = InputEvent::MouseClick {
             position: self.last_cursor_pos,
             button,
+            modifiers: self.modifiers,
         };
         let action = self.router.process(input, &self.pane_rects);
         self.handle_action(action, Some(input));
@@ -430,6 +444,7 @@ impl App {
     pub(crate) fn handle_mouse_up(&mut self, button: MouseButton) {
         if button == MouseButton::Left {
             self.interaction.mouse_left_pressed = false;
+            self.finish_terminal_selection();
         }
 
         // End workspace sidebar drag
@@ -467,6 +482,7 @@ impl App {
         // End sidebar handle drag on release
         if self.sidebar_handle_dragging {
             self.sidebar_handle_dragging = false;
+            self.schedule_session_save();
             self.compute_layout();
             self.cache.invalidate_chrome();
             return;
@@ -474,6 +490,7 @@ impl App {
 
         if self.ft.border_dragging {
             self.ft.border_dragging = false;
+            self.schedule_session_save();
             self.compute_layout();
             self.cache.invalidate_chrome();
             return;
@@ -481,6 +498,7 @@ impl App {
 
         if self.ws.border_dragging {
             self.ws.border_dragging = false;
+            self.schedule_session_save();
             self.compute_layout();
             self.cache.invalidate_chrome();
             return;
@@ -518,6 +536,7 @@ impl App {
         self.layout.end_drag();
         self.router.end_drag();
         if was_dragging {
+            self.schedule_session_save();
             self.compute_layout();
         }
     }
@@ -575,15 +594,17 @@ impl App {
         // Handle border resizes
         if self.ft.border_dragging {
             let logical = self.logical_size();
-            let max_w = (logical.width - 100.0).max(120.0);
+            let ws_width = if self.ws.show_sidebar { self.ws.width } else { 0.0 };
+            // Inverse of the border position in the hover hit-test, so the
+            // grabbed border follows the cursor without jumping.
             let new_width = match self.sidebar_side {
                 crate::LayoutSide::Left => {
                     let ft_x = self.ft.rect.map(|r| r.x).unwrap_or(0.0);
-                    (pos.x - ft_x).max(120.0).min(max_w)
+                    pos.x - PANE_GAP - ft_x
                 }
-                crate::LayoutSide::Right => (logical.width - pos.x).max(120.0).min(max_w),
+                crate::LayoutSide::Right => logical.width - pos.x - 2.0 * PANE_GAP,
             };
-            self.ft.width = new_width;
+            self.ft.width = crate::layout_compute::clamp_file_tree_width(new_width, logical.width, ws_width);
             self.compute_layout();
             self.cache.invalidate_chrome();
             return;
@@ -661,6 +682,11 @@ impl App {
         } else {
             // Text selection drag
             if self.interaction.mouse_left_pressed {
+                // Terminal selection follows the pane it started in, even when
+                // the pointer leaves it (auto-scrolls past the top/bottom edge).
+                if let Some(pid) = self.interaction.selection_drag {
+                    self.drag_terminal_selection(pid, pos);
+                }
                 let cell_size = Some(self.cell_size());
                 let drag_top_offset = TAB_BAR_HEIGHT;
 
@@ -679,7 +705,6 @@ impl App {
                     if !content.contains(pos) {
                         continue;
                     }
-                    let cell = self.pixel_to_cell(pos, pid);
                     let editor_cell = if let Some(cs) = cell_size {
                         let gutter_width = crate::editor_pane::GUTTER_WIDTH_CELLS as f32 * cs.width;
                         let content_x = rect.x + PANE_PADDING + gutter_width;
@@ -696,11 +721,7 @@ impl App {
                     };
 
                     match self.panes.get_mut(&pid) {
-                        Some(PaneKind::Terminal(pane)) => {
-                            if let (Some(ref mut sel), Some(c)) = (&mut pane.selection, cell) {
-                                sel.end = c;
-                            }
-                        }
+                        Some(PaneKind::Terminal(_)) => {}
                         Some(PaneKind::Browser(_)) => {}
                         Some(PaneKind::Editor(pane)) => {
                             if pane.preview_mode {
@@ -733,8 +754,12 @@ impl App {
                 self.cache.needs_redraw = true;
             }
 
-            // Hover target
-            let new_hover = self.compute_hover_target(pos);
+            // Hover target; over plain pane content, a terminal URL under the mouse
+            let mut new_hover = self.compute_hover_target(pos);
+            let link_pane = self.update_link_hover(new_hover.is_none().then_some(pos));
+            if let Some(id) = link_pane {
+                new_hover = Some(crate::drag_drop::HoverTarget::TerminalLink(id));
+            }
             if new_hover != self.interaction.hover_target {
                 // Bump chrome_generation only when entering/leaving chrome-rendered hover targets
                 let chrome_affected =
@@ -758,6 +783,111 @@ impl App {
 
             let input = InputEvent::MouseMove { position: pos };
             let _ = self.router.process(input, &self.pane_rects);
+            if self.router.hovered_changed() {
+                self.schedule_focus_follow(std::time::Instant::now());
+            }
+        }
+    }
+
+    /// Underline the terminal URL under `pos` (`None` clears every pane's).
+    /// Returns the pane whose link is now hovered.
+    pub(crate) fn update_link_hover(&mut self, pos: Option<Vec2>) -> Option<tide_core::PaneId> {
+        let cell = pos.and_then(|pos| {
+            let &(id, _) = self.visual_pane_rects.iter().find(|(_, r)| r.contains(pos))?;
+            Some((id, self.terminal_cell_at(id, pos)?))
+        });
+        let mut changed = false;
+        let mut hovered = None;
+        for (&id, pane) in self.panes.iter_mut() {
+            if let PaneKind::Terminal(tp) = pane {
+                let here = cell.filter(|&(cell_pane, _)| cell_pane == id).map(|(_, c)| c);
+                changed |= tp.backend.set_hovered_cell(here);
+                if tp.backend.hovered_link().is_some() {
+                    hovered = Some(id);
+                }
+            }
+        }
+        if changed {
+            self.cache.needs_redraw = true;
+        }
+        hovered
+    }
+
+    /// Focus-follows-mouse: arm a dwell timer for the newly hovered pane.
+    /// Moving to another pane (or off all panes) before it fires re-arms or cancels it.
+    pub(crate) fn schedule_focus_follow(&mut self, now: std::time::Instant) {
+        if !self.settings.focus_follows_mouse {
+            return;
+        }
+        self.interaction.focus_follow_at = self
+            .router
+            .hovered()
+            .filter(|&id| self.focused != Some(id))
+            .map(|id| (id, now + FOCUS_FOLLOWS_MOUSE_DWELL));
+    }
+
+    /// Focus the pending hovered pane once its dwell has elapsed. A border,
+    /// pane or selection drag in progress cancels the switch.
+    pub(crate) fn apply_focus_follow(&mut self, now: std::time::Instant) {
+        let Some((id, at)) = self.interaction.focus_follow_at else { return };
+        if now < at {
+            return;
+        }
+        self.interaction.focus_follow_at = None;
+        let dragging = self.router.is_dragging_border()
+            || self.interaction.mouse_left_pressed
+            || !matches!(self.interaction.pane_drag, PaneDragState::Idle);
+        if dragging || self.router.hovered() != Some(id) || !self.panes.contains_key(&id) {
+            return;
+        }
+        self.focus_terminal(id);
+        self.cache.needs_redraw = true;
+    }
+
+    /// Extend a terminal drag-selection to the pointer position. Schedules the
+    /// next auto-scroll tick while the pointer stays past the pane's top/bottom edge.
+    pub(crate) fn drag_terminal_selection(&mut self, pane_id: tide_core::PaneId, pos: Vec2) {
+        let cell = match self.pixel_to_cell_unclamped(pos, pane_id) {
+            Some(c) => c,
+            None => return,
+        };
+        let scrolled = match self.panes.get_mut(&pane_id) {
+            Some(PaneKind::Terminal(pane)) => pane.drag_selection_to(cell.0, cell.1),
+            _ => return,
+        };
+        self.interaction.selection_autoscroll_at = if scrolled {
+            Some(std::time::Instant::now() + SELECTION_AUTOSCROLL_INTERVAL)
+        } else {
+            None
+        };
+        self.cache.needs_redraw = true;
+    }
+
+    /// End a terminal drag-selection. With `copy_on_select` enabled, a
+    /// non-empty selection is copied to the clipboard (X11 primary-style).
+    fn finish_terminal_selection(&mut self) {
+        self.interaction.selection_autoscroll_at = None;
+        if let Some(pane_id) = self.interaction.selection_drag.take() {
+            self.copy_terminal_selection(pane_id);
+        }
+    }
+
+    /// Copy a terminal pane's selection to the clipboard if `copy_on_select`
+    /// is enabled and the selection is non-empty.
+    fn copy_terminal_selection(&mut self, pane_id: tide_core::PaneId) {
+        if !self.settings.copy_on_select {
+            return;
+        }
+        if let Some(PaneKind::Terminal(pane)) = self.panes.get(&pane_id) {
+            if let Some(ref sel) = pane.selection {
+                if sel.anchor == sel.end {
+                    return;
+                }
+                let text = pane.selected_text(sel);
+                if !text.is_empty() {
+                    crate::clipboard::system().write(&text);
+                }
+            }
         }
     }
 
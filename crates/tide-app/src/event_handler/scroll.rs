@@ -1,15 +1,37 @@
 use tide_core::InputEvent;
+use tide_input::{normalize_scroll, ScrollDeltaKind};
 
 use crate::pane::PaneKind;
 use crate::theme::*;
 use crate::App;
 
 impl App {
+    /// Convert raw platform scroll deltas (wheel notches, or pixels when
+    /// `precise`) to lines, applying wheel acceleration if enabled.
+    pub(crate) fn scroll_lines(&mut self, dx: f32, dy: f32, precise: bool) -> (f32, f32) {
+        let kind = if precise { ScrollDeltaKind::Pixels } else { ScrollDeltaKind::Lines };
+        let cell_height = self.cell_size().height;
+        let dx = normalize_scroll(kind, dx, cell_height);
+        let mut dy = normalize_scroll(kind, dy, cell_height);
+        if self.settings.scroll_acceleration {
+            dy = self.interaction.scroll_acceleration.apply(kind, dy, std::time::Instant::now());
+        }
+        (dx, dy)
+    }
+
+    /// Handle a trackpad pinch: the router turns accumulated magnification
+    /// into FontSizeUp/Down steps. `ended` closes the gesture.
+    pub(crate) fn handle_pinch(&mut self, scale_delta: f32, ended: bool) {
+        let input = InputEvent::Pinch { scale_delta, position: self.last_cursor_pos, ended };
+        let action = self.router.process(input, &self.pane_rects);
+        self.handle_action(action, Some(input));
+    }
+
     /// Handle scroll event with pre-processed delta values.
-    /// dx/dy are in "line" units (platform normalizes pixel/line deltas).
+    /// dx/dy are in "line" units (see `scroll_lines`).
     pub(crate) fn handle_scroll(&mut self, dx: f32, dy: f32) {
         // Mark scroll activity so frame pacing skips coalescing
-        self.scroll_at = Some(std::time::Instant::now());
+        self.redraw.on_scroll(std::time::Instant::now());
         // Popup scroll: config page
         if let Some(ref mut cp) = self.modal.config_page {
             if matches!(cp.section, crate::ui_state::ConfigSection::Keybindings) {
@@ -66,6 +88,7 @@ impl App {
             let input = InputEvent::MouseScroll {
                 delta: editor_dy,
                 position: self.last_cursor_pos,
+                modifiers: self.modifiers,
             };
             let action = self.router.process(input, &self.pane_rects);
             self.handle_action(action, Some(input));
@@ -4,10 +4,6 @@
 //! future clipboard paste, etc.) calls `send_text_to_target()` which
 //! uses `text_input_target()` to determine the single correct destination.
 
-use std::time::Instant;
-
-use tide_core::TerminalBackend;
-
 use crate::pane::PaneKind;
 use crate::ui_state::FocusArea;
 use crate::App;
@@ -104,7 +100,7 @@ impl App {
     }
 
     /// Route a text string to the current input target.
-    /// Handles all side effects (chrome_generation, input_sent_at, scroll-to-bottom, etc.).
+    /// Handles all side effects (chrome_generation, input latency, scroll-to-bottom, etc.).
     pub(crate) fn send_text_to_target(&mut self, text: &str) {
         let target = self.text_input_target();
         match target {
@@ -190,18 +186,12 @@ impl App {
                             if pane.backend.display_offset() > 0 {
                                 pane.backend.request_scroll_to_bottom();
                             }
-                            pane.backend.write(text.as_bytes());
-                            self.input_just_sent = true;
-                            self.input_sent_at = Some(Instant::now());
+                            pane.backend.write_keystroke(text.as_bytes());
+                            self.redraw.on_input();
                         }
                     }
                     Some(PaneKind::Editor(pane)) => {
                         let was_modified = pane.editor.is_modified();
-                        // Delete selection on editing input (mirrors keybinding path)
-                        if text.chars().any(|ch| !ch.is_control() || ch == '\r' || ch == '\n' || ch == '\u{7f}' || ch == '\u{8}') {
-                            pane.delete_selection();
-                            pane.selection = None;
-                        }
                         for ch in text.chars() {
                             // Map control characters to editor actions
                             let action = match ch {
@@ -210,7 +200,13 @@ impl App {
                                 ch if ch.is_control() => continue,
                                 ch => tide_editor::EditorActionKind::InsertChar(ch),
                             };
-                            pane.editor.handle_action(action);
+                            // Block selections take typing on every row; an opening
+                            // bracket/quote wraps the selection; otherwise the selection
+                            // is replaced (mirrors keybinding path)
+                            if !pane.edit_block(&action) && !pane.surround_selection(&action) {
+                                pane.delete_selection();
+                                pane.editor.handle_action(action);
+                            }
                         }
                         // Ensure cursor stays visible after editing (matches keybinding path)
                         let (visible_rows, visible_cols) = editor_size;
@@ -237,7 +233,7 @@ impl App {
 mod tests {
     use super::*;
     use crate::ui_state::*;
-    use tide_core::Rect;
+    use tide_core::{PaneId, Rect};
     use std::path::PathBuf;
 
     fn test_app() -> App {
@@ -256,7 +252,7 @@ mod tests {
     #[test]
     fn focused_editor_routes_to_pane() {
         let mut app = test_app();
-        let id: tide_core::PaneId = 1;
+        let id: tide_core::PaneId = PaneId::new(1);
         app.panes.insert(id, PaneKind::Editor(crate::editor_pane::EditorPane::new_empty(id)));
         app.focused = Some(id);
         assert_eq!(app.text_input_target(), TextInputTarget::Pane(id));
@@ -265,7 +261,7 @@ mod tests {
     #[test]
     fn file_finder_overrides_pane() {
         let mut app = test_app();
-        let id: tide_core::PaneId = 1;
+        let id: tide_core::PaneId = PaneId::new(1);
         app.panes.insert(id, PaneKind::Editor(crate::editor_pane::EditorPane::new_empty(id)));
         app.focused = Some(id);
         app.modal.file_finder = Some(FileFinderState::new(PathBuf::from("/tmp"), vec![]));
@@ -275,7 +271,7 @@ mod tests {
     #[test]
     fn git_switcher_overrides_pane() {
         let mut app = test_app();
-        let id: tide_core::PaneId = 1;
+        let id: tide_core::PaneId = PaneId::new(1);
         app.panes.insert(id, PaneKind::Editor(crate::editor_pane::EditorPane::new_empty(id)));
         app.focused = Some(id);
         app.modal.git_switcher = Some(GitSwitcherState::new(
@@ -325,7 +321,7 @@ mod tests {
     #[test]
     fn search_bar_routes_to_search() {
         let mut app = test_app();
-        let id: tide_core::PaneId = 1;
+        let id: tide_core::PaneId = PaneId::new(1);
         app.panes.insert(id, PaneKind::Editor(crate::editor_pane::EditorPane::new_empty(id)));
         app.focused = Some(id);
         app.search_focus = Some(id);
@@ -335,7 +331,7 @@ mod tests {
     #[test]
     fn file_tree_focus_consumed() {
         let mut app = test_app();
-        let id: tide_core::PaneId = 1;
+        let id: tide_core::PaneId = PaneId::new(1);
         app.panes.insert(id, PaneKind::Editor(crate::editor_pane::EditorPane::new_empty(id)));
         app.focused = Some(id);
         app.focus_area = FocusArea::FileTree;
@@ -345,7 +341,7 @@ mod tests {
     #[test]
     fn save_as_input_routes() {
         let mut app = test_app();
-        app.modal.save_as_input = Some(SaveAsInput::new(1, PathBuf::from("/tmp"), Rect::new(0.0, 0.0, 100.0, 30.0)));
+        app.modal.save_as_input = Some(SaveAsInput::new(PaneId::new(1), PathBuf::from("/tmp"), Rect::new(0.0, 0.0, 100.0, 30.0)));
         assert_eq!(app.text_input_target(), TextInputTarget::SaveAsInput);
     }
 
@@ -363,7 +359,7 @@ mod tests {
     #[test]
     fn context_menu_consumed() {
         let mut app = test_app();
-        let id: tide_core::PaneId = 1;
+        let id: tide_core::PaneId = PaneId::new(1);
         app.panes.insert(id, PaneKind::Editor(crate::editor_pane::EditorPane::new_empty(id)));
         app.focused = Some(id);
         app.modal.context_menu = Some(ContextMenuState {
@@ -380,7 +376,7 @@ mod tests {
     #[test]
     fn priority_git_switcher_over_search_bar() {
         let mut app = test_app();
-        let id: tide_core::PaneId = 1;
+        let id: tide_core::PaneId = PaneId::new(1);
         app.panes.insert(id, PaneKind::Editor(crate::editor_pane::EditorPane::new_empty(id)));
         app.focused = Some(id);
         app.search_focus = Some(id);
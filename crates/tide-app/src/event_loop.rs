@@ -2,7 +2,7 @@
 
 use std::time::{Duration, Instant};
 
-use tide_core::TerminalBackend;
+use tide_core::{frame_budget, ControlFlow, Rect};
 use tide_platform::{PlatformEvent, PlatformWindow, WindowProxy};
 
 use crate::pane::PaneKind;
@@ -37,8 +37,15 @@ impl App {
         // become stale if the shell process is killed mid-rehash (e.g. on app quit).
         cleanup_stale_shell_locks();
 
-        if is_crash {
-            // In crash recovery, skip pre-spawning a shell: restore_from_session
+        // Restore the full session after a crash, or on every launch when
+        // `restore_session` is enabled; otherwise only preferences carry over.
+        // Launch options ask for a specific first pane, so they skip pane restore.
+        let restore_panes = saved_session.is_some()
+            && self.launch.is_empty()
+            && (is_crash || self.settings.restore_session);
+
+        if restore_panes {
+            // When restoring, skip pre-spawning a shell: restore_from_session
             // will create its own terminals, and the pre-spawned shell would just
             // be killed mid-init (potentially leaving pyenv-rehash locks).
             self.init_gpu(window);
@@ -55,7 +62,7 @@ impl App {
             // The shell starts loading ~/.zshrc in parallel with GPU initialization,
             // so the prompt appears sooner after launch.
             let early_terminal =
-                tide_terminal::Terminal::with_cwd(80, 24, None, self.dark_mode).ok();
+                tide_terminal::Terminal::with_options(80, 24, self.terminal_options(self.launch_cwd())).ok();
 
             self.init_gpu(window); // Shell is loading in parallel
 
@@ -79,6 +86,7 @@ impl App {
         window: WindowProxy,
     ) {
         loop {
+            self.redraw.set_budget(frame_budget(self.settings.max_fps));
             let timeout = self.next_timeout();
 
             // Block until an event arrives or a timer fires
@@ -106,54 +114,43 @@ impl App {
                 self.cache.needs_redraw = true;
             }
 
-            // Render if needed
-            if self.cache.needs_redraw && !self.is_occluded && self.batch_depth == 0 {
-                let now = Instant::now();
-                let skip_coalesce = self.input_just_sent
-                    || self.input_sent_at.map_or(false, |at| {
-                        now.duration_since(at) < Duration::from_millis(16)
-                    })
-                    || self.scroll_at.map_or(false, |at| {
-                        now.duration_since(at) < Duration::from_millis(32)
-                    });
-                if skip_coalesce
-                    || now.duration_since(self.last_frame) >= Duration::from_millis(2)
-                {
-                    self.update();
-                    if self.render() {
-                        self.cache.needs_redraw = false;
-                        self.last_frame = now;
-
-                        // Reveal window after first frame
-                        if !self.window_shown {
-                            window.show_window();
-                            // Re-establish first responder: macOS may reset
-                            // it during window lifecycle initialization
-                            // (delegate is set after makeKeyAndOrderFront,
-                            // so the initial Focused event is missed).
-                            if let Some(target) = self.effective_ime_target() {
-                                window.focus_ime_proxy(target);
-                            }
-                            self.window_shown = true;
+            // Render if needed (at most once per frame budget, see RedrawScheduler)
+            let now = Instant::now();
+            if self.batch_depth == 0 && self.redraw.should_render(now, self.frame_dirty()) {
+                self.update();
+                if self.render() {
+                    self.cache.needs_redraw = false;
+                    self.redraw.on_frame_rendered(now);
+
+                    // Reveal window after first frame
+                    if !self.window_shown {
+                        window.show_window();
+                        // Re-establish first responder: macOS may reset
+                        // it during window lifecycle initialization
+                        // (delegate is set after makeKeyAndOrderFront,
+                        // so the initial Focused event is missed).
+                        if let Some(target) = self.effective_ime_target() {
+                            window.focus_ime_proxy(target.as_u64());
                         }
+                        self.window_shown = true;
                     }
-                    // If render() returned false (render thread busy),
-                    // the render thread waker will wake us when it finishes.
                 }
+                // If render() returned false (render thread busy),
+                // the render thread waker will wake us when it finishes.
             }
         }
     }
 
     /// Compute the timeout for the next `recv_timeout` call.
-    fn next_timeout(&self) -> Duration {
+    fn next_timeout(&mut self) -> Duration {
         let now = Instant::now();
         let mut timeout = Duration::from_millis(100); // default max sleep
 
-        // Cursor blink: next toggle
+        // Cursor blink: wake for the next toggle
         if self.focused.is_some() {
             let blink_elapsed = now.duration_since(self.cursor_blink_at);
             let next_toggle_ms = 530 - (blink_elapsed.as_millis() % 530) as u64;
-            timeout = timeout.min(Duration::from_millis(next_toggle_ms));
+            self.redraw.wake_at(now + Duration::from_millis(next_toggle_ms));
         }
 
         // Deferred resize
@@ -174,29 +171,61 @@ impl App {
             }
         }
 
-        // Frame pacing: if we need to render but are within 2ms coalescing window
-        if self.cache.needs_redraw && !self.is_occluded && self.batch_depth == 0 {
-            let skip_coalesce = self.input_just_sent
-                || self.input_sent_at.map_or(false, |at| {
-                    now.duration_since(at) < Duration::from_millis(16)
-                })
-                || self.scroll_at.map_or(false, |at| {
-                    now.duration_since(at) < Duration::from_millis(32)
-                });
-            if skip_coalesce {
-                return Duration::ZERO; // render immediately
-            }
-            let since_last = now.duration_since(self.last_frame);
-            if since_last < Duration::from_millis(2) {
-                timeout = timeout.min(Duration::from_millis(2) - since_last);
+        // Session autosave
+        if let Some(at) = self.session_save_at {
+            if at > now {
+                timeout = timeout.min(at - now);
+            } else {
+                return Duration::ZERO;
+            }
+        }
+
+        // Selection auto-scroll
+        if let Some(at) = self.interaction.selection_autoscroll_at {
+            if at > now {
+                timeout = timeout.min(at - now);
+            } else {
+                return Duration::ZERO;
+            }
+        }
+
+        // Focus-follows-mouse dwell
+        if let Some((_, at)) = self.interaction.focus_follow_at {
+            if at > now {
+                timeout = timeout.min(at - now);
+            } else {
+                return Duration::ZERO;
+            }
+        }
+
+        // Held navigation chord repeat
+        if let Some(at) = self.key_repeat.next_deadline() {
+            if at > now {
+                timeout = timeout.min(at - now);
             } else {
-                return Duration::ZERO; // past coalescing window, render now
+                return Duration::ZERO;
+            }
+        }
+
+        // Frame pacing: wait out the remainder of the frame budget, or until
+        // the blink timer, whichever comes first
+        if self.batch_depth == 0 {
+            let dirty = self.frame_dirty();
+            match self.redraw.control_flow(now, dirty) {
+                ControlFlow::Poll => return Duration::ZERO,
+                ControlFlow::WaitUntil(at) => timeout = timeout.min(at.saturating_duration_since(now)),
+                ControlFlow::Wait => {}
             }
         }
 
         timeout
     }
 
+    /// Whether a frame is wanted: something changed and the window is visible.
+    fn frame_dirty(&self) -> bool {
+        self.cache.needs_redraw && !self.is_occluded
+    }
+
     // ── Event handler (runs on app thread) ───────────────────────────
 
     /// Process a single platform event.  Called from the app thread loop.
@@ -237,6 +266,11 @@ impl App {
             }
             PlatformEvent::ScaleFactorChanged(scale) => {
                 self.scale_factor = scale as f32;
+                // Re-rasterize glyphs and refresh cell metrics before layout
+                // resizes the terminals.
+                if let Some(renderer) = self.renderer.as_mut() {
+                    renderer.set_scale_factor(self.scale_factor);
+                }
                 self.reconfigure_surface();
                 self.compute_layout();
                 self.cache.invalidate_chrome();
@@ -246,6 +280,7 @@ impl App {
                 let new_shift = modifiers.shift;
                 let old_meta = self.modifiers.meta;
                 self.modifiers = modifiers;
+                self.key_repeat.on_modifiers_changed(modifiers);
 
                 // Meta key toggles link underlines — redraw immediately
                 if old_meta != modifiers.meta {
@@ -299,6 +334,8 @@ impl App {
                     self.sync_ime_proxies(window);
                     self.sync_browser_webview_frames();
                 } else {
+                    // Keys released while unfocused never send KeyUp here
+                    self.key_repeat.cancel();
                     // Cancel any in-progress drag when the window loses focus
                     if !matches!(self.interaction.pane_drag, crate::drag_drop::PaneDragState::Idle) {
                         self.interaction.pane_drag = crate::drag_drop::PaneDragState::Idle;
@@ -344,6 +381,12 @@ impl App {
                 self.focus_area = FocusArea::PaneArea;
                 self.cache.invalidate_chrome();
             }
+            PlatformEvent::MenuItemSelected(id) => {
+                if let Some(action) = crate::menu_bar::action_for_menu_id(id) {
+                    self.handle_global_action(action);
+                    self.cache.needs_redraw = true;
+                }
+            }
             PlatformEvent::ImeCommit(text) => {
                 self.shift_tap_clean = false;
                 self.handle_ime_commit(&text);
@@ -362,15 +405,16 @@ impl App {
                 key,
                 modifiers,
                 chars,
+                composing,
             } => {
                 // Invalidate Shift+Shift detection on any real key press
                 self.shift_tap_clean = false;
-                self.handle_key_down(key, modifiers, chars);
+                self.handle_platform_key_down(key, modifiers, chars, composing);
                 self.ime.cursor_dirty = true;
                 self.cursor_blink_at = Instant::now();
                 self.cursor_visible = true;
             }
-            PlatformEvent::KeyUp { .. } => {}
+            PlatformEvent::KeyUp { key, .. } => self.key_repeat.on_key_up(key),
             PlatformEvent::MouseDown { button, position } => {
                 let pos = self.physical_to_logical(position);
                 self.last_cursor_pos = pos;
@@ -397,12 +441,20 @@ impl App {
             PlatformEvent::Scroll {
                 dx,
                 dy,
+                precise,
                 position,
             } => {
                 let pos = self.physical_to_logical(position);
                 self.last_cursor_pos = pos;
+                let (dx, dy) = self.scroll_lines(dx, dy, precise);
                 self.handle_scroll(dx, dy);
             }
+            PlatformEvent::Pinch { scale_delta, position, ended } => {
+                self.last_cursor_pos = self.physical_to_logical(position);
+                self.handle_pinch(scale_delta, ended);
+            }
+            // No default binding for swipes yet.
+            PlatformEvent::Swipe { .. } => {}
         }
 
         // Process deferred fullscreen toggle
@@ -427,10 +479,10 @@ impl App {
         // idempotent contains_key check runs, otherwise the create is a
         // no-op and the subsequent remove deletes the only proxy.
         for id in self.ime.pending_removes.drain(..) {
-            window.remove_ime_proxy(id);
+            window.remove_ime_proxy(id.as_u64());
         }
         for id in self.ime.pending_creates.drain(..) {
-            window.create_ime_proxy(id);
+            window.create_ime_proxy(id.as_u64());
         }
 
         let target = self.effective_ime_target();
@@ -447,7 +499,7 @@ impl App {
             self.ime.last_target = target;
         }
         if let Some(target) = target {
-            window.focus_ime_proxy(target);
+            window.focus_ime_proxy(target.as_u64());
         } else {
             // No IME target (e.g. browser pane focused without URL bar):
             // clear LAST_IME_TARGET so windowDidBecomeKey won't restore a
@@ -465,18 +517,19 @@ impl App {
         let editor_size = self.visible_editor_size(pane_id);
         match self.panes.get_mut(&pane_id) {
             Some(PaneKind::Terminal(pane)) => {
-                pane.backend.write(text.as_bytes());
+                pane.backend.write_keystroke(text.as_bytes());
             }
             Some(PaneKind::Editor(pane)) => {
                 if !pane.preview_mode {
-                    pane.delete_selection();
-                    pane.selection = None;
                     for ch in text.chars() {
                         let action = match ch {
                             ch if ch.is_control() => continue,
                             ch => tide_editor::EditorActionKind::InsertChar(ch),
                         };
-                        pane.editor.handle_action(action);
+                        if !pane.edit_block(&action) && !pane.surround_selection(&action) {
+                            pane.delete_selection();
+                            pane.editor.handle_action(action);
+                        }
                     }
                     // Ensure cursor stays visible after editing
                     let (visible_rows, visible_cols) = editor_size;
@@ -523,11 +576,40 @@ impl App {
         if let Some(at) = self.resize_deferred_at {
             if Instant::now() >= at {
                 self.resize_deferred_at = None;
+                self.schedule_session_save();
                 self.compute_layout();
                 self.cache.needs_redraw = true;
+                // Once the resize settles, trim the dead strip (snap_to_cells)
+                if let Some((width, height)) = self.settle_cell_snap() {
+                    window.set_inner_size(width, height);
+                }
             }
         }
 
+        // Keep auto-scrolling while a drag-selection is held past a pane edge
+        if let (Some(at), Some(pid)) = (
+            self.interaction.selection_autoscroll_at,
+            self.interaction.selection_drag,
+        ) {
+            if !self.interaction.mouse_left_pressed {
+                self.interaction.selection_autoscroll_at = None;
+            } else if Instant::now() >= at {
+                self.drag_terminal_selection(pid, self.last_cursor_pos);
+            }
+        }
+
+        // Focus-follows-mouse: hovered pane takes focus once its dwell elapses
+        self.apply_focus_follow(Instant::now());
+
+        // Held navigation chord: AppKit drops the keyUp of a Cmd chord, so
+        // ask whether the key is still down before repeating it
+        if let Some((key, _)) = self.key_repeat.held_key() {
+            if !tide_platform::macos::MacosApp::is_key_down(key) {
+                self.key_repeat.cancel();
+            }
+        }
+        self.apply_key_repeat(Instant::now());
+
         // Check PTY output
         let mut had_pty_output = false;
         for pane in self.panes.values() {
@@ -535,8 +617,7 @@ impl App {
                 if terminal.backend.has_new_output() {
                     self.cache.needs_redraw = true;
                     self.ime.cursor_dirty = true;
-                    self.input_just_sent = false;
-                    self.input_sent_at = None;
+                    self.redraw.on_output();
                     had_pty_output = true;
                     break;
                 }
@@ -547,6 +628,15 @@ impl App {
             self.badge_check_at = Some(Instant::now() + Duration::from_millis(150));
         }
 
+        // OSC 52 clipboard sets (only queued when `allow_osc52` is on)
+        let clipboard_text = self.panes.values().filter_map(|pane| match pane {
+            PaneKind::Terminal(terminal) => terminal.backend.take_clipboard_request(),
+            _ => None,
+        }).last();
+        if let Some(text) = clipboard_text {
+            crate::clipboard::system().write(&text);
+        }
+
         // File watcher
         if self
             .file_watch_dirty
@@ -584,6 +674,14 @@ impl App {
             }
         }
 
+        // Debounced session autosave
+        if let Some(at) = self.session_save_at {
+            if Instant::now() >= at {
+                self.session_save_at = None;
+                session::save_session(&session::Session::from_app(self));
+            }
+        }
+
         // Update IME cursor area
         self.update_ime_cursor_area(window);
     }
@@ -594,88 +692,35 @@ impl App {
             return;
         }
         self.ime.cursor_dirty = false;
-        use tide_core::TerminalBackend;
-
-        let cell_size = self.cell_size();
-
-        let target_id = match self.focused {
-            Some(id) => id,
-            None => return,
+        let Some(target_id) = self.focused else {
+            return;
         };
-
-        match self.panes.get(&target_id) {
-            Some(PaneKind::Terminal(pane)) => {
-                if let Some((_, rect)) = self
-                    .visual_pane_rects
-                    .iter()
-                    .find(|(id, _)| *id == target_id)
-                {
-                    let cursor = pane.backend.cursor();
-                    let inner_w = rect.width - 2.0 * crate::theme::PANE_PADDING;
-                    let max_cols = (inner_w / cell_size.width).floor() as usize;
-                    let actual_w = max_cols as f32 * cell_size.width;
-                    let center_x = (inner_w - actual_w) / 2.0;
-                    let top = crate::theme::TAB_BAR_HEIGHT;
-                    let cx = rect.x
-                        + crate::theme::PANE_PADDING
-                        + center_x
-                        + cursor.col as f32 * cell_size.width;
-                    let cy = rect.y + top + cursor.row as f32 * cell_size.height;
-                    window.set_ime_proxy_cursor_area(
-                        target_id,
-                        cx as f64,
-                        cy as f64,
-                        cell_size.width as f64,
-                        cell_size.height as f64,
-                    );
-                }
-            }
-            Some(PaneKind::Editor(pane)) => {
-                let pos = pane.editor.cursor_position();
-                let scroll = pane.editor.scroll_offset();
-                let h_scroll = pane.editor.h_scroll_offset();
-                if pos.line < scroll {
-                    return;
-                }
-                let visual_row = pos.line - scroll;
-                let cursor_char_col =
-                    if let Some(line_text) = pane.editor.buffer.line(pos.line) {
-                        let byte_col = pos.col.min(line_text.len());
-                        line_text[..byte_col].chars().count()
-                    } else {
-                        0
-                    };
-                if cursor_char_col < h_scroll {
-                    return;
-                }
-                let visual_col = cursor_char_col - h_scroll;
-                let gutter_cells = crate::editor_pane::GUTTER_WIDTH_CELLS;
-
-                let (inner_x, inner_y) = if let Some((_, rect)) = self
-                    .visual_pane_rects
-                    .iter()
-                    .find(|(id, _)| *id == target_id)
-                {
-                    let top = crate::theme::TAB_BAR_HEIGHT;
-                    (rect.x + crate::theme::PANE_PADDING, rect.y + top)
-                } else {
-                    return;
-                };
-
-                let gutter_width = gutter_cells as f32 * cell_size.width;
-                let cx = inner_x + gutter_width + visual_col as f32 * cell_size.width;
-                let cy = inner_y + visual_row as f32 * cell_size.height;
-                window.set_ime_proxy_cursor_area(
-                    target_id,
-                    cx as f64,
-                    cy as f64,
-                    cell_size.width as f64,
-                    cell_size.height as f64,
-                );
-            }
-            _ => {}
+        if let Some(r) = self.ime_cursor_rect() {
+            window.set_ime_proxy_cursor_area(
+                target_id.as_u64(),
+                r.x as f64,
+                r.y as f64,
+                r.width as f64,
+                r.height as f64,
+            );
         }
     }
+
+    /// Window-space rect of the focused pane's cursor, for positioning the
+    /// IME candidate window. Uses the same content area as cursor rendering.
+    pub(crate) fn ime_cursor_rect(&self) -> Option<Rect> {
+        let id = self.focused?;
+        let &(_, rect) = self.visual_pane_rects.iter().find(|(pid, _)| *pid == id)?;
+        let pane_bar = crate::rendering::bar_offset_for(id, &self.panes, &self.modal.save_confirm);
+        let top = TAB_BAR_HEIGHT + pane_bar;
+        let inner = Rect::new(
+            rect.x + PANE_PADDING,
+            rect.y + top,
+            rect.width - 2.0 * PANE_PADDING,
+            (rect.height - top - PANE_PADDING).max(1.0),
+        );
+        self.panes.get(&id)?.ime_cursor_rect(inner, self.cell_size())
+    }
 }
 
 fn platform_button_to_core(
@@ -139,15 +139,26 @@ impl App {
     /// Bumps chrome_generation if anything changed.
     pub(crate) fn update_terminal_badges(&mut self) {
         let mut changed = false;
+        let mut cwd_changed = false;
         let pane_ids: Vec<tide_core::PaneId> = self.panes.keys().copied().collect();
 
         for id in &pane_ids {
             if let Some(PaneKind::Terminal(pane)) = self.panes.get_mut(id) {
-                // CWD (reads /proc or sysctl — no subprocess)
-                let new_cwd = pane.backend.detect_cwd_fallback();
+                // CWD: OSC 7 report, else the shell process's (reads /proc
+                // or sysctl, rate-limited by the terminal's poll interval)
+                let new_cwd = pane.backend.cwd();
                 if new_cwd != pane.cwd {
+                    if let Some(ref cwd) = new_cwd {
+                        pane.push_event(tide_core::PaneEvent::CwdChanged(cwd.clone()));
+                    }
                     pane.cwd = new_cwd;
                     changed = true;
+                    cwd_changed = true;
+                }
+
+                // Window title a program set (OSC 0/2)
+                if let Some(title) = pane.backend.take_title_request() {
+                    pane.push_event(tide_core::PaneEvent::SetTitle(title));
                 }
 
                 // Shell idle
@@ -159,6 +170,9 @@ impl App {
             }
         }
 
+        if cwd_changed {
+            self.schedule_session_save();
+        }
         if self.consume_git_poll_results() || changed {
             self.cache.invalidate_chrome();
         }
@@ -451,7 +465,9 @@ impl App {
             let entries = tree.visible_entries();
             if index < entries.len() {
                 let entry = entries[index].clone();
-                if entry.entry.is_dir {
+                if entry.is_placeholder {
+                    None
+                } else if entry.entry.is_dir {
                     tree.toggle(&entry.entry.path);
                     self.cache.invalidate_chrome();
                     None
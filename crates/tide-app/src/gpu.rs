@@ -38,7 +38,9 @@ impl App {
         let (device, queue) = pollster::block_on(adapter.request_device(
             &wgpu::DeviceDescriptor {
                 label: Some("tide_device"),
-                required_features: wgpu::Features::empty(),
+                // Lets format_features report adapter-specific MSAA counts (2x)
+                required_features: adapter.features()
+                    & wgpu::Features::TEXTURE_ADAPTER_SPECIFIC_FORMAT_FEATURES,
                 required_limits: wgpu::Limits::default(),
                 memory_hints: Default::default(),
             },
@@ -70,7 +72,10 @@ impl App {
             width: self.window_size.0,
             height: self.window_size.1,
             present_mode,
-            alpha_mode: caps.alpha_modes[0],
+            alpha_mode: tide_renderer::preferred_alpha_mode(
+                &caps.alpha_modes,
+                self.settings.background_opacity,
+            ),
             view_formats: vec![],
             desired_maximum_frame_latency: 2,
         };
@@ -80,11 +85,15 @@ impl App {
             Arc::clone(&device),
             Arc::clone(&queue),
             format,
+            tide_renderer::format_features(&adapter, &device, format),
             self.scale_factor,
+            self.settings.msaa_samples,
         );
 
-        // Set initial clear color from theme palette
-        renderer.clear_color = self.palette().border_color;
+        // Set initial clear color from theme
+        renderer.set_theme(&self.theme());
+        renderer.set_text_gamma(self.text_gamma());
+        renderer.set_background_opacity(self.settings.background_opacity);
 
         // Pre-warm ASCII + Korean Jamo glyphs before first frame to avoid input latency
         renderer.warmup_ascii();
@@ -760,14 +760,14 @@ mod tests {
 
     #[test]
     fn plain_file_no_badges() {
-        let ep = make_editor(1);
+        let ep = make_editor(PaneId::new(1));
         let badges = editor_header_badges(&ep);
         assert!(badges.is_empty());
     }
 
     #[test]
     fn markdown_shows_preview_badge() {
-        let ep = make_markdown_editor(1);
+        let ep = make_markdown_editor(PaneId::new(1));
         let badges = editor_header_badges(&ep);
         assert_eq!(badges.len(), 1);
         assert_eq!(badges[0].text, "preview");
@@ -776,7 +776,7 @@ mod tests {
 
     #[test]
     fn markdown_preview_mode_shows_edit_badge() {
-        let mut ep = make_markdown_editor(1);
+        let mut ep = make_markdown_editor(PaneId::new(1));
         ep.preview_mode = true;
         let badges = editor_header_badges(&ep);
         assert_eq!(badges.len(), 1);
@@ -786,7 +786,7 @@ mod tests {
 
     #[test]
     fn markdown_diff_mode_shows_back_not_preview() {
-        let mut ep = make_markdown_editor(1);
+        let mut ep = make_markdown_editor(PaneId::new(1));
         ep.diff_mode = true;
         let badges = editor_header_badges(&ep);
         // diff_mode suppresses preview badge, shows back instead
@@ -797,7 +797,7 @@ mod tests {
 
     #[test]
     fn diff_mode_shows_back_badge() {
-        let mut ep = make_editor(1);
+        let mut ep = make_editor(PaneId::new(1));
         ep.diff_mode = true;
         let badges = editor_header_badges(&ep);
         assert_eq!(badges.len(), 1);
@@ -807,7 +807,7 @@ mod tests {
 
     #[test]
     fn conflict_shows_compare_and_label() {
-        let mut ep = make_editor(1);
+        let mut ep = make_editor(PaneId::new(1));
         ep.disk_changed = true;
         // Make the editor modified by inserting text
         ep.editor.handle_action(tide_editor::EditorActionKind::InsertChar('x'));
@@ -822,7 +822,7 @@ mod tests {
 
     #[test]
     fn file_deleted_shows_deleted_badge() {
-        let mut ep = make_editor(1);
+        let mut ep = make_editor(PaneId::new(1));
         ep.file_deleted = true;
         let badges = editor_header_badges(&ep);
         assert_eq!(badges.len(), 1);
@@ -832,7 +832,7 @@ mod tests {
 
     #[test]
     fn deleted_and_conflict_skips_conflict_when_deleted() {
-        let mut ep = make_editor(1);
+        let mut ep = make_editor(PaneId::new(1));
         ep.disk_changed = true;
         ep.file_deleted = true;
         ep.editor.handle_action(tide_editor::EditorActionKind::InsertChar('x'));
@@ -844,7 +844,7 @@ mod tests {
 
     #[test]
     fn diff_mode_suppresses_conflict() {
-        let mut ep = make_editor(1);
+        let mut ep = make_editor(PaneId::new(1));
         ep.diff_mode = true;
         ep.disk_changed = true;
         ep.editor.handle_action(tide_editor::EditorActionKind::InsertChar('x'));
@@ -857,7 +857,7 @@ mod tests {
     #[test]
     fn markdown_extensions_all_recognized() {
         for ext in &["md", "markdown", "mdown", "mkd"] {
-            let mut ep = make_editor(1);
+            let mut ep = make_editor(PaneId::new(1));
             ep.editor.buffer.file_path = Some(PathBuf::from(format!("file.{}", ext)));
             let badges = editor_header_badges(&ep);
             assert!(!badges.is_empty(), "expected badge for .{} file", ext);
@@ -0,0 +1,60 @@
+// Command-line launch options: starting directory and startup command.
+
+use std::path::{Path, PathBuf};
+
+pub(crate) const USAGE: &str = "usage: tide [--cwd <dir>] [--exec <command>]";
+
+/// Options given on the command line, consumed by `create_initial_pane`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct LaunchOptions {
+    /// Directory for the first terminal and the file tree root (`--cwd`)
+    pub cwd: Option<PathBuf>,
+    /// Command typed into the first terminal once it is spawned (`--exec`)
+    pub exec: Option<String>,
+}
+
+impl LaunchOptions {
+    /// Parse arguments (without the program name). Accepts `--flag value` and
+    /// `--flag=value`; the `-psn_*` argument Finder adds to .app launches is ignored.
+    pub fn parse<I, S>(args: I) -> Result<Self, String>
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let mut opts = Self::default();
+        let mut args = args.into_iter().map(Into::into);
+        while let Some(arg) = args.next() {
+            if arg.starts_with("-psn_") {
+                continue;
+            }
+            let (flag, inline) = match arg.split_once('=') {
+                Some((flag, value)) => (flag.to_string(), Some(value.to_string())),
+                None => (arg.clone(), None),
+            };
+            let slot = match flag.as_str() {
+                "--cwd" | "--exec" => flag,
+                _ => return Err(format!("unknown argument: {arg}")),
+            };
+            let value = match inline.or_else(|| args.next()) {
+                Some(v) if !v.is_empty() => v,
+                _ => return Err(format!("{slot} needs a value")),
+            };
+            if slot == "--cwd" {
+                opts.cwd = Some(PathBuf::from(value));
+            } else {
+                opts.exec = Some(value);
+            }
+        }
+        Ok(opts)
+    }
+
+    /// True when no option was given, so a saved session may restore its panes.
+    pub fn is_empty(&self) -> bool {
+        self.cwd.is_none() && self.exec.is_none()
+    }
+
+    /// `--cwd` resolved against `base` (relative paths are relative to where tide was started).
+    pub fn resolved_cwd(&self, base: &Path) -> Option<PathBuf> {
+        self.cwd.as_ref().map(|dir| base.join(dir))
+    }
+}
@@ -26,6 +26,7 @@ impl App {
             | Some(HoverTarget::BrowserForward)
             | Some(HoverTarget::BrowserRefresh)
             | Some(HoverTarget::BrowserUrlBar)
+            | Some(HoverTarget::TerminalLink(_))
             | Some(HoverTarget::WorkspaceSidebarItem(_))
             | Some(HoverTarget::WorkspaceSidebarNewBtn) => CursorIcon::Pointer,
             Some(HoverTarget::EditorScrollbar(_)) => CursorIcon::Default,
@@ -329,9 +330,38 @@ impl App {
     }
 
 
+    /// Chrome palette for the current mode. A user theme.toml for this mode
+    /// overrides the colors it shares with the terminals and editors.
+    pub(crate) fn palette(&self) -> &ThemePalette {
+        match (&self.user_theme, &self.user_palette) {
+            (Some(theme), Some(palette)) if theme.dark == self.dark_mode => palette,
+            _ if self.dark_mode => &DARK,
+            _ => &LIGHT,
+        }
+    }
+
+    /// Replace the user theme and the chrome palette derived from it.
+    pub(crate) fn set_user_theme(&mut self, theme: Option<tide_core::Theme>) {
+        self.user_palette = theme.as_ref().map(ThemePalette::for_theme);
+        self.user_theme = theme;
+    }
+
+    /// Shared base theme handed to the renderer, terminals and editors.
+    /// A user theme.toml replaces the built-in theme for its own mode.
+    pub(crate) fn theme(&self) -> tide_core::Theme {
+        match &self.user_theme {
+            Some(theme) if theme.dark == self.dark_mode => theme.clone(),
+            _ => tide_core::Theme::for_mode(self.dark_mode),
+        }
+    }
 
-    pub(crate) fn palette(&self) -> &'static ThemePalette {
-        if self.dark_mode { &DARK } else { &LIGHT }
+    /// Text gamma setting for the current mode, handed to the renderer with the theme.
+    pub(crate) fn text_gamma(&self) -> f32 {
+        if self.dark_mode {
+            self.settings.text_gamma_dark
+        } else {
+            self.settings.text_gamma_light
+        }
     }
 
     /// Compute the full layout: sidebar (optional file tree) + pane area (split tree fills remaining space).
@@ -350,10 +380,9 @@ impl App {
         }
         let ws_sidebar_width = if show_ws_sidebar { self.ws.width } else { 0.0 };
 
-        // Clamp file tree width so it never exceeds the window (leave at least 100px for panes).
-        let max_sidebar = (logical.width - ws_sidebar_width - 100.0).max(0.0);
-        if show_file_tree && self.ft.width > max_sidebar {
-            self.ft.width = max_sidebar;
+        // Clamp file tree width (dragged, restored, or after a window shrink).
+        if show_file_tree {
+            self.ft.width = clamp_file_tree_width(self.ft.width, logical.width, ws_sidebar_width);
         }
         let sidebar_width = if show_file_tree { self.ft.width } else { 0.0 };
 
@@ -492,18 +521,11 @@ impl App {
             || self.ft.border_dragging
             || self.ws.border_dragging;
         if !skip_pty_resize {
-            let content_top = TAB_BAR_HEIGHT;
             let cell_size = self.cell_size();
             if cell_size.width > 0.0 {
                 for &(id, vr) in &self.visual_pane_rects {
                     if let Some(PaneKind::Terminal(pane)) = self.panes.get_mut(&id) {
-                        let content_rect = Rect::new(
-                            vr.x + PANE_PADDING,
-                            vr.y + content_top,
-                            (vr.width - 2.0 * PANE_PADDING).max(cell_size.width),
-                            (vr.height - content_top - PANE_PADDING).max(cell_size.height),
-                        );
-                        pane.resize_to_rect(content_rect, cell_size);
+                        pane.resize_to_rect(terminal_content_rect(vr, cell_size), cell_size);
                     }
                 }
             }
@@ -524,6 +546,49 @@ impl App {
         self.sync_browser_webview_frames();
     }
 
+    /// With `snap_to_cells` on, the window size that makes the focused
+    /// terminal's content area a whole number of cells, if that differs from
+    /// the current size. `None` when off, fullscreen, or not on a terminal.
+    pub(crate) fn cell_snapped_window_size(&self) -> Option<Size> {
+        if !self.settings.snap_to_cells || self.is_fullscreen {
+            return None;
+        }
+        let cell_size = self.cell_size();
+        if cell_size.width <= 0.0 || cell_size.height <= 0.0 {
+            return None;
+        }
+        let id = self.focused?;
+        if !matches!(self.panes.get(&id), Some(PaneKind::Terminal(_))) {
+            return None;
+        }
+        let &(_, vr) = self.visual_pane_rects.iter().find(|(pid, _)| *pid == id)?;
+        let content = terminal_content_rect(vr, cell_size);
+        let window = self.logical_size();
+        let snapped = crate::pane::snap_window_to_cells(
+            window,
+            Size::new(content.width, content.height),
+            cell_size,
+        );
+        let moved = (snapped.width - window.width).abs() >= 0.5
+            || (snapped.height - window.height).abs() >= 0.5;
+        moved.then_some(snapped)
+    }
+
+    /// Physical window size to request once a resize settles, from
+    /// `cell_snapped_window_size` of the settled layout. The settle that
+    /// follows a snap request is the snap's own resize, so it never snaps
+    /// again: rounding to physical pixels can't make the window bounce.
+    pub(crate) fn settle_cell_snap(&mut self) -> Option<(u32, u32)> {
+        if self.cell_snap_requested.take().is_some() {
+            return None;
+        }
+        let size = self.cell_snapped_window_size()?;
+        let scale = self.scale_factor;
+        let physical = ((size.width * scale).round() as u32, (size.height * scale).round() as u32);
+        self.cell_snap_requested = Some(physical);
+        Some(physical)
+    }
+
     /// Create/show/hide/reposition WKWebView instances for browser panes.
     /// Browser panes now live in the split tree and use visual_pane_rects for positioning.
     pub(crate) fn sync_browser_webview_frames(&mut self) {
@@ -626,3 +691,22 @@ impl App {
         }
     }
 }
+
+/// The grid area of a terminal pane with visual rect `vr`: below the tab bar,
+/// inside the pane padding, at least one cell. Matches the render inner rect.
+pub(crate) fn terminal_content_rect(vr: Rect, cell_size: Size) -> Rect {
+    Rect::new(
+        vr.x + PANE_PADDING,
+        vr.y + TAB_BAR_HEIGHT,
+        (vr.width - 2.0 * PANE_PADDING).max(cell_size.width),
+        (vr.height - TAB_BAR_HEIGHT - PANE_PADDING).max(cell_size.height),
+    )
+}
+
+/// File tree width clamped to `FILE_TREE_MIN_WIDTH`, leaving at least
+/// `FILE_TREE_MIN_PANE_AREA` of the window beside the workspace sidebar for
+/// panes. The minimum wins when the window is too narrow for both.
+pub(crate) fn clamp_file_tree_width(width: f32, window_width: f32, ws_sidebar_width: f32) -> f32 {
+    let max = window_width - ws_sidebar_width - FILE_TREE_MIN_PANE_AREA;
+    width.min(max).max(FILE_TREE_MIN_WIDTH)
+}
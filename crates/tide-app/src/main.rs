@@ -4,6 +4,7 @@
 
 mod action;
 mod browser_pane;
+mod clipboard;
 mod diff;
 mod diff_pane;
 mod drag_drop;
@@ -13,7 +14,9 @@ mod event_loop;
 mod file_tree;
 mod gpu;
 mod header;
+mod launch;
 mod layout_compute;
+mod menu_bar;
 mod pane;
 mod render_thread;
 mod rendering;
@@ -104,14 +107,20 @@ struct App {
     /// update promptly regardless of whether user or AI agent changed dirs.
     badge_check_at: Option<Instant>,
 
-    // Frame pacing (needs_redraw moved to cache)
-    pub(crate) last_frame: Instant,
+    // Frame pacing: input/scroll latency bypass and the frame budget (needs_redraw is on cache)
+    pub(crate) redraw: tide_core::RedrawScheduler,
     /// Last time we checked child process liveness (throttled to ~2s).
     pub(crate) last_child_check: Instant,
 
     /// Deferred PTY resize after window resize settles (debounce).
     /// While Some, compute_layout skips PTY resize to avoid SIGWINCH spam.
     pub(crate) resize_deferred_at: Option<Instant>,
+    /// Physical size last requested by snap_to_cells; the resize it causes
+    /// settles without snapping again.
+    pub(crate) cell_snap_requested: Option<(u32, u32)>,
+    /// Debounced session autosave: pushed back on every layout/CWD change,
+    /// flushed once things settle so a crash loses at most a few seconds.
+    pub(crate) session_save_at: Option<Instant>,
 
     // IME composition state (grouped)
     pub(crate) ime: ui_state::ImeState,
@@ -127,12 +136,8 @@ struct App {
     // Render generation tracking (grouped)
     pub(crate) cache: ui_state::RenderCache,
 
-    // Input latency: skip coalescing after keypress while awaiting PTY response
-    pub(crate) input_just_sent: bool,
-    pub(crate) input_sent_at: Option<Instant>,
-    // Scroll latency: skip coalescing during active scroll gestures
-    pub(crate) scroll_at: Option<Instant>,
-
+    // Text produced by the KeyDown being routed (composed Option/Alt char)
+    pub(crate) key_chars: Option<String>,
     // Mouse/drag/scroll interaction (grouped)
     pub(crate) interaction: ui_state::InteractionState,
 
@@ -151,6 +156,9 @@ struct App {
     pub(crate) last_shift_up: Option<Instant>,
     pub(crate) shift_tap_clean: bool,
 
+    // Software repeat of a held navigation chord (Cmd+HJKL)
+    pub(crate) key_repeat: tide_input::KeyRepeat,
+
 
     // Theme mode
     pub(crate) dark_mode: bool,
@@ -172,6 +180,10 @@ struct App {
 
     // Loaded settings
     pub(crate) settings: settings::TideSettings,
+    /// User theme from theme.toml (None = built-in dark/light).
+    pub(crate) user_theme: Option<tide_core::Theme>,
+    /// Chrome palette merged from `user_theme`, kept in step by `set_user_theme`.
+    pub(crate) user_palette: Option<theme::ThemePalette>,
 
     // File watcher for external change detection in editor panes
     pub(crate) file_watcher: Option<notify::RecommendedWatcher>,
@@ -211,6 +223,9 @@ struct App {
 
     // Zoomed pane: when Some, this pane fills the entire pane area (Cmd+Enter toggle)
     pub(crate) zoomed_pane: Option<PaneId>,
+
+    // Command-line options for the first pane (`--cwd`, `--exec`)
+    pub(crate) launch: launch::LaunchOptions,
 }
 
 // Safety: App contains raw pointers (content_view_ptr, window_ptr) and browser
@@ -221,6 +236,7 @@ unsafe impl Send for App {}
 
 impl App {
     fn new() -> Self {
+        let user_theme = settings::load_theme();
         Self {
             device: None,
             queue: None,
@@ -245,24 +261,25 @@ impl App {
             last_cursor_pos: tide_core::Vec2::new(0.0, 0.0),
             last_cwd: None,
             badge_check_at: None,
-            last_frame: Instant::now(),
+            redraw: tide_core::RedrawScheduler::new(tide_core::frame_budget(60)),
             last_child_check: Instant::now(),
             resize_deferred_at: None,
+            cell_snap_requested: None,
+            session_save_at: None,
             ime: ui_state::ImeState::new(),
             pane_rects: Vec::new(),
             visual_pane_rects: Vec::new(),
             prev_visual_pane_rects: Vec::new(),
             pane_area_rect: None,
             cache: ui_state::RenderCache::new(),
-            input_just_sent: false,
-            input_sent_at: None,
-            scroll_at: None,
+            key_chars: None,
             interaction: ui_state::InteractionState::new(),
             search_focus: None,
             modal: ui_state::ModalStack::new(),
             pending_terminal_close: None,
             last_shift_up: None,
             shift_tap_clean: false,
+            key_repeat: tide_input::KeyRepeat::default(),
             dark_mode: true,
             top_inset: if cfg!(target_os = "macos") { TITLEBAR_HEIGHT } else { 0.0 },
             is_fullscreen: false,
@@ -272,6 +289,8 @@ impl App {
             focus_area: FocusArea::PaneArea,
             ws: ui_state::WorkspaceManager::new(),
             settings: settings::load_settings(),
+            user_palette: user_theme.as_ref().map(theme::ThemePalette::for_theme),
+            user_theme,
             file_watcher: None,
             file_watch_rx: None,
             file_watch_dirty: Arc::new(AtomicBool::new(false)),
@@ -289,6 +308,7 @@ impl App {
             batch_depth: 0,
             drawable_wait_us: 0,
             zoomed_pane: None,
+            launch: launch::LaunchOptions::default(),
         }
     }
 
@@ -303,9 +323,56 @@ impl App {
         }
     }
 
+    /// Install an event-loop waker on the file tree so a finished background
+    /// directory load wakes us to apply it.
+    fn install_tree_waker(&self, tree: &FsTree) {
+        if let Some(ref waker) = self.event_loop_waker {
+            let w = waker.clone();
+            tree.set_waker(Box::new(move || w()));
+        }
+    }
+
+    /// Spawn options for a new terminal: `cwd` plus the user's `terminal_env`.
+    pub(crate) fn terminal_options(&self, cwd: Option<PathBuf>) -> tide_terminal::TerminalOptions {
+        tide_terminal::TerminalOptions {
+            cwd,
+            dark_mode: self.dark_mode,
+            env_extra: self.settings.terminal_env.clone(),
+            ..Default::default()
+        }
+    }
+
+    /// Apply per-terminal settings to a newly created terminal pane.
+    fn apply_terminal_settings(&self, pane: &mut TerminalPane) {
+        pane.backend.set_option_as_meta(self.settings.option_as_meta);
+        pane.backend.set_allow_osc52(self.settings.allow_osc52);
+        pane.backend.set_word_chars(&self.settings.word_chars);
+        pane.backend.set_reflow(self.settings.terminal_reflow);
+        pane.backend.set_write_filter(settings::write_filter(&self.settings));
+        let cwd_poll = self.settings.cwd_poll_ms;
+        pane.backend.set_cwd_poll_interval((cwd_poll > 0).then(|| std::time::Duration::from_millis(cwd_poll)));
+        pane.backend.set_cursor_style(tide_terminal::CursorStyleConfig {
+            fallback_color: self.theme().cursor,
+            ..pane.backend.cursor_style()
+        });
+    }
+
+    /// Apply per-editor settings to a newly created editor pane.
+    pub(crate) fn apply_editor_settings(&self, pane: &mut editor_pane::EditorPane) {
+        pane.editor.set_dark_mode(self.dark_mode);
+        pane.editor.set_auto_close(self.settings.editor_auto_close);
+    }
+
+    /// Directory for the first terminal and file tree: `--cwd` if given.
+    pub(crate) fn launch_cwd(&self) -> Option<PathBuf> {
+        let base = std::env::current_dir().unwrap_or_else(|_| PathBuf::from("/"));
+        self.launch.resolved_cwd(&base)
+    }
+
     /// Create the initial terminal pane. If `early_terminal` is provided, reuse it
     /// (pre-spawned before GPU init so the shell loads in parallel). Otherwise
-    /// spawn a fresh PTY.
+    /// spawn a fresh PTY. `--cwd` sets its directory and the file tree root,
+    /// `--exec` is typed into it.
     fn create_initial_pane(&mut self, early_terminal: Option<tide_terminal::Terminal>) {
         let (layout, pane_id) = SplitLayout::with_initial_pane();
         self.layout = layout;
@@ -314,27 +381,28 @@ impl App {
         let logical_w = self.window_size.0 as f32 / self.scale_factor;
         let logical_h = self.window_size.1 as f32 / self.scale_factor;
 
-        let cols = if cell_size.width > 0.0 {
-            ((logical_w / cell_size.width).max(1.0).min(1000.0)) as u16
-        } else {
-            80
-        };
-        let rows = if cell_size.height > 0.0 {
-            ((logical_h / cell_size.height).max(1.0).min(500.0)) as u16
+        let (cols, rows) = if cell_size.width > 0.0 && cell_size.height > 0.0 {
+            pane::grid_dimensions(Size::new(logical_w, logical_h), cell_size)
         } else {
-            24
+            (80, 24)
         };
 
         let result = if let Some(mut terminal) = early_terminal {
             // Resize pre-spawned terminal to actual dimensions
             terminal.resize(cols, rows);
+            terminal.set_theme(&self.theme());
             Ok(TerminalPane::with_terminal(pane_id, terminal))
         } else {
-            TerminalPane::with_cwd(pane_id, cols, rows, None, self.dark_mode)
+            TerminalPane::with_options(pane_id, cols, rows, self.terminal_options(self.launch_cwd()), &self.theme())
         };
 
         match result {
-            Ok(pane) => {
+            Ok(mut pane) => {
+                self.apply_terminal_settings(&mut pane);
+                // Written ahead of the prompt: the tty buffers it until the shell reads input
+                if let Some(cmd) = self.launch.exec.take() {
+                    pane.backend.write(format!("{cmd}\n").as_bytes());
+                }
                 self.install_pty_waker(&pane);
                 self.panes.insert(pane_id, PaneKind::Terminal(pane));
                 self.ime.pending_creates.push(pane_id);
@@ -346,9 +414,12 @@ impl App {
             }
         }
 
-        // Initialize file tree with CWD
-        let cwd = std::env::current_dir().unwrap_or_else(|_| PathBuf::from("/"));
+        // Initialize file tree with --cwd or the process CWD
+        let cwd = self
+            .launch_cwd()
+            .unwrap_or_else(|| std::env::current_dir().unwrap_or_else(|_| PathBuf::from("/")));
         let tree = FsTree::new(cwd.clone());
+        self.install_tree_waker(&tree);
         self.ft.tree = Some(tree);
         self.last_cwd = Some(cwd);
 
@@ -435,6 +506,14 @@ fn main() {
 
     env_logger::init();
 
+    let launch = match launch::LaunchOptions::parse(std::env::args().skip(1)) {
+        Ok(launch) => launch,
+        Err(e) => {
+            eprintln!("tide: {e}\n{}", launch::USAGE);
+            std::process::exit(2);
+        }
+    };
+
     // ── Channels ──────────────────────────────────────────────────────
     // event channel: main thread → app thread (platform events + wake signals)
     // command channel: app thread → main thread (window mutations)
@@ -465,12 +544,21 @@ fn main() {
     // ── App setup ────────────────────────────────────────────────────
     let mut app = App::new();
     app.event_loop_waker = Some(combined_waker);
+    app.launch = launch;
 
     // Initialize keybinding map from saved settings
     if !app.settings.keybindings.is_empty() {
         let map = settings::build_keybinding_map(&app.settings);
         app.router.keybinding_map = Some(map);
     }
+    app.router.set_clipboard_key_policy(settings::clipboard_key_policy(&app.settings));
+    app.router.set_modifier_normalization(settings::modifier_normalization(&app.settings));
+    tide_platform::macos::MacosApp::set_option_as_meta(app.settings.option_as_meta);
+    let menu_spec = match &app.router.keybinding_map {
+        Some(map) => menu_bar::menu_spec(map),
+        None => menu_bar::menu_spec(&tide_input::KeybindingMap::new()),
+    };
+    tide_platform::macos::MacosApp::set_menu(menu_spec);
 
     // Try loading a saved session to restore window size
     let saved_session = session::load_session();
@@ -486,6 +574,7 @@ fn main() {
         min_width: 400.0,
         min_height: 300.0,
         transparent_titlebar: true,
+        transparent_background: app.settings.background_opacity < 1.0,
     };
 
     // ── Phase 1 handoff state ────────────────────────────────────────
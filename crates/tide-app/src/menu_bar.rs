@@ -0,0 +1,105 @@
+// Native menu bar: lists GlobalActions so they're discoverable from the mouse.
+//
+// Menu bar item ids are `MENU_BAR_ID_BASE` + position in
+// `GlobalAction::all_actions()`, so a `MenuItemSelected(id)` maps straight
+// back to the action it was built from. Native context menus share the same
+// `MenuItemSelected` event and use ids below the base.
+
+use tide_input::{GlobalAction, KeybindingMap};
+use tide_platform::{Menu, MenuItem, MenuSpec};
+
+/// First menu bar id. Ids below it belong to context menus.
+pub(crate) const MENU_BAR_ID_BASE: u32 = 0x1_0000;
+
+/// Menu bar id for `action`.
+fn menu_id(action: &GlobalAction) -> u32 {
+    GlobalAction::all_actions()
+        .iter()
+        .position(|a| a == action)
+        .map_or(0, |i| MENU_BAR_ID_BASE + i as u32)
+}
+
+/// The action a menu bar item was built from, or `None` for ids outside the
+/// menu bar range (e.g. a context menu item).
+pub(crate) fn action_for_menu_id(id: u32) -> Option<GlobalAction> {
+    let index = id.checked_sub(MENU_BAR_ID_BASE)? as usize;
+    GlobalAction::all_actions().into_iter().nth(index)
+}
+
+/// Build the menu bar. Shortcuts shown come from `bindings` (the active hotkey
+/// table), which stays the only dispatcher for them.
+pub(crate) fn menu_spec(bindings: &KeybindingMap) -> MenuSpec {
+    use tide_input::Direction;
+
+    let item = |action: GlobalAction| {
+        let entry = MenuItem::item(menu_id(&action), action.label());
+        match bindings.hotkey_for(&action) {
+            Some(hotkey) => entry.shortcut(hotkey.key, hotkey.modifiers()),
+            None => entry,
+        }
+    };
+
+    MenuSpec {
+        app_name: "Tide".to_string(),
+        preferences_id: Some(menu_id(&GlobalAction::OpenConfig)),
+        menus: vec![
+            Menu::new("File", vec![
+                item(GlobalAction::NewWindow),
+                item(GlobalAction::NewTab),
+                item(GlobalAction::NewFile),
+                item(GlobalAction::FileFinder),
+                MenuItem::separator(),
+                item(GlobalAction::OpenBrowser),
+                MenuItem::separator(),
+                item(GlobalAction::ClosePane),
+            ]),
+            Menu::new("Edit", vec![
+                item(GlobalAction::Copy),
+                item(GlobalAction::Paste),
+                MenuItem::separator(),
+                item(GlobalAction::Find),
+                MenuItem::separator(),
+                item(GlobalAction::ClearScrollback),
+                item(GlobalAction::ResetTerminal),
+                MenuItem::separator(),
+                item(GlobalAction::PrevPrompt),
+                item(GlobalAction::NextPrompt),
+                item(GlobalAction::SelectCommandOutput),
+            ]),
+            Menu::new("View", vec![
+                item(GlobalAction::ToggleFileTree),
+                item(GlobalAction::ToggleWorkspaceSidebar),
+                item(GlobalAction::ToggleZoom),
+                MenuItem::separator(),
+                item(GlobalAction::FontSizeUp),
+                item(GlobalAction::FontSizeDown),
+                item(GlobalAction::FontSizeReset),
+                MenuItem::separator(),
+                item(GlobalAction::ToggleTheme),
+                item(GlobalAction::ToggleFullscreen),
+            ]),
+            Menu::new("Pane", vec![
+                item(GlobalAction::SplitVertical),
+                item(GlobalAction::SplitHorizontal),
+                item(GlobalAction::SplitVerticalHere),
+                item(GlobalAction::SplitHorizontalHere),
+                item(GlobalAction::DuplicatePane),
+                MenuItem::separator(),
+                item(GlobalAction::Navigate(Direction::Left)),
+                item(GlobalAction::Navigate(Direction::Right)),
+                item(GlobalAction::Navigate(Direction::Up)),
+                item(GlobalAction::Navigate(Direction::Down)),
+                MenuItem::separator(),
+                item(GlobalAction::TabPrev),
+                item(GlobalAction::TabNext),
+            ]),
+            Menu::new("Workspace", vec![
+                item(GlobalAction::NewWorkspace),
+                item(GlobalAction::CloseWorkspace),
+                MenuItem::separator(),
+                item(GlobalAction::WorkspacePrev),
+                item(GlobalAction::WorkspaceNext),
+            ]),
+        ],
+    }
+}
@@ -1,18 +1,20 @@
 // Terminal pane: wraps a terminal backend with rendering helpers.
 
 use std::path::PathBuf;
+use std::time::Instant;
 
 use unicode_width::UnicodeWidthChar;
 
-use tide_core::{Color, CursorShape, Key, Modifiers, Rect, Renderer, Size, TerminalBackend, Vec2};
+use tide_core::{Color, CursorShape, Key, Modifiers, PaneEvent, Rect, Renderer, Size, TerminalBackend, Vec2};
 use tide_renderer::WgpuRenderer;
-use tide_terminal::Terminal;
+use tide_terminal::{PromptMark, PromptMarkKind, Terminal, TerminalOptions};
 use tide_terminal::git::GitInfo;
 
 use crate::browser_pane::BrowserPane;
 use crate::diff_pane::DiffPane;
 use crate::editor_pane::EditorPane;
 use crate::search::SearchState;
+use crate::theme::DOUBLE_CLICK_INTERVAL;
 
 pub type PaneId = tide_core::PaneId;
 
@@ -26,13 +28,160 @@ pub enum PaneKind {
     Launcher(PaneId),
 }
 
+impl PaneKind {
+    /// Where the IME candidate window should appear for this pane, given its
+    /// content area. `None` for panes without a text cursor.
+    pub fn ime_cursor_rect(&self, rect: Rect, cell_size: Size) -> Option<Rect> {
+        match self {
+            PaneKind::Terminal(pane) => pane.ime_cursor_rect(rect, cell_size),
+            PaneKind::Editor(pane) => pane.ime_cursor_rect(rect, cell_size),
+            PaneKind::Diff(_) | PaneKind::Browser(_) | PaneKind::Launcher(_) => None,
+        }
+    }
+
+    /// Drain the app-level requests this pane queued since the last call.
+    /// Only terminal and editor panes raise events.
+    pub fn take_events(&mut self) -> Vec<PaneEvent> {
+        match self {
+            PaneKind::Terminal(pane) => pane.take_events(),
+            PaneKind::Editor(pane) => pane.take_events(),
+            PaneKind::Diff(_) | PaneKind::Browser(_) | PaneKind::Launcher(_) => Vec::new(),
+        }
+    }
+}
+
 /// Text selection state (anchor = drag start, end = current position).
+/// Terminal panes store absolute lines (0 = oldest scrollback line) so a
+/// selection survives scrolling; editor panes store buffer lines.
 #[derive(Debug, Clone)]
 pub struct Selection {
     pub anchor: (usize, usize), // (row, col)
     pub end: (usize, usize),    // (row, col)
 }
 
+impl Selection {
+    /// The selection's geometry for rendering, covering text as `mode` says.
+    pub fn geometry(&self, mode: tide_core::SelectionMode) -> tide_core::Selection {
+        tide_core::Selection::new(self.anchor, self.end, mode)
+    }
+}
+
+/// Maximum lines scrolled per auto-scroll step while drag-selecting past a pane edge.
+const SELECTION_AUTOSCROLL_MAX_LINES: i32 = 5;
+
+/// Convert a pixel position to a `(row, col)` screen cell inside a terminal's
+/// content rect, matching the horizontal centering used by `render_grid`.
+/// Rows above/below the rect are returned as-is (negative or past the last row)
+/// so drag-selection can auto-scroll; columns are clamped to `0..=max_cols`.
+pub fn pixel_to_grid_cell(pos: Vec2, inner: Rect, cell_size: Size) -> (isize, usize) {
+    let max_cols = (inner.width / cell_size.width).floor().max(0.0) as usize;
+    let extra_x = (inner.width - max_cols as f32 * cell_size.width) / 2.0;
+    let col = ((pos.x - inner.x - extra_x) / cell_size.width).floor();
+    let row = ((pos.y - inner.y) / cell_size.height).floor() as isize;
+    let col = if col < 0.0 { 0 } else { (col as usize).min(max_cols) };
+    (row, col)
+}
+
+/// Terminal columns and rows that fit in `area`: whole cells only (the
+/// leftover is letterboxed), at least 1x1, at most 1000x500.
+pub fn grid_dimensions(area: Size, cell_size: Size) -> (u16, u16) {
+    let cols = (area.width / cell_size.width).floor().clamp(1.0, 1000.0) as u16;
+    let rows = (area.height / cell_size.height).floor().clamp(1.0, 500.0) as u16;
+    (cols, rows)
+}
+
+/// Window size that makes a `content` area (inside a `window` of the current
+/// size) a whole number of cells: each leftover strip is trimmed, or filled
+/// up to the next cell when it is more than half a cell.
+pub fn snap_window_to_cells(window: Size, content: Size, cell_size: Size) -> Size {
+    let snap = |window: f32, content: f32, cell: f32| {
+        let leftover = content.rem_euclid(cell);
+        if content < cell {
+            window + (cell - content)
+        } else if leftover > cell / 2.0 {
+            window + (cell - leftover)
+        } else {
+            window - leftover
+        }
+    };
+    Size::new(
+        snap(window.width, content.width, cell_size.width),
+        snap(window.height, content.height, cell_size.height),
+    )
+}
+
+/// Auto-scroll delta for a drag-selection at `screen_row` in a pane showing
+/// `visible_rows` rows. Positive = scroll up into history, negative = toward
+/// the bottom, 0 = pointer is inside the pane.
+pub fn selection_autoscroll_delta(screen_row: isize, visible_rows: usize) -> i32 {
+    if screen_row < 0 {
+        ((-screen_row) as i32).min(SELECTION_AUTOSCROLL_MAX_LINES)
+    } else if screen_row >= visible_rows as isize {
+        -((screen_row - visible_rows as isize + 1) as i32).min(SELECTION_AUTOSCROLL_MAX_LINES)
+    } else {
+        0
+    }
+}
+
+/// Scroll delta (positive = into history) that puts the previous / next
+/// OSC 133 prompt at the top of a terminal with `history_size` scrollback
+/// lines scrolled back by `display_offset`. Prompts on the bottom screen
+/// can only be scrolled to the live view. `None` when no prompt is there.
+pub fn prompt_jump_delta(marks: &[PromptMark], history_size: usize, display_offset: usize, forward: bool) -> Option<i32> {
+    let top_line = history_size.saturating_sub(display_offset);
+    let mut prompts = marks.iter().filter(|m| m.kind == PromptMarkKind::PromptStart).map(|m| m.line);
+    let line = if forward {
+        prompts.find(|&line| line > top_line)?
+    } else {
+        prompts.rfind(|&line| line < top_line)?
+    };
+    let delta = history_size.saturating_sub(line) as i32 - display_offset as i32;
+    (delta != 0).then_some(delta)
+}
+
+/// Output lines `[start, end)` of the last finished command whose prompt is
+/// at or above `limit`: from its `OutputStart` mark to its `CommandEnd` (or
+/// the next prompt). Commands without output are skipped.
+pub fn command_output_lines(marks: &[PromptMark], limit: usize) -> Option<(usize, usize)> {
+    let mut found = None;
+    let mut prompt = None;
+    let mut output = None;
+    for mark in marks {
+        match mark.kind {
+            PromptMarkKind::PromptStart => {
+                if let (Some(_), Some(start)) = (prompt, output.take()) {
+                    if mark.line > start {
+                        found = Some((start, mark.line));
+                    }
+                }
+                prompt = (mark.line <= limit).then_some(mark.line);
+            }
+            PromptMarkKind::OutputStart if prompt.is_some() => output = Some(mark.line),
+            PromptMarkKind::CommandEnd { .. } => {
+                if let (Some(_), Some(start)) = (prompt, output.take()) {
+                    if mark.line > start {
+                        found = Some((start, mark.line));
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    found
+}
+
+/// Whether a click on `cell` in `pane` at `now` completes a double-click
+/// with the previous click `last` (same pane and cell, within `DOUBLE_CLICK_INTERVAL`).
+pub fn is_double_click(
+    last: Option<(Instant, PaneId, (usize, usize))>,
+    now: Instant,
+    pane: PaneId,
+    cell: (usize, usize),
+) -> bool {
+    matches!(last, Some((at, id, last_cell))
+        if id == pane && last_cell == cell && now.duration_since(at) <= DOUBLE_CLICK_INTERVAL)
+}
+
 pub struct TerminalPane {
     #[allow(dead_code)]
     pub id: PaneId,
@@ -52,15 +201,20 @@ pub struct TerminalPane {
     pub worktree_count: usize,
     /// Whether the child shell process has died.
     pub child_dead: bool,
+    /// Tab title set by `PaneEvent::SetTitle`, shown instead of the CWD.
+    pub title: Option<String>,
+    /// App-level requests queued for the next update tick (`take_events`).
+    events: Vec<PaneEvent>,
 }
 
 impl TerminalPane {
-    pub fn with_cwd(id: PaneId, cols: u16, rows: u16, cwd: Option<std::path::PathBuf>, dark_mode: bool) -> Result<Self, Box<dyn std::error::Error>> {
-        let backend = Terminal::with_cwd(cols, rows, cwd, dark_mode)?;
+    pub fn with_options(id: PaneId, cols: u16, rows: u16, options: TerminalOptions, theme: &tide_core::Theme) -> Result<Self, tide_terminal::TerminalError> {
+        let mut backend = Terminal::with_options(cols, rows, TerminalOptions { dark_mode: theme.dark, ..options })?;
+        backend.set_theme(theme);
         Ok(Self {
             id, backend, selection: None, search: None, cursor_suppress: 3,
             cwd: None, git_info: None, shell_idle: true, worktree_count: 0,
-            child_dead: false,
+            child_dead: false, title: None, events: Vec::new(),
         })
     }
 
@@ -71,47 +225,80 @@ impl TerminalPane {
         Self {
             id, backend, selection: None, search: None, cursor_suppress: 3,
             cwd: None, git_info: None, shell_idle: true, worktree_count: 0,
-            child_dead: false,
+            child_dead: false, title: None, events: Vec::new(),
+        }
+    }
+
+    /// An exited terminal with no shell, shown in place of a closed last pane
+    /// (`OnLastPaneExit::KeepOpen`). Any key relaunches a shell in `cwd`.
+    pub fn exited_placeholder(id: PaneId, cols: u16, rows: u16, cwd: Option<PathBuf>, theme: &tide_core::Theme) -> Self {
+        let mut backend = Terminal::from_reader(std::io::empty(), cols, rows);
+        backend.set_theme(theme);
+        Self {
+            id, backend, selection: None, search: None, cursor_suppress: 0,
+            cwd, git_info: None, shell_idle: true, worktree_count: 0,
+            child_dead: true, title: None, events: Vec::new(),
+        }
+    }
+
+    /// Queue a request for the app (drained by `take_events`).
+    pub fn push_event(&mut self, event: PaneEvent) {
+        self.events.push(event);
+    }
+
+    /// Drain the requests queued since the last call.
+    pub fn take_events(&mut self) -> Vec<PaneEvent> {
+        std::mem::take(&mut self.events)
+    }
+
+    /// Record that the shell exited; with `close_on_exit` the pane asks the
+    /// app to close it (`PaneEvent::RequestClose`).
+    pub fn mark_child_dead(&mut self, close_on_exit: bool) {
+        self.child_dead = true;
+        if close_on_exit {
+            self.push_event(PaneEvent::RequestClose);
         }
     }
 
-    /// Extract selected text from the terminal grid.
+    /// Extract selected text from the terminal buffer (scrollback included).
     pub fn selected_text(&self, sel: &Selection) -> String {
-        let grid = self.backend.grid();
-        let (start, end) = if sel.anchor < sel.end {
-            (sel.anchor, sel.end)
-        } else {
-            (sel.end, sel.anchor)
-        };
+        self.backend.text_in_range(sel.anchor, sel.end)
+    }
 
-        let mut result = String::new();
-        for row in start.0..=end.0 {
-            if row >= grid.cells.len() {
-                break;
-            }
-            let line = &grid.cells[row];
-            let col_start = if row == start.0 { start.1 } else { 0 };
-            let col_end = if row == end.0 {
-                end.1.min(line.len())
-            } else {
-                line.len()
-            };
-            for col in col_start..col_end {
-                if col < line.len() {
-                    let ch = line[col].character;
-                    if ch != '\0' {
-                        result.push(ch);
-                    }
-                }
-            }
-            if row != end.0 {
-                // Trim trailing spaces from line before adding newline
-                let trimmed = result.trim_end_matches(' ');
-                result.truncate(trimmed.len());
-                result.push('\n');
-            }
+    /// Start a selection at a screen cell (converted to an absolute line).
+    pub fn begin_selection(&mut self, screen_row: usize, col: usize) {
+        let line = self.backend.first_visible_line() + screen_row;
+        self.selection = Some(Selection { anchor: (line, col), end: (line, col) });
+    }
+
+    /// Select the word under a screen cell (double-click), using the
+    /// terminal's word characters.
+    pub fn select_word_at(&mut self, screen_row: usize, col: usize) {
+        let line = self.backend.first_visible_line() + screen_row;
+        let (start, end) = self.backend.word_range_at(line, col);
+        self.selection = Some(Selection { anchor: (line, start), end: (line, end) });
+    }
+
+    /// Extend the selection to a screen cell during a drag. When the pointer
+    /// is above/below the pane, scrolls the scrollback and pins the selection
+    /// end to the edge row. Returns true if the display auto-scrolled.
+    pub fn drag_selection_to(&mut self, screen_row: isize, col: usize) -> bool {
+        if self.selection.is_none() {
+            return false;
+        }
+        let rows = self.backend.current_rows() as usize;
+        let delta = selection_autoscroll_delta(screen_row, rows);
+        let before = self.backend.display_offset();
+        if delta != 0 {
+            self.backend.scroll_display(delta);
         }
-        result
+        let scrolled = self.backend.display_offset() != before;
+        let row = screen_row.clamp(0, rows.saturating_sub(1) as isize) as usize;
+        let line = self.backend.first_visible_line() + row;
+        if let Some(ref mut sel) = self.selection {
+            sel.end = (line, col);
+        }
+        scrolled
     }
 
     /// Render the grid cells into the cached grid layer.
@@ -138,10 +325,11 @@ impl TerminalPane {
                 let cell = &grid.cells[row][col];
                 if (cell.character == '\0' || cell.character == ' ')
                     && cell.style.background.is_none()
+                    && !cell.style.underline
                 {
                     continue;
                 }
-                renderer.draw_grid_cell(cell.character, row, col, cell.style, cell_size, offset);
+                renderer.draw_grid_cluster(cell.character, &cell.zerowidth, row, col, cell.style, cell_size, offset);
             }
         }
     }
@@ -180,13 +368,28 @@ impl TerminalPane {
         }
     }
 
-    /// Render the cursor into the overlay layer (always redrawn).
-    pub fn render_cursor(&self, rect: Rect, renderer: &mut WgpuRenderer, cursor_color: Color) {
+    /// Cell-sized rect of the cursor within `rect` (the pane's content area),
+    /// where the IME candidate window should appear.
+    pub fn ime_cursor_rect(&self, rect: Rect, cell_size: Size) -> Option<Rect> {
+        let cursor = self.backend.cursor();
+        // Center offset matching render_grid
+        let max_cols = (rect.width / cell_size.width).floor() as usize;
+        let extra_x = (rect.width - max_cols as f32 * cell_size.width) / 2.0;
+        let cx = rect.x + extra_x + cursor.col as f32 * cell_size.width;
+        let cy = rect.y + cursor.row as f32 * cell_size.height;
+        Some(Rect::new(cx, cy, cell_size.width, cell_size.height))
+    }
+
+    /// Render the cursor into the overlay layer (always redrawn). Its color
+    /// comes from the backend (OSC 12 or the configured fallback); `pane_bg`
+    /// stands in for cells without a background in reverse-video mode.
+    pub fn render_cursor(&self, rect: Rect, renderer: &mut WgpuRenderer, pane_bg: Color) {
         if self.cursor_suppress > 0 {
             return;
         }
         let cell_size = renderer.cell_size();
         let cursor = self.backend.cursor();
+        let cursor_color = self.backend.cursor_color();
         // Hide cursor when scrolled into history (cursor is at the prompt below viewport)
         if self.backend.display_offset() != 0 {
             return;
@@ -225,6 +428,25 @@ impl TerminalPane {
                     1
                 };
                 let cursor_w = char_width as f32 * cell_size.width;
+                let cell = grid.cells.get(row).and_then(|r| r.get(col));
+
+                if self.backend.cursor_style().reverse_video {
+                    // Swap the cell's colors instead of painting the cursor color
+                    let (fg, bg) = cell.map_or((Color::WHITE, pane_bg), |c| {
+                        (c.style.foreground, c.style.background.unwrap_or(pane_bg))
+                    });
+                    renderer.draw_top_rect(Rect::new(cx, cy, cursor_w, cell_size.height), fg);
+                    if let Some(cell) = cell.filter(|c| c.character != ' ' && c.character != '\0') {
+                        renderer.draw_top_glyph(
+                            cell.character,
+                            Vec2::new(cx, cy),
+                            bg,
+                            cell.style.bold,
+                            cell.style.italic,
+                        );
+                    }
+                    return;
+                }
 
                 renderer.draw_top_rect(
                     Rect::new(cx, cy, cursor_w, cell_size.height),
@@ -232,8 +454,7 @@ impl TerminalPane {
                 );
 
                 // Draw the character under the cursor in inverse color
-                if row < grid.cells.len() && col < grid.cells[row].len() {
-                    let cell = &grid.cells[row][col];
+                if let Some(cell) = cell {
                     if cell.character != ' ' && cell.character != '\0' {
                         // Pick inverse text color based on cursor brightness
                         let lum = cursor_color.r * 0.299 + cursor_color.g * 0.587 + cursor_color.b * 0.114;
@@ -268,14 +489,14 @@ impl TerminalPane {
         }
     }
 
-    pub fn handle_key(&mut self, key: &Key, modifiers: &Modifiers) {
-        let bytes = Terminal::key_to_bytes(key, modifiers);
+    pub fn handle_key(&mut self, key: &Key, modifiers: &Modifiers, composed: Option<&str>) {
+        let bytes = self.backend.encode_key(key, modifiers, composed);
         if !bytes.is_empty() {
             // Scroll back to bottom on user input (applied atomically during next grid sync)
             if self.backend.display_offset() > 0 {
                 self.backend.request_scroll_to_bottom();
             }
-            self.backend.write(&bytes);
+            self.backend.write_keystroke(&bytes);
         }
     }
 
@@ -283,9 +504,53 @@ impl TerminalPane {
         self.backend.scroll_display(delta);
     }
 
+    /// Scroll so the previous / next OSC 133 prompt is the top line.
+    /// Returns false when there is no prompt to jump to.
+    pub fn jump_to_prompt(&mut self, forward: bool) -> bool {
+        let (history, offset) = (self.backend.history_size(), self.backend.display_offset());
+        let Some(delta) = prompt_jump_delta(self.backend.prompt_marks(), history, offset, forward) else {
+            return false;
+        };
+        self.backend.scroll_display(delta);
+        true
+    }
+
+    /// Select the output of a finished command (OSC 133 C to D): the one
+    /// whose prompt is at the top of the view when scrolled back (e.g. after
+    /// `jump_to_prompt`), else the last one. Returns false without marks.
+    pub fn select_command_output(&mut self) -> bool {
+        let limit = if self.backend.display_offset() > 0 { self.backend.first_visible_line() } else { usize::MAX };
+        let Some((start, end)) = command_output_lines(self.backend.prompt_marks(), limit) else {
+            return false;
+        };
+        // Ends at column 0 of the line after the output, so whole lines (with
+        // their newlines) are copied and trailing blanks are trimmed
+        self.selection = Some(Selection { anchor: (start, 0), end: (end, 0) });
+        true
+    }
+
+    /// Drop scrollback history. Selection and search hits point at absolute
+    /// lines that no longer exist, so they're cleared / re-run.
+    pub fn clear_scrollback(&mut self) {
+        self.backend.clear_scrollback();
+        self.forget_line_positions();
+    }
+
+    /// Hard-reset the terminal (RIS).
+    pub fn reset(&mut self) {
+        self.backend.reset();
+        self.forget_line_positions();
+    }
+
+    fn forget_line_positions(&mut self) {
+        self.selection = None;
+        if let Some(ref mut search) = self.search {
+            crate::search::execute_search_terminal(search, &self.backend);
+        }
+    }
+
     pub fn resize_to_rect(&mut self, rect: Rect, cell_size: Size) {
-        let cols = ((rect.width / cell_size.width).max(1.0).min(1000.0)) as u16;
-        let rows = ((rect.height / cell_size.height).max(1.0).min(500.0)) as u16;
+        let (cols, rows) = grid_dimensions(Size::new(rect.width, rect.height), cell_size);
         self.backend.resize(cols, rows);
     }
 }
@@ -117,6 +117,7 @@ fn run(
             });
 
         let mut renderer = job.renderer;
+        renderer.set_target_size(config.width, config.height);
         renderer.render_frame(&mut encoder, &view);
 
         queue.submit(std::iter::once(encoder.finish()));
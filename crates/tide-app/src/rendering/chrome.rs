@@ -1,4 +1,4 @@
-use tide_core::{FileTreeSource, Rect, Renderer, TextStyle, Vec2};
+use tide_core::{FileTreeSource, PaneId, Rect, Renderer, TextStyle, Vec2};
 
 use crate::drag_drop::HoverTarget;
 use crate::header;
@@ -16,11 +16,11 @@ pub(crate) fn render_chrome(
     renderer: &mut tide_renderer::WgpuRenderer,
     p: &ThemePalette,
     logical: tide_core::Size,
-    focused: Option<u64>,
+    focused: Option<PaneId>,
     show_file_tree: bool,
     file_tree_scroll: f32,
-    visual_pane_rects: &[(u64, Rect)],
-    _all_pane_ids: &[u64],
+    visual_pane_rects: &[(PaneId, Rect)],
+    _all_pane_ids: &[PaneId],
 ) {
     renderer.invalidate_chrome();
 
@@ -246,8 +246,6 @@ pub(crate) fn render_chrome(
 
             // Active item: pane-bg background with 1px rounded border
             if is_active {
-                // Outer rounded rect = border color
-                renderer.draw_chrome_rounded_rect(item_rect, p.border_focused, PANE_CORNER_RADIUS);
                 // Inner rounded rect = fill color (inset by 1px)
                 let inner = Rect::new(
                     item_rect.x + 1.0,
@@ -255,7 +253,7 @@ pub(crate) fn render_chrome(
                     item_rect.width - 2.0,
                     item_rect.height - 2.0,
                 );
-                renderer.draw_chrome_rounded_rect(inner, p.pane_bg, (PANE_CORNER_RADIUS - 1.0).max(0.0));
+                draw_bordered_fill(renderer, item_rect, inner, p.border_focused, p.pane_bg, PANE_CORNER_RADIUS, 1.0);
             } else {
                 // Hover highlight
                 if matches!(app.interaction.hover_target, Some(HoverTarget::WorkspaceSidebarItem(idx)) if idx == i) {
@@ -588,13 +586,19 @@ pub(crate) fn render_chrome(
                     background: None,
                     bold: is_expanded_dir,
                     dim: false,
-                    italic: false,
+                    italic: entry.is_placeholder,
                     underline: false,
                 };
-                renderer.draw_chrome_text(
+                // Leave room for the status badge so long names end in "…"
+                let mut name_right = tree_visual_rect.x + tree_visual_rect.width - PANE_PADDING;
+                if status_badge.is_some() {
+                    name_right -= cell_size.width * 2.0;
+                }
+                renderer.draw_chrome_text_ellipsized(
                     &entry.entry.name,
                     Vec2::new(name_x, text_y),
                     name_style,
+                    name_right - name_x,
                     tree_text_clip,
                 );
 
@@ -642,14 +646,13 @@ pub(crate) fn render_chrome(
         let top_border = if is_focused { 2.0 } else { 1.0 };
         let side_border = if is_focused { 2.0_f32 } else { 1.0_f32 };
 
-        // Focused pane: draw outer glow shadow
-        if is_focused {
+        // Focused pane: draw outer glow shadow (skipped on a translucent
+        // window, where it would tint the pane through its fill)
+        if is_focused && renderer.background_opacity() >= 1.0 {
             let shadow_color = tide_core::Color::new(0.769, 0.722, 0.651, 0.25);
             renderer.draw_chrome_shadow(rect, shadow_color, PANE_CORNER_RADIUS, 16.0, -4.0);
         }
 
-        // Outer rounded rect (border color)
-        renderer.draw_chrome_rounded_rect(rect, border_color, PANE_CORNER_RADIUS);
         // Inner rounded rect (pane fill, inset by border widths)
         let inset = Rect::new(
             rect.x + side_border,
@@ -657,7 +660,7 @@ pub(crate) fn render_chrome(
             rect.width - 2.0 * side_border,
             rect.height - top_border - side_border,
         );
-        renderer.draw_chrome_rounded_rect(inset, p.pane_bg, (PANE_CORNER_RADIUS - side_border).max(0.0));
+        draw_bordered_fill(renderer, rect, inset, border_color, p.pane_bg, PANE_CORNER_RADIUS, side_border);
     }
 
     // Render per-pane headers (title + badges + close, or tab bar for multi-tab groups)
@@ -693,6 +696,32 @@ pub(crate) fn render_chrome(
     }
 }
 
+/// Draw `fill` in `inner` with a `border`-colored rounded edge around it out to
+/// `outer`. Opaque windows draw the outer rect and cover its middle; with
+/// `background_opacity` below 1 the fill's alpha is scaled by it and only the
+/// border ring is drawn, so the desktop shows through the fill.
+#[allow(clippy::too_many_arguments)]
+fn draw_bordered_fill(
+    renderer: &mut tide_renderer::WgpuRenderer,
+    outer: Rect,
+    inner: Rect,
+    border: tide_core::Color,
+    fill: tide_core::Color,
+    radius: f32,
+    border_width: f32,
+) {
+    let opacity = renderer.background_opacity();
+    let inner_radius = (radius - border_width).max(0.0);
+    if opacity >= 1.0 {
+        renderer.draw_chrome_rounded_rect(outer, border, radius);
+        renderer.draw_chrome_rounded_rect(inner, fill, inner_radius);
+    } else {
+        renderer.draw_chrome_rounded_border(outer, border, radius, border_width);
+        let fill = tide_core::Color { a: fill.a * opacity, ..fill };
+        renderer.draw_chrome_rounded_rect(inner, fill, inner_radius);
+    }
+}
+
 /// Render browser navigation bar (back/forward/refresh + URL bar) inside a browser pane.
 fn render_browser_nav_bar(
     bp: &crate::browser_pane::BrowserPane,
@@ -1,6 +1,6 @@
 use unicode_width::UnicodeWidthChar;
 
-use tide_core::{Rect, Renderer, TerminalBackend};
+use tide_core::{PaneId, Rect, Renderer, Selection, SelectionMode, TerminalBackend, Vec2};
 
 use crate::pane::PaneKind;
 use crate::theme::*;
@@ -15,9 +15,9 @@ pub(crate) fn render_cursor_and_highlights(
     app: &App,
     renderer: &mut tide_renderer::WgpuRenderer,
     p: &ThemePalette,
-    visual_pane_rects: &[(u64, Rect)],
-    focused: Option<u64>,
-    search_focus: Option<u64>,
+    visual_pane_rects: &[(PaneId, Rect)],
+    focused: Option<PaneId>,
+    search_focus: Option<PaneId>,
 ) {
     let top_offset = TAB_BAR_HEIGHT;
 
@@ -43,7 +43,7 @@ pub(crate) fn render_cursor_and_highlights(
                 // Only render cursor on the focused pane (and hide when search bar is active
                 // or IME preedit is composing — preedit overlay replaces the cursor).
                 if focused == Some(id) && search_focus != Some(id) && app.ime.preedit.is_empty() {
-                    pane.render_cursor(inner, renderer, p.cursor_accent);
+                    pane.render_cursor(inner, renderer, p.pane_bg);
                 }
                 // Render URL underlines when Cmd/Meta is held
                 if app.modifiers.meta {
@@ -51,38 +51,9 @@ pub(crate) fn render_cursor_and_highlights(
                 }
                 // Render selection highlight
                 if let Some(ref sel) = pane.selection {
-                    let cell_size = renderer.cell_size();
-                    let (start, end) = if sel.anchor <= sel.end {
-                        (sel.anchor, sel.end)
-                    } else {
-                        (sel.end, sel.anchor)
-                    };
-                    // Skip rendering if anchor == end (no actual selection)
-                    if start != end {
-                        let sel_color = p.selection;
-                        let grid = pane.backend.grid();
-                        let max_rows = (inner.height / cell_size.height).ceil() as usize;
-                        let max_cols = (inner.width / cell_size.width).floor() as usize;
-                        let visible_rows = (grid.rows as usize).min(max_rows);
-                        let visible_cols = (grid.cols as usize).min(max_cols);
-                        // Center offset matching terminal grid
-                        let actual_w = max_cols as f32 * cell_size.width;
-                        let center_x = (inner.width - actual_w) / 2.0;
-                        for row in start.0..=end.0.min(visible_rows.saturating_sub(1)) {
-                            let col_start = if row == start.0 { start.1 } else { 0 };
-                            let col_end = if row == end.0 { end.1 } else { visible_cols };
-                            if col_start >= col_end {
-                                continue;
-                            }
-                            let rx = inner.x + center_x + col_start as f32 * cell_size.width;
-                            let ry = inner.y + row as f32 * cell_size.height;
-                            let rw = (col_end - col_start) as f32 * cell_size.width;
-                            renderer.draw_rect(
-                                Rect::new(rx, ry, rw, cell_size.height),
-                                sel_color,
-                            );
-                        }
-                    }
+                    renderer.push_clip(inner);
+                    render_terminal_selection(pane, inner, renderer, p, sel);
+                    renderer.pop_clip();
                 }
                 // Render terminal search highlights
                 if let Some(ref search) = pane.search {
@@ -121,7 +92,9 @@ pub(crate) fn render_cursor_and_highlights(
                 if pane.preview_mode {
                     // Render selection highlight in preview mode
                     if let Some(ref sel) = pane.selection {
+                        renderer.push_clip(inner);
                         render_preview_selection(pane, inner, renderer, p, sel);
+                        renderer.pop_clip();
                     }
                     // Render preview search highlights (matches are in preview-line coords)
                     if let Some(ref search) = pane.search {
@@ -134,7 +107,9 @@ pub(crate) fn render_cursor_and_highlights(
                     }
                     // Render editor selection highlight
                     if let Some(ref sel) = pane.selection {
+                        renderer.push_clip(inner);
                         render_editor_selection(pane, inner, renderer, p, sel);
+                        renderer.pop_clip();
                     }
                     // Render editor search highlights
                     if let Some(ref search) = pane.search {
@@ -158,6 +133,42 @@ pub(crate) fn render_cursor_and_highlights(
 
 }
 
+/// Tint the cell backgrounds covered by a terminal selection. The selection is
+/// stored in absolute lines, so only the part inside the viewport is drawn.
+fn render_terminal_selection(
+    pane: &crate::pane::TerminalPane,
+    inner: Rect,
+    renderer: &mut tide_renderer::WgpuRenderer,
+    p: &ThemePalette,
+    sel: &crate::pane::Selection,
+) {
+    let sel = sel.geometry(SelectionMode::Linear);
+    // Skip rendering if anchor == end (no actual selection)
+    if sel.is_empty() {
+        return;
+    }
+    let cell_size = renderer.cell_size();
+    let grid = pane.backend.grid();
+    let max_rows = (inner.height / cell_size.height).ceil() as usize;
+    let max_cols = (inner.width / cell_size.width).floor() as usize;
+    let visible_rows = (grid.rows as usize).min(max_rows);
+    let visible_cols = (grid.cols as usize).min(max_cols);
+    // Center offset matching terminal grid
+    let actual_w = max_cols as f32 * cell_size.width;
+    let center_x = (inner.width - actual_w) / 2.0;
+    // Visible absolute line range
+    let visible_start = pane.backend.first_visible_line();
+    let visible_end = visible_start + visible_rows;
+    let visible = sel
+        .rows()
+        .skip_while(|&(line, ..)| line < visible_start)
+        .take_while(|&(line, ..)| line < visible_end);
+    let spans: Vec<_> = visible
+        .map(|(line, col_start, col_end)| (line - visible_start, (col_start, col_end.min(visible_cols))))
+        .collect();
+    renderer.draw_selection(&spans, cell_size, Vec2::new(inner.x + center_x, inner.y), p.selection);
+}
+
 /// Render selection highlight for an editor pane.
 fn render_editor_selection(
     pane: &crate::editor_pane::EditorPane,
@@ -167,45 +178,63 @@ fn render_editor_selection(
     sel: &crate::pane::Selection,
 ) {
     let cell_size = renderer.cell_size();
-    let (start, end) = if sel.anchor <= sel.end {
-        (sel.anchor, sel.end)
-    } else {
-        (sel.end, sel.anchor)
-    };
-    if start != end {
-        let sel_color = p.selection;
-        let scroll = pane.editor.scroll_offset();
-        let h_scroll = pane.editor.h_scroll_offset();
-        let gutter_width = crate::editor_pane::GUTTER_WIDTH_CELLS as f32 * cell_size.width;
-        let visible_rows = (inner.height / cell_size.height).ceil() as usize;
-        let visible_cols = ((inner.width - gutter_width) / cell_size.width).ceil() as usize;
-        for row in start.0..=end.0 {
-            if row < scroll || row >= scroll + visible_rows {
-                continue;
-            }
-            let visual_row = row - scroll;
-            let col_start = if row == start.0 { start.1 } else { 0 };
-            let col_end = if row == end.0 {
-                end.1
-            } else {
-                // Full line width: use char count or visible cols
-                let char_count = pane.editor.buffer.line(row).map_or(0, |l| l.chars().count());
-                char_count.max(h_scroll + visible_cols)
-            };
-            if col_start >= col_end {
-                continue;
-            }
-            // Clip to visible horizontal range
+    let sel = sel.geometry(pane.editor.selection_mode());
+    if sel.mode == SelectionMode::Block {
+        render_editor_block_selection(pane, inner, renderer, p, &sel);
+        return;
+    }
+    let sel_color = p.selection;
+    let scroll = pane.editor.scroll_offset();
+    let h_scroll = pane.editor.h_scroll_offset();
+    let gutter_width = crate::editor_pane::GUTTER_WIDTH_CELLS as f32 * cell_size.width;
+    let visible_rows = (inner.height / cell_size.height).ceil() as usize;
+    let visible_cols = ((inner.width - gutter_width) / cell_size.width).ceil() as usize;
+    let visible = sel
+        .rows()
+        .skip_while(|&(row, ..)| row < scroll)
+        .take_while(|&(row, ..)| row < scroll + visible_rows);
+    // Clip to visible horizontal range
+    let spans: Vec<_> = visible
+        .map(|(row, col_start, col_end)| {
             let vis_start = col_start.max(h_scroll).saturating_sub(h_scroll);
             let vis_end = col_end.saturating_sub(h_scroll).min(visible_cols);
-            if vis_start >= vis_end {
-                continue;
-            }
-            let rx = inner.x + gutter_width + vis_start as f32 * cell_size.width;
-            let ry = inner.y + visual_row as f32 * cell_size.height;
-            let rw = (vis_end - vis_start) as f32 * cell_size.width;
-            renderer.draw_rect(Rect::new(rx, ry, rw, cell_size.height), sel_color);
-        }
+            (row - scroll, (vis_start, vis_end))
+        })
+        .collect();
+    renderer.draw_selection(&spans, cell_size, Vec2::new(inner.x + gutter_width, inner.y), sel_color);
+}
+
+/// Tint the same columns on every line of a block selection. A zero-width
+/// block draws a thin caret on each line instead.
+fn render_editor_block_selection(
+    pane: &crate::editor_pane::EditorPane,
+    inner: Rect,
+    renderer: &mut tide_renderer::WgpuRenderer,
+    p: &ThemePalette,
+    sel: &Selection,
+) {
+    let cell_size = renderer.cell_size();
+    let ((_, left), (_, right)) = sel.normalized();
+    let scroll = pane.editor.scroll_offset();
+    let h_scroll = pane.editor.h_scroll_offset();
+    let gutter_width = crate::editor_pane::GUTTER_WIDTH_CELLS as f32 * cell_size.width;
+    let visible_rows = (inner.height / cell_size.height).ceil() as usize;
+    let visible_cols = ((inner.width - gutter_width) / cell_size.width).ceil() as usize;
+    if right < h_scroll || left > h_scroll + visible_cols {
+        return;
+    }
+    let vis_start = left.saturating_sub(h_scroll);
+    let vis_end = right.saturating_sub(h_scroll).min(visible_cols);
+    let rx = inner.x + gutter_width + vis_start as f32 * cell_size.width;
+    let rw = ((vis_end - vis_start) as f32 * cell_size.width).max(2.0);
+    let (color, rw) = if left == right { (p.cursor_accent, 2.0) } else { (p.selection, rw) };
+    let visible = sel
+        .rows()
+        .skip_while(|&(row, ..)| row < scroll)
+        .take_while(|&(row, ..)| row < scroll + visible_rows);
+    for (row, ..) in visible {
+        let ry = inner.y + (row - scroll) as f32 * cell_size.height;
+        renderer.draw_rect(Rect::new(rx, ry, rw, cell_size.height), color);
     }
 }
 
@@ -259,51 +288,35 @@ fn render_preview_selection(
     sel: &crate::pane::Selection,
 ) {
     let cell_size = renderer.cell_size();
-    let (start, end) = if sel.anchor <= sel.end {
-        (sel.anchor, sel.end)
-    } else {
-        (sel.end, sel.anchor)
-    };
-    if start == end {
-        return;
-    }
     let sel_color = p.selection;
     let scroll = pane.preview_scroll;
     let h_scroll = pane.preview_h_scroll;
     let visible_rows = (inner.height / cell_size.height).ceil() as usize;
     let preview_lines = pane.preview_lines();
 
-    for row in start.0..=end.0 {
-        if row < scroll || row >= scroll + visible_rows {
-            continue;
-        }
-        let visual_row = row - scroll;
-        let col_start = if row == start.0 { start.1 } else { 0 };
-        let col_end = if row == end.0 {
-            end.1
-        } else {
-            // Full line width from preview spans
-            preview_lines.get(row).map_or(0, |line| {
-                use unicode_width::UnicodeWidthChar;
-                line.spans.iter().map(|s| {
-                    s.text.chars().filter(|c| *c != '\n').map(|c| c.width().unwrap_or(1)).sum::<usize>()
-                }).sum()
-            })
-        };
-        if col_start >= col_end {
-            continue;
-        }
-        // Apply horizontal scroll offset: skip columns before h_scroll
-        let vis_start = col_start.saturating_sub(h_scroll);
-        let vis_end = col_end.saturating_sub(h_scroll);
-        if vis_start >= vis_end {
-            continue;
-        }
-        let rx = inner.x + vis_start as f32 * cell_size.width;
-        let ry = inner.y + visual_row as f32 * cell_size.height;
-        let rw = (vis_end - vis_start) as f32 * cell_size.width;
-        renderer.draw_rect(Rect::new(rx, ry, rw, cell_size.height), sel_color);
-    }
+    let sel = sel.geometry(SelectionMode::Linear);
+    let visible = sel
+        .rows()
+        .skip_while(|&(row, ..)| row < scroll)
+        .take_while(|&(row, ..)| row < scroll + visible_rows);
+    let spans: Vec<_> = visible
+        .map(|(row, col_start, col_end)| {
+            let col_end = if col_end == usize::MAX {
+                // Full line width from preview spans
+                preview_lines.get(row).map_or(0, |line| {
+                    use unicode_width::UnicodeWidthChar;
+                    line.spans.iter().map(|s| {
+                        s.text.chars().filter(|c| *c != '\n').map(|c| c.width().unwrap_or(1)).sum::<usize>()
+                    }).sum()
+                })
+            } else {
+                col_end
+            };
+            // Apply horizontal scroll offset: skip columns before h_scroll
+            (row - scroll, (col_start.saturating_sub(h_scroll), col_end.saturating_sub(h_scroll)))
+        })
+        .collect();
+    renderer.draw_selection(&spans, cell_size, Vec2::new(inner.x, inner.y), sel_color);
 }
 
 /// Render matching bracket highlights for an editor pane.
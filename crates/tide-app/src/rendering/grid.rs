@@ -1,4 +1,4 @@
-use tide_core::{Rect, Renderer};
+use tide_core::{PaneId, Rect, Renderer};
 
 use crate::pane::PaneKind;
 use crate::theme::*;
@@ -13,7 +13,7 @@ pub(crate) fn render_grid(
     app: &mut App,
     renderer: &mut tide_renderer::WgpuRenderer,
     p: &ThemePalette,
-    visual_pane_rects: &[(u64, Rect)],
+    visual_pane_rects: &[(PaneId, Rect)],
 ) -> bool {
     let top_offset = TAB_BAR_HEIGHT;
 
@@ -1,4 +1,4 @@
-use tide_core::{Rect, Renderer};
+use tide_core::{PaneId, Rect, Renderer};
 
 use crate::drag_drop;
 use crate::drag_drop::PaneDragState;
@@ -12,7 +12,7 @@ pub(crate) fn render_hover(
     renderer: &mut tide_renderer::WgpuRenderer,
     p: &ThemePalette,
     _logical: tide_core::Size,
-    visual_pane_rects: &[(u64, Rect)],
+    visual_pane_rects: &[(PaneId, Rect)],
     show_file_tree: bool,
     file_tree_scroll: f32,
 ) {
@@ -60,6 +60,9 @@ pub(crate) fn render_hover(
                 drag_drop::HoverTarget::EditorScrollbar(_) => {
                     // Scrollbar hover expansion handled in render_scrollbar
                 }
+                drag_drop::HoverTarget::TerminalLink(_) => {
+                    // Underline is part of the terminal grid (Terminal::set_hovered_cell)
+                }
                 drag_drop::HoverTarget::SplitBorder(dir) => {
                     // Highlight the border line between adjacent panes
                     for &(id_a, rect_a) in visual_pane_rects {
@@ -1,6 +1,6 @@
 use unicode_width::UnicodeWidthChar;
 
-use tide_core::{Rect, Renderer, TerminalBackend, TextStyle, Vec2};
+use tide_core::{PaneId, Rect, Renderer, TerminalBackend, TextStyle, Vec2};
 
 use crate::drag_drop::{DropDestination, PaneDragState};
 use crate::pane::PaneKind;
@@ -14,8 +14,8 @@ pub(crate) fn render_ime_and_drop_preview(
     app: &App,
     renderer: &mut tide_renderer::WgpuRenderer,
     p: &ThemePalette,
-    visual_pane_rects: &[(u64, Rect)],
-    focused: Option<u64>,
+    visual_pane_rects: &[(PaneId, Rect)],
+    focused: Option<PaneId>,
 ) {
     // Render IME preedit overlay for terminal and editor panes.
     // Skip when a text-intercepting popup is active (file finder, git switcher, etc.)
@@ -139,8 +139,8 @@ fn render_editor_ime_preedit(
     app: &App,
     renderer: &mut tide_renderer::WgpuRenderer,
     p: &ThemePalette,
-    visual_pane_rects: &[(u64, Rect)],
-    target_id: u64,
+    visual_pane_rects: &[(PaneId, Rect)],
+    target_id: PaneId,
 ) {
     let pane = match app.panes.get(&target_id) {
         Some(PaneKind::Editor(pane)) => pane,
@@ -5,7 +5,7 @@ mod hover;
 mod ime;
 mod overlays;
 
-use tide_core::{Rect, Renderer};
+use tide_core::{PaneId, Rect, Renderer};
 
 use crate::pane::PaneKind;
 use crate::theme::*;
@@ -14,7 +14,7 @@ use crate::App;
 
 /// Compute the bar offset for a pane. Returns CONFLICT_BAR_HEIGHT if a notification bar
 /// (conflict or save confirm) is visible, else 0.
-pub(super) fn bar_offset_for(
+pub(crate) fn bar_offset_for(
     pane_id: tide_core::PaneId,
     panes: &std::collections::HashMap<tide_core::PaneId, PaneKind>,
     save_confirm: &Option<crate::SaveConfirmState>,
@@ -32,6 +32,23 @@ pub(super) fn bar_offset_for(
     0.0
 }
 
+/// Dim every pane but the focused one by `amount` (`inactive_pane_dim`).
+fn render_inactive_pane_dim(
+    renderer: &mut tide_renderer::WgpuRenderer,
+    visual_pane_rects: &[(PaneId, Rect)],
+    focused: Option<PaneId>,
+    amount: f32,
+) {
+    if amount <= 0.0 {
+        return;
+    }
+    for &(id, rect) in visual_pane_rects {
+        if focused != Some(id) {
+            renderer.draw_dim_overlay(rect, amount);
+        }
+    }
+}
+
 impl App {
     /// Poll the render thread for completed frames.  Returns the renderer
     /// to `self.renderer` and updates `drawable_wait_us`.
@@ -93,17 +110,20 @@ impl App {
         let show_file_tree = self.ft.visible;
         let file_tree_scroll = self.ft.scroll;
         let visual_pane_rects = self.visual_pane_rects.clone();
-        let alive_pane_ids: Vec<u64> = self.panes.keys().copied().collect();
+        let alive_pane_ids: Vec<PaneId> = self.panes.keys().copied().collect();
         let all_pane_ids = self.layout.pane_ids();
 
-        let p = self.palette();
+        // Copied once per frame: the passes below borrow `self` mutably.
+        let p = *self.palette();
 
         // Keep runtime caches bounded to currently alive panes.
         self.cache.pane_generations.retain(|id, _| self.panes.contains_key(id));
         renderer.retain_pane_caches(&alive_pane_ids);
 
-        // Atlas reset -> all cached UV coords are stale, force full rebuild
-        if renderer.atlas_was_reset() {
+        // Atlas reset -> all cached UV coords are stale; metrics change -> all
+        // cached glyph positions are. Either forces a full rebuild.
+        let atlas_reset = renderer.atlas_was_reset();
+        if renderer.metrics_were_invalidated() || atlas_reset {
             self.cache.pane_generations.clear();
             renderer.invalidate_all_pane_caches();
             self.cache.last_chrome_generation = self.cache.chrome_generation.wrapping_sub(1);
@@ -112,7 +132,7 @@ impl App {
         // Layout change -> invalidate only panes whose rects changed
         let layout_changed = self.prev_visual_pane_rects != visual_pane_rects;
         if layout_changed {
-            let prev_map: std::collections::HashMap<u64, Rect> =
+            let prev_map: std::collections::HashMap<PaneId, Rect> =
                 self.prev_visual_pane_rects.iter().copied().collect();
             for &(id, rect) in &visual_pane_rects {
                 if prev_map.get(&id) != Some(&rect) {
@@ -156,12 +176,16 @@ impl App {
         // Always called — assemble_grid has an internal early return when nothing changed.
         // This ensures stale grid vertices are cleared when panes are added/removed.
         {
-            let order: Vec<u64> = visual_pane_rects.iter().map(|(id, _)| *id).collect();
+            let order: Vec<PaneId> = visual_pane_rects.iter().map(|(id, _)| *id).collect();
             renderer.assemble_grid(&order);
         }
 
         let t_grid = t0.elapsed();
 
+        // Dim unfocused panes over their grid, before the cursor is drawn
+        // (top layer) so the focused pane's cursor stays crisp.
+        render_inactive_pane_dim(&mut renderer, &visual_pane_rects, focused, self.settings.inactive_pane_dim);
+
         // Always render cursor (overlay layer) — cursor blinks/moves independently
         cursor::render_cursor_and_highlights(
             self, &mut renderer, &p,
@@ -1,6 +1,6 @@
 use unicode_width::UnicodeWidthChar;
 
-use tide_core::{Color, Rect, Renderer, TextStyle, Vec2};
+use tide_core::{Color, PaneId, Rect, Renderer, TextStyle, Vec2};
 
 use crate::pane::PaneKind;
 use crate::theme::*;
@@ -81,7 +81,7 @@ pub(crate) fn render_overlays(
     app: &App,
     renderer: &mut tide_renderer::WgpuRenderer,
     p: &ThemePalette,
-    visual_pane_rects: &[(u64, Rect)],
+    visual_pane_rects: &[(PaneId, Rect)],
 ) {
     render_search_bars(app, renderer, p, visual_pane_rects);
     render_notification_bars(app, renderer, p, visual_pane_rects);
@@ -97,7 +97,7 @@ fn render_search_bars(
     app: &App,
     renderer: &mut tide_renderer::WgpuRenderer,
     p: &ThemePalette,
-    visual_pane_rects: &[(u64, Rect)],
+    visual_pane_rects: &[(PaneId, Rect)],
 ) {
     let search_focus = app.search_focus;
     let cell_size = renderer.cell_size();
@@ -178,7 +178,7 @@ fn render_notification_bars(
     app: &App,
     renderer: &mut tide_renderer::WgpuRenderer,
     p: &ThemePalette,
-    visual_pane_rects: &[(u64, Rect)],
+    visual_pane_rects: &[(PaneId, Rect)],
 ) {
     let cell_size = renderer.cell_size();
 
@@ -330,7 +330,7 @@ fn render_save_as(
     app: &App,
     renderer: &mut tide_renderer::WgpuRenderer,
     p: &ThemePalette,
-    visual_pane_rects: &[(u64, Rect)],
+    visual_pane_rects: &[(PaneId, Rect)],
 ) {
     let save_as = match app.modal.save_as_input {
         Some(ref s) => s,
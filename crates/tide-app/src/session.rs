@@ -1,7 +1,8 @@
 // Session persistence: save/restore workspace state across app restarts.
 
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 use tide_core::{PaneId, SplitDirection};
 use tide_layout::{LayoutSnapshot, SplitLayout};
 
@@ -46,6 +47,12 @@ pub enum SessionLayout {
     Leaf {
         pane_id: u64,
         cwd: Option<PathBuf>,
+        /// Pane kind. Older session files have no kind and restore as terminals.
+        #[serde(default)]
+        kind: SessionPaneKind,
+        /// Open file for editor panes.
+        #[serde(default)]
+        file: Option<PathBuf>,
     },
     Split {
         direction: String, // "horizontal" or "vertical"
@@ -55,10 +62,29 @@ pub enum SessionLayout {
     },
 }
 
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SessionPaneKind {
+    #[default]
+    Terminal,
+    Editor,
+}
+
+/// A pane to recreate on restore, keyed by its freshly allocated PaneId.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct RestoredPane {
+    pub id: PaneId,
+    pub kind: SessionPaneKind,
+    pub cwd: Option<PathBuf>,
+    pub file: Option<PathBuf>,
+}
+
 // ──────────────────────────────────────────────
 // Session file I/O
 // ──────────────────────────────────────────────
 
+/// Quiet period after the last layout/CWD change before autosaving.
+const SESSION_SAVE_DEBOUNCE: std::time::Duration = std::time::Duration::from_secs(2);
+
 fn session_path() -> Option<PathBuf> {
     let config_dir = dirs::config_dir()?;
     Some(config_dir.join("tide").join("session.json"))
@@ -139,6 +165,8 @@ impl Session {
             None => SessionLayout::Leaf {
                 pane_id: 1,
                 cwd: std::env::current_dir().ok(),
+                kind: SessionPaneKind::Terminal,
+                file: None,
             },
         };
 
@@ -147,7 +175,7 @@ impl Session {
 
         Session {
             layout,
-            focused_pane_id: app.focused,
+            focused_pane_id: app.focused.map(PaneId::as_u64),
             show_file_tree: app.ft.visible,
             file_tree_width: app.ft.width,
             dark_mode: app.dark_mode,
@@ -167,13 +195,25 @@ fn snapshot_to_session(snap: &LayoutSnapshot, app: &App) -> SessionLayout {
     match snap {
         LayoutSnapshot::Leaf { tabs, active } => {
             let id = tabs[*active];
-            let cwd = match app.panes.get(&id) {
-                Some(PaneKind::Terminal(pane)) => pane.backend.detect_cwd_fallback(),
-                _ => None,
+            let (kind, cwd, file) = match app.panes.get(&id) {
+                Some(PaneKind::Terminal(pane)) => {
+                    (SessionPaneKind::Terminal, pane.backend.detect_cwd_fallback(), None)
+                }
+                Some(PaneKind::Editor(pane)) => match pane.editor.file_path() {
+                    Some(path) => (
+                        SessionPaneKind::Editor,
+                        path.parent().map(Path::to_path_buf),
+                        Some(path.to_path_buf()),
+                    ),
+                    None => (SessionPaneKind::Terminal, app.last_cwd.clone(), None),
+                },
+                _ => (SessionPaneKind::Terminal, app.last_cwd.clone(), None),
             };
             SessionLayout::Leaf {
-                pane_id: id,
+                pane_id: id.as_u64(),
                 cwd,
+                kind,
+                file,
             }
         }
         LayoutSnapshot::Split {
@@ -198,10 +238,17 @@ fn snapshot_to_session(snap: &LayoutSnapshot, app: &App) -> SessionLayout {
 // ──────────────────────────────────────────────
 
 impl App {
+    /// Schedule a debounced session save (layout or pane CWDs may have changed).
+    pub(crate) fn schedule_session_save(&mut self) {
+        self.session_save_at =
+            Some(std::time::Instant::now() + SESSION_SAVE_DEBOUNCE);
+    }
+
     pub(crate) fn restore_from_session(&mut self, session: Session) -> bool {
-        // Rebuild layout tree from session, collecting pane info
-        let mut pane_infos: Vec<(PaneId, Option<PathBuf>)> = Vec::new();
-        let snap = match session_to_snapshot(&session.layout, &mut pane_infos) {
+        // Rebuild layout tree from session with fresh PaneIds, collecting pane info
+        let mut restored: Vec<RestoredPane> = Vec::new();
+        let mut id_map: HashMap<u64, PaneId> = HashMap::new();
+        let snap = match session_to_snapshot(&session.layout, &mut restored, &mut id_map) {
             Some(s) => s,
             None => return false,
         };
@@ -211,7 +258,7 @@ impl App {
         // Apply dark mode early so pane creation uses the correct palette
         self.dark_mode = session.dark_mode;
 
-        // Create terminal panes
+        // Create panes
         let cell_size = self.cell_size();
         let logical = self.logical_size();
         let cols = if cell_size.width > 0.0 {
@@ -225,15 +272,35 @@ impl App {
             24
         };
 
-        for (pane_id, cwd) in &pane_infos {
-            match crate::pane::TerminalPane::with_cwd(*pane_id, cols, rows, cwd.clone(), self.dark_mode) {
-                Ok(pane) => {
+        for info in &restored {
+            if info.kind == SessionPaneKind::Editor {
+                if let Some(path) = &info.file {
+                    match crate::editor_pane::EditorPane::open(info.id, path) {
+                        Ok(mut pane) => {
+                            self.apply_editor_settings(&mut pane);
+                            self.panes.insert(info.id, PaneKind::Editor(pane));
+                            self.ime.pending_creates.push(info.id);
+                            self.watch_file(path);
+                            continue;
+                        }
+                        Err(e) => {
+                            // File is gone: fall back to a terminal in its directory
+                            log::warn!("Failed to reopen {:?} from session: {}", path, e);
+                        }
+                    }
+                }
+            }
+
+            let cwd = resolve_restore_cwd(info.cwd.as_deref());
+            match crate::pane::TerminalPane::with_options(info.id, cols, rows, self.terminal_options(cwd), &self.theme()) {
+                Ok(mut pane) => {
+                    self.apply_terminal_settings(&mut pane);
                     self.install_pty_waker(&pane);
-                    self.panes.insert(*pane_id, PaneKind::Terminal(pane));
-                    self.ime.pending_creates.push(*pane_id);
+                    self.panes.insert(info.id, PaneKind::Terminal(pane));
+                    self.ime.pending_creates.push(info.id);
                 }
                 Err(e) => {
-                    log::error!("Failed to create terminal pane {}: {}", pane_id, e);
+                    log::error!("Failed to create terminal pane {}: {}", info.id, e);
                     return false;
                 }
             }
@@ -248,15 +315,16 @@ impl App {
             _ => crate::LayoutSide::Left,
         };
         // Apply dark mode to renderer
-        let border_color = self.palette().border_color;
+        let theme = self.theme();
         if let Some(renderer) = &mut self.renderer {
-            renderer.clear_color = border_color;
+            renderer.set_theme(&theme);
         }
 
         // Resolve focus: try saved focus, fall back to first tree pane
         let all_pane_ids = self.layout.pane_ids();
         let focus_id = session
             .focused_pane_id
+            .and_then(|id| id_map.get(&id).copied())
             .and_then(|id| {
                 if self.panes.contains_key(&id) {
                     Some(id)
@@ -272,11 +340,12 @@ impl App {
         }
 
         // Initialize file tree
-        let cwd = pane_infos
+        let cwd = restored
             .first()
-            .and_then(|(_, c)| c.clone())
+            .and_then(|p| resolve_restore_cwd(p.cwd.as_deref()))
             .unwrap_or_else(|| std::env::current_dir().unwrap_or_else(|_| PathBuf::from("/")));
         let tree = tide_tree::FsTree::new(cwd.clone());
+        self.install_tree_waker(&tree);
         self.ft.tree = Some(tree);
         self.last_cwd = Some(cwd);
 
@@ -295,9 +364,9 @@ impl App {
         };
 
         // Apply dark mode to renderer
-        let border_color = self.palette().border_color;
+        let theme = self.theme();
         if let Some(renderer) = &mut self.renderer {
-            renderer.clear_color = border_color;
+            renderer.set_theme(&theme);
         }
 
         self.create_initial_pane(early_terminal);
@@ -305,15 +374,25 @@ impl App {
 }
 
 /// Convert a `SessionLayout` to a `LayoutSnapshot`, collecting pane info.
-/// Public for testing.
-fn session_to_snapshot(
+/// Saved PaneIds are remapped to fresh sequential ids (starting at 1) so a
+/// stale or hand-edited session file can't produce colliding ids; `id_map`
+/// records saved id → new id for resolving the saved focus.
+pub(crate) fn session_to_snapshot(
     layout: &SessionLayout,
-    pane_infos: &mut Vec<(PaneId, Option<PathBuf>)>,
+    restored: &mut Vec<RestoredPane>,
+    id_map: &mut HashMap<u64, PaneId>,
 ) -> Option<LayoutSnapshot> {
     match layout {
-        SessionLayout::Leaf { pane_id, cwd } => {
-            pane_infos.push((*pane_id, cwd.clone()));
-            Some(LayoutSnapshot::Leaf { tabs: vec![*pane_id], active: 0 })
+        SessionLayout::Leaf { pane_id, cwd, kind, file } => {
+            let id = PaneId::new(restored.len() as u64 + 1);
+            id_map.entry(*pane_id).or_insert(id);
+            restored.push(RestoredPane {
+                id,
+                kind: *kind,
+                cwd: cwd.clone(),
+                file: file.clone(),
+            });
+            Some(LayoutSnapshot::Leaf { tabs: vec![id], active: 0 })
         }
         SessionLayout::Split {
             direction,
@@ -326,8 +405,8 @@ fn session_to_snapshot(
                 "vertical" => SplitDirection::Vertical,
                 _ => return None,
             };
-            let l = session_to_snapshot(left, pane_infos)?;
-            let r = session_to_snapshot(right, pane_infos)?;
+            let l = session_to_snapshot(left, restored, id_map)?;
+            let r = session_to_snapshot(right, restored, id_map)?;
             Some(LayoutSnapshot::Split {
                 direction: dir,
                 ratio: *ratio,
@@ -338,21 +417,37 @@ fn session_to_snapshot(
     }
 }
 
+/// CWD to spawn a restored terminal in. A saved directory that no longer
+/// exists falls back to `$HOME`; no saved directory keeps the shell default.
+pub(crate) fn resolve_restore_cwd(cwd: Option<&Path>) -> Option<PathBuf> {
+    match cwd {
+        Some(dir) if dir.is_dir() => Some(dir.to_path_buf()),
+        Some(_) => dirs::home_dir(),
+        None => None,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    fn terminal_leaf(pane_id: u64, cwd: Option<PathBuf>) -> SessionLayout {
+        SessionLayout::Leaf {
+            pane_id,
+            cwd,
+            kind: SessionPaneKind::Terminal,
+            file: None,
+        }
+    }
+
     #[test]
     fn session_layout_leaf_roundtrip() {
-        let layout = SessionLayout::Leaf {
-            pane_id: 42,
-            cwd: Some(PathBuf::from("/home/user")),
-        };
+        let layout = terminal_leaf(42, Some(PathBuf::from("/home/user")));
         let json = serde_json::to_string(&layout).unwrap();
         let restored: SessionLayout = serde_json::from_str(&json).unwrap();
 
         match restored {
-            SessionLayout::Leaf { pane_id, cwd } => {
+            SessionLayout::Leaf { pane_id, cwd, .. } => {
                 assert_eq!(pane_id, 42);
                 assert_eq!(cwd, Some(PathBuf::from("/home/user")));
             }
@@ -365,14 +460,8 @@ mod tests {
         let layout = SessionLayout::Split {
             direction: "horizontal".to_string(),
             ratio: 0.5,
-            left: Box::new(SessionLayout::Leaf {
-                pane_id: 1,
-                cwd: None,
-            }),
-            right: Box::new(SessionLayout::Leaf {
-                pane_id: 2,
-                cwd: Some(PathBuf::from("/tmp")),
-            }),
+            left: Box::new(terminal_leaf(1, None)),
+            right: Box::new(terminal_leaf(2, Some(PathBuf::from("/tmp")))),
         };
         let json = serde_json::to_string(&layout).unwrap();
         let restored: SessionLayout = serde_json::from_str(&json).unwrap();
@@ -386,7 +475,7 @@ mod tests {
                     _ => panic!("expected Leaf"),
                 }
                 match *right {
-                    SessionLayout::Leaf { pane_id, cwd } => {
+                    SessionLayout::Leaf { pane_id, cwd, .. } => {
                         assert_eq!(pane_id, 2);
                         assert_eq!(cwd, Some(PathBuf::from("/tmp")));
                     }
@@ -400,7 +489,7 @@ mod tests {
     #[test]
     fn session_full_roundtrip() {
         let session = Session {
-            layout: SessionLayout::Leaf { pane_id: 1, cwd: None },
+            layout: terminal_leaf(1, None),
             focused_pane_id: Some(1),
             show_file_tree: true,
             file_tree_width: 250.0,
@@ -423,20 +512,19 @@ mod tests {
 
     #[test]
     fn session_to_snapshot_leaf() {
-        let layout = SessionLayout::Leaf {
-            pane_id: 10,
-            cwd: Some(PathBuf::from("/home")),
-        };
-        let mut pane_infos = Vec::new();
-        let snap = session_to_snapshot(&layout, &mut pane_infos).unwrap();
+        let layout = terminal_leaf(10, Some(PathBuf::from("/home")));
+        let mut restored = Vec::new();
+        let mut id_map = HashMap::new();
+        let snap = session_to_snapshot(&layout, &mut restored, &mut id_map).unwrap();
 
-        assert_eq!(pane_infos.len(), 1);
-        assert_eq!(pane_infos[0].0, 10);
-        assert_eq!(pane_infos[0].1, Some(PathBuf::from("/home")));
+        assert_eq!(restored.len(), 1);
+        assert_eq!(restored[0].id, PaneId::new(1));
+        assert_eq!(restored[0].cwd, Some(PathBuf::from("/home")));
+        assert_eq!(id_map.get(&10), Some(&PaneId::new(1)));
 
         match snap {
             LayoutSnapshot::Leaf { tabs, active } => {
-                assert_eq!(tabs, vec![10]);
+                assert_eq!(tabs, vec![PaneId::new(1)]);
                 assert_eq!(active, 0);
             }
             _ => panic!("expected Leaf"),
@@ -448,13 +536,13 @@ mod tests {
         let layout = SessionLayout::Split {
             direction: "vertical".to_string(),
             ratio: 0.6,
-            left: Box::new(SessionLayout::Leaf { pane_id: 1, cwd: None }),
-            right: Box::new(SessionLayout::Leaf { pane_id: 2, cwd: None }),
+            left: Box::new(terminal_leaf(1, None)),
+            right: Box::new(terminal_leaf(2, None)),
         };
-        let mut pane_infos = Vec::new();
-        let snap = session_to_snapshot(&layout, &mut pane_infos).unwrap();
+        let mut restored = Vec::new();
+        let snap = session_to_snapshot(&layout, &mut restored, &mut HashMap::new()).unwrap();
 
-        assert_eq!(pane_infos.len(), 2);
+        assert_eq!(restored.len(), 2);
 
         match snap {
             LayoutSnapshot::Split { direction, ratio, .. } => {
@@ -470,11 +558,11 @@ mod tests {
         let layout = SessionLayout::Split {
             direction: "diagonal".to_string(),
             ratio: 0.5,
-            left: Box::new(SessionLayout::Leaf { pane_id: 1, cwd: None }),
-            right: Box::new(SessionLayout::Leaf { pane_id: 2, cwd: None }),
+            left: Box::new(terminal_leaf(1, None)),
+            right: Box::new(terminal_leaf(2, None)),
         };
-        let mut pane_infos = Vec::new();
-        assert!(session_to_snapshot(&layout, &mut pane_infos).is_none());
+        let mut restored = Vec::new();
+        assert!(session_to_snapshot(&layout, &mut restored, &mut HashMap::new()).is_none());
     }
 
     #[test]
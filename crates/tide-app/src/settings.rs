@@ -3,6 +3,7 @@
 // on macOS, ~/.config/tide/settings.json on Linux.
 
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::path::PathBuf;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -11,6 +12,154 @@ pub struct TideSettings {
     pub worktree: WorktreeSettings,
     #[serde(default)]
     pub keybindings: Vec<KeybindingOverride>,
+    /// Copy terminal selections to the clipboard on mouse release (X11 primary-style).
+    #[serde(default)]
+    pub copy_on_select: bool,
+    /// Ctrl+C copies when the focused pane has a selection; without one it
+    /// still reaches the shell as an interrupt.
+    #[serde(default)]
+    pub ctrl_c_copies_selection: bool,
+    /// Ctrl+V pastes instead of sending the literal control character.
+    #[serde(default)]
+    pub ctrl_v_pastes: bool,
+    /// Ctrl stands in for Cmd in hotkeys (Ctrl+T opens a tab), for keyboards
+    /// without a Cmd key. Ctrl+C, Ctrl+D, Ctrl+W and the other keys a shell
+    /// relies on still reach the terminal.
+    #[serde(default)]
+    pub ctrl_as_cmd: bool,
+    /// Option/Alt stands in for Cmd in hotkeys.
+    #[serde(default)]
+    pub option_as_cmd: bool,
+    /// Target frame rate cap. Renders triggered by terminal output are
+    /// coalesced into at most one frame per `1 / max_fps`. `0` = uncapped,
+    /// which still presents at the display's refresh rate (vsync).
+    #[serde(default = "default_max_fps")]
+    pub max_fps: u32,
+    /// Reopen the last session's panes and layout on launch (not just after a crash).
+    #[serde(default = "default_restore_session")]
+    pub restore_session: bool,
+    /// Option/Alt+key sends ESC + key (Meta, for Emacs/readline) instead of
+    /// the composed character. `alt_sends_escape` is accepted as an alias.
+    #[serde(default, alias = "alt_sends_escape")]
+    pub option_as_meta: bool,
+    /// Let programs in the terminal set the system clipboard via OSC 52
+    /// (tmux/nvim yank). Off by default: any program could overwrite the clipboard.
+    #[serde(default)]
+    pub allow_osc52: bool,
+    /// Characters that join words for terminal double-click selection, on top
+    /// of letters, digits and `_` (e.g. `"/.-~"` to select whole paths).
+    #[serde(default)]
+    pub word_chars: String,
+    /// Rewrap terminal lines when a pane gets narrower or wider. Off keeps
+    /// long lines clipped instead (fixed-width logs, ASCII art).
+    #[serde(default = "default_terminal_reflow")]
+    pub terminal_reflow: bool,
+    /// Strip control characters other than tab and newline from pasted text
+    /// and from commands Tide types into a terminal, so a hidden `\r` or
+    /// escape sequence can't run anything. Typed keys are never filtered.
+    #[serde(default)]
+    pub safe_paste: bool,
+    /// Window background opacity (0.0–1.0). Below 1.0 the gaps and window
+    /// background show the desktop. Applied at startup.
+    #[serde(default = "default_background_opacity")]
+    pub background_opacity: f32,
+    /// MSAA samples per pixel (1, 2 or 4) for smoother UI edges. Counts the
+    /// GPU can't do fall back to 1. Applied at startup.
+    #[serde(default = "default_msaa_samples")]
+    pub msaa_samples: u32,
+    /// Gamma applied to text coverage in dark mode (1.0 = unchanged). Above
+    /// 1.0 makes thin light-on-dark text heavier, like macOS font smoothing.
+    #[serde(default = "default_text_gamma")]
+    pub text_gamma_dark: f32,
+    /// Gamma applied to text coverage in light mode.
+    #[serde(default = "default_text_gamma")]
+    pub text_gamma_light: f32,
+    /// Scroll further per notch when the mouse wheel is spun quickly.
+    /// Trackpad scrolling is unaffected (the OS already accelerates it).
+    #[serde(default)]
+    pub scroll_acceleration: bool,
+    /// Focus the pane under the pointer after it rests there briefly,
+    /// instead of only on click.
+    #[serde(default)]
+    pub focus_follows_mouse: bool,
+    /// After a window resize, nudge the window so the focused terminal fills
+    /// its pane with whole cells (no dead strip at the right/bottom edge).
+    #[serde(default)]
+    pub snap_to_cells: bool,
+    /// How much to dim panes other than the focused one (0.0–1.0, the alpha
+    /// of a black overlay, white in light mode). `0` turns dimming off.
+    #[serde(default)]
+    pub inactive_pane_dim: f32,
+    /// How often (ms) a terminal's working directory is read from its shell
+    /// process when the shell doesn't report it via OSC 7. `0` turns polling
+    /// off (for shells set up to send OSC 7).
+    #[serde(default = "default_cwd_poll_ms")]
+    pub cwd_poll_ms: u64,
+    /// Extra environment for every new shell, e.g. `{"EDITOR": "nvim"}`.
+    /// Overrides the defaults (`TERM`, `COLORTERM`, `COLORFGBG`,
+    /// `PROMPT_EOL_MARK`, `TIDE_PANE_ID`).
+    #[serde(default)]
+    pub terminal_env: HashMap<String, String>,
+    /// Typing an opening bracket or quote in an editor inserts its closer,
+    /// and typing one over a selection wraps the selection.
+    #[serde(default = "default_editor_auto_close")]
+    pub editor_auto_close: bool,
+    /// What happens when the last pane is closed or its shell exits.
+    #[serde(default)]
+    pub on_last_pane_exit: OnLastPaneExit,
+    /// Close a terminal pane when its shell exits instead of leaving the
+    /// dead-shell overlay. The last pane follows `on_last_pane_exit`.
+    #[serde(default)]
+    pub close_on_shell_exit: bool,
+}
+
+/// Policy for the last pane of a workspace going away (`on_last_pane_exit`).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OnLastPaneExit {
+    /// Closing the last pane closes the workspace, or quits (saving the
+    /// session) if it is the last one. A shell exiting there leaves the
+    /// dead-shell overlay.
+    #[default]
+    CloseWindow,
+    /// Like `CloseWindow`, and the last pane's shell exiting closes the
+    /// window too.
+    CloseWindowOnExit,
+    /// Keep the window open on an exited terminal that relaunches the shell
+    /// on a key press. Closing that placeholder closes the window.
+    KeepOpen,
+}
+
+fn default_restore_session() -> bool {
+    true
+}
+
+fn default_editor_auto_close() -> bool {
+    true
+}
+
+fn default_terminal_reflow() -> bool {
+    true
+}
+
+fn default_max_fps() -> u32 {
+    0
+}
+
+fn default_background_opacity() -> f32 {
+    1.0
+}
+
+fn default_msaa_samples() -> u32 {
+    1
+}
+
+fn default_text_gamma() -> f32 {
+    1.0
+}
+
+fn default_cwd_poll_ms() -> u64 {
+    tide_terminal::DEFAULT_CWD_POLL_INTERVAL.as_millis() as u64
 }
 
 impl Default for TideSettings {
@@ -18,6 +167,31 @@ impl Default for TideSettings {
         Self {
             worktree: WorktreeSettings::default(),
             keybindings: Vec::new(),
+            copy_on_select: false,
+            ctrl_c_copies_selection: false,
+            ctrl_v_pastes: false,
+            ctrl_as_cmd: false,
+            option_as_cmd: false,
+            max_fps: default_max_fps(),
+            restore_session: default_restore_session(),
+            option_as_meta: false,
+            allow_osc52: false,
+            word_chars: String::new(),
+            terminal_reflow: default_terminal_reflow(),
+            safe_paste: false,
+            background_opacity: default_background_opacity(),
+            msaa_samples: default_msaa_samples(),
+            text_gamma_dark: default_text_gamma(),
+            text_gamma_light: default_text_gamma(),
+            scroll_acceleration: false,
+            focus_follows_mouse: false,
+            snap_to_cells: false,
+            inactive_pane_dim: 0.0,
+            cwd_poll_ms: default_cwd_poll_ms(),
+            terminal_env: HashMap::new(),
+            editor_auto_close: default_editor_auto_close(),
+            on_last_pane_exit: OnLastPaneExit::default(),
+            close_on_shell_exit: false,
         }
     }
 }
@@ -177,6 +351,65 @@ pub fn save_settings(settings: &TideSettings) {
     }
 }
 
+pub fn theme_path() -> Option<PathBuf> {
+    let config_dir = dirs::config_dir()?;
+    Some(config_dir.join("tide").join("theme.toml"))
+}
+
+/// Load the user theme. Missing file = None; a malformed file is logged
+/// (with line and key) and ignored so the built-in theme still applies.
+pub fn load_theme() -> Option<tide_core::Theme> {
+    let path = theme_path()?;
+    let data = std::fs::read_to_string(&path).ok()?;
+    match tide_core::Theme::from_toml(&data) {
+        Ok(theme) => Some(theme),
+        Err(e) => {
+            log::warn!("Failed to parse {}: {}", path.display(), e);
+            None
+        }
+    }
+}
+
+/// Write `theme` as a theme.toml template unless the user already has one.
+pub fn save_theme_template(theme: &tide_core::Theme) {
+    let Some(path) = theme_path() else { return };
+    if path.exists() {
+        return;
+    }
+    if let Some(parent) = path.parent() {
+        if let Err(e) = std::fs::create_dir_all(parent) {
+            log::error!("Failed to create config dir {}: {}", parent.display(), e);
+            return;
+        }
+    }
+    if let Err(e) = std::fs::write(&path, theme.to_toml()) {
+        log::error!("Failed to write {}: {}", path.display(), e);
+    }
+}
+
+/// Which plain-Ctrl clipboard keys the router claims.
+pub fn clipboard_key_policy(settings: &TideSettings) -> tide_input::ClipboardKeyPolicy {
+    tide_input::ClipboardKeyPolicy {
+        ctrl_c_copies_selection: settings.ctrl_c_copies_selection,
+        ctrl_v_pastes: settings.ctrl_v_pastes,
+    }
+}
+
+/// Which modifiers the router merges into Cmd before matching hotkeys.
+pub fn modifier_normalization(settings: &TideSettings) -> tide_core::ModifierNormalization {
+    tide_core::ModifierNormalization { ctrl_as_meta: settings.ctrl_as_cmd, alt_as_meta: settings.option_as_cmd }
+}
+
+/// Which controls terminals strip from pastes and app-written commands.
+/// Multi-line pastes aren't confirmed: there is no prompt for it yet.
+pub fn write_filter(settings: &TideSettings) -> tide_terminal::WriteFilter {
+    if settings.safe_paste {
+        tide_terminal::WriteFilter { confirm_on_newline: false, ..tide_terminal::WriteFilter::SAFE_PASTE }
+    } else {
+        tide_terminal::WriteFilter::NONE
+    }
+}
+
 /// Build a KeybindingMap from settings overrides.
 pub fn build_keybinding_map(settings: &TideSettings) -> tide_input::KeybindingMap {
     if settings.keybindings.is_empty() {
@@ -1,6 +1,6 @@
 // UI theme constants — dark/light mode with warm neutral palette.
 
-use tide_core::Color;
+use tide_core::{Color, Theme};
 
 // ──────────────────────────────────────────────
 // ThemePalette — all mode-dependent colors
@@ -123,24 +123,55 @@ pub struct ThemePalette {
     pub git_conflict: Color,
 }
 
+impl ThemePalette {
+    /// This palette with the shared colors taken from `theme` (a user
+    /// theme.toml), so the chrome matches what terminals and editors draw.
+    /// The built-in palette for the theme's mode, overlaid with its colors.
+    pub fn for_theme(theme: &Theme) -> Self {
+        let base = if theme.dark { DARK } else { LIGHT };
+        base.with_theme(theme)
+    }
+
+    pub fn with_theme(mut self, theme: &Theme) -> Self {
+        self.surface_bg = theme.surface_bg;
+        self.pane_bg = theme.pane_bg;
+        self.file_tree_bg = theme.tree_bg;
+        self.border_color = theme.border;
+        self.border_focused = theme.border_focused;
+        self.border_subtle = theme.border_subtle;
+        self.tree_text = theme.tree_text;
+        self.tree_dir = theme.tree_dir;
+        self.tree_dir_icon = theme.tree_dir_icon;
+        self.tree_icon = theme.tree_icon;
+        self.selection = theme.selection;
+        self.cursor_accent = theme.cursor;
+        self
+    }
+}
+
+/// Shared base themes (tide-core). Surface, border, tree, selection and
+/// cursor colors come from here; the rest of the chrome palette is app-only.
+const DARK_BASE: Theme = Theme::dark();
+const LIGHT_BASE: Theme = Theme::light();
+
 // ──────────────────────────────────────────────
 // DARK palette — deep black, monochrome, high contrast
 // ──────────────────────────────────────────────
 
 pub static DARK: ThemePalette = ThemePalette {
     // Surface — #0A0A0B base, #0E0E10 pane, #111113 panels
-    surface_bg:     Color::new(0.039, 0.039, 0.043, 1.0),
-    pane_bg:        Color::new(0.055, 0.055, 0.063, 1.0),
-    file_tree_bg:   Color::new(0.067, 0.067, 0.075, 1.0),
-    border_color:   Color::new(0.039, 0.039, 0.043, 1.0),
-    border_focused: Color::new(0.769, 0.722, 0.651, 0.50),
-    border_subtle:  Color::new(0.122, 0.122, 0.137, 1.0),
+    surface_bg:     DARK_BASE.surface_bg,
+    pane_bg:        DARK_BASE.pane_bg,
+    file_tree_bg:   DARK_BASE.tree_bg,
+    border_color:   DARK_BASE.border,
+    border_focused: DARK_BASE.border_focused,
+    border_subtle:  DARK_BASE.border_subtle,
 
     // Text — warm neutral
-    tree_text:      Color::new(0.545, 0.545, 0.565, 1.0),   // file names #8B8B90
-    tree_dir:       Color::new(0.678, 0.678, 0.690, 1.0),   // folder names #ADADB0
-    tree_dir_icon:  Color::new(0.831, 0.784, 0.714, 1.0),   // folder icons #D4C8B6
-    tree_icon:      Color::new(0.290, 0.290, 0.306, 1.0),   // file icons #4A4A4E
+    tree_text:      DARK_BASE.tree_text,   // file names #8B8B90
+    tree_dir:       DARK_BASE.tree_dir,   // folder names #ADADB0
+    tree_dir_icon:  DARK_BASE.tree_dir_icon,   // folder icons #D4C8B6
+    tree_icon:      DARK_BASE.tree_icon,   // file icons #4A4A4E
     tree_row_active: Color::new(0.102, 0.102, 0.114, 1.0),  // expanded folder row bg #1A1A1D
 
     // Tab bar
@@ -186,10 +217,10 @@ pub static DARK: ThemePalette = ThemePalette {
     ime_preedit_fg: Color::new(0.95, 0.95, 0.95, 1.0),
 
     // Selection
-    selection: Color::new(1.0, 1.0, 1.0, 0.25),
+    selection: DARK_BASE.selection,
 
     // Cursor accent
-    cursor_accent: Color::new(1.0, 1.0, 1.0, 1.0),
+    cursor_accent: DARK_BASE.cursor,
 
     // Conflict bar
     conflict_bar_bg:       Color::new(0.18, 0.15, 0.06, 1.0),
@@ -246,18 +277,18 @@ pub static DARK: ThemePalette = ThemePalette {
 
 pub static LIGHT: ThemePalette = ThemePalette {
     // Surface — warm beige tones
-    surface_bg:     Color::new(0.96, 0.94, 0.92, 1.0),  // #F5F0EB warm off-white
-    pane_bg:        Color::new(0.94, 0.92, 0.89, 1.0),  // #F0EBE3 slightly warmer
-    file_tree_bg:   Color::new(0.92, 0.89, 0.86, 1.0),  // #EBE3DB light tan
-    border_color:   Color::new(0.89, 0.86, 0.82, 1.0),  // #E3DCD1 warm beige gap
-    border_focused: Color::new(0.55, 0.38, 0.18, 0.55), // chestnut brown accent
-    border_subtle:  Color::new(0.40, 0.30, 0.15, 0.08), // warm subtle
+    surface_bg:     LIGHT_BASE.surface_bg,  // #F5F0EB warm off-white
+    pane_bg:        LIGHT_BASE.pane_bg,  // #F0EBE3 slightly warmer
+    file_tree_bg:   LIGHT_BASE.tree_bg,  // #EBE3DB light tan
+    border_color:   LIGHT_BASE.border,  // #E3DCD1 warm beige gap
+    border_focused: LIGHT_BASE.border_focused, // chestnut brown accent
+    border_subtle:  LIGHT_BASE.border_subtle, // warm subtle
 
     // Text — warm dark brown (high contrast)
-    tree_text:      Color::new(0.22, 0.18, 0.13, 1.0),  // dark warm brown
-    tree_dir:       Color::new(0.14, 0.11, 0.07, 1.0),  // near-black brown
-    tree_dir_icon:  Color::new(0.40, 0.30, 0.18, 1.0),  // chestnut icon
-    tree_icon:      Color::new(0.48, 0.42, 0.34, 1.0),  // muted warm
+    tree_text:      LIGHT_BASE.tree_text,  // dark warm brown
+    tree_dir:       LIGHT_BASE.tree_dir,  // near-black brown
+    tree_dir_icon:  LIGHT_BASE.tree_dir_icon,  // chestnut icon
+    tree_icon:      LIGHT_BASE.tree_icon,  // muted warm
     tree_row_active: Color::new(0.88, 0.85, 0.80, 1.0), // warm active row
 
     // Tab bar — warm tones (high contrast)
@@ -303,10 +334,10 @@ pub static LIGHT: ThemePalette = ThemePalette {
     ime_preedit_fg: Color::new(0.10, 0.08, 0.05, 1.0),  // warm dark
 
     // Selection — soft warm highlight
-    selection: Color::new(0.55, 0.45, 0.20, 0.18),
+    selection: LIGHT_BASE.selection,
 
     // Cursor accent — chestnut brown
-    cursor_accent: Color::new(0.30, 0.22, 0.12, 1.0),
+    cursor_accent: LIGHT_BASE.cursor,
 
     // Conflict bar
     conflict_bar_bg:       Color::new(1.0, 0.96, 0.84, 1.0),
@@ -368,6 +399,8 @@ pub const PANE_CORNER_RADIUS: f32 = 6.0;
 pub const FILE_TREE_LINE_SPACING: f32 = 1.5;
 pub const FILE_TREE_ROW_RADIUS: f32 = 6.0;
 pub const FILE_TREE_WIDTH: f32 = 240.0;
+pub const FILE_TREE_MIN_WIDTH: f32 = 120.0;
+pub const FILE_TREE_MIN_PANE_AREA: f32 = 100.0;
 
 pub const TAB_BAR_HEIGHT: f32 = 32.0;
 
@@ -377,6 +410,12 @@ pub const PANE_CLOSE_SIZE: f32 = 14.0;
 pub const DROP_PREVIEW_BORDER_WIDTH: f32 = 2.0;
 pub const SWAP_PREVIEW_BORDER_WIDTH: f32 = 3.0;
 pub const DRAG_THRESHOLD: f32 = 5.0;
+/// Interval between auto-scroll steps while drag-selecting past a terminal pane edge.
+pub const SELECTION_AUTOSCROLL_INTERVAL: std::time::Duration = std::time::Duration::from_millis(50);
+/// Max time between two clicks on the same terminal cell to count as a double-click.
+pub const DOUBLE_CLICK_INTERVAL: std::time::Duration = std::time::Duration::from_millis(400);
+/// How long the pointer must rest on a pane before focus-follows-mouse focuses it.
+pub const FOCUS_FOLLOWS_MOUSE_DWELL: std::time::Duration = std::time::Duration::from_millis(150);
 
 pub const SCROLLBAR_WIDTH: f32 = 6.0;
 pub const SCROLLBAR_WIDTH_HOVER: f32 = 10.0;
@@ -773,9 +773,9 @@ pub(crate) struct FileTreeRenameState {
 pub(crate) struct ImeState {
     pub composing: bool,
     pub preedit: String,
-    pub last_target: Option<u64>,
-    pub pending_creates: Vec<u64>,
-    pub pending_removes: Vec<u64>,
+    pub last_target: Option<PaneId>,
+    pub pending_creates: Vec<PaneId>,
+    pub pending_removes: Vec<PaneId>,
     pub cursor_dirty: bool,
 }
 
@@ -861,7 +861,16 @@ impl ModalStack {
 pub(crate) struct InteractionState {
     pub pane_drag: super::PaneDragState,
     pub scroll_accumulator: std::collections::HashMap<PaneId, f32>,
+    pub scroll_acceleration: tide_input::ScrollAcceleration,
     pub mouse_left_pressed: bool,
+    /// Terminal pane with an in-progress drag-selection.
+    pub selection_drag: Option<PaneId>,
+    /// Next auto-scroll tick while drag-selecting past a terminal pane edge.
+    pub selection_autoscroll_at: Option<std::time::Instant>,
+    /// Hovered pane waiting to take focus (focus-follows-mouse), and when.
+    pub focus_follow_at: Option<(PaneId, std::time::Instant)>,
+    /// Last terminal click (time, pane, screen cell), for double-click detection.
+    pub last_click: Option<(std::time::Instant, PaneId, (usize, usize))>,
     pub scrollbar_dragging: Option<PaneId>,
     pub scrollbar_drag_rect: Option<Rect>,
     pub hover_target: Option<super::HoverTarget>,
@@ -872,7 +881,12 @@ impl InteractionState {
         Self {
             pane_drag: super::PaneDragState::Idle,
             scroll_accumulator: std::collections::HashMap::new(),
+            scroll_acceleration: tide_input::ScrollAcceleration::new(),
             mouse_left_pressed: false,
+            selection_drag: None,
+            selection_autoscroll_at: None,
+            focus_follow_at: None,
+            last_click: None,
             scrollbar_dragging: None,
             scrollbar_drag_rect: None,
             hover_target: None,
@@ -1181,7 +1195,7 @@ mod tests {
     #[test]
     fn save_as_resolve_path() {
         let sa = SaveAsInput {
-            pane_id: 1,
+            pane_id: PaneId::new(1),
             filename: InputLine::with_text("test.rs".into()),
             directory: InputLine::with_text("/tmp".into()),
             active_field: SaveAsField::Filename,
@@ -1193,7 +1207,7 @@ mod tests {
     #[test]
     fn save_as_empty_filename() {
         let sa = SaveAsInput {
-            pane_id: 1,
+            pane_id: PaneId::new(1),
             filename: InputLine::new(),
             directory: InputLine::with_text("/tmp".into()),
             active_field: SaveAsField::Filename,
@@ -1205,7 +1219,7 @@ mod tests {
     #[test]
     fn save_as_absolute_filename() {
         let sa = SaveAsInput {
-            pane_id: 1,
+            pane_id: PaneId::new(1),
             filename: InputLine::with_text("/abs/path.rs".into()),
             directory: InputLine::with_text("/tmp".into()),
             active_field: SaveAsField::Filename,
@@ -1217,7 +1231,7 @@ mod tests {
     #[test]
     fn save_as_toggle_field() {
         let mut sa = SaveAsInput {
-            pane_id: 1,
+            pane_id: PaneId::new(1),
             filename: InputLine::new(),
             directory: InputLine::new(),
             active_field: SaveAsField::Filename,
@@ -1273,9 +1287,9 @@ mod tests {
     #[test]
     fn ime_state_pending_queues() {
         let mut ime = ImeState::new();
-        ime.pending_creates.push(1);
-        ime.pending_creates.push(2);
-        ime.pending_removes.push(3);
+        ime.pending_creates.push(PaneId::new(1));
+        ime.pending_creates.push(PaneId::new(2));
+        ime.pending_removes.push(PaneId::new(3));
         assert_eq!(ime.pending_creates.len(), 2);
         assert_eq!(ime.pending_removes.len(), 1);
     }
@@ -1348,10 +1362,10 @@ mod tests {
     #[test]
     fn render_cache_invalidate_pane() {
         let mut rc = RenderCache::new();
-        rc.pane_generations.insert(42, 100);
+        rc.pane_generations.insert(PaneId::new(42), 100);
         rc.needs_redraw = false;
-        rc.invalidate_pane(42);
-        assert!(!rc.pane_generations.contains_key(&42));
+        rc.invalidate_pane(PaneId::new(42));
+        assert!(!rc.pane_generations.contains_key(&PaneId::new(42)));
         assert!(rc.needs_redraw);
     }
 
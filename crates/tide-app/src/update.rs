@@ -6,7 +6,7 @@ use std::sync::mpsc;
 
 use notify::{self, Watcher};
 
-use tide_core::TerminalBackend;
+use tide_core::{PaneEvent, PaneId, TerminalBackend};
 
 use crate::pane::PaneKind;
 use crate::search;
@@ -67,7 +67,10 @@ impl App {
         // skip non-critical work (browser sync, file tree, badge updates)
         // to keep drag and resize interactions smooth.
         let now = std::time::Instant::now();
-        let is_rapid = now.duration_since(self.last_frame) < std::time::Duration::from_millis(8);
+        let is_rapid = self
+            .redraw
+            .last_frame()
+            .is_some_and(|last| now.duration_since(last) < std::time::Duration::from_millis(8));
 
         // Process PTY output for terminal panes only
         for pane in self.panes.values_mut() {
@@ -98,7 +101,6 @@ impl App {
         // Keep file tree/CWD in sync with terminal output (works for RedrawRequested path too).
         // Skip during rapid updates — these are non-critical and can run on the next calm frame.
         if had_terminal_output && !is_rapid {
-            self.update_file_tree_cwd();
             self.update_terminal_badges();
 
             if let Some(ref tx) = self.git_poll_cwd_tx {
@@ -293,6 +295,8 @@ impl App {
             // Next natural chrome rebuild will use the correct final value.
             self.ft.scroll = self.ft.scroll_target;
         }
+        // Keep frames coming until the scroll settles, even with nothing dirty
+        self.redraw.set_animating((self.ft.scroll_target - self.ft.scroll).abs() > SCROLL_SNAP);
 
         // Consume git info from background poller (non-blocking).
         // Skip during rapid updates — badge refresh is cosmetic, not critical.
@@ -309,32 +313,102 @@ impl App {
         // This detects dead shells in both active and background workspaces.
         if now.duration_since(self.last_child_check) > std::time::Duration::from_secs(2) {
             self.last_child_check = now;
-            // Active workspace panes
-            let mut newly_dead: Vec<u64> = Vec::new();
-            for (&id, pane) in self.panes.iter_mut() {
+            self.check_child_exits();
+        }
+
+        self.drain_pane_events();
+    }
+
+    /// Mark terminals whose shell exited, in every workspace. With
+    /// `close_on_shell_exit` they ask to be closed (`PaneEvent::RequestClose`,
+    /// drained once their workspace is active).
+    pub(crate) fn check_child_exits(&mut self) {
+        let close_on_exit = self.settings.close_on_shell_exit;
+        // Active workspace panes
+        let mut newly_dead: Vec<PaneId> = Vec::new();
+        for (&id, pane) in self.panes.iter_mut() {
+            if let PaneKind::Terminal(t) = pane {
+                if !t.child_dead && !t.backend.is_child_alive() {
+                    t.mark_child_dead(close_on_exit);
+                    newly_dead.push(id);
+                }
+            }
+        }
+        if !newly_dead.is_empty() {
+            for id in &newly_dead {
+                self.cache.invalidate_pane(*id);
+            }
+            self.cache.invalidate_chrome();
+        }
+        // The last pane's shell exiting leaves the dead-shell overlay
+        // unless the policy closes the window or swaps in a placeholder.
+        let pane_ids = self.layout.pane_ids();
+        if let [last] = pane_ids[..] {
+            if newly_dead.contains(&last) {
+                let outcome = crate::action::last_pane_exit_outcome(
+                    self.settings.on_last_pane_exit,
+                    crate::action::PaneExit::ShellExited,
+                    1,
+                    self.ws.workspaces.len(),
+                );
+                self.apply_pane_exit_outcome(last, outcome);
+            }
+        }
+        // Background workspace panes
+        for ws in &mut self.ws.workspaces {
+            for pane in ws.panes.values_mut() {
                 if let PaneKind::Terminal(t) = pane {
                     if !t.child_dead && !t.backend.is_child_alive() {
-                        t.child_dead = true;
-                        newly_dead.push(id);
+                        t.mark_child_dead(close_on_exit);
                     }
                 }
             }
-            if !newly_dead.is_empty() {
-                for id in &newly_dead {
-                    self.cache.invalidate_pane(*id);
-                }
-                self.cache.invalidate_chrome();
-            }
-            // Background workspace panes
-            for ws in &mut self.ws.workspaces {
-                for pane in ws.panes.values_mut() {
-                    if let PaneKind::Terminal(t) = pane {
-                        if !t.child_dead && !t.backend.is_child_alive() {
-                            t.child_dead = true;
+        }
+    }
+
+    /// Act on the requests panes queued since the last tick (`PaneEvent`).
+    pub(crate) fn drain_pane_events(&mut self) {
+        let mut events = Vec::new();
+        for (&id, pane) in self.panes.iter_mut() {
+            events.extend(pane.take_events().into_iter().map(|event| (id, event)));
+        }
+
+        let mut cwd_changed = false;
+        for (id, event) in events {
+            match event {
+                PaneEvent::CwdChanged(_) => cwd_changed = true,
+                PaneEvent::OpenFile(path, at) => {
+                    self.open_editor_pane(path);
+                    if let (Some((line, col)), Some(active_id)) = (at, self.focused) {
+                        let visible_rows = self.visible_editor_size(active_id).0;
+                        if let Some(PaneKind::Editor(pane)) = self.panes.get_mut(&active_id) {
+                            pane.editor.go_to(tide_editor::EditorPosition { line, col }, visible_rows.max(30));
+                            self.cache.invalidate_pane(active_id);
                         }
                     }
                 }
+                PaneEvent::SetTitle(title) => {
+                    // Editor, diff and browser titles come from their content.
+                    // An empty title (reset) falls back to the CWD.
+                    if let Some(PaneKind::Terminal(pane)) = self.panes.get_mut(&id) {
+                        pane.title = (!title.is_empty()).then_some(title);
+                        self.cache.invalidate_chrome();
+                    }
+                }
+                PaneEvent::RequestClose => {
+                    // A last pane whose shell exited was already handed to
+                    // `on_last_pane_exit` by the child check.
+                    let shell_exited = matches!(self.panes.get(&id), Some(PaneKind::Terminal(t)) if t.child_dead);
+                    let last = self.layout.pane_ids().len() <= 1;
+                    if self.panes.contains_key(&id) && !(shell_exited && last) {
+                        self.close_specific_pane(id);
+                    }
+                }
             }
         }
+        // The file tree picks the terminal to follow (the focused one first).
+        if cwd_changed {
+            self.update_file_tree_cwd();
+        }
     }
 }
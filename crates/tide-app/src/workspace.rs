@@ -71,6 +71,7 @@ impl App {
         self.cache.pane_generations.clear();
         self.cache.invalidate_chrome();
         self.ime.cursor_dirty = true;
+        self.schedule_session_save();
         self.compute_layout();
         self.update_file_tree_cwd();
         self.sync_browser_webview_frames();
@@ -108,6 +109,7 @@ impl App {
         self.visual_pane_rects.clear();
         self.cache.pane_generations.clear();
         self.cache.invalidate_chrome();
+        self.schedule_session_save();
         self.compute_layout();
         self.update_file_tree_cwd();
     }
@@ -186,6 +188,7 @@ impl App {
         self.cache.pane_generations.clear();
         self.cache.invalidate_chrome();
         self.ime.cursor_dirty = true;
+        self.schedule_session_save();
         self.compute_layout();
         self.update_file_tree_cwd();
         self.sync_browser_webview_frames();
@@ -214,29 +217,29 @@ mod tests {
             panes: HashMap::new(),
         });
         app.ws.active = 0;
-        app.focused = Some(42);
+        app.focused = Some(PaneId::new(42));
 
         // Save: swaps app.focused ↔ ws[0].focused
         app.save_active_workspace();
-        assert_eq!(app.ws.workspaces[0].focused, Some(42));
+        assert_eq!(app.ws.workspaces[0].focused, Some(PaneId::new(42)));
         assert_eq!(app.focused, None); // swapped out
 
         // Load: swaps back
         app.load_active_workspace();
-        assert_eq!(app.focused, Some(42));
+        assert_eq!(app.focused, Some(PaneId::new(42)));
         assert_eq!(app.ws.workspaces[0].focused, None);
     }
 
     #[test]
     fn save_load_empty_workspaces_is_noop() {
         let mut app = test_app();
-        app.focused = Some(10);
+        app.focused = Some(PaneId::new(10));
 
         // No workspaces — should not panic
         app.save_active_workspace();
-        assert_eq!(app.focused, Some(10)); // unchanged
+        assert_eq!(app.focused, Some(PaneId::new(10))); // unchanged
         app.load_active_workspace();
-        assert_eq!(app.focused, Some(10)); // unchanged
+        assert_eq!(app.focused, Some(PaneId::new(10))); // unchanged
     }
 
     #[test]
@@ -259,29 +262,29 @@ mod tests {
 
         // Set up WS1 as active with focused pane 100
         app.ws.active = 0;
-        app.focused = Some(100);
+        app.focused = Some(PaneId::new(100));
 
         // Save WS1's state, switch to WS2
         // First save WS2 state manually so there's something to load
         app.save_active_workspace();
         app.ws.active = 1;
-        app.focused = Some(200);
+        app.focused = Some(PaneId::new(200));
         app.save_active_workspace();
 
         // Now load WS1 back
         app.ws.active = 0;
         app.load_active_workspace();
-        assert_eq!(app.focused, Some(100));
+        assert_eq!(app.focused, Some(PaneId::new(100)));
 
         // Switch to WS2 via the method
         app.switch_workspace(1);
         assert_eq!(app.ws.active, 1);
-        assert_eq!(app.focused, Some(200));
+        assert_eq!(app.focused, Some(PaneId::new(200)));
 
         // Switch back to WS1
         app.switch_workspace(0);
         assert_eq!(app.ws.active, 0);
-        assert_eq!(app.focused, Some(100));
+        assert_eq!(app.focused, Some(PaneId::new(100)));
     }
 
     #[test]
@@ -294,12 +297,12 @@ mod tests {
             panes: HashMap::new(),
         });
         app.ws.active = 0;
-        app.focused = Some(42);
+        app.focused = Some(PaneId::new(42));
 
         let gen_before = app.cache.chrome_generation;
         app.switch_workspace(0); // same index
         // Should not have changed anything
-        assert_eq!(app.focused, Some(42));
+        assert_eq!(app.focused, Some(PaneId::new(42)));
         assert_eq!(app.cache.chrome_generation, gen_before);
     }
 
@@ -313,10 +316,10 @@ mod tests {
             panes: HashMap::new(),
         });
         app.ws.active = 0;
-        app.focused = Some(42);
+        app.focused = Some(PaneId::new(42));
 
         app.switch_workspace(99); // out of bounds
-        assert_eq!(app.focused, Some(42));
+        assert_eq!(app.focused, Some(PaneId::new(42)));
         assert_eq!(app.ws.active, 0);
     }
 
@@ -348,11 +351,11 @@ mod tests {
         app.ws.workspaces.push(Workspace {
             name: "WS2".into(),
             layout: SplitLayout::new(),
-            focused: Some(200),
+            focused: Some(PaneId::new(200)),
             panes: HashMap::new(),
         });
         app.ws.active = 0;
-        app.focused = Some(100);
+        app.focused = Some(PaneId::new(100));
 
         // Close the first workspace
         app.close_workspace();
@@ -361,7 +364,7 @@ mod tests {
         assert_eq!(app.ws.active, 0);
         assert_eq!(app.ws.workspaces[0].name, "WS2");
         // After close, the remaining workspace's state is loaded
-        assert_eq!(app.focused, Some(200));
+        assert_eq!(app.focused, Some(PaneId::new(200)));
     }
 
     #[test]
@@ -531,10 +534,10 @@ mod tests {
             panes: HashMap::new(),
         });
         app.ws.active = 0;
-        app.focused = Some(100);
+        app.focused = Some(PaneId::new(100));
         app.save_active_workspace();
         app.ws.active = 1;
-        app.focused = Some(200);
+        app.focused = Some(PaneId::new(200));
         app.save_active_workspace();
         app.ws.active = 0;
         app.load_active_workspace();
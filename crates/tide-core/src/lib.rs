@@ -1,4 +1,9 @@
+use std::collections::BTreeMap;
+use std::ops::RangeInclusive;
 use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
 
 // ──────────────────────────────────────────────
 // Geometry
@@ -32,9 +37,86 @@ impl Rect {
         let y2 = (self.y + self.height).min(clip.y + clip.height);
         Rect::new(x1, y1, (x2 - x1).max(0.0), (y2 - y1).max(0.0))
     }
+
+    /// True if the rect has no area.
+    pub fn is_empty(&self) -> bool {
+        self.width <= 0.0 || self.height <= 0.0
+    }
+
+    /// True if the two rects overlap (share some area).
+    pub fn intersects(&self, other: &Rect) -> bool {
+        !self.clip_to(other).is_empty()
+    }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq)]
+/// Nested clip regions: each push intersects with the region below it, so a
+/// list inside a panel inside the window clips to all three.
+#[derive(Debug, Clone, Default)]
+pub struct ClipStack {
+    /// Effective (already intersected) region per level.
+    stack: Vec<Rect>,
+}
+
+impl ClipStack {
+    pub fn push(&mut self, clip: Rect) {
+        let effective = match self.stack.last() {
+            Some(top) => clip.clip_to(top),
+            None => clip,
+        };
+        self.stack.push(effective);
+    }
+
+    /// Drop the innermost region. Popping an empty stack does nothing.
+    pub fn pop(&mut self) {
+        self.stack.pop();
+    }
+
+    /// The effective region, `None` when nothing is pushed.
+    pub fn top(&self) -> Option<Rect> {
+        self.stack.last().copied()
+    }
+
+    /// `clip` intersected with the effective region.
+    pub fn apply(&self, clip: Rect) -> Rect {
+        match self.top() {
+            Some(top) => clip.clip_to(&top),
+            None => clip,
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.stack.clear();
+    }
+
+    pub fn depth(&self) -> usize {
+        self.stack.len()
+    }
+}
+
+/// Cells of a grid drawn from the top-left of `pane_rect` that show through
+/// `clip`: the inclusive row and column index ranges, counting cells only
+/// partly inside. `None` when nothing is visible (or the cell size is empty),
+/// so a pane can skip building vertices for clipped rows.
+pub fn visible_cell_range(
+    pane_rect: Rect,
+    clip: Rect,
+    cell_size: Size,
+) -> Option<(RangeInclusive<usize>, RangeInclusive<usize>)> {
+    let visible = pane_rect.clip_to(&clip);
+    if visible.is_empty() || cell_size.width <= 0.0 || cell_size.height <= 0.0 {
+        return None;
+    }
+    let span = |start: f32, len: f32, origin: f32, cell: f32| {
+        let first = ((start - origin) / cell).floor() as usize;
+        let end = ((start + len - origin) / cell).ceil() as usize;
+        first..=end.max(first + 1) - 1
+    };
+    let rows = span(visible.y, visible.height, pane_rect.y, cell_size.height);
+    let cols = span(visible.x, visible.width, pane_rect.x, cell_size.width);
+    Some((rows, cols))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub struct Size {
     pub width: f32,
     pub height: f32,
@@ -46,7 +128,7 @@ impl Size {
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub struct Vec2 {
     pub x: f32,
     pub y: f32,
@@ -62,7 +144,41 @@ impl Vec2 {
 // Identity
 // ──────────────────────────────────────────────
 
-pub type PaneId = u64;
+/// Identifies a pane. A newtype so a pane id can't be mixed up with other
+/// integers (counts, generations, workspace indices); convert with
+/// `From<u64>` / `as_u64()` only at boundaries such as the platform's IME
+/// proxies, which are keyed by `u64`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct PaneId(u64);
+
+impl PaneId {
+    pub const fn new(raw: u64) -> Self {
+        Self(raw)
+    }
+
+    pub const fn as_u64(self) -> u64 {
+        self.0
+    }
+}
+
+impl From<u64> for PaneId {
+    fn from(raw: u64) -> Self {
+        Self(raw)
+    }
+}
+
+impl From<PaneId> for u64 {
+    fn from(id: PaneId) -> Self {
+        id.0
+    }
+}
+
+impl std::fmt::Display for PaneId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
 
 // ──────────────────────────────────────────────
 // Colors
@@ -87,8 +203,302 @@ impl Color {
 
     pub const BLACK: Self = Self::new(0.0, 0.0, 0.0, 1.0);
     pub const WHITE: Self = Self::new(1.0, 1.0, 1.0, 1.0);
+
+    /// Parse `#RRGGBB` or `#RRGGBBAA` (the `#` is optional).
+    pub fn from_hex(hex: &str) -> Option<Self> {
+        let hex = hex.strip_prefix('#').unwrap_or(hex);
+        if !hex.is_ascii() || (hex.len() != 6 && hex.len() != 8) {
+            return None;
+        }
+        let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok().map(|v| v as f32 / 255.0);
+        let a = if hex.len() == 8 { channel(6)? } else { 1.0 };
+        Some(Self::new(channel(0)?, channel(2)?, channel(4)?, a))
+    }
+
+    /// Format as `#RRGGBB`, or `#RRGGBBAA` when not fully opaque.
+    pub fn to_hex(&self) -> String {
+        let byte = |v: f32| (v.clamp(0.0, 1.0) * 255.0).round() as u8;
+        let a = byte(self.a);
+        if a == 255 {
+            format!("#{:02X}{:02X}{:02X}", byte(self.r), byte(self.g), byte(self.b))
+        } else {
+            format!("#{:02X}{:02X}{:02X}{:02X}", byte(self.r), byte(self.g), byte(self.b), a)
+        }
+    }
 }
 
+// ──────────────────────────────────────────────
+// Theme
+// ──────────────────────────────────────────────
+
+/// Base color theme shared by every crate: the renderer's clear color, the
+/// terminal ANSI palette, the editor syntax theme and the file tree colors
+/// all derive from it. App-level chrome builds its full palette on top.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Theme {
+    /// Dark or light variant (selects e.g. the editor syntax theme).
+    pub dark: bool,
+
+    // Backgrounds
+    pub surface_bg: Color,
+    pub pane_bg: Color,
+    pub tree_bg: Color,
+
+    // Borders
+    pub border: Color,         // gap / clear color
+    pub border_focused: Color, // accent bar on focused pane
+    pub border_subtle: Color,
+
+    // File tree
+    pub tree_text: Color,
+    pub tree_dir: Color,
+    pub tree_dir_icon: Color,
+    pub tree_icon: Color,
+
+    // Text
+    pub selection: Color,
+    pub cursor: Color,
+    /// Default terminal foreground.
+    pub foreground: Color,
+    /// ANSI 16: normal colors 0-7, then bright colors 8-15.
+    pub ansi: [Color; 16],
+}
+
+impl Theme {
+    /// Deep black, monochrome, high contrast.
+    pub const fn dark() -> Self {
+        Self {
+            dark: true,
+            surface_bg: Color::new(0.039, 0.039, 0.043, 1.0),
+            pane_bg: Color::new(0.055, 0.055, 0.063, 1.0),
+            tree_bg: Color::new(0.067, 0.067, 0.075, 1.0),
+            border: Color::new(0.039, 0.039, 0.043, 1.0),
+            border_focused: Color::new(0.769, 0.722, 0.651, 0.50),
+            border_subtle: Color::new(0.122, 0.122, 0.137, 1.0),
+            tree_text: Color::new(0.545, 0.545, 0.565, 1.0),
+            tree_dir: Color::new(0.678, 0.678, 0.690, 1.0),
+            tree_dir_icon: Color::new(0.831, 0.784, 0.714, 1.0),
+            tree_icon: Color::new(0.290, 0.290, 0.306, 1.0),
+            selection: Color::new(1.0, 1.0, 1.0, 0.25),
+            cursor: Color::new(1.0, 1.0, 1.0, 1.0),
+            foreground: Color::rgb(0.9, 0.91, 0.95),
+            ansi: [
+                Color::rgb(0.1, 0.1, 0.14),   // Black
+                Color::rgb(1.0, 0.33, 0.33),  // Red #FF5555
+                Color::rgb(0.31, 0.98, 0.48), // Green #50FA7B
+                Color::rgb(0.94, 0.9, 0.55),  // Yellow #F0E68D
+                Color::rgb(0.39, 0.58, 1.0),  // Blue #6495FF
+                Color::rgb(0.74, 0.45, 1.0),  // Magenta #BD73FF
+                Color::rgb(0.35, 0.87, 0.93), // Cyan #59DEED
+                Color::rgb(0.78, 0.8, 0.87),  // White #C7CCDE
+                Color::rgb(0.4, 0.42, 0.53),  // BrightBlack #676B87
+                Color::rgb(1.0, 0.47, 0.42),  // BrightRed #FF786B
+                Color::rgb(0.45, 1.0, 0.6),   // BrightGreen #73FF99
+                Color::rgb(1.0, 0.98, 0.55),  // BrightYellow #FFFA8D
+                Color::rgb(0.53, 0.7, 1.0),   // BrightBlue #87B3FF
+                Color::rgb(0.85, 0.6, 1.0),   // BrightMagenta #D999FF
+                Color::rgb(0.47, 0.94, 1.0),  // BrightCyan #78F0FF
+                Color::rgb(0.95, 0.96, 0.98), // BrightWhite #F2F5FA
+            ],
+        }
+    }
+
+    /// Warm off-white. White/BrightWhite stay light so they work as backgrounds.
+    pub const fn light() -> Self {
+        Self {
+            dark: false,
+            surface_bg: Color::new(0.96, 0.94, 0.92, 1.0),
+            pane_bg: Color::new(0.94, 0.92, 0.89, 1.0),
+            tree_bg: Color::new(0.92, 0.89, 0.86, 1.0),
+            border: Color::new(0.89, 0.86, 0.82, 1.0),
+            border_focused: Color::new(0.55, 0.38, 0.18, 0.55),
+            border_subtle: Color::new(0.40, 0.30, 0.15, 0.08),
+            tree_text: Color::new(0.22, 0.18, 0.13, 1.0),
+            tree_dir: Color::new(0.14, 0.11, 0.07, 1.0),
+            tree_dir_icon: Color::new(0.40, 0.30, 0.18, 1.0),
+            tree_icon: Color::new(0.48, 0.42, 0.34, 1.0),
+            selection: Color::new(0.55, 0.45, 0.20, 0.18),
+            cursor: Color::new(0.30, 0.22, 0.12, 1.0),
+            foreground: Color::rgb(0.10, 0.08, 0.05),
+            ansi: [
+                Color::rgb(0.0, 0.0, 0.0),     // Black
+                Color::rgb(0.68, 0.08, 0.08),  // Red
+                Color::rgb(0.05, 0.40, 0.10),  // Green
+                Color::rgb(0.45, 0.35, 0.0),   // Yellow
+                Color::rgb(0.10, 0.22, 0.65),  // Blue
+                Color::rgb(0.48, 0.15, 0.65),  // Magenta
+                Color::rgb(0.0, 0.35, 0.42),   // Cyan
+                Color::rgb(0.85, 0.83, 0.80),  // White
+                Color::rgb(0.40, 0.38, 0.35),  // BrightBlack
+                Color::rgb(0.75, 0.12, 0.10),  // BrightRed
+                Color::rgb(0.08, 0.48, 0.12),  // BrightGreen
+                Color::rgb(0.52, 0.40, 0.0),   // BrightYellow
+                Color::rgb(0.12, 0.30, 0.75),  // BrightBlue
+                Color::rgb(0.55, 0.22, 0.75),  // BrightMagenta
+                Color::rgb(0.05, 0.45, 0.50),  // BrightCyan
+                Color::rgb(0.95, 0.93, 0.90),  // BrightWhite
+            ],
+        }
+    }
+
+    /// The dark or light theme.
+    pub const fn for_mode(dark: bool) -> Self {
+        if dark { Self::dark() } else { Self::light() }
+    }
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self::dark()
+    }
+}
+
+/// ANSI 16 keys, indexed like `Theme::ansi`.
+const THEME_ANSI_KEYS: [&str; 16] = [
+    "black", "red", "green", "yellow", "blue", "magenta", "cyan", "white",
+    "bright_black", "bright_red", "bright_green", "bright_yellow",
+    "bright_blue", "bright_magenta", "bright_cyan", "bright_white",
+];
+
+impl Theme {
+    fn color_slot_mut(&mut self, key: &str) -> Option<&mut Color> {
+        let slot = match key {
+            "surface_bg" => &mut self.surface_bg,
+            "pane_bg" => &mut self.pane_bg,
+            "tree_bg" => &mut self.tree_bg,
+            "border" => &mut self.border,
+            "border_focused" => &mut self.border_focused,
+            "border_subtle" => &mut self.border_subtle,
+            "tree_text" => &mut self.tree_text,
+            "tree_dir" => &mut self.tree_dir,
+            "tree_dir_icon" => &mut self.tree_dir_icon,
+            "tree_icon" => &mut self.tree_icon,
+            "selection" => &mut self.selection,
+            "cursor" => &mut self.cursor,
+            "foreground" => &mut self.foreground,
+            _ => {
+                let idx = THEME_ANSI_KEYS.iter().position(|k| *k == key)?;
+                &mut self.ansi[idx]
+            }
+        };
+        Some(slot)
+    }
+
+    /// Parse a theme file (TOML, top-level keys only). `dark = true|false`
+    /// picks the built-in theme that supplies every omitted color; colors
+    /// are hex strings (`"#RRGGBB[AA]"`).
+    pub fn from_toml(src: &str) -> Result<Theme, ThemeError> {
+        let line_at = |offset: usize| src[..offset.min(src.len())].matches('\n').count() + 1;
+        let table: BTreeMap<toml::Spanned<String>, toml::Spanned<toml::Value>> =
+            toml::from_str(src).map_err(|e| ThemeError::Syntax {
+                line: e.span().map_or(1, |span| line_at(span.start)),
+                message: e.message().to_string(),
+            })?;
+        // Report the first bad entry in file order, not key order.
+        let mut entries: Vec<_> = table.iter().collect();
+        entries.sort_by_key(|(key, _)| key.span().start);
+
+        let mut dark = true;
+        for (key, value) in &entries {
+            if key.get_ref() == "dark" {
+                dark = value.get_ref().as_bool().ok_or_else(|| ThemeError::InvalidValue {
+                    line: line_at(value.span().start),
+                    key: "dark".to_string(),
+                    value: value.get_ref().to_string(),
+                    expected: "true or false",
+                })?;
+            }
+        }
+
+        let mut theme = Theme::for_mode(dark);
+        for (key, value) in entries {
+            let name = key.get_ref().as_str();
+            if name == "dark" {
+                continue;
+            }
+            let Some(slot) = theme.color_slot_mut(name) else {
+                return Err(ThemeError::UnknownKey { line: line_at(key.span().start), key: name.to_string() });
+            };
+            *slot = value.get_ref().as_str().and_then(Color::from_hex).ok_or_else(|| ThemeError::InvalidValue {
+                line: line_at(value.span().start),
+                key: name.to_string(),
+                value: value.get_ref().to_string(),
+                expected: "a hex color string like \"#RRGGBB\" or \"#RRGGBBAA\"",
+            })?;
+        }
+        Ok(theme)
+    }
+
+    /// Serialize every slot, so the output is a complete editable template.
+    pub fn to_toml(&self) -> String {
+        let mut out = String::from("# Tide theme\n");
+        out.push_str(&format!("dark = {}\n\n", self.dark));
+        let colors = [
+            ("surface_bg", self.surface_bg),
+            ("pane_bg", self.pane_bg),
+            ("tree_bg", self.tree_bg),
+            ("border", self.border),
+            ("border_focused", self.border_focused),
+            ("border_subtle", self.border_subtle),
+            ("tree_text", self.tree_text),
+            ("tree_dir", self.tree_dir),
+            ("tree_dir_icon", self.tree_dir_icon),
+            ("tree_icon", self.tree_icon),
+            ("selection", self.selection),
+            ("cursor", self.cursor),
+            ("foreground", self.foreground),
+        ];
+        for (key, color) in colors {
+            out.push_str(&format!("{key} = \"{}\"\n", color.to_hex()));
+        }
+        out.push_str("\n# ANSI colors\n");
+        for (key, color) in THEME_ANSI_KEYS.iter().zip(self.ansi.iter()) {
+            out.push_str(&format!("{key} = \"{}\"\n", color.to_hex()));
+        }
+        out
+    }
+}
+
+/// Why a theme file failed to load. Every variant carries the 1-based line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ThemeError {
+    Syntax { line: usize, message: String },
+    UnknownKey { line: usize, key: String },
+    InvalidValue { line: usize, key: String, value: String, expected: &'static str },
+}
+
+impl ThemeError {
+    pub fn line(&self) -> usize {
+        match self {
+            ThemeError::Syntax { line, .. }
+            | ThemeError::UnknownKey { line, .. }
+            | ThemeError::InvalidValue { line, .. } => *line,
+        }
+    }
+
+    /// The offending key, if the line got far enough to have one.
+    pub fn key(&self) -> Option<&str> {
+        match self {
+            ThemeError::Syntax { .. } => None,
+            ThemeError::UnknownKey { key, .. } | ThemeError::InvalidValue { key, .. } => Some(key),
+        }
+    }
+}
+
+impl std::fmt::Display for ThemeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ThemeError::Syntax { line, message } => write!(f, "line {line}: {message}"),
+            ThemeError::UnknownKey { line, key } => write!(f, "line {line}: unknown theme key `{key}`"),
+            ThemeError::InvalidValue { line, key, value, expected } => {
+                write!(f, "line {line}: invalid value {value} for `{key}` (expected {expected})")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ThemeError {}
+
 // ──────────────────────────────────────────────
 // Text Styling
 // ──────────────────────────────────────────────
@@ -116,11 +526,224 @@ impl Default for TextStyle {
     }
 }
 
+// ──────────────────────────────────────────────
+// Selection
+// ──────────────────────────────────────────────
+
+/// How a selection covers text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SelectionMode {
+    /// A run of text from one position to another.
+    #[default]
+    Linear,
+    /// The same columns on every line between the corners (Alt+drag).
+    Block,
+}
+
+/// Selection geometry over `(line, col)` positions, shared by terminal and
+/// editor selection rendering. `anchor` is where the selection started and
+/// `head` where it currently ends; either may come first. Columns are
+/// half-open: the column at the far end is not selected.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Selection {
+    pub anchor: (usize, usize),
+    pub head: (usize, usize),
+    pub mode: SelectionMode,
+}
+
+impl Selection {
+    pub fn new(anchor: (usize, usize), head: (usize, usize), mode: SelectionMode) -> Self {
+        Self { anchor, head, mode }
+    }
+
+    /// True when anchor and head coincide (nothing selected).
+    pub fn is_empty(&self) -> bool {
+        self.anchor == self.head
+    }
+
+    /// The selection's `(start, end)` corners: for `Linear`, anchor and head
+    /// in text order; for `Block`, the top-left and bottom-right corners.
+    pub fn normalized(&self) -> ((usize, usize), (usize, usize)) {
+        let (a, h) = (self.anchor, self.head);
+        match self.mode {
+            SelectionMode::Linear => (a.min(h), a.max(h)),
+            SelectionMode::Block => ((a.0.min(h.0), a.1.min(h.1)), (a.0.max(h.0), a.1.max(h.1))),
+        }
+    }
+
+    /// Whether the cell at `(line, col)` is selected.
+    pub fn contains(&self, line: usize, col: usize) -> bool {
+        let (start, end) = self.normalized();
+        match self.mode {
+            SelectionMode::Linear => start <= (line, col) && (line, col) < end,
+            SelectionMode::Block => (start.0..=end.0).contains(&line) && (start.1..end.1).contains(&col),
+        }
+    }
+
+    /// Selected columns per line as `(line, start_col, end_col)`, top to
+    /// bottom. Lines a linear selection passes through run to the end of
+    /// the line, reported as `usize::MAX` for the caller to clamp, and lines
+    /// with nothing selected are skipped. A block yields the same span on
+    /// every line, even when it is zero-width (a caret per line).
+    pub fn rows(&self) -> impl Iterator<Item = (usize, usize, usize)> {
+        let (start, end) = self.normalized();
+        let mode = self.mode;
+        (start.0..=end.0).filter_map(move |line| match mode {
+            SelectionMode::Block => Some((line, start.1, end.1)),
+            SelectionMode::Linear => {
+                let from = if line == start.0 { start.1 } else { 0 };
+                let to = if line == end.0 { end.1 } else { usize::MAX };
+                (from < to).then_some((line, from, to))
+            }
+        })
+    }
+}
+
+// ──────────────────────────────────────────────
+// Redraw scheduling
+// ──────────────────────────────────────────────
+
+/// Shortest frame interval, even when `max_fps` is uncapped.  Coalesces
+/// bursts of wakes (PTY sync, file watcher) that land within a few ms.
+const MIN_FRAME_INTERVAL: Duration = Duration::from_millis(2);
+
+/// How long after a scroll event frames skip the budget.
+const SCROLL_LATENCY_WINDOW: Duration = Duration::from_millis(32);
+
+/// Frame budget for a target frame rate (`0` = uncapped).
+pub fn frame_budget(max_fps: u32) -> Duration {
+    if max_fps == 0 {
+        return MIN_FRAME_INTERVAL;
+    }
+    (Duration::from_secs(1) / max_fps).max(MIN_FRAME_INTERVAL)
+}
+
+/// What an event loop should do until its next wake.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ControlFlow {
+    /// Sleep until the next event.
+    Wait,
+    /// Sleep until the given instant, or an earlier event.
+    WaitUntil(Instant),
+    /// Don't sleep: a frame is due.
+    Poll,
+}
+
+/// Frame pacing for an event loop. The loop reports signals (input sent,
+/// terminal output, scroll, animation, timers, rendered frames) and asks
+/// whether to render now and how long to sleep. Dirty wakes within one
+/// budget window collapse into a single frame at the window's end; recent
+/// input or scroll renders immediately for low latency.
+#[derive(Debug, Clone)]
+pub struct RedrawScheduler {
+    budget: Duration,
+    last_frame: Option<Instant>,
+    /// Input was sent and its echo hasn't arrived yet
+    input_pending: bool,
+    scroll_at: Option<Instant>,
+    animating: bool,
+    timer: Option<Instant>,
+}
+
+impl RedrawScheduler {
+    pub fn new(budget: Duration) -> Self {
+        Self {
+            budget,
+            last_frame: None,
+            input_pending: false,
+            scroll_at: None,
+            animating: false,
+            timer: None,
+        }
+    }
+
+    /// Minimum spacing between frames (see `frame_budget`).
+    pub fn set_budget(&mut self, budget: Duration) {
+        self.budget = budget;
+    }
+
+    pub fn budget(&self) -> Duration {
+        self.budget
+    }
+
+    /// When the last frame was rendered (`None` before the first).
+    pub fn last_frame(&self) -> Option<Instant> {
+        self.last_frame
+    }
+
+    /// Input was sent to a terminal: render without waiting out the budget
+    /// until its echo arrives.
+    pub fn on_input(&mut self) {
+        self.input_pending = true;
+    }
+
+    /// Terminal output arrived: the echo of any pending input is in, so
+    /// frames go back to the budget.
+    pub fn on_output(&mut self) {
+        self.input_pending = false;
+    }
+
+    /// A scroll gesture is in progress.
+    pub fn on_scroll(&mut self, now: Instant) {
+        self.scroll_at = Some(now);
+    }
+
+    /// While animating, a frame is due every budget window even when nothing is dirty.
+    pub fn set_animating(&mut self, animating: bool) {
+        self.animating = animating;
+    }
+
+    /// Wake the loop at `at`; the earliest pending timer wins.
+    pub fn wake_at(&mut self, at: Instant) {
+        self.timer = Some(self.timer.map_or(at, |t| t.min(at)));
+    }
+
+    pub fn on_frame_rendered(&mut self, now: Instant) {
+        self.last_frame = Some(now);
+    }
+
+    fn low_latency(&self, now: Instant) -> bool {
+        self.input_pending
+            || self.scroll_at.is_some_and(|at| now.duration_since(at) < SCROLL_LATENCY_WINDOW)
+    }
+
+    /// `None` when no frame is pending, `Some(ZERO)` to render now, or the
+    /// remaining wait inside the current budget window.
+    pub fn frame_wait(&self, now: Instant, dirty: bool) -> Option<Duration> {
+        if !dirty && !self.animating {
+            return None;
+        }
+        if self.low_latency(now) {
+            return Some(Duration::ZERO);
+        }
+        let since = self.last_frame.map_or(self.budget, |last| now.duration_since(last));
+        Some(self.budget.saturating_sub(since))
+    }
+
+    /// Whether to render a frame now. `dirty` is the caller's redraw flag.
+    pub fn should_render(&self, now: Instant, dirty: bool) -> bool {
+        self.frame_wait(now, dirty) == Some(Duration::ZERO)
+    }
+
+    /// How to sleep until the next frame or timer. Timers that have fired are dropped.
+    pub fn control_flow(&mut self, now: Instant, dirty: bool) -> ControlFlow {
+        self.timer = self.timer.filter(|at| *at > now);
+        match self.frame_wait(now, dirty) {
+            Some(wait) if wait.is_zero() => ControlFlow::Poll,
+            Some(wait) => {
+                let frame_at = now + wait;
+                ControlFlow::WaitUntil(self.timer.map_or(frame_at, |t| t.min(frame_at)))
+            }
+            None => self.timer.map_or(ControlFlow::Wait, ControlFlow::WaitUntil),
+        }
+    }
+}
+
 // ──────────────────────────────────────────────
 // Input
 // ──────────────────────────────────────────────
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum Key {
     Char(char),
     Enter,
@@ -136,11 +759,16 @@ pub enum Key {
     End,
     PageUp,
     PageDown,
+    /// Function keys F1–F24.
     F(u8),
+    /// Insert (Help on Mac keyboards).
     Insert,
+    /// Numeric keypad key: '0'–'9', '.', '+', '-', '*', '/', '='.
+    /// Keypad Enter is reported as `Enter`.
+    Keypad(char),
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
 pub struct Modifiers {
     pub shift: bool,
     pub ctrl: bool,
@@ -148,20 +776,107 @@ pub struct Modifiers {
     pub meta: bool,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+impl Modifiers {
+    /// No modifier held.
+    pub const fn none() -> Self {
+        Self { shift: false, ctrl: false, alt: false, meta: false }
+    }
+
+    pub const fn with_shift(self) -> Self {
+        Self { shift: true, ..self }
+    }
+
+    pub const fn with_ctrl(self) -> Self {
+        Self { ctrl: true, ..self }
+    }
+
+    pub const fn with_alt(self) -> Self {
+        Self { alt: true, ..self }
+    }
+
+    pub const fn with_meta(self) -> Self {
+        Self { meta: true, ..self }
+    }
+
+    /// True when no modifier is held.
+    pub fn is_empty(&self) -> bool {
+        *self == Self::none()
+    }
+
+    /// True when exactly `mods` are held: Cmd+Shift+T is `only(none().with_meta().with_shift())`
+    /// but Cmd+Alt+Shift+T is not.
+    pub fn only(&self, mods: Modifiers) -> bool {
+        *self == mods
+    }
+
+    /// Fold modifiers together as `normalization` asks, for matching
+    /// hotkeys. Left and right keys already arrive as one flag (Right-Cmd
+    /// is Cmd), so this only merges different modifiers.
+    pub fn normalized(self, normalization: ModifierNormalization) -> Self {
+        let mut mods = self;
+        if normalization.ctrl_as_meta && mods.ctrl {
+            mods.ctrl = false;
+            mods.meta = true;
+        }
+        if normalization.alt_as_meta && mods.alt {
+            mods.alt = false;
+            mods.meta = true;
+        }
+        mods
+    }
+}
+
+/// How `Modifiers::normalized` merges modifiers. The default changes nothing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ModifierNormalization {
+    /// Ctrl counts as Cmd (keyboards without a Cmd key).
+    pub ctrl_as_meta: bool,
+    /// Option/Alt counts as Cmd.
+    pub alt_as_meta: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum MouseButton {
     Left,
     Right,
     Middle,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub enum InputEvent {
     KeyPress { key: Key, modifiers: Modifiers },
-    MouseClick { position: Vec2, button: MouseButton },
+    /// `modifiers` are the keys held when the button went down, so chords
+    /// like Cmd+click can be told apart from plain clicks. Defaulted so
+    /// recordings made before the field existed still load.
+    MouseClick {
+        position: Vec2,
+        button: MouseButton,
+        #[serde(default)]
+        modifiers: Modifiers,
+    },
     MouseMove { position: Vec2 },
-    MouseDrag { position: Vec2, button: MouseButton },
-    MouseScroll { delta: f32, position: Vec2 },
+    MouseDrag {
+        position: Vec2,
+        button: MouseButton,
+        #[serde(default)]
+        modifiers: Modifiers,
+    },
+    MouseScroll {
+        delta: f32,
+        position: Vec2,
+        #[serde(default)]
+        modifiers: Modifiers,
+    },
+    /// Trackpad pinch; `scale_delta` is the magnification change (+ = zoom in).
+    /// `ended` marks the gesture's last event.
+    Pinch {
+        scale_delta: f32,
+        position: Vec2,
+        #[serde(default)]
+        ended: bool,
+    },
+    /// Trackpad swipe; each delta is -1.0, 0.0 or 1.0.
+    Swipe { dx: f32, dy: f32 },
     Resize { size: Size },
 }
 
@@ -182,6 +897,9 @@ pub struct TreeEntry {
     pub depth: usize,
     pub is_expanded: bool,
     pub has_children: bool,
+    /// "loading…" row standing in for the children of a directory still
+    /// being read; not a real file (its path is the directory's).
+    pub is_placeholder: bool,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -204,16 +922,113 @@ pub struct TerminalGrid {
     pub cells: Vec<Vec<TerminalCell>>,
 }
 
+impl TerminalGrid {
+    /// The grid's text, one line per row. Wide-char spacers (`'\0'`) become
+    /// spaces and trailing whitespace is trimmed from every row.
+    pub fn to_plain_text(&self) -> String {
+        let lines: Vec<String> = self
+            .cells
+            .iter()
+            .map(|row| {
+                let mut line = String::with_capacity(row.len());
+                for c in row {
+                    if c.character == '\0' {
+                        line.push(' ');
+                    } else {
+                        c.push_to(&mut line);
+                    }
+                }
+                line.trim_end().to_string()
+            })
+            .collect();
+        lines.join("\n")
+    }
+
+    /// The grid as text with SGR escape sequences (truecolor fg/bg, bold, dim,
+    /// italic, underline) reconstructing each cell's style. Spacers are skipped
+    /// since the wide char before them already advances two columns; trailing
+    /// blanks without a background are trimmed, and every styled row ends
+    /// with a reset.
+    pub fn to_ansi(&self) -> String {
+        let mut out = String::new();
+        for (i, row) in self.cells.iter().enumerate() {
+            if i > 0 {
+                out.push('\n');
+            }
+            let end = row
+                .iter()
+                .rposition(|c| {
+                    !(c.character.is_whitespace() || c.character == '\0') || c.style.background.is_some()
+                })
+                .map_or(0, |i| i + 1);
+            let mut current: Option<TextStyle> = None;
+            for cell in &row[..end] {
+                if cell.character == '\0' {
+                    continue;
+                }
+                if current != Some(cell.style) {
+                    push_sgr(&mut out, &cell.style);
+                    current = Some(cell.style);
+                }
+                cell.push_to(&mut out);
+            }
+            if current.is_some() {
+                out.push_str("\x1b[0m");
+            }
+        }
+        out
+    }
+}
+
+/// Append a full SGR sequence (reset first) selecting `style`.
+fn push_sgr(out: &mut String, style: &TextStyle) {
+    use std::fmt::Write;
+
+    let byte = |v: f32| (v.clamp(0.0, 1.0) * 255.0).round() as u8;
+    out.push_str("\x1b[0");
+    if style.bold {
+        out.push_str(";1");
+    }
+    if style.dim {
+        out.push_str(";2");
+    }
+    if style.italic {
+        out.push_str(";3");
+    }
+    if style.underline {
+        out.push_str(";4");
+    }
+    let fg = style.foreground;
+    let _ = write!(out, ";38;2;{};{};{}", byte(fg.r), byte(fg.g), byte(fg.b));
+    if let Some(bg) = style.background {
+        let _ = write!(out, ";48;2;{};{};{}", byte(bg.r), byte(bg.g), byte(bg.b));
+    }
+    out.push('m');
+}
+
 #[derive(Debug, Clone)]
 pub struct TerminalCell {
     pub character: char,
+    /// Zero-width chars that follow `character` in the same cell (combining
+    /// marks, ZWJ and variation selectors), so `e` + U+0301 shows as one `é`.
+    /// Empty for nearly every cell.
+    pub zerowidth: Vec<char>,
     pub style: TextStyle,
 }
 
+impl TerminalCell {
+    /// Append the cell's grapheme cluster (`character` then `zerowidth`).
+    pub fn push_to(&self, out: &mut String) {
+        out.push(self.character);
+        out.extend(&self.zerowidth);
+    }
+}
+
 impl Default for TerminalCell {
     fn default() -> Self {
         Self {
             character: ' ',
+            zerowidth: Vec::new(),
             style: TextStyle::default(),
         }
     }
@@ -225,6 +1040,8 @@ pub struct CursorState {
     pub col: u16,
     pub visible: bool,
     pub shape: CursorShape,
+    /// Cursor color set by the app via OSC 12; `None` uses the configured default.
+    pub color: Option<Color>,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -288,6 +1105,43 @@ pub trait Renderer {
     );
     fn end_frame(&mut self);
     fn cell_size(&self) -> Size;
+
+    /// Fill the triangle `a`, `b`, `c` (logical px, either winding), e.g. a
+    /// disclosure chevron or a drop-zone arrow. A no-op by default.
+    fn draw_triangle(&mut self, _a: Vec2, _b: Vec2, _c: Vec2, _color: Color) {}
+
+    /// Fill a convex polygon, fanned into `draw_triangle` from the first
+    /// point. Fewer than three points draw nothing.
+    fn draw_convex_polygon(&mut self, points: &[Vec2], color: Color) {
+        if let Some((&first, rest)) = points.split_first() {
+            for pair in rest.windows(2) {
+                self.draw_triangle(first, pair[0], pair[1], color);
+            }
+        }
+    }
+
+    /// Push a clip region. Until the matching `pop_clip`, draws are clipped
+    /// to it intersected with the regions pushed before (see `ClipStack`),
+    /// on top of any explicit `clip` argument. A no-op for renderers that
+    /// don't clip.
+    fn push_clip(&mut self, _clip: Rect) {}
+
+    /// Pop the region pushed last.
+    fn pop_clip(&mut self) {}
+
+    /// The effective clip region, `None` when nothing is pushed (or the
+    /// renderer doesn't clip).
+    fn current_clip(&self) -> Option<Rect> {
+        None
+    }
+
+    /// `draw_text` clipped only by the clip stack.
+    fn draw_text_in_clip(&mut self, text: &str, position: Vec2, style: TextStyle) {
+        let clip = self
+            .current_clip()
+            .unwrap_or(Rect::new(f32::MIN / 2.0, f32::MIN / 2.0, f32::MAX, f32::MAX));
+        self.draw_text(text, position, style, clip);
+    }
 }
 
 // ──────────────────────────────────────────────
@@ -302,6 +1156,29 @@ pub trait Pane {
     fn render(&self, rect: Rect, renderer: &mut dyn Renderer);
     fn handle_input(&mut self, event: InputEvent, rect: Rect) -> bool;
     fn update(&mut self);
+
+    /// Drain the app-level requests queued since the last call (see
+    /// `PaneEvent`). The app calls this once per update tick. Panes that
+    /// never raise events keep the default, which returns nothing.
+    fn take_events(&mut self) -> Vec<PaneEvent> {
+        Vec::new()
+    }
+}
+
+/// A request a pane raises for the app to act on, since only the app owns
+/// the file tree, the layout, and the other panes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PaneEvent {
+    /// The pane's working directory changed (e.g. the shell ran `cd`).
+    CwdChanged(PathBuf),
+    /// Open a file in an editor, optionally at a 0-based (line, column).
+    OpenFile(PathBuf, Option<(usize, usize)>),
+    /// Replace the pane's tab title (e.g. a program's OSC 0/2 title); an
+    /// empty title restores the default.
+    SetTitle(String),
+    /// Close the pane, with the same save prompt as Cmd+W (e.g. its shell
+    /// exited).
+    RequestClose,
 }
 
 // ──────────────────────────────────────────────
@@ -329,6 +1206,11 @@ pub trait LayoutEngine {
 /// Terminal backend: manages PTY, shell state, and terminal emulation.
 pub trait TerminalBackend {
     fn write(&mut self, data: &[u8]);
+    /// Parse `bytes` as if the program had printed them, without the shell:
+    /// for replaying recorded output and deterministic tests. Escape
+    /// sequences may span calls. The grid shows them after a `process`
+    /// that picks up the next sync.
+    fn feed(&mut self, bytes: &[u8]);
     fn process(&mut self);
     fn grid(&self) -> &TerminalGrid;
     fn resize(&mut self, cols: u16, rows: u16);
@@ -336,6 +1218,84 @@ pub trait TerminalBackend {
     fn cursor(&self) -> CursorState;
 }
 
+/// Why a terminal backend could not be created.
+#[derive(Debug)]
+pub enum TerminalError {
+    /// The shell (from `$SHELL` or the platform default) does not exist or is not executable.
+    ShellNotFound { shell: String },
+    /// Allocating the pseudo-terminal failed.
+    PtyAllocFailed(std::io::Error),
+    /// The shell process could not be spawned on the PTY.
+    Spawn(std::io::Error),
+    /// Any other I/O failure while setting up the terminal (e.g. the PTY event loop).
+    Io(std::io::Error),
+}
+
+impl std::fmt::Display for TerminalError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TerminalError::ShellNotFound { shell } => {
+                write!(f, "shell `{shell}` not found; set $SHELL to an installed shell")
+            }
+            TerminalError::PtyAllocFailed(e) => write!(f, "failed to allocate a PTY: {e}"),
+            TerminalError::Spawn(e) => write!(f, "failed to spawn shell: {e}"),
+            TerminalError::Io(e) => write!(f, "terminal I/O error: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for TerminalError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            TerminalError::ShellNotFound { .. } => None,
+            TerminalError::PtyAllocFailed(e) | TerminalError::Spawn(e) | TerminalError::Io(e) => Some(e),
+        }
+    }
+}
+
+impl From<std::io::Error> for TerminalError {
+    fn from(e: std::io::Error) -> Self {
+        TerminalError::Io(e)
+    }
+}
+
+// ──────────────────────────────────────────────
+// Trait: Clipboard
+// ──────────────────────────────────────────────
+
+/// Text clipboard. The app implements it over the system clipboard and hands
+/// it to crates that copy or paste (e.g. `EditorState::set_clipboard`), so
+/// they never talk to the platform directly.
+pub trait Clipboard: Send + Sync {
+    /// Current clipboard text; `None` when empty, non-text, or unavailable.
+    fn read(&self) -> Option<String>;
+    fn write(&self, text: &str);
+}
+
+/// In-process clipboard, for tests and headless use.
+#[derive(Debug, Default)]
+pub struct MemoryClipboard {
+    text: std::sync::Mutex<Option<String>>,
+}
+
+impl MemoryClipboard {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl Clipboard for MemoryClipboard {
+    fn read(&self) -> Option<String> {
+        self.text.lock().ok()?.clone()
+    }
+
+    fn write(&self, text: &str) {
+        if let Ok(mut slot) = self.text.lock() {
+            *slot = Some(text.to_string());
+        }
+    }
+}
+
 // ──────────────────────────────────────────────
 // Trait: FileTree
 // ──────────────────────────────────────────────
@@ -363,3 +1323,5 @@ pub trait InputRouter {
         focused: PaneId,
     ) -> Option<PaneId>;
 }
+
+mod tests;
@@ -0,0 +1,387 @@
+#[cfg(test)]
+mod tests {
+    use crate::{
+        frame_budget, visible_cell_range, ClipStack, Color, ControlFlow, ModifierNormalization, Modifiers, PaneId, Rect, RedrawScheduler, Renderer, Selection, SelectionMode, Size,
+        TerminalCell, TerminalGrid, TextStyle, Theme, ThemeError, Vec2,
+    };
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn test_theme_light_and_dark_differ_in_background() {
+        let dark = Theme::dark();
+        let light = Theme::light();
+        assert!(dark.dark);
+        assert!(!light.dark);
+        assert_ne!(dark.surface_bg, light.surface_bg);
+        assert_ne!(dark.pane_bg, light.pane_bg);
+    }
+
+    #[test]
+    fn test_theme_is_clone() {
+        let theme = Theme::light();
+        let copy = theme.clone();
+        assert_eq!(copy, theme);
+    }
+
+    #[test]
+    fn test_theme_for_mode() {
+        assert_eq!(Theme::for_mode(true), Theme::dark());
+        assert_eq!(Theme::for_mode(false), Theme::light());
+    }
+
+    #[test]
+    fn test_color_hex_round_trip() {
+        assert_eq!(Color::from_hex("#FF0000"), Some(Color::rgb(1.0, 0.0, 0.0)));
+        assert_eq!(Color::from_hex("00ff0080").map(|c| c.to_hex()), Some("#00FF0080".to_string()));
+        assert_eq!(Color::WHITE.to_hex(), "#FFFFFF");
+        assert_eq!(Color::from_hex("#12345"), None);
+        assert_eq!(Color::from_hex("#GG0000"), None);
+        assert_eq!(Color::from_hex("#ééé"), None);
+    }
+
+    #[test]
+    fn test_theme_toml_round_trip() {
+        for theme in [Theme::dark(), Theme::light()] {
+            let toml = theme.to_toml();
+            let parsed = Theme::from_toml(&toml).unwrap();
+            assert_eq!(parsed.dark, theme.dark);
+            // Hex quantizes to 8 bits per channel; a second pass is exact.
+            assert_eq!(parsed.to_toml(), toml);
+            assert_eq!(Theme::from_toml(&parsed.to_toml()).unwrap(), parsed);
+            assert!((parsed.pane_bg.r - theme.pane_bg.r).abs() <= 0.5 / 255.0);
+        }
+    }
+
+    #[test]
+    fn test_theme_toml_omitted_keys_use_mode_defaults() {
+        let theme = Theme::from_toml("dark = false\ncursor = \"#FF0000\" # red\n").unwrap();
+        assert!(!theme.dark);
+        assert_eq!(theme.cursor, Color::rgb(1.0, 0.0, 0.0));
+        assert_eq!(theme.pane_bg, Theme::light().pane_bg);
+        assert_eq!(theme.ansi, Theme::light().ansi);
+    }
+
+    #[test]
+    fn test_theme_toml_rejects_invalid_hex() {
+        let err = Theme::from_toml("# my theme\ndark = true\ncursor = \"#12345G\"\n").unwrap_err();
+        assert_eq!(err.line(), 3);
+        assert_eq!(err.key(), Some("cursor"));
+        let msg = err.to_string();
+        assert!(msg.contains("line 3"), "{msg}");
+        assert!(msg.contains("`cursor`"), "{msg}");
+        assert!(msg.contains("#12345G"), "{msg}");
+    }
+
+    #[test]
+    fn test_theme_toml_rejects_unknown_key_and_bad_syntax() {
+        assert_eq!(
+            Theme::from_toml("dark = true\ncursr = \"#FFFFFF\"").unwrap_err(),
+            ThemeError::UnknownKey { line: 2, key: "cursr".to_string() },
+        );
+        assert!(matches!(Theme::from_toml("dark"), Err(ThemeError::Syntax { line: 1, .. })));
+        assert!(matches!(Theme::from_toml("dark = maybe"), Err(ThemeError::Syntax { line: 1, .. })));
+        assert!(matches!(
+            Theme::from_toml("dark = \"maybe\""),
+            Err(ThemeError::InvalidValue { line: 1, .. })
+        ));
+        assert!(matches!(
+            Theme::from_toml("dark = true\ncursor = \"#FFFFFF\"\ncursor = \"#000000\""),
+            Err(ThemeError::Syntax { line: 3, .. })
+        ));
+    }
+
+    #[test]
+    fn test_theme_toml_accepts_toml_syntax() {
+        let theme = Theme::from_toml("'dark' = false # light\n\"cursor\" = '#FF0000'\n").unwrap();
+        assert!(!theme.dark);
+        assert_eq!(theme.cursor, Color::rgb(1.0, 0.0, 0.0));
+    }
+
+    fn grid_from_rows(rows: &[&str]) -> TerminalGrid {
+        let cols = rows.iter().map(|r| r.chars().count()).max().unwrap_or(0);
+        let cells = rows
+            .iter()
+            .map(|r| {
+                let mut row: Vec<TerminalCell> =
+                    r.chars().map(|character| TerminalCell { character, ..TerminalCell::default() }).collect();
+                row.resize_with(cols, TerminalCell::default);
+                row
+            })
+            .collect();
+        TerminalGrid { cols: cols as u16, rows: rows.len() as u16, cells }
+    }
+
+    #[test]
+    fn test_grid_plain_text_trims_rows_and_blanks_spacers() {
+        let grid = grid_from_rows(&["ls -la   ", "\u{4e2d}\0x  \0", "      "]);
+        assert_eq!(grid.to_plain_text(), "ls -la\n\u{4e2d} x\n");
+    }
+
+    #[test]
+    fn test_grid_ansi_reconstructs_cell_style() {
+        let mut grid = grid_from_rows(&["ab  "]);
+        grid.cells[0][0].style = TextStyle {
+            foreground: Color::rgb(1.0, 0.0, 0.0),
+            background: Some(Color::rgb(0.0, 0.0, 1.0)),
+            bold: true,
+            dim: false,
+            italic: true,
+            underline: true,
+        };
+        assert_eq!(
+            grid.to_ansi(),
+            "\x1b[0;1;3;4;38;2;255;0;0;48;2;0;0;255ma\x1b[0;38;2;255;255;255mb\x1b[0m",
+        );
+        // Unstyled blank rows stay empty
+        assert_eq!(grid_from_rows(&["   "]).to_ansi(), "");
+    }
+
+    #[test]
+    fn test_pane_id_round_trips_through_u64() {
+        let id = PaneId::from(u64::MAX);
+        assert_eq!(id.as_u64(), u64::MAX);
+        assert_eq!(u64::from(PaneId::new(7)), 7);
+        assert!(PaneId::new(1) < PaneId::new(2));
+        assert_eq!(PaneId::new(42).to_string(), "42");
+    }
+
+    #[test]
+    fn test_clip_stack_intersects_nested_regions() {
+        let mut clips = ClipStack::default();
+        assert_eq!(clips.top(), None);
+        let text = Rect::new(-50.0, 0.0, 500.0, 20.0);
+        assert_eq!(clips.apply(text), text, "nothing pushed: clip unchanged");
+
+        clips.push(Rect::new(0.0, 0.0, 200.0, 100.0)); // panel
+        clips.push(Rect::new(20.0, 50.0, 300.0, 300.0)); // list scrolled past the panel
+        assert_eq!(clips.top(), Some(Rect::new(20.0, 50.0, 180.0, 50.0)));
+        assert_eq!(clips.apply(Rect::new(0.0, 60.0, 50.0, 10.0)), Rect::new(20.0, 60.0, 30.0, 10.0));
+
+        // Disjoint push leaves an empty region, not a negative one
+        clips.push(Rect::new(500.0, 500.0, 10.0, 10.0));
+        assert!(clips.top().unwrap().is_empty());
+
+        clips.pop();
+        assert_eq!(clips.top(), Some(Rect::new(20.0, 50.0, 180.0, 50.0)));
+        clips.pop();
+        assert_eq!(clips.top(), Some(Rect::new(0.0, 0.0, 200.0, 100.0)));
+        clips.pop();
+        clips.pop();
+        assert_eq!(clips.depth(), 0);
+    }
+
+    #[test]
+    fn test_visible_cell_range_follows_clip() {
+        // 10 rows × 20 cols of 8×16 cells at (100, 40)
+        let pane = Rect::new(100.0, 40.0, 160.0, 160.0);
+        let cell = Size::new(8.0, 16.0);
+        assert_eq!(visible_cell_range(pane, pane, cell), Some((0..=9, 0..=19)));
+
+        // An overlay leaves only the top half visible
+        let top_half = Rect::new(0.0, 0.0, 1000.0, 40.0 + 80.0);
+        assert_eq!(visible_cell_range(pane, top_half, cell), Some((0..=4, 0..=19)));
+
+        // Partly covered cells count as visible
+        let middle = Rect::new(110.0, 50.0, 10.0, 20.0);
+        assert_eq!(visible_cell_range(pane, middle, cell), Some((0..=1, 1..=2)));
+    }
+
+    #[test]
+    fn test_visible_cell_range_none_without_overlap() {
+        let pane = Rect::new(100.0, 40.0, 160.0, 160.0);
+        let cell = Size::new(8.0, 16.0);
+        assert_eq!(visible_cell_range(pane, Rect::new(300.0, 0.0, 50.0, 50.0), cell), None);
+        // Touching edges share no area
+        assert_eq!(visible_cell_range(pane, Rect::new(100.0, 0.0, 160.0, 40.0), cell), None);
+        assert_eq!(visible_cell_range(pane, pane, Size::new(0.0, 16.0)), None);
+    }
+
+    /// Records the clip of every `draw_text` and every triangle, relying on
+    /// the default clip and polygon methods.
+    #[derive(Default)]
+    struct CaptureRenderer {
+        text_clips: Vec<Rect>,
+        triangles: Vec<[Vec2; 3]>,
+    }
+
+    impl Renderer for CaptureRenderer {
+        fn begin_frame(&mut self, _size: Size) {}
+        fn draw_rect(&mut self, _rect: Rect, _color: Color) {}
+        fn draw_text(&mut self, _text: &str, _position: Vec2, _style: TextStyle, clip: Rect) {
+            self.text_clips.push(clip);
+        }
+        fn draw_cell(&mut self, _: char, _: usize, _: usize, _: TextStyle, _: Size, _: Vec2) {}
+        fn end_frame(&mut self) {}
+        fn cell_size(&self) -> Size {
+            Size::new(8.0, 16.0)
+        }
+        fn draw_triangle(&mut self, a: Vec2, b: Vec2, c: Vec2, _color: Color) {
+            self.triangles.push([a, b, c]);
+        }
+    }
+
+    #[test]
+    fn test_convex_polygon_fans_into_triangles() {
+        let mut renderer = CaptureRenderer::default();
+        let p = |x: f32, y: f32| Vec2::new(x, y);
+        let pentagon = [p(0.0, 0.0), p(10.0, 0.0), p(12.0, 8.0), p(5.0, 12.0), p(-2.0, 8.0)];
+        renderer.draw_convex_polygon(&pentagon, Color::WHITE);
+        assert_eq!(
+            renderer.triangles,
+            [
+                [pentagon[0], pentagon[1], pentagon[2]],
+                [pentagon[0], pentagon[2], pentagon[3]],
+                [pentagon[0], pentagon[3], pentagon[4]],
+            ]
+        );
+
+        renderer.triangles.clear();
+        renderer.draw_convex_polygon(&pentagon[..2], Color::WHITE);
+        renderer.draw_convex_polygon(&[], Color::WHITE);
+        assert!(renderer.triangles.is_empty());
+    }
+
+    #[test]
+    fn test_default_clip_methods_are_no_ops() {
+        let mut renderer = CaptureRenderer::default();
+        let style = TextStyle::default();
+        renderer.push_clip(Rect::new(0.0, 0.0, 10.0, 10.0));
+        assert_eq!(renderer.current_clip(), None);
+        renderer.draw_text("a", Vec2::new(0.0, 0.0), style, Rect::new(1.0, 2.0, 3.0, 4.0));
+        renderer.draw_text_in_clip("b", Vec2::new(0.0, 0.0), style);
+        renderer.pop_clip();
+
+        assert_eq!(renderer.text_clips[0], Rect::new(1.0, 2.0, 3.0, 4.0));
+        // Without a stack, clip-less text is unbounded
+        assert!(renderer.text_clips[1].contains(Vec2::new(-1e6, 1e6)));
+    }
+
+    #[test]
+    fn test_reversed_linear_selection_normalizes() {
+        // Dragged upward: from (3, 2) back to (1, 5)
+        let sel = Selection::new((3, 2), (1, 5), SelectionMode::Linear);
+        assert_eq!(sel.normalized(), ((1, 5), (3, 2)));
+        let rows: Vec<_> = sel.rows().collect();
+        assert_eq!(rows, [(1, 5, usize::MAX), (2, 0, usize::MAX), (3, 0, 2)]);
+
+        assert!(!sel.contains(1, 4));
+        assert!(sel.contains(1, 5));
+        assert!(sel.contains(2, 1000));
+        assert!(sel.contains(3, 1));
+        assert!(!sel.contains(3, 2));
+    }
+
+    #[test]
+    fn test_linear_selection_skips_empty_rows() {
+        // Ends at the start of a line: that line contributes nothing
+        let sel = Selection::new((0, 4), (1, 0), SelectionMode::Linear);
+        assert_eq!(sel.rows().collect::<Vec<_>>(), [(0, 4, usize::MAX)]);
+        let empty = Selection::new((2, 3), (2, 3), SelectionMode::Linear);
+        assert!(empty.is_empty());
+        assert_eq!(empty.rows().count(), 0);
+        assert!(!empty.contains(2, 3));
+    }
+
+    #[test]
+    fn test_block_selection_yields_equal_width_rows() {
+        // Corners given bottom-left then top-right
+        let sel = Selection::new((4, 2), (1, 6), SelectionMode::Block);
+        assert_eq!(sel.normalized(), ((1, 2), (4, 6)));
+        let rows: Vec<_> = sel.rows().collect();
+        assert_eq!(rows.len(), 4);
+        assert!(rows.iter().all(|&(_, from, to)| (from, to) == (2, 6)));
+        assert_eq!(rows.iter().map(|r| r.0).collect::<Vec<_>>(), [1, 2, 3, 4]);
+
+        assert!(sel.contains(1, 2) && sel.contains(4, 5));
+        assert!(!sel.contains(2, 6) && !sel.contains(0, 3) && !sel.contains(5, 3));
+
+        // Zero-width block: a caret span on each line
+        let caret = Selection::new((0, 3), (2, 3), SelectionMode::Block);
+        assert_eq!(caret.rows().collect::<Vec<_>>(), [(0, 3, 3), (1, 3, 3), (2, 3, 3)]);
+    }
+
+    #[test]
+    fn test_redraw_keypress_renders_immediately() {
+        let start = Instant::now();
+        let mut redraw = RedrawScheduler::new(frame_budget(60));
+        redraw.on_frame_rendered(start);
+        let now = start + Duration::from_millis(1);
+        assert!(!redraw.should_render(now, true));
+        redraw.on_input();
+        assert!(redraw.should_render(now, true));
+        assert_eq!(redraw.control_flow(now, true), ControlFlow::Poll);
+    }
+
+    #[test]
+    fn test_redraw_sustained_output_is_capped_to_budget() {
+        let budget = frame_budget(30);
+        let start = Instant::now();
+        let mut redraw = RedrawScheduler::new(budget);
+        let mut frames = 0;
+        for ms in 0..1000 {
+            let now = start + Duration::from_millis(ms);
+            redraw.on_output();
+            if redraw.should_render(now, true) {
+                frames += 1;
+                redraw.on_frame_rendered(now);
+            } else {
+                assert!(matches!(redraw.control_flow(now, true), ControlFlow::WaitUntil(at) if at > now));
+            }
+        }
+        assert!((29..=30).contains(&frames), "rendered {frames} frames");
+    }
+
+    #[test]
+    fn test_redraw_idle_waits() {
+        let now = Instant::now();
+        let mut redraw = RedrawScheduler::new(frame_budget(60));
+        assert_eq!(redraw.control_flow(now, false), ControlFlow::Wait);
+        // First dirty frame is due at once; animation keeps frames coming
+        assert!(redraw.should_render(now, true));
+        redraw.on_frame_rendered(now);
+        redraw.set_animating(true);
+        assert_eq!(redraw.control_flow(now, false), ControlFlow::WaitUntil(now + frame_budget(60)));
+        redraw.set_animating(false);
+
+        // A timer wakes the loop once, then it goes back to waiting
+        let at = now + Duration::from_millis(5);
+        redraw.wake_at(at);
+        redraw.wake_at(now + Duration::from_millis(50));
+        assert_eq!(redraw.control_flow(now, false), ControlFlow::WaitUntil(at));
+        assert_eq!(redraw.control_flow(at, false), ControlFlow::Wait);
+    }
+
+    #[test]
+    fn test_modifier_builders_chain() {
+        assert_eq!(Modifiers::none(), Modifiers::default());
+        assert!(Modifiers::none().is_empty());
+        let mods = Modifiers::none().with_meta().with_shift();
+        assert_eq!(mods, Modifiers { shift: true, ctrl: false, alt: false, meta: true });
+        assert!(!mods.is_empty());
+        assert_eq!(
+            Modifiers::none().with_ctrl().with_alt().with_ctrl(),
+            Modifiers { shift: false, ctrl: true, alt: true, meta: false }
+        );
+    }
+
+    #[test]
+    fn test_modifiers_only_matches_exact_set() {
+        let shift_meta = Modifiers::none().with_shift().with_meta();
+        assert!(Modifiers::none().with_meta().with_shift().only(shift_meta));
+        assert!(!Modifiers::none().with_meta().only(shift_meta));
+        assert!(!shift_meta.with_alt().only(shift_meta));
+        assert!(!shift_meta.with_ctrl().only(shift_meta));
+        assert!(Modifiers::none().only(Modifiers::none()));
+    }
+
+    #[test]
+    fn test_modifiers_normalized_folds_into_meta() {
+        let ctrl_t = Modifiers::none().with_ctrl().with_shift();
+        assert_eq!(ctrl_t.normalized(ModifierNormalization::default()), ctrl_t);
+        let fold_ctrl = ModifierNormalization { ctrl_as_meta: true, ..Default::default() };
+        assert_eq!(ctrl_t.normalized(fold_ctrl), Modifiers::none().with_meta().with_shift());
+        assert_eq!(Modifiers::none().with_alt().normalized(fold_ctrl), Modifiers::none().with_alt());
+        let fold_alt = ModifierNormalization { alt_as_meta: true, ..Default::default() };
+        assert_eq!(Modifiers::none().with_alt().with_meta().normalized(fold_alt), Modifiers::none().with_meta());
+    }
+}
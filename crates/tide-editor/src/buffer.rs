@@ -4,7 +4,7 @@ use std::fs;
 use std::io;
 use std::path::{Path, PathBuf};
 
-use crate::undo::EditOp;
+use crate::undo::{EditOp, UndoEntry, UndoGroups};
 
 /// Find the largest byte offset <= idx that is a valid char boundary in the string.
 pub fn floor_char_boundary(s: &str, idx: usize) -> usize {
@@ -96,7 +96,8 @@ pub fn word_boundary_right(line: &str, byte_col: usize) -> usize {
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// Ordered by line, then column.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 pub struct Position {
     pub line: usize,
     pub col: usize,
@@ -109,8 +110,9 @@ pub struct Buffer {
     /// Snapshot of the content at the last save (or load) point.
     /// Used for content-based dirty tracking.
     saved_content: Vec<String>,
-    pub(crate) undo_stack: Vec<(EditOp, Position)>, // (op, cursor_before)
-    pub(crate) redo_stack: Vec<(EditOp, Position)>,
+    pub(crate) undo_stack: Vec<UndoEntry>,
+    pub(crate) redo_stack: Vec<UndoEntry>,
+    pub(crate) undo_groups: UndoGroups,
     /// Whether the original file ended with a newline (preserved on save).
     trailing_newline: bool,
 }
@@ -125,6 +127,7 @@ impl Buffer {
             generation: 0,
             undo_stack: Vec::new(),
             redo_stack: Vec::new(),
+            undo_groups: UndoGroups::default(),
             trailing_newline: true,
         }
     }
@@ -150,6 +153,7 @@ impl Buffer {
             generation: 0,
             undo_stack: Vec::new(),
             redo_stack: Vec::new(),
+            undo_groups: UndoGroups::default(),
             trailing_newline,
         })
     }
@@ -205,8 +209,7 @@ impl Buffer {
         }
         let col = floor_char_boundary(&self.lines[pos.line], pos.col.min(self.lines[pos.line].len()));
         let actual_pos = Position { line: pos.line, col };
-        self.undo_stack.push((EditOp::InsertChar { pos: actual_pos, ch }, pos));
-        self.redo_stack.clear();
+        self.record_undo(EditOp::InsertChar { pos: actual_pos, ch }, pos);
         self.lines[pos.line].insert(col, ch);
         self.generation += 1;
     }
@@ -220,14 +223,12 @@ impl Buffer {
         if col < line_len {
             let ch = self.lines[pos.line].remove(col);
             let actual_pos = Position { line: pos.line, col };
-            self.undo_stack.push((EditOp::DeleteChar { pos: actual_pos, ch, merged_next: false }, pos));
-            self.redo_stack.clear();
+            self.record_undo(EditOp::DeleteChar { pos: actual_pos, ch, merged_next: false }, pos);
             self.generation += 1;
         } else if pos.line + 1 < self.lines.len() {
             // Delete at end of line: merge with next line
             let next = self.lines.remove(pos.line + 1);
-            self.undo_stack.push((EditOp::DeleteChar { pos, ch: '\n', merged_next: true }, pos));
-            self.redo_stack.clear();
+            self.record_undo(EditOp::DeleteChar { pos, ch: '\n', merged_next: true }, pos);
             self.lines[pos.line].push_str(&next);
             self.generation += 1;
         }
@@ -244,13 +245,12 @@ impl Buffer {
                 let prev = floor_char_boundary(&self.lines[pos.line], col - 1);
                 let ch = self.lines[pos.line].remove(prev);
                 let result_pos = Position { line: pos.line, col: prev };
-                self.undo_stack.push((EditOp::Backspace {
+                self.record_undo(EditOp::Backspace {
                     original_pos: pos,
                     result_pos,
                     ch: Some(ch),
                     merged_line: false,
-                }, pos));
-                self.redo_stack.clear();
+                }, pos);
                 self.generation += 1;
                 return result_pos;
             }
@@ -264,13 +264,12 @@ impl Buffer {
             let new_col = self.lines[pos.line - 1].len();
             self.lines[pos.line - 1].push_str(&current);
             let result_pos = Position { line: pos.line - 1, col: new_col };
-            self.undo_stack.push((EditOp::Backspace {
+            self.record_undo(EditOp::Backspace {
                 original_pos: pos,
                 result_pos,
                 ch: None,
                 merged_line: true,
-            }, pos));
-            self.redo_stack.clear();
+            }, pos);
             self.generation += 1;
             result_pos
         } else {
@@ -284,8 +283,7 @@ impl Buffer {
         }
         let col = floor_char_boundary(&self.lines[pos.line], pos.col.min(self.lines[pos.line].len()));
         let actual_pos = Position { line: pos.line, col };
-        self.undo_stack.push((EditOp::InsertNewline { pos: actual_pos }, pos));
-        self.redo_stack.clear();
+        self.record_undo(EditOp::InsertNewline { pos: actual_pos }, pos);
         let rest = self.lines[pos.line][col..].to_string();
         self.lines[pos.line].truncate(col);
         self.lines.insert(pos.line + 1, rest);
@@ -309,6 +307,28 @@ impl Buffer {
         self.lines.iter().map(|l| l.chars().count()).max().unwrap_or(0)
     }
 
+    /// Text between two byte-offset positions (start <= end), lines joined with `\n`.
+    /// Positions past the end are clamped.
+    pub fn text_range(&self, start: Position, end: Position) -> String {
+        if start >= end || start.line >= self.lines.len() {
+            return String::new();
+        }
+        let end_line = end.line.min(self.lines.len() - 1);
+        let end_col = floor_char_boundary(&self.lines[end_line], end.col.min(self.lines[end_line].len()));
+        let start_col = floor_char_boundary(&self.lines[start.line], start.col.min(self.lines[start.line].len()));
+        if start.line == end_line {
+            return self.lines[start.line][start_col..end_col.max(start_col)].to_string();
+        }
+        let mut text = self.lines[start.line][start_col..].to_string();
+        for line in &self.lines[start.line + 1..end_line] {
+            text.push('\n');
+            text.push_str(line);
+        }
+        text.push('\n');
+        text.push_str(&self.lines[end_line][..end_col]);
+        text
+    }
+
     /// Delete text between two byte-offset positions, returning the new cursor position (start).
     /// `start` and `end` are (line, byte_col) positions; start must be <= end.
     pub fn delete_range(&mut self, start: Position, end: Position) -> Position {
@@ -333,15 +353,14 @@ impl Buffer {
 
         let actual_start = Position { line: start.line, col: start_col };
         let actual_end = Position { line: end_line, col: end_col };
-        self.undo_stack.push((
+        self.record_undo(
             crate::undo::EditOp::DeleteRange {
                 start: actual_start,
                 end: actual_end,
                 deleted_lines,
             },
             start,
-        ));
-        self.redo_stack.clear();
+        );
 
         if start.line == end_line {
             self.lines[start.line].drain(start_col..end_col);
@@ -364,35 +383,32 @@ impl Buffer {
         let col = floor_char_boundary(&self.lines[pos.line], pos.col.min(self.lines[pos.line].len()));
         let actual_pos = Position { line: pos.line, col };
 
-        let suffix = self.lines[pos.line][col..].to_string();
-        self.lines[pos.line].truncate(col);
-
         // Normalize \r\n to \n, skip standalone \r
         let normalized: String = text.replace("\r\n", "\n").replace('\r', "");
-        let text_lines: Vec<&str> = normalized.split('\n').collect();
+        let end_pos = self.splice_text(actual_pos, &normalized);
 
-        let end_pos = if text_lines.len() == 1 {
-            self.lines[pos.line].push_str(text_lines[0]);
+        self.record_undo(EditOp::InsertText { pos: actual_pos, text: normalized, end_pos }, pos);
+        self.generation += 1;
+        end_pos
+    }
+
+    /// Insert `\n`-separated `text` at `pos` (a char boundary), returning
+    /// the end position. New lines go in with one splice, so the cost is
+    /// O(inserted + lines after `pos`) however many lines `text` has.
+    pub(crate) fn splice_text(&mut self, pos: Position, text: &str) -> Position {
+        let suffix = self.lines[pos.line].split_off(pos.col);
+        let mut text_lines = text.split('\n');
+        self.lines[pos.line].push_str(text_lines.next().unwrap_or_default());
+        let Some(last) = text_lines.next_back() else {
             let end_col = self.lines[pos.line].len();
             self.lines[pos.line].push_str(&suffix);
-            Position { line: pos.line, col: end_col }
-        } else {
-            self.lines[pos.line].push_str(text_lines[0]);
-            for (i, tl) in text_lines[1..text_lines.len() - 1].iter().enumerate() {
-                self.lines.insert(pos.line + 1 + i, tl.to_string());
-            }
-            let last_idx = pos.line + text_lines.len() - 1;
-            let mut last_line = text_lines.last().unwrap().to_string();
-            let end_col = last_line.len();
-            last_line.push_str(&suffix);
-            self.lines.insert(last_idx, last_line);
-            Position { line: last_idx, col: end_col }
+            return Position { line: pos.line, col: end_col };
         };
-
-        self.undo_stack.push((EditOp::InsertText { pos: actual_pos, text: normalized, end_pos }, pos));
-        self.redo_stack.clear();
-        self.generation += 1;
-        end_pos
+        let middle: Vec<String> = text_lines.map(str::to_string).collect();
+        let end = Position { line: pos.line + middle.len() + 1, col: last.len() };
+        let last_line = last.to_string() + &suffix;
+        self.lines.splice(pos.line + 1..pos.line + 1, middle.into_iter().chain(std::iter::once(last_line)));
+        end
     }
 
     /// Delete the word to the left of the cursor. Returns the new cursor position.
@@ -466,15 +482,13 @@ impl Buffer {
         let cursor_before = Position { line: line_idx, col: 0 };
         if self.lines.len() == 1 {
             let content = std::mem::take(&mut self.lines[0]);
-            self.undo_stack.push((EditOp::DeleteLine { line: 0, content }, cursor_before));
-            self.redo_stack.clear();
+            self.record_undo(EditOp::DeleteLine { line: 0, content }, cursor_before);
             self.lines[0] = String::new();
             self.generation += 1;
             return Position { line: 0, col: 0 };
         }
         let content = self.lines.remove(line_idx);
-        self.undo_stack.push((EditOp::DeleteLine { line: line_idx, content }, cursor_before));
-        self.redo_stack.clear();
+        self.record_undo(EditOp::DeleteLine { line: line_idx, content }, cursor_before);
         self.generation += 1;
         let new_line = line_idx.min(self.lines.len().saturating_sub(1));
         Position { line: new_line, col: 0 }
@@ -486,11 +500,10 @@ impl Buffer {
             return false;
         }
         self.lines.swap(line_idx, line_idx - 1);
-        self.undo_stack.push((
+        self.record_undo(
             EditOp::SwapLines { line_a: line_idx - 1, line_b: line_idx },
             Position { line: line_idx, col: 0 },
-        ));
-        self.redo_stack.clear();
+        );
         self.generation += 1;
         true
     }
@@ -501,11 +514,10 @@ impl Buffer {
             return false;
         }
         self.lines.swap(line_idx, line_idx + 1);
-        self.undo_stack.push((
+        self.record_undo(
             EditOp::SwapLines { line_a: line_idx, line_b: line_idx + 1 },
             Position { line: line_idx, col: 0 },
-        ));
-        self.redo_stack.clear();
+        );
         self.generation += 1;
         true
     }
@@ -513,17 +525,23 @@ impl Buffer {
     /// Remove one level of indentation from the given line.
     /// Returns the number of bytes removed.
     pub fn unindent_line(&mut self, line_idx: usize) -> usize {
+        self.unindent_line_by(line_idx, 4)
+    }
+
+    /// Remove one indent level from a line: one tab or up to `width` spaces.
+    /// Returns the number of bytes removed.
+    pub fn unindent_line_by(&mut self, line_idx: usize, width: usize) -> usize {
         if line_idx >= self.lines.len() {
             return 0;
         }
         let line = &self.lines[line_idx];
-        // Determine how much to remove: one tab or up to 4 spaces
+        // Determine how much to remove: one tab or up to `width` spaces
         let mut remove = 0;
         for ch in line.chars() {
             if ch == '\t' && remove == 0 {
                 remove = 1;
                 break;
-            } else if ch == ' ' && remove < 4 {
+            } else if ch == ' ' && remove < width {
                 remove += 1;
             } else {
                 break;
@@ -1004,4 +1022,49 @@ mod tests {
         assert_eq!(removed, 0);
         assert_eq!(buf.line(0), Some("hello"));
     }
+
+    #[test]
+    fn undo_group_undoes_together() {
+        let mut buf = Buffer::new();
+        buf.begin_undo_group();
+        buf.insert_char(Position { line: 0, col: 0 }, 'A');
+        buf.insert_newline(Position { line: 0, col: 1 });
+        buf.insert_char(Position { line: 1, col: 0 }, 'B');
+        buf.end_undo_group();
+        buf.insert_char(Position { line: 1, col: 1 }, 'C');
+
+        buf.undo();
+        assert_eq!(buf.lines, vec!["A", "B"]);
+        let pos = buf.undo();
+        assert_eq!(pos, Some(Position { line: 0, col: 0 }));
+        assert_eq!(buf.lines, vec![""]);
+
+        buf.redo();
+        assert_eq!(buf.lines, vec!["A", "B"]);
+    }
+
+    #[test]
+    fn join_last_undo_group_coalesces() {
+        let mut buf = Buffer::new();
+        buf.insert_char(Position { line: 0, col: 0 }, 'A');
+        buf.join_last_undo_group();
+        buf.insert_char(Position { line: 0, col: 1 }, 'B');
+        buf.insert_char(Position { line: 0, col: 2 }, 'C');
+        buf.undo();
+        assert_eq!(buf.line(0), Some("AB"));
+        buf.undo();
+        assert_eq!(buf.line(0), Some(""));
+    }
+
+    #[test]
+    fn undo_limit_drops_oldest_groups() {
+        let mut buf = Buffer::new();
+        buf.set_undo_limit(3);
+        for (i, ch) in "abcde".chars().enumerate() {
+            buf.insert_char(Position { line: 0, col: i }, ch);
+        }
+        assert_eq!(buf.undo_stack.len(), 3);
+        while buf.undo().is_some() {}
+        assert_eq!(buf.line(0), Some("ab"));
+    }
 }
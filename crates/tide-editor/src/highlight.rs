@@ -1,13 +1,16 @@
 // Syntax highlighting via syntect, with incremental state caching.
 
 use std::cell::RefCell;
+use std::ops::Range;
 use std::path::Path;
 
 use syntect::highlighting::{
     HighlightState, Highlighter as SyntectHighlighter, RangedHighlightIterator, Style, Theme,
     ThemeSet,
 };
-use syntect::parsing::{ParseState, ScopeStack, SyntaxDefinition, SyntaxReference, SyntaxSet};
+use syntect::parsing::{
+    ParseState, Scope, ScopeStack, SyntaxDefinition, SyntaxReference, SyntaxSet,
+};
 
 use tide_core::{Color, TextStyle};
 
@@ -18,7 +21,7 @@ pub struct StyledSpan {
 }
 
 /// Interval (in lines) between cached parse-state checkpoints.
-const CHECKPOINT_INTERVAL: usize = 256;
+pub(crate) const CHECKPOINT_INTERVAL: usize = 256;
 
 /// Cached highlighting state for incremental re-highlighting on scroll.
 struct HighlightCache {
@@ -79,6 +82,22 @@ impl Highlighter {
         self.cache.borrow_mut().checkpoints.clear();
     }
 
+    /// Drop the checkpoints an edit at `line` made stale, keeping the ones
+    /// before it, and adopt the buffer's new `line_count` so the next
+    /// `highlight_lines` resumes at `line` instead of re-parsing from the top.
+    /// Without this call, a change in line count clears the whole cache.
+    pub fn invalidate_from(&self, line: usize, line_count: usize) {
+        let mut cache = self.cache.borrow_mut();
+        cache.checkpoints.truncate(line / CHECKPOINT_INTERVAL);
+        cache.line_count = line_count;
+    }
+
+    /// Number of leading lines whose parse state is cached: highlighting
+    /// anything past them parses forward from this line.
+    pub fn cached_lines(&self) -> usize {
+        self.cache.borrow().checkpoints.len() * CHECKPOINT_INTERVAL
+    }
+
     /// Detect syntax from file extension. Returns None if unknown.
     pub fn detect_syntax(&self, path: &Path) -> Option<&SyntaxReference> {
         let ext = path.extension()?.to_str()?;
@@ -186,13 +205,6 @@ impl Highlighter {
                 }
             };
 
-            // Save checkpoint at interval boundaries.
-            let cp_slot = (i + 1) / CHECKPOINT_INTERVAL;
-            if (i + 1) % CHECKPOINT_INTERVAL == 0 && cp_slot > cache.checkpoints.len() {
-                cache.checkpoints
-                    .push((parse_state.clone(), highlight_state.clone()));
-            }
-
             // Only build StyledSpans for visible lines.
             if i >= start_line {
                 let regions: Vec<(Style, &str)> =
@@ -250,11 +262,84 @@ impl Highlighter {
                 // Still need to advance highlight_state for non-visible lines.
                 for _ in RangedHighlightIterator::new(&mut highlight_state, &ops, &line_with_newline, &highlighter) {}
             }
+
+            // Save checkpoint at interval boundaries, once both states have
+            // consumed line i.
+            let cp_slot = (i + 1) / CHECKPOINT_INTERVAL;
+            if (i + 1) % CHECKPOINT_INTERVAL == 0 && cp_slot > cache.checkpoints.len() {
+                cache.checkpoints
+                    .push((parse_state.clone(), highlight_state.clone()));
+            }
         }
 
         result
     }
 
+    /// Byte ranges of string and comment tokens, one entry per line from
+    /// `from_line` on. Parsing resumes from the nearest cached checkpoint at
+    /// or before `from_line` and continues lazily as the iterator advances.
+    pub fn literal_ranges<'a>(
+        &'a self,
+        lines: &'a [String],
+        syntax: &SyntaxReference,
+        from_line: usize,
+    ) -> impl Iterator<Item = Vec<Range<usize>>> + 'a {
+        let (mut line, mut parse_state, mut stack) = self.resume_point(lines, syntax, from_line);
+        let prefixes: Vec<Scope> = ["string", "comment"]
+            .into_iter()
+            .filter_map(|s| Scope::new(s).ok())
+            .collect();
+        let is_literal =
+            move |stack: &ScopeStack| stack.as_slice().iter().any(|s| prefixes.iter().any(|p| p.is_prefix_of(*s)));
+
+        std::iter::from_fn(move || {
+            while line < lines.len() {
+                let text = &lines[line];
+                line += 1;
+                let ops = parse_state
+                    .parse_line(&format!("{}\n", text), &self.syntax_set)
+                    .unwrap_or_default();
+
+                let mut ranges = Vec::new();
+                let mut open = is_literal(&stack).then_some(0);
+                for (pos, op) in &ops {
+                    let _ = stack.apply(op);
+                    let pos = (*pos).min(text.len());
+                    match (open, is_literal(&stack)) {
+                        (None, true) => open = Some(pos),
+                        (Some(from), false) => {
+                            if pos > from {
+                                ranges.push(from..pos);
+                            }
+                            open = None;
+                        }
+                        _ => {}
+                    }
+                }
+                if let Some(from) = open.filter(|&from| from < text.len()) {
+                    ranges.push(from..text.len());
+                }
+
+                if line > from_line {
+                    return Some(ranges);
+                }
+            }
+            None
+        })
+    }
+
+    /// Parser state at the latest cached checkpoint at or before `line`, or
+    /// a fresh state at line 0 when the cache doesn't cover these lines.
+    fn resume_point(&self, lines: &[String], syntax: &SyntaxReference, line: usize) -> (usize, ParseState, ScopeStack) {
+        let cache = self.cache.borrow();
+        let have = (line / CHECKPOINT_INTERVAL).min(cache.checkpoints.len());
+        if have > 0 && cache.syntax_name == syntax.name && cache.line_count == lines.len() {
+            let (parse_state, highlight_state) = &cache.checkpoints[have - 1];
+            return (have * CHECKPOINT_INTERVAL, parse_state.clone(), highlight_state.path.clone());
+        }
+        (0, ParseState::new(syntax), ScopeStack::new())
+    }
+
     pub fn syntax_set(&self) -> &SyntaxSet {
         &self.syntax_set
     }
@@ -33,6 +33,8 @@ pub enum EditorAction {
     MoveLineUp,
     MoveLineDown,
     Unindent,
+    /// Comment or uncomment the cursor line with the language's line comment token.
+    ToggleComment,
     ScrollUp(f32),
     ScrollDown(f32),
     ScrollLeft(f32),
@@ -68,6 +70,11 @@ pub fn key_to_editor_action(key: &Key, modifiers: &Modifiers) -> Option<EditorAc
         return Some(EditorAction::DeleteLine);
     }
 
+    // Cmd+/ -> Toggle line comment
+    if (modifiers.ctrl || modifiers.meta) && matches!(key, Key::Char('/')) {
+        return Some(EditorAction::ToggleComment);
+    }
+
     // Cmd+Left -> Home (line start)
     if (modifiers.ctrl || modifiers.meta) && matches!(key, Key::Left) {
         return Some(EditorAction::Home);
@@ -195,6 +202,14 @@ mod tests {
         );
     }
 
+    #[test]
+    fn ctrl_slash_maps_to_toggle_comment() {
+        assert_eq!(
+            key_to_editor_action(&Key::Char('/'), &ctrl()),
+            Some(EditorAction::ToggleComment)
+        );
+    }
+
     #[test]
     fn ctrl_other_returns_none() {
         assert_eq!(key_to_editor_action(&Key::Char('b'), &ctrl()), None);
@@ -0,0 +1,87 @@
+// Per-language editing settings (indentation, rulers, comment token), keyed
+// by syntect syntax name (e.g. "Python", "Go").
+
+use std::collections::HashMap;
+
+/// How one indentation level is written.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IndentConfig {
+    /// Insert a tab character instead of spaces.
+    pub use_tabs: bool,
+    /// Columns per level: the number of spaces inserted, and how many
+    /// spaces Shift+Tab removes.
+    pub width: usize,
+}
+
+impl IndentConfig {
+    pub const fn spaces(width: usize) -> Self {
+        Self { use_tabs: false, width }
+    }
+
+    pub const fn tabs(width: usize) -> Self {
+        Self { use_tabs: true, width }
+    }
+
+    /// Text that indents from char column `col` to the next indent stop.
+    pub fn indent_from(&self, col: usize) -> String {
+        if self.use_tabs {
+            return "\t".to_string();
+        }
+        let width = self.width.max(1);
+        " ".repeat(width - col % width)
+    }
+}
+
+/// Editing settings for one language.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LanguageConfig {
+    pub indent: IndentConfig,
+    /// Columns at which to draw vertical rulers (empty = none).
+    pub rulers: Vec<usize>,
+    /// Line comment token (e.g. "//", "#"); `None` if the language has none.
+    pub line_comment: Option<String>,
+}
+
+impl LanguageConfig {
+    pub fn new(indent: IndentConfig, rulers: &[usize], line_comment: Option<&str>) -> Self {
+        Self {
+            indent,
+            rulers: rulers.to_vec(),
+            line_comment: line_comment.map(str::to_string),
+        }
+    }
+}
+
+/// Used for plain text and any syntax missing from the table: Tab inserts a
+/// tab character, no rulers.
+impl Default for LanguageConfig {
+    fn default() -> Self {
+        Self::new(IndentConfig::tabs(4), &[], None)
+    }
+}
+
+/// Built-in settings, keyed by syntect syntax name.
+pub fn default_language_configs() -> HashMap<String, LanguageConfig> {
+    let table = [
+        ("Rust", LanguageConfig::new(IndentConfig::spaces(4), &[100], Some("//"))),
+        ("Python", LanguageConfig::new(IndentConfig::spaces(4), &[79], Some("#"))),
+        ("Go", LanguageConfig::new(IndentConfig::tabs(4), &[], Some("//"))),
+        ("Makefile", LanguageConfig::new(IndentConfig::tabs(4), &[], Some("#"))),
+        ("C", LanguageConfig::new(IndentConfig::spaces(4), &[80], Some("//"))),
+        ("C++", LanguageConfig::new(IndentConfig::spaces(4), &[80], Some("//"))),
+        ("Java", LanguageConfig::new(IndentConfig::spaces(4), &[100], Some("//"))),
+        ("JavaScript", LanguageConfig::new(IndentConfig::spaces(2), &[], Some("//"))),
+        ("JSX", LanguageConfig::new(IndentConfig::spaces(2), &[], Some("//"))),
+        ("JSON", LanguageConfig::new(IndentConfig::spaces(2), &[], None)),
+        ("HTML", LanguageConfig::new(IndentConfig::spaces(2), &[], None)),
+        ("CSS", LanguageConfig::new(IndentConfig::spaces(2), &[], None)),
+        ("YAML", LanguageConfig::new(IndentConfig::spaces(2), &[], Some("#"))),
+        ("Ruby", LanguageConfig::new(IndentConfig::spaces(2), &[], Some("#"))),
+        ("Bourne Again Shell (bash)", LanguageConfig::new(IndentConfig::spaces(2), &[], Some("#"))),
+        ("Markdown", LanguageConfig::new(IndentConfig::spaces(2), &[], None)),
+    ];
+    table
+        .into_iter()
+        .map(|(name, config)| (name.to_string(), config))
+        .collect()
+}
@@ -4,21 +4,29 @@ pub mod buffer;
 pub mod cursor;
 pub mod highlight;
 pub mod input;
+pub mod language;
 pub mod markdown;
 mod undo;
 
+use std::cell::Cell;
+use std::collections::HashMap;
 use std::io;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 use buffer::{Buffer, Position};
 use cursor::EditorCursor;
 use highlight::{Highlighter, StyledSpan};
 use input::EditorAction;
 use syntect::parsing::SyntaxReference;
+use tide_core::{Clipboard, Color};
 
 pub use buffer::Position as EditorPosition;
 pub use highlight::StyledSpan as EditorStyledSpan;
 pub use input::{key_to_editor_action, EditorAction as EditorActionKind};
+pub use language::{IndentConfig, LanguageConfig};
+pub use tide_core::SelectionMode;
 
 /// The main editor state orchestrator.
 pub struct EditorState {
@@ -28,7 +36,189 @@ pub struct EditorState {
     syntax: Option<String>, // syntax name, used to look up reference on demand
     scroll_offset: usize,
     h_scroll_offset: usize,
+    /// Soft-wrap column: when set, long lines are split into display rows of
+    /// at most this many characters.
+    wrap_width: Option<usize>,
     generation: u64,
+    /// Document statistics cached against the buffer generation.
+    stats_cache: Cell<Option<(u64, DocStats)>>,
+    /// Last `matching_bracket` result, keyed by buffer generation and cursor.
+    bracket_cache: Cell<Option<BracketMatchCache>>,
+    /// The run of typed characters currently coalescing into one undo group.
+    typing_run: Option<TypingRun>,
+    /// How the owner's selection maps onto the buffer (see [`SelectionMode`]).
+    selection_mode: SelectionMode,
+    /// Per-syntax editing settings; the active one follows `syntax`.
+    language_configs: HashMap<String, LanguageConfig>,
+    /// Used when the syntax is unknown or missing from `language_configs`.
+    fallback_language: LanguageConfig,
+    /// Target of copy/cut and source of paste; `None` until the owner sets one.
+    clipboard: Option<Arc<dyn Clipboard>>,
+    /// Previously visited locations, most recent last (see `push_mark`).
+    marks: Vec<Position>,
+    /// Which whitespace `visible_highlighted_lines` makes visible.
+    render_whitespace: WhitespaceMode,
+    /// Whether typing an opening bracket or quote inserts its closer
+    /// (see `set_auto_close`).
+    auto_close: bool,
+}
+
+/// Typing pause after which the next character starts a new undo group.
+const TYPING_IDLE: Duration = Duration::from_secs(1);
+
+/// Marks kept by `push_mark`; pushing beyond this drops the oldest.
+const MARK_RING_SIZE: usize = 16;
+
+/// Glyphs drawn in place of a space and a tab when whitespace is rendered.
+const SPACE_GLYPH: char = '\u{b7}';
+const TAB_GLYPH: char = '\u{2192}';
+
+/// Background of trailing whitespace when whitespace is rendered.
+const TRAILING_WHITESPACE_BG: Color = Color::new(0.9, 0.3, 0.3, 0.35);
+
+/// State of an in-progress typing run (see `EditorAction::InsertChar`).
+struct TypingRun {
+    /// Cursor position right after the last typed char.
+    next_pos: Position,
+    /// Buffer generation right after the last typed char (detects other edits).
+    buffer_gen: u64,
+    at: Instant,
+}
+
+/// Memoized [`EditorState::matching_bracket`] result.
+#[derive(Clone, Copy)]
+struct BracketMatchCache {
+    buffer_gen: u64,
+    cursor: Position,
+    pair: Option<(Position, Position)>,
+}
+
+/// Document statistics for a status line (see [`EditorState::stats`]).
+/// Line breaks between lines count as one char and one byte each.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct DocStats {
+    pub lines: usize,
+    pub chars: usize,
+    /// Whitespace-delimited words.
+    pub words: usize,
+    pub bytes: usize,
+}
+
+/// Cursor and scroll of an editor (see [`EditorState::save_edit_state`]), for
+/// the owner to remember per file and restore when it is reopened.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EditState {
+    pub position: Position,
+    pub scroll_offset: usize,
+    /// Horizontal scroll, in chars.
+    pub h_scroll_offset: usize,
+}
+
+/// Which whitespace is drawn as visible glyphs (`·` for a space, `→` for a
+/// tab). Each glyph replaces exactly one char, so columns are unchanged.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum WhitespaceMode {
+    /// Whitespace is drawn as-is.
+    #[default]
+    None,
+    /// All whitespace except single spaces between words.
+    Boundary,
+    /// All whitespace.
+    All,
+}
+
+/// A rectangle of char columns `left..right` on lines `first_line..=last_line`.
+/// A zero-width block (`left == right`) is a caret on each of its lines.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BlockRange {
+    pub first_line: usize,
+    pub last_line: usize,
+    pub left: usize,
+    pub right: usize,
+}
+
+impl BlockRange {
+    /// The block spanned by two `(line, char_col)` corners, in any order.
+    pub fn from_corners(a: (usize, usize), b: (usize, usize)) -> Self {
+        Self {
+            first_line: a.0.min(b.0),
+            last_line: a.0.max(b.0),
+            left: a.1.min(b.1),
+            right: a.1.max(b.1),
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.right - self.left
+    }
+}
+
+/// A `path[:line[:col]]` location as compilers and grep print it
+/// (`src/main.rs:10:5`). Line and column are 1-based.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileLocation {
+    pub path: PathBuf,
+    pub line: Option<usize>,
+    pub col: Option<usize>,
+}
+
+impl FileLocation {
+    /// Split up to two trailing `:number` parts off `spec` (one trailing `:`,
+    /// as in `file.rs:12: error`, is ignored). Other colons stay in the path.
+    pub fn parse(spec: &str) -> Self {
+        fn split_number(s: &str) -> Option<(&str, usize)> {
+            let (rest, digits) = s.rsplit_once(':')?;
+            if rest.is_empty() || digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+                return None;
+            }
+            Some((rest, digits.parse().ok()?))
+        }
+        let spec = spec.strip_suffix(':').unwrap_or(spec);
+        match split_number(spec) {
+            Some((rest, last)) => match split_number(rest) {
+                Some((path, line)) => Self { path: path.into(), line: Some(line), col: Some(last) },
+                None => Self { path: rest.into(), line: Some(last), col: None },
+            },
+            None => Self { path: spec.into(), line: None, col: None },
+        }
+    }
+}
+
+/// Byte offset of char column `col` in `line` (end of line if past it).
+fn char_col_to_byte(line: &str, col: usize) -> usize {
+    line.char_indices().nth(col).map_or(line.len(), |(i, _)| i)
+}
+
+/// Closer typed around a selection for an opening bracket or quote.
+fn closing_pair(open: char) -> Option<char> {
+    match open {
+        '(' => Some(')'),
+        '[' => Some(']'),
+        '{' => Some('}'),
+        '"' | '\'' | '`' => Some(open),
+        _ => None,
+    }
+}
+
+/// Closer auto-inserted after typing `open` between `prev` and `next`: only
+/// before whitespace, a closing bracket or the line end, and never for a
+/// quote right after a word char (`don't`).
+fn auto_closer(open: char, prev: Option<char>, next: Option<char>) -> Option<char> {
+    let close = closing_pair(open)?;
+    let before_gap = next.is_none_or(|c| c.is_whitespace() || matches!(c, ')' | ']' | '}'));
+    let quote_after_word = close == open && prev.is_some_and(|c| c.is_alphanumeric() || c == '_');
+    (before_gap && !quote_after_word).then_some(close)
+}
+
+/// Gutter content for one display row (see [`EditorState::gutter_info`]).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GutterLine {
+    /// Buffer line (0-based) shown on this row.
+    pub buffer_line: usize,
+    /// Line number text (1-based); empty for wrap continuation rows.
+    pub display: String,
+    /// True when this row continues a soft-wrapped line.
+    pub is_wrap_continuation: bool,
 }
 
 impl EditorState {
@@ -41,7 +231,18 @@ impl EditorState {
             syntax: None,
             scroll_offset: 0,
             h_scroll_offset: 0,
+            wrap_width: None,
             generation: 0,
+            stats_cache: Cell::new(None),
+            bracket_cache: Cell::new(None),
+            typing_run: None,
+            selection_mode: SelectionMode::Linear,
+            language_configs: language::default_language_configs(),
+            fallback_language: LanguageConfig::default(),
+            clipboard: None,
+            marks: Vec::new(),
+            render_whitespace: WhitespaceMode::None,
+            auto_close: true,
         }
     }
 
@@ -60,10 +261,36 @@ impl EditorState {
             syntax: syntax_name,
             scroll_offset: 0,
             h_scroll_offset: 0,
+            wrap_width: None,
             generation: 0,
+            stats_cache: Cell::new(None),
+            bracket_cache: Cell::new(None),
+            typing_run: None,
+            selection_mode: SelectionMode::Linear,
+            language_configs: language::default_language_configs(),
+            fallback_language: LanguageConfig::default(),
+            clipboard: None,
+            marks: Vec::new(),
+            render_whitespace: WhitespaceMode::None,
+            auto_close: true,
         })
     }
 
+    /// Open `spec` = `path[:line[:col]]` (see [`FileLocation`]) with the
+    /// cursor on that 1-based line and character column, clamped to the file,
+    /// and its line scrolled to the top. Without a line this is `open`.
+    pub fn open_at(spec: &str) -> io::Result<Self> {
+        let location = FileLocation::parse(spec);
+        let mut state = Self::open(&location.path)?;
+        if let Some(line) = location.line {
+            let line = line.saturating_sub(1).min(state.buffer.line_count().saturating_sub(1));
+            let char_col = location.col.unwrap_or(1).saturating_sub(1);
+            let col = state.buffer.line(line).map_or(0, |l| char_col_to_byte(l, char_col));
+            state.go_to(Position { line, col }, 1);
+        }
+        Ok(state)
+    }
+
     /// Reload the file from disk, preserving cursor position (clamped to valid bounds).
     pub fn reload(&mut self) -> io::Result<()> {
         let old_lines = self.buffer.lines.clone();
@@ -88,13 +315,53 @@ impl EditorState {
         // Defensive: clamp cursor to valid buffer bounds before any operation.
         // This prevents panics if cursor drifts out of sync (e.g. after file reload).
         self.cursor.clamp(&self.buffer);
+        // Anything other than typing (cursor moves included) ends the typing run.
+        let typing_run = self.typing_run.take();
 
         match action {
+            EditorAction::InsertChar('\t') if !self.language_config().indent.use_tabs => {
+                let pos = self.cursor.position;
+                let col = self.buffer.line(pos.line).map_or(0, |l| l[..pos.col].chars().count());
+                let spaces = self.language_config().indent.indent_from(col);
+                let end_pos = self.buffer.insert_text(pos, &spaces);
+                self.cursor.set_position(end_pos);
+                self.generation += 1;
+            }
             EditorAction::InsertChar(ch) => {
-                self.buffer.insert_char(self.cursor.position, ch);
+                // Consecutive non-whitespace chars coalesce into one undo group.
+                let continues = !ch.is_whitespace()
+                    && typing_run.is_some_and(|run| {
+                        run.next_pos == self.cursor.position
+                            && run.buffer_gen == self.buffer.generation()
+                            && run.at.elapsed() < TYPING_IDLE
+                    });
+                let pos = self.cursor.position;
+                let (prev, next) = self
+                    .buffer
+                    .line(pos.line)
+                    .map_or((None, None), |line| (line[..pos.col].chars().next_back(), line[pos.col..].chars().next()));
+                // With auto-close, typing a closer just before the same closer steps over it
+                let steps_over = self.auto_close && next == Some(ch) && matches!(ch, ')' | ']' | '}' | '"' | '\'' | '`');
+                if !steps_over {
+                    if continues {
+                        self.buffer.join_last_undo_group();
+                    }
+                    self.buffer.insert_char(pos, ch);
+                    if let Some(close) = auto_closer(ch, prev, next).filter(|_| self.auto_close) {
+                        self.buffer.join_last_undo_group();
+                        self.buffer.insert_char(Position { line: pos.line, col: pos.col + ch.len_utf8() }, close);
+                    }
+                }
                 self.cursor.position.col += ch.len_utf8();
                 self.cursor.desired_col = self.cursor.position.col;
                 self.generation += 1;
+                if !ch.is_whitespace() {
+                    self.typing_run = Some(TypingRun {
+                        next_pos: self.cursor.position,
+                        buffer_gen: self.buffer.generation(),
+                        at: Instant::now(),
+                    });
+                }
             }
             EditorAction::Backspace => {
                 let new_pos = self.buffer.backspace(self.cursor.position);
@@ -115,9 +382,27 @@ impl EditorState {
                 } else {
                     String::new()
                 };
-                let new_pos = self.buffer.insert_newline(self.cursor.position);
+                let pos = self.cursor.position;
+                let between_pair = self.buffer.line(pos.line).is_some_and(|line| {
+                    let open = line[..pos.col].chars().next_back();
+                    let close = line[pos.col..].chars().next();
+                    matches!((open, close), (Some('{'), Some('}')) | (Some('['), Some(']')) | (Some('('), Some(')')))
+                });
+                // Newline + indent undo as one step.
+                self.buffer.begin_undo_group();
+                let new_pos = self.buffer.insert_newline(pos);
                 // Insert the indent on the new line (handles empty string gracefully)
-                let end_pos = self.buffer.insert_text(new_pos, &indent);
+                let end_pos = if between_pair {
+                    // `{|}`: indented middle line for the cursor, closer on its own line
+                    let body = self.language_config().indent.indent_from(indent.chars().count());
+                    let mid_end = self.buffer.insert_text(new_pos, &format!("{indent}{body}"));
+                    let close_pos = self.buffer.insert_newline(mid_end);
+                    self.buffer.insert_text(close_pos, &indent);
+                    mid_end
+                } else {
+                    self.buffer.insert_text(new_pos, &indent)
+                };
+                self.buffer.end_undo_group();
                 self.cursor.set_position(end_pos);
                 self.generation += 1;
             }
@@ -189,8 +474,14 @@ impl EditorState {
                     self.generation += 1;
                 }
             }
+            EditorAction::ToggleComment => {
+                if self.toggle_line_comment(self.cursor.position.line) {
+                    self.generation += 1;
+                }
+            }
             EditorAction::Unindent => {
-                let removed = self.buffer.unindent_line(self.cursor.position.line);
+                let width = self.language_config().indent.width;
+                let removed = self.buffer.unindent_line_by(self.cursor.position.line, width);
                 if removed > 0 {
                     self.cursor.position.col = self.cursor.position.col.saturating_sub(removed);
                     self.cursor.desired_col = self.cursor.position.col;
@@ -243,7 +534,8 @@ impl EditorState {
         }
     }
 
-    /// Get syntax-highlighted lines for the visible viewport.
+    /// Get syntax-highlighted lines for the visible viewport. Whitespace is
+    /// marked according to `set_render_whitespace`.
     pub fn visible_highlighted_lines(&self, visible_rows: usize) -> Vec<Vec<StyledSpan>> {
         let syntax_ref = self.syntax.as_ref().and_then(|name| {
             self.highlighter.syntax_set().find_syntax_by_name(name)
@@ -252,27 +544,313 @@ impl EditorState {
             Some(s) => s,
             None => self.highlighter.plain_text_syntax(),
         };
-        self.highlighter.highlight_lines(
+        let lines = self.highlighter.highlight_lines(
             &self.buffer.lines,
             syntax,
             self.scroll_offset,
             visible_rows,
-        )
+        );
+        match self.render_whitespace {
+            WhitespaceMode::None => lines,
+            mode => lines.into_iter().map(|spans| mark_whitespace(spans, mode)).collect(),
+        }
     }
 
-    /// Insert a block of text at the current cursor position (single undo entry).
+    /// Get syntax-highlighted display rows for the viewport. Same as
+    /// `visible_highlighted_lines`, but with soft-wrap enabled long lines are
+    /// split into several rows and `visible_rows` counts display rows.
+    pub fn visible_display_rows(&self, visible_rows: usize) -> Vec<Vec<StyledSpan>> {
+        let lines = self.visible_highlighted_lines(visible_rows);
+        let Some(width) = self.wrap_width else {
+            return lines;
+        };
+        let mut rows = Vec::new();
+        for spans in lines {
+            rows.extend(wrap_spans(spans, width));
+            if rows.len() >= visible_rows {
+                break;
+            }
+        }
+        rows.truncate(visible_rows);
+        rows
+    }
+
+    /// Gutter info for each row returned by `visible_display_rows`, in the same order.
+    /// Wrap continuation rows carry the buffer line they belong to but a blank number.
+    pub fn gutter_info(&self, visible_rows: usize) -> Vec<GutterLine> {
+        let mut out = Vec::with_capacity(visible_rows);
+        let mut line = self.scroll_offset;
+        while out.len() < visible_rows && line < self.buffer.line_count() {
+            for i in 0..self.display_row_count(line) {
+                if out.len() >= visible_rows {
+                    break;
+                }
+                out.push(GutterLine {
+                    buffer_line: line,
+                    display: if i == 0 { (line + 1).to_string() } else { String::new() },
+                    is_wrap_continuation: i > 0,
+                });
+            }
+            line += 1;
+        }
+        out
+    }
+
+    /// Number of characters needed for the widest line number in the buffer.
+    pub fn gutter_width_chars(&self) -> usize {
+        self.buffer.line_count().max(1).to_string().len()
+    }
+
+    /// Enable soft-wrap at `width` characters, or disable it with `None`.
+    pub fn set_wrap_width(&mut self, width: Option<usize>) {
+        let width = width.filter(|w| *w > 0);
+        if self.wrap_width != width {
+            self.wrap_width = width;
+            self.generation += 1;
+        }
+    }
+
+    pub fn wrap_width(&self) -> Option<usize> {
+        self.wrap_width
+    }
+
+    /// Choose which whitespace is drawn as visible glyphs. Trailing
+    /// whitespace is also given a highlight background unless `mode` is
+    /// `None`.
+    pub fn set_render_whitespace(&mut self, mode: WhitespaceMode) {
+        if self.render_whitespace != mode {
+            self.render_whitespace = mode;
+            self.generation += 1;
+        }
+    }
+
+    pub fn render_whitespace(&self) -> WhitespaceMode {
+        self.render_whitespace
+    }
+
+    /// Enable or disable closing pairs (on by default). When on, typing an
+    /// opening bracket or quote inserts its closer after the cursor, typing
+    /// a closer right before the same closer steps over it, and
+    /// `surround_range` wraps ranges. When off, every char is inserted as
+    /// typed.
+    pub fn set_auto_close(&mut self, enabled: bool) {
+        self.auto_close = enabled;
+    }
+
+    pub fn auto_close(&self) -> bool {
+        self.auto_close
+    }
+
+    /// Insert a block of text at the current cursor position (single undo
+    /// entry). Highlighting is invalidated from the insert line on, so a big
+    /// paste deep in a file doesn't re-parse everything above it.
     pub fn insert_text(&mut self, text: &str) {
         self.cursor.clamp(&self.buffer);
+        let start_line = self.cursor.position.line;
         let end_pos = self.buffer.insert_text(self.cursor.position, text);
+        self.highlighter.invalidate_from(start_line, self.buffer.line_count());
         self.cursor.set_position(end_pos);
         self.generation += 1;
     }
 
+    pub fn set_clipboard(&mut self, clipboard: Arc<dyn Clipboard>) {
+        self.clipboard = Some(clipboard);
+    }
+
+    pub fn clipboard(&self) -> Option<&Arc<dyn Clipboard>> {
+        self.clipboard.as_ref()
+    }
+
+    /// Copy the text between two byte positions to the clipboard.
+    /// Returns false (nothing copied) for an empty range or without a clipboard.
+    pub fn copy_range(&self, start: Position, end: Position) -> bool {
+        let Some(clipboard) = &self.clipboard else { return false };
+        let text = self.buffer.text_range(start.min(end), start.max(end));
+        if text.is_empty() {
+            return false;
+        }
+        clipboard.write(&text);
+        true
+    }
+
+    /// Copy the text between two byte positions to the clipboard and delete
+    /// it (single undo entry). Without a clipboard nothing is deleted.
+    pub fn cut_range(&mut self, start: Position, end: Position) -> bool {
+        let (start, end) = (start.min(end), start.max(end));
+        if !self.copy_range(start, end) {
+            return false;
+        }
+        let new_pos = self.buffer.delete_range(start, end);
+        self.cursor.set_position(new_pos);
+        self.generation += 1;
+        true
+    }
+
+    /// Insert the clipboard text at the cursor. Returns false if there was
+    /// nothing to paste.
+    pub fn paste(&mut self) -> bool {
+        let Some(text) = self.clipboard.as_ref().and_then(|c| c.read()) else { return false };
+        if text.is_empty() {
+            return false;
+        }
+        self.insert_text(&text);
+        true
+    }
+
+    /// Wrap the text between two byte positions in `open` and its closer
+    /// (single undo entry), e.g. `"` around `word` gives `"word"`. The cursor
+    /// ends before the closer. Returns false (nothing inserted) for an empty
+    /// range, a char that opens no pair, or with auto-close off.
+    pub fn surround_range(&mut self, start: Position, end: Position, open: char) -> bool {
+        let (start, end) = (start.min(end), start.max(end));
+        let Some(close) = closing_pair(open) else { return false };
+        if !self.auto_close || start == end {
+            return false;
+        }
+        self.typing_run = None;
+        self.buffer.begin_undo_group();
+        // Closer first so `start` stays valid
+        self.buffer.insert_char(end, close);
+        self.buffer.insert_char(start, open);
+        self.buffer.end_undo_group();
+        let shift = if end.line == start.line { open.len_utf8() } else { 0 };
+        self.cursor.set_position(Position { line: end.line, col: end.col + shift });
+        self.generation += 1;
+        true
+    }
+
+    pub fn selection_mode(&self) -> SelectionMode {
+        self.selection_mode
+    }
+
+    pub fn set_selection_mode(&mut self, mode: SelectionMode) {
+        self.selection_mode = mode;
+    }
+
+    /// Text inside `block`, one line per row. Rows shorter than the block
+    /// contribute the part they have (possibly nothing).
+    pub fn block_text(&self, block: &BlockRange) -> String {
+        let last = block.last_line.min(self.buffer.line_count().saturating_sub(1));
+        (block.first_line..=last)
+            .filter_map(|line| self.buffer.line(line))
+            .map(|text| text.chars().skip(block.left).take(block.width()).collect::<String>())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// Replace `block` on every row with `text` (single-line) as one undo entry,
+    /// for typing and deleting across a column block. Rows ending before the
+    /// block's left edge are padded with spaces when inserting and skipped when
+    /// deleting. The cursor ends after `text` on the last row.
+    pub fn replace_block(&mut self, block: &BlockRange, text: &str) {
+        let last = block.last_line.min(self.buffer.line_count().saturating_sub(1));
+        if block.first_line > last {
+            return;
+        }
+        self.typing_run = None;
+        self.buffer.begin_undo_group();
+        for line in block.first_line..=last {
+            let Some(current) = self.buffer.line(line) else { break };
+            let len = current.chars().count();
+            if len < block.left {
+                if !text.is_empty() {
+                    let pos = Position { line, col: current.len() };
+                    let padded = format!("{}{text}", " ".repeat(block.left - len));
+                    self.buffer.insert_text(pos, &padded);
+                }
+                continue;
+            }
+            let start = Position { line, col: char_col_to_byte(current, block.left) };
+            let end = Position { line, col: char_col_to_byte(current, block.right) };
+            if start != end {
+                self.buffer.delete_range(start, end);
+            }
+            if !text.is_empty() {
+                self.buffer.insert_text(start, text);
+            }
+        }
+        self.buffer.end_undo_group();
+
+        let caret = block.left + text.chars().count();
+        let col = self.buffer.line(last).map_or(0, |l| char_col_to_byte(l, caret));
+        self.cursor.set_position(Position { line: last, col });
+        self.generation += 1;
+    }
+
     /// Ensure the cursor is visible within the viewport (both vertically and horizontally).
     pub fn ensure_cursor_visible(&mut self, visible_rows: usize) {
         self.ensure_cursor_visible_v(visible_rows);
     }
 
+    /// Move the cursor to a 1-based line number (as typed by the user), clamped
+    /// to the buffer, and scroll it into view. The column resets to the line start.
+    pub fn go_to_line(&mut self, line_1_based: usize, visible_rows: usize) {
+        let line = line_1_based.saturating_sub(1);
+        self.go_to(Position { line, col: 0 }, visible_rows);
+    }
+
+    /// Move the cursor to `pos` (0-based line, byte column), clamped to valid
+    /// bounds, and scroll it into view.
+    pub fn go_to(&mut self, pos: Position, visible_rows: usize) {
+        self.cursor.position = pos;
+        self.cursor.clamp(&self.buffer);
+        self.cursor.set_position(self.cursor.position);
+        self.ensure_cursor_visible(visible_rows);
+        self.generation += 1;
+    }
+
+    /// Cursor and scroll offsets, to hand back to `restore_edit_state`.
+    pub fn save_edit_state(&self) -> EditState {
+        EditState {
+            position: self.cursor.position,
+            scroll_offset: self.scroll_offset,
+            h_scroll_offset: self.h_scroll_offset,
+        }
+    }
+
+    /// Restore a saved cursor and scroll, clamped to the current buffer (the
+    /// file may have changed since it was saved).
+    pub fn restore_edit_state(&mut self, state: EditState) {
+        self.cursor.position = state.position;
+        self.cursor.clamp(&self.buffer);
+        self.cursor.set_position(self.cursor.position);
+        self.scroll_offset = state.scroll_offset.min(self.buffer.line_count().saturating_sub(1));
+        let widest = self.buffer.lines.iter().map(|l| l.chars().count()).max().unwrap_or(0);
+        self.h_scroll_offset = state.h_scroll_offset.min(widest);
+        self.generation += 1;
+    }
+
+    /// Remember the cursor position on the mark ring, to return to with
+    /// `pop_mark` (like Emacs' C-SPC). Pushing the top mark again is a no-op.
+    pub fn push_mark(&mut self) {
+        let pos = self.cursor.position;
+        if self.marks.last() == Some(&pos) {
+            return;
+        }
+        if self.marks.len() == MARK_RING_SIZE {
+            self.marks.remove(0);
+        }
+        self.marks.push(pos);
+    }
+
+    /// Jump back to the most recently pushed mark (clamped, in case the
+    /// buffer shrank) and scroll it into view. Returns false if the ring is
+    /// empty.
+    pub fn pop_mark(&mut self, visible_rows: usize) -> bool {
+        match self.marks.pop() {
+            Some(pos) => {
+                self.go_to(pos, visible_rows);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Number of marks on the ring.
+    pub fn mark_count(&self) -> usize {
+        self.marks.len()
+    }
+
     /// Ensure the cursor is vertically visible.
     fn ensure_cursor_visible_v(&mut self, visible_rows: usize) {
         if visible_rows == 0 {
@@ -281,8 +859,22 @@ impl EditorState {
         let line = self.cursor.position.line;
         if line < self.scroll_offset {
             self.scroll_offset = line;
-        } else if line >= self.scroll_offset + visible_rows {
-            self.scroll_offset = line - visible_rows + 1;
+            return;
+        }
+        // Count display rows (not buffer lines) so soft-wrapped lines above
+        // the cursor can't push it below the viewport.
+        let mut rows: usize = (self.scroll_offset..=line).map(|l| self.display_row_count(l)).sum();
+        while rows > visible_rows && self.scroll_offset < line {
+            rows -= self.display_row_count(self.scroll_offset);
+            self.scroll_offset += 1;
+        }
+    }
+
+    /// Display rows buffer line `line` occupies (1 unless soft-wrapped).
+    fn display_row_count(&self, line: usize) -> usize {
+        match (self.wrap_width, self.buffer.line(line)) {
+            (Some(width), Some(text)) => wrapped_row_count(text, width),
+            _ => 1,
         }
     }
 
@@ -344,6 +936,38 @@ impl EditorState {
         self.cursor.position
     }
 
+    /// Cursor as a 1-based (line, column), with the column counted in characters
+    /// rather than bytes.
+    pub fn cursor_line_col(&self) -> (usize, usize) {
+        let pos = self.cursor.position;
+        let char_col = self.buffer.line(pos.line).map_or(0, |line| {
+            let byte_col = buffer::floor_char_boundary(line, pos.col.min(line.len()));
+            line[..byte_col].chars().count()
+        });
+        (pos.line + 1, char_col + 1)
+    }
+
+    /// Line/char/word/byte counts for the whole buffer. Recomputed only when
+    /// the buffer content changes.
+    pub fn stats(&self) -> DocStats {
+        let gen = self.buffer.generation();
+        if let Some((cached_gen, stats)) = self.stats_cache.get() {
+            if cached_gen == gen {
+                return stats;
+            }
+        }
+        let lines = self.buffer.line_count();
+        let breaks = lines.saturating_sub(1);
+        let mut stats = DocStats { lines, chars: breaks, words: 0, bytes: breaks };
+        for line in &self.buffer.lines {
+            stats.chars += line.chars().count();
+            stats.bytes += line.len();
+            stats.words += line.split_whitespace().count();
+        }
+        self.stats_cache.set(Some((gen, stats)));
+        stats
+    }
+
     pub fn scroll_offset(&self) -> usize {
         self.scroll_offset
     }
@@ -386,6 +1010,53 @@ impl EditorState {
     pub fn detect_and_set_syntax(&mut self, path: &Path) {
         self.syntax = self.highlighter.detect_syntax(path).map(|s| s.name.clone());
         self.generation += 1;
+        self.bracket_cache.set(None);
+    }
+
+    /// Editing settings for the current syntax (indent, rulers, comment token).
+    pub fn language_config(&self) -> &LanguageConfig {
+        self.syntax
+            .as_ref()
+            .and_then(|name| self.language_configs.get(name))
+            .unwrap_or(&self.fallback_language)
+    }
+
+    /// Override (or add) the settings used for syntax `name` (e.g. "Python").
+    pub fn set_language_config(&mut self, name: &str, config: LanguageConfig) {
+        self.language_configs.insert(name.to_string(), config);
+        self.generation += 1;
+    }
+
+    /// Comment `line` with the current syntax's line comment token (placed
+    /// after the indentation, followed by a space), or uncomment it if it
+    /// already starts with the token. Returns false if the syntax has none.
+    fn toggle_line_comment(&mut self, line: usize) -> bool {
+        let Some(token) = self.language_config().line_comment.clone() else { return false };
+        let Some(text) = self.buffer.line(line) else { return false };
+        let indent = text.len() - text.trim_start_matches([' ', '\t']).len();
+        let rest = &text[indent..];
+        let at = Position { line, col: indent };
+        let col = &mut self.cursor.position.col;
+        if let Some(after) = rest.strip_prefix(token.as_str()) {
+            let removed = token.len() + usize::from(after.starts_with(' '));
+            self.buffer.delete_range(at, Position { line, col: indent + removed });
+            if *col > indent {
+                *col = col.saturating_sub(removed).max(indent);
+            }
+        } else {
+            let inserted = format!("{token} ");
+            self.buffer.insert_text(at, &inserted);
+            if *col >= indent {
+                *col += inserted.len();
+            }
+        }
+        self.cursor.desired_col = self.cursor.position.col;
+        true
+    }
+
+    /// Ruler columns for the current syntax.
+    pub fn rulers(&self) -> &[usize] {
+        &self.language_config().rulers
     }
 
     /// Switch syntax highlighting theme for dark/light mode.
@@ -394,9 +1065,28 @@ impl EditorState {
         self.generation += 1;
     }
 
+    /// Select the syntax highlighting theme matching a `tide_core::Theme`.
+    pub fn set_theme(&mut self, theme: &tide_core::Theme) {
+        self.set_dark_mode(theme.dark);
+    }
+
     /// Find the matching bracket for the bracket at (or near) the cursor position.
     /// Returns `Some((open_pos, close_pos))` if a matching pair is found.
+    /// The scan reruns only when the buffer or the cursor changed since the
+    /// last call, so calling this every frame is cheap.
     pub fn matching_bracket(&self) -> Option<(Position, Position)> {
+        let key = (self.buffer.generation(), self.cursor.position);
+        if let Some(cached) = self.bracket_cache.get() {
+            if (cached.buffer_gen, cached.cursor) == key {
+                return cached.pair;
+            }
+        }
+        let pair = self.scan_matching_bracket();
+        self.bracket_cache.set(Some(BracketMatchCache { buffer_gen: key.0, cursor: key.1, pair }));
+        pair
+    }
+
+    fn scan_matching_bracket(&self) -> Option<(Position, Position)> {
         let pos = self.cursor.position;
         let line_text = self.buffer.line(pos.line)?;
         let byte_col = pos.col.min(line_text.len());
@@ -446,17 +1136,31 @@ impl EditorState {
         };
 
         let start_pos = Position { line: pos.line, col: bracket_byte };
+        // With a syntax set, brackets inside strings and comments don't count
+        let syntax = self.syntax.as_ref().and_then(|name| {
+            self.highlighter.syntax_set().find_syntax_by_name(name)
+        });
+        let in_literal = |ranges: &[std::ops::Range<usize>], byte: usize| {
+            ranges.iter().any(|r| r.contains(&byte))
+        };
 
         if forward {
             // Scan forward from start_pos
+            let mut literals = syntax
+                .map(|s| self.highlighter.literal_ranges(&self.buffer.lines, s, pos.line));
             let mut depth = 0i32;
             let mut line_idx = pos.line;
             let mut col_start = bracket_byte;
             let total_lines = self.buffer.line_count();
             while line_idx < total_lines {
                 let text = self.buffer.line(line_idx)?;
+                let skip = literals.as_mut().and_then(|it| it.next()).unwrap_or_default();
+                if line_idx == pos.line && in_literal(&skip, bracket_byte) {
+                    return None;
+                }
                 for (byte_i, ch) in text[col_start..].char_indices() {
                     let abs_byte = col_start + byte_i;
+                    if in_literal(&skip, abs_byte) { continue; }
                     if ch == open { depth += 1; }
                     if ch == close { depth -= 1; }
                     if depth == 0 {
@@ -467,23 +1171,46 @@ impl EditorState {
                 col_start = 0;
             }
         } else {
-            // Scan backward from start_pos
+            // Scan backward from start_pos. Literal ranges are computed a
+            // checkpoint-sized block at a time, walking back as needed.
+            let block_ranges = |last: usize| -> (usize, Vec<Vec<std::ops::Range<usize>>>) {
+                let first = last - last % highlight::CHECKPOINT_INTERVAL;
+                let ranges = match syntax {
+                    Some(s) => self
+                        .highlighter
+                        .literal_ranges(&self.buffer.lines, s, first)
+                        .take(last - first + 1)
+                        .collect(),
+                    None => Vec::new(),
+                };
+                (first, ranges)
+            };
+            let (mut block_first, mut block) = block_ranges(pos.line);
             let mut depth = 0i32;
             let mut line_idx = pos.line as isize;
             let mut scan_from_end = false;
             let first_col = bracket_byte;
             loop {
-                let text = self.buffer.line(line_idx as usize)?;
+                let line = line_idx as usize;
+                if line < block_first {
+                    (block_first, block) = block_ranges(line);
+                }
+                let skip = block.get(line - block_first).map(Vec::as_slice).unwrap_or_default();
+                if !scan_from_end && in_literal(skip, bracket_byte) {
+                    return None;
+                }
+                let text = self.buffer.line(line)?;
                 let scan_text = if !scan_from_end {
                     &text[..first_col + bracket_char.len_utf8()]
                 } else {
                     text
                 };
                 for (byte_i, ch) in scan_text.char_indices().rev() {
+                    if in_literal(skip, byte_i) { continue; }
                     if ch == close { depth += 1; }
                     if ch == open { depth -= 1; }
                     if depth == 0 {
-                        return Some((Position { line: line_idx as usize, col: byte_i }, start_pos));
+                        return Some((Position { line, col: byte_i }, start_pos));
                     }
                 }
                 line_idx -= 1;
@@ -495,3 +1222,763 @@ impl EditorState {
         None
     }
 }
+
+/// Number of display rows a line occupies when wrapped at `width` characters.
+fn wrapped_row_count(text: &str, width: usize) -> usize {
+    text.chars().count().div_ceil(width).max(1)
+}
+
+/// Split one highlighted line into rows of at most `width` characters,
+/// keeping each piece's style.
+fn wrap_spans(spans: Vec<StyledSpan>, width: usize) -> Vec<Vec<StyledSpan>> {
+    let mut rows = vec![Vec::new()];
+    let mut col = 0usize;
+    for span in spans {
+        let mut piece = String::new();
+        for ch in span.text.chars().filter(|&c| c != '\n') {
+            if col == width {
+                if !piece.is_empty() {
+                    rows.last_mut().unwrap().push(StyledSpan { text: std::mem::take(&mut piece), style: span.style });
+                }
+                rows.push(Vec::new());
+                col = 0;
+            }
+            piece.push(ch);
+            col += 1;
+        }
+        if !piece.is_empty() {
+            rows.last_mut().unwrap().push(StyledSpan { text: piece, style: span.style });
+        }
+    }
+    rows
+}
+
+/// Replace the whitespace `mode` makes visible with dim glyphs, and give
+/// trailing whitespace a highlight background. Spans are split where the
+/// style changes; the char count of the line is unchanged.
+fn mark_whitespace(spans: Vec<StyledSpan>, mode: WhitespaceMode) -> Vec<StyledSpan> {
+    let chars: Vec<char> = spans.iter().flat_map(|s| s.text.chars()).collect();
+    let is_ws = |c: char| c == ' ' || c == '\t';
+    let content_end = chars.iter().rposition(|&c| c != '\n').map_or(0, |i| i + 1);
+    let trailing_start = chars[..content_end].iter().rposition(|&c| !is_ws(c)).map_or(0, |i| i + 1);
+    let visible = |i: usize| match mode {
+        WhitespaceMode::None => false,
+        WhitespaceMode::All => true,
+        // A lone space between two non-whitespace chars stays blank
+        WhitespaceMode::Boundary => {
+            chars[i] == '\t'
+                || i == 0
+                || i + 1 >= content_end
+                || is_ws(chars[i - 1])
+                || is_ws(chars[i + 1])
+        }
+    };
+
+    let mut out: Vec<StyledSpan> = Vec::with_capacity(spans.len());
+    let mut i = 0;
+    for span in spans {
+        let mut piece = String::new();
+        let mut piece_style = span.style;
+        for ch in span.text.chars() {
+            let mut style = span.style;
+            let mut glyph = ch;
+            if is_ws(ch) && visible(i) {
+                glyph = if ch == '\t' { TAB_GLYPH } else { SPACE_GLYPH };
+                style.dim = true;
+            }
+            if is_ws(ch) && i >= trailing_start && i < content_end {
+                style.background = Some(TRAILING_WHITESPACE_BG);
+            }
+            if style != piece_style && !piece.is_empty() {
+                out.push(StyledSpan { text: std::mem::take(&mut piece), style: piece_style });
+            }
+            piece_style = style;
+            piece.push(glyph);
+            i += 1;
+        }
+        if !piece.is_empty() {
+            out.push(StyledSpan { text: piece, style: piece_style });
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn editor_with(text: &str) -> EditorState {
+        let mut state = EditorState::new_empty();
+        state.insert_text(text);
+        state
+    }
+
+    #[test]
+    fn go_to_line_beyond_eof_clamps_and_scrolls() {
+        let mut state = editor_with(&"line\n".repeat(99));
+        state.go_to_line(500, 10);
+        assert_eq!(state.cursor_position(), Position { line: 99, col: 0 });
+        assert_eq!(state.cursor.desired_col, 0);
+        assert_eq!(state.scroll_offset(), 90);
+
+        state.go_to_line(0, 10);
+        assert_eq!(state.cursor_position().line, 0);
+        assert_eq!(state.scroll_offset(), 0);
+    }
+
+    #[test]
+    fn large_multi_line_insert_moves_cursor_and_keeps_earlier_highlighting() {
+        let mut state = editor_with(&"let x = 1;\n".repeat(2000));
+        // Highlight near the end so checkpoints cover most of the file
+        state.go_to_line(2000, 10);
+        state.visible_highlighted_lines(10);
+        assert_eq!(state.highlighter.cached_lines(), 1792);
+
+        state.go_to(Position { line: 1000, col: 4 }, 10);
+        let block = "row\n".repeat(10_000);
+        state.insert_text(&block);
+        assert_eq!(state.buffer.line_count(), 12_001);
+        assert_eq!(state.cursor_position(), Position { line: 11_000, col: 0 });
+        assert_eq!(state.buffer.line(1000), Some("let row"));
+        assert_eq!(state.buffer.line(11_000), Some("x = 1;"));
+        // Checkpoints before the insert line survive
+        assert_eq!(state.highlighter.cached_lines(), 768);
+
+        state.ensure_cursor_visible(40);
+        assert_eq!(state.scroll_offset(), 10_961);
+        assert_eq!(state.visible_highlighted_lines(40).len(), 40);
+
+        // One undo entry removes the whole block; redo puts it back
+        state.buffer.undo();
+        assert_eq!(state.buffer.line_count(), 2001);
+        assert_eq!(state.buffer.line(1000), Some("let x = 1;"));
+        state.buffer.redo();
+        assert_eq!(state.buffer.line_count(), 12_001);
+        assert_eq!(state.buffer.line(11_000), Some("x = 1;"));
+    }
+
+    #[test]
+    fn go_to_clamps_column_and_resets_desired_col() {
+        let mut state = editor_with("short\nlonger line\nend");
+        state.go_to(Position { line: 1, col: 6 }, 10);
+        assert_eq!(state.cursor_position(), Position { line: 1, col: 6 });
+        assert_eq!(state.cursor.desired_col, 6);
+        state.go_to(Position { line: 0, col: 42 }, 10);
+        assert_eq!(state.cursor_position(), Position { line: 0, col: 5 });
+        assert_eq!(state.cursor.desired_col, 5);
+    }
+
+    #[test]
+    fn restore_edit_state_round_trips() {
+        let mut state = editor_with(&"some text\n".repeat(50));
+        state.go_to(Position { line: 30, col: 4 }, 10);
+        state.set_h_scroll_offset(2);
+        let saved = state.save_edit_state();
+        assert_eq!(saved, EditState { position: Position { line: 30, col: 4 }, scroll_offset: 21, h_scroll_offset: 2 });
+
+        let mut reopened = editor_with(&"some text\n".repeat(50));
+        reopened.restore_edit_state(saved);
+        assert_eq!(reopened.save_edit_state(), saved);
+        assert_eq!(reopened.cursor.desired_col, 4);
+    }
+
+    #[test]
+    fn restore_edit_state_clamps_out_of_range_position() {
+        // The file shrank since the state was saved.
+        let mut state = editor_with("short\nlast");
+        state.restore_edit_state(EditState {
+            position: Position { line: 40, col: 12 },
+            scroll_offset: 35,
+            h_scroll_offset: 80,
+        });
+        assert_eq!(state.cursor_position(), Position { line: 1, col: 4 });
+        assert_eq!(state.scroll_offset(), 1);
+        assert_eq!(state.h_scroll_offset(), 5);
+    }
+
+    #[test]
+    fn pop_mark_returns_to_pushed_location() {
+        let mut state = editor_with(&"line\n".repeat(99));
+        state.go_to(Position { line: 10, col: 2 }, 10);
+        state.push_mark();
+        state.go_to(Position { line: 80, col: 0 }, 10);
+        state.push_mark();
+        state.go_to_line(1, 10);
+
+        assert!(state.pop_mark(10));
+        assert_eq!(state.cursor_position(), Position { line: 80, col: 0 });
+        assert!(state.pop_mark(10));
+        assert_eq!(state.cursor_position(), Position { line: 10, col: 2 });
+        assert_eq!(state.scroll_offset(), 10);
+        assert!(!state.pop_mark(10));
+        assert_eq!(state.cursor_position(), Position { line: 10, col: 2 });
+    }
+
+    #[test]
+    fn mark_ring_drops_oldest_and_skips_duplicates() {
+        let mut state = editor_with(&"line\n".repeat(40));
+        state.go_to_line(1, 10);
+        state.push_mark();
+        state.push_mark();
+        assert_eq!(state.mark_count(), 1);
+        for line in 1..=MARK_RING_SIZE {
+            state.go_to(Position { line, col: 0 }, 10);
+            state.push_mark();
+        }
+        assert_eq!(state.mark_count(), MARK_RING_SIZE);
+        // Line 0 fell off; the oldest left is line 1.
+        while state.pop_mark(10) {}
+        assert_eq!(state.cursor_position().line, 1);
+    }
+
+    #[test]
+    fn stats_for_known_document() {
+        let state = editor_with("Hello world\n  tide  editor  \n\nédité");
+        // 3 line breaks; "édité" is 5 chars but 7 bytes.
+        assert_eq!(state.stats(), DocStats { lines: 4, chars: 35, words: 5, bytes: 37 });
+        assert_eq!(EditorState::new_empty().stats(), DocStats { lines: 1, ..DocStats::default() });
+    }
+
+    #[test]
+    fn stats_refresh_after_edit() {
+        let mut state = editor_with("one two");
+        assert_eq!(state.stats().words, 2);
+        state.insert_text(" three");
+        assert_eq!(state.stats().words, 3);
+    }
+
+    #[test]
+    fn cursor_line_col_counts_chars_on_multibyte_line() {
+        let mut state = editor_with("ab\n한글abc");
+        // Byte col 6 is after the two 3-byte Hangul syllables.
+        state.go_to(Position { line: 1, col: 6 }, 10);
+        assert_eq!(state.cursor_line_col(), (2, 3));
+        state.go_to(Position { line: 0, col: 0 }, 10);
+        assert_eq!(state.cursor_line_col(), (1, 1));
+    }
+
+    fn type_str(state: &mut EditorState, text: &str) {
+        for ch in text.chars() {
+            state.handle_action(EditorAction::InsertChar(ch));
+        }
+    }
+
+    #[test]
+    fn typing_coalesces_into_one_undo() {
+        let mut state = EditorState::new_empty();
+        type_str(&mut state, "hello");
+        assert_eq!(state.buffer.line(0), Some("hello"));
+        state.handle_action(EditorAction::Undo);
+        assert_eq!(state.buffer.line(0), Some(""));
+        assert_eq!(state.cursor_position(), Position { line: 0, col: 0 });
+        state.handle_action(EditorAction::Redo);
+        assert_eq!(state.buffer.line(0), Some("hello"));
+        assert_eq!(state.cursor_position(), Position { line: 0, col: 5 });
+    }
+
+    #[test]
+    fn cursor_move_starts_new_undo_group() {
+        let mut state = EditorState::new_empty();
+        type_str(&mut state, "ab");
+        state.handle_action(EditorAction::MoveLeft);
+        state.handle_action(EditorAction::MoveRight);
+        type_str(&mut state, "cd");
+        state.handle_action(EditorAction::Undo);
+        assert_eq!(state.buffer.line(0), Some("ab"));
+        state.handle_action(EditorAction::Undo);
+        assert_eq!(state.buffer.line(0), Some(""));
+    }
+
+    #[test]
+    fn whitespace_ends_typing_group() {
+        let mut state = EditorState::new_empty();
+        type_str(&mut state, "hello world");
+        state.handle_action(EditorAction::Undo);
+        assert_eq!(state.buffer.line(0), Some("hello "));
+        state.handle_action(EditorAction::Undo);
+        assert_eq!(state.buffer.line(0), Some("hello"));
+    }
+
+    #[test]
+    fn enter_with_indent_undoes_in_one_step() {
+        let mut state = editor_with("    foo");
+        state.handle_action(EditorAction::Enter);
+        assert_eq!(state.buffer.line(1), Some("    "));
+        state.handle_action(EditorAction::Undo);
+        assert_eq!(state.buffer.line_count(), 1);
+        assert_eq!(state.buffer.line(0), Some("    foo"));
+    }
+
+    #[test]
+    fn enter_between_braces_opens_an_indented_block() {
+        let mut state = open_temp("block.rs", "    if x {}");
+        assert_eq!(state.language_config().indent, IndentConfig::spaces(4));
+        state.go_to(Position { line: 0, col: 10 }, 10);
+        state.handle_action(EditorAction::Enter);
+        assert_eq!(state.buffer.line(0), Some("    if x {"));
+        assert_eq!(state.buffer.line(1), Some("        "));
+        assert_eq!(state.buffer.line(2), Some("    }"));
+        assert_eq!(state.cursor.position, Position { line: 1, col: 8 });
+        state.handle_action(EditorAction::Undo);
+        assert_eq!(state.buffer.line_count(), 1);
+        assert_eq!(state.buffer.line(0), Some("    if x {}"));
+    }
+
+    #[test]
+    fn enter_outside_a_bracket_pair_only_copies_indent() {
+        let mut state = editor_with("  f(a)");
+        state.go_to(Position { line: 0, col: 5 }, 10);
+        state.handle_action(EditorAction::Enter);
+        assert_eq!(state.buffer.line_count(), 2);
+        assert_eq!(state.buffer.line(1), Some("  )"));
+        // Mismatched pair
+        let mut state = editor_with("{)");
+        state.go_to(Position { line: 0, col: 1 }, 10);
+        state.handle_action(EditorAction::Enter);
+        assert_eq!(state.buffer.line_count(), 2);
+    }
+
+    #[test]
+    fn gutter_width_grows_with_line_count() {
+        assert_eq!(EditorState::new_empty().gutter_width_chars(), 1);
+        assert_eq!(editor_with(&"x\n".repeat(9)).gutter_width_chars(), 2);
+        assert_eq!(editor_with(&"x\n".repeat(99)).gutter_width_chars(), 3);
+        assert_eq!(editor_with(&"x\n".repeat(999)).gutter_width_chars(), 4);
+    }
+
+    #[test]
+    fn gutter_info_follows_scroll_offset() {
+        let mut state = editor_with("a\nb\nc\nd");
+        state.set_scroll_offset(1);
+        let gutter = state.gutter_info(10);
+        let numbers: Vec<&str> = gutter.iter().map(|g| g.display.as_str()).collect();
+        assert_eq!(numbers, ["2", "3", "4"]);
+        assert!(gutter.iter().all(|g| !g.is_wrap_continuation));
+    }
+
+    #[test]
+    fn gutter_info_flags_wrap_continuations() {
+        let mut state = editor_with("abcdefghij\nxy");
+        state.set_wrap_width(Some(4));
+        let gutter = state.gutter_info(10);
+        assert_eq!(gutter.len(), 4);
+        assert_eq!(gutter[0], GutterLine { buffer_line: 0, display: "1".into(), is_wrap_continuation: false });
+        assert_eq!(gutter[1], GutterLine { buffer_line: 0, display: String::new(), is_wrap_continuation: true });
+        assert!(gutter[2].is_wrap_continuation && gutter[2].buffer_line == 0);
+        assert_eq!(gutter[3], GutterLine { buffer_line: 1, display: "2".into(), is_wrap_continuation: false });
+        assert_eq!(state.gutter_info(2).len(), 2);
+    }
+
+    #[test]
+    fn display_rows_match_gutter_rows() {
+        let mut state = editor_with("abcdefghij\nxy");
+        state.set_wrap_width(Some(4));
+        let rows = state.visible_display_rows(10);
+        let text: Vec<String> = rows
+            .iter()
+            .map(|r| r.iter().map(|s| s.text.as_str()).collect())
+            .collect();
+        assert_eq!(text, ["abcd", "efgh", "ij", "xy"]);
+        assert_eq!(rows.len(), state.gutter_info(10).len());
+    }
+
+    #[test]
+    fn ensure_cursor_visible_counts_wrapped_rows() {
+        // Rows: 3 + 3 + 1. Four buffer lines would fit in 4 rows, but the
+        // wrapped lines above push the cursor line off screen.
+        let mut state = editor_with("abcdefghij\nabcdefghij\nxy");
+        state.set_wrap_width(Some(4));
+        state.set_scroll_offset(0);
+        state.ensure_cursor_visible(4);
+        assert_eq!(state.scroll_offset(), 1);
+        let gutter = state.gutter_info(4);
+        assert_eq!(gutter.last().unwrap().buffer_line, 2);
+    }
+
+    fn line_text(spans: &[StyledSpan]) -> String {
+        spans.iter().map(|s| s.text.as_str()).collect()
+    }
+
+    #[test]
+    fn render_whitespace_all_marks_tab_and_trailing_space() {
+        let mut state = editor_with("a\tb ");
+        state.set_render_whitespace(WhitespaceMode::All);
+        let spans = &state.visible_highlighted_lines(1)[0];
+        assert_eq!(line_text(spans), "a\u{2192}b\u{b7}");
+
+        let tab = spans.iter().find(|s| s.text.contains('\u{2192}')).unwrap();
+        assert!(tab.style.dim);
+        assert_eq!(tab.style.background, None);
+        let trailing = spans.iter().find(|s| s.text.contains('\u{b7}')).unwrap();
+        assert!(trailing.style.dim);
+        assert_eq!(trailing.style.background, Some(TRAILING_WHITESPACE_BG));
+        let a = spans.iter().find(|s| s.text.contains('a')).unwrap();
+        assert!(!a.style.dim);
+    }
+
+    #[test]
+    fn render_whitespace_boundary_skips_single_inner_spaces() {
+        let mut state = editor_with("a b  c\t ");
+        state.set_render_whitespace(WhitespaceMode::Boundary);
+        let spans = &state.visible_highlighted_lines(1)[0];
+        assert_eq!(line_text(spans), "a b\u{b7}\u{b7}c\u{2192}\u{b7}");
+        // Tab and space after `c` are trailing.
+        let flagged: String = spans
+            .iter()
+            .filter(|s| s.style.background == Some(TRAILING_WHITESPACE_BG))
+            .map(|s| s.text.as_str())
+            .collect();
+        assert_eq!(flagged, "\u{2192}\u{b7}");
+    }
+
+    #[test]
+    fn render_whitespace_none_leaves_text_and_columns_alone() {
+        let mut state = editor_with("\tx  ");
+        assert_eq!(state.render_whitespace(), WhitespaceMode::None);
+        assert_eq!(line_text(&state.visible_highlighted_lines(1)[0]), "\tx  ");
+        state.set_render_whitespace(WhitespaceMode::All);
+        let marked = line_text(&state.visible_highlighted_lines(1)[0]);
+        assert_eq!(marked.chars().count(), "\tx  ".chars().count());
+        assert_eq!(state.cursor_line_col(), (1, 5));
+    }
+
+    fn lines(state: &EditorState) -> Vec<&str> {
+        (0..state.buffer.line_count()).filter_map(|i| state.buffer.line(i)).collect()
+    }
+
+    #[test]
+    fn block_text_is_per_row_column_slices() {
+        let state = editor_with("abcd\nefgh\nijkl\nmnop");
+        let block = BlockRange::from_corners((2, 3), (0, 1));
+        assert_eq!(block, BlockRange { first_line: 0, last_line: 2, left: 1, right: 3 });
+        assert_eq!(state.block_text(&block), "bc\nfg\njk");
+    }
+
+    #[test]
+    fn block_text_short_rows_contribute_what_they_have() {
+        let state = editor_with("abcd\ne\nijkl");
+        assert_eq!(state.block_text(&BlockRange::from_corners((0, 1), (2, 3))), "bc\n\njk");
+    }
+
+    #[test]
+    fn typing_over_block_inserts_on_each_row_in_one_undo() {
+        let mut state = editor_with("abcd\nefgh\nijkl");
+        let block = BlockRange::from_corners((0, 1), (2, 3));
+        state.replace_block(&block, "X");
+        assert_eq!(lines(&state), ["aXd", "eXh", "iXl"]);
+        assert_eq!(state.cursor_position(), Position { line: 2, col: 2 });
+
+        // Typing again at the resulting zero-width block
+        state.replace_block(&BlockRange::from_corners((0, 2), (2, 2)), "Y");
+        assert_eq!(lines(&state), ["aXYd", "eXYh", "iXYl"]);
+
+        state.handle_action(EditorAction::Undo);
+        assert_eq!(lines(&state), ["aXd", "eXh", "iXl"]);
+        state.handle_action(EditorAction::Undo);
+        assert_eq!(lines(&state), ["abcd", "efgh", "ijkl"]);
+    }
+
+    #[test]
+    fn block_backspace_deletes_one_column_per_row() {
+        let mut state = editor_with("abcd\nefgh\nijkl");
+        // Caret block at col 2 → backspace deletes col 1 on every row
+        state.replace_block(&BlockRange::from_corners((0, 1), (2, 2)), "");
+        assert_eq!(lines(&state), ["acd", "egh", "ikl"]);
+        state.handle_action(EditorAction::Undo);
+        assert_eq!(lines(&state), ["abcd", "efgh", "ijkl"]);
+    }
+
+    #[test]
+    fn block_insert_pads_short_rows() {
+        let mut state = editor_with("abcd\ne\nijkl");
+        state.replace_block(&BlockRange::from_corners((0, 3), (2, 3)), "|");
+        assert_eq!(lines(&state), ["abc|d", "e  |", "ijk|l"]);
+        // Deleting skips rows that end before the block
+        let mut state = editor_with("abcd\ne\nijkl");
+        state.replace_block(&BlockRange::from_corners((0, 2), (2, 4)), "");
+        assert_eq!(lines(&state), ["ab", "e", "ij"]);
+    }
+
+    #[test]
+    fn typing_an_opener_inserts_its_closer_and_the_closer_steps_over_it() {
+        let mut state = EditorState::new_empty();
+        type_str(&mut state, "f(");
+        assert_eq!(lines(&state), ["f()"]);
+        assert_eq!(state.cursor_position(), Position { line: 0, col: 2 });
+        type_str(&mut state, "x)");
+        assert_eq!(lines(&state), ["f(x)"]);
+        assert_eq!(state.cursor_position(), Position { line: 0, col: 4 });
+
+        // The pair undoes with the typing around it
+        state.handle_action(EditorAction::Undo);
+        assert_eq!(lines(&state), [""]);
+    }
+
+    #[test]
+    fn auto_close_skips_quotes_after_words_and_openers_before_text() {
+        let mut state = EditorState::new_empty();
+        type_str(&mut state, "don't");
+        assert_eq!(lines(&state), ["don't"]);
+
+        let mut state = editor_with("word");
+        state.cursor.set_position(Position { line: 0, col: 0 });
+        type_str(&mut state, "[");
+        assert_eq!(lines(&state), ["[word"]);
+
+        let mut state = EditorState::new_empty();
+        state.set_auto_close(false);
+        type_str(&mut state, "(\")");
+        assert_eq!(lines(&state), ["(\")"]);
+    }
+
+    #[test]
+    fn surround_range_wraps_selection_in_one_undo() {
+        let mut state = editor_with("say selection now");
+        let (start, end) = (Position { line: 0, col: 4 }, Position { line: 0, col: 13 });
+        assert!(state.surround_range(end, start, '"'));
+        assert_eq!(lines(&state), ["say \"selection\" now"]);
+        assert_eq!(state.cursor_position(), Position { line: 0, col: 14 });
+
+        state.handle_action(EditorAction::Undo);
+        assert_eq!(lines(&state), ["say selection now"]);
+    }
+
+    #[test]
+    fn surround_range_spans_lines_and_respects_auto_close() {
+        let mut state = editor_with("ab\ncd");
+        assert!(state.surround_range(Position { line: 0, col: 1 }, Position { line: 1, col: 1 }, '('));
+        assert_eq!(lines(&state), ["a(b", "c)d"]);
+        assert_eq!(state.cursor_position(), Position { line: 1, col: 1 });
+
+        let mut state = editor_with("word");
+        let (start, end) = (Position { line: 0, col: 0 }, Position { line: 0, col: 4 });
+        assert!(!state.surround_range(start, end, 'x'), "not an opener");
+        assert!(!state.surround_range(start, start, '['), "empty range");
+        state.set_auto_close(false);
+        assert!(!state.surround_range(start, end, '['));
+        assert_eq!(lines(&state), ["word"]);
+    }
+
+    fn rust_editor_with(text: &str) -> EditorState {
+        let mut state = editor_with(text);
+        state.detect_and_set_syntax(Path::new("main.rs"));
+        state
+    }
+
+    fn bracket_at(state: &mut EditorState, line: usize, col: usize) -> Option<(Position, Position)> {
+        state.go_to(Position { line, col }, 10);
+        state.matching_bracket()
+    }
+
+    #[test]
+    fn bracket_match_skips_parens_in_strings() {
+        let mut state = rust_editor_with("foo(\"a)b\", x);");
+        let open = Position { line: 0, col: 3 };
+        let close = Position { line: 0, col: 12 };
+        assert_eq!(bracket_at(&mut state, 0, 3), Some((open, close)));
+        assert_eq!(bracket_at(&mut state, 0, 12), Some((open, close)));
+        // A bracket inside the string has no partner
+        assert_eq!(bracket_at(&mut state, 0, 6), None);
+    }
+
+    #[test]
+    fn bracket_match_skips_comments_across_lines() {
+        let mut state = rust_editor_with("fn f() {\n    /* } */\n    // }\n}");
+        let close = Position { line: 3, col: 0 };
+        assert_eq!(bracket_at(&mut state, 0, 7), Some((Position { line: 0, col: 7 }, close)));
+        assert_eq!(bracket_at(&mut state, 3, 0), Some((Position { line: 0, col: 7 }, close)));
+    }
+
+    #[test]
+    fn bracket_match_backward_crosses_checkpoint_blocks() {
+        let mut text = String::from("let v = [\n");
+        text.push_str(&"    1,\n".repeat(299));
+        text.push_str("    \"[\",\n];");
+        let mut state = rust_editor_with(&text);
+        assert_eq!(
+            bracket_at(&mut state, 301, 0),
+            Some((Position { line: 0, col: 8 }, Position { line: 301, col: 0 })),
+        );
+    }
+
+    #[test]
+    fn bracket_match_is_cached_until_buffer_or_cursor_changes() {
+        let mut state = rust_editor_with("f(a)");
+        let pair = Some((Position { line: 0, col: 1 }, Position { line: 0, col: 3 }));
+        assert_eq!(bracket_at(&mut state, 0, 1), pair);
+        let cached = state.bracket_cache.get().expect("result cached");
+        assert_eq!(cached.pair, pair);
+        assert_eq!(state.matching_bracket(), pair);
+
+        // Deleting the closer bumps the buffer generation and drops the pair
+        state.go_to(Position { line: 0, col: 4 }, 10);
+        state.handle_action(EditorAction::Backspace);
+        assert_eq!(bracket_at(&mut state, 0, 1), None);
+    }
+
+    #[test]
+    fn bracket_match_without_syntax_is_naive() {
+        let mut state = editor_with("foo(\"a)b\", x);");
+        assert_eq!(
+            bracket_at(&mut state, 0, 3),
+            Some((Position { line: 0, col: 3 }, Position { line: 0, col: 6 })),
+        );
+    }
+
+    /// Open a scratch file named `name` holding `text`.
+    fn open_temp(name: &str, text: &str) -> EditorState {
+        let dir = std::env::temp_dir().join(format!("tide-editor-lang-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(name);
+        std::fs::write(&path, text).unwrap();
+        let state = EditorState::open(&path).unwrap();
+        let _ = std::fs::remove_file(&path);
+        state
+    }
+
+    #[test]
+    fn file_location_parses_line_and_column_suffixes() {
+        let parse = FileLocation::parse;
+        let at = |path: &str, line, col| FileLocation { path: path.into(), line, col };
+        assert_eq!(parse("src/main.rs:10:5"), at("src/main.rs", Some(10), Some(5)));
+        assert_eq!(parse("src/main.rs:10"), at("src/main.rs", Some(10), None));
+        assert_eq!(parse("src/main.rs:10:"), at("src/main.rs", Some(10), None));
+        assert_eq!(parse("src/main.rs"), at("src/main.rs", None, None));
+        // Only numeric parts are positions
+        assert_eq!(parse("notes:draft.md:3"), at("notes:draft.md", Some(3), None));
+        assert_eq!(parse("a:b:1:2"), at("a:b", Some(1), Some(2)));
+        assert_eq!(parse(":12"), at(":12", None, None));
+    }
+
+    #[test]
+    fn open_at_places_cursor_and_clamps_out_of_range_positions() {
+        let dir = std::env::temp_dir().join(format!("tide-editor-open-at-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("main.rs");
+        let text: String = (1..=40).map(|n| format!("line {n} é\n")).collect();
+        std::fs::write(&path, text).unwrap();
+        let spec = |suffix: &str| format!("{}{suffix}", path.display());
+
+        let state = EditorState::open_at(&spec(":10:5")).unwrap();
+        assert_eq!(state.cursor_position(), Position { line: 9, col: 4 });
+        assert_eq!(state.scroll_offset(), 9);
+
+        // Columns count characters: column 10 is after the two-byte é
+        let state = EditorState::open_at(&spec(":3:10")).unwrap();
+        assert_eq!(state.cursor_position(), Position { line: 2, col: "line 3 é".len() });
+
+        // Past the end: the last line, same column
+        let state = EditorState::open_at(&spec(":500:3")).unwrap();
+        assert_eq!(state.cursor_position(), Position { line: 39, col: 2 });
+        let state = EditorState::open_at(&spec(":7:99")).unwrap();
+        assert_eq!(state.cursor_position(), Position { line: 6, col: "line 7 é".len() });
+        let state = EditorState::open_at(&spec(":0")).unwrap();
+        assert_eq!(state.cursor_position(), Position { line: 0, col: 0 });
+
+        let plain = EditorState::open_at(&spec("")).unwrap();
+        assert_eq!(plain.cursor_position(), Position { line: 0, col: 0 });
+        assert_eq!(plain.file_path(), Some(path.as_path()));
+        assert!(EditorState::open_at(&format!("{}/missing.rs:3", dir.display())).is_err());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn python_file_indents_with_four_spaces() {
+        let mut state = open_temp("indent.py", "def f():\n\n");
+        assert_eq!(state.language_config().indent, IndentConfig::spaces(4));
+        state.go_to(Position { line: 1, col: 0 }, 10);
+        state.handle_action(EditorAction::InsertChar('\t'));
+        assert_eq!(state.buffer.line(1), Some("    "));
+        // Tab stops align: from column 2 only two spaces are needed
+        state.handle_action(EditorAction::Unindent);
+        state.insert_text("  ");
+        state.handle_action(EditorAction::InsertChar('\t'));
+        assert_eq!(state.buffer.line(1), Some("    "));
+        assert_eq!(state.cursor.position.col, 4);
+        assert_eq!(state.rulers(), &[79]);
+    }
+
+    #[test]
+    fn toggle_comment_uses_the_language_token() {
+        let mut state = rust_editor_with("    let x = 1;");
+        state.go_to(Position { line: 0, col: 8 }, 10);
+        state.handle_action(EditorAction::ToggleComment);
+        assert_eq!(state.buffer.line(0), Some("    // let x = 1;"));
+        assert_eq!(state.cursor.position.col, 11);
+        state.handle_action(EditorAction::ToggleComment);
+        assert_eq!(state.buffer.line(0), Some("    let x = 1;"));
+        assert_eq!(state.cursor.position.col, 8);
+
+        let mut py = open_temp("comment.py", "x = 1\n");
+        py.handle_action(EditorAction::ToggleComment);
+        assert_eq!(py.buffer.line(0), Some("# x = 1"));
+        // No token for plain text: nothing changes
+        let mut plain = editor_with("x");
+        plain.handle_action(EditorAction::ToggleComment);
+        assert_eq!(plain.buffer.line(0), Some("x"));
+    }
+
+    #[test]
+    fn go_file_indents_with_tabs() {
+        let mut state = open_temp("indent.go", "func f() {\n\n");
+        assert!(state.language_config().indent.use_tabs);
+        state.go_to(Position { line: 1, col: 0 }, 10);
+        state.handle_action(EditorAction::InsertChar('\t'));
+        assert_eq!(state.buffer.line(1), Some("\t"));
+    }
+
+    #[test]
+    fn set_language_config_overrides_indent_and_unindent_width() {
+        let mut state = rust_editor_with("        x");
+        state.set_language_config("Rust", LanguageConfig::new(IndentConfig::spaces(2), &[], Some("//")));
+        state.go_to(Position { line: 0, col: 8 }, 10);
+        state.handle_action(EditorAction::Unindent);
+        assert_eq!(state.buffer.line(0), Some("      x"));
+        // Plain text keeps inserting a literal tab
+        let mut plain = editor_with("");
+        plain.handle_action(EditorAction::InsertChar('\t'));
+        assert_eq!(plain.buffer.line(0), Some("\t"));
+    }
+
+    fn editor_with_clipboard(text: &str) -> (EditorState, Arc<tide_core::MemoryClipboard>) {
+        let clipboard = Arc::new(tide_core::MemoryClipboard::new());
+        let mut state = editor_with(text);
+        state.set_clipboard(clipboard.clone());
+        (state, clipboard)
+    }
+
+    #[test]
+    fn cut_writes_selection_to_clipboard_and_deletes_it() {
+        use tide_core::Clipboard;
+        let (mut state, clipboard) = editor_with_clipboard("hello world\nsecond line");
+        let end = Position { line: 1, col: 6 };
+        assert!(state.cut_range(end, Position { line: 0, col: 6 }));
+        assert_eq!(clipboard.read().as_deref(), Some("world\nsecond"));
+        assert_eq!(state.buffer.lines, vec!["hello  line"]);
+        assert_eq!(state.cursor.position, Position { line: 0, col: 6 });
+
+        // One undo restores the cut text
+        state.handle_action(EditorAction::Undo);
+        assert_eq!(state.buffer.lines, vec!["hello world", "second line"]);
+    }
+
+    #[test]
+    fn copy_and_paste_round_trip_through_clipboard() {
+        let (mut state, _clipboard) = editor_with_clipboard("abc");
+        assert!(state.copy_range(Position { line: 0, col: 0 }, Position { line: 0, col: 2 }));
+        assert_eq!(state.buffer.lines, vec!["abc"], "copy leaves the buffer alone");
+        state.go_to(Position { line: 0, col: 3 }, 10);
+        assert!(state.paste());
+        assert_eq!(state.buffer.lines, vec!["abcab"]);
+        // Empty ranges copy nothing
+        assert!(!state.copy_range(Position { line: 0, col: 1 }, Position { line: 0, col: 1 }));
+    }
+
+    #[test]
+    fn cut_without_clipboard_keeps_text() {
+        let mut state = editor_with("keep me");
+        assert!(!state.cut_range(Position { line: 0, col: 0 }, Position { line: 0, col: 4 }));
+        assert_eq!(state.buffer.lines, vec!["keep me"]);
+        assert!(!state.paste());
+    }
+}
@@ -23,10 +23,144 @@ pub(crate) enum EditOp {
     SwapLines { line_a: usize, line_b: usize },
 }
 
+/// Default maximum number of edit operations kept on the undo stack.
+pub(crate) const DEFAULT_UNDO_LIMIT: usize = 10_000;
+
+/// An undo stack entry. Entries sharing a `group` are undone/redone together.
+#[derive(Debug, Clone)]
+pub(crate) struct UndoEntry {
+    pub(crate) op: EditOp,
+    pub(crate) cursor_before: Position,
+    pub(crate) group: u64,
+}
+
+/// Undo grouping state for a buffer.
+#[derive(Debug)]
+pub(crate) struct UndoGroups {
+    /// Nesting depth of `begin_undo_group` calls.
+    depth: usize,
+    /// Group id shared by every edit recorded while `depth > 0`.
+    open: Option<u64>,
+    /// One-shot: the next recorded edit joins the group on top of the undo stack.
+    join_last: bool,
+    next_id: u64,
+    /// Maximum number of entries on the undo stack.
+    limit: usize,
+}
+
+impl Default for UndoGroups {
+    fn default() -> Self {
+        Self { depth: 0, open: None, join_last: false, next_id: 0, limit: DEFAULT_UNDO_LIMIT }
+    }
+}
+
 impl Buffer {
-    /// Undo the last edit. Returns the cursor position to restore, or None if nothing to undo.
+    /// Start an undo group: every edit until the matching `end_undo_group` is
+    /// undone as one step. Groups nest; only the outermost pair matters.
+    pub fn begin_undo_group(&mut self) {
+        if self.undo_groups.depth == 0 {
+            self.undo_groups.open = Some(self.alloc_undo_group());
+        }
+        self.undo_groups.depth += 1;
+    }
+
+    /// Close the group opened by `begin_undo_group`.
+    pub fn end_undo_group(&mut self) {
+        self.undo_groups.depth = self.undo_groups.depth.saturating_sub(1);
+        if self.undo_groups.depth == 0 {
+            self.undo_groups.open = None;
+        }
+    }
+
+    /// Make the next recorded edit part of the most recent undo group
+    /// (used to coalesce consecutive typing).
+    pub fn join_last_undo_group(&mut self) {
+        if !self.undo_stack.is_empty() {
+            self.undo_groups.join_last = true;
+        }
+    }
+
+    /// Set the maximum number of edit operations kept for undo. Oldest
+    /// groups are dropped first.
+    pub fn set_undo_limit(&mut self, limit: usize) {
+        self.undo_groups.limit = limit.max(1);
+        self.trim_undo_stack();
+    }
+
+    pub fn undo_limit(&self) -> usize {
+        self.undo_groups.limit
+    }
+
+    fn alloc_undo_group(&mut self) -> u64 {
+        self.undo_groups.next_id += 1;
+        self.undo_groups.next_id
+    }
+
+    /// Push an edit onto the undo stack and invalidate redo history.
+    pub(crate) fn record_undo(&mut self, op: EditOp, cursor_before: Position) {
+        let joined = std::mem::take(&mut self.undo_groups.join_last)
+            .then(|| self.undo_stack.last().map(|e| e.group))
+            .flatten();
+        let group = match (self.undo_groups.open, joined) {
+            (Some(open), _) => open,
+            (None, Some(last)) => last,
+            (None, None) => self.alloc_undo_group(),
+        };
+        self.undo_stack.push(UndoEntry { op, cursor_before, group });
+        self.redo_stack.clear();
+        self.trim_undo_stack();
+    }
+
+    /// Drop whole groups from the bottom of the stack until it fits the limit.
+    /// The group currently being built is never dropped.
+    fn trim_undo_stack(&mut self) {
+        while self.undo_stack.len() > self.undo_groups.limit {
+            let oldest = self.undo_stack[0].group;
+            if self.undo_groups.open == Some(oldest) {
+                break;
+            }
+            let n = self.undo_stack.iter().take_while(|e| e.group == oldest).count();
+            self.undo_stack.drain(..n);
+        }
+    }
+
+    /// Undo the last edit group. Returns the cursor position to restore, or None if nothing to undo.
     pub fn undo(&mut self) -> Option<Position> {
-        let (op, cursor_before) = self.undo_stack.pop()?;
+        self.end_open_groups();
+        let group = self.undo_stack.last()?.group;
+        let mut cursor = None;
+        while self.undo_stack.last().is_some_and(|e| e.group == group) {
+            match self.undo_one() {
+                Some(pos) => cursor = Some(pos),
+                None => break,
+            }
+        }
+        cursor
+    }
+
+    /// Redo the last undone edit group. Returns the new cursor position, or None if nothing to redo.
+    pub fn redo(&mut self) -> Option<Position> {
+        self.end_open_groups();
+        let group = self.redo_stack.last()?.group;
+        let mut cursor = None;
+        while self.redo_stack.last().is_some_and(|e| e.group == group) {
+            match self.redo_one() {
+                Some(pos) => cursor = Some(pos),
+                None => break,
+            }
+        }
+        cursor
+    }
+
+    /// Undo/redo always lands on a group boundary.
+    fn end_open_groups(&mut self) {
+        self.undo_groups.depth = 0;
+        self.undo_groups.open = None;
+        self.undo_groups.join_last = false;
+    }
+
+    fn undo_one(&mut self) -> Option<Position> {
+        let UndoEntry { op, cursor_before, group } = self.undo_stack.pop()?;
         let ok = match &op {
             EditOp::InsertChar { pos, .. } => {
                 // Reverse of insert: remove the char
@@ -156,7 +290,7 @@ impl Buffer {
             }
         };
         if ok {
-            self.redo_stack.push((op, cursor_before));
+            self.redo_stack.push(UndoEntry { op, cursor_before, group });
             self.generation += 1;
             Some(cursor_before)
         } else {
@@ -165,9 +299,8 @@ impl Buffer {
         }
     }
 
-    /// Redo the last undone edit. Returns the new cursor position, or None if nothing to redo.
-    pub fn redo(&mut self) -> Option<Position> {
-        let (op, cursor_before) = self.redo_stack.pop()?;
+    fn redo_one(&mut self) -> Option<Position> {
+        let UndoEntry { op, cursor_before, group } = self.redo_stack.pop()?;
         let new_cursor = match &op {
             EditOp::InsertChar { pos, ch } => {
                 if pos.line < self.lines.len() {
@@ -265,26 +398,7 @@ impl Buffer {
                     None
                 } else {
                     let col = pos.col.min(self.lines[pos.line].len());
-                    let suffix = self.lines[pos.line][col..].to_string();
-                    self.lines[pos.line].truncate(col);
-                    let text_lines: Vec<&str> = text.split('\n').collect();
-                    if text_lines.len() == 1 {
-                        self.lines[pos.line].push_str(text_lines[0]);
-                        let end_col = self.lines[pos.line].len();
-                        self.lines[pos.line].push_str(&suffix);
-                        Some(Position { line: pos.line, col: end_col })
-                    } else {
-                        self.lines[pos.line].push_str(text_lines[0]);
-                        for (i, tl) in text_lines[1..text_lines.len() - 1].iter().enumerate() {
-                            self.lines.insert(pos.line + 1 + i, tl.to_string());
-                        }
-                        let last_idx = pos.line + text_lines.len() - 1;
-                        let mut last_line = text_lines.last().unwrap().to_string();
-                        let end_col = last_line.len();
-                        last_line.push_str(&suffix);
-                        self.lines.insert(last_idx, last_line);
-                        Some(Position { line: last_idx, col: end_col })
-                    }
+                    Some(self.splice_text(Position { line: pos.line, col }, text))
                 }
             }
             EditOp::DeleteLine { line, .. } => {
@@ -315,7 +429,7 @@ impl Buffer {
             }
         };
         if let Some(cursor) = new_cursor {
-            self.undo_stack.push((op, cursor_before));
+            self.undo_stack.push(UndoEntry { op, cursor_before, group });
             self.generation += 1;
             Some(cursor)
         } else {
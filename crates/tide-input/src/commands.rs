@@ -0,0 +1,101 @@
+// Command registry: every GlobalAction with its label and current hotkey, for
+// a command palette to list and fuzzy-search.
+
+use crate::{GlobalAction, Hotkey, KeybindingMap};
+
+/// One palette entry.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Command {
+    pub action: GlobalAction,
+    pub label: &'static str,
+    /// First hotkey bound to the action, if any.
+    pub hotkey: Option<Hotkey>,
+}
+
+impl Command {
+    /// Hotkey as shown next to the label (e.g. "Cmd+Shift+T").
+    pub fn hotkey_display(&self) -> Option<String> {
+        self.hotkey.as_ref().map(Hotkey::display)
+    }
+}
+
+/// All commands in `GlobalAction::all_actions()` order.
+#[derive(Debug, Clone)]
+pub struct CommandRegistry {
+    commands: Vec<Command>,
+}
+
+impl CommandRegistry {
+    pub fn new(bindings: &KeybindingMap) -> Self {
+        let commands = GlobalAction::all_actions()
+            .into_iter()
+            .map(|action| Command {
+                label: action.label(),
+                hotkey: bindings.hotkey_for(&action).cloned(),
+                action,
+            })
+            .collect();
+        Self { commands }
+    }
+
+    /// Refresh hotkeys after the keybinding map changed.
+    pub fn rebind(&mut self, bindings: &KeybindingMap) {
+        for command in &mut self.commands {
+            command.hotkey = bindings.hotkey_for(&command.action).cloned();
+        }
+    }
+
+    pub fn commands(&self) -> &[Command] {
+        &self.commands
+    }
+
+    /// Commands whose label fuzzy-matches `query`, best first. An empty query
+    /// returns every command in registry order.
+    pub fn search(&self, query: &str) -> Vec<&Command> {
+        let query: Vec<char> = query.trim().to_lowercase().chars().collect();
+        let mut scored: Vec<(i32, usize, &Command)> = self
+            .commands
+            .iter()
+            .enumerate()
+            .filter_map(|(i, c)| Some((fuzzy_score(&query, c.label)?, i, c)))
+            .collect();
+        // Higher score first; then shorter labels, then registry order
+        scored.sort_by(|a, b| {
+            b.0.cmp(&a.0)
+                .then(a.2.label.len().cmp(&b.2.label.len()))
+                .then(a.1.cmp(&b.1))
+        });
+        scored.into_iter().map(|(_, _, c)| c).collect()
+    }
+}
+
+/// Score `label` against the lowercased `query` chars, matched in order as a
+/// subsequence (case-insensitive). Consecutive matches and matches at word
+/// starts score higher; skipped characters cost a little. `None` if the
+/// query isn't a subsequence.
+fn fuzzy_score(query: &[char], label: &str) -> Option<i32> {
+    let mut score = 0;
+    let mut next = 0;
+    let mut prev_match: Option<usize> = None;
+    let mut prev_char = ' ';
+    for (i, ch) in label.chars().enumerate() {
+        if next == query.len() {
+            break;
+        }
+        if ch.to_lowercase().next() == Some(query[next]) {
+            score += 1;
+            if prev_char == ' ' {
+                score += 8;
+            }
+            match prev_match {
+                Some(p) if p + 1 == i => score += 5,
+                Some(p) => score -= (i - p - 1).min(5) as i32,
+                None => score -= i.min(5) as i32,
+            }
+            prev_match = Some(i);
+            next += 1;
+        }
+        prev_char = ch;
+    }
+    (next == query.len()).then_some(score)
+}
@@ -0,0 +1,96 @@
+// Software key repeat: a held key fires again after an initial delay, then
+// at a fixed rate, independent of the OS repeat settings. Meant for held
+// navigation (Cmd+Arrow focus moves, keyboard border resize) where a steady
+// cadence matters more than matching the system's typing repeat.
+
+use std::time::{Duration, Instant};
+
+use tide_core::{Key, Modifiers};
+
+/// Default for `KeyRepeat::new`: how long a key must be held before it repeats.
+pub const DEFAULT_KEY_REPEAT_DELAY: Duration = Duration::from_millis(400);
+
+/// Default for `KeyRepeat::new`: time between repeats once they start.
+pub const DEFAULT_KEY_REPEAT_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Tracks the held key chord and when it next repeats. Only the last chord
+/// pressed repeats, like the OS does; releasing another key leaves it
+/// running, and letting go of one of its modifiers stops it.
+#[derive(Debug, Clone)]
+pub struct KeyRepeat {
+    delay: Duration,
+    interval: Duration,
+    /// The held key, its modifiers, and when its next repeat is due.
+    held: Option<(Key, Modifiers, Instant)>,
+}
+
+impl KeyRepeat {
+    /// Repeat after `delay`, then every `interval` (at least 1ms).
+    pub fn new(delay: Duration, interval: Duration) -> Self {
+        Self { delay, interval: interval.max(Duration::from_millis(1)), held: None }
+    }
+
+    /// Start repeating `key` with `modifiers`, pressed now.
+    pub fn on_key_down(&mut self, key: Key, modifiers: Modifiers) {
+        self.on_key_down_at(key, modifiers, Instant::now());
+    }
+
+    /// Start repeating `key` with `modifiers`, pressed at `now`. Pressing the
+    /// held chord again (an OS repeat) keeps the current schedule.
+    pub fn on_key_down_at(&mut self, key: Key, modifiers: Modifiers, now: Instant) {
+        if self.held_key() == Some((key, modifiers)) {
+            return;
+        }
+        self.held = Some((key, modifiers, now + self.delay));
+    }
+
+    /// Stop repeating if `key` is the one held.
+    pub fn on_key_up(&mut self, key: Key) {
+        if self.held.is_some_and(|(held, _, _)| held == key) {
+            self.held = None;
+        }
+    }
+
+    /// Stop repeating if the held chord's modifiers are no longer exactly
+    /// `modifiers` (e.g. Cmd released before the key).
+    pub fn on_modifiers_changed(&mut self, modifiers: Modifiers) {
+        if self.held.is_some_and(|(_, held, _)| held != modifiers) {
+            self.held = None;
+        }
+    }
+
+    /// Stop repeating whatever is held (focus lost, modal opened).
+    pub fn cancel(&mut self) {
+        self.held = None;
+    }
+
+    /// The held chord if a repeat is due at `now`. Repeats keep their
+    /// cadence across slightly late ticks; after a stall longer than the
+    /// interval the missed ones are dropped rather than fired in a burst.
+    pub fn tick(&mut self, now: Instant) -> Option<(Key, Modifiers)> {
+        let (key, modifiers, due) = self.held.as_mut()?;
+        if now < *due {
+            return None;
+        }
+        let next = *due + self.interval;
+        *due = if next > now { next } else { now + self.interval };
+        Some((*key, *modifiers))
+    }
+
+    /// When the next repeat is due (for the event loop's `WaitUntil`).
+    pub fn next_deadline(&self) -> Option<Instant> {
+        self.held.map(|(_, _, due)| due)
+    }
+
+    /// The chord being repeated, if any. The app swallows the OS's own
+    /// repeats of it, so the key doesn't fire from both sources.
+    pub fn held_key(&self) -> Option<(Key, Modifiers)> {
+        self.held.map(|(key, modifiers, _)| (key, modifiers))
+    }
+}
+
+impl Default for KeyRepeat {
+    fn default() -> Self {
+        Self::new(DEFAULT_KEY_REPEAT_DELAY, DEFAULT_KEY_REPEAT_INTERVAL)
+    }
+}
@@ -2,7 +2,16 @@
 // Implements tide_core::InputRouter with hit-testing, focus management,
 // hotkey interception, and drag routing.
 
-use tide_core::{InputEvent, Key, Modifiers, MouseButton, PaneId, Rect, Vec2};
+use std::time::{Duration, Instant};
+
+use tide_core::{InputEvent, Key, ModifierNormalization, Modifiers, MouseButton, PaneId, Rect, Vec2};
+
+mod commands;
+mod key_repeat;
+mod recording;
+pub use commands::{Command, CommandRegistry};
+pub use key_repeat::{KeyRepeat, DEFAULT_KEY_REPEAT_DELAY, DEFAULT_KEY_REPEAT_INTERVAL};
+pub use recording::{Player, RecordedEvent, Recorder, Recording};
 
 // ──────────────────────────────────────────────
 // Action types
@@ -17,6 +26,9 @@ pub enum Action {
     GlobalAction(GlobalAction),
     /// Start or continue dragging a border at the given position.
     DragBorder(Vec2),
+    /// Cmd+click (or Ctrl+click) in a pane: open the link or file path under
+    /// the position if there is one, otherwise treat it as a plain click.
+    OpenLinkAt(PaneId, Vec2),
     /// No action to take.
     None,
 }
@@ -36,6 +48,7 @@ pub enum GlobalAction {
     SplitHorizontal,
     SplitVerticalHere,
     SplitHorizontalHere,
+    DuplicatePane,
     ClosePane,
     FocusArea(AreaSlot),
     Navigate(Direction),
@@ -66,6 +79,11 @@ pub enum GlobalAction {
     CloseWorkspace,
     ToggleFileTree,
     ToggleWorkspaceSidebar,
+    ClearScrollback,
+    ResetTerminal,
+    PrevPrompt,
+    NextPrompt,
+    SelectCommandOutput,
 }
 
 impl GlobalAction {
@@ -76,6 +94,7 @@ impl GlobalAction {
             GlobalAction::SplitHorizontal => "Split Horizontal",
             GlobalAction::SplitVerticalHere => "Split Vertical Here",
             GlobalAction::SplitHorizontalHere => "Split Horizontal Here",
+            GlobalAction::DuplicatePane => "Duplicate Pane",
             GlobalAction::ClosePane => "Close Pane",
             GlobalAction::FocusArea(AreaSlot::Slot1) => "Focus Slot 1",
             GlobalAction::FocusArea(AreaSlot::Slot2) => "Focus Slot 2",
@@ -111,6 +130,11 @@ impl GlobalAction {
             GlobalAction::CloseWorkspace => "Close Workspace",
             GlobalAction::ToggleFileTree => "Toggle File Tree",
             GlobalAction::ToggleWorkspaceSidebar => "Toggle Workspace Sidebar",
+            GlobalAction::ClearScrollback => "Clear Scrollback",
+            GlobalAction::ResetTerminal => "Reset Terminal",
+            GlobalAction::PrevPrompt => "Previous Prompt",
+            GlobalAction::NextPrompt => "Next Prompt",
+            GlobalAction::SelectCommandOutput => "Select Command Output",
         }
     }
 
@@ -121,6 +145,7 @@ impl GlobalAction {
             GlobalAction::SplitHorizontal => "SplitHorizontal",
             GlobalAction::SplitVerticalHere => "SplitVerticalHere",
             GlobalAction::SplitHorizontalHere => "SplitHorizontalHere",
+            GlobalAction::DuplicatePane => "DuplicatePane",
             GlobalAction::ClosePane => "ClosePane",
             GlobalAction::FocusArea(AreaSlot::Slot1) => "FocusSlot1",
             GlobalAction::FocusArea(AreaSlot::Slot2) => "FocusSlot2",
@@ -156,6 +181,11 @@ impl GlobalAction {
             GlobalAction::CloseWorkspace => "CloseWorkspace",
             GlobalAction::ToggleFileTree => "ToggleFileTree",
             GlobalAction::ToggleWorkspaceSidebar => "ToggleWorkspaceSidebar",
+            GlobalAction::ClearScrollback => "ClearScrollback",
+            GlobalAction::ResetTerminal => "ResetTerminal",
+            GlobalAction::PrevPrompt => "PrevPrompt",
+            GlobalAction::NextPrompt => "NextPrompt",
+            GlobalAction::SelectCommandOutput => "SelectCommandOutput",
         }
     }
 
@@ -166,6 +196,7 @@ impl GlobalAction {
             "SplitHorizontal" => Some(GlobalAction::SplitHorizontal),
             "SplitVerticalHere" => Some(GlobalAction::SplitVerticalHere),
             "SplitHorizontalHere" => Some(GlobalAction::SplitHorizontalHere),
+            "DuplicatePane" => Some(GlobalAction::DuplicatePane),
             "ClosePane" => Some(GlobalAction::ClosePane),
             "FocusSlot1" => Some(GlobalAction::FocusArea(AreaSlot::Slot1)),
             "FocusSlot2" => Some(GlobalAction::FocusArea(AreaSlot::Slot2)),
@@ -201,6 +232,11 @@ impl GlobalAction {
             "CloseWorkspace" => Some(GlobalAction::CloseWorkspace),
             "ToggleFileTree" => Some(GlobalAction::ToggleFileTree),
             "ToggleWorkspaceSidebar" => Some(GlobalAction::ToggleWorkspaceSidebar),
+            "ClearScrollback" => Some(GlobalAction::ClearScrollback),
+            "ResetTerminal" => Some(GlobalAction::ResetTerminal),
+            "PrevPrompt" => Some(GlobalAction::PrevPrompt),
+            "NextPrompt" => Some(GlobalAction::NextPrompt),
+            "SelectCommandOutput" => Some(GlobalAction::SelectCommandOutput),
             _ => None,
         }
     }
@@ -212,6 +248,7 @@ impl GlobalAction {
             GlobalAction::SplitVertical,
             GlobalAction::SplitHorizontalHere,
             GlobalAction::SplitVerticalHere,
+            GlobalAction::DuplicatePane,
             GlobalAction::ClosePane,
             GlobalAction::Navigate(Direction::Up),
             GlobalAction::Navigate(Direction::Down),
@@ -244,6 +281,11 @@ impl GlobalAction {
             GlobalAction::ScrollHalfPageUp,
             GlobalAction::ScrollHalfPageDown,
             GlobalAction::ToggleWorkspaceSidebar,
+            GlobalAction::ClearScrollback,
+            GlobalAction::ResetTerminal,
+            GlobalAction::PrevPrompt,
+            GlobalAction::NextPrompt,
+            GlobalAction::SelectCommandOutput,
         ]
     }
 }
@@ -287,6 +329,57 @@ impl Hotkey {
         parts.join("+")
     }
 
+    /// Parse a `display()`-style string (e.g. "Cmd+Shift+T") back into a hotkey.
+    /// Modifiers (`Cmd`/`Meta`, `Ctrl`, `Alt`/`Opt`, `Shift`) are case-insensitive;
+    /// the last token is the key, either a `display_key` symbol or a `key_from_name` name.
+    pub fn parse(s: &str) -> Option<Hotkey> {
+        let s = s.trim();
+        // A trailing "+" is (part of) the key itself ("Cmd++", "Cmd+Kp+"), not a separator.
+        let (mods, key) = match s.strip_suffix('+') {
+            Some("") => ("", "+"),
+            Some(rest) if rest.ends_with('+') => (&rest[..rest.len() - 1], "+"),
+            Some(rest) => match rest.rfind('+') {
+                Some(i) => (&s[..i], &s[i + 1..]),
+                None => ("", s),
+            },
+            None => s.rsplit_once('+').unwrap_or(("", s)),
+        };
+
+        let mut hotkey = Hotkey::new(Key::Char(' '), false, false, false, false);
+        if !mods.is_empty() {
+            for m in mods.split('+') {
+                match m.trim().to_ascii_lowercase().as_str() {
+                    "cmd" | "meta" => hotkey.meta = true,
+                    "ctrl" => hotkey.ctrl = true,
+                    "alt" | "opt" => hotkey.alt = true,
+                    "shift" => hotkey.shift = true,
+                    _ => return None,
+                }
+            }
+        }
+
+        hotkey.key = match key.trim() {
+            "\u{2191}" => Key::Up,
+            "\u{2193}" => Key::Down,
+            "\u{2190}" => Key::Left,
+            "\u{2192}" => Key::Right,
+            "Esc" => Key::Escape,
+            "Bksp" => Key::Backspace,
+            "Del" => Key::Delete,
+            "PgUp" => Key::PageUp,
+            "PgDn" => Key::PageDown,
+            "Ins" => Key::Insert,
+            name if name.starts_with("Kp") => Self::key_from_name(&format!("Keypad{}", &name[2..]))?,
+            name => Self::key_from_name(name)?,
+        };
+        Some(hotkey)
+    }
+
+    /// The modifier flags as a `Modifiers`.
+    pub fn modifiers(&self) -> Modifiers {
+        Modifiers { shift: self.shift, ctrl: self.ctrl, alt: self.alt, meta: self.meta }
+    }
+
     /// Check if this hotkey matches a given key + modifiers.
     pub fn matches(&self, key: &Key, modifiers: &Modifiers) -> bool {
         // For character keys, compare case-insensitively
@@ -294,11 +387,7 @@ impl Hotkey {
             (Key::Char(a), Key::Char(b)) => a.to_lowercase().eq(b.to_lowercase()),
             (a, b) => a == b,
         };
-        key_matches
-            && self.shift == modifiers.shift
-            && self.ctrl == modifiers.ctrl
-            && self.meta == modifiers.meta
-            && self.alt == modifiers.alt
+        key_matches && modifiers.only(self.modifiers())
     }
 
     /// Serialization key for the key field.
@@ -318,7 +407,9 @@ impl Hotkey {
             Key::End => "End".to_string(),
             Key::PageUp => "PageUp".to_string(),
             Key::PageDown => "PageDown".to_string(),
-            _ => format!("{:?}", self.key),
+            Key::Insert => "Insert".to_string(),
+            Key::F(n) => format!("F{n}"),
+            Key::Keypad(c) => format!("Keypad{c}"),
         }
     }
 
@@ -338,6 +429,17 @@ impl Hotkey {
             "End" => Some(Key::End),
             "PageUp" => Some(Key::PageUp),
             "PageDown" => Some(Key::PageDown),
+            "Insert" => Some(Key::Insert),
+            _ if s.len() > 1 && s.starts_with('F') => {
+                let n: u8 = s[1..].parse().ok()?;
+                (1..=24).contains(&n).then_some(Key::F(n))
+            }
+            _ if s.starts_with("Keypad") => {
+                let mut chars = s["Keypad".len()..].chars();
+                let c = chars.next()?;
+                (chars.next().is_none() && (c.is_ascii_digit() || ".+-*/=".contains(c)))
+                    .then_some(Key::Keypad(c))
+            }
             _ => {
                 let mut chars = s.chars();
                 let c = chars.next()?;
@@ -376,10 +478,27 @@ pub fn display_key(key: &Key) -> String {
         Key::End => "End".to_string(),
         Key::PageUp => "PgUp".to_string(),
         Key::PageDown => "PgDn".to_string(),
-        _ => "?".to_string(),
+        Key::Insert => "Ins".to_string(),
+        Key::F(n) => format!("F{n}"),
+        Key::Keypad(c) => format!("Kp{c}"),
     }
 }
 
+/// Whether a key event may be a global hotkey. On macOS, Cmd (Meta) is the
+/// app-level modifier; on Linux (no Meta key), Ctrl+Shift serves as the hotkey
+/// modifier. Function keys never produce text, so they may be bound bare (F11).
+pub fn is_hotkey_candidate(key: &Key, modifiers: &Modifiers) -> bool {
+    modifiers.meta || (modifiers.ctrl && modifiers.shift) || matches!(key, Key::F(_))
+}
+
+/// Plain Ctrl chords a shell relies on: interrupt, EOF, suspend, quit and
+/// readline editing. Modifier normalization leaves them alone, so Ctrl as Cmd
+/// doesn't turn Ctrl+C into Copy or Ctrl+W into ClosePane.
+pub fn is_terminal_control_key(key: &Key, modifiers: &Modifiers) -> bool {
+    modifiers.only(Modifiers::none().with_ctrl())
+        && matches!(key, Key::Char(c) if "cdzwuraekl\\".contains(c.to_ascii_lowercase()))
+}
+
 /// A user-customizable keybinding map. Overrides the hardcoded hotkey table.
 pub struct KeybindingMap {
     pub bindings: Vec<(Hotkey, GlobalAction)>,
@@ -428,6 +547,8 @@ impl KeybindingMap {
             (Hotkey::new(Key::Char(']'), false, false, true, false), GlobalAction::BrowserForward),
             (Hotkey::new(Key::Char('u'), false, false, true, false), GlobalAction::ScrollHalfPageUp),
             (Hotkey::new(Key::Char('d'), false, false, true, false), GlobalAction::ScrollHalfPageDown),
+            (Hotkey::new(Key::Char('k'), true, false, true, false), GlobalAction::ClearScrollback),
+            (Hotkey::new(Key::Char('r'), true, false, true, false), GlobalAction::ResetTerminal),
         ]
     }
 
@@ -473,6 +594,75 @@ impl Default for KeybindingMap {
     }
 }
 
+// ──────────────────────────────────────────────
+// Scroll normalization
+// ──────────────────────────────────────────────
+
+/// Lines scrolled per mouse-wheel notch.
+pub const LINES_PER_NOTCH: f32 = 3.0;
+
+/// Wheel events closer together than this count toward acceleration.
+const SCROLL_ACCEL_WINDOW: Duration = Duration::from_millis(120);
+/// Wheel speed (lines/sec) below which no acceleration is applied.
+const SCROLL_ACCEL_BASE_VELOCITY: f32 = 60.0;
+/// Upper bound on the acceleration multiplier.
+const SCROLL_ACCEL_MAX: f32 = 4.0;
+
+/// Unit of a raw scroll delta as reported by the platform.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScrollDeltaKind {
+    /// Discrete mouse-wheel notches.
+    Lines,
+    /// Precise (trackpad) deltas in logical pixels.
+    Pixels,
+}
+
+/// Convert a raw scroll delta to lines. A wheel notch scrolls
+/// `LINES_PER_NOTCH` lines; a pixel delta scrolls the lines it spans at
+/// `cell_height`, so content tracks the fingers on a trackpad.
+pub fn normalize_scroll(kind: ScrollDeltaKind, raw: f32, cell_height: f32) -> f32 {
+    match kind {
+        ScrollDeltaKind::Lines => raw * LINES_PER_NOTCH,
+        ScrollDeltaKind::Pixels => raw / cell_height.max(1.0),
+    }
+}
+
+/// Velocity-based acceleration for mouse-wheel scrolling. Spinning the wheel
+/// quickly scrolls further per notch (up to `SCROLL_ACCEL_MAX`×). Pixel deltas
+/// pass through unchanged: trackpads already get the OS's acceleration curve.
+#[derive(Debug, Default)]
+pub struct ScrollAcceleration {
+    last: Option<(Instant, f32)>,
+}
+
+impl ScrollAcceleration {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Scale `lines` (already normalized) by the current wheel velocity.
+    pub fn apply(&mut self, kind: ScrollDeltaKind, lines: f32, now: Instant) -> f32 {
+        if kind != ScrollDeltaKind::Lines || lines == 0.0 {
+            return lines;
+        }
+        let factor = match self.last {
+            // Same direction, in quick succession: scale by speed
+            Some((at, prev)) if prev.signum() == lines.signum() => {
+                let dt = now.saturating_duration_since(at);
+                if dt < SCROLL_ACCEL_WINDOW {
+                    let velocity = lines.abs() / dt.as_secs_f32().max(0.001);
+                    (velocity / SCROLL_ACCEL_BASE_VELOCITY).clamp(1.0, SCROLL_ACCEL_MAX)
+                } else {
+                    1.0
+                }
+            }
+            _ => 1.0,
+        };
+        self.last = Some((now, lines));
+        lines * factor
+    }
+}
+
 // ──────────────────────────────────────────────
 // Router
 // ──────────────────────────────────────────────
@@ -480,15 +670,37 @@ impl Default for KeybindingMap {
 /// Default border detection threshold in logical pixels.
 const DEFAULT_BORDER_THRESHOLD: f32 = 4.0;
 
+/// Accumulated pinch magnification that triggers one font size step.
+pub const PINCH_FONT_STEP: f32 = 0.15;
+
+/// Which plain-Ctrl clipboard keys the router claims instead of passing them
+/// to the pane. Both are off by default, so Ctrl+C / Ctrl+V reach the shell.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ClipboardKeyPolicy {
+    /// Ctrl+C copies while a selection exists; without one it still interrupts
+    pub ctrl_c_copies_selection: bool,
+    /// Ctrl+V pastes instead of sending the literal control character
+    pub ctrl_v_pastes: bool,
+}
+
 /// The input router determines what happens with each input event:
 /// which pane it goes to, whether it triggers a global action, or
 /// whether it initiates a border drag.
 pub struct Router {
     focused: Option<PaneId>,
     hovered: Option<PaneId>,
+    /// The last mouse move entered a different pane (or left all panes)
+    hovered_changed: bool,
     dragging_border: bool,
     border_threshold: f32,
+    /// Pinch magnification not yet turned into a font size step
+    pinch_accum: f32,
     pub keybinding_map: Option<KeybindingMap>,
+    clipboard_keys: ClipboardKeyPolicy,
+    /// The focused pane has a non-empty selection (see `set_has_selection`)
+    has_selection: bool,
+    /// Applied to key modifiers before hotkeys are matched
+    modifier_normalization: ModifierNormalization,
 }
 
 impl Router {
@@ -497,9 +709,14 @@ impl Router {
         Self {
             focused: None,
             hovered: None,
+            hovered_changed: false,
             dragging_border: false,
             border_threshold: DEFAULT_BORDER_THRESHOLD,
+            pinch_accum: 0.0,
             keybinding_map: None,
+            clipboard_keys: ClipboardKeyPolicy::default(),
+            has_selection: false,
+            modifier_normalization: ModifierNormalization::default(),
         }
     }
 
@@ -508,9 +725,14 @@ impl Router {
         Self {
             focused: None,
             hovered: None,
+            hovered_changed: false,
             dragging_border: false,
             border_threshold: threshold,
+            pinch_accum: 0.0,
             keybinding_map: None,
+            clipboard_keys: ClipboardKeyPolicy::default(),
+            has_selection: false,
+            modifier_normalization: ModifierNormalization::default(),
         }
     }
 
@@ -524,11 +746,39 @@ impl Router {
         self.focused = Some(pane);
     }
 
+    /// Set which plain-Ctrl clipboard keys are claimed by the router.
+    pub fn set_clipboard_key_policy(&mut self, policy: ClipboardKeyPolicy) {
+        self.clipboard_keys = policy;
+    }
+
+    /// Merge modifiers (e.g. Ctrl as Cmd) before matching hotkeys. Keys
+    /// routed to a pane keep their original modifiers, and terminal control
+    /// keys (`is_terminal_control_key`) are never merged.
+    pub fn set_modifier_normalization(&mut self, normalization: ModifierNormalization) {
+        self.modifier_normalization = normalization;
+    }
+
+    /// Tell the router whether the focused pane currently has a selection.
+    /// Consulted by `ClipboardKeyPolicy::ctrl_c_copies_selection`.
+    pub fn set_has_selection(&mut self, has_selection: bool) {
+        self.has_selection = has_selection;
+    }
+
     /// Get the currently hovered pane, if any.
     pub fn hovered(&self) -> Option<PaneId> {
         self.hovered
     }
 
+    /// Whether the last mouse move changed the hovered pane.
+    pub fn hovered_changed(&self) -> bool {
+        self.hovered_changed
+    }
+
+    fn set_hovered(&mut self, pane: Option<PaneId>) {
+        self.hovered_changed = pane != self.hovered;
+        self.hovered = pane;
+    }
+
     /// Returns true if a border drag is currently in progress.
     pub fn is_dragging_border(&self) -> bool {
         self.dragging_border
@@ -544,8 +794,8 @@ impl Router {
         match event {
             InputEvent::KeyPress { key, modifiers } => self.process_key(key, modifiers),
             InputEvent::MouseClick {
-                position, button, ..
-            } => self.process_click(position, button, pane_rects),
+                position, button, modifiers,
+            } => self.process_click(position, button, modifiers, pane_rects),
             InputEvent::MouseMove { position } => self.process_mouse_move(position, pane_rects),
             InputEvent::MouseDrag {
                 position, button, ..
@@ -557,6 +807,9 @@ impl Router {
                     None => Action::None,
                 }
             }
+            InputEvent::Pinch { scale_delta, ended, .. } => self.process_pinch(scale_delta, ended),
+            // Swipes have no default binding; the app decides what they do.
+            InputEvent::Swipe { .. } => Action::None,
             InputEvent::Resize { .. } => {
                 // Resize events are handled globally by the app, not routed to panes.
                 Action::None
@@ -564,19 +817,50 @@ impl Router {
         }
     }
 
+    /// Accumulate pinch magnification; each `PINCH_FONT_STEP` crossed yields
+    /// one FontSizeUp/Down. Reversing direction drops the opposite remainder,
+    /// and the remainder is dropped when the gesture ends so the next pinch
+    /// starts from zero.
+    fn process_pinch(&mut self, scale_delta: f32, ended: bool) -> Action {
+        if scale_delta.signum() != self.pinch_accum.signum() {
+            self.pinch_accum = 0.0;
+        }
+        self.pinch_accum += scale_delta;
+        let action = if self.pinch_accum >= PINCH_FONT_STEP {
+            self.pinch_accum -= PINCH_FONT_STEP;
+            Action::GlobalAction(GlobalAction::FontSizeUp)
+        } else if self.pinch_accum <= -PINCH_FONT_STEP {
+            self.pinch_accum += PINCH_FONT_STEP;
+            Action::GlobalAction(GlobalAction::FontSizeDown)
+        } else {
+            Action::None
+        };
+        if ended {
+            self.pinch_accum = 0.0;
+        }
+        action
+    }
+
     // ── Key processing ──────────────────────────
 
     fn process_key(&self, key: Key, modifiers: Modifiers) -> Action {
-        // Check global hotkeys.  On macOS, Cmd (Meta) is the app-level
-        // modifier; plain Ctrl must pass through to the terminal (Ctrl+C,
-        // Ctrl+W, etc.).  On Linux (no Meta key), Ctrl+Shift serves as
-        // the hotkey modifier (e.g. Ctrl+Shift+C for copy).
-        if modifiers.meta || (modifiers.ctrl && modifiers.shift) {
+        let modifiers = if is_terminal_control_key(&key, &modifiers) {
+            modifiers
+        } else {
+            modifiers.normalized(self.modifier_normalization)
+        };
+        // Check global hotkeys.  Plain Ctrl must pass through to the
+        // terminal (Ctrl+C, Ctrl+W, etc.).
+        if is_hotkey_candidate(&key, &modifiers) {
             if let Some(action) = self.match_hotkey(key, modifiers) {
                 return Action::GlobalAction(action);
             }
         }
 
+        if let Some(action) = self.match_clipboard_key(&key, &modifiers) {
+            return Action::GlobalAction(action);
+        }
+
         // Not a hotkey -- route to the focused pane.
         match self.focused {
             Some(id) => Action::RouteToPane(id),
@@ -584,6 +868,24 @@ impl Router {
         }
     }
 
+    /// Plain Ctrl+C / Ctrl+V claimed under the clipboard key policy.
+    fn match_clipboard_key(&self, key: &Key, modifiers: &Modifiers) -> Option<GlobalAction> {
+        if !modifiers.only(Modifiers::none().with_ctrl()) {
+            return None;
+        }
+        match key {
+            Key::Char('c') | Key::Char('C')
+                if self.clipboard_keys.ctrl_c_copies_selection && self.has_selection =>
+            {
+                Some(GlobalAction::Copy)
+            }
+            Key::Char('v') | Key::Char('V') if self.clipboard_keys.ctrl_v_pastes => {
+                Some(GlobalAction::Paste)
+            }
+            _ => None,
+        }
+    }
+
     /// Match a key + modifiers against the hotkey table.
     /// Returns Some(GlobalAction) if the combination is a known hotkey.
     fn match_hotkey(&self, key: Key, modifiers: Modifiers) -> Option<GlobalAction> {
@@ -673,6 +975,8 @@ impl Router {
             // Cmd+HJKL -> Navigate
             Key::Char('h') | Key::Char('H') => Some(GlobalAction::Navigate(Direction::Left)),
             Key::Char('j') | Key::Char('J') => Some(GlobalAction::Navigate(Direction::Down)),
+            // Cmd+Shift+K -> clear scrollback
+            Key::Char('k') | Key::Char('K') if modifiers.shift => Some(GlobalAction::ClearScrollback),
             Key::Char('k') | Key::Char('K') => Some(GlobalAction::Navigate(Direction::Up)),
             Key::Char('l') | Key::Char('L') => Some(GlobalAction::Navigate(Direction::Right)),
             // Cmd+I -> tab prev
@@ -693,6 +997,8 @@ impl Router {
                     Some(GlobalAction::NewWindow)
                 }
             }
+            // Cmd+Shift+R -> reset terminal
+            Key::Char('r') | Key::Char('R') if modifiers.shift => Some(GlobalAction::ResetTerminal),
             // Cmd+U -> scroll half page up
             Key::Char('u') | Key::Char('U') => Some(GlobalAction::ScrollHalfPageUp),
             // Cmd+= / Cmd++ -> font size up, Cmd+- -> font size down, Cmd+0 -> reset
@@ -718,7 +1024,8 @@ impl Router {
     fn process_click(
         &mut self,
         position: Vec2,
-        _button: MouseButton,
+        button: MouseButton,
+        modifiers: Modifiers,
         pane_rects: &[(PaneId, Rect)],
     ) -> Action {
         // End any ongoing border drag on click.
@@ -734,7 +1041,12 @@ impl Router {
         match self.pane_at(position, pane_rects) {
             Some(id) => {
                 self.focused = Some(id);
-                Action::RouteToPane(id)
+                let link_chord = modifiers.meta || modifiers.ctrl;
+                if button == MouseButton::Left && link_chord {
+                    Action::OpenLinkAt(id, position)
+                } else {
+                    Action::RouteToPane(id)
+                }
             }
             None => Action::None,
         }
@@ -747,7 +1059,7 @@ impl Router {
         position: Vec2,
         pane_rects: &[(PaneId, Rect)],
     ) -> Action {
-        self.hovered = self.pane_at(position, pane_rects);
+        self.set_hovered(self.pane_at(position, pane_rects));
         Action::None
     }
 
@@ -893,12 +1205,13 @@ impl tide_core::InputRouter for Router {
                 }
             }
             InputEvent::MouseMove { position } => {
-                self.hovered = self.pane_at(position, pane_rects);
+                self.set_hovered(self.pane_at(position, pane_rects));
                 // Mouse move is informational; no pane "consumes" it via routing.
                 self.hovered
             }
             InputEvent::MouseDrag { position, .. } => self.pane_at(position, pane_rects),
             InputEvent::MouseScroll { position, .. } => self.pane_at(position, pane_rects),
+            InputEvent::Pinch { .. } | InputEvent::Swipe { .. } => None,
             InputEvent::Resize { .. } => None,
         }
     }
@@ -0,0 +1,114 @@
+// Input recording and replay: capture a timestamped InputEvent stream (e.g. to
+// attach to a bug report) and feed it back into a Router deterministically.
+
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+use tide_core::{InputEvent, PaneId, Rect};
+
+use crate::{Action, Router};
+
+/// One captured event and when it happened, relative to the recording start.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct RecordedEvent {
+    pub at: Duration,
+    pub event: InputEvent,
+}
+
+/// A captured input session. Events are in the order they were recorded.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Recording {
+    pub events: Vec<RecordedEvent>,
+}
+
+/// Captures `InputEvent`s with their offset from when recording started.
+#[derive(Debug)]
+pub struct Recorder {
+    start: Instant,
+    recording: Recording,
+}
+
+impl Recorder {
+    pub fn new() -> Self {
+        Self::started_at(Instant::now())
+    }
+
+    /// A recorder whose timestamps are measured from `start`.
+    pub fn started_at(start: Instant) -> Self {
+        Self { start, recording: Recording::default() }
+    }
+
+    /// Record `event` as happening now.
+    pub fn record(&mut self, event: InputEvent) {
+        self.record_at(event, Instant::now());
+    }
+
+    /// Record `event` as happening at `now`. Times before the start clamp to zero.
+    pub fn record_at(&mut self, event: InputEvent, now: Instant) {
+        let at = now.saturating_duration_since(self.start);
+        self.recording.events.push(RecordedEvent { at, event });
+    }
+
+    /// Stop recording and return what was captured.
+    pub fn finish(self) -> Recording {
+        self.recording
+    }
+}
+
+impl Default for Recorder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Feeds a `Recording` back, either at the original cadence or as fast as possible.
+#[derive(Debug)]
+pub struct Player {
+    recording: Recording,
+    next: usize,
+    /// Playback start for paced replay; `None` replays without waiting.
+    paced_from: Option<Instant>,
+}
+
+impl Player {
+    /// Replay every event immediately, ignoring the recorded timing.
+    pub fn new(recording: Recording) -> Self {
+        Self { recording, next: 0, paced_from: None }
+    }
+
+    /// Replay events at their recorded offsets from `start`.
+    pub fn paced(recording: Recording, start: Instant) -> Self {
+        Self { recording, next: 0, paced_from: Some(start) }
+    }
+
+    /// The next event if it is due at `now`.
+    pub fn poll(&mut self, now: Instant) -> Option<InputEvent> {
+        let recorded = self.recording.events.get(self.next)?;
+        if let Some(start) = self.paced_from {
+            if now < start + recorded.at {
+                return None;
+            }
+        }
+        self.next += 1;
+        Some(recorded.event)
+    }
+
+    /// When the next event becomes due (for the event loop's poll timeout).
+    /// `None` once finished or when not pacing.
+    pub fn next_deadline(&self) -> Option<Instant> {
+        let start = self.paced_from?;
+        self.recording.events.get(self.next).map(|e| start + e.at)
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.next >= self.recording.events.len()
+    }
+
+    /// Feed every remaining event into `router` without waiting and return
+    /// the actions it produced, in order.
+    pub fn replay_into(&mut self, router: &mut Router, pane_rects: &[(PaneId, Rect)]) -> Vec<Action> {
+        let events = &self.recording.events[self.next..];
+        self.next = self.recording.events.len();
+        events.iter().map(|e| router.process(e.event, pane_rects)).collect()
+    }
+}
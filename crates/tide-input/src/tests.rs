@@ -1,21 +1,21 @@
 #[cfg(test)]
 mod tests {
     use crate::{Action, AreaSlot, Direction, GlobalAction, Router};
-    use tide_core::{InputEvent, Key, Modifiers, MouseButton, Rect, Size, Vec2};
+    use tide_core::{InputEvent, Key, Modifiers, MouseButton, PaneId, Rect, Size, Vec2};
 
     /// Helper: creates a set of two side-by-side pane rects.
     fn two_panes_horizontal() -> Vec<(tide_core::PaneId, Rect)> {
         vec![
-            (1, Rect::new(0.0, 0.0, 200.0, 400.0)),
-            (2, Rect::new(200.0, 0.0, 200.0, 400.0)),
+            (PaneId::new(1), Rect::new(0.0, 0.0, 200.0, 400.0)),
+            (PaneId::new(2), Rect::new(200.0, 0.0, 200.0, 400.0)),
         ]
     }
 
     /// Helper: creates a set of two vertically stacked pane rects.
     fn two_panes_vertical() -> Vec<(tide_core::PaneId, Rect)> {
         vec![
-            (1, Rect::new(0.0, 0.0, 400.0, 200.0)),
-            (2, Rect::new(0.0, 200.0, 400.0, 200.0)),
+            (PaneId::new(1), Rect::new(0.0, 0.0, 400.0, 200.0)),
+            (PaneId::new(2), Rect::new(0.0, 200.0, 400.0, 200.0)),
         ]
     }
 
@@ -24,26 +24,15 @@ mod tests {
     }
 
     fn meta() -> Modifiers {
-        Modifiers {
-            meta: true,
-            ..Default::default()
-        }
+        Modifiers::none().with_meta()
     }
 
     fn ctrl_shift() -> Modifiers {
-        Modifiers {
-            ctrl: true,
-            shift: true,
-            ..Default::default()
-        }
+        Modifiers::none().with_ctrl().with_shift()
     }
 
     fn meta_shift() -> Modifiers {
-        Modifiers {
-            meta: true,
-            shift: true,
-            ..Default::default()
-        }
+        Modifiers::none().with_meta().with_shift()
     }
 
     // ── Focus management tests ──────────────────
@@ -56,11 +45,12 @@ mod tests {
         let event = InputEvent::MouseClick {
             position: Vec2::new(100.0, 200.0),
             button: MouseButton::Left,
+            modifiers: Modifiers::default(),
         };
         let action = router.process(event, &panes);
 
-        assert_eq!(action, Action::RouteToPane(1));
-        assert_eq!(router.focused(), Some(1));
+        assert_eq!(action, Action::RouteToPane(PaneId::new(1)));
+        assert_eq!(router.focused(), Some(PaneId::new(1)));
     }
 
     #[test]
@@ -71,34 +61,96 @@ mod tests {
         let event1 = InputEvent::MouseClick {
             position: Vec2::new(100.0, 200.0),
             button: MouseButton::Left,
+            modifiers: Modifiers::default(),
         };
         router.process(event1, &panes);
-        assert_eq!(router.focused(), Some(1));
+        assert_eq!(router.focused(), Some(PaneId::new(1)));
 
         let event2 = InputEvent::MouseClick {
             position: Vec2::new(300.0, 200.0),
             button: MouseButton::Left,
+            modifiers: Modifiers::default(),
         };
         let action = router.process(event2, &panes);
 
-        assert_eq!(action, Action::RouteToPane(2));
-        assert_eq!(router.focused(), Some(2));
+        assert_eq!(action, Action::RouteToPane(PaneId::new(2)));
+        assert_eq!(router.focused(), Some(PaneId::new(2)));
     }
 
     #[test]
     fn click_outside_panes_does_not_change_focus() {
         let mut router = Router::new();
-        router.set_focused(1);
+        router.set_focused(PaneId::new(1));
         let panes = two_panes_horizontal();
 
         let event = InputEvent::MouseClick {
             position: Vec2::new(500.0, 500.0),
             button: MouseButton::Left,
+            modifiers: Modifiers::default(),
         };
         let action = router.process(event, &panes);
 
         assert_eq!(action, Action::None);
-        assert_eq!(router.focused(), Some(1));
+        assert_eq!(router.focused(), Some(PaneId::new(1)));
+    }
+
+    #[test]
+    fn cmd_click_in_pane_opens_link_instead_of_plain_click() {
+        let mut router = Router::new();
+        let panes = two_panes_horizontal();
+        let click = |modifiers| InputEvent::MouseClick {
+            position: Vec2::new(300.0, 200.0),
+            button: MouseButton::Left,
+            modifiers,
+        };
+
+        assert_eq!(router.process(click(no_modifiers()), &panes), Action::RouteToPane(PaneId::new(2)));
+        assert_eq!(
+            router.process(click(meta()), &panes),
+            Action::OpenLinkAt(PaneId::new(2), Vec2::new(300.0, 200.0))
+        );
+        assert_eq!(router.focused(), Some(PaneId::new(2)));
+        let ctrl = Modifiers { ctrl: true, ..Default::default() };
+        assert!(matches!(router.process(click(ctrl), &panes), Action::OpenLinkAt(id, _) if id == PaneId::new(2)));
+        // Shift/Alt clicks stay plain clicks
+        let alt = Modifiers { alt: true, ..Default::default() };
+        assert_eq!(router.process(click(alt), &panes), Action::RouteToPane(PaneId::new(2)));
+    }
+
+    #[test]
+    fn cmd_click_chord_needs_left_button_and_a_pane() {
+        let mut router = Router::new();
+        let panes = two_panes_horizontal();
+
+        let right = InputEvent::MouseClick {
+            position: Vec2::new(100.0, 200.0),
+            button: MouseButton::Right,
+            modifiers: meta(),
+        };
+        assert_eq!(router.process(right, &panes), Action::RouteToPane(PaneId::new(1)));
+
+        let on_border = InputEvent::MouseClick {
+            position: Vec2::new(200.0, 200.0),
+            button: MouseButton::Left,
+            modifiers: meta(),
+        };
+        assert!(matches!(router.process(on_border, &panes), Action::DragBorder(_)));
+    }
+
+    #[test]
+    fn mouse_events_without_modifiers_still_deserialize() {
+        let event: InputEvent = serde_json::from_str(
+            r#"{"MouseClick":{"position":{"x":1.0,"y":2.0},"button":"Left"}}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            event,
+            InputEvent::MouseClick {
+                position: Vec2::new(1.0, 2.0),
+                button: MouseButton::Left,
+                modifiers: no_modifiers(),
+            }
+        );
     }
 
     // ── Keyboard routing tests ──────────────────
@@ -106,7 +158,7 @@ mod tests {
     #[test]
     fn keyboard_event_routes_to_focused_pane() {
         let mut router = Router::new();
-        router.set_focused(2);
+        router.set_focused(PaneId::new(2));
         let panes = two_panes_horizontal();
 
         let event = InputEvent::KeyPress {
@@ -115,7 +167,7 @@ mod tests {
         };
         let action = router.process(event, &panes);
 
-        assert_eq!(action, Action::RouteToPane(2));
+        assert_eq!(action, Action::RouteToPane(PaneId::new(2)));
     }
 
     #[test]
@@ -137,7 +189,7 @@ mod tests {
     #[test]
     fn meta_t_triggers_new_tab() {
         let mut router = Router::new();
-        router.set_focused(1);
+        router.set_focused(PaneId::new(1));
         let panes = two_panes_horizontal();
 
         let event = InputEvent::KeyPress {
@@ -152,7 +204,7 @@ mod tests {
     #[test]
     fn ctrl_shift_t_triggers_split_vertical() {
         let mut router = Router::new();
-        router.set_focused(1);
+        router.set_focused(PaneId::new(1));
         let panes = two_panes_horizontal();
 
         let event = InputEvent::KeyPress {
@@ -167,7 +219,7 @@ mod tests {
     #[test]
     fn meta_shift_t_triggers_split_vertical() {
         let mut router = Router::new();
-        router.set_focused(1);
+        router.set_focused(PaneId::new(1));
         let panes = two_panes_horizontal();
 
         let event = InputEvent::KeyPress {
@@ -182,7 +234,7 @@ mod tests {
     #[test]
     fn meta_w_triggers_close_pane() {
         let mut router = Router::new();
-        router.set_focused(1);
+        router.set_focused(PaneId::new(1));
         let panes = two_panes_horizontal();
 
         let event = InputEvent::KeyPress {
@@ -197,7 +249,7 @@ mod tests {
     #[test]
     fn meta_bracket_triggers_workspace_prev() {
         let mut router = Router::new();
-        router.set_focused(1);
+        router.set_focused(PaneId::new(1));
         let panes = two_panes_horizontal();
 
         let event = InputEvent::KeyPress {
@@ -212,7 +264,7 @@ mod tests {
     #[test]
     fn meta_bracket_triggers_workspace_next() {
         let mut router = Router::new();
-        router.set_focused(1);
+        router.set_focused(PaneId::new(1));
         let panes = two_panes_horizontal();
 
         let event = InputEvent::KeyPress {
@@ -227,7 +279,7 @@ mod tests {
     #[test]
     fn meta_hjkl_triggers_navigate() {
         let mut router = Router::new();
-        router.set_focused(1);
+        router.set_focused(PaneId::new(1));
         let panes = two_panes_horizontal();
 
         let cases = [
@@ -253,7 +305,7 @@ mod tests {
     #[test]
     fn meta_arrow_triggers_navigate_all_directions() {
         let mut router = Router::new();
-        router.set_focused(1);
+        router.set_focused(PaneId::new(1));
         let panes = two_panes_horizontal();
 
         let cases = [
@@ -279,7 +331,7 @@ mod tests {
     #[test]
     fn meta_enter_triggers_toggle_zoom() {
         let mut router = Router::new();
-        router.set_focused(1);
+        router.set_focused(PaneId::new(1));
         let panes = two_panes_horizontal();
 
         let event = InputEvent::KeyPress {
@@ -294,7 +346,7 @@ mod tests {
     #[test]
     fn meta_i_triggers_dock_tab_prev() {
         let mut router = Router::new();
-        router.set_focused(1);
+        router.set_focused(PaneId::new(1));
         let panes = two_panes_horizontal();
 
         let event = InputEvent::KeyPress {
@@ -309,7 +361,7 @@ mod tests {
     #[test]
     fn meta_o_triggers_dock_tab_next() {
         let mut router = Router::new();
-        router.set_focused(1);
+        router.set_focused(PaneId::new(1));
         let panes = two_panes_horizontal();
 
         let event = InputEvent::KeyPress {
@@ -324,7 +376,7 @@ mod tests {
     #[test]
     fn meta_shift_o_triggers_file_finder() {
         let mut router = Router::new();
-        router.set_focused(1);
+        router.set_focused(PaneId::new(1));
         let panes = two_panes_horizontal();
 
         let event = InputEvent::KeyPress {
@@ -339,7 +391,7 @@ mod tests {
     #[test]
     fn meta_shift_n_triggers_new_workspace() {
         let mut router = Router::new();
-        router.set_focused(1);
+        router.set_focused(PaneId::new(1));
         let panes = two_panes_horizontal();
 
         let event = InputEvent::KeyPress {
@@ -354,7 +406,7 @@ mod tests {
     #[test]
     fn hotkey_is_not_routed_to_pane() {
         let mut router = Router::new();
-        router.set_focused(1);
+        router.set_focused(PaneId::new(1));
         let panes = two_panes_horizontal();
 
         let event = InputEvent::KeyPress {
@@ -369,6 +421,112 @@ mod tests {
         }
     }
 
+    // ── Hotkey parsing tests ────────────────────
+
+    #[test]
+    fn hotkey_parse_round_trips_every_default_binding() {
+        // Keys that share a display symbol with another key ("+" covers '+' and '=').
+        let display_aliases = ['=', '|', '_', '!', '@', '#'];
+        for (hotkey, action) in crate::KeybindingMap::default_bindings() {
+            let text = hotkey.display();
+            let parsed = crate::Hotkey::parse(&text)
+                .unwrap_or_else(|| panic!("{action:?}: failed to parse {text:?}"));
+            assert_eq!(parsed.display(), text, "{action:?}");
+            match hotkey.key {
+                Key::Char(c) if display_aliases.contains(&c) => {}
+                _ => assert_eq!(parsed, hotkey, "{action:?}: {text:?}"),
+            }
+        }
+    }
+
+    #[test]
+    fn hotkey_parse_modifiers_are_case_insensitive_with_aliases() {
+        let parsed = crate::Hotkey::parse("meta+SHIFT+opt+ctrl+t").unwrap();
+        assert_eq!(parsed, crate::Hotkey::new(Key::Char('t'), true, true, true, true));
+        assert_eq!(crate::Hotkey::parse("Cmd+\u{2190}").unwrap().key, Key::Left);
+        assert_eq!(crate::Hotkey::parse("Cmd+Esc").unwrap().key, Key::Escape);
+        assert_eq!(crate::Hotkey::parse("Cmd+-").unwrap().key, Key::Char('-'));
+        assert_eq!(crate::Hotkey::parse("+").unwrap().key, Key::Char('+'));
+    }
+
+    #[test]
+    fn hotkey_parse_rejects_malformed_strings() {
+        assert_eq!(crate::Hotkey::parse(""), None);
+        assert_eq!(crate::Hotkey::parse("Cmd+"), None);
+        assert_eq!(crate::Hotkey::parse("Hyper+T"), None);
+        assert_eq!(crate::Hotkey::parse("Cmd++T"), None);
+        assert_eq!(crate::Hotkey::parse("Cmd+Banana"), None);
+    }
+
+    #[test]
+    fn hotkey_parse_function_keys() {
+        let f5 = crate::Hotkey::parse("F5").unwrap();
+        assert_eq!(f5, crate::Hotkey::new(Key::F(5), false, false, false, false));
+        let cmd_f12 = crate::Hotkey::parse("Cmd+F12").unwrap();
+        assert_eq!(cmd_f12, crate::Hotkey::new(Key::F(12), false, false, true, false));
+        assert_eq!(cmd_f12.display(), "Cmd+F12");
+        assert_eq!(cmd_f12.key_name(), "F12");
+        assert_eq!(crate::Hotkey::key_from_name("F24"), Some(Key::F(24)));
+        assert_eq!(crate::Hotkey::key_from_name("F25"), None);
+        assert_eq!(crate::Hotkey::key_from_name("F0"), None);
+        assert_eq!(crate::Hotkey::key_from_name("F"), Some(Key::Char('f')));
+    }
+
+    #[test]
+    fn hotkey_parse_keypad_and_insert() {
+        let kp = crate::Hotkey::new(Key::Keypad('+'), false, false, true, false);
+        assert_eq!(kp.display(), "Cmd+Kp+");
+        assert_eq!(crate::Hotkey::parse("Cmd+Kp+"), Some(kp.clone()));
+        assert_eq!(crate::Hotkey::key_from_name(&kp.key_name()), Some(Key::Keypad('+')));
+        assert_eq!(crate::Hotkey::key_from_name("Keypad7"), Some(Key::Keypad('7')));
+        assert_eq!(crate::Hotkey::key_from_name("KeypadX"), None);
+        assert_eq!(crate::Hotkey::parse("Shift+Ins").unwrap().key, Key::Insert);
+        assert_eq!(crate::Hotkey::key_from_name("Insert"), Some(Key::Insert));
+    }
+
+    #[test]
+    fn hotkey_matches_only_its_exact_modifiers() {
+        let hotkey = crate::Hotkey::parse("Cmd+Shift+T").unwrap();
+        assert_eq!(hotkey.modifiers(), meta_shift());
+        assert!(hotkey.matches(&Key::Char('T'), &meta_shift()));
+        assert!(!hotkey.matches(&Key::Char('t'), &meta()));
+        assert!(!hotkey.matches(&Key::Char('t'), &meta_shift().with_alt()));
+    }
+
+    #[test]
+    fn keybinding_map_matches_function_key_hotkeys() {
+        let map = crate::KeybindingMap::with_overrides(vec![
+            (crate::Hotkey::parse("F11").unwrap(), GlobalAction::ToggleFullscreen),
+            (crate::Hotkey::parse("Cmd+F12").unwrap(), GlobalAction::ToggleTheme),
+        ]);
+        assert_eq!(map.lookup(&Key::F(11), &Modifiers::default()), Some(GlobalAction::ToggleFullscreen));
+        assert_eq!(map.lookup(&Key::F(12), &meta()), Some(GlobalAction::ToggleTheme));
+        assert_eq!(map.lookup(&Key::F(12), &Modifiers::default()), None);
+    }
+
+    #[test]
+    fn router_matches_bare_function_key_hotkey() {
+        let mut router = Router::new();
+        router.set_focused(PaneId::new(1));
+        router.keybinding_map = Some(crate::KeybindingMap::with_overrides(vec![
+            (crate::Hotkey::parse("F11").unwrap(), GlobalAction::ToggleFullscreen),
+        ]));
+        let panes = two_panes_horizontal();
+
+        let action = router.process(
+            InputEvent::KeyPress { key: Key::F(11), modifiers: Modifiers::default() },
+            &panes,
+        );
+        assert_eq!(action, Action::GlobalAction(GlobalAction::ToggleFullscreen));
+
+        // Unbound function keys still reach the focused pane.
+        let action = router.process(
+            InputEvent::KeyPress { key: Key::F(5), modifiers: Modifiers::default() },
+            &panes,
+        );
+        assert_eq!(action, Action::RouteToPane(PaneId::new(1)));
+    }
+
     // ── Mouse hit-testing tests ─────────────────
 
     #[test]
@@ -379,10 +537,11 @@ mod tests {
         let event = InputEvent::MouseClick {
             position: Vec2::new(350.0, 100.0),
             button: MouseButton::Left,
+            modifiers: Modifiers::default(),
         };
         let action = router.process(event, &panes);
 
-        assert_eq!(action, Action::RouteToPane(2));
+        assert_eq!(action, Action::RouteToPane(PaneId::new(2)));
     }
 
     #[test]
@@ -394,13 +553,13 @@ mod tests {
             position: Vec2::new(50.0, 50.0),
         };
         router.process(event1, &panes);
-        assert_eq!(router.hovered(), Some(1));
+        assert_eq!(router.hovered(), Some(PaneId::new(1)));
 
         let event2 = InputEvent::MouseMove {
             position: Vec2::new(300.0, 50.0),
         };
         router.process(event2, &panes);
-        assert_eq!(router.hovered(), Some(2));
+        assert_eq!(router.hovered(), Some(PaneId::new(2)));
 
         let event3 = InputEvent::MouseMove {
             position: Vec2::new(500.0, 50.0),
@@ -417,10 +576,11 @@ mod tests {
         let event = InputEvent::MouseScroll {
             delta: -1.0,
             position: Vec2::new(300.0, 200.0),
+            modifiers: Modifiers::default(),
         };
         let action = router.process(event, &panes);
 
-        assert_eq!(action, Action::RouteToPane(2));
+        assert_eq!(action, Action::RouteToPane(PaneId::new(2)));
     }
 
     // ── Border detection and drag tests ─────────
@@ -432,6 +592,7 @@ mod tests {
         let event = InputEvent::MouseClick {
             position: Vec2::new(200.0, 200.0),
             button: MouseButton::Left,
+            modifiers: Modifiers::default(),
         };
         let action = router.process(event, &panes);
 
@@ -446,6 +607,7 @@ mod tests {
         let event = InputEvent::MouseClick {
             position: Vec2::new(200.0, 200.0),
             button: MouseButton::Left,
+            modifiers: Modifiers::default(),
         };
         let action = router.process(event, &panes);
 
@@ -461,10 +623,11 @@ mod tests {
         let event = InputEvent::MouseClick {
             position: Vec2::new(50.0, 200.0),
             button: MouseButton::Left,
+            modifiers: Modifiers::default(),
         };
         let action = router.process(event, &panes);
 
-        assert_eq!(action, Action::RouteToPane(1));
+        assert_eq!(action, Action::RouteToPane(PaneId::new(1)));
         assert!(!router.is_dragging_border());
     }
 
@@ -476,6 +639,7 @@ mod tests {
         let click = InputEvent::MouseClick {
             position: Vec2::new(200.0, 200.0),
             button: MouseButton::Left,
+            modifiers: Modifiers::default(),
         };
         router.process(click, &panes);
         assert!(router.is_dragging_border());
@@ -483,6 +647,7 @@ mod tests {
         let drag = InputEvent::MouseDrag {
             position: Vec2::new(210.0, 200.0),
             button: MouseButton::Left,
+            modifiers: Modifiers::default(),
         };
         let action = router.process(drag, &panes);
 
@@ -497,10 +662,11 @@ mod tests {
         let drag = InputEvent::MouseDrag {
             position: Vec2::new(50.0, 200.0),
             button: MouseButton::Left,
+            modifiers: Modifiers::default(),
         };
         let action = router.process(drag, &panes);
 
-        assert_eq!(action, Action::RouteToPane(1));
+        assert_eq!(action, Action::RouteToPane(PaneId::new(1)));
         assert!(!router.is_dragging_border());
     }
 
@@ -512,6 +678,7 @@ mod tests {
         let click_border = InputEvent::MouseClick {
             position: Vec2::new(200.0, 200.0),
             button: MouseButton::Left,
+            modifiers: Modifiers::default(),
         };
         router.process(click_border, &panes);
         assert!(router.is_dragging_border());
@@ -519,6 +686,7 @@ mod tests {
         let click_pane = InputEvent::MouseClick {
             position: Vec2::new(50.0, 200.0),
             button: MouseButton::Left,
+            modifiers: Modifiers::default(),
         };
         router.process(click_pane, &panes);
         assert!(!router.is_dragging_border());
@@ -527,18 +695,51 @@ mod tests {
     #[test]
     fn border_only_detected_between_adjacent_panes() {
         let mut router = Router::new();
-        let panes = vec![(1, Rect::new(0.0, 0.0, 200.0, 400.0))];
+        let panes = vec![(PaneId::new(1), Rect::new(0.0, 0.0, 200.0, 400.0))];
 
         let event = InputEvent::MouseClick {
             position: Vec2::new(200.0, 200.0),
             button: MouseButton::Left,
+            modifiers: Modifiers::default(),
         };
         let action = router.process(event, &panes);
 
-        assert_eq!(action, Action::RouteToPane(1));
+        assert_eq!(action, Action::RouteToPane(PaneId::new(1)));
         assert!(!router.is_dragging_border());
     }
 
+    #[test]
+    fn hovered_changed_reports_only_pane_transitions() {
+        let mut router = Router::new();
+        let rects = two_panes_horizontal();
+        let mv = |x| InputEvent::MouseMove { position: Vec2::new(x, 100.0) };
+
+        router.process(mv(50.0), &rects);
+        assert!(router.hovered_changed());
+        router.process(mv(60.0), &rects);
+        assert!(!router.hovered_changed());
+        router.process(mv(250.0), &rects);
+        assert!(router.hovered_changed());
+        assert_eq!(router.hovered(), Some(PaneId::new(2)));
+        router.process(mv(900.0), &rects);
+        assert!(router.hovered_changed());
+        assert_eq!(router.hovered(), None);
+    }
+
+    #[test]
+    fn cmd_shift_k_and_r_clear_and_reset_terminal() {
+        let meta_shift = Modifiers { meta: true, shift: true, ..Default::default() };
+        // Hardcoded table and the default keybinding map agree
+        let mut with_map = Router::new();
+        with_map.keybinding_map = Some(crate::KeybindingMap::new());
+        for mut router in [Router::new(), with_map] {
+            let clear = InputEvent::KeyPress { key: Key::Char('k'), modifiers: meta_shift };
+            assert_eq!(router.process(clear, &[]), Action::GlobalAction(GlobalAction::ClearScrollback));
+            let reset = InputEvent::KeyPress { key: Key::Char('r'), modifiers: meta_shift };
+            assert_eq!(router.process(reset, &[]), Action::GlobalAction(GlobalAction::ResetTerminal));
+        }
+    }
+
     // ── Trait implementation tests ───────────────
 
     #[test]
@@ -552,9 +753,9 @@ mod tests {
             key: Key::Char('x'),
             modifiers: no_modifiers(),
         };
-        let result = router.route(event, &panes, 2);
+        let result = router.route(event, &panes, PaneId::new(2));
 
-        assert_eq!(result, Some(2));
+        assert_eq!(result, Some(PaneId::new(2)));
     }
 
     #[test]
@@ -568,7 +769,7 @@ mod tests {
             key: Key::Char('t'),
             modifiers: meta(),
         };
-        let result = router.route(event, &panes, 1);
+        let result = router.route(event, &panes, PaneId::new(1));
 
         assert_eq!(result, None);
     }
@@ -583,11 +784,12 @@ mod tests {
         let event = InputEvent::MouseClick {
             position: Vec2::new(300.0, 200.0),
             button: MouseButton::Left,
+            modifiers: Modifiers::default(),
         };
-        let result = router.route(event, &panes, 1);
+        let result = router.route(event, &panes, PaneId::new(1));
 
-        assert_eq!(result, Some(2));
-        assert_eq!(router.focused(), Some(2));
+        assert_eq!(result, Some(PaneId::new(2)));
+        assert_eq!(router.focused(), Some(PaneId::new(2)));
     }
 
     #[test]
@@ -600,10 +802,11 @@ mod tests {
         let event = InputEvent::MouseScroll {
             delta: 1.0,
             position: Vec2::new(100.0, 200.0),
+            modifiers: Modifiers::default(),
         };
-        let result = router.route(event, &panes, 2);
+        let result = router.route(event, &panes, PaneId::new(2));
 
-        assert_eq!(result, Some(1));
+        assert_eq!(result, Some(PaneId::new(1)));
     }
 
     #[test]
@@ -616,7 +819,7 @@ mod tests {
         let event = InputEvent::Resize {
             size: Size::new(800.0, 600.0),
         };
-        let result = router.route(event, &panes, 1);
+        let result = router.route(event, &panes, PaneId::new(1));
 
         assert_eq!(result, None);
     }
@@ -631,6 +834,7 @@ mod tests {
         let event = InputEvent::MouseClick {
             position: Vec2::new(100.0, 100.0),
             button: MouseButton::Left,
+            modifiers: Modifiers::default(),
         };
         let action = router.process(event, &panes);
 
@@ -645,6 +849,7 @@ mod tests {
         let event = InputEvent::MouseClick {
             position: Vec2::new(192.0, 200.0),
             button: MouseButton::Left,
+            modifiers: Modifiers::default(),
         };
         let action = router.process(event, &panes);
 
@@ -659,10 +864,11 @@ mod tests {
         let event = InputEvent::MouseClick {
             position: Vec2::new(180.0, 200.0),
             button: MouseButton::Left,
+            modifiers: Modifiers::default(),
         };
         let action = router.process(event, &panes);
 
-        assert_eq!(action, Action::RouteToPane(1));
+        assert_eq!(action, Action::RouteToPane(PaneId::new(1)));
         assert!(!router.is_dragging_border());
     }
 
@@ -671,11 +877,11 @@ mod tests {
         let mut router = Router::new();
         assert_eq!(router.focused(), None);
 
-        router.set_focused(42);
-        assert_eq!(router.focused(), Some(42));
+        router.set_focused(PaneId::new(42));
+        assert_eq!(router.focused(), Some(PaneId::new(42)));
 
-        router.set_focused(7);
-        assert_eq!(router.focused(), Some(7));
+        router.set_focused(PaneId::new(7));
+        assert_eq!(router.focused(), Some(PaneId::new(7)));
     }
 
     #[test]
@@ -685,4 +891,361 @@ mod tests {
         assert_eq!(router.hovered(), None);
         assert!(!router.is_dragging_border());
     }
+
+    // ── Scroll normalization ──
+
+    #[test]
+    fn wheel_notch_and_equivalent_pixel_delta_scroll_the_same_lines() {
+        use crate::{normalize_scroll, ScrollDeltaKind, LINES_PER_NOTCH};
+        let cell_height = 17.0;
+        let notch = normalize_scroll(ScrollDeltaKind::Lines, 1.0, cell_height);
+        let pixels = normalize_scroll(ScrollDeltaKind::Pixels, LINES_PER_NOTCH * cell_height, cell_height);
+        assert_eq!(notch, LINES_PER_NOTCH);
+        assert!((notch - pixels).abs() < 1e-4);
+        // Direction is preserved
+        assert_eq!(normalize_scroll(ScrollDeltaKind::Lines, -1.0, cell_height), -LINES_PER_NOTCH);
+    }
+
+    #[test]
+    fn fast_wheel_scrolling_accelerates_and_slow_does_not() {
+        use crate::{ScrollAcceleration, ScrollDeltaKind};
+        use std::time::{Duration, Instant};
+        let mut accel = ScrollAcceleration::new();
+        let t0 = Instant::now();
+
+        // First notch and slow notches are unscaled
+        assert_eq!(accel.apply(ScrollDeltaKind::Lines, 3.0, t0), 3.0);
+        assert_eq!(accel.apply(ScrollDeltaKind::Lines, 3.0, t0 + Duration::from_millis(500)), 3.0);
+        // Rapid notches scroll further
+        let fast = accel.apply(ScrollDeltaKind::Lines, 3.0, t0 + Duration::from_millis(520));
+        assert!(fast > 3.0);
+        // Reversing direction resets
+        assert_eq!(accel.apply(ScrollDeltaKind::Lines, -3.0, t0 + Duration::from_millis(530)), -3.0);
+        // Trackpad deltas are never scaled
+        let mut accel = ScrollAcceleration::new();
+        accel.apply(ScrollDeltaKind::Pixels, 2.0, t0);
+        assert_eq!(accel.apply(ScrollDeltaKind::Pixels, 2.0, t0 + Duration::from_millis(5)), 2.0);
+    }
+
+    // ── Gestures ──
+
+    #[test]
+    fn pinch_past_threshold_changes_font_size() {
+        use crate::PINCH_FONT_STEP;
+        let mut router = Router::new();
+        let pinch = |scale_delta| InputEvent::Pinch { scale_delta, position: Vec2::new(10.0, 10.0), ended: false };
+
+        // Below the step: nothing yet, but it accumulates
+        assert_eq!(router.process(pinch(PINCH_FONT_STEP * 0.6), &[]), Action::None);
+        assert_eq!(
+            router.process(pinch(PINCH_FONT_STEP * 0.6), &[]),
+            Action::GlobalAction(GlobalAction::FontSizeUp)
+        );
+        // Pinching in shrinks, and the leftover zoom-in doesn't cancel it
+        assert_eq!(
+            router.process(pinch(-PINCH_FONT_STEP * 1.1), &[]),
+            Action::GlobalAction(GlobalAction::FontSizeDown)
+        );
+    }
+
+    #[test]
+    fn pinch_remainder_is_dropped_when_the_gesture_ends() {
+        use crate::PINCH_FONT_STEP;
+        let mut router = Router::new();
+        let pinch = |scale_delta, ended| InputEvent::Pinch { scale_delta, position: Vec2::new(10.0, 10.0), ended };
+
+        assert_eq!(router.process(pinch(PINCH_FONT_STEP * 0.6, false), &[]), Action::None);
+        assert_eq!(router.process(pinch(0.0, true), &[]), Action::None);
+        // A new gesture needs a full step of its own
+        assert_eq!(router.process(pinch(PINCH_FONT_STEP * 0.6, false), &[]), Action::None);
+        // The ending event still counts toward its own gesture
+        assert_eq!(
+            router.process(pinch(PINCH_FONT_STEP * 0.6, true), &[]),
+            Action::GlobalAction(GlobalAction::FontSizeUp)
+        );
+        assert_eq!(router.process(pinch(PINCH_FONT_STEP * 0.6, false), &[]), Action::None);
+    }
+
+    #[test]
+    fn swipe_and_pinch_are_not_routed_to_panes() {
+        use tide_core::InputRouter as _;
+        let mut router = Router::new();
+        let rects = vec![(PaneId::new(1), Rect::new(0.0, 0.0, 100.0, 100.0))];
+        assert_eq!(router.process(InputEvent::Swipe { dx: 1.0, dy: 0.0 }, &rects), Action::None);
+        let pinch = InputEvent::Pinch { scale_delta: 0.01, position: Vec2::new(10.0, 10.0), ended: false };
+        assert_eq!(router.route(pinch, &rects, PaneId::new(1)), None);
+    }
+
+    // ── Recording and replay ──
+
+    fn sample_session() -> Vec<InputEvent> {
+        vec![
+            InputEvent::MouseClick { position: Vec2::new(250.0, 100.0), button: MouseButton::Left, modifiers: no_modifiers() },
+            InputEvent::KeyPress { key: Key::Char('l'), modifiers: no_modifiers() },
+            InputEvent::MouseMove { position: Vec2::new(199.0, 100.0) },
+            InputEvent::MouseDrag { position: Vec2::new(150.0, 100.0), button: MouseButton::Left, modifiers: no_modifiers() },
+            InputEvent::KeyPress { key: Key::Char('t'), modifiers: meta() },
+            InputEvent::MouseScroll { delta: 3.0, position: Vec2::new(50.0, 50.0), modifiers: no_modifiers() },
+        ]
+    }
+
+    #[test]
+    fn replayed_recording_reproduces_router_actions() {
+        use crate::{Player, Recorder, Recording};
+
+        let panes = two_panes_horizontal();
+        let start = std::time::Instant::now();
+        let mut live = Router::new();
+        let mut recorder = Recorder::started_at(start);
+        let mut expected = Vec::new();
+        for (i, event) in sample_session().into_iter().enumerate() {
+            recorder.record_at(event, start + std::time::Duration::from_millis(i as u64 * 40));
+            expected.push(live.process(event, &panes));
+        }
+
+        // Survives a serde round trip (what a bug report would attach)
+        let json = serde_json::to_string(&recorder.finish()).unwrap();
+        let recording: Recording = serde_json::from_str(&json).unwrap();
+        assert_eq!(recording.events[2].at, std::time::Duration::from_millis(80));
+
+        let mut player = Player::new(recording);
+        let replayed = player.replay_into(&mut Router::new(), &panes);
+        assert_eq!(replayed, expected);
+        assert!(player.is_finished());
+    }
+
+    #[test]
+    fn paced_player_releases_events_at_recorded_offsets() {
+        use crate::{Player, Recorder};
+
+        let start = std::time::Instant::now();
+        let mut recorder = Recorder::started_at(start);
+        let events = sample_session();
+        recorder.record_at(events[0], start);
+        recorder.record_at(events[1], start + std::time::Duration::from_millis(100));
+
+        let replay_start = start + std::time::Duration::from_secs(5);
+        let mut player = Player::paced(recorder.finish(), replay_start);
+        assert_eq!(player.poll(replay_start), Some(events[0]));
+        let second_due = replay_start + std::time::Duration::from_millis(100);
+        assert_eq!(player.next_deadline(), Some(second_due));
+        assert_eq!(player.poll(second_due - std::time::Duration::from_millis(1)), None);
+        assert_eq!(player.poll(second_due), Some(events[1]));
+        assert!(player.is_finished());
+        assert_eq!(player.next_deadline(), None);
+    }
+
+    // ── Command registry ──
+
+    #[test]
+    fn command_search_ranks_split_commands_with_hotkeys() {
+        use crate::{CommandRegistry, KeybindingMap};
+
+        let registry = CommandRegistry::new(&KeybindingMap::new());
+        let found: Vec<(&str, Option<String>)> = registry
+            .search("split")
+            .into_iter()
+            .map(|c| (c.label, c.hotkey_display()))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                ("Split Vertical", Some("Cmd+Shift+T".to_string())),
+                ("Split Horizontal", None),
+                ("Split Vertical Here", Some("Cmd+Shift+\\".to_string())),
+                ("Split Horizontal Here", Some("Cmd+\\".to_string())),
+            ],
+        );
+    }
+
+    #[test]
+    fn command_search_prefers_word_starts_and_skips_non_matches() {
+        use crate::{CommandRegistry, KeybindingMap};
+
+        let registry = CommandRegistry::new(&KeybindingMap::new());
+        let labels = |q: &str| registry.search(q).into_iter().map(|c| c.label).collect::<Vec<_>>();
+        assert_eq!(labels("fsu").first(), Some(&"Font Size Up"));
+        assert_eq!(labels("tft").first(), Some(&"Toggle File Tree"));
+        assert!(labels("zzz").is_empty());
+        assert_eq!(labels("").len(), GlobalAction::all_actions().len());
+    }
+
+    #[test]
+    fn command_registry_follows_rebinding() {
+        use crate::{CommandRegistry, Hotkey, KeybindingMap};
+
+        let mut registry = CommandRegistry::new(&KeybindingMap::new());
+        let custom = KeybindingMap::with_overrides(vec![(
+            Hotkey::new(Key::Char('y'), false, true, false, false),
+            GlobalAction::SplitHorizontal,
+        )]);
+        registry.rebind(&custom);
+        let split = registry.search("split horizontal")[0];
+        assert_eq!(split.action, GlobalAction::SplitHorizontal);
+        assert_eq!(split.hotkey_display().as_deref(), Some("Ctrl+Y"));
+    }
+
+    // ── Clipboard key policy tests ──────────────
+
+    fn ctrl_key(c: char) -> InputEvent {
+        InputEvent::KeyPress {
+            key: Key::Char(c),
+            modifiers: Modifiers { ctrl: true, ..Default::default() },
+        }
+    }
+
+    fn router_with_clipboard_keys() -> Router {
+        let mut router = Router::new();
+        router.set_focused(PaneId::new(1));
+        router.set_clipboard_key_policy(crate::ClipboardKeyPolicy {
+            ctrl_c_copies_selection: true,
+            ctrl_v_pastes: true,
+        });
+        router
+    }
+
+    #[test]
+    fn ctrl_c_copies_only_while_a_selection_exists() {
+        let mut router = router_with_clipboard_keys();
+        let panes = two_panes_horizontal();
+
+        router.set_has_selection(true);
+        assert_eq!(router.process(ctrl_key('c'), &panes), Action::GlobalAction(GlobalAction::Copy));
+
+        router.set_has_selection(false);
+        assert_eq!(router.process(ctrl_key('c'), &panes), Action::RouteToPane(PaneId::new(1)));
+    }
+
+    #[test]
+    fn ctrl_v_pastes_under_the_policy() {
+        let mut router = router_with_clipboard_keys();
+        let panes = two_panes_horizontal();
+        assert_eq!(router.process(ctrl_key('v'), &panes), Action::GlobalAction(GlobalAction::Paste));
+    }
+
+    #[test]
+    fn clipboard_keys_pass_through_by_default() {
+        let mut router = Router::new();
+        router.set_focused(PaneId::new(1));
+        router.set_has_selection(true);
+        let panes = two_panes_horizontal();
+        assert_eq!(router.process(ctrl_key('c'), &panes), Action::RouteToPane(PaneId::new(1)));
+        assert_eq!(router.process(ctrl_key('v'), &panes), Action::RouteToPane(PaneId::new(1)));
+    }
+
+    #[test]
+    fn modifier_normalization_lets_ctrl_stand_in_for_cmd() {
+        let mut router = Router::new();
+        router.set_focused(PaneId::new(1));
+        let panes = two_panes_horizontal();
+        let ctrl_t = InputEvent::KeyPress { key: Key::Char('t'), modifiers: Modifiers::none().with_ctrl() };
+        assert_eq!(router.process(ctrl_t, &panes), Action::RouteToPane(PaneId::new(1)));
+
+        router.set_modifier_normalization(tide_core::ModifierNormalization { ctrl_as_meta: true, ..Default::default() });
+        assert_eq!(router.process(ctrl_t, &panes), Action::GlobalAction(GlobalAction::NewTab));
+    }
+
+    #[test]
+    fn modifier_normalization_leaves_terminal_control_keys_to_the_pane() {
+        let mut router = Router::new();
+        router.set_focused(PaneId::new(1));
+        let panes = two_panes_horizontal();
+        router.set_modifier_normalization(tide_core::ModifierNormalization { ctrl_as_meta: true, ..Default::default() });
+        for c in ['c', 'd', 'z', 'w', 'u', 'r', 'a', 'e', 'k', 'l', '\\'] {
+            let key = InputEvent::KeyPress { key: Key::Char(c), modifiers: Modifiers::none().with_ctrl() };
+            assert_eq!(router.process(key, &panes), Action::RouteToPane(PaneId::new(1)), "Ctrl+{c}");
+        }
+        // Other modifiers with Ctrl are still folded
+        let ctrl_shift_w = InputEvent::KeyPress { key: Key::Char('w'), modifiers: Modifiers::none().with_ctrl().with_shift() };
+        assert_eq!(router.process(ctrl_shift_w, &panes), Action::GlobalAction(GlobalAction::CloseWorkspace));
+
+        // The clipboard key policy still sees plain Ctrl+C
+        router.set_clipboard_key_policy(crate::ClipboardKeyPolicy { ctrl_c_copies_selection: true, ctrl_v_pastes: false });
+        router.set_has_selection(true);
+        let ctrl_c = InputEvent::KeyPress { key: Key::Char('c'), modifiers: Modifiers::none().with_ctrl() };
+        assert_eq!(router.process(ctrl_c, &panes), Action::GlobalAction(GlobalAction::Copy));
+    }
+
+    #[test]
+    fn key_repeat_fires_after_the_delay_at_the_interval_until_key_up() {
+        use crate::KeyRepeat;
+        use std::time::{Duration, Instant};
+
+        let ms = Duration::from_millis;
+        let cmd = Modifiers::none().with_meta();
+        let start = Instant::now();
+        let mut repeat = KeyRepeat::new(ms(300), ms(40));
+        assert_eq!(repeat.tick(start), None);
+
+        repeat.on_key_down_at(Key::Right, cmd, start);
+        assert_eq!(repeat.next_deadline(), Some(start + ms(300)));
+        assert_eq!(repeat.tick(start + ms(299)), None, "nothing before the initial delay");
+        assert_eq!(repeat.tick(start + ms(300)), Some((Key::Right, cmd)));
+        assert_eq!(repeat.tick(start + ms(300)), None, "one repeat per interval");
+        assert_eq!(repeat.tick(start + ms(339)), None);
+        assert_eq!(repeat.tick(start + ms(340)), Some((Key::Right, cmd)));
+        // A slightly late tick keeps the cadence
+        assert_eq!(repeat.tick(start + ms(385)), Some((Key::Right, cmd)));
+        assert_eq!(repeat.next_deadline(), Some(start + ms(420)));
+
+        // An OS repeat of the held chord doesn't restart the delay
+        repeat.on_key_down_at(Key::Right, cmd, start + ms(400));
+        assert_eq!(repeat.next_deadline(), Some(start + ms(420)));
+
+        // Releasing another key leaves it running; releasing it stops it
+        repeat.on_key_up(Key::Left);
+        assert_eq!(repeat.tick(start + ms(420)), Some((Key::Right, cmd)));
+        repeat.on_key_up(Key::Right);
+        assert_eq!(repeat.tick(start + ms(1000)), None);
+        assert_eq!(repeat.next_deadline(), None);
+    }
+
+    #[test]
+    fn key_repeat_follows_the_last_key_and_drops_repeats_missed_in_a_stall() {
+        use crate::KeyRepeat;
+        use std::time::{Duration, Instant};
+
+        let ms = Duration::from_millis;
+        let cmd = Modifiers::none().with_meta();
+        let start = Instant::now();
+        let mut repeat = KeyRepeat::new(ms(300), ms(40));
+        repeat.on_key_down_at(Key::Right, cmd, start);
+        repeat.on_key_down_at(Key::Down, cmd, start + ms(100));
+        assert_eq!(repeat.held_key(), Some((Key::Down, cmd)));
+        assert_eq!(repeat.tick(start + ms(300)), None, "the new key starts its own delay");
+        assert_eq!(repeat.tick(start + ms(400)), Some((Key::Down, cmd)));
+
+        // Woken a second late: one repeat, then back on the interval
+        assert_eq!(repeat.tick(start + ms(1400)), Some((Key::Down, cmd)));
+        assert_eq!(repeat.tick(start + ms(1400)), None);
+        assert_eq!(repeat.next_deadline(), Some(start + ms(1440)));
+
+        repeat.cancel();
+        assert_eq!(repeat.tick(start + ms(2000)), None);
+    }
+
+    #[test]
+    fn key_repeat_tracks_modifiers_with_the_key() {
+        use crate::KeyRepeat;
+        use std::time::{Duration, Instant};
+
+        let ms = Duration::from_millis;
+        let cmd = Modifiers::none().with_meta();
+        let cmd_shift = Modifiers { shift: true, ..cmd };
+        let start = Instant::now();
+        let mut repeat = KeyRepeat::new(ms(300), ms(40));
+
+        // Same key with another modifier is a new chord with its own delay
+        repeat.on_key_down_at(Key::Right, cmd, start);
+        repeat.on_key_down_at(Key::Right, cmd_shift, start + ms(200));
+        assert_eq!(repeat.held_key(), Some((Key::Right, cmd_shift)));
+        assert_eq!(repeat.next_deadline(), Some(start + ms(500)));
+
+        // Still holding every modifier keeps it; letting go of one stops it
+        repeat.on_modifiers_changed(cmd_shift);
+        assert_eq!(repeat.tick(start + ms(500)), Some((Key::Right, cmd_shift)));
+        repeat.on_modifiers_changed(cmd);
+        assert_eq!(repeat.held_key(), None);
+        assert_eq!(repeat.tick(start + ms(1000)), None);
+    }
 }
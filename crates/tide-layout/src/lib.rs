@@ -7,10 +7,12 @@ mod tests;
 
 pub use tab_group::TabGroup;
 
-use tide_core::{DropZone, LayoutEngine, PaneDecorations, PaneId, Rect, Size, SplitDirection, Vec2};
+use tide_core::{DropTarget, DropZone, LayoutEngine, PaneDecorations, PaneId, Rect, Size, SplitDirection, Vec2};
 
 use node::Node;
 
+use std::time::Duration;
+
 // ──────────────────────────────────────────────
 // SplitLayout
 // ──────────────────────────────────────────────
@@ -21,13 +23,28 @@ const MIN_RATIO: f32 = 0.1;
 /// Border hit-test threshold in pixels.
 const BORDER_HIT_THRESHOLD: f32 = 8.0;
 
+/// Fraction of a pane's width/height (centered) that counts as the Center drop zone.
+const DROP_CENTER_FRACTION: f32 = 0.4;
+
 pub struct SplitLayout {
     pub(crate) root: Option<Node>,
-    next_id: PaneId,
+    next_id: u64,
     /// The currently active drag: path to the split node being dragged.
     pub(crate) active_drag: Option<Vec<bool>>,
     /// The last window size used for drag computation (needed to reconstruct rects during drag).
     pub last_window_size: Option<Size>,
+    /// Duration of ratio animations. `None` means ratio changes apply instantly.
+    animation_duration: Option<Duration>,
+    /// In-flight ratio animations, keyed by split path.
+    ratio_tweens: Vec<RatioTween>,
+}
+
+/// A single split ratio animating from `from` to `to`.
+struct RatioTween {
+    path: Vec<bool>,
+    from: f32,
+    to: f32,
+    elapsed: Duration,
 }
 
 impl SplitLayout {
@@ -37,23 +54,27 @@ impl SplitLayout {
             next_id: 1,
             active_drag: None,
             last_window_size: None,
+            animation_duration: None,
+            ratio_tweens: Vec::new(),
         }
     }
 
     /// Create a layout with a single initial pane and return both the layout and the PaneId.
     pub fn with_initial_pane() -> (Self, PaneId) {
-        let id: PaneId = 1;
+        let id = PaneId::new(1);
         let layout = Self {
             root: Some(Node::Leaf(TabGroup::single(id))),
             next_id: 2,
             active_drag: None,
             last_window_size: None,
+            animation_duration: None,
+            ratio_tweens: Vec::new(),
         };
         (layout, id)
     }
 
     pub fn alloc_id(&mut self) -> PaneId {
-        let id = self.next_id;
+        let id = PaneId::new(self.next_id);
         self.next_id += 1;
         id
     }
@@ -80,6 +101,85 @@ impl SplitLayout {
         self.active_drag = None;
     }
 
+    /// Set the duration of ratio animations. `None` (the default) applies
+    /// ratio changes instantly.
+    pub fn set_animation_duration(&mut self, duration: Option<Duration>) {
+        self.animation_duration = duration.filter(|d| !d.is_zero());
+        if self.animation_duration.is_none() {
+            self.finish_animations();
+        }
+    }
+
+    /// Change the ratio of the split at `path` (false = left, true = right from
+    /// the root). With an animation duration set, the current ratio is tweened
+    /// toward the target by `tick`; otherwise it is applied immediately.
+    /// Returns false if `path` does not address a split.
+    pub fn set_target_ratio(&mut self, path: &[bool], ratio: f32) -> bool {
+        if !ratio.is_finite() {
+            return false;
+        }
+        let target = ratio.clamp(MIN_RATIO, 1.0 - MIN_RATIO);
+        let Some(current) = self.root.as_mut().and_then(|r| r.ratio_at_mut(path)) else {
+            return false;
+        };
+        self.ratio_tweens.retain(|t| t.path != path);
+        if self.animation_duration.is_none() {
+            *current = target;
+        } else if (*current - target).abs() > f32::EPSILON {
+            self.ratio_tweens.push(RatioTween {
+                path: path.to_vec(),
+                from: *current,
+                to: target,
+                elapsed: Duration::ZERO,
+            });
+        }
+        true
+    }
+
+    /// Advance ratio animations by `dt`. Returns true while any animation is
+    /// still in progress (the caller should keep requesting frames).
+    pub fn tick(&mut self, dt: Duration) -> bool {
+        let Some(duration) = self.animation_duration else {
+            return false;
+        };
+        let Some(ref mut root) = self.root else {
+            self.ratio_tweens.clear();
+            return false;
+        };
+        self.ratio_tweens.retain_mut(|tween| {
+            tween.elapsed += dt;
+            let t = (tween.elapsed.as_secs_f32() / duration.as_secs_f32()).min(1.0);
+            // Ease-out cubic: fast start, gentle settle.
+            let eased = 1.0 - (1.0 - t).powi(3);
+            match root.ratio_at_mut(&tween.path) {
+                Some(ratio) => {
+                    *ratio = tween.from + (tween.to - tween.from) * eased;
+                    t < 1.0
+                }
+                // The split disappeared (pane closed / tree restructured).
+                None => false,
+            }
+        });
+        !self.ratio_tweens.is_empty()
+    }
+
+    /// Whether any ratio animation is still in progress.
+    pub fn is_animating(&self) -> bool {
+        !self.ratio_tweens.is_empty()
+    }
+
+    /// Jump every in-flight animation to its target ratio.
+    pub fn finish_animations(&mut self) {
+        if let Some(ref mut root) = self.root {
+            for tween in &self.ratio_tweens {
+                if let Some(ratio) = root.ratio_at_mut(&tween.path) {
+                    *ratio = tween.to;
+                }
+            }
+        }
+        self.ratio_tweens.clear();
+    }
+
     /// Get all pane IDs in the layout (all tabs from all groups).
     pub fn pane_ids(&self) -> Vec<PaneId> {
         let mut ids = Vec::new();
@@ -104,6 +204,14 @@ impl SplitLayout {
         }
     }
 
+    /// Replace any non-finite split ratio with 0.5, so a ratio poisoned by a
+    /// degenerate window or a bad session file can't make the layout vanish.
+    pub fn sanitize(&mut self) {
+        if let Some(ref mut root) = self.root {
+            root.sanitize_ratios();
+        }
+    }
+
     /// Snap all split ratios so that pane content areas align to cell boundaries.
     /// Call this after `compute()` but before using the resulting rects for rendering.
     /// The caller should call `compute()` again after snapping.
@@ -119,6 +227,19 @@ impl SplitLayout {
         }
     }
 
+    /// Detach a pane from the tree so it can be moved into another window
+    /// (tab tear-off). The tree collapses exactly as with `remove`.
+    /// Refuses to extract the last pane, which would leave the layout empty.
+    /// Returns false if the pane isn't in the layout or is the last one.
+    pub fn extract_pane(&mut self, pane: PaneId) -> bool {
+        let ids = self.pane_ids();
+        if ids.len() <= 1 || !ids.contains(&pane) {
+            return false;
+        }
+        self.remove(pane);
+        true
+    }
+
     /// Insert a new pane next to an existing target pane in the split tree.
     /// Used when moving panes from the editor panel into the tree.
     pub fn insert_pane(
@@ -390,6 +511,8 @@ impl SplitLayout {
             next_id: self.next_id,
             active_drag: None,
             last_window_size: None,
+            animation_duration: None,
+            ratio_tweens: Vec::new(),
         };
 
         match target {
@@ -425,6 +548,40 @@ impl SplitLayout {
         rects.into_iter().find(|(id, _)| *id == source).map(|(_, r)| r)
     }
 
+    /// Classify a cursor position into a drop target on the pane under it.
+    /// The central `DROP_CENTER_FRACTION` box is `Center`; elsewhere the
+    /// nearest pane edge wins. Returns None when no pane is under the cursor.
+    pub fn drop_target_at(&self, position: Vec2, window_size: Size) -> Option<DropTarget> {
+        let (id, rect) = self
+            .compute(window_size, &[], None)
+            .into_iter()
+            .find(|(_, r)| r.contains(position))?;
+        if rect.width <= 0.0 || rect.height <= 0.0 {
+            return None;
+        }
+
+        let rel_x = (position.x - rect.x) / rect.width;
+        let rel_y = (position.y - rect.y) / rect.height;
+        let lo = (1.0 - DROP_CENTER_FRACTION) / 2.0;
+        let hi = 1.0 - lo;
+        if (lo..=hi).contains(&rel_x) && (lo..=hi).contains(&rel_y) {
+            return Some(DropTarget::Pane(id, DropZone::Center));
+        }
+
+        let edges = [
+            (rel_y, DropZone::Top),
+            (1.0 - rel_y, DropZone::Bottom),
+            (rel_x, DropZone::Left),
+            (1.0 - rel_x, DropZone::Right),
+        ];
+        let zone = edges
+            .iter()
+            .min_by(|a, b| a.0.total_cmp(&b.0))
+            .map(|&(_, zone)| zone)
+            .unwrap_or(DropZone::Center);
+        Some(DropTarget::Pane(id, zone))
+    }
+
     // ──────────────────────────────────────────────
     // TabGroup operations
     // ──────────────────────────────────────────────
@@ -463,6 +620,22 @@ impl SplitLayout {
     pub fn right_neighbor_pane(&self, pane: PaneId) -> Option<PaneId> {
         self.root.as_ref().and_then(|r| r.find_right_neighbor(pane))
     }
+
+    /// Path from the root split to the leaf holding `pane` (false = left/top,
+    /// true = right/bottom). Empty when the pane is the only leaf; `None` if
+    /// the pane isn't in the layout.
+    pub fn path_to(&self, pane: PaneId) -> Option<Vec<bool>> {
+        let mut path = Vec::new();
+        self.root.as_ref()?.path_to(pane, &mut path).then_some(path)
+    }
+
+    /// Direction, ratio and rect of the split at `path` (as used by
+    /// `set_target_ratio`), laid out in `window_size`. `None` if `path`
+    /// does not address a split.
+    pub fn split_at_path(&self, path: &[bool], window_size: Size) -> Option<(SplitDirection, f32, Rect)> {
+        let window_rect = Rect::new(0.0, 0.0, window_size.width, window_size.height);
+        self.root.as_ref()?.split_at(window_rect, path)
+    }
 }
 
 // ──────────────────────────────────────────────
@@ -507,15 +680,20 @@ impl SplitLayout {
     }
 
     /// Reconstruct a `SplitLayout` from a `LayoutSnapshot`.
-    /// The `next_id` is set to one past the maximum PaneId found.
+    /// The `next_id` is set to one past the maximum PaneId found, and
+    /// non-finite ratios are reset to 0.5.
     pub fn from_snapshot(snap: LayoutSnapshot) -> Self {
         let max_id = Self::max_id_in_snapshot(&snap);
-        Self {
+        let mut layout = Self {
             root: Some(Self::snapshot_to_node(&snap)),
             next_id: max_id + 1,
             active_drag: None,
             last_window_size: None,
-        }
+            animation_duration: None,
+            ratio_tweens: Vec::new(),
+        };
+        layout.sanitize();
+        layout
     }
 
     fn snapshot_to_node(snap: &LayoutSnapshot) -> Node {
@@ -535,10 +713,10 @@ impl SplitLayout {
         }
     }
 
-    fn max_id_in_snapshot(snap: &LayoutSnapshot) -> PaneId {
+    fn max_id_in_snapshot(snap: &LayoutSnapshot) -> u64 {
         match snap {
             LayoutSnapshot::Leaf { tabs, .. } => {
-                tabs.iter().copied().max().unwrap_or(0)
+                tabs.iter().map(|id| id.as_u64()).max().unwrap_or(0)
             }
             LayoutSnapshot::Split { left, right, .. } => {
                 Self::max_id_in_snapshot(left).max(Self::max_id_in_snapshot(right))
@@ -592,10 +770,14 @@ impl LayoutEngine for SplitLayout {
             }
         };
 
+        // A manual drag takes over from any animation on the same border.
+        self.ratio_tweens.retain(|t| t.path != drag_path);
+
         if let (Some(ref mut root), Some(ws)) = (&mut self.root, self.last_window_size) {
             let window_rect = Rect::new(0.0, 0.0, ws.width, ws.height);
             root.apply_drag(window_rect, &drag_path, position, MIN_RATIO);
         }
+        self.sanitize();
     }
 
     fn split(&mut self, pane: PaneId, direction: SplitDirection) -> PaneId {
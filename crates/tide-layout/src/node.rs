@@ -252,16 +252,15 @@ impl Node {
         } = self
         {
             if path.is_empty() {
-                // This is the target split node. Update its ratio.
+                // This is the target split node. Update its ratio, unless the
+                // rect is degenerate (e.g. a zero-size window while minimized).
                 let new_ratio = match direction {
-                    SplitDirection::Horizontal => {
-                        (position.x - rect.x) / rect.width
-                    }
-                    SplitDirection::Vertical => {
-                        (position.y - rect.y) / rect.height
-                    }
+                    SplitDirection::Horizontal => ratio_along(position.x - rect.x, rect.width, min_ratio),
+                    SplitDirection::Vertical => ratio_along(position.y - rect.y, rect.height, min_ratio),
                 };
-                *ratio = new_ratio.clamp(min_ratio, 1.0 - min_ratio);
+                if let Some(new_ratio) = new_ratio {
+                    *ratio = new_ratio;
+                }
             } else {
                 let (left_rect, right_rect) = split_rect(rect, *direction, *ratio);
                 if !path[0] {
@@ -273,29 +272,83 @@ impl Node {
         }
     }
 
-    /// Replace all occurrences of `from` PaneId with `to` in leaf nodes.
-    pub(crate) fn replace_pane_id(&mut self, from: PaneId, to: PaneId) {
+    /// Replace every non-finite split ratio in this subtree with 0.5.
+    pub(crate) fn sanitize_ratios(&mut self) {
+        if let Node::Split { ratio, left, right, .. } = self {
+            if !ratio.is_finite() {
+                *ratio = 0.5;
+            }
+            left.sanitize_ratios();
+            right.sanitize_ratios();
+        }
+    }
+
+    /// Follow the path to a split node and return a mutable reference to its ratio.
+    /// Returns None if the path does not end at a split.
+    pub(crate) fn ratio_at_mut(&mut self, path: &[bool]) -> Option<&mut f32> {
         match self {
-            Node::Leaf(tg) => {
-                for tab in &mut tg.tabs {
-                    if *tab == from {
-                        *tab = to;
+            Node::Split { ratio, left, right, .. } => match path.split_first() {
+                None => Some(ratio),
+                Some((false, rest)) => left.ratio_at_mut(rest),
+                Some((true, rest)) => right.ratio_at_mut(rest),
+            },
+            Node::Leaf(_) => None,
+        }
+    }
+
+    /// Push the path (false = left, true = right) from this node to the leaf
+    /// holding `pane` onto `path`. Returns false (leaving `path` unchanged)
+    /// if the pane isn't in this subtree.
+    pub(crate) fn path_to(&self, pane: PaneId, path: &mut Vec<bool>) -> bool {
+        match self {
+            Node::Leaf(tg) => tg.contains(pane),
+            Node::Split { left, right, .. } => {
+                for (side, child) in [(false, left), (true, right)] {
+                    path.push(side);
+                    if child.path_to(pane, path) {
+                        return true;
                     }
+                    path.pop();
                 }
+                false
             }
-            Node::Split { left, right, .. } => {
-                left.replace_pane_id(from, to);
-                right.replace_pane_id(from, to);
+        }
+    }
+
+    /// Follow the path to a split node and return its direction, ratio and the
+    /// rect it occupies, given the rect this node occupies.
+    pub(crate) fn split_at(&self, rect: Rect, path: &[bool]) -> Option<(SplitDirection, f32, Rect)> {
+        match self {
+            Node::Split { direction, ratio, left, right } => {
+                let (left_rect, right_rect) = split_rect(rect, *direction, *ratio);
+                match path.split_first() {
+                    None => Some((*direction, *ratio, rect)),
+                    Some((false, rest)) => left.split_at(left_rect, rest),
+                    Some((true, rest)) => right.split_at(right_rect, rest),
+                }
             }
+            Node::Leaf(_) => None,
         }
     }
 
-    /// Swap two pane IDs using a sentinel value for 3-way swap.
+    /// Swap two pane IDs in a single pass. No placeholder id is written, so
+    /// any valid id (including `u64::MAX`) can take part in a swap.
     pub(crate) fn swap_panes(&mut self, a: PaneId, b: PaneId) {
-        let sentinel = u64::MAX;
-        self.replace_pane_id(a, sentinel);
-        self.replace_pane_id(b, a);
-        self.replace_pane_id(sentinel, b);
+        match self {
+            Node::Leaf(tg) => {
+                for tab in &mut tg.tabs {
+                    if *tab == a {
+                        *tab = b;
+                    } else if *tab == b {
+                        *tab = a;
+                    }
+                }
+            }
+            Node::Split { left, right, .. } => {
+                left.swap_panes(a, b);
+                right.swap_panes(a, b);
+            }
+        }
     }
 
     /// Recursively snap split ratios so that the left/top child's content area
@@ -587,9 +640,10 @@ fn try_split(
         if clean && !left_group.is_empty() && !right_group.is_empty() {
             // Compute ratio from original bounding box sizes
             let ratio = match direction {
-                SplitDirection::Horizontal => (split_pos - min_x) / (max_x - min_x),
-                SplitDirection::Vertical => (split_pos - min_y) / (max_y - min_y),
-            };
+                SplitDirection::Horizontal => ratio_along(split_pos - min_x, max_x - min_x, 0.0),
+                SplitDirection::Vertical => ratio_along(split_pos - min_y, max_y - min_y, 0.0),
+            }
+            .unwrap_or(0.5);
             return Some((left_group, right_group, ratio));
         }
     }
@@ -682,6 +736,15 @@ pub(crate) fn min_ratio_for_direction(
     }
 }
 
+/// `offset / extent` clamped to `[min_ratio, 1 - min_ratio]`. `None` when the
+/// extent is empty or either value is non-finite, so no NaN reaches the tree.
+pub(crate) fn ratio_along(offset: f32, extent: f32, min_ratio: f32) -> Option<f32> {
+    if extent <= 0.0 || !extent.is_finite() || !offset.is_finite() {
+        return None;
+    }
+    Some((offset / extent).clamp(min_ratio, 1.0 - min_ratio))
+}
+
 /// Split a rect into two sub-rects based on direction and ratio.
 pub(crate) fn split_rect(rect: Rect, direction: SplitDirection, ratio: f32) -> (Rect, Rect) {
     match direction {
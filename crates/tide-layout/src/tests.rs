@@ -1,7 +1,8 @@
 #[cfg(test)]
 mod tests {
-    use crate::SplitLayout;
-    use tide_core::{LayoutEngine, PaneDecorations, Rect, Size, SplitDirection, Vec2};
+    use crate::{LayoutSnapshot, SplitLayout};
+    use std::time::Duration;
+    use tide_core::{DropTarget, DropZone, LayoutEngine, PaneDecorations, PaneId, Rect, Size, SplitDirection, Vec2};
 
     const WINDOW: Size = Size {
         width: 800.0,
@@ -36,7 +37,7 @@ mod tests {
     #[test]
     fn test_with_initial_pane() {
         let (layout, pane_id) = SplitLayout::with_initial_pane();
-        assert_eq!(pane_id, 1);
+        assert_eq!(pane_id, PaneId::new(1));
         let rects = layout.compute(WINDOW, &[pane_id], None);
         assert_eq!(rects.len(), 1);
         assert_eq!(rects[0].0, pane_id);
@@ -210,11 +211,69 @@ mod tests {
     #[test]
     fn test_remove_nonexistent_pane() {
         let (mut layout, _pane1) = SplitLayout::with_initial_pane();
-        layout.remove(999);
+        layout.remove(PaneId::new(999));
         let rects = layout.compute(WINDOW, &[], None);
         assert_eq!(rects.len(), 1);
     }
 
+    // ──────────────────────────────────────────
+    // Extract pane (tear-off)
+    // ──────────────────────────────────────────
+
+    #[test]
+    fn test_extract_pane_collapses_like_remove() {
+        let build = || {
+            let (mut layout, pane1) = SplitLayout::with_initial_pane();
+            let pane2 = layout.split(pane1, SplitDirection::Horizontal);
+            let pane3 = layout.split(pane2, SplitDirection::Vertical);
+            let pane4 = layout.split(pane1, SplitDirection::Vertical);
+            (layout, [pane1, pane2, pane3, pane4])
+        };
+        let (mut extracted, [pane1, pane2, pane3, pane4]) = build();
+        let (mut removed, _) = build();
+
+        assert!(extracted.extract_pane(pane3));
+        removed.remove(pane3);
+
+        let active = [pane1, pane2, pane4];
+        assert_eq!(extracted.pane_ids(), removed.pane_ids());
+        let a = extracted.compute(WINDOW, &active, None);
+        let b = removed.compute(WINDOW, &active, None);
+        assert_eq!(a.len(), b.len());
+        for ((id_a, rect_a), (id_b, rect_b)) in a.iter().zip(b.iter()) {
+            assert_eq!(id_a, id_b);
+            assert!(rect_approx_eq(rect_a, rect_b));
+        }
+    }
+
+    #[test]
+    fn test_extract_tab_keeps_group_leaf() {
+        let (mut layout, pane1) = SplitLayout::with_initial_pane();
+        let pane2 = layout.alloc_id();
+        layout.add_tab(pane1, pane2);
+
+        assert!(layout.extract_pane(pane2));
+        assert_eq!(layout.pane_ids(), vec![pane1]);
+    }
+
+    #[test]
+    fn test_extract_refuses_last_pane() {
+        let (mut layout, pane1) = SplitLayout::with_initial_pane();
+        assert!(!layout.extract_pane(pane1));
+        assert_eq!(layout.pane_ids(), vec![pane1]);
+    }
+
+    #[test]
+    fn test_extract_missing_pane_returns_false() {
+        let (mut layout, pane1) = SplitLayout::with_initial_pane();
+        let pane2 = layout.split(pane1, SplitDirection::Horizontal);
+
+        assert!(!layout.extract_pane(PaneId::new(999)));
+        assert!(layout.extract_pane(pane2));
+        assert!(!layout.extract_pane(pane2));
+        assert_eq!(layout.pane_ids(), vec![pane1]);
+    }
+
     // ──────────────────────────────────────────
     // No gaps, no overlaps (rects tile the window)
     // ──────────────────────────────────────────
@@ -456,8 +515,8 @@ mod tests {
     #[test]
     fn test_split_nonexistent_pane() {
         let (mut layout, _pane1) = SplitLayout::with_initial_pane();
-        let new_id = layout.split(999, SplitDirection::Horizontal);
-        assert!(new_id > 0);
+        let new_id = layout.split(PaneId::new(999), SplitDirection::Horizontal);
+        assert!(new_id > PaneId::new(0));
         let rects = layout.compute(WINDOW, &[], None);
         assert_eq!(rects.len(), 1);
     }
@@ -521,7 +580,7 @@ mod tests {
         if let Some(ref root) = layout.root {
             assert!(root.contains(pane1));
             assert!(root.contains(pane2));
-            assert!(!root.contains(999));
+            assert!(!root.contains(PaneId::new(999)));
         }
     }
 
@@ -837,22 +896,21 @@ mod tests {
             left: Box::new(Node::Split {
                 direction: SplitDirection::Vertical,
                 ratio: 0.5,
-                left: Box::new(Node::Leaf(TabGroup::single(2))),
-                right: Box::new(Node::Leaf(TabGroup::single(3))),
+                left: Box::new(Node::Leaf(TabGroup::single(PaneId::new(2)))),
+                right: Box::new(Node::Leaf(TabGroup::single(PaneId::new(3)))),
             }),
             right: Box::new(Node::Split {
                 direction: SplitDirection::Vertical,
                 ratio: 0.5,
-                left: Box::new(Node::Leaf(TabGroup::single(1))),
-                right: Box::new(Node::Leaf(TabGroup::single(4))),
+                left: Box::new(Node::Leaf(TabGroup::single(PaneId::new(1)))),
+                right: Box::new(Node::Leaf(TabGroup::single(PaneId::new(4)))),
             }),
         };
 
         SplitLayout {
             root: Some(root),
             next_id: 5,
-            active_drag: None,
-            last_window_size: None,
+            ..SplitLayout::new()
         }
     }
 
@@ -864,25 +922,25 @@ mod tests {
     #[test]
     fn test_restructure_move_to_root_left() {
         let mut layout = make_quadrant_layout();
-        assert!(layout.restructure_move_to_root(4, tide_core::DropZone::Left, WINDOW));
+        assert!(layout.restructure_move_to_root(PaneId::new(4), tide_core::DropZone::Left, WINDOW));
 
         let rects = layout.compute(WINDOW, &[], None);
         assert_eq!(rects.len(), 4);
         assert_no_gaps_no_overlaps(&rects, WINDOW);
 
         // Pane 4 should be on the far left, full height
-        let r4 = rects.iter().find(|(id, _)| *id == 4).unwrap();
+        let r4 = rects.iter().find(|(id, _)| *id == PaneId::new(4)).unwrap();
         assert!(approx_eq(r4.1.x, 0.0), "pane 4 x: {}", r4.1.x);
         assert!(approx_eq(r4.1.height, 600.0), "pane 4 height: {}", r4.1.height);
 
         // Panes 2 and 3 should be stacked vertically in the middle column
-        let r2 = rects.iter().find(|(id, _)| *id == 2).unwrap();
-        let r3 = rects.iter().find(|(id, _)| *id == 3).unwrap();
+        let r2 = rects.iter().find(|(id, _)| *id == PaneId::new(2)).unwrap();
+        let r3 = rects.iter().find(|(id, _)| *id == PaneId::new(3)).unwrap();
         assert!(approx_eq(r2.1.x, r3.1.x), "2 and 3 should share x");
         assert!(r2.1.y < r3.1.y, "2 should be above 3");
 
         // Pane 1 should be on the far right, full height
-        let r1 = rects.iter().find(|(id, _)| *id == 1).unwrap();
+        let r1 = rects.iter().find(|(id, _)| *id == PaneId::new(1)).unwrap();
         assert!(approx_eq(r1.1.height, 600.0), "pane 1 height: {}", r1.1.height);
         assert!(r1.1.x > r2.1.x, "pane 1 should be right of pane 2");
     }
@@ -895,15 +953,15 @@ mod tests {
     #[test]
     fn test_restructure_move_pane_to_pane2_left() {
         let mut layout = make_quadrant_layout();
-        assert!(layout.restructure_move_pane(4, 2, tide_core::DropZone::Left, WINDOW));
+        assert!(layout.restructure_move_pane(PaneId::new(4), PaneId::new(2), tide_core::DropZone::Left, WINDOW));
 
         let rects = layout.compute(WINDOW, &[], None);
         assert_eq!(rects.len(), 4);
         assert_no_gaps_no_overlaps(&rects, WINDOW);
 
         // Pane 4 should be to the left of pane 2
-        let r4 = rects.iter().find(|(id, _)| *id == 4).unwrap();
-        let r2 = rects.iter().find(|(id, _)| *id == 2).unwrap();
+        let r4 = rects.iter().find(|(id, _)| *id == PaneId::new(4)).unwrap();
+        let r2 = rects.iter().find(|(id, _)| *id == PaneId::new(2)).unwrap();
         assert!(r4.1.x < r2.1.x, "pane 4 should be left of pane 2");
         assert!(approx_eq(r4.1.y, r2.1.y), "pane 4 and 2 should share top y");
     }
@@ -916,16 +974,16 @@ mod tests {
     #[test]
     fn test_restructure_move_pane4_to_pane1_left() {
         let mut layout = make_quadrant_layout();
-        assert!(layout.restructure_move_pane(4, 1, tide_core::DropZone::Left, WINDOW));
+        assert!(layout.restructure_move_pane(PaneId::new(4), PaneId::new(1), tide_core::DropZone::Left, WINDOW));
 
         let rects = layout.compute(WINDOW, &[], None);
         assert_eq!(rects.len(), 4);
         assert_no_gaps_no_overlaps(&rects, WINDOW);
 
         // Pane 4 should be between V(2,3) and pane 1
-        let r4 = rects.iter().find(|(id, _)| *id == 4).unwrap();
-        let r1 = rects.iter().find(|(id, _)| *id == 1).unwrap();
-        let r2 = rects.iter().find(|(id, _)| *id == 2).unwrap();
+        let r4 = rects.iter().find(|(id, _)| *id == PaneId::new(4)).unwrap();
+        let r1 = rects.iter().find(|(id, _)| *id == PaneId::new(1)).unwrap();
+        let r2 = rects.iter().find(|(id, _)| *id == PaneId::new(2)).unwrap();
         assert!(r4.1.x > r2.1.x, "pane 4 should be right of pane 2");
         assert!(r4.1.x < r1.1.x, "pane 4 should be left of pane 1");
         // Pane 4 should be full height (it's a standalone column)
@@ -940,16 +998,16 @@ mod tests {
     #[test]
     fn test_restructure_move_pane4_to_pane1_right() {
         let mut layout = make_quadrant_layout();
-        assert!(layout.restructure_move_pane(4, 1, tide_core::DropZone::Right, WINDOW));
+        assert!(layout.restructure_move_pane(PaneId::new(4), PaneId::new(1), tide_core::DropZone::Right, WINDOW));
 
         let rects = layout.compute(WINDOW, &[], None);
         assert_eq!(rects.len(), 4);
         assert_no_gaps_no_overlaps(&rects, WINDOW);
 
         // Pane 4 should be to the right of pane 1
-        let r4 = rects.iter().find(|(id, _)| *id == 4).unwrap();
-        let r1 = rects.iter().find(|(id, _)| *id == 1).unwrap();
-        let r2 = rects.iter().find(|(id, _)| *id == 2).unwrap();
+        let r4 = rects.iter().find(|(id, _)| *id == PaneId::new(4)).unwrap();
+        let r1 = rects.iter().find(|(id, _)| *id == PaneId::new(1)).unwrap();
+        let r2 = rects.iter().find(|(id, _)| *id == PaneId::new(2)).unwrap();
         assert!(r4.1.x > r1.1.x, "pane 4 should be right of pane 1");
         assert!(r1.1.x > r2.1.x, "pane 1 should be right of pane 2");
         assert!(approx_eq(r4.1.height, 600.0), "pane 4 height: {}", r4.1.height);
@@ -963,25 +1021,25 @@ mod tests {
     #[test]
     fn test_restructure_move_to_root_top() {
         let mut layout = make_quadrant_layout();
-        assert!(layout.restructure_move_to_root(4, tide_core::DropZone::Top, WINDOW));
+        assert!(layout.restructure_move_to_root(PaneId::new(4), tide_core::DropZone::Top, WINDOW));
 
         let rects = layout.compute(WINDOW, &[], None);
         assert_eq!(rects.len(), 4);
         assert_no_gaps_no_overlaps(&rects, WINDOW);
 
         // Pane 4 should be on top, full width
-        let r4 = rects.iter().find(|(id, _)| *id == 4).unwrap();
+        let r4 = rects.iter().find(|(id, _)| *id == PaneId::new(4)).unwrap();
         assert!(approx_eq(r4.1.y, 0.0), "pane 4 y: {}", r4.1.y);
         assert!(approx_eq(r4.1.width, 800.0), "pane 4 width: {}", r4.1.width);
 
         // Panes 2 and 1 should be in the middle row, side by side
-        let r2 = rects.iter().find(|(id, _)| *id == 2).unwrap();
-        let r1 = rects.iter().find(|(id, _)| *id == 1).unwrap();
+        let r2 = rects.iter().find(|(id, _)| *id == PaneId::new(2)).unwrap();
+        let r1 = rects.iter().find(|(id, _)| *id == PaneId::new(1)).unwrap();
         assert!(approx_eq(r2.1.y, r1.1.y), "2 and 1 should share y");
         assert!(r2.1.y > r4.1.y, "2 should be below 4");
 
         // Pane 3 should be on the bottom, full width
-        let r3 = rects.iter().find(|(id, _)| *id == 3).unwrap();
+        let r3 = rects.iter().find(|(id, _)| *id == PaneId::new(3)).unwrap();
         assert!(approx_eq(r3.1.width, 800.0), "pane 3 width: {}", r3.1.width);
         assert!(r3.1.y > r2.1.y, "3 should be below 2");
     }
@@ -994,25 +1052,25 @@ mod tests {
     #[test]
     fn test_restructure_move_to_root_bottom() {
         let mut layout = make_quadrant_layout();
-        assert!(layout.restructure_move_to_root(4, tide_core::DropZone::Bottom, WINDOW));
+        assert!(layout.restructure_move_to_root(PaneId::new(4), tide_core::DropZone::Bottom, WINDOW));
 
         let rects = layout.compute(WINDOW, &[], None);
         assert_eq!(rects.len(), 4);
         assert_no_gaps_no_overlaps(&rects, WINDOW);
 
         // Panes 2 and 1 should be in the top row, side by side
-        let r2 = rects.iter().find(|(id, _)| *id == 2).unwrap();
-        let r1 = rects.iter().find(|(id, _)| *id == 1).unwrap();
+        let r2 = rects.iter().find(|(id, _)| *id == PaneId::new(2)).unwrap();
+        let r1 = rects.iter().find(|(id, _)| *id == PaneId::new(1)).unwrap();
         assert!(approx_eq(r2.1.y, 0.0) || approx_eq(r1.1.y, 0.0), "top row at y=0");
         assert!(approx_eq(r2.1.y, r1.1.y), "2 and 1 should share y");
 
         // Pane 3 should be in the middle row, full width
-        let r3 = rects.iter().find(|(id, _)| *id == 3).unwrap();
+        let r3 = rects.iter().find(|(id, _)| *id == PaneId::new(3)).unwrap();
         assert!(r3.1.y > r2.1.y, "3 should be below 2");
         assert!(approx_eq(r3.1.width, 800.0), "pane 3 width: {}", r3.1.width);
 
         // Pane 4 should be on the bottom, full width
-        let r4 = rects.iter().find(|(id, _)| *id == 4).unwrap();
+        let r4 = rects.iter().find(|(id, _)| *id == PaneId::new(4)).unwrap();
         assert!(r4.1.y > r3.1.y, "4 should be below 3");
         assert!(approx_eq(r4.1.width, 800.0), "pane 4 width: {}", r4.1.width);
     }
@@ -1025,20 +1083,20 @@ mod tests {
     #[test]
     fn test_restructure_move_pane4_to_pane3_top() {
         let mut layout = make_quadrant_layout();
-        assert!(layout.restructure_move_pane(4, 3, tide_core::DropZone::Top, WINDOW));
+        assert!(layout.restructure_move_pane(PaneId::new(4), PaneId::new(3), tide_core::DropZone::Top, WINDOW));
 
         let rects = layout.compute(WINDOW, &[], None);
         assert_eq!(rects.len(), 4);
         assert_no_gaps_no_overlaps(&rects, WINDOW);
 
         // Pane 4 should be above pane 3
-        let r4 = rects.iter().find(|(id, _)| *id == 4).unwrap();
-        let r3 = rects.iter().find(|(id, _)| *id == 3).unwrap();
+        let r4 = rects.iter().find(|(id, _)| *id == PaneId::new(4)).unwrap();
+        let r3 = rects.iter().find(|(id, _)| *id == PaneId::new(3)).unwrap();
         assert!(r4.1.y < r3.1.y, "pane 4 should be above pane 3");
 
         // Panes 2 and 1 should be in the top row
-        let r2 = rects.iter().find(|(id, _)| *id == 2).unwrap();
-        let r1 = rects.iter().find(|(id, _)| *id == 1).unwrap();
+        let r2 = rects.iter().find(|(id, _)| *id == PaneId::new(2)).unwrap();
+        let r1 = rects.iter().find(|(id, _)| *id == PaneId::new(1)).unwrap();
         assert!(approx_eq(r2.1.y, r1.1.y), "2 and 1 should share y");
     }
 
@@ -1050,22 +1108,50 @@ mod tests {
     fn test_restructure_swap_center() {
         let mut layout = make_quadrant_layout();
         let rects_before = layout.compute(WINDOW, &[], None);
-        let r4_before = rects_before.iter().find(|(id, _)| *id == 4).unwrap().1;
-        let r1_before = rects_before.iter().find(|(id, _)| *id == 1).unwrap().1;
+        let r4_before = rects_before.iter().find(|(id, _)| *id == PaneId::new(4)).unwrap().1;
+        let r1_before = rects_before.iter().find(|(id, _)| *id == PaneId::new(1)).unwrap().1;
 
-        assert!(layout.restructure_move_pane(4, 1, tide_core::DropZone::Center, WINDOW));
+        assert!(layout.restructure_move_pane(PaneId::new(4), PaneId::new(1), tide_core::DropZone::Center, WINDOW));
 
         let rects_after = layout.compute(WINDOW, &[], None);
         assert_eq!(rects_after.len(), 4);
         assert_no_gaps_no_overlaps(&rects_after, WINDOW);
 
         // After swap: pane 4 should be where pane 1 was, and vice versa
-        let r4_after = rects_after.iter().find(|(id, _)| *id == 4).unwrap().1;
-        let r1_after = rects_after.iter().find(|(id, _)| *id == 1).unwrap().1;
+        let r4_after = rects_after.iter().find(|(id, _)| *id == PaneId::new(4)).unwrap().1;
+        let r1_after = rects_after.iter().find(|(id, _)| *id == PaneId::new(1)).unwrap().1;
         assert!(rect_approx_eq(&r4_after, &r1_before), "pane 4 should be at pane 1's old position");
         assert!(rect_approx_eq(&r1_after, &r4_before), "pane 1 should be at pane 4's old position");
     }
 
+    #[test]
+    fn test_swap_works_for_any_id_value() {
+        use crate::node::Node;
+        use crate::TabGroup;
+
+        // Swapping writes no placeholder id, so even u64::MAX is an ordinary id
+        let max = PaneId::new(u64::MAX);
+        let mut layout = SplitLayout {
+            root: Some(Node::Split {
+                direction: SplitDirection::Horizontal,
+                ratio: 0.5,
+                left: Box::new(Node::Leaf(TabGroup::single(PaneId::new(1)))),
+                right: Box::new(Node::Leaf(TabGroup::single(max))),
+            }),
+            next_id: 2,
+            ..SplitLayout::new()
+        };
+        let before = layout.compute(WINDOW, &[], None);
+        let left_before = before.iter().find(|(id, _)| *id == PaneId::new(1)).unwrap().1;
+
+        assert!(layout.restructure_move_pane(max, PaneId::new(1), tide_core::DropZone::Center, WINDOW));
+        let after = layout.compute(WINDOW, &[], None);
+        assert_eq!(after.len(), 2);
+        let moved = after.iter().find(|(id, _)| *id == max).unwrap().1;
+        assert!(rect_approx_eq(&moved, &left_before));
+        assert_ne!(layout.alloc_id(), max);
+    }
+
     // ──────────────────────────────────────────
     // Restructure: edge cases
     // ──────────────────────────────────────────
@@ -1101,7 +1187,7 @@ mod tests {
     #[test]
     fn test_restructure_same_pane_noop() {
         let mut layout = make_quadrant_layout();
-        assert!(!layout.restructure_move_pane(4, 4, tide_core::DropZone::Left, WINDOW));
+        assert!(!layout.restructure_move_pane(PaneId::new(4), PaneId::new(4), tide_core::DropZone::Left, WINDOW));
 
         // Layout unchanged
         let rects = layout.compute(WINDOW, &[], None);
@@ -1117,7 +1203,7 @@ mod tests {
         let layout = make_quadrant_layout();
 
         // Simulate moving pane 4 to root-left (source_in_tree = true)
-        let preview = layout.simulate_drop(4, None, tide_core::DropZone::Left, true, WINDOW);
+        let preview = layout.simulate_drop(PaneId::new(4), None, tide_core::DropZone::Left, true, WINDOW);
         assert!(preview.is_some(), "simulate_drop should return a rect");
         let r = preview.unwrap();
         // Pane 4 should be on the far left
@@ -1130,7 +1216,7 @@ mod tests {
         let layout = make_quadrant_layout();
 
         // Simulate moving pane 4 to pane 1's left (source_in_tree = true)
-        let preview = layout.simulate_drop(4, Some(1), tide_core::DropZone::Left, true, WINDOW);
+        let preview = layout.simulate_drop(PaneId::new(4), Some(PaneId::new(1)), tide_core::DropZone::Left, true, WINDOW);
         assert!(preview.is_some(), "simulate_drop should return a rect");
         let r = preview.unwrap();
         // Pane 4 should be full height (standalone column)
@@ -1234,7 +1320,7 @@ mod tests {
         assert_eq!(tg2.tabs.len(), 2);
 
         // Nonexistent pane
-        assert!(layout.tab_group_containing(999).is_none());
+        assert!(layout.tab_group_containing(PaneId::new(999)).is_none());
     }
 
     #[test]
@@ -1264,7 +1350,7 @@ mod tests {
     fn test_add_tab_nonexistent_target() {
         let (mut layout, _p1) = SplitLayout::with_initial_pane();
         let p2 = layout.alloc_id();
-        assert!(!layout.add_tab(999, p2));
+        assert!(!layout.add_tab(PaneId::new(999), p2));
     }
 
     #[test]
@@ -1365,4 +1451,269 @@ mod tests {
         assert_eq!(layout.right_neighbor_pane(p2), Some(p3));
         assert_eq!(layout.right_neighbor_pane(p3), None);
     }
+
+    // ──────────────────────────────────────────
+    // Drop target hit-testing
+    // ──────────────────────────────────────────
+
+    #[test]
+    fn test_drop_target_near_left_edge() {
+        // p1 | p2 (each 400 wide)
+        let (mut layout, p1) = SplitLayout::with_initial_pane();
+        let p2 = layout.split(p1, SplitDirection::Horizontal);
+        assert_eq!(
+            layout.drop_target_at(Vec2::new(410.0, 300.0), WINDOW),
+            Some(DropTarget::Pane(p2, DropZone::Left)),
+        );
+        assert_eq!(
+            layout.drop_target_at(Vec2::new(390.0, 300.0), WINDOW),
+            Some(DropTarget::Pane(p1, DropZone::Right)),
+        );
+    }
+
+    #[test]
+    fn test_drop_target_middle_is_center() {
+        let (mut layout, p1) = SplitLayout::with_initial_pane();
+        let p2 = layout.split(p1, SplitDirection::Horizontal);
+        assert_eq!(
+            layout.drop_target_at(Vec2::new(600.0, 300.0), WINDOW),
+            Some(DropTarget::Pane(p2, DropZone::Center)),
+        );
+    }
+
+    #[test]
+    fn test_drop_target_nearest_edge_outside_center() {
+        let (layout, p1) = SplitLayout::with_initial_pane();
+        assert_eq!(
+            layout.drop_target_at(Vec2::new(400.0, 20.0), WINDOW),
+            Some(DropTarget::Pane(p1, DropZone::Top)),
+        );
+        assert_eq!(
+            layout.drop_target_at(Vec2::new(400.0, 590.0), WINDOW),
+            Some(DropTarget::Pane(p1, DropZone::Bottom)),
+        );
+        assert_eq!(layout.drop_target_at(Vec2::new(900.0, 300.0), WINDOW), None);
+        assert_eq!(SplitLayout::new().drop_target_at(Vec2::new(10.0, 10.0), WINDOW), None);
+    }
+
+    // ──────────────────────────────────────────
+    // Ratio animation
+    // ──────────────────────────────────────────
+
+    fn left_width(layout: &SplitLayout, pane: PaneId) -> f32 {
+        layout
+            .compute(WINDOW, &[], None)
+            .into_iter()
+            .find(|(id, _)| *id == pane)
+            .map(|(_, r)| r.width)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_set_target_ratio_instant_by_default() {
+        let (mut layout, p1) = SplitLayout::with_initial_pane();
+        layout.split(p1, SplitDirection::Horizontal);
+        assert!(layout.set_target_ratio(&[], 0.75));
+        assert!(approx_eq(left_width(&layout, p1), 600.0));
+        assert!(!layout.is_animating());
+        assert!(!layout.tick(Duration::from_millis(16)));
+    }
+
+    #[test]
+    fn test_set_target_ratio_converges_over_ticks() {
+        let (mut layout, p1) = SplitLayout::with_initial_pane();
+        layout.split(p1, SplitDirection::Horizontal);
+        layout.set_animation_duration(Some(Duration::from_millis(100)));
+        assert!(layout.set_target_ratio(&[], 0.75));
+
+        // Nothing moves until the first tick.
+        assert!(approx_eq(left_width(&layout, p1), 400.0));
+
+        let mut prev = 400.0;
+        for _ in 0..5 {
+            assert!(layout.tick(Duration::from_millis(16)));
+            let w = left_width(&layout, p1);
+            assert!(w > prev && w < 600.0, "width {w} should move toward 600");
+            prev = w;
+        }
+
+        // Past the duration: settled exactly on the target.
+        assert!(!layout.tick(Duration::from_millis(50)));
+        assert!(approx_eq(left_width(&layout, p1), 600.0));
+        assert!(!layout.is_animating());
+        assert!(!layout.tick(Duration::from_millis(16)));
+    }
+
+    #[test]
+    fn test_set_target_ratio_clamps_and_rejects_bad_path() {
+        let (mut layout, p1) = SplitLayout::with_initial_pane();
+        assert!(!layout.set_target_ratio(&[], 0.3));
+        layout.split(p1, SplitDirection::Horizontal);
+        assert!(!layout.set_target_ratio(&[true], 0.3));
+        assert!(layout.set_target_ratio(&[], 0.0));
+        assert!(approx_eq(left_width(&layout, p1), 800.0 * MIN_RATIO));
+    }
+
+    #[test]
+    fn test_finish_animations_jumps_to_target() {
+        let (mut layout, p1) = SplitLayout::with_initial_pane();
+        let p2 = layout.split(p1, SplitDirection::Horizontal);
+        layout.set_animation_duration(Some(Duration::from_millis(200)));
+        layout.set_target_ratio(&[], 0.25);
+        layout.tick(Duration::from_millis(16));
+        layout.finish_animations();
+        assert!(approx_eq(left_width(&layout, p1), 200.0));
+        assert!(approx_eq(left_width(&layout, p2), 600.0));
+        assert!(!layout.is_animating());
+    }
+
+    // ──────────────────────────────────────────
+    // Split paths
+    // ──────────────────────────────────────────
+
+    #[test]
+    fn test_path_to_pane_in_nested_layout() {
+        let (mut layout, p1) = SplitLayout::with_initial_pane();
+        let p2 = layout.split(p1, SplitDirection::Horizontal);
+        let p3 = layout.split(p2, SplitDirection::Vertical);
+        // root: H(p1, V(p2, p3))
+        assert_eq!(layout.path_to(p1), Some(vec![false]));
+        assert_eq!(layout.path_to(p2), Some(vec![true, false]));
+        assert_eq!(layout.path_to(p3), Some(vec![true, true]));
+        assert_eq!(layout.path_to(PaneId::new(99)), None);
+    }
+
+    #[test]
+    fn test_path_to_single_pane_and_background_tab() {
+        let (mut layout, p1) = SplitLayout::with_initial_pane();
+        assert_eq!(layout.path_to(p1), Some(vec![]));
+        let p2 = layout.split(p1, SplitDirection::Horizontal);
+        let tab = layout.alloc_id();
+        layout.add_tab(p2, tab);
+        layout.set_active_tab(p2);
+        assert_eq!(layout.path_to(tab), Some(vec![true]));
+        assert!(SplitLayout::new().path_to(p1).is_none());
+    }
+
+    #[test]
+    fn test_split_at_path_returns_direction_ratio_and_rect() {
+        let (mut layout, p1) = SplitLayout::with_initial_pane();
+        let p2 = layout.split(p1, SplitDirection::Horizontal);
+        layout.split(p2, SplitDirection::Vertical);
+        assert!(layout.set_target_ratio(&[], 0.25));
+        assert!(layout.set_target_ratio(&[true], 0.75));
+
+        let (dir, ratio, rect) = layout.split_at_path(&[], WINDOW).unwrap();
+        assert_eq!(dir, SplitDirection::Horizontal);
+        assert!(approx_eq(ratio, 0.25));
+        assert!(rect_approx_eq(&rect, &Rect::new(0.0, 0.0, 800.0, 600.0)));
+
+        let (dir, ratio, rect) = layout.split_at_path(&[true], WINDOW).unwrap();
+        assert_eq!(dir, SplitDirection::Vertical);
+        assert!(approx_eq(ratio, 0.75));
+        assert!(rect_approx_eq(&rect, &Rect::new(200.0, 0.0, 600.0, 600.0)));
+
+        // Paths that end at a leaf or run past one address no split.
+        assert!(layout.split_at_path(&[false], WINDOW).is_none());
+        assert!(layout.split_at_path(&[true, true], WINDOW).is_none());
+    }
+
+    #[test]
+    fn test_split_at_parent_of_pane_path() {
+        let (mut layout, p1) = SplitLayout::with_initial_pane();
+        let p2 = layout.split(p1, SplitDirection::Vertical);
+        let p3 = layout.split(p2, SplitDirection::Horizontal);
+        let path = layout.path_to(p3).unwrap();
+        let (dir, _, rect) = layout.split_at_path(&path[..path.len() - 1], WINDOW).unwrap();
+        assert_eq!(dir, SplitDirection::Horizontal);
+        assert!(rect_approx_eq(&rect, &Rect::new(0.0, 300.0, 800.0, 300.0)));
+    }
+
+    // ──────────────────────────────────────────
+    // Degenerate windows and non-finite ratios
+    // ──────────────────────────────────────────
+
+    /// Every rect is finite and the rects cover the whole window.
+    fn assert_tiles(layout: &SplitLayout, window: Size) {
+        let rects = layout.compute(window, &[], None);
+        assert_eq!(rects.len(), layout.pane_ids().len());
+        for (id, r) in &rects {
+            assert!(
+                r.x.is_finite() && r.y.is_finite() && r.width.is_finite() && r.height.is_finite(),
+                "pane {id:?} has a non-finite rect {r:?}"
+            );
+        }
+        let area: f32 = rects.iter().map(|(_, r)| r.width * r.height).sum();
+        assert!((area - window.width * window.height).abs() < 1.0, "area {area}");
+    }
+
+    #[test]
+    fn test_drag_in_zero_width_window_keeps_ratios_finite() {
+        let (mut layout, pane1) = SplitLayout::with_initial_pane();
+        let pane2 = layout.split(pane1, SplitDirection::Horizontal);
+        layout.split(pane2, SplitDirection::Vertical);
+
+        let minimized = Size::new(0.0, 600.0);
+        layout.begin_drag(Vec2::new(0.0, 300.0), minimized);
+        assert!(layout.active_drag.is_some());
+        layout.drag_border(Vec2::new(0.0, 300.0));
+        layout.drag_border(Vec2::new(250.0, 300.0));
+        layout.end_drag();
+
+        let (_, ratio, _) = layout.split_at_path(&[], WINDOW).unwrap();
+        assert!(approx_eq(ratio, 0.5), "ratio {ratio}");
+        assert_tiles(&layout, WINDOW);
+        assert_tiles(&layout, minimized);
+    }
+
+    #[test]
+    fn test_drag_in_zero_size_window_keeps_ratios_finite() {
+        let (mut layout, pane1) = SplitLayout::with_initial_pane();
+        layout.split(pane1, SplitDirection::Vertical);
+
+        layout.begin_drag(Vec2::new(0.0, 0.0), Size::new(0.0, 0.0));
+        layout.drag_border(Vec2::new(0.0, 0.0));
+        layout.end_drag();
+
+        let (_, ratio, _) = layout.split_at_path(&[], WINDOW).unwrap();
+        assert!(ratio.is_finite());
+        assert_tiles(&layout, WINDOW);
+    }
+
+    #[test]
+    fn test_set_target_ratio_rejects_nan() {
+        let (mut layout, pane1) = SplitLayout::with_initial_pane();
+        layout.split(pane1, SplitDirection::Horizontal);
+        assert!(!layout.set_target_ratio(&[], f32::NAN));
+        let (_, ratio, _) = layout.split_at_path(&[], WINDOW).unwrap();
+        assert!(approx_eq(ratio, 0.5));
+    }
+
+    #[test]
+    fn test_sanitize_replaces_non_finite_ratios() {
+        let (mut layout, pane1) = SplitLayout::with_initial_pane();
+        let pane2 = layout.split(pane1, SplitDirection::Horizontal);
+        layout.split(pane2, SplitDirection::Vertical);
+        *layout.root.as_mut().unwrap().ratio_at_mut(&[]).unwrap() = f32::NAN;
+        *layout.root.as_mut().unwrap().ratio_at_mut(&[true]).unwrap() = f32::INFINITY;
+
+        layout.sanitize();
+
+        assert!(approx_eq(layout.split_at_path(&[], WINDOW).unwrap().1, 0.5));
+        assert!(approx_eq(layout.split_at_path(&[true], WINDOW).unwrap().1, 0.5));
+        assert_tiles(&layout, WINDOW);
+    }
+
+    #[test]
+    fn test_from_snapshot_sanitizes_ratios() {
+        let snap = LayoutSnapshot::Split {
+            direction: SplitDirection::Horizontal,
+            ratio: f32::NAN,
+            left: Box::new(LayoutSnapshot::Leaf { tabs: vec![PaneId::new(1)], active: 0 }),
+            right: Box::new(LayoutSnapshot::Leaf { tabs: vec![PaneId::new(2)], active: 0 }),
+        };
+        let layout = SplitLayout::from_snapshot(snap);
+        assert!(approx_eq(layout.split_at_path(&[], WINDOW).unwrap().1, 0.5));
+        assert_tiles(&layout, WINDOW);
+    }
 }
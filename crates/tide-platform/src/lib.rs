@@ -7,7 +7,7 @@
 pub mod macos;
 
 use raw_window_handle::{HasDisplayHandle, HasWindowHandle};
-use tide_core::{Key, Modifiers};
+use tide_core::{Key, Modifiers, Rect};
 
 // ──────────────────────────────────────────────
 // Platform Events
@@ -21,6 +21,10 @@ pub enum PlatformEvent {
         key: Key,
         modifiers: Modifiers,
         chars: Option<String>,
+        /// The key belongs to an active composition (IME preedit or a
+        /// pending dead key such as `´`). Its text, if any, arrives as
+        /// `ImeCommit`, so handlers must not insert `chars` or encode the key.
+        composing: bool,
     },
     /// A key was released.
     KeyUp {
@@ -51,11 +55,27 @@ pub enum PlatformEvent {
     MouseMoved {
         position: (f64, f64),
     },
+    /// Raw scroll deltas: wheel notches, or logical pixels when `precise`
+    /// (trackpad). The app converts both to lines.
     Scroll {
         dx: f32,
         dy: f32,
+        precise: bool,
         position: (f64, f64),
     },
+    /// Trackpad pinch. `scale_delta` is the magnification change since the
+    /// last event (+ = zoom in); `ended` is set on the gesture's last event
+    /// (fingers lifted or cancelled).
+    Pinch {
+        scale_delta: f32,
+        position: (f64, f64),
+        ended: bool,
+    },
+    /// Trackpad swipe. Each delta is -1.0, 0.0 or 1.0 (+dx = swipe left).
+    Swipe {
+        dx: f32,
+        dy: f32,
+    },
 
     // ── Window ──
     Resized {
@@ -84,10 +104,16 @@ pub enum PlatformEvent {
     /// Begin an event batch: suppress rendering until the matching `BatchEnd`.
     /// Used by ImeProxyView to flush deferred IME events atomically so that
     /// intermediate states (e.g. Backspace before replacement commit) never
-    /// render a partial frame.
+    /// render a partial frame. A `KeyDown` with `composing` set is delivered
+    /// inside the same batch, after the commit/preedit events of its keystroke.
     BatchStart,
     /// End an event batch and allow rendering to proceed.
     BatchEnd,
+
+    // ── Menus ──
+    /// The user picked a context menu or menu bar item. Carries the item's
+    /// `id`. Nothing is sent if a menu is dismissed.
+    MenuItemSelected(u32),
 }
 
 /// Mouse button identifiers.
@@ -113,6 +139,86 @@ pub enum CursorIcon {
     RowResize,
 }
 
+// ──────────────────────────────────────────────
+// Menus
+// ──────────────────────────────────────────────
+
+/// An entry in a native menu: a context menu (`PlatformWindow::show_context_menu`)
+/// or a menu bar menu (`MenuSpec`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MenuItem {
+    /// A selectable entry. Choosing it emits `PlatformEvent::MenuItemSelected(id)`.
+    Item {
+        id: u32,
+        label: String,
+        enabled: bool,
+        /// Key equivalent displayed next to the label.
+        shortcut: Option<(Key, Modifiers)>,
+    },
+    /// A horizontal divider.
+    Separator,
+}
+
+impl MenuItem {
+    /// An enabled item without a shortcut.
+    pub fn item(id: u32, label: impl Into<String>) -> Self {
+        MenuItem::Item { id, label: label.into(), enabled: true, shortcut: None }
+    }
+
+    pub fn separator() -> Self {
+        MenuItem::Separator
+    }
+
+    /// Set the enabled state (no-op on separators).
+    pub fn enabled(mut self, on: bool) -> Self {
+        if let MenuItem::Item { enabled, .. } = &mut self {
+            *enabled = on;
+        }
+        self
+    }
+
+    /// Set the displayed key equivalent (no-op on separators).
+    pub fn shortcut(mut self, key: Key, modifiers: Modifiers) -> Self {
+        if let MenuItem::Item { shortcut, .. } = &mut self {
+            *shortcut = Some((key, modifiers));
+        }
+        self
+    }
+}
+
+/// A top-level menu in the menu bar (e.g. "Edit", "View").
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Menu {
+    pub title: String,
+    pub items: Vec<MenuItem>,
+}
+
+impl Menu {
+    pub fn new(title: impl Into<String>, items: Vec<MenuItem>) -> Self {
+        Self { title: title.into(), items }
+    }
+}
+
+/// Menu bar layout, installed with `MacosApp::set_menu`.
+///
+/// The application menu (About, Preferences…, Services, Hide, Hide Others,
+/// Show All, Quit) is generated by the backend; `menus` follow it left to right.
+///
+/// Shortcuts are display-only while the window is key: the backend claims
+/// Cmd+ key equivalents before the menu bar sees them and delivers them as
+/// `KeyDown`, so the app's hotkey table stays the single dispatcher and an
+/// item is never triggered twice. Menu clicks arrive as `MenuItemSelected`.
+/// The one exception is Cmd+Q, which goes to the Quit item (emitting
+/// `CloseRequested`) once a menu bar is installed.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MenuSpec {
+    /// Used in "About …", "Hide …" and "Quit …".
+    pub app_name: String,
+    /// Id emitted by the "Preferences…" (Cmd+,) item. `None` omits it.
+    pub preferences_id: Option<u32>,
+    pub menus: Vec<Menu>,
+}
+
 // ──────────────────────────────────────────────
 // Window trait
 // ──────────────────────────────────────────────
@@ -154,6 +260,109 @@ pub trait PlatformWindow: HasWindowHandle + HasDisplayHandle {
     /// Reveal the window (set alpha to 1). Called after the first frame renders
     /// so the user never sees a blank window during GPU initialization.
     fn show_window(&self) {}
+
+    /// Pop up a native context menu at `at` (view coordinates, same space as
+    /// mouse event positions). The chosen item arrives as
+    /// `PlatformEvent::MenuItemSelected(id)`. Must run on the main thread, so
+    /// the app thread goes through `WindowProxy::show_context_menu`.
+    /// Default: no-op (platforms without native menus).
+    ///
+    /// ```
+    /// use tide_platform::{MenuItem, PlatformEvent, WindowProxy};
+    ///
+    /// const COPY: u32 = 1;
+    /// const PASTE: u32 = 2;
+    /// const SPLIT: u32 = 3;
+    ///
+    /// fn on_right_click(proxy: &WindowProxy, position: (f64, f64), has_selection: bool) {
+    ///     proxy.show_context_menu(
+    ///         vec![
+    ///             MenuItem::item(COPY, "Copy").enabled(has_selection),
+    ///             MenuItem::item(PASTE, "Paste"),
+    ///             MenuItem::separator(),
+    ///             MenuItem::item(SPLIT, "Split Right"),
+    ///         ],
+    ///         position,
+    ///     );
+    /// }
+    ///
+    /// fn on_event(event: PlatformEvent) {
+    ///     if let PlatformEvent::MenuItemSelected(id) = event {
+    ///         match id {
+    ///             COPY => { /* copy selection */ }
+    ///             PASTE => { /* paste clipboard */ }
+    ///             SPLIT => { /* split focused pane */ }
+    ///             _ => {}
+    ///         }
+    ///     }
+    /// }
+    /// ```
+    fn show_context_menu(&self, _items: Vec<MenuItem>, _at: (f64, f64)) {}
+
+    /// Resize the window's content area to `width` x `height`, keeping its
+    /// top-left corner in place. Default: no-op.
+    ///
+    /// The size is in physical pixels, the same units as `inner_size()` and
+    /// `PlatformEvent::Resized`; the backend divides by `scale_factor()`, so
+    /// on a 2x display `set_inner_size(1600, 1200)` gives an 800x600 point
+    /// window. Sizes that aren't a multiple of the scale factor round to the
+    /// nearest point.
+    fn set_inner_size(&self, _width: u32, _height: u32) {}
+
+    /// Move the window so its outer (title bar included) top-left corner is
+    /// at `x`, `y`. Default: no-op.
+    ///
+    /// Unlike sizes, positions are in logical points, in the global space of
+    /// `ScreenInfo::frame` (primary screen's top-left origin, y down): a
+    /// window spanning screens with different scale factors has no single
+    /// physical position.
+    fn set_position(&self, _x: i32, _y: i32) {}
+
+    /// The window's outer top-left corner, in the same logical global space
+    /// as `set_position`. Default: `(0, 0)`.
+    fn outer_position(&self) -> (i32, i32) {
+        (0, 0)
+    }
+
+    /// Ask for the user's attention while the app is in the background
+    /// (dock icon bounce on macOS). `critical` keeps bouncing until the app is
+    /// activated; otherwise it bounces once. No effect while the app is active.
+    /// Default: no-op.
+    fn request_attention(&self, _critical: bool) {}
+
+    /// The screen the window is (mostly) on.
+    /// Default: one synthetic primary screen the size of the window.
+    fn current_screen(&self) -> ScreenInfo {
+        let scale_factor = self.scale_factor();
+        let (w, h) = self.inner_size();
+        ScreenInfo {
+            frame: Rect::new(0.0, 0.0, (w as f64 / scale_factor) as f32, (h as f64 / scale_factor) as f32),
+            scale_factor,
+            is_primary: true,
+        }
+    }
+
+    /// Every connected screen, primary first.
+    /// Default: just `current_screen()`.
+    fn available_screens(&self) -> Vec<ScreenInfo> {
+        vec![self.current_screen()]
+    }
+}
+
+/// A connected display.
+///
+/// `frame` is in logical points, in one global space whose origin is the
+/// top-left corner of the primary screen with y growing downward — the same
+/// orientation as view coordinates. macOS reports screen frames bottom-left
+/// with y growing upward; the backend flips them against the primary
+/// screen's height, so a screen above the primary one has a negative `y`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ScreenInfo {
+    pub frame: Rect,
+    /// Physical pixels per logical point (2.0 on Retina).
+    pub scale_factor: f64,
+    /// The screen with the menu bar (global origin).
+    pub is_primary: bool,
 }
 
 // ──────────────────────────────────────────────
@@ -168,6 +377,9 @@ pub struct WindowConfig {
     pub min_width: f64,
     pub min_height: f64,
     pub transparent_titlebar: bool,
+    /// Non-opaque window so a translucent GPU clear color shows the desktop
+    /// (pair with `WgpuRenderer::set_background_opacity`).
+    pub transparent_background: bool,
 }
 
 impl Default for WindowConfig {
@@ -179,6 +391,7 @@ impl Default for WindowConfig {
             min_width: 400.0,
             min_height: 300.0,
             transparent_titlebar: true,
+            transparent_background: false,
         }
     }
 }
@@ -217,6 +430,19 @@ pub enum WindowCommand {
         w: f64,
         h: f64,
     },
+    ShowContextMenu {
+        items: Vec<MenuItem>,
+        at: (f64, f64),
+    },
+    RequestAttention(bool),
+    SetInnerSize {
+        width: u32,
+        height: u32,
+    },
+    SetPosition {
+        x: i32,
+        y: i32,
+    },
 }
 
 /// Execute a `WindowCommand` on the main thread using the actual window.
@@ -232,6 +458,10 @@ pub fn execute_window_command(window: &dyn PlatformWindow, cmd: WindowCommand) {
         WindowCommand::SetImeCursorArea { pane_id, x, y, w, h } => {
             window.set_ime_proxy_cursor_area(pane_id, x, y, w, h);
         }
+        WindowCommand::ShowContextMenu { items, at } => window.show_context_menu(items, at),
+        WindowCommand::RequestAttention(critical) => window.request_attention(critical),
+        WindowCommand::SetInnerSize { width, height } => window.set_inner_size(width, height),
+        WindowCommand::SetPosition { x, y } => window.set_position(x, y),
     }
 }
 
@@ -296,4 +526,20 @@ impl WindowProxy {
     pub fn set_ime_proxy_cursor_area(&self, pane_id: u64, x: f64, y: f64, w: f64, h: f64) {
         self.send(WindowCommand::SetImeCursorArea { pane_id, x, y, w, h });
     }
+
+    pub fn show_context_menu(&self, items: Vec<MenuItem>, at: (f64, f64)) {
+        self.send_and_wake(WindowCommand::ShowContextMenu { items, at });
+    }
+
+    pub fn request_attention(&self, critical: bool) {
+        self.send_and_wake(WindowCommand::RequestAttention(critical));
+    }
+
+    pub fn set_inner_size(&self, width: u32, height: u32) {
+        self.send_and_wake(WindowCommand::SetInnerSize { width, height });
+    }
+
+    pub fn set_position(&self, x: i32, y: i32) {
+        self.send_and_wake(WindowCommand::SetPosition { x, y });
+    }
 }
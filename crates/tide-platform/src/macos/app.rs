@@ -8,7 +8,9 @@ use objc2::rc::Retained;
 use objc2_app_kit::{NSApplication, NSApplicationActivationPolicy};
 use objc2_foundation::MainThreadMarker;
 
-use crate::{EventCallback, WakeCallback, WindowConfig};
+use tide_core::Key;
+
+use crate::{EventCallback, MenuSpec, WakeCallback, WindowConfig};
 
 use super::window::MacosWindow;
 
@@ -20,6 +22,15 @@ static GLOBAL_VIEW: AtomicPtr<std::ffi::c_void> = AtomicPtr::new(std::ptr::null_
 /// is already pending. Cleared by the main thread in triggerRedraw.
 static WAKEUP_PENDING: AtomicBool = AtomicBool::new(false);
 
+#[link(name = "CoreGraphics", kind = "framework")]
+extern "C" {
+    fn CGEventSourceKeyState(state_id: i32, key: u16) -> bool;
+}
+
+/// `kCGEventSourceStateCombinedSessionState`: the keyboard state seen by
+/// every event source in the login session.
+const COMBINED_SESSION_STATE: i32 = 0;
+
 /// macOS platform entry point.
 pub struct MacosApp;
 
@@ -50,6 +61,11 @@ impl MacosApp {
             cell.replace(Some(window));
         });
 
+        // Menu bar requested before the window existed (needs the view as target)
+        if let Some(spec) = super::menu::take_pending() {
+            with_main_window(|window| super::menu::install_menu_bar(&spec, &window.view));
+        }
+
         // Emit a synthetic event to trigger Phase 1 initialization immediately,
         // before the run loop starts. Without this, Phase 1 may be delayed until
         // the first event arrives from the run loop (e.g., windowDidBecomeKey),
@@ -71,6 +87,34 @@ impl MacosApp {
         unreachable!("NSApp.run() should never return")
     }
 
+    /// Treat Option as Meta: Option+key bypasses the input method and is
+    /// delivered as a `KeyDown` with `alt` set, instead of an `ImeCommit` of
+    /// the composed character (Option+b → Alt+b rather than "∫").
+    pub fn set_option_as_meta(on: bool) {
+        super::OPTION_AS_META.store(on, Ordering::Relaxed);
+    }
+
+    /// Whether `key` is physically held down right now. AppKit drops the
+    /// `keyUp` of a Cmd chord, so a software key repeat checks here rather
+    /// than waiting for `KeyUp`. Keys with no fixed keycode report false.
+    pub fn is_key_down(key: Key) -> bool {
+        match super::view::keycode_for_key(key) {
+            Some(code) => unsafe { CGEventSourceKeyState(COMBINED_SESSION_STATE, code) },
+            None => false,
+        }
+    }
+
+    /// Install an application menu bar built from `spec`. Must be called on
+    /// the main thread; before `run` the spec is kept and installed once the
+    /// window exists. Apps that never call this keep AppKit's bare menu.
+    pub fn set_menu(spec: MenuSpec) {
+        let installed =
+            with_main_window(|window| super::menu::install_menu_bar(&spec, &window.view));
+        if installed.is_none() {
+            super::menu::set_pending(spec);
+        }
+    }
+
     /// Create a waker that can be sent to background threads.
     /// When invoked, it wakes the run loop and triggers a redraw.
     /// Uses AtomicBool coalescing to skip duplicate wakeups when one is already pending.
@@ -16,6 +16,16 @@
 //! and redraw independently), `committed_text` is cleared on every fresh
 //! insert (no replacement range) and on every non-IME keystroke.  This
 //! limits the buffer to only the current composition context.
+//!
+//! ## Dead keys and compose sequences
+//!
+//! Dead keys (`´` then `e` → `é`) and Option/AltGr combos go through
+//! `interpretKeyEvents` like any IME: the pending accent arrives as
+//! `ImePreedit`, the result as `ImeCommit`.  A keystroke the input context
+//! leaves unhandled while a composition is open — or a dead key with no
+//! characters of its own — is emitted as `KeyDown { composing: true,
+//! chars: None }` inside the keystroke's `BatchStart`/`BatchEnd`, after its
+//! commit, so the app can skip it instead of typing the character twice.
 
 use std::cell::{Cell, RefCell};
 use std::rc::Rc;
@@ -198,6 +208,22 @@ declare_class!(
                 !self.ivars().marked_text.borrow().is_empty(),
             );
 
+            // Option-as-Meta: skip the input method so Option+b reaches the
+            // app as Alt+b instead of composing "∫".
+            let (key, modifiers) = key_and_modifiers_from_event(event);
+            if super::OPTION_AS_META.load(std::sync::atomic::Ordering::Relaxed)
+                && modifiers.alt
+                && !modifiers.meta
+                && !modifiers.ctrl
+                && !self.ivars().composing_at_key_down.get()
+            {
+                self.ivars().committed_text.borrow_mut().clear();
+                let chars = unsafe { event.characters().map(|s| s.to_string()) };
+                self.emit(PlatformEvent::KeyDown { key, modifiers, chars, composing: false });
+                *self.ivars().current_event.borrow_mut() = None;
+                return;
+            }
+
             // Begin deferring: accumulate PlatformEvents instead of emitting
             // them immediately. This prevents the full event pipeline (including
             // poll_background_events / rendering) from running mid-interpretKeyEvents,
@@ -218,18 +244,38 @@ declare_class!(
                 let _: () = msg_send![self, interpretKeyEvents: &*events];
             }
 
+            // A key the IME left alone while a composition is open (e.g. a
+            // dead key on a layout without an input context, or a key pressed
+            // mid-composition that the IME ignored) is still part of that
+            // composition: flag it and queue it into this keystroke's batch,
+            // after any commit, so the app never types its chars as well.
+            let mut composing = false;
+            if !self.ivars().ime_handled.get() {
+                let chars = unsafe { event.characters().map(|s| s.to_string()) };
+                // A dead key produces no characters until the next key
+                let dead_key = chars.as_deref() == Some("");
+                composing = dead_key
+                    || self.ivars().composing_at_key_down.get()
+                    || !self.ivars().marked_text.borrow().is_empty();
+                if composing {
+                    let (key, modifiers) = key_and_modifiers_from_event(event);
+                    self.ivars().ime_handled.set(true);
+                    self.emit(PlatformEvent::KeyDown { key, modifiers, chars: None, composing });
+                }
+            }
+
             // Stop deferring and flush all accumulated events.
             self.ivars().deferring.set(false);
             self.flush_deferred_events();
 
-            if !self.ivars().ime_handled.get() {
+            if !self.ivars().ime_handled.get() && !composing {
                 // Non-IME keystroke: clear committed_text so it doesn't
                 // accumulate stale data across keystrokes that change the
                 // terminal state without going through the IME.
                 self.ivars().committed_text.borrow_mut().clear();
                 let (key, modifiers) = key_and_modifiers_from_event(event);
                 let chars = unsafe { event.characters().map(|s| s.to_string()) };
-                self.emit(PlatformEvent::KeyDown { key, modifiers, chars });
+                self.emit(PlatformEvent::KeyDown { key, modifiers, chars, composing: false });
             }
 
             *self.ivars().current_event.borrow_mut() = None;
@@ -562,7 +608,8 @@ declare_class!(
                     Modifiers::default()
                 };
                 self.ivars().ime_handled.set(true);
-                self.emit(PlatformEvent::KeyDown { key, modifiers, chars: None });
+                // The IME passed this command through, so it is not composing
+                self.emit(PlatformEvent::KeyDown { key, modifiers, chars: None, composing: false });
             }
         }
     }
@@ -0,0 +1,260 @@
+//! Native menus: the application menu bar and context menus.
+//!
+//! NSMenuItem isn't bound by our objc2-app-kit features, so items are built
+//! through dynamic messaging. App items target TideView's `menuItemSelected:`
+//! action with the caller's id in the item tag.
+
+use std::cell::RefCell;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use objc2::rc::Retained;
+use objc2::runtime::{AnyClass, AnyObject, Bool, Sel};
+use objc2::{msg_send, msg_send_id, sel, Message};
+use objc2_app_kit::NSApplication;
+use objc2_foundation::{MainThreadMarker, NSString};
+use tide_core::{Key, Modifiers};
+
+use crate::{MenuItem, MenuSpec};
+
+use super::view::TideView;
+
+// NSEventModifierFlags
+const FLAG_SHIFT: usize = 1 << 17;
+const FLAG_CONTROL: usize = 1 << 18;
+const FLAG_OPTION: usize = 1 << 19;
+const FLAG_COMMAND: usize = 1 << 20;
+
+/// Set once a menu bar with a Quit item is installed. TideView then lets
+/// Cmd+Q through to the menu instead of emitting it as a KeyDown.
+static QUIT_INSTALLED: AtomicBool = AtomicBool::new(false);
+
+thread_local! {
+    /// Spec passed to `MacosApp::set_menu` before the window exists.
+    static PENDING_MENU: RefCell<Option<MenuSpec>> = const { RefCell::new(None) };
+}
+
+pub(crate) fn set_pending(spec: MenuSpec) {
+    PENDING_MENU.with(|p| *p.borrow_mut() = Some(spec));
+}
+
+pub(crate) fn take_pending() -> Option<MenuSpec> {
+    PENDING_MENU.with(|p| p.borrow_mut().take())
+}
+
+/// Whether `key` + `modifiers` should be left to the menu bar's Quit item.
+pub(crate) fn is_quit_equivalent(key: &Key, modifiers: &Modifiers) -> bool {
+    QUIT_INSTALLED.load(Ordering::Relaxed)
+        && *key == Key::Char('q')
+        && modifiers.meta
+        && !modifiers.shift
+        && !modifiers.ctrl
+        && !modifiers.alt
+}
+
+/// Create an empty menu. Auto-enabling is off so `MenuItem::enabled` is honored
+/// instead of responder-chain validation.
+pub(crate) unsafe fn new_menu(title: &str) -> Option<Retained<AnyObject>> {
+    let cls = AnyClass::get("NSMenu")?;
+    let menu: Retained<AnyObject> = msg_send_id![cls, new];
+    let title = NSString::from_str(title);
+    let _: () = msg_send![&*menu, setTitle: &*title];
+    let _: () = msg_send![&*menu, setAutoenablesItems: Bool::NO];
+    Some(menu)
+}
+
+/// Build an NSMenuItem for `item`, targeting `target`'s `menuItemSelected:`.
+pub(crate) unsafe fn build_item(
+    item: &MenuItem,
+    target: &impl Message,
+) -> Option<Retained<AnyObject>> {
+    let cls = AnyClass::get("NSMenuItem")?;
+    match item {
+        MenuItem::Separator => Some(msg_send_id![cls, separatorItem]),
+        MenuItem::Item { id, label, enabled, shortcut } => {
+            let (key, mask) = shortcut
+                .as_ref()
+                .and_then(|(key, mods)| Some((key_equivalent(key)?, modifier_mask(mods))))
+                .unwrap_or_default();
+            let ns_item =
+                action_item(label, Some(sel!(menuItemSelected:)), &key, mask, Some(target))?;
+            let _: () = msg_send![&*ns_item, setTag: *id as isize];
+            let _: () = msg_send![&*ns_item, setEnabled: Bool::new(*enabled)];
+            Some(ns_item)
+        }
+    }
+}
+
+/// Build an NSMenuItem with an explicit action. A `None` target sends the
+/// action up the responder chain (NSApplication handles hide:, the about panel, …).
+unsafe fn action_item(
+    title: &str,
+    action: Option<Sel>,
+    key: &str,
+    mask: usize,
+    target: Option<&impl Message>,
+) -> Option<Retained<AnyObject>> {
+    let cls = AnyClass::get("NSMenuItem")?;
+    let item: Retained<AnyObject> = msg_send_id![cls, new];
+    let title = NSString::from_str(title);
+    let key = NSString::from_str(key);
+    let _: () = msg_send![&*item, setTitle: &*title];
+    let _: () = msg_send![&*item, setKeyEquivalent: &*key];
+    let _: () = msg_send![&*item, setKeyEquivalentModifierMask: mask];
+    if let Some(action) = action {
+        let _: () = msg_send![&*item, setAction: action];
+    }
+    if let Some(target) = target {
+        let _: () = msg_send![&*item, setTarget: target];
+    }
+    Some(item)
+}
+
+/// Wrap `submenu` in an item so it can be added to a parent menu.
+unsafe fn submenu_item(title: &str, submenu: &AnyObject) -> Option<Retained<AnyObject>> {
+    let item = action_item(title, None, "", 0, None::<&AnyObject>)?;
+    let _: () = msg_send![&*item, setSubmenu: submenu];
+    Some(item)
+}
+
+unsafe fn add(menu: &AnyObject, item: Option<Retained<AnyObject>>) {
+    if let Some(item) = item {
+        let _: () = msg_send![menu, addItem: &*item];
+    }
+}
+
+/// Build the menu bar from `spec` and make it the application's main menu.
+pub(crate) fn install_menu_bar(spec: &MenuSpec, view: &TideView) {
+    let Some(mtm) = MainThreadMarker::new() else {
+        log::warn!("set_menu: menu bar must be installed on the main thread");
+        return;
+    };
+    let app = NSApplication::sharedApplication(mtm);
+    let name = &spec.app_name;
+    let separator = || unsafe { build_item(&MenuItem::Separator, view) };
+
+    unsafe {
+        let (Some(menu_bar), Some(app_menu), Some(services)) =
+            (new_menu(""), new_menu(name), new_menu("Services"))
+        else {
+            return;
+        };
+
+        // ── Application menu ──
+        add(
+            &app_menu,
+            action_item(
+                &format!("About {name}"),
+                Some(sel!(orderFrontStandardAboutPanel:)),
+                "",
+                0,
+                None::<&AnyObject>,
+            ),
+        );
+        if let Some(id) = spec.preferences_id {
+            add(&app_menu, separator());
+            let prefs = MenuItem::item(id, "Preferences…").shortcut(
+                Key::Char(','),
+                Modifiers { meta: true, ..Modifiers::default() },
+            );
+            add(&app_menu, build_item(&prefs, view));
+        }
+        add(&app_menu, separator());
+        add(&app_menu, submenu_item("Services", &services));
+        let _: () = msg_send![&app, setServicesMenu: &*services];
+        add(&app_menu, separator());
+        // No key equivalents on the hide items: Cmd+H and Opt+Cmd+H belong to
+        // the hotkey table (pane navigation), which sees Cmd+ keys first.
+        add(
+            &app_menu,
+            action_item(&format!("Hide {name}"), Some(sel!(hide:)), "", 0, None::<&AnyObject>),
+        );
+        add(
+            &app_menu,
+            action_item(
+                "Hide Others",
+                Some(sel!(hideOtherApplications:)),
+                "",
+                0,
+                None::<&AnyObject>,
+            ),
+        );
+        add(
+            &app_menu,
+            action_item(
+                "Show All",
+                Some(sel!(unhideAllApplications:)),
+                "",
+                0,
+                None::<&AnyObject>,
+            ),
+        );
+        add(&app_menu, separator());
+        // Quit goes through the view so the app sees CloseRequested and can
+        // save its session, same as closing the window.
+        add(
+            &app_menu,
+            action_item(
+                &format!("Quit {name}"),
+                Some(sel!(quitRequested:)),
+                "q",
+                FLAG_COMMAND,
+                Some(view),
+            ),
+        );
+        add(&menu_bar, submenu_item(name, &app_menu));
+
+        // ── App-defined menus ──
+        for menu in &spec.menus {
+            let Some(ns_menu) = new_menu(&menu.title) else { continue };
+            for item in &menu.items {
+                add(&ns_menu, build_item(item, view));
+            }
+            add(&menu_bar, submenu_item(&menu.title, &ns_menu));
+        }
+
+        let _: () = msg_send![&app, setMainMenu: &*menu_bar];
+    }
+    QUIT_INSTALLED.store(true, Ordering::Relaxed);
+}
+
+/// NSMenuItem key equivalent string for `key`, if it has one.
+fn key_equivalent(key: &Key) -> Option<String> {
+    let c = match *key {
+        Key::Char(c) | Key::Keypad(c) => c.to_ascii_lowercase(),
+        Key::Enter => '\r',
+        Key::Tab => '\t',
+        Key::Escape => '\u{1b}',
+        Key::Backspace => '\u{8}',
+        Key::Delete => '\u{F728}',
+        // NSFunctionKey unicode values
+        Key::Up => '\u{F700}',
+        Key::Down => '\u{F701}',
+        Key::Left => '\u{F702}',
+        Key::Right => '\u{F703}',
+        Key::F(n @ 1..=24) => char::from_u32(0xF703 + n as u32)?,
+        Key::Insert => '\u{F727}',
+        Key::Home => '\u{F729}',
+        Key::End => '\u{F72B}',
+        Key::PageUp => '\u{F72C}',
+        Key::PageDown => '\u{F72D}',
+        Key::F(_) => return None,
+    };
+    Some(c.to_string())
+}
+
+fn modifier_mask(modifiers: &Modifiers) -> usize {
+    let mut mask = 0;
+    if modifiers.shift {
+        mask |= FLAG_SHIFT;
+    }
+    if modifiers.ctrl {
+        mask |= FLAG_CONTROL;
+    }
+    if modifiers.alt {
+        mask |= FLAG_OPTION;
+    }
+    if modifiers.meta {
+        mask |= FLAG_COMMAND;
+    }
+    mask
+}
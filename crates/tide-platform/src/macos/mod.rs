@@ -2,6 +2,7 @@
 
 mod app;
 pub(crate) mod ime_proxy;
+mod menu;
 mod view;
 pub mod webview;
 mod window;
@@ -11,7 +12,7 @@ pub use window::MacosWindow;
 
 use std::cell::RefCell;
 use std::rc::Rc;
-use std::sync::atomic::AtomicU64;
+use std::sync::atomic::{AtomicBool, AtomicU64};
 
 use crate::{EventCallback, PlatformEvent};
 
@@ -20,6 +21,9 @@ use crate::{EventCallback, PlatformEvent};
 /// on the main thread, without waiting for the app thread round-trip.
 pub(crate) static LAST_IME_TARGET: AtomicU64 = AtomicU64::new(0);
 
+/// Set by `MacosApp::set_option_as_meta`; read by ImeProxyView's keyDown.
+pub(crate) static OPTION_AS_META: AtomicBool = AtomicBool::new(false);
+
 thread_local! {
     /// Queue for events that arrive during re-entrancy (callback already borrowed).
     /// Drained after the outer callback returns, so no events are lost.
@@ -134,6 +134,12 @@ declare_class!(
             if modifiers.meta {
                 let (key, modifiers) = key_and_modifiers_from_event(event);
 
+                // Cmd+Q belongs to the menu bar's Quit item when one is
+                // installed. Declining here lets NSApp hand it to the main menu.
+                if super::menu::is_quit_equivalent(&key, &modifiers) {
+                    return Bool::NO;
+                }
+
                 let is_editing_shortcut = match key {
                     Key::Char('c') | Key::Char('v') | Key::Char('a') | Key::Char('x') => {
                         !modifiers.shift && !modifiers.ctrl && !modifiers.alt
@@ -193,7 +199,7 @@ declare_class!(
 
                 // Intercept: emit as KeyDown and claim the event
                 let chars = unsafe { event.characters().map(|s| s.to_string()) };
-                self.emit(PlatformEvent::KeyDown { key, modifiers, chars });
+                self.emit(PlatformEvent::KeyDown { key, modifiers, chars, composing: false });
                 return Bool::YES;
             }
 
@@ -218,7 +224,7 @@ declare_class!(
                 if first_responder_is_ime_proxy {
                     let (key, modifiers) = key_and_modifiers_from_event(event);
                     let chars = unsafe { event.characters().map(|s| s.to_string()) };
-                    self.emit(PlatformEvent::KeyDown { key, modifiers, chars });
+                    self.emit(PlatformEvent::KeyDown { key, modifiers, chars, composing: false });
                     return Bool::YES;
                 }
             }
@@ -299,19 +305,34 @@ declare_class!(
         #[method(scrollWheel:)]
         fn scroll_wheel(&self, event: &NSEvent) {
             let pos = self.mouse_pos(event);
-            let (dx, dy) = unsafe {
+            // Precise deltas are in points; wheel deltas are in notches.
+            let (dx, dy, precise) = unsafe {
                 let has_precise: Bool = msg_send![event, hasPreciseScrollingDeltas];
-                if has_precise.as_bool() {
-                    let sdx: f64 = msg_send![event, scrollingDeltaX];
-                    let sdy: f64 = msg_send![event, scrollingDeltaY];
-                    (sdx as f32 / 6.0, sdy as f32 / 6.0)
-                } else {
-                    let sdx: f64 = msg_send![event, scrollingDeltaX];
-                    let sdy: f64 = msg_send![event, scrollingDeltaY];
-                    (sdx as f32 * 3.0, sdy as f32 * 3.0)
-                }
+                let sdx: f64 = msg_send![event, scrollingDeltaX];
+                let sdy: f64 = msg_send![event, scrollingDeltaY];
+                (sdx as f32, sdy as f32, has_precise.as_bool())
             };
-            self.emit(PlatformEvent::Scroll { dx, dy, position: pos });
+            self.emit(PlatformEvent::Scroll { dx, dy, precise, position: pos });
+        }
+
+        #[method(magnifyWithEvent:)]
+        fn magnify_with_event(&self, event: &NSEvent) {
+            let pos = self.mouse_pos(event);
+            let scale_delta: f64 = unsafe { msg_send![event, magnification] };
+            // NSEventPhaseEnded | NSEventPhaseCancelled
+            let phase: usize = unsafe { msg_send![event, phase] };
+            let ended = phase & (0x8 | 0x10) != 0;
+            self.emit(PlatformEvent::Pinch { scale_delta: scale_delta as f32, position: pos, ended });
+        }
+
+        #[method(swipeWithEvent:)]
+        fn swipe_with_event(&self, event: &NSEvent) {
+            let (dx, dy) = unsafe {
+                let dx: f64 = msg_send![event, deltaX];
+                let dy: f64 = msg_send![event, deltaY];
+                (dx as f32, dy as f32)
+            };
+            self.emit(PlatformEvent::Swipe { dx, dy });
         }
 
         /// Detect left-mouse-down clicks on non-Tide subviews (e.g. WKWebView)
@@ -385,6 +406,20 @@ declare_class!(
             super::app::clear_wakeup_pending();
             self.emit(PlatformEvent::RedrawRequested);
         }
+
+        /// Action target for context menu and menu bar items.
+        /// The item's tag carries the caller-supplied id.
+        #[method(menuItemSelected:)]
+        fn menu_item_selected(&self, sender: &AnyObject) {
+            let tag: isize = unsafe { msg_send![sender, tag] };
+            self.emit(PlatformEvent::MenuItemSelected(tag as u32));
+        }
+
+        /// Action of the menu bar's Quit item (Cmd+Q).
+        #[method(quitRequested:)]
+        fn quit_requested(&self, _sender: &AnyObject) {
+            self.emit(PlatformEvent::CloseRequested);
+        }
     }
 
 );
@@ -586,10 +621,27 @@ pub(super) fn key_from_keycode(keycode: u16) -> Key {
         0x7A => Key::F(1), 0x78 => Key::F(2), 0x63 => Key::F(3), 0x76 => Key::F(4),
         0x60 => Key::F(5), 0x61 => Key::F(6), 0x62 => Key::F(7), 0x64 => Key::F(8),
         0x65 => Key::F(9), 0x6D => Key::F(10), 0x67 => Key::F(11), 0x6F => Key::F(12),
+        0x69 => Key::F(13), 0x6B => Key::F(14), 0x71 => Key::F(15), 0x6A => Key::F(16),
+        0x40 => Key::F(17), 0x4F => Key::F(18), 0x50 => Key::F(19), 0x5A => Key::F(20),
+        0x52 => Key::Keypad('0'), 0x53 => Key::Keypad('1'), 0x54 => Key::Keypad('2'),
+        0x55 => Key::Keypad('3'), 0x56 => Key::Keypad('4'), 0x57 => Key::Keypad('5'),
+        0x58 => Key::Keypad('6'), 0x59 => Key::Keypad('7'), 0x5B => Key::Keypad('8'),
+        0x5C => Key::Keypad('9'), 0x41 => Key::Keypad('.'), 0x43 => Key::Keypad('*'),
+        0x45 => Key::Keypad('+'), 0x4B => Key::Keypad('/'), 0x4E => Key::Keypad('-'),
+        0x51 => Key::Keypad('='), 0x4C => Key::Enter,
         _ => Key::Char('?'),
     }
 }
 
+/// The keycode `key_from_keycode` maps to `key` (the first, for keys on two
+/// codes such as Enter).
+pub(super) fn keycode_for_key(key: Key) -> Option<u16> {
+    if key == Key::Char('?') {
+        return None; // key_from_keycode's fallback, not a real mapping
+    }
+    (0..0x80).find(|&code| key_from_keycode(code) == key)
+}
+
 pub(super) fn key_and_modifiers_from_event(event: &NSEvent) -> (Key, Modifiers) {
     let keycode = unsafe { event.keyCode() };
     let flags = unsafe { event.modifierFlags() };
@@ -604,7 +656,10 @@ pub(super) fn key_and_modifiers_from_event(event: &NSEvent) -> (Key, Modifiers)
             let s = s.to_string();
             let mut chars = s.chars();
             if let Some(c) = chars.next() {
-                if chars.next().is_none() && !c.is_control() {
+                // AppKit reports function/navigation keys as private-use
+                // chars (NSF1FunctionKey = U+F704 …); map those by keycode.
+                let function_key = ('\u{F700}'..='\u{F8FF}').contains(&c);
+                if chars.next().is_none() && !c.is_control() && !function_key {
                     return Some(Key::Char(c));
                 }
             }
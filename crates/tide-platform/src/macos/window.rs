@@ -9,7 +9,7 @@ use objc2::runtime::{AnyObject, Bool};
 use objc2::{declare_class, msg_send, msg_send_id, mutability, ClassType, DeclaredClass};
 use objc2_foundation::MainThreadMarker;
 use objc2_app_kit::{
-    NSBackingStoreType, NSView, NSWindow, NSWindowStyleMask,
+    NSApplication, NSBackingStoreType, NSView, NSWindow, NSWindowStyleMask,
 };
 use objc2_foundation::{
     CGFloat, NSMutableArray, NSPoint, NSRect, NSSize, NSString,
@@ -19,7 +19,7 @@ use raw_window_handle::{
     HasWindowHandle, RawDisplayHandle, RawWindowHandle, WindowHandle,
 };
 
-use crate::{CursorIcon, EventCallback, PlatformWindow, WindowConfig};
+use crate::{CursorIcon, EventCallback, MenuItem, PlatformWindow, ScreenInfo, WindowConfig};
 
 /// Initial window background color (dark gray) to avoid white flash before
 /// the first GPU frame renders. RGB values in 0.0–1.0 range.
@@ -190,6 +190,21 @@ impl MacosWindow {
             let _: () = msg_send![&ns_window, setBackgroundColor: &*bg_color];
         }
 
+        // Translucent background: let the window server composite the
+        // (premultiplied) surface alpha over the desktop.
+        if config.transparent_background {
+            unsafe {
+                use objc2::msg_send_id;
+                use objc2::runtime::AnyClass;
+                let clear: Retained<objc2::runtime::AnyObject> = msg_send_id![
+                    AnyClass::get("NSColor").expect("NSColor class must exist"),
+                    clearColor
+                ];
+                let _: () = msg_send![&ns_window, setOpaque: Bool::NO];
+                let _: () = msg_send![&ns_window, setBackgroundColor: &*clear];
+            }
+        }
+
         // Start invisible — show_window() reveals after the first frame renders,
         // so the user never sees a blank window during GPU initialization.
         unsafe {
@@ -339,4 +354,136 @@ impl PlatformWindow for MacosWindow {
             let _: () = msg_send![&self.ns_window, setAlphaValue: 1.0_f64];
         }
     }
+
+    fn show_context_menu(&self, items: Vec<MenuItem>, at: (f64, f64)) {
+        unsafe {
+            let Some(menu) = super::menu::new_menu("") else { return };
+            for item in &items {
+                if let Some(ns_item) = super::menu::build_item(item, &*self.view) {
+                    let _: () = msg_send![&*menu, addItem: &*ns_item];
+                }
+            }
+            // The view is flipped, so `at` (view coords, top-left origin) is
+            // already in the space popUpMenu expects. Runs a modal tracking
+            // loop; the selection is emitted from the view's action method.
+            let location = NSPoint::new(at.0, at.1);
+            let _: Bool = msg_send![
+                &*menu,
+                popUpMenuPositioningItem: std::ptr::null::<AnyObject>(),
+                atLocation: location,
+                inView: &*self.view
+            ];
+        }
+    }
+
+    fn set_inner_size(&self, width: u32, height: u32) {
+        let scale = self.scale_factor();
+        let width = (width as f64 / scale).round();
+        let height = (height as f64 / scale).round();
+        // setContentSize: keeps the bottom-left corner; pin the top edge instead
+        let frame: NSRect = unsafe { msg_send![&self.ns_window, frame] };
+        let content: NSRect = unsafe { msg_send![&self.ns_window, contentRectForFrameRect: frame] };
+        let top = content.origin.y + content.size.height;
+        let new_content = NSRect::new(NSPoint::new(content.origin.x, top - height), NSSize::new(width, height));
+        let new_frame: NSRect = unsafe { msg_send![&self.ns_window, frameRectForContentRect: new_content] };
+        unsafe {
+            let _: () = msg_send![&self.ns_window, setFrame: new_frame, display: Bool::YES];
+        }
+    }
+
+    fn set_position(&self, x: i32, y: i32) {
+        let frame: NSRect = unsafe { msg_send![&self.ns_window, frame] };
+        // Flip the y-down global position back to Cocoa's bottom-left origin
+        let primary_height = unsafe { primary_screen_height() };
+        let origin_y = primary_height - y as f64 - frame.size.height;
+        let new_frame = NSRect::new(NSPoint::new(x as f64, origin_y), frame.size);
+        unsafe {
+            let _: () = msg_send![&self.ns_window, setFrame: new_frame, display: Bool::YES];
+        }
+    }
+
+    fn outer_position(&self) -> (i32, i32) {
+        let frame: NSRect = unsafe { msg_send![&self.ns_window, frame] };
+        let primary_height = unsafe { primary_screen_height() };
+        let top = primary_height - (frame.origin.y + frame.size.height);
+        (frame.origin.x.round() as i32, top.round() as i32)
+    }
+
+    fn request_attention(&self, critical: bool) {
+        // NSRequestUserAttentionType: NSCriticalRequest = 0, NSInformationalRequest = 10
+        let kind: isize = if critical { 0 } else { 10 };
+        let Some(mtm) = MainThreadMarker::new() else { return };
+        let app = NSApplication::sharedApplication(mtm);
+        unsafe {
+            let _: isize = msg_send![&app, requestUserAttention: kind];
+        }
+    }
+
+    fn current_screen(&self) -> ScreenInfo {
+        unsafe {
+            let screen: Option<Retained<AnyObject>> = msg_send_id![&self.ns_window, screen];
+            let primary_height = primary_screen_height();
+            match screen {
+                Some(screen) => screen_info(&screen, primary_height),
+                // Off-screen window: fall back to the primary screen
+                None => self.available_screens().into_iter().next().unwrap_or(ScreenInfo {
+                    frame: tide_core::Rect::new(0.0, 0.0, 0.0, 0.0),
+                    scale_factor: self.scale_factor(),
+                    is_primary: true,
+                }),
+            }
+        }
+    }
+
+    fn available_screens(&self) -> Vec<ScreenInfo> {
+        unsafe {
+            let Some(screens) = screens() else { return Vec::new() };
+            let primary_height = primary_screen_height();
+            let count: usize = msg_send![&*screens, count];
+            (0..count)
+                .map(|i| {
+                    let screen: Retained<AnyObject> = msg_send_id![&*screens, objectAtIndex: i];
+                    screen_info(&screen, primary_height)
+                })
+                .collect()
+        }
+    }
+}
+
+/// `NSScreen.screens`; the first entry is the primary (menu bar) screen.
+unsafe fn screens() -> Option<Retained<AnyObject>> {
+    let cls = objc2::runtime::AnyClass::get("NSScreen")?;
+    let screens: Retained<AnyObject> = msg_send_id![cls, screens];
+    Some(screens)
+}
+
+/// Height of the primary screen, the pivot for flipping Cocoa's bottom-left
+/// global coordinates to top-left.
+unsafe fn primary_screen_height() -> f64 {
+    let Some(screens) = screens() else { return 0.0 };
+    let count: usize = msg_send![&*screens, count];
+    if count == 0 {
+        return 0.0;
+    }
+    let primary: Retained<AnyObject> = msg_send_id![&*screens, objectAtIndex: 0usize];
+    let frame: NSRect = msg_send![&*primary, frame];
+    frame.size.height
+}
+
+unsafe fn screen_info(screen: &AnyObject, primary_height: f64) -> ScreenInfo {
+    let frame: NSRect = msg_send![screen, frame];
+    let scale_factor: CGFloat = msg_send![screen, backingScaleFactor];
+    // Cocoa's global origin is the primary screen's bottom-left corner
+    let top = primary_height - (frame.origin.y + frame.size.height);
+    ScreenInfo {
+        frame: tide_core::Rect::new(
+            frame.origin.x as f32,
+            top as f32,
+            frame.size.width as f32,
+            frame.size.height as f32,
+        ),
+        scale_factor,
+        // The primary screen is the one at the global origin
+        is_primary: frame.origin.x == 0.0 && frame.origin.y == 0.0,
+    }
 }
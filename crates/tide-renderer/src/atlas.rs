@@ -73,12 +73,10 @@ impl GlyphAtlas {
 
     /// Clear the atlas cache, allowing it to be repacked from scratch.
     pub fn reset(&mut self) {
-        let count = self.cache.len();
         self.cursor_x = 0;
         self.cursor_y = 0;
         self.row_height = 0;
         self.cache.clear();
-        log::warn!("Glyph atlas full: cleared {count} cached glyphs");
     }
 
     /// Upload an MSDF glyph (RGBA data) into the atlas, returning the region.
@@ -113,6 +111,7 @@ impl GlyphAtlas {
 
         // If we've run out of space, reset and retry
         if self.cursor_y + texel_height > ATLAS_SIZE {
+            log::warn!("Glyph atlas full: cleared {} cached glyphs", self.cache.len());
             self.reset();
             if self.cursor_x + texel_width > ATLAS_SIZE {
                 self.cursor_x = 0;
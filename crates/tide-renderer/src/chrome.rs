@@ -1,10 +1,24 @@
+use std::borrow::Cow;
+
+use unicode_segmentation::UnicodeSegmentation;
 use unicode_width::UnicodeWidthChar;
 
 use tide_core::{Color, Rect, TextStyle, Vec2};
 
+use crate::atlas::AtlasRegion;
 use crate::vertex::{ChromeRectVertex, GlyphVertex};
 use crate::WgpuRenderer;
 
+/// One char of laid-out text (see `WgpuRenderer::layout_text`).
+pub(crate) struct PlacedGlyph {
+    /// Pen position, logical px from the start of the run.
+    pub x: f32,
+    /// Logical px the pen moves past this char.
+    pub advance: f32,
+    /// The styled glyph; `None` for spaces and tabs.
+    pub region: Option<AtlasRegion>,
+}
+
 impl WgpuRenderer {
     /// Draw a sharp rect into the cached chrome layer (radius = 0).
     pub fn draw_chrome_rect(&mut self, rect: Rect, color: Color) {
@@ -13,6 +27,22 @@ impl WgpuRenderer {
 
     /// Draw a rounded rect into the cached chrome layer (SDF-based AA).
     pub fn draw_chrome_rounded_rect(&mut self, rect: Rect, color: Color, radius: f32) {
+        self.push_chrome_rounded_quad(rect, color, radius, 0.0);
+    }
+
+    /// Draw only the `width`-wide outline of a rounded rect into the cached
+    /// chrome layer, leaving the inside untouched. Same edge as
+    /// `draw_chrome_rounded_rect(rect, ..)` outside and as a fill of `rect`
+    /// inset by `width` with radius `radius - width` inside, so a translucent
+    /// fill there doesn't show the border color through it.
+    pub fn draw_chrome_rounded_border(&mut self, rect: Rect, color: Color, radius: f32, width: f32) {
+        if width > 0.0 {
+            // Negative `shadow_blur` selects the shader's stroke mode.
+            self.push_chrome_rounded_quad(rect, color, radius, -width * self.scale_factor);
+        }
+    }
+
+    fn push_chrome_rounded_quad(&mut self, rect: Rect, color: Color, radius: f32, mode: f32) {
         let s = self.scale_factor;
         let x = rect.x * s;
         let y = rect.y * s;
@@ -38,7 +68,7 @@ impl WgpuRenderer {
             rect_center: center,
             rect_half: half,
             corner_radius: r,
-            shadow_blur: 0.0,
+            shadow_blur: mode,
         };
         self.chrome_rect_vertices.push(vert(qx, qy));
         self.chrome_rect_vertices.push(vert(qx + qw, qy));
@@ -88,11 +118,11 @@ impl WgpuRenderer {
 
     /// Draw text into the cached chrome layer.
     pub fn draw_chrome_text(&mut self, text: &str, position: Vec2, style: TextStyle, clip: Rect) {
+        let clip = self.clip_stack.apply(clip);
         let scale = self.scale_factor;
-        let cell_w = self.cached_cell_size.width * scale;
         let baseline_y = self.baseline_y(self.cached_cell_size.height * scale);
 
-        let mut cursor_x = position.x * scale;
+        let cursor_x = position.x * scale;
         let start_y = position.y * scale;
 
         let clip_left = clip.x * scale;
@@ -100,19 +130,12 @@ impl WgpuRenderer {
         let clip_right = (clip.x + clip.width) * scale;
         let clip_bottom = (clip.y + clip.height) * scale;
 
-        for ch in text.chars() {
-            let char_cells = ch.width().unwrap_or(1) as f32;
+        for glyph in self.layout_text(text, style) {
+            let qx = cursor_x + glyph.x * scale;
+            let qw = glyph.advance * scale;
 
-            if ch == ' ' || ch == '\t' {
-                let advance = if ch == '\t' { cell_w * 4.0 } else { cell_w };
-                cursor_x += advance;
-                continue;
-            }
-
-            if let Some(bg) = style.background {
-                let qx = cursor_x;
+            if let Some(bg) = style.background.filter(|_| glyph.region.is_some()) {
                 let qy = start_y;
-                let qw = cell_w * char_cells;
                 let qh = self.cached_cell_size.height * scale;
                 if qx + qw > clip_left && qx < clip_right && qy + qh > clip_top && qy < clip_bottom {
                     let base = self.chrome_rect_vertices.len() as u32;
@@ -135,11 +158,9 @@ impl WgpuRenderer {
                 }
             }
 
-            let region = self.ensure_glyph_cached(ch, style.bold, style.italic);
-
-            if !region.is_empty() {
+            if let Some(region) = glyph.region.filter(|r| !r.is_empty()) {
                 let em_scale = self.em_scale();
-                let gx = cursor_x + region.em_left * em_scale;
+                let gx = qx + region.em_left * em_scale;
                 let gy = start_y + baseline_y - region.em_top * em_scale;
                 let gw = region.em_width * em_scale;
                 let gh = region.em_height * em_scale;
@@ -154,9 +175,58 @@ impl WgpuRenderer {
                     self.chrome_glyph_indices.extend_from_slice(&[base, base + 1, base + 2, base, base + 2, base + 3]);
                 }
             }
+        }
+    }
 
-            cursor_x += cell_w * char_cells;
+    /// Lay `text` out the way `draw_text`, `draw_chrome_text` and
+    /// `draw_top_text` place it: each char advances `char_advance_cells`
+    /// cells of the shaped cell width, and every char but spaces and tabs
+    /// gets its glyph for `style`'s weight and slant. Positions are logical,
+    /// relative to the start of the run.
+    pub(crate) fn layout_text(&mut self, text: &str, style: TextStyle) -> Vec<PlacedGlyph> {
+        let cell_w = self.cached_cell_size.width;
+        let mut x = 0.0;
+        let mut glyphs = Vec::with_capacity(text.len());
+        for ch in text.chars() {
+            let advance = char_advance_cells(ch) * cell_w;
+            let region = (ch != ' ' && ch != '\t').then(|| self.ensure_glyph_cached(ch, style.bold, style.italic));
+            glyphs.push(PlacedGlyph { x, advance, region });
+            x += advance;
         }
+        glyphs
+    }
+
+    /// Logical width `text` occupies when drawn with `style` by any of the
+    /// text draw calls (measured from the same `layout_text` they draw from).
+    pub fn measure_text(&mut self, text: &str, style: TextStyle) -> f32 {
+        self.layout_text(text, style).last().map_or(0.0, |g| g.x + g.advance)
+    }
+
+    /// How many leading chars of `text` fit within `max_width` (logical).
+    /// Used to truncate labels before appending an ellipsis.
+    pub fn measure_text_clamped(&mut self, text: &str, style: TextStyle, max_width: f32) -> usize {
+        self.layout_text(text, style)
+            .iter()
+            .take_while(|g| g.x + g.advance <= max_width + 1e-3)
+            .count()
+    }
+
+    /// Draw chrome text cut to `max_width` (logical), ending in "…" when it
+    /// doesn't fit. Text that fits is drawn unchanged.
+    pub fn draw_chrome_text_ellipsized(
+        &mut self,
+        text: &str,
+        position: Vec2,
+        style: TextStyle,
+        max_width: f32,
+        clip: Rect,
+    ) {
+        let cell_w = self.cached_cell_size.width;
+        if cell_w <= 0.0 {
+            return;
+        }
+        let shown = ellipsize(text, max_width / cell_w);
+        self.draw_chrome_text(&shown, position, style, clip);
     }
 
     /// Signal that chrome content has changed and needs a full rebuild.
@@ -168,3 +238,40 @@ impl WgpuRenderer {
         self.chrome_needs_upload = true;
     }
 }
+
+/// Cells `ch` advances the chrome text cursor by.
+pub(crate) fn char_advance_cells(ch: char) -> f32 {
+    match ch {
+        '\t' => 4.0,
+        ' ' => 1.0,
+        _ => ch.width().unwrap_or(1) as f32,
+    }
+}
+
+/// Total cell advance of `text`.
+pub(crate) fn text_advance_cells(text: &str) -> f32 {
+    text.chars().map(char_advance_cells).sum()
+}
+
+/// `text` truncated to `max_cells` with a trailing "…". Cuts between
+/// graphemes so wide chars and combining sequences stay whole.
+pub(crate) fn ellipsize(text: &str, max_cells: f32) -> Cow<'_, str> {
+    if text_advance_cells(text) <= max_cells + f32::EPSILON {
+        return Cow::Borrowed(text);
+    }
+    let budget = max_cells - char_advance_cells('\u{2026}');
+    if budget < 0.0 {
+        return Cow::Borrowed("");
+    }
+    let mut used = 0.0;
+    let mut end = 0;
+    for (offset, grapheme) in text.grapheme_indices(true) {
+        let advance = text_advance_cells(grapheme);
+        if used + advance > budget + f32::EPSILON {
+            break;
+        }
+        used += advance;
+        end = offset + grapheme.len();
+    }
+    Cow::Owned(format!("{}\u{2026}", &text[..end]))
+}
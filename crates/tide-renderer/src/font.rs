@@ -57,10 +57,18 @@ mod coretext_fallback {
 const FONT_SIZE_MIN: u32 = 8;
 const FONT_SIZE_MAX: u32 = 32;
 
+/// Default line height as a multiple of the font size.
+pub(crate) const DEFAULT_LINE_HEIGHT: f32 = 1.2;
+
 impl WgpuRenderer {
-    pub(crate) fn compute_cell_size(font_system: &mut FontSystem, scale_factor: f32, base_font_size: f32) -> Size {
+    pub(crate) fn compute_cell_size(
+        font_system: &mut FontSystem,
+        scale_factor: f32,
+        base_font_size: f32,
+        line_height_multiplier: f32,
+    ) -> Size {
         let font_size = base_font_size * scale_factor;
-        let line_height = (font_size * 1.2).ceil();
+        let line_height = (font_size * line_height_multiplier).ceil();
         let metrics = Metrics::new(font_size, line_height);
 
         // Create a buffer to measure a single character
@@ -86,9 +94,13 @@ impl WgpuRenderer {
 
     /// Precompute cell sizes for every integer font size (8..=32) so that
     /// set_font_size() can do a table lookup instead of font shaping.
-    pub(crate) fn precompute_cell_sizes(font_system: &mut FontSystem, scale_factor: f32) -> Vec<Size> {
+    pub(crate) fn precompute_cell_sizes(
+        font_system: &mut FontSystem,
+        scale_factor: f32,
+        line_height_multiplier: f32,
+    ) -> Vec<Size> {
         (FONT_SIZE_MIN..=FONT_SIZE_MAX)
-            .map(|s| Self::compute_cell_size(font_system, scale_factor, s as f32))
+            .map(|s| Self::compute_cell_size(font_system, scale_factor, s as f32, line_height_multiplier))
             .collect()
     }
 
@@ -98,7 +110,12 @@ impl WgpuRenderer {
         if let Some(&size) = self.cell_size_table.get(idx) {
             size
         } else {
-            Self::compute_cell_size(&mut self.font_system, self.scale_factor, base_font_size)
+            Self::compute_cell_size(
+                &mut self.font_system,
+                self.scale_factor,
+                base_font_size,
+                self.line_height_multiplier,
+            )
         }
     }
 
@@ -361,8 +378,47 @@ impl WgpuRenderer {
         self.cached_cell_size = self.lookup_cell_size(size);
         // No atlas reset! MSDF atlas is font-size-independent.
         self.invalidate_all_pane_caches();
-        self.atlas_reset_count += 1;
+        self.metrics_invalidated = true;
+        self.grid_needs_upload = true;
+        self.chrome_needs_upload = true;
+    }
+
+    /// Set line height as a multiple of the font size (default 1.2, clamped
+    /// to 1.0..=3.0). Recomputes the cell size table; the atlas is kept.
+    pub fn set_line_height_multiplier(&mut self, multiplier: f32) {
+        let multiplier = multiplier.clamp(1.0, 3.0);
+        if (multiplier - self.line_height_multiplier).abs() < 0.001 {
+            return;
+        }
+        self.line_height_multiplier = multiplier;
+        self.cell_size_table = Self::precompute_cell_sizes(&mut self.font_system, self.scale_factor, multiplier);
+        self.cached_cell_size = self.lookup_cell_size(self.base_font_size);
+        self.invalidate_all_pane_caches();
+        self.metrics_invalidated = true;
+        self.grid_needs_upload = true;
+        self.chrome_needs_upload = true;
+    }
+
+    pub fn line_height_multiplier(&self) -> f32 {
+        self.line_height_multiplier
+    }
+
+    /// Set where the text sits vertically in a cell: the fraction of the
+    /// leading (cell height minus ascender + descender) placed above the
+    /// glyphs. 0.0 = top, 0.5 = centered (default), 1.0 = bottom.
+    pub fn set_baseline_ratio(&mut self, ratio: f32) {
+        let ratio = ratio.clamp(0.0, 1.0);
+        if (ratio - self.baseline_ratio).abs() < 0.001 {
+            return;
+        }
+        self.baseline_ratio = ratio;
+        self.invalidate_all_pane_caches();
+        self.metrics_invalidated = true;
         self.grid_needs_upload = true;
         self.chrome_needs_upload = true;
     }
+
+    pub fn baseline_ratio(&self) -> f32 {
+        self.baseline_ratio
+    }
 }
@@ -1,6 +1,6 @@
 use std::collections::HashSet;
 
-use tide_core::{Color, Rect, Size, TextStyle, Vec2};
+use tide_core::{Color, PaneId, Rect, Size, TextStyle, Vec2};
 
 use crate::vertex::{GridBgInstance, GridGlyphInstance};
 use crate::WgpuRenderer;
@@ -31,13 +31,15 @@ impl WgpuRenderer {
     // ── Per-pane cache API ──────────────────────────────────
 
     /// Begin recording grid draw calls for a specific pane.
-    pub fn begin_pane_grid(&mut self, pane_id: u64) {
+    pub fn begin_pane_grid(&mut self, pane_id: PaneId) {
         self.active_pane_cache.clear();
         self.active_pane_id = Some(pane_id);
+        self.grid_bg_run = None;
     }
 
     /// Finish recording and store the pane's cache.
     pub fn end_pane_grid(&mut self) {
+        self.grid_bg_run = None;
         if let Some(id) = self.active_pane_id.take() {
             let mut cache = self.pane_grid_caches.remove(&id).unwrap_or_default();
             std::mem::swap(&mut cache, &mut self.active_pane_cache);
@@ -47,14 +49,14 @@ impl WgpuRenderer {
     }
 
     /// Remove a pane's cached instances (call when pane is closed).
-    pub fn remove_pane_cache(&mut self, pane_id: u64) {
+    pub fn remove_pane_cache(&mut self, pane_id: PaneId) {
         self.pane_grid_caches.remove(&pane_id);
         self.pane_grid_ranges.remove(&pane_id);
     }
 
     /// Keep only pane caches whose IDs are present in `pane_ids`.
-    pub fn retain_pane_caches(&mut self, pane_ids: &[u64]) {
-        let keep: HashSet<u64> = pane_ids.iter().copied().collect();
+    pub fn retain_pane_caches(&mut self, pane_ids: &[PaneId]) {
+        let keep: HashSet<PaneId> = pane_ids.iter().copied().collect();
         self.pane_grid_caches.retain(|id, _| keep.contains(id));
     }
 
@@ -69,7 +71,7 @@ impl WgpuRenderer {
 
     /// Assemble all per-pane caches into the global grid arrays, in the given order.
     /// Uses incremental update when only some panes changed and instance counts match.
-    pub fn assemble_grid(&mut self, pane_order: &[u64]) {
+    pub fn assemble_grid(&mut self, pane_order: &[PaneId]) {
         // Nothing dirty and order unchanged → skip entirely
         if self.grid_dirty_panes.is_empty() && pane_order == &self.last_pane_order[..] {
             return;
@@ -94,6 +96,7 @@ impl WgpuRenderer {
         }
 
         // Full assembly
+        self.grid_bg_run = None;
         self.grid_bg_instances.clear();
         self.grid_glyph_instances.clear();
         self.pane_grid_ranges.clear();
@@ -116,7 +119,7 @@ impl WgpuRenderer {
         }
 
         // Remove stale caches for panes no longer in the order
-        let keep: HashSet<u64> = pane_order.iter().copied().collect();
+        let keep: HashSet<PaneId> = pane_order.iter().copied().collect();
         self.pane_grid_caches.retain(|id, _| keep.contains(id));
 
         self.last_pane_order = pane_order.to_vec();
@@ -129,7 +132,7 @@ impl WgpuRenderer {
     fn assemble_grid_incremental(&mut self) {
         self.grid_partial_uploads.clear();
 
-        let dirty: Vec<u64> = self.grid_dirty_panes.drain().collect();
+        let dirty: Vec<PaneId> = self.grid_dirty_panes.drain().collect();
         for id in dirty {
             let (cache, range) = match (
                 self.pane_grid_caches.get(&id),
@@ -164,6 +167,9 @@ impl WgpuRenderer {
             color: [color.r, color.g, color.b, color.a],
         };
 
+        // A rect may overlap the next cell, so that cell can't join an
+        // earlier run (it would move beneath the rect)
+        self.grid_bg_run = None;
         if self.active_pane_id.is_some() {
             self.active_pane_cache.bg_instances.push(inst);
         } else {
@@ -178,14 +184,23 @@ impl WgpuRenderer {
         prev != self.atlas_reset_count
     }
 
+    /// Check if the cell metrics changed since last check (cached glyph
+    /// positions are stale, but the atlas and its UV coords are not).
+    pub fn metrics_were_invalidated(&mut self) -> bool {
+        std::mem::take(&mut self.metrics_invalidated)
+    }
+
     /// Signal that the grid content has changed and needs a full rebuild.
     pub fn invalidate_grid(&mut self) {
+        self.grid_bg_run = None;
         self.grid_bg_instances.clear();
         self.grid_glyph_instances.clear();
         self.grid_needs_upload = true;
     }
 
     /// Draw a cell into the cached grid layer (or active pane cache) as instances.
+    /// Backgrounds of horizontally adjacent cells drawn in sequence with the
+    /// same color merge into one instance spanning the run.
     pub fn draw_grid_cell(
         &mut self,
         character: char,
@@ -222,12 +237,36 @@ impl WgpuRenderer {
             (&mut self.grid_bg_instances, &mut self.grid_glyph_instances)
         };
 
-        // Background instance
+        // Background instance, or widen the run it continues. Only underlines
+        // can sit between the run and this cell, and they stay in their own
+        // cells, so extending the earlier instance doesn't change what overlaps.
         if let Some(bg_color) = style.background {
+            let color = [bg_color.r, bg_color.g, bg_color.b, bg_color.a * self.background_opacity];
+            let run = self.grid_bg_run.and_then(|i| bg.get_mut(i)).filter(|inst| {
+                inst.color == color
+                    && inst.position[1] == py
+                    && inst.size[1] == ch
+                    && (inst.position[0] + inst.size[0] - px).abs() < 0.5
+            });
+            match run {
+                Some(inst) => inst.size[0] = px + cw - inst.position[0],
+                None => {
+                    self.grid_bg_run = Some(bg.len());
+                    bg.push(GridBgInstance { position: [px, py], size: [cw, ch], color });
+                }
+            }
+        } else {
+            self.grid_bg_run = None;
+        }
+
+        // Underline: a one-logical-pixel bar along the cell bottom in the text color
+        if style.underline {
+            let thickness = scale.round().max(1.0);
+            let fg = style.foreground;
             bg.push(GridBgInstance {
-                position: [px, py],
-                size: [cw, ch],
-                color: [bg_color.r, bg_color.g, bg_color.b, bg_color.a],
+                position: [px, py + ch - thickness],
+                size: [cw, thickness],
+                color: [fg.r, fg.g, fg.b, fg.a],
             });
         }
 
@@ -247,4 +286,60 @@ impl WgpuRenderer {
             });
         }
     }
+
+    /// `draw_grid_cell` for a grapheme cluster: `character` plus the
+    /// zero-width chars stored with it. Combining marks are centered over the
+    /// base's cell(s) at their own height; fonts disagree on where a lone
+    /// mark's outline sits, and there is no shaping to anchor it. A ZWJ
+    /// ends the cluster here (glyphs are per-char, so a ZWJ emoji sequence
+    /// shows its first emoji), and invisible marks draw nothing.
+    #[allow(clippy::too_many_arguments)]
+    pub fn draw_grid_cluster(
+        &mut self,
+        character: char,
+        zerowidth: &[char],
+        row: usize,
+        col: usize,
+        style: TextStyle,
+        cell_size: Size,
+        offset: Vec2,
+    ) {
+        self.draw_grid_cell(character, row, col, style, cell_size, offset);
+        if zerowidth.is_empty() || character == '\0' {
+            return;
+        }
+
+        let scale = self.scale_factor;
+        let em_scale = self.em_scale();
+        let base_cols = unicode_width::UnicodeWidthChar::width(character).unwrap_or(1).max(1);
+        let px = (offset.x + col as f32 * cell_size.width) * scale;
+        let base_w = base_cols as f32 * cell_size.width * scale;
+        let py = (offset.y + row as f32 * cell_size.height) * scale;
+        let baseline_y = self.baseline_y(cell_size.height * scale);
+        let color = [style.foreground.r, style.foreground.g, style.foreground.b, style.foreground.a];
+
+        let marks = zerowidth
+            .iter()
+            .take_while(|&&c| c != '\u{200d}')
+            .filter(|&&c| unicode_width::UnicodeWidthChar::width(c) == Some(0));
+        for &mark in marks {
+            let region = self.ensure_glyph_cached(mark, style.bold, style.italic);
+            if region.is_empty() {
+                continue;
+            }
+            let gl = if self.active_pane_id.is_some() {
+                &mut self.active_pane_cache.glyph_instances
+            } else {
+                &mut self.grid_glyph_instances
+            };
+            let gw = region.em_width * em_scale;
+            gl.push(GridGlyphInstance {
+                position: [px + (base_w - gw) / 2.0, py + baseline_y - region.em_top * em_scale],
+                size: [gw, region.em_height * em_scale],
+                uv_min: region.uv_min,
+                uv_max: region.uv_max,
+                color,
+            });
+        }
+    }
 }
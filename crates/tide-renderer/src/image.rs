@@ -0,0 +1,136 @@
+// Caller-provided RGBA images (inline terminal images, icons), drawn as
+// textured quads in the overlay layer. Each image is its own texture, keyed by
+// an id the caller chooses, so it is uploaded once and drawn every frame.
+
+use std::ops::Range;
+
+use tide_core::Rect;
+
+use crate::vertex::GlyphVertex;
+use crate::WgpuRenderer;
+
+/// Caller-chosen id for a registered image.
+pub type ImageKey = u64;
+
+/// A registered image's texture and the bind group that samples it.
+pub(crate) struct ImageTexture {
+    pub(crate) bind_group: wgpu::BindGroup,
+    pub(crate) width: u32,
+    pub(crate) height: u32,
+}
+
+/// One batch of image quads sharing a texture, as a range of `image_indices`.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct ImageDraw {
+    pub(crate) key: ImageKey,
+    pub(crate) indices: Range<u32>,
+}
+
+impl WgpuRenderer {
+    /// Upload `rgba` (row-major RGBA8, `width * height * 4` bytes) as the image
+    /// for `key`, replacing any image already registered under it. Returns
+    /// false (and registers nothing) if the size doesn't match the data.
+    pub fn register_image(&mut self, key: ImageKey, rgba: &[u8], width: u32, height: u32) -> bool {
+        let max = self.device.limits().max_texture_dimension_2d;
+        if width == 0 || height == 0 || width > max || height > max {
+            return false;
+        }
+        if rgba.len() != width as usize * height as usize * 4 {
+            return false;
+        }
+        let size = wgpu::Extent3d { width, height, depth_or_array_layers: 1 };
+        let texture = self.device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("image"),
+            size,
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: wgpu::TextureFormat::Rgba8UnormSrgb,
+            usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
+            view_formats: &[],
+        });
+        self.queue.write_texture(
+            wgpu::ImageCopyTexture {
+                texture: &texture,
+                mip_level: 0,
+                origin: wgpu::Origin3d::ZERO,
+                aspect: wgpu::TextureAspect::All,
+            },
+            rgba,
+            wgpu::ImageDataLayout {
+                offset: 0,
+                bytes_per_row: Some(width * 4),
+                rows_per_image: Some(height),
+            },
+            size,
+        );
+        let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+        let bind_group = self.device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("image_bg"),
+            layout: &self.image_bind_group_layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: wgpu::BindingResource::TextureView(&view),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: wgpu::BindingResource::Sampler(&self.image_sampler),
+                },
+            ],
+        });
+        self.images.insert(key, ImageTexture { bind_group, width, height });
+        true
+    }
+
+    /// Drop the texture registered under `key`.
+    pub fn unregister_image(&mut self, key: ImageKey) {
+        self.images.remove(&key);
+    }
+
+    /// Whether an image is registered under `key`.
+    pub fn has_image(&self, key: ImageKey) -> bool {
+        self.images.contains_key(&key)
+    }
+
+    /// Pixel size of the image registered under `key`.
+    pub fn image_size(&self, key: ImageKey) -> Option<(u32, u32)> {
+        self.images.get(&key).map(|image| (image.width, image.height))
+    }
+
+    /// Draw the image registered under `key`, stretched over `rect` (logical
+    /// coords), this frame. Returns false if no image is registered.
+    pub fn draw_image_by_key(&mut self, key: ImageKey, rect: Rect) -> bool {
+        if !self.images.contains_key(&key) {
+            return false;
+        }
+        let s = self.scale_factor;
+        let (x, y, w, h) = (rect.x * s, rect.y * s, rect.width * s, rect.height * s);
+        let base = self.image_vertices.len() as u32;
+        // White vertex color: the shader multiplies, so the image is untinted
+        let c = [1.0; 4];
+        let corners = [([x, y], [0.0, 0.0]), ([x + w, y], [1.0, 0.0]), ([x + w, y + h], [1.0, 1.0]), ([x, y + h], [0.0, 1.0])];
+        for (position, uv) in corners {
+            self.image_vertices.push(GlyphVertex { position, uv, color: c });
+        }
+        let start = self.image_indices.len() as u32;
+        self.image_indices.extend_from_slice(&[base, base + 1, base + 2, base, base + 2, base + 3]);
+        let end = start + 6;
+        // Consecutive quads of the same image share one draw call
+        match self.image_draws.last_mut() {
+            Some(last) if last.key == key && last.indices.end == start => last.indices.end = end,
+            _ => self.image_draws.push(ImageDraw { key, indices: start..end }),
+        }
+        true
+    }
+
+    /// Draw `rgba` over `rect` this frame, uploading it under `key` only if
+    /// nothing of that size is registered yet. The key stands for the
+    /// content: use a new key (or `register_image`) when the pixels change.
+    pub fn draw_image(&mut self, key: ImageKey, rect: Rect, rgba: &[u8], width: u32, height: u32) -> bool {
+        if self.image_size(key) != Some((width, height)) && !self.register_image(key, rgba, width, height) {
+            return false;
+        }
+        self.draw_image_by_key(key, rect)
+    }
+}
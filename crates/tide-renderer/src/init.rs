@@ -1,26 +1,32 @@
 use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 
-use tide_core::{Color, Size};
+use tide_core::{ClipStack, Color, Size};
 
 use crate::atlas::GlyphAtlas;
+use crate::font::DEFAULT_LINE_HEIGHT;
 use crate::grid::PaneGridCache;
 use crate::msdf::MsdfFontStore;
-use crate::shaders::{CHROME_RECT_SHADER, GRID_BG_INSTANCED_SHADER, RECT_SHADER};
+use crate::shaders::{CHROME_RECT_SHADER, GRID_BG_INSTANCED_SHADER, IMAGE_SHADER, RECT_SHADER};
 use crate::vertex::{ChromeRectVertex, GlyphVertex, GridBgInstance, GridGlyphInstance, RectVertex};
-use crate::WgpuRenderer;
+use crate::{resolve_sample_count, WgpuRenderer};
 
 impl WgpuRenderer {
     pub fn new(
         device: Arc<wgpu::Device>,
         queue: Arc<wgpu::Queue>,
         format: wgpu::TextureFormat,
+        format_features: wgpu::TextureFormatFeatures,
         scale_factor: f32,
+        sample_count: u32,
     ) -> Self {
+        let sample_count = resolve_sample_count(sample_count, format_features);
+        let multisample = wgpu::MultisampleState { count: sample_count, ..Default::default() };
+
         // --- Uniform buffer ---
         let uniform_buffer = device.create_buffer(&wgpu::BufferDescriptor {
             label: Some("uniform_buffer"),
-            size: 16, // vec2<f32> padded to 16 bytes
+            size: 16, // screen_size: vec2<f32>, text_gamma: f32, padding
             usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
             mapped_at_creation: false,
         });
@@ -31,7 +37,8 @@ impl WgpuRenderer {
                 label: Some("uniform_bgl"),
                 entries: &[wgpu::BindGroupLayoutEntry {
                     binding: 0,
-                    visibility: wgpu::ShaderStages::VERTEX,
+                    // The glyph fragment shaders read text_gamma
+                    visibility: wgpu::ShaderStages::VERTEX | wgpu::ShaderStages::FRAGMENT,
                     ty: wgpu::BindingType::Buffer {
                         ty: wgpu::BufferBindingType::Uniform,
                         has_dynamic_offset: false,
@@ -92,7 +99,7 @@ impl WgpuRenderer {
                 conservative: false,
             },
             depth_stencil: None,
-            multisample: wgpu::MultisampleState::default(),
+            multisample,
             multiview: None,
             cache: None,
         });
@@ -140,7 +147,7 @@ impl WgpuRenderer {
                     conservative: false,
                 },
                 depth_stencil: None,
-                multisample: wgpu::MultisampleState::default(),
+                multisample,
                 multiview: None,
                 cache: None,
             });
@@ -238,11 +245,60 @@ impl WgpuRenderer {
                 conservative: false,
             },
             depth_stencil: None,
-            multisample: wgpu::MultisampleState::default(),
+            multisample,
             multiview: None,
             cache: None,
         });
 
+        // --- Image pipeline (caller RGBA textures; same bind group shape as the atlas) ---
+        let image_shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("image_shader"),
+            source: wgpu::ShaderSource::Wgsl(IMAGE_SHADER.into()),
+        });
+
+        let image_pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("image_pipeline"),
+            layout: Some(&glyph_pipeline_layout),
+            vertex: wgpu::VertexState {
+                module: &image_shader,
+                entry_point: Some("vs_main"),
+                buffers: &[GlyphVertex::LAYOUT],
+                compilation_options: Default::default(),
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &image_shader,
+                entry_point: Some("fs_main"),
+                targets: &[Some(wgpu::ColorTargetState {
+                    format,
+                    blend: Some(wgpu::BlendState::ALPHA_BLENDING),
+                    write_mask: wgpu::ColorWrites::ALL,
+                })],
+                compilation_options: Default::default(),
+            }),
+            primitive: wgpu::PrimitiveState {
+                topology: wgpu::PrimitiveTopology::TriangleList,
+                strip_index_format: None,
+                front_face: wgpu::FrontFace::Ccw,
+                cull_mode: None,
+                polygon_mode: wgpu::PolygonMode::Fill,
+                unclipped_depth: false,
+                conservative: false,
+            },
+            depth_stencil: None,
+            multisample,
+            multiview: None,
+            cache: None,
+        });
+
+        let image_sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            label: Some("image_sampler"),
+            address_mode_u: wgpu::AddressMode::ClampToEdge,
+            address_mode_v: wgpu::AddressMode::ClampToEdge,
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            ..Default::default()
+        });
+
         // --- Instanced grid bg pipeline ---
         let grid_bg_shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
             label: Some("grid_bg_instanced_shader"),
@@ -285,7 +341,7 @@ impl WgpuRenderer {
                 conservative: false,
             },
             depth_stencil: None,
-            multisample: wgpu::MultisampleState::default(),
+            multisample,
             multiview: None,
             cache: None,
         });
@@ -333,7 +389,7 @@ impl WgpuRenderer {
                 conservative: false,
             },
             depth_stencil: None,
-            multisample: wgpu::MultisampleState::default(),
+            multisample,
             multiview: None,
             cache: None,
         });
@@ -342,7 +398,7 @@ impl WgpuRenderer {
         let mut font_system = cosmic_text::FontSystem::new();
 
         // Precompute cell sizes for all font sizes (8..=32) and look up initial
-        let cell_size_table = Self::precompute_cell_sizes(&mut font_system, scale_factor);
+        let cell_size_table = Self::precompute_cell_sizes(&mut font_system, scale_factor, DEFAULT_LINE_HEIGHT);
         let cached_cell_size = cell_size_table[(14 - 8) as usize];
 
         // --- MSDF font store ---
@@ -406,6 +462,7 @@ impl WgpuRenderer {
             glyph_pipeline,
             grid_bg_pipeline,
             grid_glyph_pipeline,
+            image_pipeline,
             uniform_buffer,
             uniform_bind_group,
             atlas,
@@ -416,6 +473,7 @@ impl WgpuRenderer {
             pane_grid_caches: HashMap::new(),
             active_pane_cache: PaneGridCache::default(),
             active_pane_id: None,
+            grid_bg_run: None,
             // Grid layer (instanced)
             grid_bg_instances: Vec::with_capacity(4096),
             grid_glyph_instances: Vec::with_capacity(8192),
@@ -470,15 +528,35 @@ impl WgpuRenderer {
             top_rounded_rect_ib_capacity: initial_buf_size as usize,
             top_glyph_vb_capacity: initial_buf_size as usize,
             top_glyph_ib_capacity: initial_buf_size as usize,
+            // Images (overlay layer, one texture per registered key)
+            image_bind_group_layout: atlas_bind_group_layout,
+            image_sampler,
+            images: HashMap::new(),
+            image_vertices: Vec::new(),
+            image_indices: Vec::new(),
+            image_draws: Vec::new(),
+            image_vb: create_buf("image_vb", vb_usage),
+            image_ib: create_buf("image_ib", ib_usage),
+            image_vb_capacity: initial_buf_size as usize,
+            image_ib_capacity: initial_buf_size as usize,
             screen_size: Size::new(800.0, 600.0),
+            clip_stack: ClipStack::default(),
             scale_factor,
             base_font_size: 14.0,
             cached_cell_size,
             cell_size_table,
+            line_height_multiplier: DEFAULT_LINE_HEIGHT,
+            baseline_ratio: 0.5,
             mono_em_ascender,
             mono_em_descender,
             surface_format: format,
+            sample_count,
+            msaa_target: None,
             clear_color: Color::new(0.02, 0.02, 0.02, 1.0),
+            dim_overlay_color: Color::BLACK,
+            background_opacity: 1.0,
+            text_gamma: 1.0,
+            overlay_text_outline: None,
             // Incremental grid assembly
             pane_grid_ranges: HashMap::new(),
             last_pane_order: Vec::new(),
@@ -486,7 +564,8 @@ impl WgpuRenderer {
             grid_partial_uploads: Vec::new(),
             atlas_reset_count: 0,
             last_atlas_reset_count: 0,
-            last_uniform_screen: [0.0, 0.0],
+            metrics_invalidated: false,
+            last_uniforms: [0.0; 4],
             device: Arc::clone(&device),
             queue: Arc::clone(&queue),
         }
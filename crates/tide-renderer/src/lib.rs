@@ -5,23 +5,27 @@ mod atlas;
 mod chrome;
 mod font;
 mod grid;
+mod image;
 mod init;
 mod msdf;
 mod overlay;
 mod shaders;
+mod tests;
 mod vertex;
 
 use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 
 use cosmic_text::FontSystem;
-use tide_core::{Color, Rect, Renderer, Size, TextStyle, Vec2};
+use tide_core::{ClipStack, Color, PaneId, Rect, Renderer, Size, TextStyle, Theme, Vec2};
 
 use atlas::GlyphAtlas;
 use grid::PaneGridCache;
 use msdf::MsdfFontStore;
 use vertex::{ChromeRectVertex, GlyphVertex, GridBgInstance, GridGlyphInstance, RectVertex};
 
+pub use image::ImageKey;
+
 // ──────────────────────────────────────────────
 // WgpuRenderer
 // ──────────────────────────────────────────────
@@ -31,8 +35,9 @@ pub struct WgpuRenderer {
     pub(crate) rect_pipeline: wgpu::RenderPipeline,
     pub(crate) chrome_rounded_pipeline: wgpu::RenderPipeline,
     pub(crate) glyph_pipeline: wgpu::RenderPipeline,
+    pub(crate) image_pipeline: wgpu::RenderPipeline,
 
-    // Uniform buffer (screen size)
+    // Uniform buffer (screen size, text gamma)
     pub(crate) uniform_buffer: wgpu::Buffer,
     pub(crate) uniform_bind_group: wgpu::BindGroup,
 
@@ -45,9 +50,14 @@ pub struct WgpuRenderer {
     pub(crate) msdf_font_store: MsdfFontStore,
 
     // Per-pane grid caching
-    pub(crate) pane_grid_caches: HashMap<u64, PaneGridCache>,
+    pub(crate) pane_grid_caches: HashMap<PaneId, PaneGridCache>,
     pub(crate) active_pane_cache: PaneGridCache,
-    pub(crate) active_pane_id: Option<u64>,
+    pub(crate) active_pane_id: Option<PaneId>,
+    /// Index of the last cell background `draw_grid_cell` pushed to the
+    /// current target; the next cell to its right with the same color widens
+    /// it instead of adding an instance. Reset whenever anything else could
+    /// be drawn in between.
+    pub(crate) grid_bg_run: Option<usize>,
 
     // Instanced grid pipelines (GPU generates quad corners from vertex_index)
     pub(crate) grid_bg_pipeline: wgpu::RenderPipeline,
@@ -115,8 +125,22 @@ pub struct WgpuRenderer {
     pub(crate) top_glyph_vb_capacity: usize,
     pub(crate) top_glyph_ib_capacity: usize,
 
+    // Images — registered textures, plus quads rebuilt every frame (overlay layer)
+    pub(crate) image_bind_group_layout: wgpu::BindGroupLayout,
+    pub(crate) image_sampler: wgpu::Sampler,
+    pub(crate) images: HashMap<ImageKey, image::ImageTexture>,
+    pub(crate) image_vertices: Vec<GlyphVertex>,
+    pub(crate) image_indices: Vec<u32>,
+    pub(crate) image_draws: Vec<image::ImageDraw>,
+    pub(crate) image_vb: wgpu::Buffer,
+    pub(crate) image_ib: wgpu::Buffer,
+    pub(crate) image_vb_capacity: usize,
+    pub(crate) image_ib_capacity: usize,
+
     // Current frame state
     pub(crate) screen_size: Size,
+    /// Regions pushed with `push_clip`; emptied every `begin_frame`.
+    pub(crate) clip_stack: ClipStack,
     pub(crate) scale_factor: f32,
     pub(crate) base_font_size: f32,
 
@@ -124,36 +148,133 @@ pub struct WgpuRenderer {
     pub(crate) cached_cell_size: Size,
     // Precomputed cell sizes for font sizes 8..=32 (avoids shaping on Cmd+/-)
     pub(crate) cell_size_table: Vec<Size>,
+    // Line height = font size × this (cell height before rounding)
+    pub(crate) line_height_multiplier: f32,
+    // Fraction of a cell's leading placed above the text (0.5 = centered)
+    pub(crate) baseline_ratio: f32,
 
     // Font metrics for correct baseline positioning (em-relative, both positive)
     pub(crate) mono_em_ascender: f32,
     pub(crate) mono_em_descender: f32,
 
     // Surface format (for potential re-creation)
-    #[allow(dead_code)]
     pub(crate) surface_format: wgpu::TextureFormat,
 
+    // MSAA: pipelines are built for `sample_count`; above 1 the frame renders
+    // into `msaa_target` (sized by `set_target_size`) and resolves to the surface
+    pub(crate) sample_count: u32,
+    pub(crate) msaa_target: Option<MsaaTarget>,
+
     // Clear color (gap / background)
     pub clear_color: Color,
+    // Window background opacity applied to the clear color (1.0 = opaque)
+    pub(crate) background_opacity: f32,
+    // Exponent applied to glyph coverage in the glyph shaders (1.0 = unchanged)
+    pub(crate) text_gamma: f32,
+    // Halo drawn behind overlay text (`set_overlay_text_outline`); `None` = off
+    pub(crate) overlay_text_outline: Option<Color>,
+    // Base color of `draw_dim_overlay`: black on dark themes, white on light
+    pub(crate) dim_overlay_color: Color,
 
     // Incremental grid assembly: per-pane ranges, dirty tracking, partial upload
-    pub(crate) pane_grid_ranges: HashMap<u64, grid::PaneGridRange>,
-    pub(crate) last_pane_order: Vec<u64>,
-    pub(crate) grid_dirty_panes: HashSet<u64>,
+    pub(crate) pane_grid_ranges: HashMap<PaneId, grid::PaneGridRange>,
+    pub(crate) last_pane_order: Vec<PaneId>,
+    pub(crate) grid_dirty_panes: HashSet<PaneId>,
     pub(crate) grid_partial_uploads: Vec<grid::PaneGridRange>,
 
     // Atlas overflow tracking
     pub(crate) atlas_reset_count: u64,
     pub(crate) last_atlas_reset_count: u64,
+    // Cell metrics changed (font size, line height, baseline) since the last
+    // `metrics_were_invalidated` check; the atlas itself is still valid
+    pub(crate) metrics_invalidated: bool,
 
-    // Cached uniform screen size to avoid redundant writes
-    pub(crate) last_uniform_screen: [f32; 2],
+    // Last uniform data written ([width, height, text_gamma, pad]) to avoid redundant writes
+    pub(crate) last_uniforms: [f32; 4],
 
     // Store device and queue for uploading glyphs during draw calls
     pub(crate) device: Arc<wgpu::Device>,
     pub(crate) queue: Arc<wgpu::Queue>,
 }
 
+/// Offsets (logical pixels) of the halo copies drawn behind outlined overlay text.
+const OUTLINE_OFFSETS: [(f32, f32); 4] = [(-1.0, 0.0), (1.0, 0.0), (0.0, -1.0), (0.0, 1.0)];
+
+/// Accepted `set_text_gamma` values.
+pub const TEXT_GAMMA_RANGE: std::ops::RangeInclusive<f32> = 0.25..=4.0;
+
+/// `gamma` clamped to `TEXT_GAMMA_RANGE`; non-finite values become 1.0.
+pub(crate) fn clamp_text_gamma(gamma: f32) -> f32 {
+    if gamma.is_finite() {
+        gamma.clamp(*TEXT_GAMMA_RANGE.start(), *TEXT_GAMMA_RANGE.end())
+    } else {
+        1.0
+    }
+}
+
+/// Multisampled color texture the frame is drawn into before resolving.
+pub(crate) struct MsaaTarget {
+    pub(crate) view: wgpu::TextureView,
+    pub(crate) size: (u32, u32),
+}
+
+/// `color` with its alpha scaled by `opacity`, premultiplied for a
+/// `PreMultiplied` surface (macOS composites layers premultiplied).
+pub(crate) fn premultiplied_clear(color: Color, opacity: f32) -> wgpu::Color {
+    let a = (color.a * opacity.clamp(0.0, 1.0)) as f64;
+    wgpu::Color {
+        r: color.r as f64 * a,
+        g: color.g as f64 * a,
+        b: color.b as f64 * a,
+        a,
+    }
+}
+
+/// Pick a surface alpha mode from `supported` (`SurfaceCapabilities::alpha_modes`).
+/// Opaque backgrounds keep the first (preferred) mode; translucent ones need
+/// `PreMultiplied`, falling back to `PostMultiplied`, then the first mode
+/// (transparency unavailable).
+pub fn preferred_alpha_mode(
+    supported: &[wgpu::CompositeAlphaMode],
+    background_opacity: f32,
+) -> wgpu::CompositeAlphaMode {
+    use wgpu::CompositeAlphaMode as M;
+    let first = supported.first().copied().unwrap_or(M::Auto);
+    if background_opacity >= 1.0 {
+        return first;
+    }
+    [M::PreMultiplied, M::PostMultiplied]
+        .into_iter()
+        .find(|m| supported.contains(m))
+        .unwrap_or(first)
+}
+
+/// Features of `format` the device may rely on: the adapter's own when the
+/// device was created with `TEXTURE_ADAPTER_SPECIFIC_FORMAT_FEATURES` (needed
+/// for 2x MSAA on most GPUs), otherwise only the ones wgpu guarantees.
+pub fn format_features(
+    adapter: &wgpu::Adapter,
+    device: &wgpu::Device,
+    format: wgpu::TextureFormat,
+) -> wgpu::TextureFormatFeatures {
+    if device.features().contains(wgpu::Features::TEXTURE_ADAPTER_SPECIFIC_FORMAT_FEATURES) {
+        adapter.get_texture_format_features(format)
+    } else {
+        format.guaranteed_format_features(device.features())
+    }
+}
+
+/// MSAA sample count to use for a request of `requested` (1, 2 or 4) given the
+/// target format's features; anything unsupported falls back to 1.
+pub(crate) fn resolve_sample_count(requested: u32, features: wgpu::TextureFormatFeatures) -> u32 {
+    let renderable = features.allowed_usages.contains(wgpu::TextureUsages::RENDER_ATTACHMENT);
+    if matches!(requested, 2 | 4) && renderable && features.flags.sample_count_supported(requested) {
+        requested
+    } else {
+        1
+    }
+}
+
 // Helper: convert em-relative AtlasRegion metrics to physical pixel values
 impl WgpuRenderer {
     /// Scale factor for converting em-relative glyph metrics to physical pixels.
@@ -162,15 +283,16 @@ impl WgpuRenderer {
     }
 
     /// Compute the baseline Y offset (in physical pixels) within a cell of
-    /// the given physical-pixel height. Centers the font vertically using
-    /// the actual ascender/descender metrics from the monospace font.
+    /// the given physical-pixel height. Places the font vertically using
+    /// the actual ascender/descender metrics from the monospace font, with
+    /// `baseline_ratio` of the leading above it (0.5 = centered).
     fn baseline_y(&self, cell_height_px: f32) -> f32 {
         let font_size_px = self.base_font_size * self.scale_factor;
         let ascender_px = self.mono_em_ascender * font_size_px;
         let descender_px = self.mono_em_descender * font_size_px;
         let font_height_px = ascender_px + descender_px;
         let leading = cell_height_px - font_height_px;
-        leading * 0.5 + ascender_px
+        leading * self.baseline_ratio + ascender_px
     }
 }
 
@@ -181,6 +303,7 @@ impl WgpuRenderer {
 impl Renderer for WgpuRenderer {
     fn begin_frame(&mut self, size: Size) {
         self.screen_size = size;
+        self.clip_stack.clear();
         self.rect_vertices.clear();
         self.rect_indices.clear();
         self.glyph_vertices.clear();
@@ -191,9 +314,16 @@ impl Renderer for WgpuRenderer {
         self.top_rounded_rect_indices.clear();
         self.top_glyph_vertices.clear();
         self.top_glyph_indices.clear();
+        self.image_vertices.clear();
+        self.image_indices.clear();
+        self.image_draws.clear();
     }
 
     fn draw_rect(&mut self, rect: Rect, color: Color) {
+        let rect = self.clip_stack.apply(rect);
+        if rect.is_empty() {
+            return;
+        }
         let x = rect.x * self.scale_factor;
         let y = rect.y * self.scale_factor;
         let w = rect.width * self.scale_factor;
@@ -201,13 +331,25 @@ impl Renderer for WgpuRenderer {
         self.push_rect_quad(x, y, w, h, color);
     }
 
+    fn draw_triangle(&mut self, a: Vec2, b: Vec2, c: Vec2, color: Color) {
+        let mut points = vec![a, b, c];
+        if let Some(clip) = self.clip_stack.top() {
+            points = overlay::clip_convex_polygon(&points, clip);
+        }
+        let s = self.scale_factor;
+        for p in &mut points {
+            *p = Vec2::new(p.x * s, p.y * s);
+        }
+        self.push_rect_polygon(&points, color);
+    }
+
     fn draw_text(&mut self, text: &str, position: Vec2, style: TextStyle, clip: Rect) {
+        let clip = self.clip_stack.apply(clip);
         let scale = self.scale_factor;
         let em_scale = self.em_scale();
-        let cell_w = self.cached_cell_size.width * scale;
         let baseline_y = self.baseline_y(self.cached_cell_size.height * scale);
 
-        let mut cursor_x = position.x * scale;
+        let cursor_x = position.x * scale;
         let start_y = position.y * scale;
 
         // Clip bounds in physical pixels
@@ -216,18 +358,14 @@ impl Renderer for WgpuRenderer {
         let clip_right = (clip.x + clip.width) * scale;
         let clip_bottom = (clip.y + clip.height) * scale;
 
-        for ch in text.chars() {
-            if ch == ' ' || ch == '\t' {
-                let advance = if ch == '\t' { cell_w * 4.0 } else { cell_w };
-                cursor_x += advance;
-                continue;
-            }
+        for glyph in self.layout_text(text, style) {
+            let Some(region) = glyph.region else { continue };
+            let qx = cursor_x + glyph.x * scale;
 
             // Draw background if present
             if let Some(bg) = style.background {
-                let qx = cursor_x;
                 let qy = start_y;
-                let qw = cell_w;
+                let qw = glyph.advance * scale;
                 let qh = self.cached_cell_size.height * scale;
                 if qx + qw > clip_left && qx < clip_right && qy + qh > clip_top && qy < clip_bottom
                 {
@@ -235,10 +373,8 @@ impl Renderer for WgpuRenderer {
                 }
             }
 
-            let region = self.ensure_glyph_cached(ch, style.bold, style.italic);
-
             if !region.is_empty() {
-                let gx = cursor_x + region.em_left * em_scale;
+                let gx = qx + region.em_left * em_scale;
                 let gy = start_y + baseline_y - region.em_top * em_scale;
                 let gw = region.em_width * em_scale;
                 let gh = region.em_height * em_scale;
@@ -246,6 +382,19 @@ impl Renderer for WgpuRenderer {
                 // Simple clip check
                 if gx + gw > clip_left && gx < clip_right && gy + gh > clip_top && gy < clip_bottom
                 {
+                    if let Some(halo) = self.overlay_text_outline {
+                        for (dx, dy) in OUTLINE_OFFSETS {
+                            self.push_glyph_quad(
+                                gx + dx * scale,
+                                gy + dy * scale,
+                                gw,
+                                gh,
+                                region.uv_min,
+                                region.uv_max,
+                                halo,
+                            );
+                        }
+                    }
                     self.push_glyph_quad(
                         gx,
                         gy,
@@ -257,8 +406,6 @@ impl Renderer for WgpuRenderer {
                     );
                 }
             }
-
-            cursor_x += cell_w;
         }
     }
 
@@ -278,8 +425,17 @@ impl Renderer for WgpuRenderer {
         let cw = cell_size.width * scale;
         let ch = cell_size.height * scale;
 
+        // Cull cells outside the clip stack (partly visible cells draw whole)
+        if let Some(clip) = self.clip_stack.top() {
+            let cell = Rect::new(px / scale, py / scale, cell_size.width, cell_size.height);
+            if !cell.intersects(&clip) {
+                return;
+            }
+        }
+
         // Draw background
         if let Some(bg) = style.background {
+            let bg = Color { a: bg.a * self.background_opacity, ..bg };
             self.push_rect_quad(px, py, cw, ch, bg);
         }
 
@@ -315,6 +471,18 @@ impl Renderer for WgpuRenderer {
     fn cell_size(&self) -> Size {
         self.cached_cell_size
     }
+
+    fn push_clip(&mut self, clip: Rect) {
+        self.clip_stack.push(clip);
+    }
+
+    fn pop_clip(&mut self) {
+        self.clip_stack.pop();
+    }
+
+    fn current_clip(&self) -> Option<Rect> {
+        self.clip_stack.top()
+    }
 }
 
 // ──────────────────────────────────────────────
@@ -328,13 +496,105 @@ impl WgpuRenderer {
     }
 
 
-    /// Update the scale factor used for logical-to-physical coordinate conversion.
+    /// Apply a theme: the clear color (gaps between panes) follows its border
+    /// color, and `draw_dim_overlay` darkens on dark themes, lightens on light.
+    pub fn set_theme(&mut self, theme: &Theme) {
+        self.clear_color = theme.border;
+        self.dim_overlay_color = if theme.dark { Color::BLACK } else { Color::WHITE };
+    }
+
+    /// Set the window background opacity (clamped to 0.0–1.0). The clear
+    /// color is submitted with this alpha, premultiplied, and cell
+    /// backgrounds (`draw_grid_cell`, `draw_cell`) have their alpha scaled by
+    /// it, so gaps and cells show the desktop unless the app paints an opaque
+    /// fill behind them. Grid instances already cached keep the old alpha
+    /// until redrawn. Needs a non-opaque surface (`preferred_alpha_mode`) and
+    /// a non-opaque native window.
+    pub fn set_background_opacity(&mut self, opacity: f32) {
+        self.background_opacity = opacity.clamp(0.0, 1.0);
+    }
+
+    pub fn background_opacity(&self) -> f32 {
+        self.background_opacity
+    }
+
+    /// Set the gamma applied to glyph coverage (clamped to
+    /// `TEXT_GAMMA_RANGE`; non-finite values reset to 1.0). The glyph shaders
+    /// output `coverage^(1 / gamma)`, so values above 1.0 fatten anti-aliased
+    /// edges (light-on-dark text looks heavier, like macOS font smoothing) and
+    /// values below 1.0 thin them. Takes effect on the next `render_frame`.
+    pub fn set_text_gamma(&mut self, gamma: f32) {
+        self.text_gamma = clamp_text_gamma(gamma);
+    }
+
+    pub fn text_gamma(&self) -> f32 {
+        self.text_gamma
+    }
+
+    /// Draw overlay text (`draw_text`: preedit, search counters, drop-zone
+    /// labels) with a 1px halo in `color`, so it stays legible over any
+    /// terminal content. Each glyph is drawn four times offset by ±1 logical
+    /// pixel before the glyph itself. Grid, chrome and top-layer text are
+    /// unaffected.
+    pub fn set_overlay_text_outline(&mut self, enabled: bool, color: Color) {
+        self.overlay_text_outline = enabled.then_some(color);
+    }
+
+    pub fn overlay_text_outline(&self) -> Option<Color> {
+        self.overlay_text_outline
+    }
+
+    /// The clear color as submitted to the GPU (premultiplied by the opacity).
+    pub fn clear_color_wgpu(&self) -> wgpu::Color {
+        premultiplied_clear(self.clear_color, self.background_opacity)
+    }
+
+    /// Update the scale factor used for logical-to-physical coordinate conversion
+    /// (e.g. the window moved to a display with a different DPI). Cell metrics
+    /// are recomputed and the glyph atlas is cleared so glyphs are rasterized
+    /// again at the new scale; `atlas_was_reset()` reports the change.
     pub fn set_scale_factor(&mut self, scale: f32) {
-        if (scale - self.scale_factor).abs() > 0.001 {
-            self.scale_factor = scale;
-            self.cell_size_table = Self::precompute_cell_sizes(&mut self.font_system, scale);
-            self.cached_cell_size = self.lookup_cell_size(self.base_font_size);
+        if (scale - self.scale_factor).abs() <= 0.001 {
+            return;
         }
+        self.scale_factor = scale;
+        self.cell_size_table =
+            Self::precompute_cell_sizes(&mut self.font_system, scale, self.line_height_multiplier);
+        self.cached_cell_size = self.lookup_cell_size(self.base_font_size);
+        self.atlas.reset();
+        self.warmup_ascii();
+        self.warmup_common_unicode();
+        self.invalidate_all_pane_caches();
+        self.atlas_reset_count += 1;
+        self.grid_needs_upload = true;
+        self.chrome_needs_upload = true;
+    }
+
+    /// MSAA samples per pixel the pipelines were built with (1 = no MSAA).
+    pub fn sample_count(&self) -> u32 {
+        self.sample_count
+    }
+
+    /// Size in physical pixels of the texture `render_frame` draws to. With
+    /// MSAA the multisampled target is reallocated to match; without it this
+    /// is a no-op.
+    pub fn set_target_size(&mut self, width: u32, height: u32) {
+        let size = (width.max(1), height.max(1));
+        if self.sample_count <= 1 || self.msaa_target.as_ref().is_some_and(|t| t.size == size) {
+            return;
+        }
+        let texture = self.device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("msaa_target"),
+            size: wgpu::Extent3d { width: size.0, height: size.1, depth_or_array_layers: 1 },
+            mip_level_count: 1,
+            sample_count: self.sample_count,
+            dimension: wgpu::TextureDimension::D2,
+            format: self.surface_format,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+            view_formats: &[],
+        });
+        let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+        self.msaa_target = Some(MsaaTarget { view, size });
     }
 
     /// Draw a rounded rect in the top layer (SDF-based AA, rendered after all text).
@@ -430,6 +690,18 @@ impl WgpuRenderer {
         self.top_rect_indices.push(base + 3);
     }
 
+    /// Dim a region (an unfocused pane) with a translucent top-layer rect at
+    /// `amount` alpha (clamped to 0.0–1.0; 0 draws nothing). The top layer
+    /// covers the grid's text as well as its backgrounds, so nothing has to
+    /// be recolored. Call it before drawing the cursor, which must stay on top.
+    pub fn draw_dim_overlay(&mut self, rect: Rect, amount: f32) {
+        if amount.is_nan() || amount <= 0.0 {
+            return;
+        }
+        let base = self.dim_overlay_color;
+        self.draw_top_rect(rect, Color::new(base.r, base.g, base.b, amount.min(1.0)));
+    }
+
     /// Draw a single glyph in the top layer (rendered after all other layers).
     /// Used for rendering inverse cursor characters on top of the cursor rect.
     pub fn draw_top_glyph(&mut self, ch: char, position: Vec2, color: Color, bold: bool, italic: bool) {
@@ -469,12 +741,12 @@ impl WgpuRenderer {
 
     /// Draw text in the top layer (rendered after all text).
     pub fn draw_top_text(&mut self, text: &str, position: Vec2, style: TextStyle, clip: Rect) {
+        let clip = self.clip_stack.apply(clip);
         let scale = self.scale_factor;
         let em_scale = self.em_scale();
-        let cell_w = self.cached_cell_size.width * scale;
         let baseline_y = self.baseline_y(self.cached_cell_size.height * scale);
 
-        let mut cursor_x = position.x * scale;
+        let cursor_x = position.x * scale;
         let start_y = position.y * scale;
 
         let clip_left = clip.x * scale;
@@ -482,17 +754,13 @@ impl WgpuRenderer {
         let clip_right = (clip.x + clip.width) * scale;
         let clip_bottom = (clip.y + clip.height) * scale;
 
-        for ch in text.chars() {
-            if ch == ' ' || ch == '\t' {
-                let advance = if ch == '\t' { cell_w * 4.0 } else { cell_w };
-                cursor_x += advance;
-                continue;
-            }
+        for glyph in self.layout_text(text, style) {
+            let Some(region) = glyph.region else { continue };
+            let qx = cursor_x + glyph.x * scale;
 
             if let Some(bg) = style.background {
-                let qx = cursor_x;
                 let qy = start_y;
-                let qw = cell_w;
+                let qw = glyph.advance * scale;
                 let qh = self.cached_cell_size.height * scale;
                 if qx + qw > clip_left && qx < clip_right && qy + qh > clip_top && qy < clip_bottom {
                     // Push into top rect arrays
@@ -511,10 +779,8 @@ impl WgpuRenderer {
                 }
             }
 
-            let region = self.ensure_glyph_cached(ch, style.bold, style.italic);
-
             if !region.is_empty() {
-                let gx = cursor_x + region.em_left * em_scale;
+                let gx = qx + region.em_left * em_scale;
                 let gy = start_y + baseline_y - region.em_top * em_scale;
                 let gw = region.em_width * em_scale;
                 let gh = region.em_height * em_scale;
@@ -534,8 +800,6 @@ impl WgpuRenderer {
                     self.top_glyph_indices.push(base + 3);
                 }
             }
-
-            cursor_x += cell_w;
         }
     }
 }
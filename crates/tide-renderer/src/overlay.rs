@@ -1,9 +1,39 @@
-use tide_core::Color;
+use tide_core::{Color, Rect, Renderer, Size, Vec2};
 
 use crate::vertex::{GlyphVertex, RectVertex};
 use crate::WgpuRenderer;
 
 impl WgpuRenderer {
+    /// Tint selected cells in the overlay layer. `rows` are
+    /// `(row, (start_col, end_col))` spans, end exclusive, in a grid whose
+    /// cell (0, 0) is at `offset`; spans on one row that touch or overlap are
+    /// drawn as a single rect. Goes through `draw_rect`, so push the pane's
+    /// rect with `push_clip` to keep the tint inside it.
+    pub fn draw_selection(&mut self, rows: &[(usize, (usize, usize))], cell_size: Size, offset: Vec2, color: Color) {
+        let mut spans: Vec<(usize, usize, usize)> = rows
+            .iter()
+            .filter(|(_, (start, end))| start < end)
+            .map(|&(row, (start, end))| (row, start, end))
+            .collect();
+        spans.sort_unstable();
+        let mut merged: Vec<(usize, usize, usize)> = Vec::with_capacity(spans.len());
+        for (row, start, end) in spans {
+            match merged.last_mut() {
+                Some(last) if last.0 == row && start <= last.2 => last.2 = last.2.max(end),
+                _ => merged.push((row, start, end)),
+            }
+        }
+        for (row, start, end) in merged {
+            let rect = Rect::new(
+                offset.x + start as f32 * cell_size.width,
+                offset.y + row as f32 * cell_size.height,
+                (end - start) as f32 * cell_size.width,
+                cell_size.height,
+            );
+            self.draw_rect(rect, color);
+        }
+    }
+
     /// Push a colored quad (two triangles) into the rect batch.
     pub(crate) fn push_rect_quad(&mut self, x: f32, y: f32, w: f32, h: f32, color: Color) {
         let base = self.rect_vertices.len() as u32;
@@ -34,6 +64,28 @@ impl WgpuRenderer {
         self.rect_indices.push(base + 3);
     }
 
+    /// Push a colored convex polygon (physical px), fanned from its first
+    /// point, into the rect batch: one vertex per point, three indices per
+    /// triangle.
+    pub(crate) fn push_rect_polygon(&mut self, points: &[Vec2], color: Color) {
+        if points.len() < 3 {
+            return;
+        }
+        let base = self.rect_vertices.len() as u32;
+        let c = [color.r, color.g, color.b, color.a];
+        for p in points {
+            self.rect_vertices.push(RectVertex {
+                position: [p.x, p.y],
+                color: c,
+            });
+        }
+        for i in 1..points.len() as u32 - 1 {
+            self.rect_indices.push(base);
+            self.rect_indices.push(base + i);
+            self.rect_indices.push(base + i + 1);
+        }
+    }
+
     /// Push a textured glyph quad into the glyph batch.
     #[allow(clippy::too_many_arguments)]
     pub(crate) fn push_glyph_quad(
@@ -100,7 +152,8 @@ impl WgpuRenderer {
     }
 
     /// Submit batched draw calls to a render pass.
-    /// Draws: grid rects → chrome rects → overlay rects → grid glyphs → chrome glyphs → overlay glyphs → top rects → top glyphs
+    /// Draws: grid rects → chrome rects → overlay rects → grid glyphs → chrome glyphs → images → overlay glyphs → top rects → top glyphs
+    /// With MSAA the pass resolves into `view`, which must match `set_target_size`.
     pub fn render_frame(
         &mut self,
         encoder: &mut wgpu::CommandEncoder,
@@ -109,16 +162,17 @@ impl WgpuRenderer {
         let vb_usage = wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST;
         let ib_usage = wgpu::BufferUsages::INDEX | wgpu::BufferUsages::COPY_DST;
 
-        // Update uniform buffer only when screen size changed
-        let screen_phys = [
+        // Update uniform buffer only when screen size or text gamma changed
+        let uniforms = [
             self.screen_size.width * self.scale_factor,
             self.screen_size.height * self.scale_factor,
+            self.text_gamma,
+            0.0f32,
         ];
-        if screen_phys != self.last_uniform_screen {
-            let screen_data = [screen_phys[0], screen_phys[1], 0.0f32, 0.0f32];
+        if uniforms != self.last_uniforms {
             self.queue
-                .write_buffer(&self.uniform_buffer, 0, bytemuck::cast_slice(&screen_data));
-            self.last_uniform_screen = screen_phys;
+                .write_buffer(&self.uniform_buffer, 0, bytemuck::cast_slice(&uniforms));
+            self.last_uniforms = uniforms;
         }
 
         // ── Upload grid layer (instanced) ──
@@ -199,6 +253,15 @@ impl WgpuRenderer {
             self.queue.write_buffer(&self.glyph_ib, 0, ib_bytes);
         }
 
+        if !self.image_draws.is_empty() {
+            let vb_bytes = bytemuck::cast_slice(&self.image_vertices);
+            Self::ensure_buffer_capacity(&self.device, &mut self.image_vb, &mut self.image_vb_capacity, vb_bytes.len(), vb_usage, "image_vb");
+            self.queue.write_buffer(&self.image_vb, 0, vb_bytes);
+            let ib_bytes = bytemuck::cast_slice(&self.image_indices);
+            Self::ensure_buffer_capacity(&self.device, &mut self.image_ib, &mut self.image_ib_capacity, ib_bytes.len(), ib_usage, "image_ib");
+            self.queue.write_buffer(&self.image_ib, 0, ib_bytes);
+        }
+
         // ── Upload top layer (every frame) ──
         let has_top_rects = !self.top_rect_vertices.is_empty();
         let has_top_rounded_rects = !self.top_rounded_rect_vertices.is_empty();
@@ -241,20 +304,26 @@ impl WgpuRenderer {
         let top_rounded_rect_count = self.top_rounded_rect_indices.len() as u32;
         let top_glyph_count = self.top_glyph_indices.len() as u32;
 
+        // With MSAA, draw into the multisampled target and resolve into `view`
+        if self.sample_count > 1 && self.msaa_target.is_none() {
+            let width = (self.screen_size.width * self.scale_factor).round() as u32;
+            let height = (self.screen_size.height * self.scale_factor).round() as u32;
+            self.set_target_size(width, height);
+        }
+        let (target, resolve_target, store) = match &self.msaa_target {
+            Some(msaa) if self.sample_count > 1 => (&msaa.view, Some(view), wgpu::StoreOp::Discard),
+            _ => (view, None, wgpu::StoreOp::Store),
+        };
+
         {
             let mut pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
                 label: Some("main_pass"),
                 color_attachments: &[Some(wgpu::RenderPassColorAttachment {
-                    view,
-                    resolve_target: None,
+                    view: target,
+                    resolve_target,
                     ops: wgpu::Operations {
-                        load: wgpu::LoadOp::Clear(wgpu::Color {
-                            r: self.clear_color.r as f64,
-                            g: self.clear_color.g as f64,
-                            b: self.clear_color.b as f64,
-                            a: self.clear_color.a as f64,
-                        }),
-                        store: wgpu::StoreOp::Store,
+                        load: wgpu::LoadOp::Clear(self.clear_color_wgpu()),
+                        store,
                     },
                 })],
                 depth_stencil_attachment: None,
@@ -312,6 +381,20 @@ impl WgpuRenderer {
                 pass.draw(0..6, 0..grid_glyph_instance_count);
             }
 
+            // Images — one draw per texture run, above the grid text they cover
+            if !self.image_draws.is_empty() {
+                pass.set_pipeline(&self.image_pipeline);
+                pass.set_bind_group(0, &self.uniform_bind_group, &[]);
+                pass.set_vertex_buffer(0, self.image_vb.slice(..));
+                pass.set_index_buffer(self.image_ib.slice(..), wgpu::IndexFormat::Uint32);
+                for draw in &self.image_draws {
+                    if let Some(image) = self.images.get(&draw.key) {
+                        pass.set_bind_group(1, &image.bind_group, &[]);
+                        pass.draw_indexed(draw.indices.clone(), 0, 0..1);
+                    }
+                }
+            }
+
             // Overlay glyphs — indexed (traditional)
             if overlay_glyph_count > 0 {
                 pass.set_pipeline(&self.glyph_pipeline);
@@ -351,3 +434,35 @@ impl WgpuRenderer {
         }
     }
 }
+
+/// `points` (a convex polygon) cut to `clip` (Sutherland–Hodgman: one pass
+/// per rect edge). Empty when nothing is left inside.
+pub(crate) fn clip_convex_polygon(points: &[Vec2], clip: Rect) -> Vec<Vec2> {
+    let (left, right) = (clip.x, clip.x + clip.width);
+    let (top, bottom) = (clip.y, clip.y + clip.height);
+    let at_x = |a: Vec2, b: Vec2, x: f32| Vec2::new(x, a.y + (x - a.x) / (b.x - a.x) * (b.y - a.y));
+    let at_y = |a: Vec2, b: Vec2, y: f32| Vec2::new(a.x + (y - a.y) / (b.y - a.y) * (b.x - a.x), y);
+    let points = clip_edge(points, |p| p.x >= left, |a, b| at_x(a, b, left));
+    let points = clip_edge(&points, |p| p.x <= right, |a, b| at_x(a, b, right));
+    let points = clip_edge(&points, |p| p.y >= top, |a, b| at_y(a, b, top));
+    clip_edge(&points, |p| p.y <= bottom, |a, b| at_y(a, b, bottom))
+}
+
+/// Keep the part of `points` where `inside` holds; `cross` is where an edge
+/// from inside to outside (or back) meets the boundary.
+fn clip_edge(points: &[Vec2], inside: impl Fn(Vec2) -> bool, cross: impl Fn(Vec2, Vec2) -> Vec2) -> Vec<Vec2> {
+    let mut out = Vec::with_capacity(points.len() + 1);
+    for (i, &cur) in points.iter().enumerate() {
+        let prev = points[(i + points.len() - 1) % points.len()];
+        match (inside(prev), inside(cur)) {
+            (true, true) => out.push(cur),
+            (true, false) => out.push(cross(prev, cur)),
+            (false, true) => {
+                out.push(cross(prev, cur));
+                out.push(cur);
+            }
+            (false, false) => {}
+        }
+    }
+    out
+}
@@ -91,6 +91,13 @@ fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
         let alpha = 1.0 - smoothstep(-in.shadow_blur * 0.5, in.shadow_blur, dist);
         if alpha < 0.001 { discard; }
         return vec4<f32>(in.color.rgb, in.color.a * alpha);
+    } else if in.shadow_blur < 0.0 {
+        // Stroke mode: crisp outer edge minus the inner edge |shadow_blur| px in
+        let outer = 1.0 - smoothstep(-1.0, 0.5, dist);
+        let inner = 1.0 - smoothstep(-1.0, 0.5, dist - in.shadow_blur);
+        let alpha = outer - inner;
+        if alpha < 0.001 { discard; }
+        return vec4<f32>(in.color.rgb, in.color.a * alpha);
     } else {
         // Normal mode: crisp SDF edge
         let alpha = 1.0 - smoothstep(-1.0, 0.5, dist);
@@ -100,6 +107,49 @@ fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
 }
 "#;
 
+// Image quads: plain RGBA texture sampled per quad, multiplied by the vertex color.
+pub const IMAGE_SHADER: &str = r#"
+struct VertexInput {
+    @location(0) position: vec2<f32>,
+    @location(1) uv: vec2<f32>,
+    @location(2) color: vec4<f32>,
+};
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) uv: vec2<f32>,
+    @location(1) color: vec4<f32>,
+};
+
+struct Uniforms {
+    screen_size: vec2<f32>,
+};
+
+@group(0) @binding(0)
+var<uniform> uniforms: Uniforms;
+
+@group(1) @binding(0)
+var image_texture: texture_2d<f32>;
+@group(1) @binding(1)
+var image_sampler: sampler;
+
+@vertex
+fn vs_main(in: VertexInput) -> VertexOutput {
+    var out: VertexOutput;
+    let ndc_x = (in.position.x / uniforms.screen_size.x) * 2.0 - 1.0;
+    let ndc_y = 1.0 - (in.position.y / uniforms.screen_size.y) * 2.0;
+    out.clip_position = vec4<f32>(ndc_x, ndc_y, 0.0, 1.0);
+    out.uv = in.uv;
+    out.color = in.color;
+    return out;
+}
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    return textureSample(image_texture, image_sampler, in.uv) * in.color;
+}
+"#;
+
 // ── MSDF helper: shared by all glyph fragment shaders ──
 
 const MSDF_FRAGMENT_COMMON: &str = "
@@ -122,6 +172,12 @@ fn msdf_alpha(uv: vec2<f32>) -> f32 {
     let screen_px_distance = screen_px_range * (sd - 0.5);
     return clamp(screen_px_distance + 0.5, 0.0, 1.0);
 }
+
+// Coverage^(1 / gamma): gamma > 1 fattens anti-aliased edges (set_text_gamma).
+// Expects a `uniforms` binding with a `text_gamma` field.
+fn apply_text_gamma(alpha: f32) -> f32 {
+    return pow(alpha, 1.0 / uniforms.text_gamma);
+}
 ";
 
 // ── Instanced grid shaders ──
@@ -179,6 +235,7 @@ struct VertexOutput {{
 
 struct Uniforms {{
     screen_size: vec2<f32>,
+    text_gamma: f32,
 }};
 
 @group(0) @binding(0)
@@ -217,7 +274,7 @@ fn vs_main(
 
 @fragment
 fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {{
-    let alpha = msdf_alpha(in.uv);
+    let alpha = apply_text_gamma(msdf_alpha(in.uv));
     if alpha < 0.001 {{ discard; }}
     return vec4<f32>(in.color.rgb, in.color.a * alpha);
 }}
@@ -244,6 +301,7 @@ struct VertexOutput {{
 
 struct Uniforms {{
     screen_size: vec2<f32>,
+    text_gamma: f32,
 }};
 
 @group(0) @binding(0)
@@ -269,7 +327,7 @@ fn vs_main(in: VertexInput) -> VertexOutput {{
 
 @fragment
 fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {{
-    let alpha = msdf_alpha(in.uv);
+    let alpha = apply_text_gamma(msdf_alpha(in.uv));
     if alpha < 0.001 {{ discard; }}
     return vec4<f32>(in.color.rgb, in.color.a * alpha);
 }}
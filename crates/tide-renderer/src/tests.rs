@@ -0,0 +1,685 @@
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    #[test]
+    fn clear_color_alpha_reflects_background_opacity() {
+        let color = Color::new(0.2, 0.4, 0.6, 1.0);
+        let opaque = premultiplied_clear(color, 1.0);
+        assert_eq!(opaque.a, 1.0);
+        assert!((opaque.g - 0.4).abs() < 1e-6);
+
+        let translucent = premultiplied_clear(color, 0.8);
+        assert!((translucent.a - 0.8).abs() < 1e-6);
+        // Premultiplied: channels scaled by alpha
+        assert!((translucent.r - 0.16).abs() < 1e-6);
+        assert!((translucent.b - 0.48).abs() < 1e-6);
+    }
+
+    #[test]
+    fn clear_color_opacity_is_clamped() {
+        let color = Color::new(1.0, 1.0, 1.0, 1.0);
+        assert_eq!(premultiplied_clear(color, 1.5).a, 1.0);
+        assert_eq!(premultiplied_clear(color, -0.5).a, 0.0);
+    }
+
+    #[test]
+    fn translucent_background_prefers_premultiplied_alpha_mode() {
+        use wgpu::CompositeAlphaMode as M;
+        let modes = [M::Opaque, M::PostMultiplied, M::PreMultiplied];
+        assert_eq!(preferred_alpha_mode(&modes, 1.0), M::Opaque);
+        assert_eq!(preferred_alpha_mode(&modes, 0.9), M::PreMultiplied);
+        assert_eq!(preferred_alpha_mode(&[M::Opaque, M::PostMultiplied], 0.9), M::PostMultiplied);
+        assert_eq!(preferred_alpha_mode(&[M::Opaque], 0.9), M::Opaque);
+    }
+
+    #[test]
+    fn unsupported_sample_counts_fall_back_to_one() {
+        let format = wgpu::TextureFormat::Bgra8UnormSrgb;
+        let features = format.guaranteed_format_features(wgpu::Features::empty());
+        assert_eq!(resolve_sample_count(4, features), 4);
+        assert_eq!(resolve_sample_count(1, features), 1);
+        assert_eq!(resolve_sample_count(3, features), 1);
+        assert_eq!(resolve_sample_count(8, features), 1);
+        // 2x is adapter-specific, not guaranteed without extra device features
+        assert_eq!(resolve_sample_count(2, features), 1);
+        // ...but honored when the adapter's features report it
+        let adapter_features = wgpu::TextureFormatFeatures {
+            flags: features.flags | wgpu::TextureFormatFeatureFlags::MULTISAMPLE_X2,
+            ..features
+        };
+        assert_eq!(resolve_sample_count(2, adapter_features), 2);
+    }
+
+    #[test]
+    fn wide_chars_measure_double_ascii() {
+        use crate::chrome::text_advance_cells;
+        let ascii = text_advance_cells("abcd");
+        let wide = text_advance_cells("한글漢字");
+        assert_eq!(ascii, 4.0);
+        assert!((wide / ascii - 2.0).abs() < 0.01, "wide={wide} ascii={ascii}");
+        assert_eq!(text_advance_cells("a\tb"), 6.0);
+    }
+
+    #[test]
+    fn ellipsize_shortens_overflowing_text() {
+        use crate::chrome::{ellipsize, text_advance_cells};
+        let shown = ellipsize("very_long_file_name.rs", 10.0);
+        assert!(shown.ends_with('\u{2026}'));
+        assert_eq!(shown, "very_long\u{2026}");
+        assert!(text_advance_cells(&shown) <= 10.0);
+    }
+
+    #[test]
+    fn ellipsize_keeps_text_that_fits() {
+        use crate::chrome::ellipsize;
+        assert_eq!(ellipsize("main.rs", 7.0), "main.rs");
+        assert!(matches!(ellipsize("main.rs", 20.0), std::borrow::Cow::Borrowed(_)));
+    }
+
+    #[test]
+    fn ellipsize_never_splits_wide_chars_or_graphemes() {
+        use crate::chrome::{ellipsize, text_advance_cells};
+        // 한 is two cells; with 4 cells there's room for "a", "한", and "…".
+        assert_eq!(ellipsize("a한글b", 4.0), "a한\u{2026}");
+        // 3 cells: "a" + "…" — the next wide char would straddle the limit.
+        assert_eq!(ellipsize("a한글b", 3.0), "a\u{2026}");
+        // e + combining acute stays together.
+        let shown = ellipsize("e\u{301}e\u{301}e\u{301}", 2.0);
+        assert_eq!(shown, "e\u{301}\u{2026}");
+        assert!(text_advance_cells(&shown) <= 2.0);
+        assert_eq!(ellipsize("abc", 0.5), "");
+    }
+
+    /// Renderer on a headless adapter, or `None` when the machine has no GPU.
+    /// A renderer on the default adapter. Panics when there is none: these
+    /// tests need a GPU (or a software adapter such as lavapipe).
+    fn headless_renderer(scale_factor: f32) -> WgpuRenderer {
+        headless_renderer_with_samples(scale_factor, 1)
+    }
+
+    fn headless_renderer_with_samples(scale_factor: f32, sample_count: u32) -> WgpuRenderer {
+        let instance = wgpu::Instance::default();
+        let adapter = pollster::block_on(instance.request_adapter(&wgpu::RequestAdapterOptions::default()))
+            .expect("GPU adapter available");
+        let (device, queue) = pollster::block_on(adapter.request_device(&wgpu::DeviceDescriptor::default(), None))
+            .expect("GPU device available");
+        let format = wgpu::TextureFormat::Bgra8UnormSrgb;
+        let features = crate::format_features(&adapter, &device, format);
+        WgpuRenderer::new(
+            std::sync::Arc::new(device),
+            std::sync::Arc::new(queue),
+            format,
+            features,
+            scale_factor,
+            sample_count,
+        )
+    }
+
+    #[test]
+    fn measure_matches_where_text_draws() {
+        let mut renderer = headless_renderer(2.0);
+        let cell_w = renderer.cell_size().width;
+        let plain = TextStyle::default();
+        let bold_italic = TextStyle { bold: true, italic: true, ..plain };
+        assert_eq!(renderer.measure_text("main.rs", plain), 7.0 * cell_w);
+        assert_eq!(renderer.measure_text("ab한글\t", bold_italic), 10.0 * cell_w);
+        // A combining mark sits on its base and adds no width
+        assert_eq!(renderer.measure_text("e\u{301}", plain), cell_w);
+
+        // The next glyph drawn after the text starts at the measured width
+        renderer.begin_frame(Size::new(400.0, 100.0));
+        let clip = Rect::new(0.0, 0.0, 400.0, 100.0);
+        let glyph_x = |r: &WgpuRenderer| r.chrome_glyph_vertices.last().unwrap().position[0];
+        renderer.draw_chrome_text("|", Vec2::new(0.0, 0.0), bold_italic, clip);
+        let bar_x = glyph_x(&renderer);
+        let width = renderer.measure_text("한a", bold_italic);
+        renderer.draw_chrome_text("한a|", Vec2::new(0.0, 0.0), bold_italic, clip);
+        assert!((glyph_x(&renderer) - (bar_x + width * 2.0)).abs() < 1e-3);
+
+        assert_eq!(renderer.measure_text_clamped("main.rs", plain, 10.0 * cell_w), 7);
+        assert_eq!(renderer.measure_text_clamped("main.rs", plain, 4.0 * cell_w), 4);
+        // A wide char that would straddle the limit is excluded.
+        assert_eq!(renderer.measure_text_clamped("ab한글", plain, 3.0 * cell_w), 2);
+        assert_eq!(renderer.measure_text_clamped("ab한글", plain, 4.0 * cell_w), 3);
+        assert_eq!(renderer.measure_text_clamped("abc", plain, 0.0), 0);
+    }
+
+    #[test]
+    fn scale_factor_change_recomputes_cells_and_resets_atlas() {
+        let mut renderer = headless_renderer(1.0);
+        renderer.atlas_was_reset();
+        let before = renderer.cell_size();
+
+        renderer.set_scale_factor(1.0);
+        assert!(!renderer.atlas_was_reset(), "same scale is a no-op");
+
+        renderer.set_scale_factor(1.5);
+        assert!(renderer.atlas_was_reset());
+        // Line height is rounded at physical resolution, so the logical cell moves with scale
+        assert_ne!(renderer.cell_size(), before);
+        let size = renderer.font_size();
+        let expected = WgpuRenderer::compute_cell_size(&mut renderer.font_system, 1.5, size, 1.2);
+        assert_eq!(renderer.cell_size(), expected);
+    }
+
+    #[test]
+    fn line_height_multiplier_changes_cell_height() {
+        let mut renderer = headless_renderer(1.0);
+        let before = renderer.cell_size();
+        assert_eq!(before.height, (14.0_f32 * 1.2).ceil());
+        renderer.atlas_was_reset();
+
+        renderer.set_line_height_multiplier(1.5);
+        assert!(renderer.metrics_were_invalidated());
+        assert!(!renderer.metrics_were_invalidated(), "the flag is consumed");
+        assert!(!renderer.atlas_was_reset(), "the atlas is kept");
+        let after = renderer.cell_size();
+        assert_eq!(after.height, (14.0_f32 * 1.5).ceil());
+        assert_eq!(after.width, before.width, "width is unaffected");
+
+        // The table follows, so font size changes keep the multiplier
+        renderer.set_font_size(20.0);
+        assert_eq!(renderer.cell_size().height, 30.0);
+    }
+
+    #[test]
+    fn baseline_follows_ratio_and_line_height() {
+        let mut renderer = headless_renderer(1.0);
+        let cell_h = renderer.cell_size().height;
+        let centered = renderer.baseline_y(cell_h);
+
+        renderer.set_baseline_ratio(0.0);
+        let top = renderer.baseline_y(cell_h);
+        renderer.set_baseline_ratio(1.0);
+        let bottom = renderer.baseline_y(cell_h);
+        assert!(top < centered && centered < bottom);
+        assert!((centered - (top + bottom) / 2.0).abs() < 0.001);
+
+        // Taller cells push a centered baseline down by half the extra leading
+        renderer.set_baseline_ratio(0.5);
+        renderer.set_line_height_multiplier(2.0);
+        let tall_h = renderer.cell_size().height;
+        let expected = centered + (tall_h - cell_h) * 0.5;
+        assert!((renderer.baseline_y(tall_h) - expected).abs() < 0.001);
+    }
+
+    #[test]
+    fn registered_image_draws_a_quad_bound_to_its_texture() {
+        let mut renderer = headless_renderer(2.0);
+        let red = [255u8, 0, 0, 255].repeat(4);
+        assert!(!renderer.register_image(1, &red, 3, 2), "size must match the data");
+        assert!(renderer.register_image(7, &red, 2, 2));
+        assert_eq!(renderer.image_size(7), Some((2, 2)));
+
+        renderer.begin_frame(Size::new(100.0, 100.0));
+        assert!(!renderer.draw_image_by_key(8, Rect::new(0.0, 0.0, 10.0, 10.0)));
+        assert!(renderer.draw_image_by_key(7, Rect::new(10.0, 20.0, 30.0, 40.0)));
+        assert!(renderer.draw_image_by_key(7, Rect::new(50.0, 20.0, 30.0, 40.0)));
+        // Both quads sample texture 7, so they batch into one draw
+        assert_eq!(renderer.image_draws, vec![image::ImageDraw { key: 7, indices: 0..12 }]);
+        // Physical pixels, full texture mapped corner to corner
+        assert_eq!(renderer.image_vertices[0].position, [20.0, 40.0]);
+        assert_eq!(renderer.image_vertices[2].position, [80.0, 120.0]);
+        assert_eq!(renderer.image_vertices[2].uv, [1.0, 1.0]);
+
+        // draw_image with a known key and size reuses the texture
+        assert!(renderer.draw_image(9, Rect::new(0.0, 0.0, 4.0, 4.0), &red, 2, 2));
+        assert!(renderer.draw_image(9, Rect::new(0.0, 0.0, 4.0, 4.0), &[], 2, 2));
+        assert_eq!(renderer.image_draws.len(), 2);
+
+        // The image pipeline validates against a real render pass
+        let target = renderer.device.create_texture(&wgpu::TextureDescriptor {
+            label: None,
+            size: wgpu::Extent3d { width: 200, height: 200, depth_or_array_layers: 1 },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: wgpu::TextureFormat::Bgra8UnormSrgb,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+            view_formats: &[],
+        });
+        let view = target.create_view(&wgpu::TextureViewDescriptor::default());
+        let mut encoder = renderer.device.create_command_encoder(&wgpu::CommandEncoderDescriptor::default());
+        renderer.render_frame(&mut encoder, &view);
+        renderer.queue.submit([encoder.finish()]);
+
+        renderer.begin_frame(Size::new(100.0, 100.0));
+        assert!(renderer.image_draws.is_empty());
+        renderer.unregister_image(7);
+        assert!(!renderer.has_image(7));
+    }
+
+    #[test]
+    fn msaa_renderer_resolves_into_a_single_sample_target() {
+        let mut renderer = headless_renderer_with_samples(1.0, 4);
+        assert_eq!(renderer.sample_count(), 4);
+
+        renderer.set_target_size(120, 80);
+        assert_eq!(renderer.msaa_target.as_ref().map(|t| t.size), Some((120, 80)));
+
+        renderer.begin_frame(Size::new(120.0, 80.0));
+        renderer.draw_rect(Rect::new(10.0, 10.0, 50.0, 20.0), Color::new(1.0, 0.0, 0.0, 1.0));
+        renderer.draw_top_rounded_rect(Rect::new(20.0, 40.0, 60.0, 30.0), Color::new(0.0, 1.0, 0.0, 1.0), 6.0);
+        renderer.end_frame();
+
+        // Every pipeline validates against the multisampled pass
+        let target = renderer.device.create_texture(&wgpu::TextureDescriptor {
+            label: None,
+            size: wgpu::Extent3d { width: 120, height: 80, depth_or_array_layers: 1 },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: wgpu::TextureFormat::Bgra8UnormSrgb,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+            view_formats: &[],
+        });
+        let view = target.create_view(&wgpu::TextureViewDescriptor::default());
+        let mut encoder = renderer.device.create_command_encoder(&wgpu::CommandEncoderDescriptor::default());
+        renderer.render_frame(&mut encoder, &view);
+        renderer.queue.submit([encoder.finish()]);
+
+        // Same size keeps the texture; single-sample renderers never allocate one
+        renderer.set_target_size(120, 80);
+        assert_eq!(renderer.msaa_target.as_ref().map(|t| t.size), Some((120, 80)));
+        let mut plain = headless_renderer(1.0);
+        plain.set_target_size(120, 80);
+        assert!(plain.msaa_target.is_none());
+    }
+
+    /// Render "O" in white on black at `text_gamma` and read back one byte
+    /// (green channel) per pixel of the 64x32 target.
+    fn capture_text(renderer: &mut WgpuRenderer, text_gamma: f32) -> Vec<u8> {
+        let (width, height) = (64u32, 32u32);
+        renderer.set_text_gamma(text_gamma);
+        renderer.clear_color = Color::new(0.0, 0.0, 0.0, 1.0);
+        renderer.begin_frame(Size::new(width as f32, height as f32));
+        let style = TextStyle { foreground: Color::WHITE, ..TextStyle::default() };
+        renderer.draw_text("O", Vec2::new(8.0, 4.0), style, Rect::new(0.0, 0.0, 64.0, 32.0));
+        renderer.end_frame();
+
+        let target = renderer.device.create_texture(&wgpu::TextureDescriptor {
+            label: None,
+            size: wgpu::Extent3d { width, height, depth_or_array_layers: 1 },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: wgpu::TextureFormat::Bgra8UnormSrgb,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_SRC,
+            view_formats: &[],
+        });
+        // 64 px * 4 bytes = 256, the required row alignment
+        let readback = renderer.device.create_buffer(&wgpu::BufferDescriptor {
+            label: None,
+            size: (width * height * 4) as u64,
+            usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+            mapped_at_creation: false,
+        });
+        let view = target.create_view(&wgpu::TextureViewDescriptor::default());
+        let mut encoder = renderer.device.create_command_encoder(&wgpu::CommandEncoderDescriptor::default());
+        renderer.render_frame(&mut encoder, &view);
+        encoder.copy_texture_to_buffer(
+            target.as_image_copy(),
+            wgpu::ImageCopyBuffer {
+                buffer: &readback,
+                layout: wgpu::ImageDataLayout { offset: 0, bytes_per_row: Some(width * 4), rows_per_image: None },
+            },
+            wgpu::Extent3d { width, height, depth_or_array_layers: 1 },
+        );
+        renderer.queue.submit([encoder.finish()]);
+        let slice = readback.slice(..);
+        slice.map_async(wgpu::MapMode::Read, |_| {});
+        renderer.device.poll(wgpu::Maintain::Wait);
+        let pixels = slice.get_mapped_range().chunks(4).map(|bgra| bgra[1]).collect();
+        readback.unmap();
+        pixels
+    }
+
+    #[test]
+    fn text_gamma_is_clamped() {
+        assert_eq!(clamp_text_gamma(1.8), 1.8);
+        assert_eq!(clamp_text_gamma(10.0), *TEXT_GAMMA_RANGE.end());
+        assert_eq!(clamp_text_gamma(0.0), *TEXT_GAMMA_RANGE.start());
+        assert_eq!(clamp_text_gamma(f32::NAN), 1.0);
+        assert_eq!(clamp_text_gamma(f32::INFINITY), 1.0);
+    }
+
+    #[test]
+    fn text_gamma_updates_the_uniform_and_fattens_glyph_edges() {
+        let mut renderer = headless_renderer(1.0);
+        assert_eq!(renderer.text_gamma(), 1.0);
+        let plain = capture_text(&mut renderer, 1.0);
+        assert_eq!(renderer.last_uniforms, [64.0, 32.0, 1.0, 0.0]);
+        let fat = capture_text(&mut renderer, 2.0);
+        assert_eq!(renderer.last_uniforms, [64.0, 32.0, 2.0, 0.0]);
+
+        // An anti-aliased edge pixel gets brighter; solid and empty pixels don't move
+        let (edge, &mid) = plain
+            .iter()
+            .enumerate()
+            .find(|&(_, &v)| (48..208).contains(&v))
+            .expect("a partially covered pixel");
+        assert!(fat[edge] > mid, "edge pixel {edge}: {mid} -> {}", fat[edge]);
+        for (i, (&a, &b)) in plain.iter().zip(&fat).enumerate() {
+            assert!(b >= a, "pixel {i} got darker: {a} -> {b}");
+            if a == 0 || a == 255 {
+                assert_eq!(a, b, "pixel {i}");
+            }
+        }
+    }
+
+    fn bg_style(background: Color) -> TextStyle {
+        TextStyle {
+            foreground: Color::new(1.0, 1.0, 1.0, 1.0),
+            background: Some(background),
+            bold: false,
+            dim: false,
+            italic: false,
+            underline: false,
+        }
+    }
+
+    #[test]
+    fn same_background_cells_merge_into_one_rect_per_run() {
+        let mut renderer = headless_renderer(2.0);
+        let cell = Size::new(8.0, 16.0);
+        let blue = bg_style(Color::new(0.0, 0.0, 1.0, 1.0));
+        let red = bg_style(Color::new(1.0, 0.0, 0.0, 1.0));
+
+        renderer.begin_pane_grid(PaneId::new(1));
+        for col in 0..80 {
+            renderer.draw_grid_cell('x', 0, col, blue, cell, Vec2::new(4.0, 0.0));
+        }
+        renderer.end_pane_grid();
+        let bg = &renderer.pane_grid_caches[&PaneId::new(1)].bg_instances;
+        assert_eq!(bg.len(), 1, "a row of 80 same-bg cells is one rect");
+        assert_eq!(bg[0].position, [8.0, 0.0]);
+        assert_eq!(bg[0].size, [80.0 * 16.0, 32.0]);
+        assert_eq!(renderer.pane_grid_caches[&PaneId::new(1)].glyph_instances.len(), 80);
+
+        // Runs break on a color change, a new row, a gap, and an explicit rect
+        renderer.begin_pane_grid(PaneId::new(2));
+        renderer.draw_grid_cell(' ', 0, 0, blue, cell, Vec2::new(0.0, 0.0));
+        renderer.draw_grid_cell(' ', 0, 1, red, cell, Vec2::new(0.0, 0.0));
+        renderer.draw_grid_cell(' ', 0, 2, red, cell, Vec2::new(0.0, 0.0));
+        renderer.draw_grid_cell(' ', 0, 4, red, cell, Vec2::new(0.0, 0.0));
+        renderer.draw_grid_cell(' ', 1, 5, red, cell, Vec2::new(0.0, 0.0));
+        renderer.draw_grid_rect(Rect::new(0.0, 16.0, 80.0, 16.0), Color::new(0.0, 1.0, 0.0, 1.0));
+        renderer.draw_grid_cell(' ', 1, 6, red, cell, Vec2::new(0.0, 0.0));
+        renderer.end_pane_grid();
+        let widths: Vec<f32> = renderer.pane_grid_caches[&PaneId::new(2)].bg_instances.iter().map(|i| i.size[0]).collect();
+        assert_eq!(widths, vec![16.0, 32.0, 16.0, 16.0, 160.0, 16.0]);
+    }
+
+    #[test]
+    fn translucent_window_scales_cell_backgrounds_and_strokes_borders() {
+        let mut renderer = headless_renderer(2.0);
+        renderer.set_background_opacity(0.5);
+        let cell = Size::new(8.0, 16.0);
+        renderer.begin_pane_grid(PaneId::new(1));
+        renderer.draw_grid_cell(' ', 0, 0, bg_style(Color::new(0.0, 0.0, 1.0, 0.8)), cell, Vec2::new(0.0, 0.0));
+        renderer.end_pane_grid();
+        let bg = &renderer.pane_grid_caches[&PaneId::new(1)].bg_instances;
+        assert!((bg[0].color[3] - 0.4).abs() < 1e-6);
+
+        // A border is a stroke (negative blur, physical px); a fill is not
+        renderer.draw_chrome_rounded_border(Rect::new(0.0, 0.0, 40.0, 40.0), Color::new(1.0, 1.0, 1.0, 1.0), 6.0, 1.5);
+        renderer.draw_chrome_rounded_rect(Rect::new(0.0, 0.0, 40.0, 40.0), Color::new(1.0, 1.0, 1.0, 1.0), 6.0);
+        let blur: Vec<f32> = renderer.chrome_rect_vertices.iter().map(|v| v.shadow_blur).collect();
+        assert_eq!(blur, [-3.0, -3.0, -3.0, -3.0, 0.0, 0.0, 0.0, 0.0]);
+        renderer.draw_chrome_rounded_border(Rect::new(0.0, 0.0, 40.0, 40.0), Color::new(1.0, 1.0, 1.0, 1.0), 6.0, 0.0);
+        assert_eq!(renderer.chrome_rect_vertices.len(), 8, "zero-width borders draw nothing");
+    }
+
+    #[test]
+    fn underlined_cells_still_merge_backgrounds() {
+        let mut renderer = headless_renderer(1.0);
+        let cell = Size::new(8.0, 16.0);
+        let style = TextStyle { underline: true, ..bg_style(Color::new(0.0, 0.0, 1.0, 1.0)) };
+
+        renderer.begin_pane_grid(PaneId::new(1));
+        for col in 0..4 {
+            renderer.draw_grid_cell('a', 0, col, style, cell, Vec2::new(0.0, 0.0));
+        }
+        renderer.end_pane_grid();
+        // One merged background, then an underline bar per cell on top of it
+        let bg = &renderer.pane_grid_caches[&PaneId::new(1)].bg_instances;
+        assert_eq!(bg.len(), 5);
+        assert_eq!(bg[0].size, [32.0, 16.0]);
+        assert!(bg[1..].iter().all(|i| i.size == [8.0, 1.0]));
+    }
+
+    #[test]
+    fn grid_cluster_draws_combining_marks_over_the_base_cell() {
+        let mut renderer = headless_renderer(1.0);
+        let cell = Size::new(8.0, 16.0);
+        let style = TextStyle::default();
+        let glyphs = |r: &WgpuRenderer, id| r.pane_grid_caches[&PaneId::new(id)].glyph_instances.len();
+
+        renderer.begin_pane_grid(PaneId::new(1));
+        renderer.draw_grid_cluster('e', &[], 0, 0, style, cell, Vec2::new(0.0, 0.0));
+        renderer.end_pane_grid();
+        let base = glyphs(&renderer, 1);
+        assert_eq!(base, 1);
+
+        // A ZWJ ends the cluster: the emoji it joins on is not overlaid
+        renderer.begin_pane_grid(PaneId::new(2));
+        renderer.draw_grid_cluster('e', &['\u{200d}', '\u{301}'], 0, 0, style, cell, Vec2::new(0.0, 0.0));
+        renderer.end_pane_grid();
+        assert_eq!(glyphs(&renderer, 2), base);
+
+        // A combining acute adds its glyph, centered on the cell
+        renderer.begin_pane_grid(PaneId::new(3));
+        renderer.draw_grid_cluster('e', &['\u{301}'], 0, 3, style, cell, Vec2::new(0.0, 0.0));
+        renderer.end_pane_grid();
+        let instances = &renderer.pane_grid_caches[&PaneId::new(3)].glyph_instances;
+        assert_eq!(instances.len(), base + 1, "the acute is drawn");
+        let mark = &instances[1];
+        assert_eq!(mark.position[0] + mark.size[0] / 2.0, 28.0);
+        assert!(mark.position[1] < instances[0].position[1], "acute sits above the e");
+    }
+
+    /// Logical x-extents of the glyph quads drawn this frame.
+    fn glyph_spans(renderer: &WgpuRenderer) -> Vec<(f32, f32)> {
+        let s = renderer.scale_factor;
+        renderer
+            .glyph_vertices
+            .chunks(4)
+            .map(|quad| (quad[0].position[0] / s, quad[1].position[0] / s))
+            .collect()
+    }
+
+    #[test]
+    fn clip_stack_intersects_pushed_regions() {
+        let mut renderer = headless_renderer(2.0);
+        let style = TextStyle::default();
+        let text = "x".repeat(40);
+        let everywhere = Rect::new(0.0, 0.0, 1000.0, 1000.0);
+
+        renderer.begin_frame(Size::new(1000.0, 1000.0));
+        renderer.draw_text(&text, Vec2::new(0.0, 0.0), style, everywhere);
+        let unclipped = glyph_spans(&renderer).len();
+        assert_eq!(unclipped, 40);
+
+        renderer.begin_frame(Size::new(1000.0, 1000.0));
+        renderer.push_clip(Rect::new(0.0, 0.0, 100.0, 100.0));
+        renderer.push_clip(Rect::new(20.0, 0.0, 500.0, 100.0));
+        assert_eq!(renderer.current_clip(), Some(Rect::new(20.0, 0.0, 80.0, 100.0)));
+        renderer.draw_text(&text, Vec2::new(0.0, 0.0), style, everywhere);
+        let nested = glyph_spans(&renderer);
+        assert!(!nested.is_empty() && nested.len() < unclipped);
+        assert!(nested.iter().all(|&(l, r)| r > 20.0 && l < 100.0), "glyphs outside 20..100: {nested:?}");
+
+        // Popping the inner region restores the panel's
+        renderer.pop_clip();
+        renderer.begin_frame(Size::new(1000.0, 1000.0));
+        assert_eq!(renderer.current_clip(), None, "begin_frame empties the stack");
+        renderer.push_clip(Rect::new(0.0, 0.0, 100.0, 100.0));
+        renderer.push_clip(Rect::new(20.0, 0.0, 500.0, 100.0));
+        renderer.pop_clip();
+        renderer.draw_text_in_clip(&text, Vec2::new(0.0, 0.0), style);
+        let panel = glyph_spans(&renderer);
+        assert!(panel.len() > nested.len());
+        assert!(panel.iter().all(|&(_, r)| r > 0.0) && panel.iter().all(|&(l, _)| l < 100.0));
+
+        // Rects are cut to the region; ones outside it are dropped
+        renderer.draw_rect(Rect::new(50.0, 50.0, 200.0, 10.0), Color::new(1.0, 0.0, 0.0, 1.0));
+        renderer.draw_rect(Rect::new(300.0, 50.0, 10.0, 10.0), Color::new(1.0, 0.0, 0.0, 1.0));
+        assert_eq!(renderer.rect_vertices.len(), 4);
+        assert_eq!(renderer.rect_vertices[1].position, [200.0, 100.0]);
+        renderer.pop_clip();
+        assert_eq!(renderer.current_clip(), None);
+    }
+
+    #[test]
+    fn overlay_text_outline_draws_four_halo_quads_per_glyph() {
+        let mut renderer = headless_renderer(2.0);
+        let style = TextStyle::default();
+        let everywhere = Rect::new(0.0, 0.0, 1000.0, 1000.0);
+        let halo = Color::new(0.0, 0.0, 0.0, 0.8);
+
+        renderer.begin_frame(Size::new(1000.0, 1000.0));
+        renderer.draw_text("3 of 12", Vec2::new(10.0, 10.0), style, everywhere);
+        let plain = glyph_spans(&renderer);
+        assert_eq!(plain.len(), 5);
+
+        renderer.set_overlay_text_outline(true, halo);
+        assert_eq!(renderer.overlay_text_outline(), Some(halo));
+        renderer.begin_frame(Size::new(1000.0, 1000.0));
+        renderer.draw_text("3 of 12", Vec2::new(10.0, 10.0), style, everywhere);
+        let outlined = glyph_spans(&renderer);
+        assert_eq!(outlined.len(), plain.len() * 5);
+        assert_eq!(renderer.glyph_indices.len(), renderer.glyph_vertices.len() / 4 * 6);
+
+        // Halo copies come first, shifted ±1 logical px; the glyph itself is last and unshifted
+        for (glyph, quads) in plain.iter().zip(outlined.chunks(5)) {
+            assert_eq!(quads[0].0, glyph.0 - 1.0);
+            assert_eq!(quads[1].0, glyph.0 + 1.0);
+            assert_eq!(quads[2].0, glyph.0);
+            assert_eq!(quads[4], *glyph);
+        }
+        let colors: Vec<[f32; 4]> = renderer.glyph_vertices.iter().step_by(4).map(|v| v.color).collect();
+        assert!(colors.chunks(5).all(|c| c[..4].iter().all(|&h| h == [0.0, 0.0, 0.0, 0.8])));
+
+        // Turning it off draws one quad per glyph again
+        renderer.set_overlay_text_outline(false, halo);
+        renderer.begin_frame(Size::new(1000.0, 1000.0));
+        renderer.draw_text("3 of 12", Vec2::new(10.0, 10.0), style, everywhere);
+        assert_eq!(glyph_spans(&renderer).len(), plain.len());
+    }
+
+    /// `(x, y, width)` of each quad in the overlay rect batch.
+    fn overlay_rects(renderer: &WgpuRenderer) -> Vec<(f32, f32, f32)> {
+        renderer
+            .rect_vertices
+            .chunks(4)
+            .map(|q| (q[0].position[0], q[0].position[1], q[1].position[0] - q[0].position[0]))
+            .collect()
+    }
+
+    #[test]
+    fn draw_selection_merges_spans_per_row() {
+        let mut renderer = headless_renderer(1.0);
+        renderer.begin_frame(Size::new(1000.0, 1000.0));
+        let cell = Size::new(10.0, 20.0);
+        let color = Color::new(0.2, 0.4, 1.0, 0.3);
+        // Three rows: partial first row, full middle row in overlapping
+        // pieces, partial last row; given out of order, plus an empty span
+        let rows = [
+            (2, (0, 3)),
+            (0, (4, 8)),
+            (1, (3, 10)),
+            (0, (8, 10)),
+            (1, (0, 5)),
+            (2, (3, 3)),
+        ];
+        renderer.draw_selection(&rows, cell, Vec2::new(100.0, 50.0), color);
+        assert_eq!(
+            overlay_rects(&renderer),
+            [(140.0, 50.0, 60.0), (100.0, 70.0, 100.0), (100.0, 90.0, 30.0)]
+        );
+        assert_eq!(renderer.rect_vertices[0].color, [0.2, 0.4, 1.0, 0.3]);
+    }
+
+    #[test]
+    fn draw_selection_is_clipped_to_the_pane() {
+        let mut renderer = headless_renderer(1.0);
+        renderer.begin_frame(Size::new(1000.0, 1000.0));
+        let cell = Size::new(10.0, 20.0);
+        renderer.push_clip(Rect::new(0.0, 0.0, 50.0, 40.0));
+        // Row 0 runs past the pane's right edge; row 2 is below it
+        renderer.draw_selection(&[(0, (2, 20)), (2, (0, 4))], cell, Vec2::new(0.0, 0.0), Color::WHITE);
+        renderer.pop_clip();
+        assert_eq!(overlay_rects(&renderer), [(20.0, 0.0, 30.0)]);
+    }
+
+    #[test]
+    fn draw_triangle_pushes_three_vertices_and_one_triangle() {
+        let mut renderer = headless_renderer(2.0);
+        renderer.begin_frame(Size::new(1000.0, 1000.0));
+        let color = Color::new(0.5, 0.5, 0.5, 1.0);
+        renderer.draw_triangle(Vec2::new(10.0, 10.0), Vec2::new(20.0, 15.0), Vec2::new(10.0, 20.0), color);
+        assert_eq!(renderer.rect_vertices.len(), 3);
+        assert_eq!(renderer.rect_indices, [0, 1, 2]);
+        let positions: Vec<[f32; 2]> = renderer.rect_vertices.iter().map(|v| v.position).collect();
+        assert_eq!(positions, [[20.0, 20.0], [40.0, 30.0], [20.0, 40.0]]);
+        assert_eq!(renderer.rect_vertices[0].color, [0.5, 0.5, 0.5, 1.0]);
+
+        // Indices follow on after earlier quads in the batch
+        renderer.begin_frame(Size::new(1000.0, 1000.0));
+        renderer.draw_rect(Rect::new(0.0, 0.0, 5.0, 5.0), color);
+        renderer.draw_triangle(Vec2::new(10.0, 10.0), Vec2::new(20.0, 15.0), Vec2::new(10.0, 20.0), color);
+        assert_eq!(renderer.rect_vertices.len(), 7);
+        assert_eq!(renderer.rect_indices[6..], [4, 5, 6]);
+    }
+
+    #[test]
+    fn draw_triangle_is_cut_to_the_clip_region() {
+        let mut renderer = headless_renderer(1.0);
+        renderer.begin_frame(Size::new(1000.0, 1000.0));
+        let (a, b, c) = (Vec2::new(0.0, 0.0), Vec2::new(100.0, 0.0), Vec2::new(0.0, 100.0));
+
+        // Inside the region: drawn as is
+        renderer.push_clip(Rect::new(0.0, 0.0, 200.0, 200.0));
+        renderer.draw_triangle(a, b, c, Color::WHITE);
+        renderer.pop_clip();
+        assert_eq!(renderer.rect_vertices.len(), 3);
+
+        // Right angle inside a 40×40 region: cut to a square, two triangles
+        renderer.begin_frame(Size::new(1000.0, 1000.0));
+        renderer.push_clip(Rect::new(0.0, 0.0, 40.0, 40.0));
+        renderer.draw_triangle(a, b, c, Color::WHITE);
+        assert_eq!(renderer.rect_vertices.len(), 4);
+        assert_eq!(renderer.rect_indices.len(), 6);
+        assert!(renderer.rect_vertices.iter().all(|v| v.position[0] <= 40.0 && v.position[1] <= 40.0));
+
+        // Outside the region: nothing
+        renderer.begin_frame(Size::new(1000.0, 1000.0));
+        renderer.push_clip(Rect::new(200.0, 200.0, 50.0, 50.0));
+        renderer.draw_triangle(a, b, c, Color::WHITE);
+        assert!(renderer.rect_vertices.is_empty() && renderer.rect_indices.is_empty());
+    }
+
+    #[test]
+    fn dim_overlay_covers_the_pane_rect_in_the_top_layer_at_the_given_alpha() {
+        let mut renderer = headless_renderer(2.0);
+        renderer.begin_frame(Size::new(1000.0, 1000.0));
+        let pane = Rect::new(10.0, 20.0, 100.0, 50.0);
+        renderer.draw_dim_overlay(pane, 0.25);
+        let positions: Vec<[f32; 2]> = renderer.top_rect_vertices.iter().map(|v| v.position).collect();
+        assert_eq!(positions, [[20.0, 40.0], [220.0, 40.0], [220.0, 140.0], [20.0, 140.0]]);
+        assert!(renderer.top_rect_vertices.iter().all(|v| v.color == [0.0, 0.0, 0.0, 0.25]));
+        assert!(renderer.rect_vertices.is_empty(), "drawn over the grid text, not under it");
+
+        // Light themes lighten instead; the amount is clamped to 1.0
+        renderer.set_theme(&tide_core::Theme::light());
+        renderer.begin_frame(Size::new(1000.0, 1000.0));
+        renderer.draw_dim_overlay(pane, 3.0);
+        assert!(renderer.top_rect_vertices.iter().all(|v| v.color == [1.0, 1.0, 1.0, 1.0]));
+
+        // Zero disables it
+        renderer.begin_frame(Size::new(1000.0, 1000.0));
+        renderer.draw_dim_overlay(pane, 0.0);
+        assert!(renderer.top_rect_vertices.is_empty());
+    }
+}
@@ -44,7 +44,7 @@ pub struct ChromeRectVertex {
     pub rect_center: [f32; 2],   // rect center (px)
     pub rect_half: [f32; 2],     // half-width, half-height (px)
     pub corner_radius: f32,      // rounded corner radius (px)
-    pub shadow_blur: f32,        // >0 = shadow mode: soft falloff over this radius (px)
+    pub shadow_blur: f32,        // >0 = shadow mode: soft falloff over this radius (px); <0 = stroke of this width (px)
 }
 
 impl ChromeRectVertex {
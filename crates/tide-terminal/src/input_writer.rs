@@ -0,0 +1,133 @@
+// Throttled PTY input: `Terminal::write` queues its bytes here and returns.
+// A writer thread hands them to the PTY event loop in `chunk_size` pieces,
+// waiting while the loop still holds `HIGH_WATER_CHUNKS` chunks it hasn't
+// written to the PTY. A huge paste into a shell that isn't reading therefore
+// waits in this queue (bounded by `MAX_PASTE_BYTES` upstream) instead of
+// flooding the loop, and the caller's thread never blocks.
+
+use std::borrow::Cow;
+use std::collections::VecDeque;
+use std::sync::{Arc, Condvar, Mutex, MutexGuard};
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+use alacritty_terminal::event_loop::{EventLoopSender, Msg};
+
+/// Chunks the event loop may hold unwritten before the writer waits.
+const HIGH_WATER_CHUNKS: usize = 4;
+
+/// How often a waiting writer rechecks the event loop's backlog.
+const BACKPRESSURE_POLL: Duration = Duration::from_millis(2);
+
+pub(crate) struct InputWriter {
+    shared: Arc<Shared>,
+    thread: Option<JoinHandle<()>>,
+}
+
+struct Shared {
+    state: Mutex<State>,
+    changed: Condvar,
+}
+
+struct State {
+    queue: VecDeque<Vec<u8>>,
+    queued_bytes: usize,
+    chunk_size: usize,
+    shutdown: bool,
+}
+
+impl Shared {
+    fn lock(&self) -> MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl InputWriter {
+    /// Start the writer thread feeding `sender`.
+    pub(crate) fn new(sender: EventLoopSender, chunk_size: usize) -> Self {
+        let shared = Arc::new(Shared {
+            state: Mutex::new(State {
+                queue: VecDeque::new(),
+                queued_bytes: 0,
+                chunk_size: chunk_size.max(1),
+                shutdown: false,
+            }),
+            changed: Condvar::new(),
+        });
+        let thread = {
+            let shared = shared.clone();
+            std::thread::Builder::new()
+                .name("pty-writer".to_string())
+                .spawn(move || run(&shared, &sender))
+                .expect("failed to spawn PTY writer thread")
+        };
+        Self { shared, thread: Some(thread) }
+    }
+
+    /// Queue `data`, split into chunks of the current chunk size.
+    pub(crate) fn write(&self, data: &[u8]) {
+        if data.is_empty() {
+            return;
+        }
+        let mut state = self.shared.lock();
+        let chunk_size = state.chunk_size;
+        state.queue.extend(data.chunks(chunk_size).map(<[u8]>::to_vec));
+        state.queued_bytes += data.len();
+        drop(state);
+        self.shared.changed.notify_one();
+    }
+
+    /// Max bytes per `Msg::Input`; applies to data queued from now on.
+    pub(crate) fn set_chunk_size(&self, size: usize) {
+        self.shared.lock().chunk_size = size.max(1);
+    }
+
+    /// Bytes queued here and not yet handed to the event loop.
+    pub(crate) fn queued_bytes(&self) -> usize {
+        self.shared.lock().queued_bytes
+    }
+}
+
+impl Drop for InputWriter {
+    fn drop(&mut self) {
+        self.shared.lock().shutdown = true;
+        self.shared.changed.notify_one();
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+fn run(shared: &Shared, sender: &EventLoopSender) {
+    let mut state = shared.lock();
+    loop {
+        if state.shutdown {
+            return;
+        }
+        if state.queue.is_empty() {
+            state = shared.changed.wait(state).unwrap_or_else(|e| e.into_inner());
+            continue;
+        }
+        // Backpressure: the loop writes to the PTY only as fast as the
+        // child reads, so wait for it to drain before sending more.
+        if sender.pending_input() >= state.chunk_size * HIGH_WATER_CHUNKS {
+            state = shared
+                .changed
+                .wait_timeout(state, BACKPRESSURE_POLL)
+                .unwrap_or_else(|e| e.into_inner())
+                .0;
+            continue;
+        }
+        let Some(chunk) = state.queue.pop_front() else { continue };
+        drop(state);
+        let len = chunk.len();
+        let sent = sender.send(Msg::Input(Cow::Owned(chunk)));
+        state = shared.lock();
+        state.queued_bytes -= len;
+        if sent.is_err() {
+            // The event loop is gone (shell exited); nothing will drain.
+            state.queue.clear();
+            state.queued_bytes = 0;
+        }
+    }
+}
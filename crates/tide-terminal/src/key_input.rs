@@ -4,9 +4,51 @@ use tide_core::{Key, Modifiers};
 
 use super::Terminal;
 
+/// DEC private modes that change key encoding.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct KeyModes {
+    /// DECCKM (CSI ? 1 h): arrows/Home/End send SS3 (`ESC O A`) instead of CSI.
+    pub app_cursor: bool,
+    /// DECKPAM (ESC =): keypad keys send SS3 application sequences.
+    pub app_keypad: bool,
+}
+
 impl Terminal {
+    /// Alt/Option+char: send ESC + char (Meta, for Emacs/readline) when on,
+    /// or the composed character the keyboard layout produced when off.
+    /// Known as `alt_sends_escape` on Linux. Defaults to off, like the app setting.
+    pub fn set_option_as_meta(&mut self, on: bool) {
+        self.option_as_meta = on;
+    }
+
+    pub fn option_as_meta(&self) -> bool {
+        self.option_as_meta
+    }
+
+    /// Encode a key event for this terminal, honoring Option-as-Meta.
+    /// `composed` is the text the key produced (e.g. "∫" for Option+b);
+    /// without it, Alt+char falls back to the bare char when Meta is off.
+    pub fn encode_key(&self, key: &Key, modifiers: &Modifiers, composed: Option<&str>) -> Vec<u8> {
+        if modifiers.alt && !modifiers.ctrl && !self.option_as_meta {
+            if let Key::Char(_) = key {
+                if let Some(text) = composed.filter(|t| !t.is_empty() && !t.chars().any(char::is_control)) {
+                    return text.as_bytes().to_vec();
+                }
+                let plain = Modifiers { alt: false, ..*modifiers };
+                return Self::key_to_bytes(key, &plain);
+            }
+        }
+        Self::key_to_bytes_with_modes(key, modifiers, self.modes().key_modes())
+    }
+
     /// Convert a key event to the byte sequence that should be sent to the PTY
+    /// (normal cursor and keypad modes).
     pub fn key_to_bytes(key: &Key, modifiers: &Modifiers) -> Vec<u8> {
+        Self::key_to_bytes_with_modes(key, modifiers, KeyModes::default())
+    }
+
+    /// Convert a key event to PTY bytes under the given DEC key modes.
+    pub fn key_to_bytes_with_modes(key: &Key, modifiers: &Modifiers, modes: KeyModes) -> Vec<u8> {
         match key {
             Key::Char(c) => {
                 if modifiers.ctrl {
@@ -45,15 +87,22 @@ impl Terminal {
             }
             Key::Escape => vec![0x1b],
             Key::Delete => vec![0x1b, b'[', b'3', b'~'],
-            Key::Up => Self::arrow_bytes(b'A', modifiers),
-            Key::Down => Self::arrow_bytes(b'B', modifiers),
-            Key::Right => Self::arrow_bytes(b'C', modifiers),
-            Key::Left => Self::arrow_bytes(b'D', modifiers),
-            Key::Home => vec![0x1b, b'[', b'H'],
-            Key::End => vec![0x1b, b'[', b'F'],
+            Key::Up => Self::arrow_bytes(b'A', modifiers, modes.app_cursor),
+            Key::Down => Self::arrow_bytes(b'B', modifiers, modes.app_cursor),
+            Key::Right => Self::arrow_bytes(b'C', modifiers, modes.app_cursor),
+            Key::Left => Self::arrow_bytes(b'D', modifiers, modes.app_cursor),
+            Key::Home => Self::arrow_bytes(b'H', modifiers, modes.app_cursor),
+            Key::End => Self::arrow_bytes(b'F', modifiers, modes.app_cursor),
             Key::PageUp => vec![0x1b, b'[', b'5', b'~'],
             Key::PageDown => vec![0x1b, b'[', b'6', b'~'],
             Key::Insert => vec![0x1b, b'[', b'2', b'~'],
+            Key::Keypad(c) => {
+                let plain = !modifiers.shift && !modifiers.ctrl && !modifiers.alt && !modifiers.meta;
+                match Self::keypad_app_code(*c) {
+                    Some(code) if modes.app_keypad && plain => vec![0x1b, b'O', code],
+                    _ => Self::key_to_bytes(&Key::Char(*c), modifiers),
+                }
+            }
             Key::F(n) => match n {
                 1 => vec![0x1b, b'O', b'P'],
                 2 => vec![0x1b, b'O', b'Q'],
@@ -72,10 +121,25 @@ impl Terminal {
         }
     }
 
-    /// Build the CSI escape sequence for an arrow key with modifier support.
-    /// Plain arrow: `\e[{dir}`, with modifiers: `\e[1;{mod}{dir}`
+    /// SS3 final byte for a keypad key in application keypad mode.
+    fn keypad_app_code(c: char) -> Option<u8> {
+        match c {
+            '0'..='9' => Some(b'p' + (c as u8 - b'0')),
+            '.' => Some(b'n'),
+            '+' => Some(b'k'),
+            '-' => Some(b'm'),
+            '*' => Some(b'j'),
+            '/' => Some(b'o'),
+            '=' => Some(b'X'),
+            _ => None,
+        }
+    }
+
+    /// Build the escape sequence for an arrow/Home/End key with modifier support.
+    /// Plain key: `\e[{dir}` (or `\eO{dir}` in application cursor mode),
+    /// with modifiers: `\e[1;{mod}{dir}` in either mode.
     /// Modifier codes: 2=Shift, 3=Alt, 5=Ctrl, etc.
-    fn arrow_bytes(dir: u8, modifiers: &Modifiers) -> Vec<u8> {
+    fn arrow_bytes(dir: u8, modifiers: &Modifiers, app_cursor: bool) -> Vec<u8> {
         let modifier_code = 1
             + if modifiers.shift { 1 } else { 0 }
             + if modifiers.alt { 2 } else { 0 }
@@ -84,6 +148,8 @@ impl Terminal {
             // CSI 1 ; {modifier} {dir}
             let code = b'0' + modifier_code;
             vec![0x1b, b'[', b'1', b';', code, dir]
+        } else if app_cursor {
+            vec![0x1b, b'O', dir]
         } else {
             vec![0x1b, b'[', dir]
         }
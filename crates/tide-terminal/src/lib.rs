@@ -10,32 +10,223 @@
 // so input events are never blocked by terminal output processing.
 
 use std::borrow::Cow;
+use std::collections::HashMap;
+use std::io::Read;
+use std::os::fd::{AsRawFd, FromRawFd, OwnedFd};
 use std::path::PathBuf;
-use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::{Arc, Mutex, OnceLock};
-use std::time::Instant;
+use std::time::{Duration, Instant};
 
 use alacritty_terminal::event::{Event, EventListener, WindowSize};
-use alacritty_terminal::event_loop::{EventLoop, Msg, Notifier};
-use alacritty_terminal::grid::{Dimensions, Scroll};
+use alacritty_terminal::event_loop::{self, EventLoop, Msg, Notifier};
+use alacritty_terminal::grid::{Dimensions, Grid, Scroll};
 use alacritty_terminal::index::{Column, Line, Point};
 use alacritty_terminal::sync::FairMutex;
 use alacritty_terminal::term::cell::Flags as CellFlags;
 use alacritty_terminal::term::{Config as TermConfig, Term, TermMode};
 use alacritty_terminal::tty;
-use alacritty_terminal::vte::ansi::{Color as AnsiColor, NamedColor, Rgb as AnsiRgb};
+use alacritty_terminal::vte::ansi::{
+    ClearMode, Color as AnsiColor, Handler, NamedColor, Processor, Rgb as AnsiRgb, StdSyncHandler,
+};
 
 pub mod git;
 mod color;
+mod input_writer;
 mod key_input;
+#[cfg(feature = "metrics")]
+mod metrics;
+mod pause;
+mod prompt;
+mod resize;
+mod sixel;
+mod tap;
+mod write_filter;
+
+pub use key_input::KeyModes;
+pub use prompt::{PromptMark, PromptMarkKind};
+pub use sixel::InlineImage;
+pub use write_filter::WriteFilter;
+#[cfg(feature = "metrics")]
+pub use metrics::SyncMetrics;
+
+pub use tide_core::TerminalError;
 
 use tide_core::{
-    Color, CursorShape, CursorState, TerminalBackend, TerminalCell, TerminalGrid,
+    Color, CursorShape, CursorState, TerminalBackend, TerminalCell, TerminalGrid, Theme,
 };
 
 /// Number of scrollback history lines to keep.
 const SCROLLBACK_LINES: usize = 10_000;
 
+/// In power-save mode, how long without PTY output (while the shell is idle)
+/// before the terminal counts as idle.
+const POWER_SAVE_IDLE_AFTER: Duration = Duration::from_secs(2);
+
+/// Default for `set_cwd_poll_interval`: how long a CWD detected from the
+/// child process is reused by `cwd()`.
+pub const DEFAULT_CWD_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Default for `set_resize_debounce`: how long the size must stay unchanged
+/// before the PTY is told (SIGWINCH).
+pub const DEFAULT_RESIZE_DEBOUNCE: Duration = Duration::from_millis(50);
+
+/// Default size of each `Msg::Input` sent to the PTY; larger writes are split
+/// and throttled so a huge paste can't flood the PTY (see `set_write_chunk_size`).
+pub const DEFAULT_WRITE_CHUNK_SIZE: usize = 4096;
+
+/// Pastes larger than this are truncated (with a warning) by `Terminal::paste`.
+pub const MAX_PASTE_BYTES: usize = 1024 * 1024;
+
+/// Cell size in pixels reported to the PTY (`TIOCSWINSZ`). Programs size
+/// inline images against it, so captured images are placed with it too.
+const PTY_CELL_WIDTH: u16 = 8;
+const PTY_CELL_HEIGHT: u16 = 16;
+
+/// Next `TIDE_PANE_ID` handed to a spawned shell; unique within the process.
+static NEXT_PANE_ENV_ID: AtomicU64 = AtomicU64::new(1);
+
+/// OSC 133 prompt marks kept per terminal; older ones are dropped.
+const MAX_PROMPT_MARKS: usize = 1024;
+
+/// How the terminal cursor is painted (see `Terminal::set_cursor_style`).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CursorStyleConfig {
+    /// Block cursor swaps the colors of the cell under it instead of
+    /// painting a solid color.
+    pub reverse_video: bool,
+    /// Cursor color unless the app set one via OSC 12.
+    pub fallback_color: Color,
+}
+
+impl Default for CursorStyleConfig {
+    fn default() -> Self {
+        Self { reverse_video: false, fallback_color: Theme::dark().cursor }
+    }
+}
+
+/// How to spawn a shell-backed terminal (see `Terminal::with_options`).
+#[derive(Debug, Clone, Default)]
+pub struct TerminalOptions {
+    /// Starting directory; `None` uses `$HOME`.
+    pub cwd: Option<PathBuf>,
+    /// Shell path or name looked up in `$PATH`; `None` uses `$SHELL`,
+    /// falling back to zsh/bash.
+    pub shell: Option<String>,
+    pub dark_mode: bool,
+    /// Extra environment for the shell. Overrides the variables set by
+    /// default (`TERM`, `COLORTERM`, `COLORFGBG`, `PROMPT_EOL_MARK`,
+    /// `TIDE_PANE_ID`).
+    pub env_extra: HashMap<String, String>,
+}
+
+/// Terminal mode flags as of the last grid sync. Copied by the sync thread
+/// each cycle, so reading them takes no lock on the emulator.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TermModeSnapshot(TermMode);
+
+impl TermModeSnapshot {
+    /// DECTCEM (CSI ? 25 h)
+    pub fn show_cursor(&self) -> bool {
+        self.0.contains(TermMode::SHOW_CURSOR)
+    }
+
+    /// DECCKM (CSI ? 1 h)
+    pub fn app_cursor(&self) -> bool {
+        self.0.contains(TermMode::APP_CURSOR)
+    }
+
+    /// DECKPAM (ESC =)
+    pub fn app_keypad(&self) -> bool {
+        self.0.contains(TermMode::APP_KEYPAD)
+    }
+
+    /// CSI ? 2004 h
+    pub fn bracketed_paste(&self) -> bool {
+        self.0.contains(TermMode::BRACKETED_PASTE)
+    }
+
+    /// CSI ? 1049 h (or 47 / 1047)
+    pub fn alt_screen(&self) -> bool {
+        self.0.contains(TermMode::ALT_SCREEN)
+    }
+
+    /// Any of click (1000), drag (1002) or motion (1003) mouse reporting.
+    pub fn mouse_reporting(&self) -> bool {
+        self.0.intersects(TermMode::MOUSE_MODE)
+    }
+
+    /// CSI ? 1006 h: mouse reports use the SGR encoding.
+    pub fn sgr_mouse(&self) -> bool {
+        self.0.contains(TermMode::SGR_MOUSE)
+    }
+
+    /// CSI ? 1004 h
+    pub fn focus_reporting(&self) -> bool {
+        self.0.contains(TermMode::FOCUS_IN_OUT)
+    }
+
+    /// The modes that change key encoding.
+    pub fn key_modes(&self) -> KeyModes {
+        KeyModes { app_cursor: self.app_cursor(), app_keypad: self.app_keypad() }
+    }
+}
+
+/// Whether `shell` names an executable: an existing path if it contains a
+/// `/`, otherwise a file found in one of the `$PATH` directories.
+fn shell_exists(shell: &str) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    let is_executable = |path: &std::path::Path| {
+        path.metadata()
+            .map(|m| m.is_file() && m.permissions().mode() & 0o111 != 0)
+            .unwrap_or(false)
+    };
+    if shell.contains('/') {
+        return is_executable(std::path::Path::new(shell));
+    }
+    std::env::var_os("PATH")
+        .map(|paths| std::env::split_paths(&paths).any(|dir| is_executable(&dir.join(shell))))
+        .unwrap_or(false)
+}
+
+/// Allocate a PTY pair sized to `size`.
+fn open_pty(size: WindowSize) -> std::io::Result<(OwnedFd, OwnedFd)> {
+    let mut master = -1;
+    let mut slave = -1;
+    let mut winsize = libc::winsize {
+        ws_row: size.num_lines,
+        ws_col: size.num_cols,
+        ws_xpixel: size.num_cols.saturating_mul(size.cell_width),
+        ws_ypixel: size.num_lines.saturating_mul(size.cell_height),
+    };
+    let rc = unsafe {
+        libc::openpty(&mut master, &mut slave, std::ptr::null_mut(), std::ptr::null_mut(), std::ptr::addr_of_mut!(winsize))
+    };
+    if rc != 0 {
+        return Err(std::io::Error::last_os_error());
+    }
+    // SAFETY: openpty succeeded, so both descriptors are open and owned by us.
+    Ok(unsafe { (OwnedFd::from_raw_fd(master), OwnedFd::from_raw_fd(slave)) })
+}
+
+/// Name of process `pid` (its executable name, as `ps -o comm` shows it).
+#[cfg(target_os = "macos")]
+fn process_name(pid: i32) -> Option<String> {
+    let mut buf = [0u8; 256];
+    let len = unsafe { libc::proc_name(pid, buf.as_mut_ptr() as *mut libc::c_void, buf.len() as u32) };
+    if len <= 0 {
+        return None;
+    }
+    Some(String::from_utf8_lossy(&buf[..len as usize]).into_owned())
+}
+
+#[cfg(not(target_os = "macos"))]
+fn process_name(pid: i32) -> Option<String> {
+    let comm = std::fs::read_to_string(format!("/proc/{}/comm", pid)).ok()?;
+    let name = comm.trim_end_matches('\n');
+    (!name.is_empty()).then(|| name.to_string())
+}
+
 /// Simple dimensions struct that implements alacritty_terminal's Dimensions trait.
 struct TermDimensions {
     cols: usize,
@@ -72,6 +263,50 @@ struct SharedSnapshot {
     url_ranges: Vec<Vec<(usize, usize)>>,
     generation: u64,
     cursor: CursorState,
+    modes: TermModeSnapshot,
+    prompt_marks: Vec<PromptMark>,
+    /// When the sync that produced this snapshot finished
+    synced_at: Instant,
+}
+
+// ──────────────────────────────────────────────
+// Power save: idle tracking shared by PTY, sync and main threads
+// ──────────────────────────────────────────────
+
+/// Idle state for power-save mode. While idle, no-op wakes (scroll-to-bottom
+/// when already at the bottom) are dropped so the sync thread stays parked,
+/// and URL detection is suspended until real output arrives.
+struct PowerSave {
+    enabled: AtomicBool,
+    /// Last result of `Terminal::is_shell_idle()` (updated by the main thread).
+    shell_idle: AtomicBool,
+    epoch: Instant,
+    /// Milliseconds since `epoch` when PTY output last arrived.
+    last_output_ms: AtomicU64,
+}
+
+impl PowerSave {
+    fn new() -> Self {
+        Self {
+            enabled: AtomicBool::new(false),
+            shell_idle: AtomicBool::new(false),
+            epoch: Instant::now(),
+            last_output_ms: AtomicU64::new(0),
+        }
+    }
+
+    fn record_output(&self) {
+        let ms = self.epoch.elapsed().as_millis() as u64;
+        self.last_output_ms.store(ms, Ordering::Relaxed);
+    }
+
+    fn is_idle(&self) -> bool {
+        if !self.enabled.load(Ordering::Relaxed) || !self.shell_idle.load(Ordering::Relaxed) {
+            return false;
+        }
+        let last = Duration::from_millis(self.last_output_ms.load(Ordering::Relaxed));
+        self.epoch.elapsed().saturating_sub(last) >= POWER_SAVE_IDLE_AFTER
+    }
 }
 
 // ──────────────────────────────────────────────
@@ -91,6 +326,16 @@ struct TermEventListener {
     dark_mode: Arc<AtomicBool>,
     /// Mode 2031: app opted in to dark/light color-scheme notifications.
     mode_2031: Arc<AtomicBool>,
+    /// Active theme — used to answer OSC 4/10/11/12 color queries.
+    theme: Arc<Mutex<Theme>>,
+    /// Power-save idle tracking — stamped on every PTY output wakeup.
+    power_save: Arc<PowerSave>,
+    /// Whether OSC 52 clipboard-set requests are accepted.
+    allow_osc52: Arc<AtomicBool>,
+    /// Latest accepted OSC 52 clipboard text, taken by the main thread.
+    clipboard_request: Arc<Mutex<Option<String>>>,
+    /// Latest OSC 0/2 title change ("" = reset), taken by the main thread.
+    title_request: Arc<Mutex<Option<String>>>,
 }
 
 impl TermEventListener {
@@ -103,51 +348,22 @@ impl TermEventListener {
     ///   257    = Background (OSC 11)
     ///   258    = Cursor     (OSC 12)
     fn resolve_color(&self, index: usize) -> AnsiRgb {
-        let dark = self.dark_mode.load(Ordering::Relaxed);
-        match index {
-            // Foreground (OSC 10)
-            256 => {
-                if dark {
-                    AnsiRgb { r: 230, g: 232, b: 242 } // dark fg: (0.9, 0.91, 0.95)
-                } else {
-                    AnsiRgb { r: 26, g: 20, b: 13 }    // light fg: (0.10, 0.08, 0.05)
-                }
-            }
+        let theme = self.theme.lock().map(|t| t.clone()).unwrap_or_default();
+        let color = match index {
+            // Foreground (OSC 10) and cursor (OSC 12)
+            256 | 258 => theme.foreground,
             // Background (OSC 11) — report the actual visible pane background
-            257 => {
-                if dark {
-                    AnsiRgb { r: 14, g: 14, b: 16 }    // dark pane_bg: (0.055, 0.055, 0.063)
-                } else {
-                    AnsiRgb { r: 240, g: 235, b: 227 }  // light pane_bg: (0.94, 0.92, 0.89)
-                }
-            }
-            // Cursor (OSC 12) — use foreground color
-            258 => {
-                if dark {
-                    AnsiRgb { r: 230, g: 232, b: 242 }
-                } else {
-                    AnsiRgb { r: 26, g: 20, b: 13 }
-                }
-            }
+            257 => theme.pane_bg,
             // Named ANSI colors (0-15)
-            0..=15 => {
-                let color = Terminal::named_color_to_rgb(dark, Terminal::index_to_named(index as u8));
-                AnsiRgb {
-                    r: (color.r * 255.0) as u8,
-                    g: (color.g * 255.0) as u8,
-                    b: (color.b * 255.0) as u8,
-                }
-            }
+            0..=15 => theme.ansi[index],
             // 256-color palette (16-255)
-            16..=255 => {
-                let color = Terminal::indexed_color_fallback(index as u8);
-                AnsiRgb {
-                    r: (color.r * 255.0) as u8,
-                    g: (color.g * 255.0) as u8,
-                    b: (color.b * 255.0) as u8,
-                }
-            }
-            _ => AnsiRgb { r: 0, g: 0, b: 0 },
+            16..=255 => Terminal::indexed_color_fallback(index as u8),
+            _ => Color::BLACK,
+        };
+        AnsiRgb {
+            r: (color.r * 255.0).round() as u8,
+            g: (color.g * 255.0).round() as u8,
+            b: (color.b * 255.0).round() as u8,
         }
     }
 }
@@ -187,6 +403,27 @@ impl EventListener for TermEventListener {
                 }
                 return;
             }
+            Event::ClipboardStore(_, text) => {
+                // OSC 52 lets any program in the terminal overwrite the clipboard,
+                // so it is opt-in (`Terminal::set_allow_osc52`).
+                if !self.allow_osc52.load(Ordering::Relaxed) {
+                    return;
+                }
+                if let Ok(mut req) = self.clipboard_request.lock() {
+                    *req = Some(text.clone());
+                }
+            }
+            Event::Title(title) => {
+                if let Ok(mut req) = self.title_request.lock() {
+                    *req = Some(title.clone());
+                }
+            }
+            Event::ResetTitle => {
+                if let Ok(mut req) = self.title_request.lock() {
+                    *req = Some(String::new());
+                }
+            }
+            Event::Wakeup => self.power_save.record_output(),
             _ => {}
         }
         self.dirty.store(true, Ordering::Relaxed);
@@ -207,16 +444,37 @@ struct GridSyncer {
     term: Arc<FairMutex<Term<TermEventListener>>>,
     raw_buf: Vec<(char, AnsiColor, AnsiColor, CellFlags)>,
     prev_raw_buf: Vec<(char, AnsiColor, AnsiColor, CellFlags)>,
+    /// Zero-width chars of the few cells that have any, as (flat index,
+    /// chars) in index order; kept out of `raw_buf` so its entries stay `Copy`.
+    zerowidth_buf: Vec<(usize, Vec<char>)>,
+    prev_zerowidth_buf: Vec<(usize, Vec<char>)>,
     palette_buf: [Option<AnsiRgb>; 256],
     grid: TerminalGrid,
     inverse_cursor: Option<(u16, u16)>,
     cached_cursor: CursorState,
+    modes: TermModeSnapshot,
     url_ranges: Vec<Vec<(usize, usize)>>,
     grid_generation: u64,
     url_row_buf: String,
     dark_mode: Arc<AtomicBool>,
     dark_mode_changed: Arc<AtomicBool>,
+    /// Shared theme (written by `Terminal::set_theme`) and the local copy
+    /// used for color conversion, refreshed when `dark_mode_changed` is set.
+    theme: Arc<Mutex<Theme>>,
+    palette_theme: Theme,
+    /// Grid content was replaced wholesale (clear scrollback / reset): skip the diff once.
+    full_resync: Arc<AtomicBool>,
     stay_at_bottom: Arc<AtomicBool>,
+    power_save: Arc<PowerSave>,
+    /// URL ranges were cleared while power-save idle; re-detect on next sync.
+    urls_stale: bool,
+    /// Number of completed sync cycles.
+    sync_count: Arc<AtomicU64>,
+    /// OSC 133 markers read off the grid rows, oldest first (published
+    /// with the snapshot)
+    prompt_marks: Vec<PromptMark>,
+    #[cfg(feature = "metrics")]
+    metrics: Arc<metrics::SyncRecorder>,
 }
 
 impl GridSyncer {
@@ -224,8 +482,19 @@ impl GridSyncer {
     /// Phase 1: Lock Term briefly to copy raw cell data + palette.
     /// Phase 2: Convert colors and diff against previous frame (no lock held).
     fn sync(&mut self) {
-        // Check if dark mode changed — force full re-render
+        #[cfg(feature = "metrics")]
+        let sync_started = Instant::now();
+        #[cfg(feature = "metrics")]
+        let mut cells_converted = 0u64;
+
+        // Check if dark mode / theme changed — force full re-render
         if self.dark_mode_changed.swap(false, Ordering::Relaxed) {
+            if let Ok(theme) = self.theme.lock() {
+                self.palette_theme = theme.clone();
+            }
+            self.prev_raw_buf.clear();
+        }
+        if self.full_resync.swap(false, Ordering::Relaxed) {
             self.prev_raw_buf.clear();
         }
 
@@ -233,10 +502,14 @@ impl GridSyncer {
         let stay_at_bottom = self.stay_at_bottom.load(Ordering::Relaxed);
 
         // Phase 1: Hold lock briefly — copy raw cell data + palette + cursor
+        #[cfg(feature = "metrics")]
+        let lock_started = Instant::now();
         let (cols, total_lines) = {
             let mut term = self.term.lock();
 
-            if stay_at_bottom {
+            // Term::scroll_display emits MouseCursorDirty, which re-marks us
+            // dirty — only scroll when needed or the sync thread never parks.
+            if stay_at_bottom && term.grid().display_offset() != 0 {
                 term.scroll_display(Scroll::Bottom);
             }
 
@@ -257,6 +530,7 @@ impl GridSyncer {
                 total_cells,
                 (' ', AnsiColor::Named(NamedColor::Foreground), AnsiColor::Named(NamedColor::Background), CellFlags::empty()),
             );
+            self.zerowidth_buf.clear();
             for line_idx in 0..total_lines {
                 let line = Line(line_idx as i32 - display_offset as i32);
                 let base = line_idx * cols;
@@ -264,9 +538,14 @@ impl GridSyncer {
                     let point = Point::new(line, Column(col_idx));
                     let cell = &grid[point];
                     self.raw_buf[base + col_idx] = (cell.c, cell.fg, cell.bg, cell.flags);
+                    if let Some(zerowidth) = cell.zerowidth() {
+                        self.zerowidth_buf.push((base + col_idx, zerowidth.to_vec()));
+                    }
                 }
             }
 
+            collect_prompt_marks(grid, &mut self.prompt_marks);
+
             // Read cursor state while we have the lock
             let cursor_point = grid.cursor.point;
             let cursor_shape = match term.cursor_style().shape {
@@ -275,17 +554,24 @@ impl GridSyncer {
                 alacritty_terminal::vte::ansi::CursorShape::Underline => CursorShape::Underline,
                 _ => CursorShape::Block,
             };
-            let cursor_visible = term.mode().contains(TermMode::SHOW_CURSOR);
+            self.modes = TermModeSnapshot(*term.mode());
+            let cursor_visible = self.modes.show_cursor();
+            // OSC 12 cursor color (reset by OSC 112)
+            let cursor_color = colors[NamedColor::Cursor]
+                .map(|rgb| Color::rgb(rgb.r as f32 / 255.0, rgb.g as f32 / 255.0, rgb.b as f32 / 255.0));
 
             self.cached_cursor = CursorState {
                 row: cursor_point.line.0 as u16,
                 col: cursor_point.column.0 as u16,
                 visible: cursor_visible,
                 shape: cursor_shape,
+                color: cursor_color,
             };
 
             (cols, total_lines)
         }; // Lock released here!
+        #[cfg(feature = "metrics")]
+        let lock_hold = lock_started.elapsed();
 
         // Phase 2: Diff with previous frame — only convert changed cells
         let total_cells = cols * total_lines;
@@ -326,23 +612,32 @@ impl GridSyncer {
             for (col_idx, tc) in row.iter_mut().enumerate().take(cols) {
                 let idx = base + col_idx;
                 let raw = self.raw_buf[idx];
+                let zerowidth = zerowidth_at(&self.zerowidth_buf, idx);
 
-                // Skip unchanged cells (same char, fg, bg, flags)
-                if same_size && self.prev_raw_buf[idx] == raw {
+                // Skip unchanged cells (same chars, fg, bg, flags)
+                if same_size
+                    && self.prev_raw_buf[idx] == raw
+                    && zerowidth == zerowidth_at(&self.prev_zerowidth_buf, idx)
+                {
                     continue;
                 }
                 any_changed = true;
+                #[cfg(feature = "metrics")]
+                {
+                    cells_converted += 1;
+                }
 
                 let (c, fg, bg, flags) = raw;
 
                 if flags.contains(CellFlags::WIDE_CHAR_SPACER) {
                     tc.character = '\0';
+                    tc.zerowidth.clear();
                     // Preserve background for selection/ANSI highlights on
                     // the second half of wide characters (Korean, CJK, etc.).
-                    let mut bg_color = Terminal::convert_color(dark_mode, &bg, &self.palette_buf);
+                    let mut bg_color = Terminal::convert_color(&self.palette_theme, &bg, &self.palette_buf);
                     let mut bg_is_default = matches!(bg, AnsiColor::Named(NamedColor::Background));
                     if flags.contains(CellFlags::INVERSE) {
-                        let fg_color = Terminal::convert_color(dark_mode, &fg, &self.palette_buf);
+                        let fg_color = Terminal::convert_color(&self.palette_theme, &fg, &self.palette_buf);
                         bg_color = fg_color;
                         bg_is_default = false;
                     }
@@ -362,8 +657,8 @@ impl GridSyncer {
                     continue;
                 }
 
-                let mut fg_color = Terminal::convert_color(dark_mode, &fg, &self.palette_buf);
-                let mut bg_color = Terminal::convert_color(dark_mode, &bg, &self.palette_buf);
+                let mut fg_color = Terminal::convert_color(&self.palette_theme, &fg, &self.palette_buf);
+                let mut bg_color = Terminal::convert_color(&self.palette_theme, &bg, &self.palette_buf);
                 let mut bg_is_default = matches!(bg, AnsiColor::Named(NamedColor::Background));
 
                 // SGR 7: swap foreground and background
@@ -402,6 +697,8 @@ impl GridSyncer {
                 };
 
                 tc.character = c;
+                tc.zerowidth.clear();
+                tc.zerowidth.extend_from_slice(zerowidth);
                 tc.style.bold = flags.contains(CellFlags::BOLD);
                 tc.style.dim = flags.contains(CellFlags::DIM);
                 tc.style.italic = flags.contains(CellFlags::ITALIC);
@@ -420,6 +717,7 @@ impl GridSyncer {
 
         // Swap buffers for next frame's diff
         std::mem::swap(&mut self.prev_raw_buf, &mut self.raw_buf);
+        std::mem::swap(&mut self.prev_zerowidth_buf, &mut self.zerowidth_buf);
 
         if any_changed || !same_size {
             self.grid_generation += 1;
@@ -429,10 +727,21 @@ impl GridSyncer {
         self.grid.cols = cols as u16;
         self.grid.rows = total_lines as u16;
 
-        // Scan for URLs in the visible grid
-        if any_changed || !same_size {
+        // Scan for URLs in the visible grid (suspended while power-save idle;
+        // stale ranges are dropped so links never point at the wrong cells)
+        if self.power_save.is_idle() {
+            if any_changed || !same_size {
+                self.url_ranges.clear();
+                self.urls_stale = true;
+            }
+        } else if any_changed || !same_size || self.urls_stale {
             self.detect_urls();
+            self.urls_stale = false;
         }
+
+        self.sync_count.fetch_add(1, Ordering::Relaxed);
+        #[cfg(feature = "metrics")]
+        self.metrics.record(sync_started.elapsed(), lock_hold, cells_converted);
     }
 
     /// Detect URLs in the grid and store column ranges per row.
@@ -462,6 +771,147 @@ impl GridSyncer {
     }
 }
 
+/// Extract text between two `(absolute_line, col)` points of a grid.
+/// See `Terminal::text_in_range`.
+fn grid_text_in_range(
+    grid: &alacritty_terminal::grid::Grid<alacritty_terminal::term::cell::Cell>,
+    start: (usize, usize),
+    end: (usize, usize),
+) -> String {
+    let (start, end) = if start <= end { (start, end) } else { (end, start) };
+    let history_len = grid.history_size();
+    let total = history_len + grid.screen_lines();
+    let cols = grid.columns();
+
+    let mut result = String::new();
+    for abs_line in start.0..=end.0.min(total.saturating_sub(1)) {
+        let line_idx = Line(abs_line as i32 - history_len as i32);
+        let col_start = if abs_line == start.0 { start.1.min(cols) } else { 0 };
+        let col_end = if abs_line == end.0 { end.1.min(cols) } else { cols };
+        for col_idx in col_start..col_end {
+            push_cell_text(&mut result, &grid[Point::new(line_idx, Column(col_idx))]);
+        }
+        if abs_line != end.0 {
+            // Trim trailing spaces from line before adding newline
+            let trimmed = result.trim_end_matches(' ');
+            result.truncate(trimmed.len());
+            result.push('\n');
+        }
+    }
+    result
+}
+
+/// Append a cell's text: its char and any zero-width chars (combining marks)
+/// stored with it. Wide-char spacers (`'\0'`) add nothing.
+fn push_cell_text(out: &mut String, cell: &alacritty_terminal::term::cell::Cell) {
+    if cell.c != '\0' {
+        out.push(cell.c);
+        out.extend(cell.zerowidth().unwrap_or_default());
+    }
+}
+
+/// Zero-width chars recorded for flat cell index `idx` (empty for most cells).
+fn zerowidth_at(buf: &[(usize, Vec<char>)], idx: usize) -> &[char] {
+    match buf.binary_search_by_key(&idx, |&(i, _)| i) {
+        Ok(pos) => &buf[pos].1,
+        Err(_) => &[],
+    }
+}
+
+/// Whether `c` is part of a word: alphanumerics, `_`, or one of `extra`.
+fn is_word_char(c: char, extra: &str) -> bool {
+    c.is_alphanumeric() || c == '_' || extra.contains(c)
+}
+
+/// Column range `(start, end)` (end exclusive) of the word under
+/// `(abs_line, col)`. See `Terminal::word_range_at`.
+fn grid_word_range_at(
+    grid: &alacritty_terminal::grid::Grid<alacritty_terminal::term::cell::Cell>,
+    abs_line: usize,
+    col: usize,
+    word_chars: &str,
+) -> (usize, usize) {
+    let history_len = grid.history_size();
+    let cols = grid.columns();
+    if abs_line >= history_len + grid.screen_lines() || col >= cols {
+        return (col, col);
+    }
+    let line_idx = Line(abs_line as i32 - history_len as i32);
+    // A wide char's spacer cell takes the character to its left.
+    let char_at = |c: usize| {
+        let mut c = c;
+        loop {
+            let cell = &grid[Point::new(line_idx, Column(c))];
+            if c > 0 && cell.flags.contains(CellFlags::WIDE_CHAR_SPACER) {
+                c -= 1;
+                continue;
+            }
+            return cell.c;
+        }
+    };
+    if !is_word_char(char_at(col), word_chars) {
+        return (col, col + 1);
+    }
+    let mut start = col;
+    while start > 0 && is_word_char(char_at(start - 1), word_chars) {
+        start -= 1;
+    }
+    let mut end = col + 1;
+    while end < cols && is_word_char(char_at(end), word_chars) {
+        end += 1;
+    }
+    (start, end)
+}
+
+/// Plain-text dump of a grid, one line per row. See `Terminal::export_text`.
+fn grid_export_text(
+    grid: &alacritty_terminal::grid::Grid<alacritty_terminal::term::cell::Cell>,
+    include_scrollback: bool,
+) -> String {
+    let history_len = grid.history_size();
+    let first = if include_scrollback { 0 } else { history_len };
+    let total = history_len + grid.screen_lines();
+    let cols = grid.columns();
+
+    let mut lines: Vec<String> = Vec::with_capacity(total - first);
+    let mut row_text = String::with_capacity(cols);
+    for abs_line in first..total {
+        let line_idx = Line(abs_line as i32 - history_len as i32);
+        row_text.clear();
+        for col_idx in 0..cols {
+            push_cell_text(&mut row_text, &grid[Point::new(line_idx, Column(col_idx))]);
+        }
+        lines.push(row_text.trim_end().to_string());
+    }
+    lines.join("\n")
+}
+
+/// Resize `term`, keeping the line at the top of the viewport in place when
+/// scrolled into history. Reflow can change the total line count, so the top
+/// line is carried over proportionally; at the bottom the view stays pinned.
+fn term_resize_keeping_scroll<T: EventListener>(term: &mut Term<T>, size: TermDimensions) {
+    let anchor = {
+        let grid = term.grid();
+        (grid.display_offset() != 0)
+            .then(|| (grid.history_size() - grid.display_offset(), grid.total_lines()))
+    };
+    term.resize(size);
+
+    let Some((old_top, old_total)) = anchor else { return };
+    let grid = term.grid();
+    let new_total = grid.total_lines();
+    let new_top = if new_total == old_total {
+        old_top
+    } else {
+        (old_top * new_total + old_total / 2) / old_total.max(1)
+    };
+    let target = grid.history_size().saturating_sub(new_top);
+    let delta = target as i32 - grid.display_offset() as i32;
+    if delta != 0 {
+        term.scroll_display(Scroll::Delta(delta));
+    }
+}
+
 /// Trim unbalanced trailing parentheses and punctuation from a URL match.
 /// Preserves balanced parens (e.g. Wikipedia URLs like `https://en.wikipedia.org/wiki/Foo_(bar)`).
 fn trim_url_trailing(url: &str) -> &str {
@@ -491,6 +941,113 @@ fn trim_url_trailing(url: &str) -> &str {
     &url[..end]
 }
 
+/// Read the OSC 133 tags off `grid`'s rows, scrollback first, into `marks`,
+/// keeping the newest `MAX_PROMPT_MARKS`. Lines count from the top of the
+/// scrollback.
+fn collect_prompt_marks<T>(grid: &Grid<T>, marks: &mut Vec<PromptMark>) {
+    marks.clear();
+    let history = grid.history_size();
+    for (line, row) in (0..history + grid.screen_lines()).zip(-(history as i32)..) {
+        let kinds = grid[Line(row)].marks().iter().filter_map(|&tag| PromptMarkKind::from_tag(tag));
+        marks.extend(kinds.map(|kind| PromptMark { kind, line }));
+    }
+    let excess = marks.len().saturating_sub(MAX_PROMPT_MARKS);
+    marks.drain(..excess);
+}
+
+// ──────────────────────────────────────────────
+// Reader thread entry point
+// ──────────────────────────────────────────────
+
+/// Scanners for the sequences alacritty's parser drops, with their state kept
+/// across reads.
+struct OutputScanners {
+    prompts: prompt::PromptScanner,
+    sixels: sixel::SixelScanner,
+    /// Shared with `Terminal::take_images`
+    images: Arc<sixel::ImageQueue>,
+}
+
+impl OutputScanners {
+    fn new(images: Arc<sixel::ImageQueue>) -> Self {
+        Self { prompts: prompt::PromptScanner::new(), sixels: sixel::SixelScanner::new(), images }
+    }
+}
+
+/// A sequence `OutputScanners` found, at the offset just past it.
+enum ScannedSequence {
+    Prompt(PromptMarkKind),
+    Sixel(Vec<u8>),
+}
+
+/// Parse output bytes into the emulator, stopping at the end of each OSC 133
+/// marker to tag the cursor's row with it and at the end of each sixel
+/// sequence to queue its payload at the cursor cell (alacritty doesn't move
+/// the cursor for DCS). Markers on the alternate screen are dropped: its
+/// lines never reach the scrollback. A marker inside a synchronized update
+/// lands where the cursor was when the update began, since the parser holds
+/// those bytes back until it ends.
+fn advance_emulator(
+    processor: &mut Processor<StdSyncHandler>,
+    scanners: &mut OutputScanners,
+    term: &mut Term<TermEventListener>,
+    data: &[u8],
+) {
+    let mut found = Vec::new();
+    scanners.prompts.feed(data, |end, kind| found.push((end, ScannedSequence::Prompt(kind))));
+    scanners.sixels.feed(data, |end, payload| found.push((end, ScannedSequence::Sixel(payload))));
+    found.sort_by_key(|&(end, _)| end);
+
+    let mut parsed = 0;
+    for (end, sequence) in found {
+        processor.advance(term, &data[parsed..end]);
+        parsed = end;
+        let point = term.grid().cursor.point;
+        match sequence {
+            ScannedSequence::Prompt(kind) => {
+                if !term.mode().contains(TermMode::ALT_SCREEN) {
+                    term.grid_mut()[point.line].push_mark(kind.to_tag());
+                }
+            }
+            ScannedSequence::Sixel(payload) => {
+                scanners.images.push(payload, point.column.0, point.line.0.max(0) as usize);
+            }
+        }
+    }
+    processor.advance(term, &data[parsed..]);
+}
+
+/// Feed `reader`'s bytes to the emulator (through `advance_emulator`, like
+/// PTY reads) until EOF, a read error, or the terminal is
+/// dropped. A chunk read while paused is held until output resumes.
+fn reader_thread_main<R: Read>(
+    mut reader: R,
+    term: std::sync::Weak<FairMutex<Term<TermEventListener>>>,
+    listener: TermEventListener,
+    pause: Arc<pause::OutputPause>,
+    images: Arc<sixel::ImageQueue>,
+) {
+    let mut processor: Processor<StdSyncHandler> = Processor::new();
+    let mut scanners = OutputScanners::new(images);
+    let mut buf = vec![0u8; 0x10000];
+    loop {
+        let n = match reader.read(&mut buf) {
+            Ok(0) => return,
+            Ok(n) => n,
+            Err(e) if e.kind() == std::io::ErrorKind::Interrupted => continue,
+            Err(_) => return,
+        };
+        if !pause.wait_while_paused(|| term.strong_count() > 0) {
+            return;
+        }
+        let Some(term) = term.upgrade() else {
+            return;
+        };
+        advance_emulator(&mut processor, &mut scanners, &mut term.lock(), &buf[..n]);
+        listener.send_event(Event::Wakeup);
+    }
+}
+
 // ──────────────────────────────────────────────
 // Sync thread entry point
 // ──────────────────────────────────────────────
@@ -518,15 +1075,19 @@ fn grid_sync_thread_main(
             }
 
             syncer.sync();
+            let synced_at = Instant::now();
 
             // Copy results into shared snapshot
             {
                 let mut snap = snapshot.lock().unwrap();
+                snap.synced_at = synced_at;
                 snap.grid.clone_from(&syncer.grid);
                 snap.inverse_cursor = syncer.inverse_cursor;
                 snap.url_ranges.clone_from(&syncer.url_ranges);
                 snap.generation = syncer.grid_generation;
                 snap.cursor = syncer.cached_cursor;
+                snap.modes = syncer.modes;
+                snap.prompt_marks.clone_from(&syncer.prompt_marks);
             }
             snapshot_ready.store(true, Ordering::Relaxed);
 
@@ -555,18 +1116,29 @@ fn grid_sync_thread_main(
 pub struct Terminal {
     /// The alacritty terminal emulator state, wrapped in a FairMutex for thread safety
     term: Arc<FairMutex<Term<TermEventListener>>>,
-    /// Notifier to send messages to the PTY event loop
-    notifier: Notifier,
+    /// Notifier to send messages to the PTY event loop; `None` without a PTY
+    notifier: Option<Notifier>,
     /// Cached grid — swapped in from the sync thread's SharedSnapshot
     cached_grid: TerminalGrid,
-    /// Detected current working directory (from OSC 7 or fallback)
+    /// Working directory reported by the shell via OSC 7
     current_dir: Option<PathBuf>,
+    /// Last CWD detected from the child process, with when it was read
+    detected_cwd: Mutex<Option<(Instant, PathBuf)>>,
+    /// How long `detected_cwd` is reused; `None` = never inspect the child
+    cwd_poll_interval: Option<Duration>,
+    /// Number of `detect_cwd_fallback` calls (each is a syscall)
+    cwd_probe_count: AtomicU64,
     /// Current column count
     cols: u16,
     /// Current row count
     rows: u16,
     /// The child process ID for CWD detection fallback
     child_pid: Option<u32>,
+    /// Duplicate of the PTY master, for querying the foreground process group
+    pty_master: Option<OwnedFd>,
+    /// PTY event loop thread; it ends once the output left at child exit
+    /// is parsed
+    event_loop: Option<EventLoopThread>,
     /// Atomic flag: sync thread has a new snapshot ready to consume
     snapshot_ready: Arc<AtomicBool>,
     /// Shared snapshot for grid exchange with sync thread
@@ -575,75 +1147,141 @@ pub struct Terminal {
     inverse_cursor: Option<(u16, u16)>,
     /// Cached cursor state (read from snapshot)
     cached_cursor: CursorState,
+    /// Terminal modes (read from snapshot)
+    cached_modes: TermModeSnapshot,
+    /// How the cursor is painted (see `set_cursor_style`)
+    cursor_style: CursorStyleConfig,
     /// Detected URL ranges per row (read from snapshot)
     url_ranges: Vec<Vec<(usize, usize)>>,
     /// Grid generation counter
     grid_generation: u64,
+    /// When the sync thread finished the snapshot last consumed (read from snapshot)
+    last_sync_time: Instant,
+    /// URL under the mouse as (row, start_col, end_col), underlined in the cached grid
+    hovered_link: Option<(usize, usize, usize)>,
+    /// Underline bits the hovered link overwrote, restored when the hover moves off
+    hovered_link_saved: Vec<bool>,
+    /// Bumped on hover changes; added to the snapshot generation
+    hover_generation: u64,
     /// Stay-at-bottom mode (shared with sync thread via atomic)
     stay_at_bottom: Arc<AtomicBool>,
     /// Dark/light mode (shared with sync thread via atomic)
     dark_mode: Arc<AtomicBool>,
-    /// Signal to sync thread: dark mode changed, force full re-render
+    /// Signal to sync thread: dark mode / theme changed, force full re-render
     dark_mode_changed: Arc<AtomicBool>,
+    /// Signal to sync thread: grid cleared or reset, force full re-render
+    full_resync: Arc<AtomicBool>,
+    /// Active theme (shared with listener and sync thread)
+    theme: Arc<Mutex<Theme>>,
     /// Mode 2031: app opted in to color-scheme notifications (shared with listener)
     mode_2031: Arc<AtomicBool>,
     /// Dirty flag (shared with PTY thread and sync thread)
     dirty: Arc<AtomicBool>,
     /// Shared waker callback — installed by main thread, called by sync thread
     waker: Arc<Mutex<Option<Box<dyn Fn() + Send>>>>,
-    /// Pending PTY resize notification (debounced to avoid SIGWINCH storms)
-    pending_pty_resize: Option<(WindowSize, Instant)>,
+    /// Sends the debounced PTY resize (SIGWINCH); `None` without a PTY
+    pty_resize: Option<resize::ResizeDebouncer>,
+    /// How long a size must hold before it reaches the PTY (see `set_resize_debounce`)
+    resize_debounce: Duration,
     /// Handle to sync thread for unparking
     sync_thread_handle: Arc<Mutex<Option<std::thread::Thread>>>,
     /// Shutdown flag for sync thread
     sync_shutdown: Arc<AtomicBool>,
+    /// Power-save idle state (shared with listener and sync thread)
+    power_save: Arc<PowerSave>,
+    /// Completed sync cycles (incremented by sync thread)
+    sync_count: Arc<AtomicU64>,
+    /// Sync timing (recorded by sync thread)
+    #[cfg(feature = "metrics")]
+    sync_metrics: Arc<metrics::SyncRecorder>,
+    /// Alt/Option+char sends ESC + char (Meta) instead of the composed character
+    option_as_meta: bool,
+    /// Chunks and throttles `write` data to the PTY (None without a PTY)
+    input_writer: Option<input_writer::InputWriter>,
+    /// Controls stripped from `write` / `paste` / `write_text` (see `set_write_filter`)
+    write_filter: WriteFilter,
+    /// Shared with the listener: accept OSC 52 clipboard sets
+    allow_osc52: Arc<AtomicBool>,
+    /// Shared with the listener: pending OSC 52 clipboard text
+    clipboard_request: Arc<Mutex<Option<String>>>,
+    /// Shared with the listener: pending OSC 0/2 title change
+    title_request: Arc<Mutex<Option<String>>>,
+    /// Extra word-constituent characters for `word_range_at`
+    word_chars: String,
+    /// Shared with the PTY reader: output paused (see `set_paused`)
+    pause: Arc<pause::OutputPause>,
+    /// Parser state for `feed`, kept so sequences may span calls
+    feed_processor: Processor<StdSyncHandler>,
+    /// OSC 133 / sixel scanner state for `feed`
+    feed_scanners: OutputScanners,
+    /// Shared with the output parser: sixel payloads awaiting `take_images`
+    images: Arc<sixel::ImageQueue>,
+    /// OSC 133 marks from the last consumed snapshot
+    prompt_marks: Vec<PromptMark>,
+    /// Input is dropped (see `set_read_only`)
+    read_only: bool,
+    /// `TIDE_PANE_ID` given to the shell; `None` without one
+    pane_env_id: Option<String>,
     /// Sync thread join handle (joined on Drop)
     _sync_join: Option<std::thread::JoinHandle<()>>,
 }
 
+/// The PTY side of a shell-backed terminal, handed to `Terminal::assemble`.
+struct PtyHandles {
+    notifier: Notifier,
+    child_pid: u32,
+    /// Duplicate of the PTY master (see `Terminal::pty_master`)
+    master: Option<OwnedFd>,
+    event_loop: EventLoopThread,
+}
+
+type EventLoopThread = std::thread::JoinHandle<(EventLoop<tap::TapPty, TermEventListener>, event_loop::State)>;
+
 impl Terminal {
     /// Create a new terminal backend with the given dimensions.
-    pub fn new(cols: u16, rows: u16) -> Result<Self, Box<dyn std::error::Error>> {
+    pub fn new(cols: u16, rows: u16) -> Result<Self, TerminalError> {
         Self::with_cwd(cols, rows, None, true)
     }
 
     /// Create a new terminal backend, optionally starting in the given directory.
-    pub fn with_cwd(cols: u16, rows: u16, cwd: Option<PathBuf>, dark_mode: bool) -> Result<Self, Box<dyn std::error::Error>> {
-        let cell_width = 8;
-        let cell_height = 16;
+    pub fn with_cwd(cols: u16, rows: u16, cwd: Option<PathBuf>, dark_mode: bool) -> Result<Self, TerminalError> {
+        Self::with_shell(cols, rows, cwd, dark_mode, None)
+    }
+
+    /// Create a new terminal backend running `shell` (a path or a name looked up
+    /// in `$PATH`). `None` uses `$SHELL`, falling back to zsh/bash.
+    pub fn with_shell(
+        cols: u16,
+        rows: u16,
+        cwd: Option<PathBuf>,
+        dark_mode: bool,
+        shell: Option<String>,
+    ) -> Result<Self, TerminalError> {
+        Self::with_options(cols, rows, TerminalOptions { cwd, shell, dark_mode, ..Default::default() })
+    }
+
+    /// Create a new terminal backend from `options`. The shell gets a
+    /// process-unique `TIDE_PANE_ID` (see `pane_env_id`) so shell configs
+    /// can tell panes apart.
+    pub fn with_options(cols: u16, rows: u16, options: TerminalOptions) -> Result<Self, TerminalError> {
+        let TerminalOptions { cwd, shell, dark_mode, env_extra } = options;
+        // Fail early with an actionable error instead of a PTY child that exits immediately.
+        let shell = shell.unwrap_or_else(Self::detect_shell);
+        if !shell_exists(&shell) {
+            return Err(TerminalError::ShellNotFound { shell });
+        }
 
         let window_size = WindowSize {
             num_cols: cols,
             num_lines: rows,
-            cell_width,
-            cell_height,
-        };
-
-        let term_size = TermDimensions::new(cols as usize, rows as usize);
-
-        let dirty = Arc::new(AtomicBool::new(true));
-        let pty_writer = Arc::new(Mutex::new(None));
-        let sync_thread_handle: Arc<Mutex<Option<std::thread::Thread>>> = Arc::new(Mutex::new(None));
-        let dark_mode_flag = Arc::new(AtomicBool::new(dark_mode));
-        let mode_2031_flag = Arc::new(AtomicBool::new(false));
-        let listener = TermEventListener {
-            dirty: dirty.clone(),
-            pty_writer: pty_writer.clone(),
-            sync_thread: sync_thread_handle.clone(),
-            dark_mode: dark_mode_flag.clone(),
-            mode_2031: mode_2031_flag.clone(),
+            cell_width: PTY_CELL_WIDTH,
+            cell_height: PTY_CELL_HEIGHT,
         };
-
-        let config = TermConfig::default();
-        let term = Term::new(config, &term_size, listener.clone());
-        let term = Arc::new(FairMutex::new(term));
-
-        // Determine the shell to use
-        let shell = Self::detect_shell();
+        let (term, listener) = Self::new_emulator(cols, rows, dark_mode);
 
         // Use provided cwd, or fall back to $HOME so .app bundles don't land in /
         let working_directory = cwd.or_else(|| std::env::var("HOME").ok().map(PathBuf::from));
-        let mut env = std::collections::HashMap::new();
+        let mut env = HashMap::new();
         env.insert(String::from("TERM"), String::from("xterm-256color"));
         env.insert(String::from("COLORTERM"), String::from("truecolor"));
         env.insert(String::from("PROMPT_EOL_MARK"), String::new());
@@ -652,6 +1290,10 @@ impl Terminal {
         } else {
             env.insert(String::from("COLORFGBG"), String::from("0;15"));
         }
+        let pane_env_id = NEXT_PANE_ENV_ID.fetch_add(1, Ordering::Relaxed).to_string();
+        env.insert(String::from("TIDE_PANE_ID"), pane_env_id);
+        env.extend(env_extra);
+        let pane_env_id = env.get("TIDE_PANE_ID").cloned();
         let pty_config = tty::Options {
             shell: Some(tty::Shell::new(shell, vec![String::from("--login")])),
             working_directory,
@@ -659,34 +1301,142 @@ impl Terminal {
             ..tty::Options::default()
         };
 
-        // Spawn the PTY
-        let pty = tty::new(&pty_config, window_size, 0)?;
+        // Allocate the PTY ourselves so allocation and spawn failures are distinguishable
+        let (master, slave) = open_pty(window_size).map_err(TerminalError::PtyAllocFailed)?;
+        let pty_master = master.try_clone().ok();
+        let pty = tty::from_fd(&pty_config, 0, master, slave).map_err(TerminalError::Spawn)?;
 
         // Get child PID before moving pty into the event loop
         let child_pid = pty.child().id();
 
-        // Create the event loop that bridges PTY I/O with the terminal emulator
-        let event_loop = EventLoop::new(term.clone(), listener, pty, false, false)?;
+        // Create the event loop that bridges PTY I/O with the terminal emulator;
+        // it parses through `advance_emulator` to place prompt marks and
+        // capture sixel images
+        let pause = pause::OutputPause::new();
+        let images = Arc::new(sixel::ImageQueue::default());
+        let pty = tap::TapPty::new(pty, pause.clone())?;
+        // Drain on exit: output the shell left unread (say, while paused) is
+        // parsed before the loop ends
+        let mut event_loop = EventLoop::new(term.clone(), listener.clone(), pty, true, false)?;
+        let mut scanners = OutputScanners::new(images.clone());
+        event_loop.set_output_parser(Box::new(move |processor, term, data| {
+            advance_emulator(processor, &mut scanners, term, data);
+        }));
         let notifier = Notifier(event_loop.channel());
-        if let Ok(mut guard) = pty_writer.lock() {
+        if let Ok(mut guard) = listener.pty_writer.lock() {
             *guard = Some(Notifier(event_loop.channel()));
         }
-        event_loop.spawn();
+        let event_loop = event_loop.spawn();
+
+        let pty = PtyHandles { notifier, child_pid, master: pty_master, event_loop };
+        let mut terminal = Self::assemble(cols, rows, term, listener, pause, images, Some(pty));
+        terminal.pane_env_id = pane_env_id;
+        Ok(terminal)
+    }
+
+    /// Create a read-only terminal that displays whatever `reader` produces
+    /// (a log tail, another process's stdout) instead of running a shell.
+    /// A background thread feeds the bytes to the emulator until `reader`
+    /// reaches EOF or fails. There is no PTY, so `child_pid` is `None`.
+    pub fn from_reader<R: Read + Send + 'static>(reader: R, cols: u16, rows: u16) -> Self {
+        let (term, listener) = Self::new_emulator(cols, rows, true);
+        let pause = pause::OutputPause::new();
+        let images = Arc::new(sixel::ImageQueue::default());
+        {
+            let term = Arc::downgrade(&term);
+            let listener = listener.clone();
+            let pause = pause.clone();
+            let images = images.clone();
+            std::thread::Builder::new()
+                .name("term-reader".to_string())
+                .spawn(move || reader_thread_main(reader, term, listener, pause, images))
+                .expect("failed to spawn terminal reader thread");
+        }
+        let mut terminal = Self::assemble(cols, rows, term, listener, pause, images, None);
+        terminal.read_only = true;
+        terminal
+    }
+
+    /// The emulator and the listener its events go to, before an input
+    /// source is attached.
+    fn new_emulator(cols: u16, rows: u16, dark_mode: bool) -> (Arc<FairMutex<Term<TermEventListener>>>, TermEventListener) {
+        let term_size = TermDimensions::new(cols as usize, rows as usize);
+        let listener = TermEventListener {
+            dirty: Arc::new(AtomicBool::new(true)),
+            pty_writer: Arc::new(Mutex::new(None)),
+            sync_thread: Arc::new(Mutex::new(None)),
+            dark_mode: Arc::new(AtomicBool::new(dark_mode)),
+            mode_2031: Arc::new(AtomicBool::new(false)),
+            theme: Arc::new(Mutex::new(Theme::for_mode(dark_mode))),
+            power_save: Arc::new(PowerSave::new()),
+            allow_osc52: Arc::new(AtomicBool::new(false)),
+            clipboard_request: Arc::new(Mutex::new(None)),
+            title_request: Arc::new(Mutex::new(None)),
+        };
+        let config = TermConfig::default();
+        let term = Term::new(config, &term_size, listener.clone());
+        (Arc::new(FairMutex::new(term)), listener)
+    }
+
+    /// Spawn the grid sync thread and build the terminal around `term`.
+    /// `pty` is `None` for terminals without a shell (`from_reader`).
+    fn assemble(
+        cols: u16,
+        rows: u16,
+        term: Arc<FairMutex<Term<TermEventListener>>>,
+        listener: TermEventListener,
+        pause: Arc<pause::OutputPause>,
+        images: Arc<sixel::ImageQueue>,
+        pty: Option<PtyHandles>,
+    ) -> Self {
+        let TermEventListener {
+            dirty,
+            sync_thread: sync_thread_handle,
+            dark_mode: dark_mode_flag,
+            mode_2031: mode_2031_flag,
+            theme,
+            power_save,
+            allow_osc52,
+            clipboard_request,
+            title_request,
+            ..
+        } = listener;
+        let dark_mode = dark_mode_flag.load(Ordering::Relaxed);
+        let (notifier, child_pid, pty_master, event_loop) = match pty {
+            Some(pty) => (Some(pty.notifier), Some(pty.child_pid), pty.master, Some(pty.event_loop)),
+            None => (None, None, None, None),
+        };
+        let input_writer = notifier
+            .as_ref()
+            .map(|notifier| input_writer::InputWriter::new(notifier.0.clone(), DEFAULT_WRITE_CHUNK_SIZE));
+        let pty_resize = notifier.as_ref().map(|notifier| {
+            let sender = notifier.0.clone();
+            resize::ResizeDebouncer::new(move |size| {
+                let _ = sender.send(Msg::Resize(size));
+            })
+        });
 
         // Initialize shared state for the sync thread
         let cached_grid = Self::build_empty_grid(cols, rows);
         let stay_at_bottom = Arc::new(AtomicBool::new(false));
         let dark_mode_changed = Arc::new(AtomicBool::new(false));
+        let full_resync = Arc::new(AtomicBool::new(false));
         let snapshot_ready = Arc::new(AtomicBool::new(false));
         let sync_shutdown = Arc::new(AtomicBool::new(false));
         let waker: Arc<Mutex<Option<Box<dyn Fn() + Send>>>> = Arc::new(Mutex::new(None));
+        let sync_count = Arc::new(AtomicU64::new(0));
+        #[cfg(feature = "metrics")]
+        let sync_metrics = Arc::new(metrics::SyncRecorder::default());
 
         let snapshot = Arc::new(Mutex::new(SharedSnapshot {
             grid: Self::build_empty_grid(cols, rows),
             inverse_cursor: None,
             url_ranges: Vec::new(),
             generation: 0,
-            cursor: CursorState { row: 0, col: 0, visible: true, shape: CursorShape::Block },
+            cursor: CursorState { row: 0, col: 0, visible: true, shape: CursorShape::Block, color: None },
+            modes: TermModeSnapshot::default(),
+            prompt_marks: Vec::new(),
+            synced_at: Instant::now(),
         }));
 
         // Create the GridSyncer with all sync-related state
@@ -694,16 +1444,28 @@ impl Terminal {
             term: term.clone(),
             raw_buf: Vec::new(),
             prev_raw_buf: Vec::new(),
+            zerowidth_buf: Vec::new(),
+            prev_zerowidth_buf: Vec::new(),
             palette_buf: [None; 256],
             grid: Self::build_empty_grid(cols, rows),
             inverse_cursor: None,
-            cached_cursor: CursorState { row: 0, col: 0, visible: true, shape: CursorShape::Block },
+            cached_cursor: CursorState { row: 0, col: 0, visible: true, shape: CursorShape::Block, color: None },
+            modes: TermModeSnapshot::default(),
             url_ranges: Vec::new(),
             grid_generation: 0,
             url_row_buf: String::new(),
             dark_mode: dark_mode_flag.clone(),
             dark_mode_changed: dark_mode_changed.clone(),
+            theme: theme.clone(),
+            palette_theme: Theme::for_mode(dark_mode),
+            full_resync: full_resync.clone(),
             stay_at_bottom: stay_at_bottom.clone(),
+            power_save: power_save.clone(),
+            urls_stale: false,
+            sync_count: sync_count.clone(),
+            prompt_marks: Vec::new(),
+            #[cfg(feature = "metrics")]
+            metrics: sync_metrics.clone(),
         };
 
         // Spawn the grid sync thread
@@ -722,31 +1484,63 @@ impl Terminal {
                 .expect("failed to spawn grid sync thread")
         };
 
-        Ok(Terminal {
+        Terminal {
             term,
             notifier,
             cached_grid,
             current_dir: None,
+            detected_cwd: Mutex::new(None),
+            cwd_poll_interval: Some(DEFAULT_CWD_POLL_INTERVAL),
+            cwd_probe_count: AtomicU64::new(0),
             cols,
             rows,
-            child_pid: Some(child_pid),
+            child_pid,
+            pty_master,
+            event_loop,
             snapshot_ready,
             snapshot,
             inverse_cursor: None,
-            cached_cursor: CursorState { row: 0, col: 0, visible: true, shape: CursorShape::Block },
+            cached_cursor: CursorState { row: 0, col: 0, visible: true, shape: CursorShape::Block, color: None },
+            cached_modes: TermModeSnapshot::default(),
+            cursor_style: CursorStyleConfig::default(),
             url_ranges: Vec::new(),
             grid_generation: 0,
+            last_sync_time: Instant::now(),
+            hovered_link: None,
+            hovered_link_saved: Vec::new(),
+            hover_generation: 0,
             stay_at_bottom,
             dark_mode: dark_mode_flag,
             dark_mode_changed,
+            full_resync,
+            theme,
             mode_2031: mode_2031_flag,
             dirty,
             waker,
-            pending_pty_resize: None,
+            pty_resize,
+            resize_debounce: DEFAULT_RESIZE_DEBOUNCE,
             sync_thread_handle,
             sync_shutdown,
+            power_save,
+            sync_count,
+            #[cfg(feature = "metrics")]
+            sync_metrics,
+            option_as_meta: false,
+            input_writer,
+            write_filter: WriteFilter::NONE,
+            allow_osc52,
+            clipboard_request,
+            title_request,
+            word_chars: String::new(),
+            pause,
+            feed_processor: Processor::new(),
+            feed_scanners: OutputScanners::new(images.clone()),
+            images,
+            prompt_marks: Vec::new(),
+            read_only: false,
+            pane_env_id: None,
             _sync_join: Some(sync_join),
-        })
+        }
     }
 
     /// Detect the user's preferred shell
@@ -775,6 +1569,7 @@ impl Terminal {
     /// Detect the CWD of the child process using native OS APIs (no subprocess).
     #[cfg(target_os = "macos")]
     pub fn detect_cwd_fallback(&self) -> Option<PathBuf> {
+        self.cwd_probe_count.fetch_add(1, Ordering::Relaxed);
         let pid = self.child_pid? as i32;
 
         const PROC_PIDVNODEPATHINFO: i32 = 9;
@@ -810,6 +1605,7 @@ impl Terminal {
 
     #[cfg(not(target_os = "macos"))]
     pub fn detect_cwd_fallback(&self) -> Option<PathBuf> {
+        self.cwd_probe_count.fetch_add(1, Ordering::Relaxed);
         if let Some(pid) = self.child_pid {
             let path = format!("/proc/{}/cwd", pid);
             std::fs::read_link(path).ok()
@@ -818,6 +1614,48 @@ impl Terminal {
         }
     }
 
+    /// Working directory as reported by the shell via OSC 7, if any.
+    /// Unlike `cwd()`, never falls back to inspecting the child process.
+    pub fn reported_cwd(&self) -> Option<PathBuf> {
+        self.current_dir.clone()
+    }
+
+    /// How often `cwd()` may inspect the child process when the shell hasn't
+    /// reported its directory (OSC 7): a detected CWD is reused for
+    /// `interval`. `None` disables polling; `cwd()` then only returns the
+    /// OSC 7 report or the last detected CWD. Defaults to
+    /// `DEFAULT_CWD_POLL_INTERVAL`.
+    pub fn set_cwd_poll_interval(&mut self, interval: Option<Duration>) {
+        self.cwd_poll_interval = interval;
+    }
+
+    pub fn cwd_poll_interval(&self) -> Option<Duration> {
+        self.cwd_poll_interval
+    }
+
+    /// Number of `detect_cwd_fallback` calls so far.
+    #[doc(hidden)]
+    pub fn cwd_probe_count(&self) -> u64 {
+        self.cwd_probe_count.load(Ordering::Relaxed)
+    }
+
+    /// `detect_cwd_fallback()`, reusing a result younger than the poll
+    /// interval (forever when polling is disabled).
+    fn detected_cwd(&self) -> Option<PathBuf> {
+        let mut cache = self.detected_cwd.lock().unwrap_or_else(|e| e.into_inner());
+        let Some(interval) = self.cwd_poll_interval else {
+            return cache.as_ref().map(|(_, path)| path.clone());
+        };
+        if let Some((at, ref path)) = *cache {
+            if at.elapsed() < interval {
+                return Some(path.clone());
+            }
+        }
+        let path = self.detect_cwd_fallback();
+        *cache = path.clone().map(|p| (Instant::now(), p));
+        path
+    }
+
     /// Unpark the sync thread so it processes pending dirty flags.
     fn notify_sync_thread(&self) {
         if let Ok(guard) = self.sync_thread_handle.lock() {
@@ -836,10 +1674,23 @@ impl Terminal {
             std::mem::swap(&mut self.cached_grid, &mut snap.grid);
             self.inverse_cursor = snap.inverse_cursor;
             std::mem::swap(&mut self.url_ranges, &mut snap.url_ranges);
+            std::mem::swap(&mut self.prompt_marks, &mut snap.prompt_marks);
             self.grid_generation = snap.generation;
+            self.last_sync_time = snap.synced_at;
             self.cached_cursor = snap.cursor;
+            self.cached_modes = snap.modes;
         }
         self.snapshot_ready.store(false, Ordering::Relaxed);
+
+        // The new grid has no hover underline; keep it only if the link is still there
+        self.hovered_link_saved.clear();
+        if let Some((row, start, end)) = self.hovered_link {
+            if self.url_ranges.get(row).is_some_and(|r| r.contains(&(start, end))) {
+                self.underline_hovered_link();
+            } else {
+                self.hovered_link = None;
+            }
+        }
     }
 }
 
@@ -853,12 +1704,37 @@ impl Terminal {
         }
     }
 
+    /// Drop all input written with `write` (keys, pastes) while the terminal
+    /// keeps displaying its output, for log-viewer panes.
+    pub fn set_read_only(&mut self, read_only: bool) {
+        self.read_only = read_only;
+    }
+
+    pub fn is_read_only(&self) -> bool {
+        self.read_only
+    }
+
+    /// Pause or resume consuming output, independent of the shell's own flow
+    /// control (Ctrl+S). While paused nothing more is read from the PTY (or
+    /// the `from_reader` source): the grid stops changing and the kernel
+    /// buffer fills until the program writing blocks. Input still reaches the
+    /// shell. On resume the held output is parsed and shown.
+    pub fn set_paused(&self, paused: bool) {
+        self.pause.set_paused(paused);
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.pause.is_paused()
+    }
+
     /// Returns the child PID of the shell process.
     pub fn child_pid(&self) -> Option<u32> {
         self.child_pid
     }
 
-    /// Check if the child shell process is still alive.
+    /// Check if the child shell process is still alive. A shell that exited
+    /// counts as alive until the PTY event loop has parsed the output it
+    /// left unread, so that output is on the grid before the exit shows.
     pub fn is_child_alive(&self) -> bool {
         let pid = match self.child_pid {
             Some(p) => p,
@@ -866,7 +1742,8 @@ impl Terminal {
         };
         // kill(pid, 0) checks if the process exists without sending a signal.
         // Returns 0 if alive, -1 with ESRCH if dead.
-        unsafe { libc::kill(pid as i32, 0) == 0 }
+        let running = unsafe { libc::kill(pid as i32, 0) == 0 };
+        running || self.event_loop.as_ref().is_some_and(|thread| !thread.is_finished())
     }
 
     /// Detect whether the shell is idle (no foreground child process running).
@@ -884,7 +1761,9 @@ impl Terminal {
                 (pids.len() * std::mem::size_of::<i32>()) as i32,
             )
         };
-        ret <= 0
+        let idle = ret <= 0;
+        self.power_save.shell_idle.store(idle, Ordering::Relaxed);
+        idle
     }
 
     #[cfg(not(target_os = "macos"))]
@@ -899,21 +1778,73 @@ impl Terminal {
             if fields.len() > 7 {
                 let pgrp = fields[4].parse::<i32>().unwrap_or(0);
                 let tpgid = fields[7].parse::<i32>().unwrap_or(-1);
-                return pgrp == tpgid;
+                let idle = pgrp == tpgid;
+                self.power_save.shell_idle.store(idle, Ordering::Relaxed);
+                return idle;
             }
         }
+        self.power_save.shell_idle.store(false, Ordering::Relaxed);
         false
     }
 
+    /// Name of the command in the PTY's foreground process group (e.g.
+    /// "vim", "cargo"); the shell's own name while it waits at the prompt.
+    /// `None` if the terminal has exited or the name can't be read.
+    pub fn foreground_command(&self) -> Option<String> {
+        let fd = self.pty_master.as_ref()?;
+        let pgrp = unsafe { libc::tcgetpgrp(fd.as_raw_fd()) };
+        if pgrp <= 0 {
+            return None;
+        }
+        // The group id is the pid of the job's leader (the first command of
+        // a pipeline)
+        process_name(pgrp)
+    }
+
+    /// Enable or disable power-save mode. While enabled, once the shell is
+    /// idle (as last observed by `is_shell_idle()`) and no output has arrived
+    /// for a couple of seconds, no-op wakes are dropped so the sync thread
+    /// stays parked, and URL detection is suspended until the next output.
+    pub fn set_power_save(&self, enabled: bool) {
+        self.power_save.enabled.store(enabled, Ordering::Relaxed);
+    }
+
+    /// Whether power-save mode currently considers this terminal idle.
+    pub fn is_power_save_idle(&self) -> bool {
+        self.power_save.is_idle()
+    }
+
+    /// Number of grid sync cycles the sync thread has completed.
+    #[doc(hidden)]
+    pub fn sync_count(&self) -> u64 {
+        self.sync_count.load(Ordering::Relaxed)
+    }
+
+    /// Sync timing recorded since the last call (requires the `metrics`
+    /// feature). Counters reset on every call.
+    #[cfg(feature = "metrics")]
+    pub fn take_sync_metrics(&self) -> SyncMetrics {
+        self.sync_metrics.take()
+    }
+
     /// Returns true if the sync thread has produced a new snapshot since the
     /// last `process()` call.
     pub fn has_new_output(&self) -> bool {
         self.snapshot_ready.load(Ordering::Relaxed)
     }
 
-    /// Returns the grid generation counter. Increments when grid content changes.
+    /// Returns the grid generation counter. Increments when grid content
+    /// (or the hovered link underline) changes.
     pub fn grid_generation(&self) -> u64 {
-        self.grid_generation
+        self.grid_generation.wrapping_add(self.hover_generation)
+    }
+
+    /// When the sync thread finished the snapshot shown since the last
+    /// `process()` (creation time before the first one). Compared with the
+    /// time input was sent, this gives input-to-snapshot latency; a stamp
+    /// older than the frame being built marks a stale snapshot.
+    pub fn last_sync_time(&self) -> Instant {
+        self.last_sync_time
     }
 
     /// Force a sync_grid cycle for benchmarking purposes.
@@ -930,13 +1861,13 @@ impl Terminal {
     }
 
     /// Inject bytes directly into the terminal emulator for benchmarking.
-    /// Bypasses the PTY — feeds data straight into vte::ansi::Processor → Term.
+    /// Bypasses the PTY — feeds data straight into vte::ansi::Processor → Term
+    /// (through `advance_emulator`, like PTY reads).
     #[doc(hidden)]
     pub fn bench_write_to_term(&self, data: &[u8]) {
-        use alacritty_terminal::vte::ansi::{Processor, StdSyncHandler};
         let mut processor: Processor<StdSyncHandler> = Processor::new();
-        let mut term = self.term.lock();
-        processor.advance(&mut *term, data);
+        let mut scanners = OutputScanners::new(self.images.clone());
+        advance_emulator(&mut processor, &mut scanners, &mut self.term.lock(), data);
     }
 
     /// Returns detected URL column ranges per row.
@@ -944,6 +1875,49 @@ impl Terminal {
         &self.url_ranges
     }
 
+    /// Mouse is over screen cell `(row, col)`, or off the grid with `None`.
+    /// A cell inside a detected URL underlines the whole URL until the hover
+    /// moves off it. Returns true if the hovered link changed.
+    pub fn set_hovered_cell(&mut self, cell: Option<(usize, usize)>) -> bool {
+        let link = cell.and_then(|(row, col)| {
+            let &(start, end) = self.url_ranges.get(row)?.iter().find(|&&(s, e)| col >= s && col < e)?;
+            Some((row, start, end))
+        });
+        if link == self.hovered_link {
+            return false;
+        }
+        self.restore_hovered_link();
+        self.hovered_link = link;
+        self.underline_hovered_link();
+        self.hover_generation += 1;
+        true
+    }
+
+    /// The URL under the mouse as (row, start_col, end_col).
+    pub fn hovered_link(&self) -> Option<(usize, usize, usize)> {
+        self.hovered_link
+    }
+
+    fn underline_hovered_link(&mut self) {
+        let Some((row, start, end)) = self.hovered_link else { return };
+        let Some(cells) = self.cached_grid.cells.get_mut(row) else { return };
+        let end = end.min(cells.len());
+        self.hovered_link_saved = cells[start.min(end)..end]
+            .iter_mut()
+            .map(|c| std::mem::replace(&mut c.style.underline, true))
+            .collect();
+    }
+
+    fn restore_hovered_link(&mut self) {
+        let saved = std::mem::take(&mut self.hovered_link_saved);
+        let Some((row, start, _)) = self.hovered_link else { return };
+        if let Some(cells) = self.cached_grid.cells.get_mut(row) {
+            for (cell, underline) in cells.iter_mut().skip(start).zip(saved) {
+                cell.style.underline = underline;
+            }
+        }
+    }
+
     /// Returns the current column count.
     pub fn current_cols(&self) -> u16 {
         self.cols
@@ -992,6 +1966,49 @@ impl Terminal {
         results
     }
 
+    /// Extract text between two `(absolute_line, col)` points (same coordinates as
+    /// `search_buffer`). `end` is exclusive on its line. Trailing spaces are trimmed
+    /// on every line that ends with a newline; wide-char spacers are skipped.
+    pub fn text_in_range(&self, start: (usize, usize), end: (usize, usize)) -> String {
+        let term = self.term.lock();
+        grid_text_in_range(term.grid(), start, end)
+    }
+
+    /// Characters treated as part of a word in addition to alphanumerics and
+    /// `_` (e.g. `"/.-"` so double-click selects whole paths and URLs).
+    pub fn set_word_chars(&mut self, chars: &str) {
+        self.word_chars = chars.to_string();
+    }
+
+    pub fn word_chars(&self) -> &str {
+        &self.word_chars
+    }
+
+    /// Column range `(start, end)` of the word under `(abs_line, col)`, in the
+    /// `text_in_range` coordinates (`end` exclusive). A non-word character
+    /// yields just its own cell. Backs double-click word selection.
+    pub fn word_range_at(&self, abs_line: usize, col: usize) -> (usize, usize) {
+        let term = self.term.lock();
+        grid_word_range_at(term.grid(), abs_line, col, &self.word_chars)
+    }
+
+    /// Export the terminal contents as plain text (colors and attributes are
+    /// dropped), for "save output to file". Covers history + screen, or just
+    /// the screen, with trailing whitespace trimmed per line and joined by `\n`.
+    pub fn export_text(&self, include_scrollback: bool) -> String {
+        let term = self.term.lock();
+        grid_export_text(term.grid(), include_scrollback)
+    }
+
+    /// Absolute line (0 = oldest history line) shown in the top screen row.
+    /// Converts screen rows to the coordinates used by `search_buffer` and
+    /// `text_in_range`.
+    pub fn first_visible_line(&self) -> usize {
+        let term = self.term.lock();
+        let grid = term.grid();
+        grid.history_size().saturating_sub(grid.display_offset())
+    }
+
     /// Get the current display offset (how many lines scrolled up into history).
     pub fn display_offset(&self) -> usize {
         let term = self.term.lock();
@@ -1004,40 +2021,273 @@ impl Terminal {
         term.grid().history_size()
     }
 
-    /// Check if the terminal has bracketed paste mode enabled.
-    pub fn is_bracketed_paste_mode(&self) -> bool {
-        let term = self.term.lock();
-        term.mode().contains(TermMode::BRACKETED_PASTE)
+    /// The `TIDE_PANE_ID` the shell was started with (`None` for terminals
+    /// without a shell, see `from_reader`).
+    pub fn pane_env_id(&self) -> Option<&str> {
+        self.pane_env_id.as_deref()
+    }
+
+    /// Terminal modes (bracketed paste, alt screen, mouse reporting, ...) as of
+    /// the last consumed grid snapshot. Lock-free, and consistent with the grid.
+    pub fn modes(&self) -> TermModeSnapshot {
+        self.cached_modes
+    }
+
+    /// How long the size must stay unchanged after `resize` before the PTY is
+    /// told (SIGWINCH), default `DEFAULT_RESIZE_DEBOUNCE`. Applies from the
+    /// next `resize`. The notification is sent on time even when the app
+    /// loop is idle.
+    pub fn set_resize_debounce(&mut self, interval: Duration) {
+        self.resize_debounce = interval;
+    }
+
+    pub fn resize_debounce(&self) -> Duration {
+        self.resize_debounce
+    }
+
+    /// The `(cols, rows)` the PTY will be told once the resize debounce
+    /// flushes, or `None` when the shell already knows the grid size
+    /// (`current_cols` / `current_rows`). Until then a TUI may still draw for
+    /// its old size.
+    pub fn pending_pty_size(&self) -> Option<(u16, u16)> {
+        let size = self.pty_resize.as_ref()?.pending()?;
+        Some((size.num_cols, size.num_lines))
+    }
+
+    /// Whether a `resize` is still waiting for its debounced SIGWINCH.
+    pub fn resize_pending(&self) -> bool {
+        self.pending_pty_size().is_some()
+    }
+
+    /// Set the max bytes per PTY input message (clamped to at least 1).
+    pub fn set_write_chunk_size(&mut self, size: usize) {
+        if let Some(writer) = &self.input_writer {
+            writer.set_chunk_size(size);
+        }
+    }
+
+    /// Bytes passed to `write` that have not reached the PTY yet: queued
+    /// behind a child that isn't reading its input, or still in flight.
+    pub fn input_backlog(&self) -> usize {
+        let queued = self.input_writer.as_ref().map_or(0, |w| w.queued_bytes());
+        let in_flight = self.notifier.as_ref().map_or(0, |n| n.0.pending_input());
+        queued + in_flight
+    }
+
+    /// Set which control characters `write`, `paste` and `write_text` strip,
+    /// and whether multi-line pastes need confirmation. Keystrokes
+    /// (`write_keystroke`) are never filtered. Default `WriteFilter::NONE`.
+    pub fn set_write_filter(&mut self, filter: WriteFilter) {
+        self.write_filter = filter;
+    }
+
+    pub fn write_filter(&self) -> WriteFilter {
+        self.write_filter
+    }
+
+    /// Whether `paste(text)` should be confirmed by the user first (the
+    /// filter's `confirm_on_newline` and a line break in the filtered text).
+    pub fn paste_needs_confirmation(&self, text: &str) -> bool {
+        self.write_filter.needs_confirmation(text)
+    }
+
+    /// Write text that did not come from a keystroke (dictation, injected
+    /// commits), with the write filter's controls stripped.
+    pub fn write_text(&mut self, text: &str) {
+        self.write(text.as_bytes());
+    }
+
+    /// Write bytes the user typed (an encoded key, an IME commit) as they
+    /// are: the write filter would drop Enter and Ctrl+C.
+    pub fn write_keystroke(&mut self, data: &[u8]) {
+        self.write_raw(data);
+    }
+
+    fn write_raw(&mut self, data: &[u8]) {
+        if self.read_only {
+            return;
+        }
+        if let Some(writer) = &self.input_writer {
+            writer.write(data);
+        }
+    }
+
+    /// Rewrap lines on a column change (default on). Off, narrowing the
+    /// terminal clips long lines and widening doesn't rejoin wrapped ones,
+    /// which keeps fixed-width logs and ASCII art intact.
+    pub fn set_reflow(&self, reflow: bool) {
+        self.term.lock().set_reflow(reflow);
+    }
+
+    pub fn reflow(&self) -> bool {
+        self.term.lock().reflow()
+    }
+
+    /// Set how the cursor is painted: reverse video or a solid color, and the
+    /// color to use when the app hasn't set one via OSC 12.
+    pub fn set_cursor_style(&mut self, style: CursorStyleConfig) {
+        self.cursor_style = style;
+    }
+
+    pub fn cursor_style(&self) -> CursorStyleConfig {
+        self.cursor_style
+    }
+
+    /// The cursor color to paint: the OSC 12 color if the app set one,
+    /// else the configured fallback.
+    pub fn cursor_color(&self) -> Color {
+        self.cached_cursor.color.unwrap_or(self.cursor_style.fallback_color)
+    }
+
+    /// Allow programs in the terminal to set the system clipboard via OSC 52
+    /// (default off). Disabling also drops any request not yet taken.
+    pub fn set_allow_osc52(&self, allow: bool) {
+        self.allow_osc52.store(allow, Ordering::Relaxed);
+        if !allow {
+            if let Ok(mut req) = self.clipboard_request.lock() {
+                *req = None;
+            }
+        }
+    }
+
+    pub fn allow_osc52(&self) -> bool {
+        self.allow_osc52.load(Ordering::Relaxed)
+    }
+
+    /// Take the latest OSC 52 clipboard-set text (already base64-decoded).
+    /// Only the most recent request is kept.
+    pub fn take_clipboard_request(&self) -> Option<String> {
+        self.clipboard_request.lock().ok()?.take()
+    }
+
+    /// Take the latest window title a program set with OSC 0/2; an empty
+    /// string means it reset the title. Only the most recent change is kept.
+    pub fn take_title_request(&self) -> Option<String> {
+        self.title_request.lock().ok()?.take()
+    }
+
+    /// Semantic prompt marks (OSC 133 A/B/C/D) the shell emitted, oldest
+    /// first, as of the last synced snapshot. Lines count from the top of the
+    /// scrollback. Each mark is kept on the row the cursor was on when the
+    /// shell emitted it, so it follows that row through scrolling and reflow
+    /// and is dropped with it (scrollback cap, clear, reset). At most
+    /// `MAX_PROMPT_MARKS` are kept.
+    pub fn prompt_marks(&self) -> &[PromptMark] {
+        &self.prompt_marks
+    }
+
+    /// Take the inline images (sixel) captured since the last call, oldest
+    /// first, decoding them now. At most `MAX_PENDING_IMAGES` (16) wait
+    /// between calls; older ones are dropped undecoded.
+    pub fn take_images(&self) -> Vec<InlineImage> {
+        self.images.take()
+    }
+
+    /// Paste clipboard text: truncated to `MAX_PASTE_BYTES` with a warning,
+    /// stripped of the write filter's controls, wrapped in bracketed-paste
+    /// markers when the app enabled them, and written in chunks. Returns true
+    /// if the text was truncated.
+    pub fn paste(&mut self, text: &str) -> bool {
+        let mut end = text.len().min(MAX_PASTE_BYTES);
+        while !text.is_char_boundary(end) {
+            end -= 1;
+        }
+        let truncated = end < text.len();
+        if truncated {
+            log::warn!("Paste of {} bytes truncated to {} bytes", text.len(), end);
+        }
+        let filter = self.write_filter;
+        let text = filter.apply(&text[..end]);
+
+        let mut data = Vec::with_capacity(text.len() + 16);
+        if self.cached_modes.bracketed_paste() {
+            data.extend_from_slice(b"\x1b[200~");
+            // Sanitize: strip the bracket-close sequence from clipboard text
+            // to prevent pastejacking attacks that escape bracketed paste mode.
+            data.extend_from_slice(text.replace("\x1b[201~", "").as_bytes());
+            data.extend_from_slice(b"\x1b[201~");
+            // Nudge shell to redraw and clear paste standout (left + right
+            // arrow = net-zero cursor move that triggers zsh/bash/fish to
+            // re-render without the INVERSE highlight on pasted text).
+            data.extend_from_slice(b"\x1b[D\x1b[C");
+        } else {
+            data.extend_from_slice(text.as_bytes());
+        }
+        // Already filtered; the bracket markers must survive.
+        self.write_raw(&data);
+        truncated
     }
 
     /// Set dark/light mode for the terminal color palette.
-    /// Signals the sync thread to force a full grid re-render.
-    /// The listener's `dark_mode` atomic is shared, so subsequent OSC 10/11
-    /// queries from apps will automatically return the updated colors.
-    ///
-    /// If Mode 2031 is enabled (app opted in via CSI ? 2031 h), sends a
-    /// color-scheme notification (CSI ? 997 ; N n) so the app can auto-switch.
+    /// Switches to the built-in `Theme::dark()` / `Theme::light()`; see `set_theme`.
     pub fn set_dark_mode(&mut self, dark: bool) {
         if self.dark_mode.load(Ordering::Relaxed) != dark {
-            self.dark_mode.store(dark, Ordering::Relaxed);
-            self.dark_mode_changed.store(true, Ordering::Relaxed);
-            self.dirty.store(true, Ordering::Relaxed);
-            self.notify_sync_thread();
+            self.set_theme(&Theme::for_mode(dark));
+        }
+    }
 
-            // Send Mode 2031 notification only if the app opted in.
-            if self.mode_2031.load(Ordering::Relaxed) {
-                let mode = if dark { 1 } else { 2 };
-                let _ = self.notifier.0.send(Msg::Input(
+    /// Override the terminal palette (ANSI 16, foreground, OSC 11 background)
+    /// with the given theme. Signals the sync thread to force a full grid
+    /// re-render; the listener shares the theme, so subsequent OSC 4/10/11
+    /// queries from apps return the updated colors.
+    ///
+    /// If the dark/light mode flips and Mode 2031 is enabled (app opted in via
+    /// CSI ? 2031 h), sends a color-scheme notification (CSI ? 997 ; N n) so
+    /// the app can auto-switch.
+    pub fn set_theme(&mut self, theme: &Theme) {
+        if let Ok(mut guard) = self.theme.lock() {
+            *guard = theme.clone();
+        }
+        let mode_changed = self.dark_mode.swap(theme.dark, Ordering::Relaxed) != theme.dark;
+        self.dark_mode_changed.store(true, Ordering::Relaxed);
+        self.dirty.store(true, Ordering::Relaxed);
+        self.notify_sync_thread();
+
+        // Send Mode 2031 notification only if the app opted in.
+        if mode_changed && self.mode_2031.load(Ordering::Relaxed) {
+            let mode = if theme.dark { 1 } else { 2 };
+            if let Some(notifier) = &self.notifier {
+                let _ = notifier.0.send(Msg::Input(
                     Cow::Owned(format!("\x1b[?997;{}n", mode).into_bytes()),
                 ));
             }
         }
     }
 
+    /// Drop all scrollback history, keeping the visible screen (Cmd+K).
+    pub fn clear_scrollback(&mut self) {
+        {
+            let mut term = self.term.lock();
+            term.clear_screen(ClearMode::Saved);
+        }
+        self.force_full_resync();
+    }
+
+    /// Hard-reset the terminal (RIS): clears screen, history, modes and
+    /// charsets, as if the shell had printed `ESC c`.
+    pub fn reset(&mut self) {
+        {
+            let mut term = self.term.lock();
+            term.reset_state();
+        }
+        self.force_full_resync();
+    }
+
+    /// Make the sync thread re-render every cell on its next pass.
+    fn force_full_resync(&self) {
+        self.full_resync.store(true, Ordering::Relaxed);
+        self.dirty.store(true, Ordering::Relaxed);
+        self.notify_sync_thread();
+    }
+
     /// Enter stay-at-bottom mode: every sync_grid will scroll to bottom until
     /// the user explicitly scrolls away via scroll_display().
     pub fn request_scroll_to_bottom(&mut self) {
-        self.stay_at_bottom.store(true, Ordering::Relaxed);
+        let was_at_bottom = self.stay_at_bottom.swap(true, Ordering::Relaxed);
+        // Power-save idle: already pinned to the bottom, nothing to sync
+        if was_at_bottom && self.power_save.is_idle() && self.display_offset() == 0 {
+            return;
+        }
         self.dirty.store(true, Ordering::Relaxed);
         self.notify_sync_thread();
     }
@@ -1062,18 +2312,17 @@ impl Terminal {
 
 impl TerminalBackend for Terminal {
     fn write(&mut self, data: &[u8]) {
-        let _ = self.notifier.0.send(Msg::Input(Cow::Owned(data.to_vec())));
+        let filter = self.write_filter;
+        self.write_raw(&filter.apply_bytes(data));
     }
 
-    fn process(&mut self) {
-        // Flush debounced PTY resize if 50ms have elapsed
-        if let Some((window_size, stamp)) = self.pending_pty_resize {
-            if stamp.elapsed().as_millis() >= 50 {
-                self.pending_pty_resize = None;
-                let _ = self.notifier.0.send(Msg::Resize(window_size));
-            }
-        }
+    fn feed(&mut self, bytes: &[u8]) {
+        advance_emulator(&mut self.feed_processor, &mut self.feed_scanners, &mut self.term.lock(), bytes);
+        self.dirty.store(true, Ordering::Relaxed);
+        self.notify_sync_thread();
+    }
 
+    fn process(&mut self) {
         // Consume the latest snapshot from the sync thread (cheap: just pointer swaps)
         self.consume_snapshot();
     }
@@ -1093,8 +2342,8 @@ impl TerminalBackend for Terminal {
         self.cols = cols;
         self.rows = rows;
 
-        let cell_width = 8;
-        let cell_height = 16;
+        let cell_width = PTY_CELL_WIDTH;
+        let cell_height = PTY_CELL_HEIGHT;
 
         let window_size = WindowSize {
             num_cols: cols,
@@ -1107,19 +2356,24 @@ impl TerminalBackend for Terminal {
 
         {
             let mut term = self.term.lock();
-            term.resize(term_size);
+            term_resize_keeping_scroll(&mut term, term_size);
         }
 
-        // Debounce PTY resize notification (SIGWINCH) to avoid prompt artifacts
-        self.pending_pty_resize = Some((window_size, Instant::now()));
+        // Debounce PTY resize notification (SIGWINCH) to avoid prompt artifacts;
+        // the timer thread sends it even if `process()` isn't called again
+        if let Some(pty_resize) = &self.pty_resize {
+            pty_resize.schedule(window_size, Instant::now() + self.resize_debounce);
+        }
 
         // Trigger a sync so the grid reflects the new dimensions promptly
         self.dirty.store(true, Ordering::Relaxed);
         self.notify_sync_thread();
     }
 
+    /// OSC 7 directory when the shell reports one, else the child process's
+    /// actual CWD (reused for the `set_cwd_poll_interval` interval).
     fn cwd(&self) -> Option<PathBuf> {
-        self.current_dir.clone()
+        self.reported_cwd().or_else(|| self.detected_cwd())
     }
 
     fn cursor(&self) -> CursorState {
@@ -1183,8 +2437,9 @@ impl Drop for Terminal {
         }
 
         // Signal the PTY event loop to shut down
-        #[allow(unused)]
-        let _ = self.notifier.0.send(Msg::Shutdown);
+        if let Some(notifier) = &self.notifier {
+            let _ = notifier.0.send(Msg::Shutdown);
+        }
     }
 }
 
@@ -0,0 +1,72 @@
+// Sync thread timing (feature "metrics")
+//
+// The sync thread records into `SyncRecorder` with relaxed atomics only, so
+// instrumentation never adds a lock to the hot path. `Terminal::take_sync_metrics`
+// swaps the counters out and returns them as a `SyncMetrics` snapshot.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+/// Grid sync timing accumulated since the last `Terminal::take_sync_metrics`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SyncMetrics {
+    /// Completed sync cycles.
+    pub syncs: u64,
+    /// Wall time spent in sync cycles, in total and for the slowest one.
+    pub total_sync: Duration,
+    pub max_sync: Duration,
+    /// Time the `Term` lock was held while copying cells, total and worst.
+    pub total_lock_hold: Duration,
+    pub max_lock_hold: Duration,
+    /// Cells converted to `TerminalCell` (unchanged cells are skipped).
+    pub cells_converted: u64,
+}
+
+impl SyncMetrics {
+    /// Average wall time of one sync cycle.
+    pub fn mean_sync(&self) -> Duration {
+        if self.syncs == 0 {
+            Duration::ZERO
+        } else {
+            self.total_sync / self.syncs as u32
+        }
+    }
+}
+
+/// Lock-free accumulator written by the sync thread.
+#[derive(Debug, Default)]
+pub(crate) struct SyncRecorder {
+    syncs: AtomicU64,
+    sync_nanos: AtomicU64,
+    max_sync_nanos: AtomicU64,
+    lock_nanos: AtomicU64,
+    max_lock_nanos: AtomicU64,
+    cells: AtomicU64,
+}
+
+impl SyncRecorder {
+    /// Record one sync cycle.
+    pub(crate) fn record(&self, sync: Duration, lock_hold: Duration, cells_converted: u64) {
+        let sync = sync.as_nanos() as u64;
+        let lock_hold = lock_hold.as_nanos() as u64;
+        self.syncs.fetch_add(1, Ordering::Relaxed);
+        self.sync_nanos.fetch_add(sync, Ordering::Relaxed);
+        self.max_sync_nanos.fetch_max(sync, Ordering::Relaxed);
+        self.lock_nanos.fetch_add(lock_hold, Ordering::Relaxed);
+        self.max_lock_nanos.fetch_max(lock_hold, Ordering::Relaxed);
+        self.cells.fetch_add(cells_converted, Ordering::Relaxed);
+    }
+
+    /// Return everything recorded so far and reset the counters.
+    pub(crate) fn take(&self) -> SyncMetrics {
+        let nanos = |counter: &AtomicU64| Duration::from_nanos(counter.swap(0, Ordering::Relaxed));
+        SyncMetrics {
+            syncs: self.syncs.swap(0, Ordering::Relaxed),
+            total_sync: nanos(&self.sync_nanos),
+            max_sync: nanos(&self.max_sync_nanos),
+            total_lock_hold: nanos(&self.lock_nanos),
+            max_lock_hold: nanos(&self.max_lock_nanos),
+            cells_converted: self.cells.swap(0, Ordering::Relaxed),
+        }
+    }
+}
@@ -0,0 +1,124 @@
+// Output pause (`Terminal::set_paused`): while paused nothing more is read, so
+// the kernel buffer fills and the writer blocks, like Ctrl+S but independent of
+// the shell's own flow control. Unread output is parsed once resumed.
+//
+// The PTY event loop polls level-triggered, so the tap can't just skip reads:
+// the fd would stay readable and spin the loop. Instead the read interest is
+// dropped from the poller registration (writes to the shell keep going), and
+// restored on resume. The reader thread of `Terminal::from_reader` waits on a
+// condvar instead. Neither blocks while holding the term lock.
+
+use std::io;
+use std::os::fd::{BorrowedFd, RawFd};
+use std::sync::{Arc, Condvar, Mutex};
+use std::time::Duration;
+
+use polling::{Event, PollMode, Poller};
+
+/// How often a paused reader thread checks whether its terminal still exists.
+const READER_RECHECK: Duration = Duration::from_millis(100);
+
+/// Pause flag shared by the `Terminal`, the PTY tap and the reader thread.
+#[derive(Default)]
+pub(crate) struct OutputPause {
+    state: Mutex<PauseState>,
+    resumed: Condvar,
+}
+
+#[derive(Default)]
+struct PauseState {
+    paused: bool,
+    registration: Option<Registration>,
+}
+
+/// The PTY fd's poller registration as the event loop last requested it.
+struct Registration {
+    poller: Arc<Poller>,
+    fd: RawFd,
+    interest: Event,
+    mode: PollMode,
+}
+
+impl Registration {
+    fn apply(&self, paused: bool) -> io::Result<()> {
+        // SAFETY: the registration is dropped (under the same lock) before
+        // the PTY deregisters, so `fd` is still open here.
+        let fd = unsafe { BorrowedFd::borrow_raw(self.fd) };
+        self.poller.modify_with_mode(fd, masked(self.interest, paused), self.mode)
+    }
+}
+
+/// `interest` without read interest while paused.
+fn masked(mut interest: Event, paused: bool) -> Event {
+    interest.readable &= !paused;
+    interest
+}
+
+impl OutputPause {
+    pub(crate) fn new() -> Arc<Self> {
+        Arc::new(Self::default())
+    }
+
+    pub(crate) fn is_paused(&self) -> bool {
+        self.state.lock().is_ok_and(|s| s.paused)
+    }
+
+    /// Pause or resume. Updates the PTY's poller registration and wakes a
+    /// waiting reader thread.
+    pub(crate) fn set_paused(&self, paused: bool) {
+        let Ok(mut state) = self.state.lock() else { return };
+        if state.paused == paused {
+            return;
+        }
+        state.paused = paused;
+        if let Some(registration) = &state.registration {
+            if let Err(e) = registration.apply(paused) {
+                log::warn!("failed to update PTY read interest: {e}");
+            }
+        }
+        if !paused {
+            self.resumed.notify_all();
+        }
+    }
+
+    /// (Re)register the PTY fd through `register` with `interest` masked by
+    /// the pause flag, remembering it so `set_paused` can update it.
+    pub(crate) fn register(
+        &self,
+        poller: &Arc<Poller>,
+        fd: RawFd,
+        interest: Event,
+        mode: PollMode,
+        register: impl FnOnce(Event) -> io::Result<()>,
+    ) -> io::Result<()> {
+        let Ok(mut state) = self.state.lock() else {
+            return register(interest);
+        };
+        register(masked(interest, state.paused))?;
+        state.registration = Some(Registration { poller: poller.clone(), fd, interest, mode });
+        Ok(())
+    }
+
+    /// Forget the registration before the PTY leaves the poller.
+    pub(crate) fn deregister(&self) {
+        if let Ok(mut state) = self.state.lock() {
+            state.registration = None;
+        }
+    }
+
+    /// Block while paused. Returns false once `alive` reports the terminal
+    /// is gone, so a paused reader thread still exits.
+    pub(crate) fn wait_while_paused(&self, alive: impl Fn() -> bool) -> bool {
+        let Ok(mut state) = self.state.lock() else { return true };
+        while state.paused {
+            if !alive() {
+                return false;
+            }
+            state = match self.resumed.wait_timeout(state, READER_RECHECK) {
+                Ok((state, _)) => state,
+                Err(_) => return true,
+            };
+        }
+        true
+    }
+}
@@ -0,0 +1,146 @@
+// Semantic prompt marks: alacritty's parser drops OSC 133, so PTY output is
+// parsed through `PromptScanner` (see `advance_emulator`). At the end of each
+// marker the parse stops and the marker is tagged onto the cursor's grid row,
+// where it follows the line through scrolling, the scrollback cap and reflow.
+// The sync thread collects the tags from the rows.
+
+/// Longer OSC payloads can't be a prompt marker and are not buffered.
+const MAX_PAYLOAD_BYTES: usize = 64;
+
+/// Which point of a shell command cycle an OSC 133 marker announces.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PromptMarkKind {
+    /// `OSC 133 ; A`: the shell starts drawing the prompt.
+    PromptStart,
+    /// `OSC 133 ; B`: the prompt ends and the user types the command.
+    CommandStart,
+    /// `OSC 133 ; C`: the command runs; its output follows.
+    OutputStart,
+    /// `OSC 133 ; D [; exit]`: the command finished.
+    CommandEnd { exit_code: Option<i32> },
+}
+
+impl PromptMarkKind {
+    /// Encode as a row tag (see `Row::push_mark`): the kind in the high word,
+    /// the exit code in the low one.
+    pub(crate) fn to_tag(self) -> u64 {
+        match self {
+            Self::PromptStart => 1 << 32,
+            Self::CommandStart => 2 << 32,
+            Self::OutputStart => 3 << 32,
+            Self::CommandEnd { exit_code: None } => 4 << 32,
+            Self::CommandEnd { exit_code: Some(code) } => 5 << 32 | u64::from(code as u32),
+        }
+    }
+
+    /// Decode a row tag written by `to_tag`.
+    pub(crate) fn from_tag(tag: u64) -> Option<Self> {
+        let kind = match tag >> 32 {
+            1 => Self::PromptStart,
+            2 => Self::CommandStart,
+            3 => Self::OutputStart,
+            4 => Self::CommandEnd { exit_code: None },
+            5 => Self::CommandEnd { exit_code: Some(tag as u32 as i32) },
+            _ => return None,
+        };
+        Some(kind)
+    }
+}
+
+/// A marker placed on an absolute line: rows from the top of the scrollback
+/// (0 = oldest kept line), so it stays on its line while output scrolls.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PromptMark {
+    pub kind: PromptMarkKind,
+    pub line: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ScanState {
+    Ground,
+    Escape,
+    /// After `ESC ]`: the payload up to BEL or `ESC \`.
+    Osc,
+    OscEscape,
+}
+
+/// Byte-stream state machine. Sequences may span any number of reads.
+#[derive(Debug)]
+pub(crate) struct PromptScanner {
+    state: ScanState,
+    buf: Vec<u8>,
+    /// The current payload exceeded `MAX_PAYLOAD_BYTES`; ignore it.
+    overflow: bool,
+}
+
+impl PromptScanner {
+    pub(crate) fn new() -> Self {
+        Self { state: ScanState::Ground, buf: Vec::new(), overflow: false }
+    }
+
+    /// Scan `data`, calling `on_marker` with the offset just past each
+    /// marker's terminator.
+    pub(crate) fn feed(&mut self, data: &[u8], mut on_marker: impl FnMut(usize, PromptMarkKind)) {
+        let mut i = 0;
+        while i < data.len() {
+            if self.state == ScanState::Ground {
+                let Some(pos) = data[i..].iter().position(|&b| b == 0x1b) else { return };
+                i += pos + 1;
+                self.state = ScanState::Escape;
+                continue;
+            }
+            let byte = data[i];
+            i += 1;
+            self.state = match (self.state, byte) {
+                (ScanState::Escape | ScanState::OscEscape, b']') => {
+                    self.buf.clear();
+                    self.overflow = false;
+                    ScanState::Osc
+                }
+                (ScanState::Escape | ScanState::OscEscape, 0x1b) => ScanState::Escape,
+                (ScanState::Osc, 0x07) | (ScanState::OscEscape, b'\\') => {
+                    if let Some(kind) = self.finish() {
+                        on_marker(i, kind);
+                    }
+                    ScanState::Ground
+                }
+                (ScanState::Osc, 0x1b) => ScanState::OscEscape,
+                // CAN / SUB abort the sequence
+                (ScanState::Osc, 0x18 | 0x1a) => ScanState::Ground,
+                (ScanState::Osc, _) => {
+                    if self.buf.len() < MAX_PAYLOAD_BYTES {
+                        self.buf.push(byte);
+                    } else {
+                        self.overflow = true;
+                    }
+                    ScanState::Osc
+                }
+                // Other escapes (CSI, DCS, ...) are of no interest
+                _ => ScanState::Ground,
+            };
+        }
+    }
+
+    fn finish(&self) -> Option<PromptMarkKind> {
+        if self.overflow {
+            return None;
+        }
+        parse_osc133(&self.buf)
+    }
+}
+
+/// Parse an OSC payload (`133;A`, `133;D;1`, ...); other OSCs give `None`.
+pub(crate) fn parse_osc133(payload: &[u8]) -> Option<PromptMarkKind> {
+    let mut params = payload.strip_prefix(b"133;")?.split(|&b| b == b';');
+    let kind = match params.next()? {
+        b"A" => PromptMarkKind::PromptStart,
+        b"B" => PromptMarkKind::CommandStart,
+        b"C" => PromptMarkKind::OutputStart,
+        b"D" => {
+            let exit_code = params.next().and_then(|p| std::str::from_utf8(p).ok()?.parse().ok());
+            PromptMarkKind::CommandEnd { exit_code }
+        }
+        _ => return None,
+    };
+    Some(kind)
+}
@@ -0,0 +1,97 @@
+// Debounced PTY resize (SIGWINCH): `Terminal::resize` reflows the grid at
+// once but holds the PTY notification until the size has been stable for
+// the debounce interval, so a drag-resize doesn't storm the shell with
+// SIGWINCH (prompt artifacts). A timer thread sends it, so it goes out on
+// time even when the app loop is idle and never calls `process()`.
+
+use std::sync::{Arc, Condvar, Mutex, MutexGuard};
+use std::thread::JoinHandle;
+use std::time::Instant;
+
+use alacritty_terminal::event::WindowSize;
+
+/// Sends the latest scheduled size once its deadline passes; a newer
+/// `schedule` replaces the pending one, so a burst sends exactly once.
+pub(crate) struct ResizeDebouncer {
+    shared: Arc<Shared>,
+    thread: Option<JoinHandle<()>>,
+}
+
+struct Shared {
+    state: Mutex<State>,
+    changed: Condvar,
+}
+
+#[derive(Default)]
+struct State {
+    /// Size to send and when to send it.
+    pending: Option<(WindowSize, Instant)>,
+    shutdown: bool,
+}
+
+impl Shared {
+    fn lock(&self) -> MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl ResizeDebouncer {
+    /// Start the timer thread; `send` delivers each debounced size (the PTY
+    /// notifier, or a recorder in tests).
+    pub(crate) fn new(send: impl Fn(WindowSize) + Send + 'static) -> Self {
+        let shared = Arc::new(Shared { state: Mutex::new(State::default()), changed: Condvar::new() });
+        let thread = {
+            let shared = shared.clone();
+            std::thread::Builder::new()
+                .name("pty-resize".to_string())
+                .spawn(move || run(&shared, send))
+                .expect("failed to spawn PTY resize thread")
+        };
+        Self { shared, thread: Some(thread) }
+    }
+
+    /// Send `size` at `due` unless another size is scheduled first.
+    pub(crate) fn schedule(&self, size: WindowSize, due: Instant) {
+        self.shared.lock().pending = Some((size, due));
+        self.shared.changed.notify_one();
+    }
+
+    /// The size scheduled but not yet sent, if any.
+    pub(crate) fn pending(&self) -> Option<WindowSize> {
+        self.shared.lock().pending.map(|(size, _)| size)
+    }
+}
+
+fn run(shared: &Shared, send: impl Fn(WindowSize)) {
+    let mut state = shared.lock();
+    loop {
+        if state.shutdown {
+            return;
+        }
+        state = match state.pending {
+            None => shared.changed.wait(state).unwrap_or_else(|e| e.into_inner()),
+            Some((size, due)) => {
+                let now = Instant::now();
+                if now >= due {
+                    state.pending = None;
+                    drop(state);
+                    send(size);
+                    shared.lock()
+                } else {
+                    shared.changed.wait_timeout(state, due - now).unwrap_or_else(|e| e.into_inner()).0
+                }
+            }
+        };
+    }
+}
+
+impl Drop for ResizeDebouncer {
+    /// Stop the thread; a resize still pending is dropped with the PTY.
+    fn drop(&mut self) {
+        self.shared.lock().shutdown = true;
+        self.shared.changed.notify_one();
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
@@ -0,0 +1,376 @@
+// Sixel capture: alacritty's parser drops DCS payloads, so PTY output is
+// parsed through `SixelScanner` next to the prompt scanner (see
+// `advance_emulator`). At the end of each `ESC P … q … ESC \` sequence the
+// payload is queued in an `ImageQueue` with the cursor cell; it is decoded
+// only when `Terminal::take_images` asks for it, off the PTY thread.
+
+use std::collections::VecDeque;
+use std::sync::Mutex;
+
+use tide_core::Rect;
+
+/// Sequences with a larger payload are dropped instead of buffered.
+const MAX_PAYLOAD_BYTES: usize = 4 * 1024 * 1024;
+
+/// Decoded images are clipped to this many pixels per side.
+const MAX_DIMENSION: usize = 4096;
+
+/// Captured images kept until `take_images`; older ones are dropped.
+pub(crate) const MAX_PENDING_IMAGES: usize = 16;
+
+/// Cell size in pixels reported to the PTY; programs size images against it.
+const CELL_WIDTH: usize = crate::PTY_CELL_WIDTH as usize;
+const CELL_HEIGHT: usize = crate::PTY_CELL_HEIGHT as usize;
+
+/// An image a program drew with an inline image escape sequence.
+#[derive(Debug, Clone, PartialEq)]
+pub struct InlineImage {
+    /// Placement in cells: `x`/`y` are the column/row of the top-left cell
+    /// (viewport-relative when captured), `width`/`height` the cells covered.
+    pub cells: Rect,
+    /// Row-major RGBA8, `dims.0 * dims.1 * 4` bytes.
+    pub rgba: Vec<u8>,
+    /// Size in pixels (width, height).
+    pub dims: (u32, u32),
+}
+
+// ──────────────────────────────────────────────
+// Scanner: finds sixel DCS sequences in the raw byte stream
+// ──────────────────────────────────────────────
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ScanState {
+    Ground,
+    Escape,
+    /// After `ESC P`: numeric parameters up to the final `q`.
+    Params,
+    /// Sixel data up to `ESC \`.
+    Body,
+    BodyEscape,
+}
+
+/// Byte-stream state machine. Sequences may span any number of reads.
+#[derive(Debug)]
+pub(crate) struct SixelScanner {
+    state: ScanState,
+    /// Parameters, the final `q`, then the sixel data.
+    buf: Vec<u8>,
+    /// The current payload exceeded `MAX_PAYLOAD_BYTES`; drop it at the end.
+    overflow: bool,
+}
+
+impl SixelScanner {
+    pub(crate) fn new() -> Self {
+        Self { state: ScanState::Ground, buf: Vec::new(), overflow: false }
+    }
+
+    /// Scan `data`, calling `on_image` with the offset just past each
+    /// sequence's terminator and its payload (`<params>q<data>`).
+    pub(crate) fn feed(&mut self, data: &[u8], mut on_image: impl FnMut(usize, Vec<u8>)) {
+        let mut i = 0;
+        while i < data.len() {
+            if self.state == ScanState::Ground {
+                // Fast path: plain output has no ESC to look at
+                match data[i..].iter().position(|&b| b == 0x1b) {
+                    Some(pos) => {
+                        i += pos + 1;
+                        self.state = ScanState::Escape;
+                    }
+                    None => return,
+                }
+                continue;
+            }
+            let byte = data[i];
+            i += 1;
+            self.state = match (self.state, byte) {
+                (ScanState::Escape, b'P') => {
+                    self.buf.clear();
+                    self.overflow = false;
+                    ScanState::Params
+                }
+                (ScanState::Escape | ScanState::Params, 0x1b) => ScanState::Escape,
+                (ScanState::Params, b'0'..=b'9' | b';') => {
+                    self.buf.push(byte);
+                    ScanState::Params
+                }
+                (ScanState::Params, b'q') => {
+                    self.buf.push(byte);
+                    ScanState::Body
+                }
+                (ScanState::Body, 0x1b) => ScanState::BodyEscape,
+                // CAN / SUB abort the sequence
+                (ScanState::Body, 0x18 | 0x1a) => ScanState::Ground,
+                (ScanState::Body, _) => {
+                    if self.buf.len() < MAX_PAYLOAD_BYTES {
+                        self.buf.push(byte);
+                    } else {
+                        self.overflow = true;
+                    }
+                    ScanState::Body
+                }
+                (ScanState::BodyEscape, b'\\') => {
+                    if !self.overflow {
+                        on_image(i, std::mem::take(&mut self.buf));
+                    }
+                    ScanState::Ground
+                }
+                // Any other ESC cancels the DCS and starts a new sequence
+                (ScanState::BodyEscape, b'P') => {
+                    self.buf.clear();
+                    self.overflow = false;
+                    ScanState::Params
+                }
+                (ScanState::BodyEscape, 0x1b) => ScanState::Escape,
+                // Not a sixel sequence (other escapes, DECRQSS `ESC P $ q`, ...)
+                _ => ScanState::Ground,
+            };
+        }
+    }
+}
+
+// ──────────────────────────────────────────────
+// Queue: payloads waiting for `take_images`
+// ──────────────────────────────────────────────
+
+/// A sixel payload and the cursor cell it was drawn at.
+#[derive(Debug)]
+struct PendingImage {
+    payload: Vec<u8>,
+    col: usize,
+    row: usize,
+}
+
+/// Undecoded payloads shared between the PTY thread, which pushes, and
+/// `Terminal::take_images`, which decodes. Holds at most
+/// `MAX_PENDING_IMAGES`.
+#[derive(Debug, Default)]
+pub(crate) struct ImageQueue {
+    pending: Mutex<VecDeque<PendingImage>>,
+}
+
+impl ImageQueue {
+    /// Queue `payload` drawn at viewport cell (`col`, `row`), dropping the
+    /// oldest image when full.
+    pub(crate) fn push(&self, payload: Vec<u8>, col: usize, row: usize) {
+        if let Ok(mut pending) = self.pending.lock() {
+            if pending.len() == MAX_PENDING_IMAGES {
+                pending.pop_front();
+            }
+            pending.push_back(PendingImage { payload, col, row });
+        }
+    }
+
+    /// Decode and remove the queued images, oldest first. Payloads that draw
+    /// nothing are skipped.
+    pub(crate) fn take(&self) -> Vec<InlineImage> {
+        let pending = match self.pending.lock() {
+            Ok(mut pending) => std::mem::take(&mut *pending),
+            Err(_) => return Vec::new(),
+        };
+        pending
+            .into_iter()
+            .filter_map(|image| {
+                let decoded = decode(&image.payload)?;
+                Some(InlineImage {
+                    cells: Rect::new(
+                        image.col as f32,
+                        image.row as f32,
+                        decoded.width.div_ceil(CELL_WIDTH) as f32,
+                        decoded.height.div_ceil(CELL_HEIGHT) as f32,
+                    ),
+                    dims: (decoded.width as u32, decoded.height as u32),
+                    rgba: decoded.rgba,
+                })
+            })
+            .collect()
+    }
+}
+
+// ──────────────────────────────────────────────
+// Decoder
+// ──────────────────────────────────────────────
+
+/// A decoded sixel image.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct DecodedSixel {
+    pub width: usize,
+    pub height: usize,
+    pub rgba: Vec<u8>,
+}
+
+/// VT340 default palette, as RGB percentages.
+const DEFAULT_PALETTE: [[u32; 3]; 16] = [
+    [0, 0, 0],
+    [20, 20, 80],
+    [80, 13, 13],
+    [20, 80, 20],
+    [80, 20, 80],
+    [20, 80, 80],
+    [80, 80, 20],
+    [53, 53, 53],
+    [26, 26, 26],
+    [33, 33, 60],
+    [60, 26, 26],
+    [33, 60, 33],
+    [60, 33, 60],
+    [33, 60, 60],
+    [60, 60, 33],
+    [80, 80, 80],
+];
+
+fn percent_to_u8(v: u32) -> u8 {
+    ((v.min(100) * 255 + 50) / 100) as u8
+}
+
+/// DEC HLS (hue 0 = blue, 120 = red, 240 = green; L and S in percent) to RGB.
+fn hls_to_rgb(h: u32, l: u32, s: u32) -> [u8; 3] {
+    let l = l.min(100) as f32 / 100.0;
+    let s = s.min(100) as f32 / 100.0;
+    if s == 0.0 {
+        let v = (l * 255.0).round() as u8;
+        return [v, v, v];
+    }
+    let hue = ((h + 240) % 360) as f32 / 360.0;
+    let q = if l < 0.5 { l * (1.0 + s) } else { l + s - l * s };
+    let p = 2.0 * l - q;
+    let channel = |t: f32| {
+        let t = t.rem_euclid(1.0);
+        let v = if t < 1.0 / 6.0 {
+            p + (q - p) * 6.0 * t
+        } else if t < 0.5 {
+            q
+        } else if t < 2.0 / 3.0 {
+            p + (q - p) * (2.0 / 3.0 - t) * 6.0
+        } else {
+            p
+        };
+        (v * 255.0).round() as u8
+    };
+    [channel(hue + 1.0 / 3.0), channel(hue), channel(hue - 1.0 / 3.0)]
+}
+
+/// Numeric parameters (`12;3;4`) starting at `*i`; advances past them.
+fn read_params(data: &[u8], i: &mut usize) -> Vec<u32> {
+    let mut params = Vec::new();
+    let mut current: Option<u32> = None;
+    while let Some(&b) = data.get(*i) {
+        match b {
+            b'0'..=b'9' => {
+                let digit = (b - b'0') as u32;
+                current = Some(current.unwrap_or(0).saturating_mul(10).saturating_add(digit));
+            }
+            b';' => params.push(current.take().unwrap_or(0)),
+            _ => break,
+        }
+        *i += 1;
+    }
+    if let Some(v) = current {
+        params.push(v);
+    }
+    params
+}
+
+/// Decode a payload collected by `SixelScanner` (`<params>q<data>`).
+/// `None` if nothing was drawn and no raster size was given.
+pub(crate) fn decode(payload: &[u8]) -> Option<DecodedSixel> {
+    let q = payload.iter().position(|&b| b == b'q')?;
+    let mut i = 0;
+    let params = read_params(&payload[..q], &mut i);
+    // P2 = 1: pixels the image doesn't draw stay transparent
+    let transparent_bg = params.get(1) == Some(&1);
+    let data = &payload[q + 1..];
+
+    let mut palette = [[0u8; 3]; 256];
+    for (slot, rgb) in palette.iter_mut().zip(DEFAULT_PALETTE) {
+        *slot = rgb.map(percent_to_u8);
+    }
+    let mut color = palette[0];
+    // Rows of RGBA; alpha 0 marks pixels never drawn
+    let mut rows: Vec<Vec<[u8; 4]>> = Vec::new();
+    let (mut raster_w, mut raster_h) = (0usize, 0usize);
+    let (mut x, mut y) = (0usize, 0usize);
+
+    let mut i = 0;
+    while i < data.len() {
+        let b = data[i];
+        i += 1;
+        let (bits, count) = match b {
+            b'"' => {
+                let p = read_params(data, &mut i);
+                raster_w = p.get(2).copied().unwrap_or(0) as usize;
+                raster_h = p.get(3).copied().unwrap_or(0) as usize;
+                continue;
+            }
+            b'#' => {
+                let p = read_params(data, &mut i);
+                let Some(&index) = p.first() else { continue };
+                let index = index as usize % palette.len();
+                if p.len() >= 5 {
+                    palette[index] = match p[1] {
+                        1 => hls_to_rgb(p[2], p[3], p[4]),
+                        _ => [percent_to_u8(p[2]), percent_to_u8(p[3]), percent_to_u8(p[4])],
+                    };
+                }
+                color = palette[index];
+                continue;
+            }
+            b'!' => {
+                let p = read_params(data, &mut i);
+                let Some(&sixel) = data.get(i) else { break };
+                i += 1;
+                if !(0x3f..=0x7e).contains(&sixel) {
+                    continue;
+                }
+                (sixel - 0x3f, p.first().copied().unwrap_or(1).max(1) as usize)
+            }
+            b'$' => {
+                x = 0;
+                continue;
+            }
+            b'-' => {
+                x = 0;
+                y += 6;
+                continue;
+            }
+            0x3f..=0x7e => (b - 0x3f, 1),
+            _ => continue,
+        };
+
+        let end = (x + count).min(MAX_DIMENSION);
+        if bits != 0 && x < end {
+            for bit in 0..6 {
+                let py = y + bit;
+                if bits & (1 << bit) == 0 || py >= MAX_DIMENSION {
+                    continue;
+                }
+                if rows.len() <= py {
+                    rows.resize_with(py + 1, Vec::new);
+                }
+                let row = &mut rows[py];
+                if row.len() < end {
+                    row.resize(end, [0; 4]);
+                }
+                row[x..end].fill([color[0], color[1], color[2], 255]);
+            }
+        }
+        x = end;
+    }
+
+    let width = rows.iter().map(Vec::len).max().unwrap_or(0).max(raster_w).min(MAX_DIMENSION);
+    let height = rows.len().max(raster_h).min(MAX_DIMENSION);
+    if width == 0 || height == 0 {
+        return None;
+    }
+    let background = if transparent_bg { [0; 4] } else { [palette[0][0], palette[0][1], palette[0][2], 255] };
+    let mut rgba = Vec::with_capacity(width * height * 4);
+    for py in 0..height {
+        let row = rows.get(py).map(Vec::as_slice).unwrap_or(&[]);
+        for px in 0..width {
+            match row.get(px) {
+                Some(pixel) if pixel[3] != 0 => rgba.extend_from_slice(pixel),
+                _ => rgba.extend_from_slice(&background),
+            }
+        }
+    }
+    Some(DecodedSixel { width, height, rgba })
+}
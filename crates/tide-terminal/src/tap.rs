@@ -0,0 +1,97 @@
+// PTY tap: the alacritty event loop reads the PTY through `TapPty`, which
+// reads nothing while output is paused.
+
+use std::fs::File;
+use std::io::{self, Read};
+use std::os::fd::AsRawFd;
+use std::sync::Arc;
+
+use alacritty_terminal::event::{OnResize, WindowSize};
+use alacritty_terminal::tty::{self, ChildEvent, EventedPty, EventedReadWrite};
+use polling::{Event, PollMode, Poller};
+
+use crate::pause::OutputPause;
+
+/// Reads the PTY master; reads nothing while output is paused, unless the
+/// child has exited and the event loop drains what it left.
+pub(crate) struct TapReader {
+    file: File,
+    pause: Arc<OutputPause>,
+    child_exited: bool,
+}
+
+impl Read for TapReader {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.pause.is_paused() && !self.child_exited {
+            return Err(io::ErrorKind::WouldBlock.into());
+        }
+        self.file.read(buf)
+    }
+}
+
+/// `tty::Pty` with its reader replaced by a `TapReader`; everything else
+/// (polling registration, writes, resize, child events) is delegated, with
+/// read interest dropped while output is paused.
+pub(crate) struct TapPty {
+    pty: tty::Pty,
+    reader: TapReader,
+    pause: Arc<OutputPause>,
+}
+
+impl TapPty {
+    pub(crate) fn new(pty: tty::Pty, pause: Arc<OutputPause>) -> io::Result<Self> {
+        // Same open file description, so the poller registration on the
+        // original fd covers reads through the clone.
+        let file = pty.file().try_clone()?;
+        let reader = TapReader { file, pause: pause.clone(), child_exited: false };
+        Ok(Self { pty, reader, pause })
+    }
+}
+
+impl EventedReadWrite for TapPty {
+    type Reader = TapReader;
+    type Writer = File;
+
+    // The event loop's interest key (0) is the PTY fd's token, so the pause
+    // can re-apply it to the fd as is.
+    unsafe fn register(&mut self, poll: &Arc<Poller>, interest: Event, mode: PollMode) -> io::Result<()> {
+        let fd = self.pty.file().as_raw_fd();
+        let pty = &mut self.pty;
+        self.pause.register(poll, fd, interest, mode, |interest| unsafe { pty.register(poll, interest, mode) })
+    }
+
+    fn reregister(&mut self, poll: &Arc<Poller>, interest: Event, mode: PollMode) -> io::Result<()> {
+        let fd = self.pty.file().as_raw_fd();
+        let pty = &mut self.pty;
+        self.pause.register(poll, fd, interest, mode, |interest| pty.reregister(poll, interest, mode))
+    }
+
+    fn deregister(&mut self, poll: &Arc<Poller>) -> io::Result<()> {
+        self.pause.deregister();
+        self.pty.deregister(poll)
+    }
+
+    fn reader(&mut self) -> &mut TapReader {
+        &mut self.reader
+    }
+
+    fn writer(&mut self) -> &mut File {
+        self.pty.writer()
+    }
+}
+
+impl EventedPty for TapPty {
+    fn next_child_event(&mut self) -> Option<ChildEvent> {
+        let event = self.pty.next_child_event();
+        if matches!(event, Some(ChildEvent::Exited(_))) {
+            self.reader.child_exited = true;
+        }
+        event
+    }
+}
+
+impl OnResize for TapPty {
+    fn on_resize(&mut self, window_size: WindowSize) {
+        self.pty.on_resize(window_size);
+    }
+}
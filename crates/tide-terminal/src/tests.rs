@@ -43,12 +43,89 @@ mod tests {
         assert_eq!(bytes, vec![0x1b, b'O', b'P']);
     }
 
+    #[test]
+    fn test_encode_key_option_as_meta() {
+        let mut term = Terminal::new(80, 24).expect("PTY available");
+        let alt = Modifiers { alt: true, ..Default::default() };
+        assert!(!term.option_as_meta());
+        term.set_option_as_meta(true);
+        assert_eq!(term.encode_key(&Key::Char('b'), &alt, Some("\u{222B}")), b"\x1bb".to_vec());
+
+        term.set_option_as_meta(false);
+        assert_eq!(term.encode_key(&Key::Char('b'), &alt, Some("\u{222B}")), "\u{222B}".as_bytes().to_vec());
+        assert_eq!(term.encode_key(&Key::Char('b'), &alt, None), b"b".to_vec());
+        // Non-character keys keep their Alt modifier encoding.
+        assert_eq!(term.encode_key(&Key::Left, &alt, None), Terminal::key_to_bytes(&Key::Left, &alt));
+    }
+
+    #[test]
+    fn test_key_to_bytes_app_cursor_mode() {
+        let normal = KeyModes::default();
+        let app = KeyModes { app_cursor: true, ..Default::default() };
+        let none = Modifiers::default();
+        assert_eq!(Terminal::key_to_bytes_with_modes(&Key::Up, &none, normal), b"\x1b[A".to_vec());
+        assert_eq!(Terminal::key_to_bytes_with_modes(&Key::Up, &none, app), b"\x1bOA".to_vec());
+        assert_eq!(Terminal::key_to_bytes_with_modes(&Key::Home, &none, app), b"\x1bOH".to_vec());
+        assert_eq!(Terminal::key_to_bytes_with_modes(&Key::End, &none, normal), b"\x1b[F".to_vec());
+        // Modified arrows keep the CSI 1;m form in either mode.
+        let shift = Modifiers { shift: true, ..Default::default() };
+        assert_eq!(Terminal::key_to_bytes_with_modes(&Key::Up, &shift, app), b"\x1b[1;2A".to_vec());
+    }
+
+    #[test]
+    fn test_key_to_bytes_app_keypad_mode() {
+        let app = KeyModes { app_keypad: true, ..Default::default() };
+        let none = Modifiers::default();
+        assert_eq!(Terminal::key_to_bytes_with_modes(&Key::Keypad('5'), &none, app), b"\x1bOu".to_vec());
+        assert_eq!(Terminal::key_to_bytes_with_modes(&Key::Keypad('+'), &none, app), b"\x1bOk".to_vec());
+        assert_eq!(Terminal::key_to_bytes_with_modes(&Key::Keypad('5'), &none, KeyModes::default()), b"5".to_vec());
+    }
+
+    #[test]
+    fn test_large_write_waits_for_the_shell_to_read() {
+        use std::time::{Duration, Instant};
+        let mut term = Terminal::new(80, 24).expect("PTY available");
+        // Nothing reads the PTY for a second, then cat drains it
+        term.write(b"stty -echo; sleep 1; cat > /dev/null\r");
+        let line = [b'x'; 63].iter().copied().chain([b'\n']).collect::<Vec<u8>>();
+        let payload = line.repeat(MAX_PASTE_BYTES / line.len());
+
+        let start = Instant::now();
+        term.write(&payload);
+        assert!(start.elapsed() < Duration::from_millis(500), "write must not block the caller");
+        std::thread::sleep(Duration::from_millis(300));
+        let held = term.input_backlog();
+        assert!(held > payload.len() / 2, "only {held} bytes held back while the shell sleeps");
+        // They wait in the writer's queue, not in the PTY event loop's
+        let in_flight = term.notifier.as_ref().map_or(0, |n| n.0.pending_input());
+        assert!(in_flight <= 8 * DEFAULT_WRITE_CHUNK_SIZE, "{in_flight} bytes queued in the event loop");
+
+        let deadline = Instant::now() + Duration::from_secs(20);
+        while term.input_backlog() > 0 {
+            assert!(Instant::now() < deadline, "{} bytes never reached the PTY", term.input_backlog());
+            std::thread::sleep(Duration::from_millis(20));
+        }
+    }
+
+    #[test]
+    fn test_key_to_bytes_keypad_sends_character() {
+        let bytes = Terminal::key_to_bytes(&Key::Keypad('5'), &Modifiers::default());
+        assert_eq!(bytes, vec![b'5']);
+    }
+
     #[test]
     fn test_named_color_to_rgb() {
-        let color = Terminal::named_color_to_rgb(true, NamedColor::Red);
+        let color = Terminal::named_color_to_rgb(&Theme::dark(), NamedColor::Red);
         assert_eq!(color, Color::rgb(1.0, 0.33, 0.33));
     }
 
+    #[test]
+    fn test_named_color_light_fallback() {
+        let light = Theme::light();
+        assert_eq!(Terminal::named_color_to_rgb(&light, NamedColor::Foreground), light.foreground);
+        assert_eq!(Terminal::named_color_to_rgb(&light, NamedColor::DimWhite), Color::rgb(0.12, 0.12, 0.12));
+    }
+
     #[test]
     fn test_indexed_color_fallback_grayscale() {
         let color = Terminal::indexed_color_fallback(232);
@@ -82,4 +159,1044 @@ mod tests {
         // No trimming needed
         assert_eq!(crate::trim_url_trailing("https://example.com/page"), "https://example.com/page");
     }
+
+    fn feed_term(cols: usize, rows: usize, data: &[u8]) -> Term<alacritty_terminal::event::VoidListener> {
+        use alacritty_terminal::vte::ansi::{Processor, StdSyncHandler};
+        let size = TermDimensions::new(cols, rows);
+        let mut term = Term::new(TermConfig::default(), &size, alacritty_terminal::event::VoidListener);
+        let mut processor: Processor<StdSyncHandler> = Processor::new();
+        processor.advance(&mut term, data);
+        term
+    }
+
+    #[test]
+    fn test_text_in_range_spans_scrollback_and_screen() {
+        // 5 lines into a 3-row screen: "one" and "two" scroll into history
+        let term = feed_term(10, 3, b"one\r\ntwo\r\nthree\r\nfour\r\nfive");
+        assert_eq!(term.grid().history_size(), 2);
+        // From the middle of "two" (history) to the middle of "four" (screen)
+        let text = crate::grid_text_in_range(term.grid(), (1, 1), (3, 2));
+        assert_eq!(text, "wo\nthree\nfo");
+    }
+
+    #[test]
+    fn test_text_in_range_accepts_reversed_points() {
+        let term = feed_term(10, 3, b"alpha\r\nbeta");
+        let forward = crate::grid_text_in_range(term.grid(), (0, 0), (1, 4));
+        let backward = crate::grid_text_in_range(term.grid(), (1, 4), (0, 0));
+        assert_eq!(forward, "alpha\nbeta");
+        assert_eq!(backward, forward);
+    }
+
+    #[test]
+    fn test_word_range_splits_paths_by_default() {
+        let term = feed_term(20, 2, b"ls /usr/local/bin");
+        // Double-click inside "usr" (col 5)
+        assert_eq!(crate::grid_word_range_at(term.grid(), 0, 5, ""), (4, 7));
+        // A separator selects only itself
+        assert_eq!(crate::grid_word_range_at(term.grid(), 0, 3, ""), (3, 4));
+    }
+
+    #[test]
+    fn test_word_range_joins_word_chars() {
+        let term = feed_term(20, 2, b"ls /usr/local/bin");
+        let (start, end) = crate::grid_word_range_at(term.grid(), 0, 5, "/");
+        assert_eq!((start, end), (3, 17));
+        assert_eq!(crate::grid_text_in_range(term.grid(), (0, start), (0, end)), "/usr/local/bin");
+    }
+
+    #[test]
+    fn test_word_range_covers_wide_chars() {
+        let term = feed_term(20, 2, "a 한글b c".as_bytes());
+        // Clicking the spacer half of the first wide char still finds the word
+        assert_eq!(crate::grid_word_range_at(term.grid(), 0, 3, ""), (2, 7));
+    }
+
+    /// Text of the top row of the viewport.
+    fn top_visible_text<T>(term: &Term<T>) -> String {
+        let grid = term.grid();
+        let top = grid.history_size() - grid.display_offset();
+        crate::grid_text_in_range(grid, (top, 0), (top, grid.columns())).trim_end().to_string()
+    }
+
+    fn numbered_lines(n: usize) -> Vec<u8> {
+        (0..n).map(|i| format!("line {i}")).collect::<Vec<_>>().join("\r\n").into_bytes()
+    }
+
+    #[test]
+    fn test_resize_keeps_scrolled_top_line() {
+        let mut term = feed_term(20, 5, &numbered_lines(40));
+        term.scroll_display(alacritty_terminal::grid::Scroll::Delta(10));
+        let before = top_visible_text(&term);
+        assert_eq!(before, "line 25");
+
+        // Taller: rows come out of history but the top line stays put
+        crate::term_resize_keeping_scroll(&mut term, TermDimensions::new(20, 8));
+        assert_eq!(top_visible_text(&term), before);
+        // Shorter again
+        crate::term_resize_keeping_scroll(&mut term, TermDimensions::new(20, 3));
+        assert_eq!(top_visible_text(&term), before);
+        // Narrower without wrapping any line
+        crate::term_resize_keeping_scroll(&mut term, TermDimensions::new(12, 3));
+        assert_eq!(top_visible_text(&term), before);
+    }
+
+    #[test]
+    fn test_resize_reflow_keeps_scrolled_content_near_top() {
+        let data = (0..40).map(|i| format!("line {i:02} {}", "x".repeat(12))).collect::<Vec<_>>();
+        let mut term = feed_term(30, 5, data.join("\r\n").as_bytes());
+        term.scroll_display(alacritty_terminal::grid::Scroll::Delta(10));
+        assert!(top_visible_text(&term).starts_with("line 25"));
+
+        // Narrower: every line wraps onto two rows
+        crate::term_resize_keeping_scroll(&mut term, TermDimensions::new(12, 5));
+        assert!(top_visible_text(&term).starts_with("line 25"));
+        // Wider again: lines unwrap
+        crate::term_resize_keeping_scroll(&mut term, TermDimensions::new(30, 5));
+        assert!(top_visible_text(&term).starts_with("line 25"));
+    }
+
+    #[test]
+    fn test_resize_without_reflow_clips_long_lines() {
+        let data = b"0123456789ABCDEF\r\nnext";
+        let mut term = feed_term(20, 4, data);
+        term.set_reflow(false);
+        crate::term_resize_keeping_scroll(&mut term, TermDimensions::new(10, 4));
+        assert_eq!(crate::grid_export_text(term.grid(), true).trim_end(), "0123456789\nnext");
+
+        // Widening doesn't bring the clipped text back or join rows
+        crate::term_resize_keeping_scroll(&mut term, TermDimensions::new(20, 4));
+        assert_eq!(crate::grid_export_text(term.grid(), true).trim_end(), "0123456789\nnext");
+    }
+
+    #[test]
+    fn test_resize_with_reflow_rewraps_long_lines() {
+        let mut term = feed_term(20, 4, b"0123456789ABCDEF\r\nnext");
+        assert!(term.reflow());
+        crate::term_resize_keeping_scroll(&mut term, TermDimensions::new(10, 4));
+        assert_eq!(crate::grid_export_text(term.grid(), true).trim_end(), "0123456789\nABCDEF\nnext");
+
+        crate::term_resize_keeping_scroll(&mut term, TermDimensions::new(20, 4));
+        assert_eq!(crate::grid_export_text(term.grid(), true).trim_end(), "0123456789ABCDEF\nnext");
+    }
+
+    #[test]
+    fn test_resize_at_bottom_stays_at_bottom() {
+        let mut term = feed_term(20, 5, &numbered_lines(40));
+        crate::term_resize_keeping_scroll(&mut term, TermDimensions::new(20, 8));
+        assert_eq!(term.grid().display_offset(), 0);
+    }
+
+    #[test]
+    fn test_export_text_with_scrollback() {
+        let term = feed_term(10, 3, b"one  \r\ntwo\r\nthree\r\nfour\r\nfive");
+        let text = crate::grid_export_text(term.grid(), true);
+        assert_eq!(text, "one\ntwo\nthree\nfour\nfive");
+    }
+
+    #[test]
+    fn test_export_text_screen_only() {
+        let term = feed_term(10, 3, b"one\r\ntwo\r\nthree\r\nfour\r\nfive");
+        let text = crate::grid_export_text(term.grid(), false);
+        assert_eq!(text, "three\nfour\nfive");
+    }
+
+    #[test]
+    fn test_power_save_idle_drops_noop_syncs() {
+        use std::time::{Duration, Instant};
+
+        let mut term = Terminal::new(80, 24).expect("PTY available");
+        term.set_power_save(true);
+        term.request_scroll_to_bottom();
+
+        // Wait for shell startup output to settle and the idle window to pass.
+        let deadline = Instant::now() + Duration::from_secs(20);
+        while !(term.is_shell_idle() && term.is_power_save_idle()) {
+            assert!(Instant::now() < deadline, "shell never went idle");
+            std::thread::sleep(Duration::from_millis(100));
+        }
+
+        let before = term.sync_count();
+        for _ in 0..20 {
+            term.request_scroll_to_bottom();
+            std::thread::sleep(Duration::from_millis(10));
+        }
+        assert_eq!(term.sync_count(), before);
+
+        // Leaving power-save restores normal wakes.
+        term.set_power_save(false);
+        term.request_scroll_to_bottom();
+        let deadline = Instant::now() + Duration::from_secs(2);
+        while term.sync_count() == before && Instant::now() < deadline {
+            std::thread::sleep(Duration::from_millis(5));
+        }
+        assert!(term.sync_count() > before);
+    }
+
+    /// A terminal whose shell has finished its startup output, so writes
+    /// from the test aren't interleaved with a prompt.
+    fn settled_terminal() -> Terminal {
+        use std::time::{Duration, Instant};
+        let term = Terminal::new(20, 5).expect("PTY available");
+        let deadline = Instant::now() + Duration::from_secs(20);
+        while !term.is_shell_idle() {
+            assert!(Instant::now() < deadline, "shell never went idle");
+            std::thread::sleep(Duration::from_millis(50));
+        }
+        term
+    }
+
+    #[test]
+    fn test_foreground_command_names_running_child() {
+        use std::time::{Duration, Instant};
+        let mut term = settled_terminal();
+        let shell = term.foreground_command();
+        assert!(shell.is_some(), "the idle shell itself is in the foreground");
+        assert_ne!(shell.as_deref(), Some("sleep"));
+
+        term.write(b"sleep 5\r");
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while term.foreground_command().as_deref() != Some("sleep") {
+            assert!(Instant::now() < deadline, "sleep never became the foreground command");
+            std::thread::sleep(Duration::from_millis(20));
+        }
+        // Interrupt so the shell returns to its prompt
+        term.write(b"\x03");
+    }
+
+    #[test]
+    fn test_cwd_polling_disabled_never_probes_child() {
+        use std::time::{Duration, Instant};
+        use tide_core::TerminalBackend;
+
+        let mut term = Terminal::new(80, 24).expect("PTY available");
+        assert_eq!(term.cwd_poll_interval(), Some(DEFAULT_CWD_POLL_INTERVAL));
+        term.set_cwd_poll_interval(None);
+
+        let deadline = Instant::now() + Duration::from_millis(200);
+        while Instant::now() < deadline {
+            let _ = term.cwd();
+            std::thread::sleep(Duration::from_millis(5));
+        }
+        assert_eq!(term.cwd_probe_count(), 0);
+
+        // Polling again: the first call probes, then the result is reused
+        // for the interval (a shell that reports OSC 7 is never probed)
+        term.set_cwd_poll_interval(Some(Duration::from_secs(60)));
+        for _ in 0..20 {
+            let _ = term.cwd();
+        }
+        if term.reported_cwd().is_none() {
+            assert_eq!(term.cwd_probe_count(), 1);
+        }
+    }
+
+    /// Wait for `needle` to show up in the terminal's text.
+    fn wait_for_text(term: &Terminal, needle: &str) -> bool {
+        use std::time::{Duration, Instant};
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while !term.export_text(true).contains(needle) {
+            if Instant::now() > deadline {
+                return false;
+            }
+            std::thread::sleep(Duration::from_millis(20));
+        }
+        true
+    }
+
+    #[test]
+    fn test_each_shell_gets_a_distinct_pane_id() {
+        let mut first = settled_terminal();
+        let mut second = settled_terminal();
+        let (a, b) = (first.pane_env_id().unwrap().to_string(), second.pane_env_id().unwrap().to_string());
+        assert_ne!(a, b);
+        for term in [&mut first, &mut second] {
+            term.write(b"echo \"pane=<$TIDE_PANE_ID>\"\r");
+        }
+        assert!(wait_for_text(&first, &format!("pane=<{a}>")));
+        assert!(wait_for_text(&second, &format!("pane=<{b}>")));
+    }
+
+    #[test]
+    fn test_env_extra_overrides_default_vars() {
+        let options = TerminalOptions {
+            env_extra: HashMap::from([
+                ("TIDE_PANE_ID".to_string(), "custom".to_string()),
+                ("PROMPT_EOL_MARK".to_string(), "%".to_string()),
+                ("TIDE_TEST_VAR".to_string(), "dotfiles".to_string()),
+            ]),
+            ..Default::default()
+        };
+        let mut term = Terminal::with_options(80, 24, options).expect("PTY available");
+        assert_eq!(term.pane_env_id(), Some("custom"));
+        term.write(b"echo \"env=<$TIDE_PANE_ID/$PROMPT_EOL_MARK/$TIDE_TEST_VAR/$TERM>\"\r");
+        assert!(wait_for_text(&term, "env=<custom/%/dotfiles/xterm-256color>"));
+    }
+
+    #[test]
+    fn test_read_only_ignores_writes() {
+        use std::time::{Duration, Instant};
+        let mut term = settled_terminal();
+        term.set_read_only(true);
+        assert!(term.is_read_only());
+        term.write(b"echo ro-$((6*7))\r");
+        term.paste("echo ro-$((6*7))\r");
+        std::thread::sleep(Duration::from_millis(300));
+        assert!(!term.export_text(true).contains("ro-"));
+
+        term.set_read_only(false);
+        term.write(b"echo ro-$((6*7))\r");
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while !term.export_text(true).contains("ro-42") {
+            assert!(Instant::now() < deadline, "input was not delivered after leaving read-only mode");
+            std::thread::sleep(Duration::from_millis(20));
+        }
+    }
+
+    #[test]
+    fn test_from_reader_displays_piped_content() {
+        use std::time::{Duration, Instant};
+        use tide_core::TerminalBackend;
+        let data = b"first line\r\n\x1b[1msecond\x1b[0m line\r\n".to_vec();
+        let mut term = Terminal::from_reader(std::io::Cursor::new(data), 20, 5);
+        assert!(term.is_read_only());
+        assert_eq!(term.child_pid(), None);
+        term.write(b"ignored");
+
+        let row_text = |term: &Terminal, row: usize| -> String {
+            term.grid().cells[row].iter().map(|c| c.character).collect::<String>().trim_end().to_string()
+        };
+        let deadline = Instant::now() + Duration::from_secs(5);
+        loop {
+            term.process();
+            if row_text(&term, 1) == "second line" {
+                break;
+            }
+            assert!(Instant::now() < deadline, "piped content never reached the grid");
+            std::thread::sleep(Duration::from_millis(10));
+        }
+        assert_eq!(row_text(&term, 0), "first line");
+        assert!(term.grid().cells[1][0].style.bold);
+        assert_eq!(term.cursor().row, 2);
+    }
+
+    /// Reader fed through a channel, ending when the sender is dropped.
+    struct ChannelReader(std::sync::mpsc::Receiver<Vec<u8>>);
+
+    impl std::io::Read for ChannelReader {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            let Ok(data) = self.0.recv() else { return Ok(0) };
+            let n = data.len().min(buf.len());
+            buf[..n].copy_from_slice(&data[..n]);
+            Ok(n)
+        }
+    }
+
+    #[test]
+    fn test_paused_reader_holds_output_until_resumed() {
+        use std::time::{Duration, Instant};
+        use tide_core::TerminalBackend;
+        let (tx, rx) = std::sync::mpsc::channel();
+        let mut term = Terminal::from_reader(ChannelReader(rx), 20, 5);
+        let wait_for = |term: &mut Terminal, needle: &str| {
+            let deadline = Instant::now() + Duration::from_secs(5);
+            loop {
+                term.process();
+                let shown = term.grid().cells.iter().flatten().map(|c| c.character).collect::<String>();
+                if shown.contains(needle) {
+                    return;
+                }
+                assert!(Instant::now() < deadline, "{needle:?} never reached the grid");
+                std::thread::sleep(Duration::from_millis(10));
+            }
+        };
+        tx.send(b"before\r\n".to_vec()).unwrap();
+        wait_for(&mut term, "before");
+
+        term.set_paused(true);
+        assert!(term.is_paused());
+        let generation = term.grid_generation();
+        tx.send(b"during\r\n".to_vec()).unwrap();
+        tx.send(b"more\r\n".to_vec()).unwrap();
+        std::thread::sleep(Duration::from_millis(200));
+        term.process();
+        assert_eq!(term.grid_generation(), generation, "grid changed while paused");
+        assert!(!term.export_text(true).contains("during"));
+
+        term.set_paused(false);
+        wait_for(&mut term, "more");
+        assert!(term.grid_generation() > generation);
+        assert!(term.export_text(true).contains("during"));
+    }
+
+    #[test]
+    fn test_paused_shell_output_resumes() {
+        use std::time::Duration;
+        use tide_core::TerminalBackend;
+        let mut term = settled_terminal();
+        term.set_paused(true);
+        std::thread::sleep(Duration::from_millis(100));
+        term.process();
+        let generation = term.grid_generation();
+
+        // Input still reaches the shell; its echo and output wait in the PTY
+        term.write(b"echo paused-$((6*7))\r");
+        std::thread::sleep(Duration::from_millis(300));
+        term.process();
+        assert_eq!(term.grid_generation(), generation, "grid changed while paused");
+        assert!(!term.export_text(true).contains("paused-"));
+
+        term.set_paused(false);
+        assert!(wait_for_text(&term, "paused-42"));
+    }
+
+    #[test]
+    fn test_output_held_at_exit_is_parsed_before_the_exit_shows() {
+        use std::time::{Duration, Instant};
+        use tide_core::TerminalBackend;
+        let mut term = settled_terminal();
+        term.set_paused(true);
+        term.write(b"echo bye-$((6*7)); exit\r");
+
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while term.is_child_alive() {
+            assert!(Instant::now() < deadline, "shell never exited");
+            std::thread::sleep(Duration::from_millis(20));
+        }
+        // Still paused, yet the shell's last output reached the grid
+        assert!(term.is_paused());
+        assert!(term.export_text(true).contains("bye-42"));
+    }
+
+    #[test]
+    fn test_feed_colors_cells_without_a_shell() {
+        use tide_core::TerminalBackend;
+        let mut term = Terminal::from_reader(std::io::empty(), 20, 3);
+        term.feed(b"\x1b[31mred\x1b[0m");
+        assert!(sync_until(&mut term, |t| t.grid().cells[0][0].character == 'r'));
+        let red = Theme::dark().ansi[1];
+        let row = &term.grid().cells[0];
+        let text: String = row[..4].iter().map(|c| c.character).collect();
+        assert_eq!(text, "red ");
+        assert!(row[..3].iter().all(|c| c.style.foreground == red));
+        assert_ne!(row[3].style.foreground, red, "SGR 0 resets the color");
+
+        // A sequence split across calls still parses
+        term.feed(b"\r\n\x1b[3");
+        term.feed(b"1mX");
+        assert!(sync_until(&mut term, |t| t.grid().cells[1][0].character == 'X'));
+        assert_eq!(term.grid().cells[1][0].style.foreground, red);
+    }
+
+    #[test]
+    fn test_combining_mark_shares_the_base_cell() {
+        use tide_core::TerminalBackend;
+        let mut term = Terminal::from_reader(std::io::empty(), 20, 3);
+        // "e" + COMBINING ACUTE ACCENT, then "x"
+        term.feed("e\u{301}x".as_bytes());
+        assert!(sync_until(&mut term, |t| t.grid().cells[0][1].character == 'x'));
+        let row = &term.grid().cells[0];
+        assert_eq!(row[0].character, 'e');
+        assert_eq!(row[0].zerowidth, ['\u{301}']);
+        assert!(row[1].zerowidth.is_empty());
+        assert_eq!(term.grid().to_plain_text().lines().next(), Some("e\u{301}x"));
+        assert_eq!(term.text_in_range((0, 0), (0, 2)), "e\u{301}x");
+
+        // Overwriting the cell drops the mark
+        term.feed(b"\rf");
+        assert!(sync_until(&mut term, |t| t.grid().cells[0][0].character == 'f'));
+        assert!(term.grid().cells[0][0].zerowidth.is_empty());
+    }
+
+    #[test]
+    fn test_last_sync_time_advances_with_each_sync() {
+        use std::time::{Duration, Instant};
+        let mut term = Terminal::from_reader(std::io::empty(), 20, 3);
+        let created = term.last_sync_time();
+        let mut stamps = Vec::new();
+        for _ in 0..3 {
+            std::thread::sleep(Duration::from_millis(2));
+            // A snapshot published before `before` may still be pending
+            let before = Instant::now();
+            assert!(sync_until(&mut term, |t| t.last_sync_time() >= before));
+            let stamp = term.last_sync_time();
+            assert!(stamp <= Instant::now(), "stamp is set when the sync completes");
+            stamps.push(stamp);
+        }
+        assert!(created < stamps[0]);
+        assert!(stamps.windows(2).all(|w| w[0] < w[1]), "{stamps:?}");
+    }
+
+    #[test]
+    fn test_clear_scrollback_keeps_screen() {
+        let mut term = settled_terminal();
+        term.bench_write_to_term(b"\x1b[2J\x1b[H");
+        term.bench_write_to_term(&numbered_lines(40));
+        assert!(term.history_size() > 0);
+        let screen = term.export_text(false);
+        assert!(screen.ends_with("line 39"));
+
+        term.clear_scrollback();
+        assert_eq!(term.history_size(), 0);
+        assert_eq!(term.display_offset(), 0);
+        assert_eq!(term.export_text(false), screen);
+    }
+
+    /// Sync until `cond` holds. A sync cycle already running when the bytes
+    /// were written can still publish the old state.
+    fn sync_until(term: &mut Terminal, cond: impl Fn(&Terminal) -> bool) -> bool {
+        use std::time::{Duration, Instant};
+        let deadline = Instant::now() + Duration::from_secs(5);
+        loop {
+            term.bench_sync_grid();
+            if cond(term) {
+                return true;
+            }
+            if Instant::now() > deadline {
+                return false;
+            }
+        }
+    }
+
+    #[test]
+    fn test_reset_clears_screen_and_history() {
+        let mut term = settled_terminal();
+        term.bench_write_to_term(&numbered_lines(40));
+        term.bench_write_to_term(b"\x1b[?1049h\x1b[?1h");
+        assert!(sync_until(&mut term, |t| t.modes().app_cursor()));
+
+        term.reset();
+        assert_eq!(term.history_size(), 0);
+        assert!(term.export_text(true).trim().is_empty());
+        assert!(sync_until(&mut term, |t| !t.modes().app_cursor()));
+    }
+
+    #[test]
+    fn test_mode_snapshot_follows_mode_sequences() {
+        let mut term = settled_terminal();
+        // Shells may turn bracketed paste on at the prompt; start from a known state
+        term.bench_write_to_term(b"\x1b[?2004l");
+        assert!(sync_until(&mut term, |t| !t.modes().bracketed_paste()));
+        let modes = term.modes();
+        assert!(modes.show_cursor());
+        assert!(!modes.bracketed_paste() && !modes.alt_screen() && !modes.mouse_reporting());
+
+        term.bench_write_to_term(b"\x1b[?2004h\x1b[?1049h\x1b[?1002h\x1b[?1006h\x1b[?1004h\x1b[?25l\x1b=");
+        // Not visible until the sync thread publishes the next snapshot
+        assert_eq!(term.modes(), modes);
+        assert!(sync_until(&mut term, |t| t.modes().bracketed_paste()));
+        let modes = term.modes();
+        assert!(modes.bracketed_paste());
+        assert!(modes.alt_screen());
+        assert!(modes.mouse_reporting());
+        assert!(modes.sgr_mouse());
+        assert!(modes.focus_reporting());
+        assert!(!modes.show_cursor());
+        assert!(modes.key_modes().app_keypad);
+
+        term.bench_write_to_term(b"\x1b[?1049l\x1b[?1002l\x1b[?25h");
+        assert!(sync_until(&mut term, |t| !t.modes().alt_screen()));
+        let modes = term.modes();
+        assert!(!modes.alt_screen() && !modes.mouse_reporting());
+        assert!(modes.show_cursor() && modes.bracketed_paste());
+    }
+
+    #[test]
+    fn test_hovered_cell_underlines_url_under_mouse() {
+        let mut term = settled_terminal();
+        term.bench_write_to_term(b"\x1b[2J\x1b[Hsee https://a.io/x\r\n");
+        term.bench_sync_grid();
+        assert_eq!(term.url_ranges()[0], vec![(4, 18)]);
+        let gen = term.grid_generation();
+
+        assert!(term.set_hovered_cell(Some((0, 10))));
+        assert_eq!(term.hovered_link(), Some((0, 4, 18)));
+        assert!(term.grid().cells[0][4..18].iter().all(|c| c.style.underline));
+        assert!(!term.grid().cells[0][3].style.underline);
+        assert!(term.grid_generation() > gen);
+        // Moving within the same link changes nothing
+        assert!(!term.set_hovered_cell(Some((0, 17))));
+
+        assert!(term.set_hovered_cell(Some((0, 2))));
+        assert_eq!(term.hovered_link(), None);
+        assert!(term.grid().cells[0].iter().all(|c| !c.style.underline));
+        assert!(!term.set_hovered_cell(None));
+    }
+
+    #[test]
+    fn test_cwd_follows_shell_cd_without_osc7() {
+        use std::time::{Duration, Instant};
+        use tide_core::TerminalBackend;
+
+        let start = std::env::temp_dir().join(format!("tide-cwd-start-{}", std::process::id()));
+        let target = std::env::temp_dir().join(format!("tide-cwd-target-{}", std::process::id()));
+        std::fs::create_dir_all(&start).unwrap();
+        std::fs::create_dir_all(&target).unwrap();
+        let start = start.canonicalize().unwrap();
+        let target = target.canonicalize().unwrap();
+
+        let mut term = Terminal::with_shell(80, 24, Some(start.clone()), true, Some("bash".to_string())).expect("PTY and bash available");
+        assert_eq!(term.reported_cwd(), None);
+
+        let wait_for = |term: &Terminal, want: &std::path::Path| {
+            let deadline = Instant::now() + Duration::from_secs(5);
+            loop {
+                let cwd = term.cwd().and_then(|p| p.canonicalize().ok());
+                if cwd.as_deref() == Some(want) || Instant::now() > deadline {
+                    return cwd;
+                }
+                std::thread::sleep(Duration::from_millis(50));
+            }
+        };
+
+        assert_eq!(wait_for(&term, &start).as_deref(), Some(start.as_path()));
+        term.write(format!("cd '{}'\n", target.display()).as_bytes());
+        assert_eq!(wait_for(&term, &target).as_deref(), Some(target.as_path()));
+        // Detection doesn't masquerade as an OSC 7 report.
+        assert_eq!(term.reported_cwd(), None);
+
+        drop(term);
+        let _ = std::fs::remove_dir(&start);
+        let _ = std::fs::remove_dir(&target);
+    }
+
+    #[cfg(feature = "metrics")]
+    #[test]
+    fn test_sync_recorder_take_resets() {
+        use std::time::Duration;
+        let recorder = crate::metrics::SyncRecorder::default();
+        recorder.record(Duration::from_micros(300), Duration::from_micros(100), 40);
+        recorder.record(Duration::from_micros(100), Duration::from_micros(50), 2);
+
+        let m = recorder.take();
+        assert_eq!(m.syncs, 2);
+        assert_eq!(m.total_sync, Duration::from_micros(400));
+        assert_eq!(m.max_sync, Duration::from_micros(300));
+        assert_eq!(m.mean_sync(), Duration::from_micros(200));
+        assert_eq!(m.max_lock_hold, Duration::from_micros(100));
+        assert_eq!(m.cells_converted, 42);
+        assert_eq!(recorder.take(), crate::SyncMetrics::default());
+    }
+
+    #[cfg(feature = "metrics")]
+    #[test]
+    fn test_sync_metrics_after_syncs() {
+        use std::time::{Duration, Instant};
+
+        let mut term = Terminal::new(80, 24).expect("PTY available");
+        // Each request marks the grid dirty and wakes the sync thread.
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while term.sync_count() < 3 && Instant::now() < deadline {
+            term.request_scroll_to_bottom();
+            std::thread::sleep(Duration::from_millis(10));
+        }
+
+        let m = term.take_sync_metrics();
+        assert!(m.syncs >= 3, "only {} syncs recorded", m.syncs);
+        assert!(m.total_sync > Duration::ZERO);
+        assert!(m.max_sync >= m.mean_sync());
+        assert!(m.max_sync < Duration::from_secs(1));
+        assert!(m.total_lock_hold <= m.total_sync);
+        // The first sync converts the whole 80×24 screen.
+        assert!(m.cells_converted >= 80 * 24);
+    }
+
+    #[test]
+    fn test_nonexistent_shell_path_is_shell_not_found() {
+        let result = Terminal::with_shell(80, 24, None, true, Some("/nonexistent/tide-shell".to_string()));
+        match result {
+            Err(TerminalError::ShellNotFound { shell }) => assert_eq!(shell, "/nonexistent/tide-shell"),
+            Err(e) => panic!("expected ShellNotFound, got {e}"),
+            Ok(_) => panic!("expected ShellNotFound, got a terminal"),
+        }
+    }
+
+    #[test]
+    fn test_shell_name_not_on_path_is_shell_not_found() {
+        let result = Terminal::with_shell(80, 24, None, true, Some("tide-no-such-shell".to_string()));
+        assert!(matches!(result, Err(TerminalError::ShellNotFound { .. })));
+    }
+
+    #[test]
+    fn test_terminal_error_from_io() {
+        let err: TerminalError = std::io::Error::other("boom").into();
+        assert!(matches!(err, TerminalError::Io(_)));
+        assert!(std::error::Error::source(&err).is_some());
+        assert!(TerminalError::ShellNotFound { shell: "fish".into() }.to_string().contains("$SHELL"));
+    }
+
+    #[test]
+    fn test_osc52_clipboard_set_requires_permission() {
+        let term = Terminal::new(80, 24).expect("PTY available");
+        // OSC 52 ; c ; base64("hello tide") BEL
+        let osc52 = b"\x1b]52;c;aGVsbG8gdGlkZQ==\x07";
+
+        assert!(!term.allow_osc52());
+        term.bench_write_to_term(osc52);
+        assert_eq!(term.take_clipboard_request(), None);
+
+        term.set_allow_osc52(true);
+        term.bench_write_to_term(osc52);
+        assert_eq!(term.take_clipboard_request().as_deref(), Some("hello tide"));
+        assert_eq!(term.take_clipboard_request(), None);
+    }
+
+    #[test]
+    fn test_osc_title_is_kept_for_the_app() {
+        let mut term = Terminal::from_reader(std::io::empty(), 20, 3);
+        assert_eq!(term.take_title_request(), None);
+        // OSC 2 then OSC 0: only the latest change is kept
+        term.feed(b"\x1b]2;vim\x07\x1b]0;build \xe2\x9c\x93\x07");
+        assert_eq!(term.take_title_request().as_deref(), Some("build \u{2713}"));
+        assert_eq!(term.take_title_request(), None);
+        // Popping the title stack (XTWINOPS 22/23) restores the pushed title
+        term.feed(b"\x1b[22t\x1b]2;inner\x07\x1b[23t");
+        assert_eq!(term.take_title_request().as_deref(), Some("build \u{2713}"));
+    }
+
+    #[test]
+    fn test_osc12_cursor_color_reaches_snapshot() {
+        let mut term = settled_terminal();
+        term.bench_sync_grid();
+        assert_eq!(term.cursor().color, None);
+        let fallback = term.cursor_style().fallback_color;
+        assert_eq!(term.cursor_color(), fallback);
+
+        // OSC 12 ; rgb:ff/80/00 BEL
+        term.bench_write_to_term(b"\x1b]12;rgb:ff/80/00\x07");
+        term.bench_sync_grid();
+        let expected = Color::rgb(1.0, 128.0 / 255.0, 0.0);
+        assert_eq!(term.cursor().color, Some(expected));
+        assert_eq!(term.cursor_color(), expected);
+
+        // OSC 112 resets to the configured fallback
+        term.bench_write_to_term(b"\x1b]112\x07");
+        term.bench_sync_grid();
+        assert_eq!(term.cursor().color, None);
+        term.set_cursor_style(CursorStyleConfig { reverse_video: true, fallback_color: Color::WHITE });
+        assert_eq!(term.cursor_color(), Color::WHITE);
+    }
+
+    // 10x12 red block: raster attributes, RGB color 1, two sixel bands
+    const RED_SIXEL: &[u8] = b"\x1bPq\"1;1;10;12#1;2;100;0;0#1!10~-!10~\x1b\\";
+
+    #[test]
+    fn test_sixel_payload_produces_image() {
+        let mut term = Terminal::from_reader(std::io::empty(), 20, 5);
+        // Cursor to row 2, column 4 (1-based); the image is placed there
+        term.feed(b"\x1b[2;4H");
+        term.feed(RED_SIXEL);
+        let images = term.take_images();
+        assert_eq!(images.len(), 1);
+        let image = &images[0];
+        assert_eq!(image.dims, (10, 12));
+        assert_eq!(image.rgba.len(), 10 * 12 * 4);
+        assert_eq!(&image.rgba[..4], &[255, 0, 0, 255]);
+        // 8x16 px cells: 10 px wide spans 2 columns, 12 px tall fits 1 row
+        assert_eq!(image.cells, tide_core::Rect::new(3.0, 1.0, 2.0, 1.0));
+        assert!(term.take_images().is_empty());
+    }
+
+    #[test]
+    fn test_sixel_queue_keeps_the_newest_images() {
+        let mut term = Terminal::from_reader(std::io::empty(), 20, 5);
+        // One image per column, so the kept ones show which were dropped
+        for col in 1..=sixel::MAX_PENDING_IMAGES + 2 {
+            term.feed(format!("\x1b[1;{col}H").as_bytes());
+            term.feed(RED_SIXEL);
+        }
+        let images = term.take_images();
+        assert_eq!(images.len(), sixel::MAX_PENDING_IMAGES);
+        assert_eq!(images[0].cells.x, 2.0);
+    }
+
+    #[test]
+    fn test_sixel_scanner_handles_split_reads() {
+        let mut scanner = sixel::SixelScanner::new();
+        let mut payloads = Vec::new();
+        for chunk in RED_SIXEL.chunks(3) {
+            scanner.feed(chunk, |_, payload| payloads.push(payload));
+        }
+        // DECRQSS (ESC P $ q) and an aborted sequence are not images
+        scanner.feed(b"\x1bP$qm\x1b\\\x1bPq#0!5~\x18", |_, payload| payloads.push(payload));
+        assert_eq!(payloads.len(), 1);
+        let image = sixel::decode(&payloads[0]).unwrap();
+        assert_eq!((image.width, image.height), (10, 12));
+    }
+
+    #[test]
+    fn test_sixel_decode_grows_past_raster_size() {
+        // No raster attributes; P2 = 1 leaves undrawn pixels transparent.
+        // '@' sets only the top pixel, '$' returns to column 0 of the band.
+        let image = sixel::decode(b"0;1;q#2!3@$#3A-#2@").unwrap();
+        assert_eq!((image.width, image.height), (3, 7));
+        let pixel = |x: usize, y: usize| &image.rgba[(y * 3 + x) * 4..(y * 3 + x) * 4 + 4];
+        assert_eq!(pixel(1, 0), &[204, 33, 33, 255]);
+        assert_eq!(pixel(0, 1), &[51, 204, 51, 255]);
+        assert_eq!(pixel(1, 1)[3], 0);
+        assert_eq!(pixel(0, 6), &[204, 33, 33, 255]);
+    }
+
+    #[test]
+    fn test_prompt_scanner_parses_osc133_across_split_reads() {
+        let data: &[u8] = b"\x1b]133;A\x07$ \x1b]133;B\x1b\\ls\x1b]133;C\x07\r\nout\n\x1b]7;file:///tmp\x07\x1b]133;D;2\x07";
+        let mut whole = Vec::new();
+        prompt::PromptScanner::new().feed(data, |end, kind| whole.push((end, kind)));
+        assert_eq!(
+            whole.iter().map(|&(_, kind)| kind).collect::<Vec<_>>(),
+            vec![
+                PromptMarkKind::PromptStart,
+                PromptMarkKind::CommandStart,
+                PromptMarkKind::OutputStart,
+                PromptMarkKind::CommandEnd { exit_code: Some(2) },
+            ]
+        );
+        // Each offset lands just past the marker's terminator
+        for &(end, _) in &whole {
+            assert!(data[..end].ends_with(b"\x07") || data[..end].ends_with(b"\x1b\\"));
+        }
+
+        let mut scanner = prompt::PromptScanner::new();
+        let mut split = Vec::new();
+        for (i, chunk) in data.chunks(2).enumerate() {
+            scanner.feed(chunk, |end, kind| split.push((i * 2 + end, kind)));
+        }
+        assert_eq!(split, whole);
+
+        assert_eq!(prompt::parse_osc133(b"133;D"), Some(PromptMarkKind::CommandEnd { exit_code: None }));
+        assert_eq!(prompt::parse_osc133(b"133;Z"), None);
+        assert_eq!(prompt::parse_osc133(b"1337;A"), None);
+    }
+
+    #[test]
+    fn test_prompt_mark_kind_round_trips_through_row_tags() {
+        let kinds = [
+            PromptMarkKind::PromptStart,
+            PromptMarkKind::CommandStart,
+            PromptMarkKind::OutputStart,
+            PromptMarkKind::CommandEnd { exit_code: None },
+            PromptMarkKind::CommandEnd { exit_code: Some(0) },
+            PromptMarkKind::CommandEnd { exit_code: Some(-1) },
+            PromptMarkKind::CommandEnd { exit_code: Some(i32::MIN) },
+        ];
+        for kind in kinds {
+            assert_eq!(PromptMarkKind::from_tag(kind.to_tag()), Some(kind));
+        }
+        assert_eq!(PromptMarkKind::from_tag(0), None);
+    }
+
+    #[test]
+    fn test_prompt_marks_line_up_with_emitted_lines() {
+        use std::time::{Duration, Instant};
+        // Two command cycles on a 3-row screen, so the first scrolls into history
+        let data = b"\x1b]133;A\x07$ \x1b]133;B\x07ls\x1b]133;C\x07\r\na\r\nb\r\n\x1b]133;D;0\x1b\\\
+            \x1b]133;A\x07$ \x1b]133;B\x07false\x1b]133;C\x07\r\n\x1b]133;D;1\x07\x1b]133;A\x07$ "
+            .to_vec();
+        let mut term = Terminal::from_reader(std::io::Cursor::new(data), 20, 3);
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while term.prompt_marks().len() < 9 {
+            assert!(Instant::now() < deadline, "prompt marks never reached the snapshot");
+            std::thread::sleep(Duration::from_millis(10));
+            term.process();
+        }
+        assert!(term.history_size() > 0);
+
+        let marks: Vec<(PromptMarkKind, usize)> = term.prompt_marks().iter().map(|m| (m.kind, m.line)).collect();
+        assert_eq!(
+            marks,
+            vec![
+                (PromptMarkKind::PromptStart, 0),
+                (PromptMarkKind::CommandStart, 0),
+                (PromptMarkKind::OutputStart, 0),
+                (PromptMarkKind::CommandEnd { exit_code: Some(0) }, 3),
+                (PromptMarkKind::PromptStart, 3),
+                (PromptMarkKind::CommandStart, 3),
+                (PromptMarkKind::OutputStart, 3),
+                (PromptMarkKind::CommandEnd { exit_code: Some(1) }, 4),
+                (PromptMarkKind::PromptStart, 4),
+            ]
+        );
+        let text = term.export_text(true);
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines[0], "$ ls");
+        assert_eq!(lines[3], "$ false");
+        assert_eq!(lines[4], "$");
+
+        // Clearing scrollback drops the marks with the lines they pointed at;
+        // the ones on screen stay, counted from the new top
+        term.clear_scrollback();
+        term.bench_sync_grid();
+        let marks: Vec<(PromptMarkKind, usize)> = term.prompt_marks().iter().map(|m| (m.kind, m.line)).collect();
+        assert_eq!(
+            marks,
+            vec![
+                (PromptMarkKind::CommandEnd { exit_code: Some(0) }, 1),
+                (PromptMarkKind::PromptStart, 1),
+                (PromptMarkKind::CommandStart, 1),
+                (PromptMarkKind::OutputStart, 1),
+                (PromptMarkKind::CommandEnd { exit_code: Some(1) }, 2),
+                (PromptMarkKind::PromptStart, 2),
+            ]
+        );
+    }
+
+    fn prompt_mark_lines(term: &mut Terminal) -> Vec<(PromptMarkKind, usize)> {
+        term.bench_sync_grid();
+        term.prompt_marks().iter().map(|m| (m.kind, m.line)).collect()
+    }
+
+    #[test]
+    fn test_prompt_marks_follow_wraps_and_cursor_moves() {
+        use tide_core::TerminalBackend;
+        let mut term = Terminal::from_reader(std::io::empty(), 10, 5);
+        // A soft wrap, then an absolute cursor move
+        term.feed(b"\x1b]133;A\x070123456789abc\x1b]133;B\x07\x1b[5;1H\x1b]133;C\x07");
+        assert_eq!(
+            prompt_mark_lines(&mut term),
+            vec![(PromptMarkKind::PromptStart, 0), (PromptMarkKind::CommandStart, 1), (PromptMarkKind::OutputStart, 4)]
+        );
+    }
+
+    #[test]
+    fn test_prompt_marks_are_dropped_on_the_alternate_screen() {
+        use tide_core::TerminalBackend;
+        let mut term = Terminal::from_reader(std::io::empty(), 10, 5);
+        term.feed(b"\x1b[?1049h\x1b]133;A\x07\x1b[3;1H\x1b]133;B\x07\x1b[?1049l");
+        assert!(prompt_mark_lines(&mut term).is_empty());
+    }
+
+    #[test]
+    fn test_prompt_marks_follow_reflow() {
+        use tide_core::TerminalBackend;
+        let mut term = Terminal::from_reader(std::io::empty(), 10, 5);
+        term.feed(b"0123456789abcde\x1b]133;A\x07");
+        assert_eq!(prompt_mark_lines(&mut term), vec![(PromptMarkKind::PromptStart, 1)]);
+        // Widening joins the wrapped line; the mark moves with its text
+        term.resize(20, 5);
+        assert_eq!(prompt_mark_lines(&mut term), vec![(PromptMarkKind::PromptStart, 0)]);
+    }
+
+    #[test]
+    fn test_prompt_marks_survive_the_scrollback_cap() {
+        use tide_core::TerminalBackend;
+        let mut term = Terminal::from_reader(std::io::empty(), 10, 3);
+        let lines = |n: usize| "x\r\n".repeat(n).into_bytes();
+        term.feed(b"\x1b]133;A\x07");
+        term.feed(&lines(5000));
+        term.feed(b"\x1b]133;B\x07");
+        term.feed(&lines(6000));
+        term.feed(b"\x1b]133;C\x07");
+        let marks = prompt_mark_lines(&mut term);
+        // The first mark's line fell off the top of the full scrollback
+        let history = term.history_size();
+        assert_eq!(history, 10_000);
+        assert_eq!(
+            marks,
+            vec![(PromptMarkKind::CommandStart, history + 2 - 6000), (PromptMarkKind::OutputStart, history + 2)]
+        );
+    }
+
+    #[test]
+    fn test_write_filter_strips_controls_per_filter() {
+        let payload = "echo hi\r\x1b[201~rm -rf ~\n";
+        // Safe paste keeps the newline only; ESC and CR are gone
+        assert_eq!(WriteFilter::SAFE_PASTE.apply(payload), "echo hi[201~rm -rf ~\n");
+        // A custom keep list lets CR through but still drops ESC
+        let keep_cr = WriteFilter { strip_c0_except: Some(b"\r"), confirm_on_newline: false };
+        assert_eq!(keep_cr.apply(payload), "echo hi\r[201~rm -rf ~");
+        // C1 controls (8-bit CSI) are stripped too; DEL is kept
+        assert_eq!(WriteFilter::SAFE_PASTE.apply("a\u{9b}31mb\x7f"), "a31mb\x7f");
+        // The default filters nothing
+        assert_eq!(WriteFilter::default(), WriteFilter::NONE);
+        assert_eq!(WriteFilter::NONE.apply(payload), payload);
+        // Bytes: UTF-8 goes through `apply`; anything else loses only C0
+        assert_eq!(&*WriteFilter::SAFE_PASTE.apply_bytes(payload.as_bytes()), b"echo hi[201~rm -rf ~\n");
+        assert_eq!(&*WriteFilter::SAFE_PASTE.apply_bytes(b"\xff\x1b\x9b\t"), b"\xff\x9b\t");
+    }
+
+    #[test]
+    fn test_write_is_filtered_and_keystrokes_are_not() {
+        let mut term = settled_terminal();
+        term.set_write_filter(WriteFilter::SAFE_PASTE);
+        // The CR that would run the first half early and the ESC are gone
+        term.write(b"echo \"<one\x1b[7m\rtwo-$((6*7))>\"\n");
+        assert!(wait_for_text(&term, "<one[7mtwo-42>"));
+        // Enter typed by the user still runs the line
+        term.write_keystroke(b"echo typed-$((6*8))\r");
+        assert!(wait_for_text(&term, "typed-48"));
+    }
+
+    #[test]
+    fn test_write_filter_passes_benign_text_and_flags_multiline() {
+        let text = "plain text\twith a tab and ünïcode";
+        assert!(matches!(WriteFilter::SAFE_PASTE.apply(text), std::borrow::Cow::Borrowed(t) if t == text));
+        assert!(!WriteFilter::SAFE_PASTE.needs_confirmation(text));
+
+        let mut term = Terminal::from_reader(std::io::empty(), 20, 3);
+        assert!(!term.paste_needs_confirmation("ls\nrm -rf ~\n"), "off by default");
+        term.set_write_filter(WriteFilter::SAFE_PASTE);
+        assert_eq!(term.write_filter(), WriteFilter::SAFE_PASTE);
+        assert!(term.paste_needs_confirmation("ls\nrm -rf ~\n"));
+        // A line break that the filter strips doesn't count
+        let strip_all = WriteFilter { strip_c0_except: Some(b""), confirm_on_newline: true };
+        assert!(!strip_all.needs_confirmation("ls\r"));
+    }
+
+    #[test]
+    fn test_resize_debounce_sends_once_without_process() {
+        use crate::resize::ResizeDebouncer;
+        use alacritty_terminal::event::WindowSize;
+        use std::time::{Duration, Instant};
+
+        // Test notifier: record every size the debouncer sends
+        let sent = Arc::new(Mutex::new(Vec::new()));
+        let debouncer = {
+            let sent = sent.clone();
+            ResizeDebouncer::new(move |size: WindowSize| sent.lock().unwrap().push((size.num_cols, size.num_lines)))
+        };
+        let size = |num_cols, num_lines| WindowSize { num_cols, num_lines, cell_width: 8, cell_height: 16 };
+        let debounce = Duration::from_millis(50);
+
+        // A quick drag: three sizes inside the interval, then nothing at all
+        for (cols, rows) in [(90, 30), (100, 32), (110, 35)] {
+            debouncer.schedule(size(cols, rows), Instant::now() + debounce);
+        }
+        assert!(sent.lock().unwrap().is_empty(), "held until the size is stable");
+        std::thread::sleep(debounce * 4);
+        assert_eq!(*sent.lock().unwrap(), vec![(110, 35)]);
+
+        // Nothing more arrives later
+        std::thread::sleep(debounce * 2);
+        assert_eq!(sent.lock().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_set_resize_debounce() {
+        let mut term = Terminal::from_reader(std::io::empty(), 20, 3);
+        assert_eq!(term.resize_debounce(), DEFAULT_RESIZE_DEBOUNCE);
+        term.set_resize_debounce(Duration::from_millis(5));
+        assert_eq!(term.resize_debounce(), Duration::from_millis(5));
+        // Without a PTY there is nothing to notify; the grid still resizes
+        term.resize(30, 4);
+        assert!(!term.resize_pending());
+        term.bench_sync_grid();
+        assert_eq!(term.grid().cols, 30);
+    }
+
+    #[test]
+    fn test_resize_pending_until_the_debounce_flushes() {
+        let mut term = Terminal::with_shell(80, 24, None, true, Some("bash".to_string())).expect("PTY and bash available");
+        term.set_resize_debounce(Duration::from_millis(50));
+        assert!(!term.resize_pending());
+
+        term.resize(100, 30);
+        assert!(term.resize_pending());
+        assert_eq!(term.pending_pty_size(), Some((100, 30)));
+        assert_eq!((term.current_cols(), term.current_rows()), (100, 30));
+
+        // The grid size changes at once; the PTY size after the debounce
+        std::thread::sleep(Duration::from_millis(200));
+        assert!(!term.resize_pending());
+        assert_eq!(term.pending_pty_size(), None);
+    }
 }
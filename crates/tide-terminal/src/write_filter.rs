@@ -0,0 +1,76 @@
+// Control-character filter for text entering the PTY from outside the
+// keyboard (`write`, `Terminal::paste`, `Terminal::write_text`). Text copied
+// from a web page can hide `\r` or escape sequences that run commands as soon
+// as they reach the shell; the filter strips them before bracketing.
+// Keystrokes go through `Terminal::write_keystroke` unfiltered: Enter and
+// Ctrl+C must still work.
+
+use std::borrow::Cow;
+
+/// Which control characters pasted or written text may carry, set with
+/// `Terminal::set_write_filter`. The default filters nothing.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct WriteFilter {
+    /// Strip C0 (U+0000–U+001F) and C1 (U+0080–U+009F) controls except the
+    /// listed bytes; `None` strips nothing. DEL (0x7f) is always kept, since
+    /// IME replacement commits use it to erase composed characters.
+    pub strip_c0_except: Option<&'static [u8]>,
+    /// Text with a line break left after stripping needs confirmation before
+    /// it is pasted (see `Terminal::paste_needs_confirmation`).
+    pub confirm_on_newline: bool,
+}
+
+impl WriteFilter {
+    /// No filtering (the default).
+    pub const NONE: Self = Self { strip_c0_except: None, confirm_on_newline: false };
+
+    /// Keep tabs and newlines, strip every other control, and confirm
+    /// multi-line pastes.
+    pub const SAFE_PASTE: Self = Self { strip_c0_except: Some(b"\t\n"), confirm_on_newline: true };
+
+    /// `text` without the controls this filter strips. Borrowed when nothing
+    /// had to go.
+    pub fn apply<'a>(&self, text: &'a str) -> Cow<'a, str> {
+        let Some(keep) = self.strip_c0_except else {
+            return Cow::Borrowed(text);
+        };
+        let strip = |c: char| is_c0_or_c1(c) && !(c.is_ascii() && keep.contains(&(c as u8)));
+        if text.chars().any(strip) {
+            Cow::Owned(text.chars().filter(|&c| !strip(c)).collect())
+        } else {
+            Cow::Borrowed(text)
+        }
+    }
+
+    /// `apply` for raw bytes. Bytes that aren't UTF-8 can't carry C1
+    /// controls as characters, so only their C0 bytes are stripped.
+    pub fn apply_bytes<'a>(&self, data: &'a [u8]) -> Cow<'a, [u8]> {
+        let Some(keep) = self.strip_c0_except else {
+            return Cow::Borrowed(data);
+        };
+        match std::str::from_utf8(data) {
+            Ok(text) => match self.apply(text) {
+                Cow::Borrowed(_) => Cow::Borrowed(data),
+                Cow::Owned(text) => Cow::Owned(text.into_bytes()),
+            },
+            Err(_) => {
+                let strip = |b: &u8| *b < 0x20 && !keep.contains(b);
+                if data.iter().any(strip) {
+                    Cow::Owned(data.iter().copied().filter(|b| !strip(b)).collect())
+                } else {
+                    Cow::Borrowed(data)
+                }
+            }
+        }
+    }
+
+    /// Whether pasting `text` should be confirmed first: `confirm_on_newline`
+    /// is set and a `\n` or `\r` survives `apply`.
+    pub fn needs_confirmation(&self, text: &str) -> bool {
+        self.confirm_on_newline && self.apply(text).contains(['\n', '\r'])
+    }
+}
+
+fn is_c0_or_c1(c: char) -> bool {
+    matches!(c, '\u{0}'..='\u{1f}' | '\u{80}'..='\u{9f}')
+}
@@ -0,0 +1,174 @@
+// Filesystem abstraction: FsTree reads directories through `FileSystem`, so
+// edge cases (permission errors, symlink loops, huge dirs) can be modeled in
+// memory instead of on disk.
+
+use std::collections::BTreeMap;
+use std::io;
+use std::path::{Component, Path, PathBuf};
+use std::sync::Mutex;
+
+/// What FsTree needs to know about a path.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FileMetadata {
+    pub is_dir: bool,
+}
+
+/// The filesystem operations FsTree uses.
+pub trait FileSystem: Send + Sync {
+    /// Paths of the entries in directory `path` (in no particular order).
+    /// Entries that can't be read are skipped.
+    fn read_dir(&self, path: &Path) -> io::Result<Vec<PathBuf>>;
+
+    /// Metadata of `path`, following symlinks.
+    fn metadata(&self, path: &Path) -> io::Result<FileMetadata>;
+
+    /// Target of the symlink at `path`.
+    fn read_link(&self, path: &Path) -> io::Result<PathBuf>;
+}
+
+/// The real filesystem, via `std::fs`.
+pub struct StdFileSystem;
+
+impl FileSystem for StdFileSystem {
+    fn read_dir(&self, path: &Path) -> io::Result<Vec<PathBuf>> {
+        Ok(std::fs::read_dir(path)?
+            .filter_map(|entry| Some(entry.ok()?.path()))
+            .collect())
+    }
+
+    fn metadata(&self, path: &Path) -> io::Result<FileMetadata> {
+        Ok(FileMetadata { is_dir: std::fs::metadata(path)?.is_dir() })
+    }
+
+    fn read_link(&self, path: &Path) -> io::Result<PathBuf> {
+        std::fs::read_link(path)
+    }
+}
+
+/// Symlinks followed before resolution fails, like the OS's ELOOP limit.
+const MAX_SYMLINK_HOPS: usize = 40;
+
+#[derive(Debug, Clone)]
+enum Node {
+    Dir { readable: bool },
+    File,
+    Symlink(PathBuf),
+}
+
+/// An in-memory filesystem for tests. Paths are absolute; symlink targets are
+/// absolute paths whose parent directories are real (not symlinks).
+pub struct MemoryFileSystem {
+    nodes: Mutex<BTreeMap<PathBuf, Node>>,
+}
+
+impl Default for MemoryFileSystem {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MemoryFileSystem {
+    /// An empty filesystem containing only `/`.
+    pub fn new() -> Self {
+        let mut nodes = BTreeMap::new();
+        nodes.insert(PathBuf::from("/"), Node::Dir { readable: true });
+        Self { nodes: Mutex::new(nodes) }
+    }
+
+    /// Create directory `path` and any missing parents.
+    pub fn add_dir(&self, path: impl AsRef<Path>) {
+        self.insert(path.as_ref(), Node::Dir { readable: true });
+    }
+
+    /// Create an empty file at `path`, creating missing parents.
+    pub fn add_file(&self, path: impl AsRef<Path>) {
+        self.insert(path.as_ref(), Node::File);
+    }
+
+    /// Create a symlink at `path` pointing to `target`, creating missing parents.
+    pub fn add_symlink(&self, path: impl AsRef<Path>, target: impl AsRef<Path>) {
+        self.insert(path.as_ref(), Node::Symlink(target.as_ref().to_path_buf()));
+    }
+
+    /// Make reading directory `path` fail with `PermissionDenied`.
+    pub fn deny_read(&self, path: impl AsRef<Path>) {
+        self.insert(path.as_ref(), Node::Dir { readable: false });
+    }
+
+    /// Remove `path` and everything under it.
+    pub fn remove(&self, path: impl AsRef<Path>) {
+        let path = path.as_ref();
+        self.lock().retain(|p, _| !p.starts_with(path));
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, BTreeMap<PathBuf, Node>> {
+        self.nodes.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn insert(&self, path: &Path, node: Node) {
+        let mut nodes = self.lock();
+        for ancestor in path.ancestors().skip(1) {
+            nodes.entry(ancestor.to_path_buf()).or_insert(Node::Dir { readable: true });
+        }
+        nodes.insert(path.to_path_buf(), node);
+    }
+
+    /// Resolve symlinks along `path` to the node's real path. The last
+    /// component is followed only if `follow_last`.
+    fn resolve(nodes: &BTreeMap<PathBuf, Node>, path: &Path, follow_last: bool) -> io::Result<PathBuf> {
+        let components: Vec<Component> = path.components().collect();
+        let mut real = PathBuf::new();
+        let mut hops = 0;
+        for (i, component) in components.iter().enumerate() {
+            let last = i + 1 == components.len();
+            real.push(component);
+            while let Some(Node::Symlink(target)) = nodes.get(&real) {
+                if last && !follow_last {
+                    break;
+                }
+                hops += 1;
+                if hops > MAX_SYMLINK_HOPS {
+                    return Err(io::Error::other("too many levels of symbolic links"));
+                }
+                real = target.clone();
+            }
+            match nodes.get(&real) {
+                None => return Err(io::ErrorKind::NotFound.into()),
+                Some(Node::File) if !last => return Err(io::ErrorKind::NotFound.into()),
+                _ => {}
+            }
+        }
+        Ok(real)
+    }
+}
+
+impl FileSystem for MemoryFileSystem {
+    fn read_dir(&self, path: &Path) -> io::Result<Vec<PathBuf>> {
+        let nodes = self.lock();
+        let real = Self::resolve(&nodes, path, true)?;
+        match nodes.get(&real) {
+            Some(Node::Dir { readable: true }) => Ok(nodes
+                .keys()
+                .filter(|p| p.parent() == Some(real.as_path()))
+                .filter_map(|p| Some(path.join(p.file_name()?)))
+                .collect()),
+            Some(Node::Dir { readable: false }) => Err(io::ErrorKind::PermissionDenied.into()),
+            _ => Err(io::Error::other("not a directory")),
+        }
+    }
+
+    fn metadata(&self, path: &Path) -> io::Result<FileMetadata> {
+        let nodes = self.lock();
+        let real = Self::resolve(&nodes, path, true)?;
+        Ok(FileMetadata { is_dir: matches!(nodes.get(&real), Some(Node::Dir { .. })) })
+    }
+
+    fn read_link(&self, path: &Path) -> io::Result<PathBuf> {
+        let nodes = self.lock();
+        let real = Self::resolve(&nodes, path, false)?;
+        match nodes.get(&real) {
+            Some(Node::Symlink(target)) => Ok(target.clone()),
+            _ => Err(io::ErrorKind::InvalidInput.into()),
+        }
+    }
+}
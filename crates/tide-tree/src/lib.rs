@@ -4,35 +4,40 @@
 use notify::{RecommendedWatcher, RecursiveMode, Watcher};
 use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
-use std::sync::mpsc;
+use std::sync::{mpsc, Arc, Mutex, MutexGuard};
 use std::time::Instant;
 use tide_core::{FileEntry, FileTreeSource, TreeEntry};
 use unicode_normalization::UnicodeNormalization;
 
-/// Reads a directory and returns sorted FileEntry items.
+mod fs;
+
+pub use fs::{FileMetadata, FileSystem, MemoryFileSystem, StdFileSystem};
+
+/// Reads a directory through `fs` and returns sorted FileEntry items.
 /// Directories come first, then files, each group sorted alphabetically (case-insensitive).
 /// Permission errors and unreadable entries are silently skipped.
-/// Symlinks are followed.
-fn read_directory(path: &Path) -> Vec<FileEntry> {
-    let read_dir = match std::fs::read_dir(path) {
-        Ok(rd) => rd,
+/// Symlinks are followed; one that can't be resolved (broken, or a cycle) is
+/// listed as a file.
+pub fn read_directory(fs: &dyn FileSystem, path: &Path) -> Vec<FileEntry> {
+    let paths = match fs.read_dir(path) {
+        Ok(paths) => paths,
         Err(_) => return Vec::new(),
     };
 
-    let mut entries: Vec<FileEntry> = read_dir
-        .filter_map(|entry| {
-            let entry = entry.ok()?;
-            // Follow symlinks: use std::fs::metadata (follows symlinks)
-            // Fall back to symlink_metadata if that fails (e.g. broken symlink)
-            let metadata = std::fs::metadata(entry.path())
-                .or_else(|_| entry.metadata())
-                .ok()?;
-            let name: String = entry.file_name().into_string().ok()?.nfc().collect();
-            Some(FileEntry {
-                name,
-                path: entry.path(),
-                is_dir: metadata.is_dir(),
-            })
+    let mut entries: Vec<FileEntry> = paths
+        .into_iter()
+        .filter_map(|path| {
+            // Follow symlinks; if that fails but the entry is a symlink
+            // (broken or looping), show it as a file.
+            let is_dir = match fs.metadata(&path) {
+                Ok(metadata) => metadata.is_dir,
+                Err(_) => {
+                    fs.read_link(&path).ok()?;
+                    false
+                }
+            };
+            let name: String = path.file_name()?.to_str()?.nfc().collect();
+            Some(FileEntry { name, path, is_dir })
         })
         .collect();
 
@@ -49,6 +54,41 @@ fn read_directory(path: &Path) -> Vec<FileEntry> {
     entries
 }
 
+/// Source of directory listings. `FsTree` calls it on its loader thread (for
+/// the root, expansions and refreshes), so a slow implementation (network
+/// mount) doesn't block the UI.
+pub trait DirectoryProvider: Send + Sync {
+    /// Sorted children of `path` (directories first); empty if unreadable.
+    fn read_dir(&self, path: &Path) -> Vec<FileEntry>;
+}
+
+/// Any filesystem lists directories with `read_directory`.
+impl<F: FileSystem> DirectoryProvider for F {
+    fn read_dir(&self, path: &Path) -> Vec<FileEntry> {
+        read_directory(self, path)
+    }
+}
+
+/// Name shown for the placeholder row of a directory still loading.
+pub const LOADING_PLACEHOLDER: &str = "loading…";
+
+/// A directory for the loader thread to read: (load id, directory).
+type LoadRequest = (u64, PathBuf);
+
+/// A finished background load: (load id, directory, children).
+type LoadResult = (u64, PathBuf, Vec<FileEntry>);
+
+/// Wake callback, shared with the loader thread.
+type SharedWaker = Arc<Mutex<Option<Box<dyn Fn() + Send>>>>;
+
+/// Current load id per directory, shared with the loader thread so it skips
+/// requests cancelled while they were queued.
+type LoadIds = Arc<Mutex<HashMap<PathBuf, u64>>>;
+
+fn lock_ids(ids: &Mutex<HashMap<PathBuf, u64>>) -> MutexGuard<'_, HashMap<PathBuf, u64>> {
+    ids.lock().unwrap_or_else(|e| e.into_inner())
+}
+
 pub struct FsTree {
     root: PathBuf,
     /// The flattened list of visible entries, rebuilt after any mutation.
@@ -65,10 +105,43 @@ pub struct FsTree {
     last_event_time: Option<Instant>,
     /// True when events arrived during the debounce window and need processing.
     pending_events: bool,
+    /// Directories being loaded in the background, with their load id.
+    /// Removing an entry cancels the load: the loader skips it if it is
+    /// still queued, and its result is dropped on arrival otherwise.
+    loading: LoadIds,
+    next_load_id: u64,
+    /// Requests to the loader thread, which reads the current ones one at a
+    /// time in order. Dropping the sender (with the tree) stops the thread.
+    request_tx: mpsc::Sender<LoadRequest>,
+    load_rx: mpsc::Receiver<LoadResult>,
+    /// Called from the loader thread when a load finishes.
+    waker: SharedWaker,
 }
 
 impl FsTree {
     pub fn new(root: PathBuf) -> Self {
+        Self::with_provider(root, Arc::new(StdFileSystem))
+    }
+
+    /// Tree over the filesystem `fs` (e.g. a `MemoryFileSystem` in tests).
+    pub fn with_fs<F: FileSystem + 'static>(root: PathBuf, fs: Arc<F>) -> Self {
+        Self::with_provider(root, fs)
+    }
+
+    /// Tree whose directory listings come from `provider`.
+    pub fn with_provider(root: PathBuf, provider: Arc<dyn DirectoryProvider>) -> Self {
+        let (request_tx, request_rx) = mpsc::channel();
+        let (load_tx, load_rx) = mpsc::channel();
+        let waker: SharedWaker = Arc::new(Mutex::new(None));
+        let loading: LoadIds = Arc::new(Mutex::new(HashMap::new()));
+        {
+            let waker = waker.clone();
+            let loading = loading.clone();
+            std::thread::Builder::new()
+                .name("tree-load".into())
+                .spawn(move || run_loader(&*provider, &request_rx, &load_tx, &loading, &waker))
+                .expect("failed to spawn file tree loader thread");
+        }
         let mut tree = FsTree {
             root: PathBuf::new(),
             entries: Vec::new(),
@@ -78,18 +151,39 @@ impl FsTree {
             event_rx: None,
             last_event_time: None,
             pending_events: false,
+            loading,
+            next_load_id: 0,
+            request_tx,
+            load_rx,
+            waker,
         };
         tree.set_root(root);
         tree
     }
 
-    /// Call this periodically to process any pending filesystem events.
-    /// Events are debounced: changes within 100ms of the last processed batch
-    /// are deferred and processed once the debounce window expires.
+    /// Set a callback invoked from the loader thread when a directory load
+    /// finishes, so the event loop can wake and call `poll_events`.
+    pub fn set_waker(&self, f: Box<dyn Fn() + Send>) {
+        if let Ok(mut guard) = self.waker.lock() {
+            *guard = Some(f);
+        }
+    }
+
+    /// Whether `path` (the root, or an expanded directory) is still loading.
+    pub fn is_loading(&self, path: &Path) -> bool {
+        lock_ids(&self.loading).contains_key(path)
+    }
+
+    /// Call this periodically to apply finished directory loads and process
+    /// any pending filesystem events. Events are debounced: changes within
+    /// 100ms of the last processed batch are deferred and processed once the
+    /// debounce window expires. Returns true if the visible entries changed.
     pub fn poll_events(&mut self) -> bool {
+        let loaded = self.apply_loads();
+
         let rx = match self.event_rx.as_ref() {
             Some(rx) => rx,
-            None => return false,
+            None => return loaded,
         };
 
         // Drain all pending events from the channel.
@@ -100,21 +194,42 @@ impl FsTree {
         }
 
         if !self.pending_events {
-            return false;
+            return loaded;
         }
 
         // Debounce: defer if we processed events less than 100ms ago.
         let now = Instant::now();
         if let Some(last) = self.last_event_time {
             if now.duration_since(last).as_millis() < 100 {
-                return false;
+                return loaded;
             }
         }
 
         self.pending_events = false;
         self.last_event_time = Some(now);
+        // The re-read lands in a later poll
         self.refresh();
-        true
+        loaded
+    }
+
+    /// Move finished loads into the cache. Results for loads that were
+    /// cancelled (collapsed, root changed) or superseded are dropped.
+    fn apply_loads(&mut self) -> bool {
+        let mut changed = false;
+        while let Ok((id, path, children)) = self.load_rx.try_recv() {
+            let mut loading = lock_ids(&self.loading);
+            if loading.get(&path) != Some(&id) {
+                continue;
+            }
+            loading.remove(&path);
+            drop(loading);
+            self.children_cache.insert(path, children);
+            changed = true;
+        }
+        if changed {
+            self.rebuild_visible();
+        }
+        changed
     }
 
     /// Returns true if there are events waiting for the debounce window to expire.
@@ -165,19 +280,78 @@ impl FsTree {
                 depth,
                 is_expanded,
                 has_children,
+                is_placeholder: false,
             });
 
             if is_expanded {
-                self.walk_dir(&child.path, depth + 1, out);
+                // A refresh keeps showing the old children until it lands
+                if self.children_cache.contains_key(&child.path) {
+                    self.walk_dir(&child.path, depth + 1, out);
+                } else if self.is_loading(&child.path) {
+                    out.push(Self::loading_placeholder(&child.path, depth + 1));
+                }
             }
         }
     }
 
-    /// Ensure a directory's children are loaded into the cache.
+    /// The "loading…" row shown under a directory whose children are being
+    /// read. Its path is the loading directory's own path.
+    fn loading_placeholder(dir: &Path, depth: usize) -> TreeEntry {
+        TreeEntry {
+            entry: FileEntry {
+                name: LOADING_PLACEHOLDER.to_string(),
+                path: dir.to_path_buf(),
+                is_dir: false,
+            },
+            depth,
+            is_expanded: false,
+            has_children: false,
+            is_placeholder: true,
+        }
+    }
+
+    /// Start loading a directory's children in the background, unless they
+    /// are cached or already loading.
     fn ensure_loaded(&mut self, path: &Path) {
-        if !self.children_cache.contains_key(path) {
-            let children = read_directory(path);
-            self.children_cache.insert(path.to_path_buf(), children);
+        if self.children_cache.contains_key(path) || self.is_loading(path) {
+            return;
+        }
+        self.request_load(path);
+    }
+
+    /// Queue a read of `path` on the loader thread. A load already in flight
+    /// for it is superseded: its result is dropped on arrival.
+    fn request_load(&mut self, path: &Path) {
+        let id = self.next_load_id;
+        self.next_load_id += 1;
+        lock_ids(&self.loading).insert(path.to_path_buf(), id);
+        let _ = self.request_tx.send((id, path.to_path_buf()));
+    }
+}
+
+/// Loader thread: read requested directories in order until the tree (and
+/// with it the request sender or the result receiver) is dropped. Requests
+/// cancelled or superseded while queued (collapse, new root) are skipped
+/// without reading, so a new root doesn't wait behind stale reads.
+fn run_loader(
+    provider: &dyn DirectoryProvider,
+    requests: &mpsc::Receiver<LoadRequest>,
+    results: &mpsc::Sender<LoadResult>,
+    loading: &Mutex<HashMap<PathBuf, u64>>,
+    waker: &SharedWaker,
+) {
+    while let Ok((id, path)) = requests.recv() {
+        if lock_ids(loading).get(&path) != Some(&id) {
+            continue;
+        }
+        let children = provider.read_dir(&path);
+        if results.send((id, path, children)).is_err() {
+            return;
+        }
+        if let Ok(guard) = waker.lock() {
+            if let Some(ref f) = *guard {
+                f();
+            }
         }
     }
 }
@@ -188,10 +362,12 @@ impl tide_core::FileTreeSource for FsTree {
         self.expanded.clear();
         self.children_cache.clear();
         self.entries.clear();
+        // Drop pending loads from the old root.
+        lock_ids(&self.loading).clear();
 
-        // Load the root directory's children.
-        let children = read_directory(&self.root);
-        self.children_cache.insert(self.root.clone(), children);
+        // Load the root directory's children in the background.
+        let root = self.root.clone();
+        self.request_load(&root);
 
         self.rebuild_visible();
         self.start_watcher();
@@ -208,27 +384,25 @@ impl tide_core::FileTreeSource for FsTree {
     fn toggle(&mut self, path: &Path) {
         if self.expanded.contains(path) {
             self.expanded.remove(path);
+            // Cancel a load still in flight; expanding again restarts it.
+            lock_ids(&self.loading).remove(path);
         } else {
             self.expanded.insert(path.to_path_buf());
-            // Lazy-load children if not yet cached.
+            // Lazy-load children in the background if not yet cached.
             self.ensure_loaded(path);
         }
         self.rebuild_visible();
     }
 
     fn refresh(&mut self) {
-        // Re-read root directory.
-        let root_children = read_directory(&self.root);
-        self.children_cache.insert(self.root.clone(), root_children);
-
-        // Re-read all expanded directories.
+        // Re-read the root and all expanded directories in the background;
+        // the cached children stay visible until the new ones arrive.
+        let root = self.root.clone();
+        self.request_load(&root);
         let expanded_dirs: Vec<PathBuf> = self.expanded.iter().cloned().collect();
         for dir in &expanded_dirs {
-            let children = read_directory(dir);
-            self.children_cache.insert(dir.clone(), children);
+            self.request_load(dir);
         }
-
-        self.rebuild_visible();
     }
 }
 
@@ -2,9 +2,81 @@
 mod tests {
     use crate::*;
     use std::fs;
+    use std::sync::{Arc, Condvar, Mutex};
+    use std::time::{Duration, Instant};
     use tempfile::TempDir;
     use tide_core::FileTreeSource;
 
+    /// Poll until no directory is loading (5s timeout).
+    fn wait_for_loads(tree: &mut FsTree) {
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while !lock_ids(&tree.loading).is_empty() {
+            assert!(Instant::now() < deadline, "directory load did not finish");
+            tree.poll_events();
+            std::thread::sleep(Duration::from_millis(1));
+        }
+    }
+
+    /// `tree` once its root has loaded.
+    fn loaded(mut tree: FsTree) -> FsTree {
+        wait_for_loads(&mut tree);
+        tree
+    }
+
+    /// Toggle, then wait for the expansion's background load.
+    fn toggle_and_wait(tree: &mut FsTree, path: &Path) {
+        tree.toggle(path);
+        wait_for_loads(tree);
+    }
+
+    /// Reads the real filesystem, but blocks reads of anything other than the
+    /// root until `release` is called — a stand-in for a slow network mount.
+    /// Every read is logged as it starts.
+    struct GatedProvider {
+        root: PathBuf,
+        open: Mutex<bool>,
+        cond: Condvar,
+        reads: Mutex<Vec<PathBuf>>,
+    }
+
+    impl GatedProvider {
+        fn new(root: &Path) -> Arc<Self> {
+            Arc::new(Self {
+                root: root.to_path_buf(),
+                open: Mutex::new(false),
+                cond: Condvar::new(),
+                reads: Mutex::new(Vec::new()),
+            })
+        }
+
+        /// Wait until the loader has started reading `path`.
+        fn wait_for_read(&self, path: &Path) {
+            let deadline = Instant::now() + Duration::from_secs(5);
+            while !self.reads.lock().unwrap().iter().any(|p| p == path) {
+                assert!(Instant::now() < deadline, "{} was never read", path.display());
+                std::thread::sleep(Duration::from_millis(1));
+            }
+        }
+
+        fn release(&self) {
+            *self.open.lock().unwrap() = true;
+            self.cond.notify_all();
+        }
+    }
+
+    impl DirectoryProvider for GatedProvider {
+        fn read_dir(&self, path: &Path) -> Vec<FileEntry> {
+            self.reads.lock().unwrap().push(path.to_path_buf());
+            if path != self.root {
+                let mut open = self.open.lock().unwrap();
+                while !*open {
+                    open = self.cond.wait(open).unwrap();
+                }
+            }
+            read_directory(&StdFileSystem, path)
+        }
+    }
+
     /// Helper to create a temp directory with some structure.
     fn setup_temp_dir() -> TempDir {
         let tmp = TempDir::new().expect("failed to create temp dir");
@@ -27,7 +99,7 @@ mod tests {
     #[test]
     fn test_set_root_populates_entries() {
         let tmp = setup_temp_dir();
-        let tree = FsTree::new(tmp.path().to_path_buf());
+        let tree = loaded(FsTree::new(tmp.path().to_path_buf()));
 
         let entries = tree.visible_entries();
         assert!(!entries.is_empty(), "entries should be populated after set_root");
@@ -36,7 +108,7 @@ mod tests {
     #[test]
     fn test_directories_sorted_before_files() {
         let tmp = setup_temp_dir();
-        let tree = FsTree::new(tmp.path().to_path_buf());
+        let tree = loaded(FsTree::new(tmp.path().to_path_buf()));
 
         let entries = tree.visible_entries();
 
@@ -57,7 +129,7 @@ mod tests {
     #[test]
     fn test_alphabetical_within_groups() {
         let tmp = setup_temp_dir();
-        let tree = FsTree::new(tmp.path().to_path_buf());
+        let tree = loaded(FsTree::new(tmp.path().to_path_buf()));
 
         let entries = tree.visible_entries();
         let names: Vec<&str> = entries.iter().map(|e| e.entry.name.as_str()).collect();
@@ -70,7 +142,7 @@ mod tests {
     #[test]
     fn test_toggle_expands_and_collapses_directory() {
         let tmp = setup_temp_dir();
-        let mut tree = FsTree::new(tmp.path().to_path_buf());
+        let mut tree = loaded(FsTree::new(tmp.path().to_path_buf()));
 
         let alpha_path = tmp.path().join("alpha_dir");
 
@@ -78,7 +150,7 @@ mod tests {
         assert_eq!(tree.visible_entries().len(), 4);
 
         // Expand alpha_dir.
-        tree.toggle(&alpha_path);
+        toggle_and_wait(&mut tree, &alpha_path);
 
         // Now we should see the inner file too: 4 + 1 = 5.
         assert_eq!(tree.visible_entries().len(), 5);
@@ -92,7 +164,7 @@ mod tests {
         assert!(alpha_entry.is_expanded);
 
         // Collapse alpha_dir.
-        tree.toggle(&alpha_path);
+        toggle_and_wait(&mut tree, &alpha_path);
         assert_eq!(tree.visible_entries().len(), 4);
 
         let alpha_entry = tree
@@ -106,31 +178,31 @@ mod tests {
     #[test]
     fn test_visible_entries_respects_collapsed_state() {
         let tmp = setup_temp_dir();
-        let mut tree = FsTree::new(tmp.path().to_path_buf());
+        let mut tree = loaded(FsTree::new(tmp.path().to_path_buf()));
 
         let alpha_path = tmp.path().join("alpha_dir");
         let beta_path = tmp.path().join("beta_dir");
 
         // Expand alpha_dir (has inner.txt) -- should add 1 child.
-        tree.toggle(&alpha_path);
+        toggle_and_wait(&mut tree, &alpha_path);
         assert_eq!(tree.visible_entries().len(), 5);
 
         // Expand beta_dir (empty) -- no new children.
-        tree.toggle(&beta_path);
+        toggle_and_wait(&mut tree, &beta_path);
         assert_eq!(tree.visible_entries().len(), 5);
 
         // Collapse alpha_dir -- removes 1 child.
-        tree.toggle(&alpha_path);
+        toggle_and_wait(&mut tree, &alpha_path);
         assert_eq!(tree.visible_entries().len(), 4);
     }
 
     #[test]
     fn test_depth_of_nested_entries() {
         let tmp = setup_temp_dir();
-        let mut tree = FsTree::new(tmp.path().to_path_buf());
+        let mut tree = loaded(FsTree::new(tmp.path().to_path_buf()));
 
         let alpha_path = tmp.path().join("alpha_dir");
-        tree.toggle(&alpha_path);
+        toggle_and_wait(&mut tree, &alpha_path);
 
         for entry in tree.visible_entries() {
             if entry.entry.path == alpha_path {
@@ -145,7 +217,7 @@ mod tests {
     #[test]
     fn test_refresh_picks_up_new_files() {
         let tmp = setup_temp_dir();
-        let mut tree = FsTree::new(tmp.path().to_path_buf());
+        let mut tree = loaded(FsTree::new(tmp.path().to_path_buf()));
 
         let initial_count = tree.visible_entries().len();
         assert_eq!(initial_count, 4);
@@ -158,6 +230,7 @@ mod tests {
 
         // After refresh, tree picks it up.
         tree.refresh();
+        wait_for_loads(&mut tree);
         assert_eq!(tree.visible_entries().len(), 5);
 
         // The new file should be in the list.
@@ -171,26 +244,27 @@ mod tests {
     #[test]
     fn test_refresh_picks_up_new_files_in_expanded_dir() {
         let tmp = setup_temp_dir();
-        let mut tree = FsTree::new(tmp.path().to_path_buf());
+        let mut tree = loaded(FsTree::new(tmp.path().to_path_buf()));
 
         let alpha_path = tmp.path().join("alpha_dir");
-        tree.toggle(&alpha_path);
+        toggle_and_wait(&mut tree, &alpha_path);
         assert_eq!(tree.visible_entries().len(), 5);
 
         // Add a new file inside expanded alpha_dir.
         fs::write(alpha_path.join("new_inner.txt"), "new inner").unwrap();
 
         tree.refresh();
+        wait_for_loads(&mut tree);
         assert_eq!(tree.visible_entries().len(), 6);
     }
 
     #[test]
     fn test_set_root_resets_state() {
         let tmp = setup_temp_dir();
-        let mut tree = FsTree::new(tmp.path().to_path_buf());
+        let mut tree = loaded(FsTree::new(tmp.path().to_path_buf()));
 
         let alpha_path = tmp.path().join("alpha_dir");
-        tree.toggle(&alpha_path);
+        toggle_and_wait(&mut tree, &alpha_path);
         assert_eq!(tree.visible_entries().len(), 5);
 
         // Create a new temp dir and set it as root.
@@ -198,6 +272,7 @@ mod tests {
         fs::write(tmp2.path().join("only.txt"), "only").unwrap();
 
         tree.set_root(tmp2.path().to_path_buf());
+        wait_for_loads(&mut tree);
 
         assert_eq!(tree.root(), tmp2.path());
         assert_eq!(tree.visible_entries().len(), 1);
@@ -207,7 +282,7 @@ mod tests {
     #[test]
     fn test_has_children_flag() {
         let tmp = setup_temp_dir();
-        let tree = FsTree::new(tmp.path().to_path_buf());
+        let tree = loaded(FsTree::new(tmp.path().to_path_buf()));
 
         for entry in tree.visible_entries() {
             if entry.entry.is_dir {
@@ -221,17 +296,17 @@ mod tests {
     #[test]
     fn test_permission_error_skips_entry() {
         // read_directory should not panic on a nonexistent path
-        let entries = read_directory(Path::new("/nonexistent_path_12345"));
+        let entries = read_directory(&StdFileSystem, Path::new("/nonexistent_path_12345"));
         assert!(entries.is_empty());
     }
 
     #[test]
     fn test_toggle_nonexistent_path_does_not_panic() {
         let tmp = setup_temp_dir();
-        let mut tree = FsTree::new(tmp.path().to_path_buf());
+        let mut tree = loaded(FsTree::new(tmp.path().to_path_buf()));
 
         // Toggling a path that doesn't exist should not panic.
-        tree.toggle(Path::new("/nonexistent_path_12345"));
+        toggle_and_wait(&mut tree, Path::new("/nonexistent_path_12345"));
     }
 
     #[test]
@@ -249,7 +324,7 @@ mod tests {
             std::os::unix::fs::symlink(&real_dir, root.join("link_dir")).unwrap();
         }
 
-        let mut tree = FsTree::new(root.to_path_buf());
+        let mut tree = loaded(FsTree::new(root.to_path_buf()));
 
         #[cfg(unix)]
         {
@@ -265,7 +340,7 @@ mod tests {
             );
 
             // Expanding the symlink should show the contents of real_dir.
-            tree.toggle(&root.join("link_dir"));
+            toggle_and_wait(&mut tree, &root.join("link_dir"));
             let has_inner = tree
                 .visible_entries()
                 .iter()
@@ -273,4 +348,219 @@ mod tests {
             assert!(has_inner, "expanding symlink dir should show inner files");
         }
     }
+
+    #[test]
+    fn expanding_slow_directory_does_not_block() {
+        let tmp = setup_temp_dir();
+        let provider = GatedProvider::new(tmp.path());
+        let mut tree = loaded(FsTree::with_provider(tmp.path().to_path_buf(), provider.clone()));
+        let alpha_path = tmp.path().join("alpha_dir");
+
+        // Returns while the read is still blocked, with a placeholder row.
+        tree.toggle(&alpha_path);
+        assert!(tree.is_loading(&alpha_path));
+        let names: Vec<&str> = tree.visible_entries().iter().map(|e| e.entry.name.as_str()).collect();
+        assert_eq!(names, vec!["alpha_dir", LOADING_PLACEHOLDER, "beta_dir", "able.txt", "charlie.txt"]);
+        let placeholder = &tree.visible_entries()[1];
+        assert!(placeholder.is_placeholder);
+        assert_eq!(placeholder.depth, 1);
+        assert_eq!(placeholder.entry.path, alpha_path);
+        assert!(!tree.poll_events(), "nothing to apply while the read is blocked");
+        provider.release();
+    }
+
+    #[test]
+    fn slow_directory_results_appear_after_load_completes() {
+        let tmp = setup_temp_dir();
+        let provider = GatedProvider::new(tmp.path());
+        let mut tree = loaded(FsTree::with_provider(tmp.path().to_path_buf(), provider.clone()));
+        let woken = Arc::new(Mutex::new(false));
+        let flag = woken.clone();
+        tree.set_waker(Box::new(move || *flag.lock().unwrap() = true));
+        let alpha_path = tmp.path().join("alpha_dir");
+
+        tree.toggle(&alpha_path);
+        provider.release();
+        wait_for_loads(&mut tree);
+
+        assert!(*woken.lock().unwrap(), "waker should fire when the load finishes");
+        assert!(!tree.is_loading(&alpha_path));
+        let names: Vec<&str> = tree.visible_entries().iter().map(|e| e.entry.name.as_str()).collect();
+        assert_eq!(names, vec!["alpha_dir", "inner.txt", "beta_dir", "able.txt", "charlie.txt"]);
+        assert!(tree.visible_entries().iter().all(|e| !e.is_placeholder));
+    }
+
+    #[test]
+    fn collapse_cancels_pending_load() {
+        let tmp = setup_temp_dir();
+        let provider = GatedProvider::new(tmp.path());
+        let mut tree = loaded(FsTree::with_provider(tmp.path().to_path_buf(), provider.clone()));
+        let alpha_path = tmp.path().join("alpha_dir");
+
+        tree.toggle(&alpha_path);
+        tree.toggle(&alpha_path);
+        assert!(!tree.is_loading(&alpha_path));
+        assert_eq!(tree.visible_entries().len(), 4);
+
+        // The late result is dropped rather than cached.
+        provider.release();
+        std::thread::sleep(Duration::from_millis(50));
+        assert!(!tree.poll_events());
+        assert!(!tree.children_cache.contains_key(&alpha_path));
+
+        // Expanding again starts a fresh load.
+        toggle_and_wait(&mut tree, &alpha_path);
+        assert_eq!(tree.visible_entries().len(), 5);
+    }
+
+    #[test]
+    fn set_root_drops_pending_loads() {
+        let tmp = setup_temp_dir();
+        let provider = GatedProvider::new(tmp.path());
+        let mut tree = loaded(FsTree::with_provider(tmp.path().to_path_buf(), provider.clone()));
+        let alpha_path = tmp.path().join("alpha_dir");
+        let beta_path = tmp.path().join("beta_dir");
+
+        // alpha's read is in flight and blocked; beta's waits in the queue
+        tree.toggle(&alpha_path);
+        provider.wait_for_read(&alpha_path);
+        tree.toggle(&beta_path);
+        tree.set_root(tmp.path().to_path_buf());
+        assert!(!tree.is_loading(&alpha_path));
+        assert!(!tree.is_loading(&beta_path));
+
+        // The root reload waits only for the read already in flight: beta's
+        // queued request is skipped without reading
+        provider.release();
+        wait_for_loads(&mut tree);
+        assert_eq!(tree.visible_entries().len(), 4);
+        assert!(!tree.children_cache.contains_key(&alpha_path));
+        let root = tmp.path().to_path_buf();
+        assert_eq!(*provider.reads.lock().unwrap(), vec![root.clone(), alpha_path, root]);
+    }
+
+    #[test]
+    fn set_root_and_refresh_read_on_the_loader_thread() {
+        let tmp = setup_temp_dir();
+        // Gate every read, the root's included
+        let provider = GatedProvider::new(Path::new("/"));
+        let root = tmp.path().to_path_buf();
+
+        // Neither call blocks on the read; the root shows once it lands
+        let mut tree = FsTree::with_provider(root.clone(), provider.clone());
+        assert!(tree.is_loading(&root));
+        assert!(tree.visible_entries().is_empty());
+        tree.refresh();
+        assert!(tree.is_loading(&root));
+
+        provider.release();
+        wait_for_loads(&mut tree);
+        assert_eq!(tree.visible_entries().len(), 4);
+    }
+
+    /// /proj with a dir, a file, and whatever the test adds.
+    fn memory_fs() -> Arc<MemoryFileSystem> {
+        let fs = Arc::new(MemoryFileSystem::new());
+        fs.add_file("/proj/src/main.rs");
+        fs.add_file("/proj/README.md");
+        fs
+    }
+
+    fn names(tree: &FsTree) -> Vec<(usize, &str, bool)> {
+        tree.visible_entries()
+            .iter()
+            .map(|e| (e.depth, e.entry.name.as_str(), e.entry.is_dir))
+            .collect()
+    }
+
+    #[test]
+    fn memory_fs_lists_sorted_entries() {
+        let fs = memory_fs();
+        fs.add_dir("/proj/Docs");
+        fs.add_file("/proj/b.txt");
+        let tree = loaded(FsTree::with_fs(PathBuf::from("/proj"), fs));
+
+        assert_eq!(
+            names(&tree),
+            vec![(0, "Docs", true), (0, "src", true), (0, "b.txt", false), (0, "README.md", false)]
+        );
+    }
+
+    #[test]
+    fn memory_fs_symlink_cycle_lists_links_as_files() {
+        let fs = memory_fs();
+        fs.add_symlink("/proj/a", "/proj/b");
+        fs.add_symlink("/proj/b", "/proj/a");
+        let tree = loaded(FsTree::with_fs(PathBuf::from("/proj"), fs.clone()));
+
+        // Neither link resolves, so both show as plain entries instead of hanging.
+        assert_eq!(
+            names(&tree),
+            vec![(0, "src", true), (0, "a", false), (0, "b", false), (0, "README.md", false)]
+        );
+        assert!(fs.metadata(Path::new("/proj/a")).is_err());
+        assert_eq!(fs.read_link(Path::new("/proj/a")).unwrap(), PathBuf::from("/proj/b"));
+    }
+
+    #[test]
+    fn memory_fs_symlink_to_ancestor_expands_one_level_at_a_time() {
+        let fs = memory_fs();
+        fs.add_symlink("/proj/src/up", "/proj");
+        let mut tree = loaded(FsTree::with_fs(PathBuf::from("/proj"), fs));
+
+        toggle_and_wait(&mut tree, Path::new("/proj/src"));
+        toggle_and_wait(&mut tree, Path::new("/proj/src/up"));
+        toggle_and_wait(&mut tree, Path::new("/proj/src/up/src"));
+
+        // Each expansion reads only the toggled dir; the cycle never recurses.
+        assert_eq!(
+            names(&tree),
+            vec![
+                (0, "src", true),
+                (1, "up", true),
+                (2, "src", true),
+                (3, "up", true),
+                (3, "main.rs", false),
+                (2, "README.md", false),
+                (1, "main.rs", false),
+                (0, "README.md", false),
+            ]
+        );
+        let nested = &tree.visible_entries()[3];
+        assert_eq!(nested.entry.path, PathBuf::from("/proj/src/up/src/up"));
+    }
+
+    #[test]
+    fn memory_fs_permission_denied_dir_expands_empty() {
+        let fs = memory_fs();
+        fs.deny_read("/proj/secret");
+        let mut tree = loaded(FsTree::with_fs(PathBuf::from("/proj"), fs));
+
+        toggle_and_wait(&mut tree, Path::new("/proj/secret"));
+
+        let secret = tree.visible_entries().iter().find(|e| e.entry.name == "secret").unwrap();
+        assert!(secret.is_expanded);
+        assert_eq!(tree.visible_entries().len(), 3);
+    }
+
+    #[test]
+    fn memory_fs_broken_symlink_listed_as_file() {
+        let fs = memory_fs();
+        fs.add_symlink("/proj/gone", "/elsewhere/missing");
+        let tree = loaded(FsTree::with_fs(PathBuf::from("/proj"), fs));
+
+        assert!(names(&tree).contains(&(0, "gone", false)));
+    }
+
+    #[test]
+    fn memory_fs_refresh_sees_removed_entries() {
+        let fs = memory_fs();
+        let mut tree = loaded(FsTree::with_fs(PathBuf::from("/proj"), fs.clone()));
+        assert_eq!(tree.visible_entries().len(), 2);
+
+        fs.remove("/proj/src");
+        tree.refresh();
+        wait_for_loads(&mut tree);
+        assert_eq!(names(&tree), vec![(0, "README.md", false)]);
+    }
 }